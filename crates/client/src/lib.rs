@@ -0,0 +1,485 @@
+//! Typed async client for version 1 of the gateway's dashboard
+//! (`/api/v1/dashboard/*`) and admin (`/api/v1/admin/*`) APIs. Calling the
+//! versioned paths keeps the client on the response shapes it was built
+//! against when the server's current version moves on.
+//!
+//! Response and query types come from `prism-core`: request log pages, stats,
+//! cost reports and model info are core types the server returns directly, and
+//! the handler-shaped bodies are the ones in [`prism_core::dashboard_api`] that
+//! the handlers serialize, so the client cannot drift from the server's shapes.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), prism_client::Error> {
+//! use prism_client::Client;
+//! use prism_core::request_log::LogQuery;
+//!
+//! let mut client = Client::new("http://127.0.0.1:8317");
+//! client.login("admin", "secret").await?;
+//! let page = client
+//!     .query_logs(&LogQuery {
+//!         status: Some("5xx".to_string()),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! println!("{} failed requests", page.total);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod types;
+
+use prism_core::dashboard_api::{AuthKeyList, ProviderList};
+use prism_core::provider::ModelInfo;
+use prism_core::request_log::{
+    ErrorGroupQuery, ErrorGroups, FilterOptions, LogPage, LogQuery, LogStats, StatsQuery,
+    TagCostQuery, TagCostReport,
+};
+use prism_core::request_record::RequestRecord;
+use reqwest::{Method, RequestBuilder, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use types::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// A non-2xx response; `code` is the dashboard `error` field when present.
+    #[error("{status} {code}: {message}")]
+    Api {
+        status: u16,
+        code: String,
+        message: String,
+    },
+
+    #[error("invalid response body: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("invalid query: {0}")]
+    Query(#[from] serde_urlencoded::ser::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Client for one gateway instance. Dashboard calls send the token from
+/// [`Client::login`] or [`Client::with_token`] as a bearer token.
+///
+/// Reads of config-backed resources (providers, auth keys, config) return the
+/// config version as an `ETag`. A write sends the version from the last read
+/// of the resource it changes, or of its parent collection, as `If-Match`, so
+/// it fails with a `config_conflict` [`Error::Api`] when another session has
+/// changed the config since. Read the resource again to pick up the current
+/// version. Writes to a resource never read send the current version, or
+/// `If-Match: *` with [`Client::force_writes`].
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    /// Config version per dashboard path, from the `ETag` of its last read.
+    versions: Arc<Mutex<HashMap<String, String>>>,
+    force: bool,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Use a preconfigured `reqwest` client (timeouts, proxy, TLS roots).
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            versions: Arc::default(),
+            force: false,
+        }
+    }
+
+    /// Use an existing dashboard token instead of logging in.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Send `If-Match: *` on writes to resources that were never read,
+    /// overwriting whatever the current config is instead of reading it first.
+    pub fn force_writes(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Config version a write to `path` sends as `If-Match`: the one read with
+    /// `path` itself or the closest parent path, e.g. `/api/v1/dashboard/providers`
+    /// for `/api/v1/dashboard/providers/{id}/disable`.
+    pub fn config_version(&self, path: &str) -> Option<String> {
+        let versions = self.versions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut path = resource_path(path);
+        loop {
+            if let Some(version) = versions.get(path) {
+                return Some(version.clone());
+            }
+            path = &path[..path.rfind('/').filter(|&i| i > 0)?];
+        }
+    }
+
+    /// Read the current config version from the server.
+    pub async fn current_config_version(&self) -> Result<Option<String>> {
+        let response = check(
+            self.request(Method::GET, "/api/v1/dashboard/config/current")
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(etag(&response))
+    }
+
+    // ── Auth ──
+
+    /// Log in to the dashboard and keep the session token for later calls.
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<LoginResponse> {
+        let body = serde_json::json!({"username": username, "password": password});
+        let response = check(
+            self.request(Method::POST, "/api/v1/dashboard/auth/login")
+                .json(&body)
+                .send()
+                .await?,
+        )
+        .await?;
+        // The token is only returned as the session cookie.
+        self.token = response
+            .headers()
+            .get(reqwest::header::SET_COOKIE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|cookie| cookie.split(';').next())
+            .and_then(|pair| pair.split_once('='))
+            .map(|(_, value)| value.to_string());
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    // ── Admin ──
+
+    pub async fn admin_config(&self) -> Result<serde_json::Value> {
+        self.get("/api/v1/admin/config").await
+    }
+
+    pub async fn admin_metrics(&self) -> Result<serde_json::Value> {
+        self.get("/api/v1/admin/metrics").await
+    }
+
+    pub async fn admin_models(&self) -> Result<Vec<ModelInfo>> {
+        #[derive(serde::Deserialize)]
+        struct Models {
+            models: Vec<ModelInfo>,
+        }
+        Ok(self.get::<Models>("/api/v1/admin/models").await?.models)
+    }
+
+    // ── Providers ──
+
+    /// `tags` is comma-separated; only providers carrying every tag are listed.
+    pub async fn list_providers(&self, tags: Option<&str>) -> Result<Vec<ProviderSummary>> {
+        #[derive(Serialize)]
+        struct Query<'a> {
+            tags: Option<&'a str>,
+        }
+        let path = with_query("/api/v1/dashboard/providers", &Query { tags })?;
+        Ok(self.get::<ProviderList>(&path).await?.providers)
+    }
+
+    pub async fn get_provider(&self, id: &str) -> Result<ProviderDetail> {
+        self.get(&format!("/api/v1/dashboard/providers/{id}")).await
+    }
+
+    pub async fn create_provider(&self, provider: &CreateProvider) -> Result<ProviderCreated> {
+        self.send(Method::POST, "/api/v1/dashboard/providers", Some(provider))
+            .await
+    }
+
+    pub async fn delete_provider(&self, id: &str) -> Result<Message> {
+        self.send::<(), _>(
+            Method::DELETE,
+            &format!("/api/v1/dashboard/providers/{id}"),
+            None,
+        )
+        .await
+    }
+
+    pub async fn disable_provider(&self, id: &str) -> Result<ProviderToggled> {
+        self.post(&format!("/api/v1/dashboard/providers/{id}/disable"))
+            .await
+    }
+
+    pub async fn enable_provider(&self, id: &str) -> Result<ProviderToggled> {
+        self.post(&format!("/api/v1/dashboard/providers/{id}/enable"))
+            .await
+    }
+
+    // ── Auth keys ──
+
+    pub async fn list_auth_keys(&self) -> Result<Vec<AuthKeySummary>> {
+        Ok(self
+            .get::<AuthKeyList>("/api/v1/dashboard/auth-keys")
+            .await?
+            .auth_keys)
+    }
+
+    pub async fn create_auth_key(&self, key: &CreateAuthKey) -> Result<AuthKeyCreated> {
+        self.send(Method::POST, "/api/v1/dashboard/auth-keys", Some(key))
+            .await
+    }
+
+    pub async fn delete_auth_key(&self, id: &str) -> Result<Message> {
+        self.send::<(), _>(
+            Method::DELETE,
+            &format!("/api/v1/dashboard/auth-keys/{id}"),
+            None,
+        )
+        .await
+    }
+
+    pub async fn disable_auth_key(&self, id: &str) -> Result<AuthKeyToggled> {
+        self.post(&format!("/api/v1/dashboard/auth-keys/{id}/disable"))
+            .await
+    }
+
+    pub async fn enable_auth_key(&self, id: &str) -> Result<AuthKeyToggled> {
+        self.post(&format!("/api/v1/dashboard/auth-keys/{id}/enable"))
+            .await
+    }
+
+    // ── Request log and usage ──
+
+    pub async fn query_logs(&self, query: &LogQuery) -> Result<LogPage> {
+        self.get(&with_query("/api/v1/dashboard/logs", query)?)
+            .await
+    }
+
+    pub async fn get_log(&self, request_id: &str) -> Result<RequestRecord> {
+        self.get(&format!("/api/v1/dashboard/logs/{request_id}"))
+            .await
+    }
+
+    pub async fn log_stats(&self, query: &StatsQuery) -> Result<LogStats> {
+        self.get(&with_query("/api/v1/dashboard/logs/stats", query)?)
+            .await
+    }
+
+    pub async fn error_groups(&self, query: &ErrorGroupQuery) -> Result<ErrorGroups> {
+        self.get(&with_query("/api/v1/dashboard/logs/errors", query)?)
+            .await
+    }
+
+    pub async fn log_filters(&self) -> Result<FilterOptions> {
+        self.get("/api/v1/dashboard/logs/filters").await
+    }
+
+    pub async fn costs_by_tag(&self, query: &TagCostQuery) -> Result<TagCostReport> {
+        self.get(&with_query("/api/v1/dashboard/costs/by-tag", query)?)
+            .await
+    }
+
+    /// Deliver a configured usage report now.
+    pub async fn send_report(&self, name: &str) -> Result<ReportDelivery> {
+        self.post(&format!("/api/v1/dashboard/reports/{name}/send"))
+            .await
+    }
+
+    // ── Config ──
+
+    /// Reload the config file; returns `{ message, touched, warnings }`.
+    pub async fn reload_config(&self) -> Result<serde_json::Value> {
+        self.post("/api/v1/dashboard/config/reload").await
+    }
+
+    // ── Plumbing ──
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .http
+            .request(method, format!("{}{path}", self.base_url));
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send::<(), _>(Method::GET, path, None).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send::<(), _>(Method::POST, path, None).await
+    }
+
+    async fn send<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let write = method != Method::GET;
+        let mut builder = self.request(method, path);
+        let mut sent = None;
+        if write {
+            sent = match self.config_version(path) {
+                Some(version) => Some(version),
+                None if self.force => Some("*".to_string()),
+                None => self.current_config_version().await?,
+            };
+            if let Some(version) = &sent {
+                builder = builder.header(reqwest::header::IF_MATCH, version);
+            }
+        }
+        if let Some(body) = body {
+            builder = builder.json(body);
+        }
+        let response = check(builder.send().await?).await?;
+        if !write && let Some(version) = etag(&response) {
+            self.versions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(resource_path(path).to_string(), version);
+        }
+        let bytes = response.bytes().await?;
+        if let Some(sent) = sent {
+            self.advance_versions(&sent, &bytes);
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// A write that reports the new `config_version` only moved the config past
+    /// this client's own edit, so resources read at the version it was made
+    /// from stay current.
+    fn advance_versions(&self, sent: &str, body: &[u8]) {
+        let Some(next) = serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|body| Some(body.get("config_version")?.as_str()?.to_string()))
+        else {
+            return;
+        };
+        for version in self
+            .versions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values_mut()
+        {
+            if version == sent {
+                *version = format!("\"{next}\"");
+            }
+        }
+    }
+}
+
+/// `path` without its query string.
+fn resource_path(path: &str) -> &str {
+    path.split_once('?').map_or(path, |(path, _)| path)
+}
+
+fn etag(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Turn a non-2xx response into [`Error::Api`].
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let parsed: Option<serde_json::Value> = serde_json::from_str(&body).ok();
+    let field = |name: &str| {
+        parsed
+            .as_ref()
+            .and_then(|v| v.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    Err(Error::Api {
+        status: status.as_u16(),
+        code: field("error").unwrap_or_default(),
+        message: field("message").unwrap_or(body),
+    })
+}
+
+/// Append `query` to `path`; `None` fields are left out.
+fn with_query(path: &str, query: &impl Serialize) -> Result<String> {
+    let query = serde_urlencoded::to_string(query)?;
+    Ok(if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prism_core::request_log::SortField;
+
+    #[test]
+    fn test_with_query_skips_unset_fields() {
+        assert_eq!(
+            with_query("/api/v1/dashboard/logs", &LogQuery::default()).unwrap(),
+            "/api/v1/dashboard/logs"
+        );
+        let query = LogQuery {
+            tags: Some("team:ml,env:prod".to_string()),
+            stream: Some(true),
+            sort_by: Some(SortField::Latency),
+            ..Default::default()
+        };
+        assert_eq!(
+            with_query("/api/v1/dashboard/logs", &query).unwrap(),
+            "/api/v1/dashboard/logs?stream=true&tags=team%3Aml%2Cenv%3Aprod&sort_by=latency"
+        );
+    }
+
+    #[test]
+    fn test_write_uses_version_of_closest_read() {
+        let client = Client::new("http://127.0.0.1:8317");
+        client.versions.lock().unwrap().extend([
+            (
+                "/api/v1/dashboard/providers".to_string(),
+                "\"list\"".to_string(),
+            ),
+            (
+                "/api/v1/dashboard/providers/a".to_string(),
+                "\"a\"".to_string(),
+            ),
+        ]);
+        let version = |path| client.config_version(path);
+        assert_eq!(
+            version("/api/v1/dashboard/providers/a/disable").as_deref(),
+            Some("\"a\"")
+        );
+        assert_eq!(
+            version("/api/v1/dashboard/providers/b").as_deref(),
+            Some("\"list\"")
+        );
+        assert_eq!(
+            version("/api/v1/dashboard/providers?tags=x").as_deref(),
+            Some("\"list\"")
+        );
+        assert_eq!(version("/api/v1/dashboard/auth-keys/k1"), None);
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash_is_dropped() {
+        let client = Client::new("http://127.0.0.1:8317/").with_token("t");
+        assert_eq!(client.base_url, "http://127.0.0.1:8317");
+        assert_eq!(client.token(), Some("t"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Request bodies the client sends to the dashboard API, and the response
+//! bodies it reads back. Responses are the server's own types from
+//! [`prism_core::dashboard_api`]; request bodies here carry only the fields
+//! the client sets and leave the rest to the server defaults.
+
+use prism_core::auth_key::{BudgetConfig, KeyRateLimitConfig};
+pub use prism_core::dashboard_api::{
+    AuthKeyCreated, AuthKeySummary, AuthKeyToggled, AuthProfileSummary, LoginResponse, Message,
+    ProviderCreated, ProviderDetail, ProviderSummary, ProviderToggled, ReportDelivery,
+};
+use prism_core::provider::{Format, UpstreamKind, WireApi};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Body of `POST /api/v1/dashboard/providers`. Fields left unset take the server defaults.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateProvider {
+    pub name: String,
+    pub format: Format,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<UpstreamKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wire_api: Option<WireApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub disabled: bool,
+}
+
+impl CreateProvider {
+    pub fn new(name: impl Into<String>, format: Format) -> Self {
+        Self {
+            name: name.into(),
+            format,
+            upstream: None,
+            api_key: None,
+            base_url: None,
+            models: Vec::new(),
+            wire_api: None,
+            region: None,
+            tags: Vec::new(),
+            disabled: false,
+        }
+    }
+}
+
+/// Body of `POST /api/v1/dashboard/auth-keys`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateAuthKey {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_models: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_credentials: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<KeyRateLimitConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetConfig>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    pub routing_hints: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
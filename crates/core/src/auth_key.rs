@@ -5,6 +5,10 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AuthKeyEntry {
+    /// Persistent opaque identifier used by the dashboard API, so a key keeps its
+    /// address when others are added or removed. Assigned on load when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub key: String,
     #[serde(default)]
     pub name: Option<String>,
@@ -24,6 +28,20 @@ pub struct AuthKeyEntry {
     pub expires_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Overrides the global moderation action for this key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<crate::config::ModerationAction>,
+    /// Honor `x-proxy-model`, `x-proxy-provider`, and `x-proxy-credential` headers
+    /// from this key even when `routing-hint-headers` is off.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub routing_hints: bool,
+    /// Reject requests with this key without removing it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    /// Free-form labels (e.g. `team:ml`) copied into the request log of every
+    /// request made with this key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -131,6 +149,7 @@ mod tests {
     fn test_auth_key_store_lookup() {
         let entries = vec![
             AuthKeyEntry {
+                id: None,
                 key: "sk-proxy-abc123".to_string(),
                 name: Some("Team Alpha".to_string()),
                 tenant_id: Some("alpha".to_string()),
@@ -140,8 +159,13 @@ mod tests {
                 budget: None,
                 expires_at: None,
                 metadata: HashMap::new(),
+                moderation: None,
+                routing_hints: false,
+                disabled: false,
+                tags: Vec::new(),
             },
             AuthKeyEntry {
+                id: None,
                 key: "sk-proxy-def456".to_string(),
                 name: Some("Team Beta".to_string()),
                 tenant_id: Some("beta".to_string()),
@@ -151,6 +175,10 @@ mod tests {
                 budget: None,
                 expires_at: None,
                 metadata: HashMap::new(),
+                moderation: None,
+                routing_hints: false,
+                disabled: false,
+                tags: Vec::new(),
             },
         ];
         let store = AuthKeyStore::new(entries);
@@ -163,6 +191,7 @@ mod tests {
     #[test]
     fn test_model_access_check() {
         let entry = AuthKeyEntry {
+            id: None,
             key: "sk-proxy-test".to_string(),
             name: None,
             tenant_id: None,
@@ -172,6 +201,10 @@ mod tests {
             budget: None,
             expires_at: None,
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         };
         assert!(AuthKeyStore::check_model_access(&entry, "claude-3-opus"));
         assert!(AuthKeyStore::check_model_access(&entry, "gpt-4o"));
@@ -181,6 +214,7 @@ mod tests {
     #[test]
     fn test_empty_allowed_models_allows_all() {
         let entry = AuthKeyEntry {
+            id: None,
             key: "sk-proxy-test".to_string(),
             name: None,
             tenant_id: None,
@@ -190,6 +224,10 @@ mod tests {
             budget: None,
             expires_at: None,
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         };
         assert!(AuthKeyStore::check_model_access(&entry, "anything"));
     }
@@ -206,6 +244,7 @@ mod tests {
     #[test]
     fn test_is_expired() {
         let not_expired = AuthKeyEntry {
+            id: None,
             key: "k".to_string(),
             name: None,
             tenant_id: None,
@@ -215,10 +254,15 @@ mod tests {
             budget: None,
             expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         };
         assert!(!AuthKeyStore::is_expired(&not_expired));
 
         let expired = AuthKeyEntry {
+            id: None,
             key: "k".to_string(),
             name: None,
             tenant_id: None,
@@ -228,10 +272,15 @@ mod tests {
             budget: None,
             expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         };
         assert!(AuthKeyStore::is_expired(&expired));
 
         let no_expiry = AuthKeyEntry {
+            id: None,
             key: "k".to_string(),
             name: None,
             tenant_id: None,
@@ -241,6 +290,10 @@ mod tests {
             budget: None,
             expires_at: None,
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         };
         assert!(!AuthKeyStore::is_expired(&no_expiry));
     }
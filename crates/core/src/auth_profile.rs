@@ -58,6 +58,10 @@ pub struct AuthProfileEntry {
     pub disabled: bool,
     pub weight: u32,
     pub region: Option<String>,
+    /// Credential group; falls back to the provider's `group`.
+    pub group: Option<String>,
+    /// Usage caps; falls back to the provider's `quota`.
+    pub quota: Option<crate::config::CredentialQuotaConfig>,
     pub prefix: Option<String>,
     pub upstream_presentation: UpstreamPresentationConfig,
 }
@@ -80,6 +84,8 @@ impl Default for AuthProfileEntry {
             disabled: false,
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             prefix: None,
             upstream_presentation: UpstreamPresentationConfig::default(),
         }
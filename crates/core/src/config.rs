@@ -6,13 +6,12 @@ use crate::payload::PayloadConfig;
 use crate::request_record::LogDetailLevel;
 pub use crate::routing::config::RoutingConfig;
 use crate::thinking_cache::ThinkingCacheConfig;
-use arc_swap::ArcSwap;
 use notify::{RecursiveMode, Watcher};
+pub use prism_translator::rules::TranslationRule;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
 use std::time::Duration;
 
 // ─── Config ────────────────────────────────────────────────────────────────
@@ -37,6 +36,9 @@ pub struct Config {
     pub debug: bool,
     pub logging_to_file: bool,
     pub log_dir: Option<String>,
+    pub log_rotation: LogRotationConfig,
+    pub request_debug: RequestDebugConfig,
+    pub redaction: RedactionConfig,
 
     // Routing
     pub routing: RoutingConfig,
@@ -47,27 +49,58 @@ pub struct Config {
     pub connect_timeout: u64,
     pub request_timeout: u64,
 
+    // Upstream DNS overrides, caching, and address family preference
+    pub dns: DnsConfig,
+
     // Streaming
     pub streaming: StreamingConfig,
 
     // Request body size limit (MB)
     pub body_limit_mb: usize,
 
+    // Per-route body size and RPM overrides, first matching path wins
+    pub routes: Vec<RouteLimitConfig>,
+
     // Retry
     pub retry: RetryConfig,
 
     // Payload manipulation
     pub payload: PayloadConfig,
 
-    // Upstream response headers to forward to clients
+    // Per-model overrides on top of format translation
+    pub translation_rules: Vec<TranslationRule>,
+
+    // Multimodal image processing
+    pub vision: VisionConfig,
+
+    // Mirror a share of traffic to secondary providers for evaluation
+    pub shadow: Vec<ShadowRule>,
+
+    // A/B model experiments
+    pub experiments: Vec<Experiment>,
+
+    // Named models that expand to a base model plus a pinned prompt and params
+    pub preset_models: Vec<PresetModel>,
+
+    // External moderation check before dispatch
+    pub moderation: ModerationConfig,
+
+    // Untranslated OpenAI Files / Assistants / Threads routes on one credential
+    pub openai_passthrough: OpenAIPassthroughConfig,
+
+    // Upstream response headers to forward to clients. The entry `rate-limits`
+    // forwards provider rate-limit headers in normalized `x-ratelimit-*` form.
     pub passthrough_headers: Vec<String>,
 
     // Claude header defaults (injected when cloaking is active)
     pub claude_header_defaults: HashMap<String, String>,
 
-    // Reject requests without model prefix when true
+    // Reject requests whose model does not carry a credential `prefix` when true
     pub force_model_prefix: bool,
 
+    // Honor x-proxy-model / x-proxy-provider / x-proxy-credential from every client
+    pub routing_hint_headers: bool,
+
     // Non-stream keepalive interval in seconds (0 = disabled).
     pub non_stream_keepalive_secs: u64,
 
@@ -77,6 +110,12 @@ pub struct Config {
     // Rate limiting
     pub rate_limit: RateLimitConfig,
 
+    // Load shedding: caps on concurrent requests and streams
+    pub concurrency: ConcurrencyConfig,
+
+    // Faults injected into upstream calls, for resilience testing in staging
+    pub fault_injection: FaultInjectionConfig,
+
     // Circuit breaker
     pub circuit_breaker: CircuitBreakerConfig,
 
@@ -96,12 +135,40 @@ pub struct Config {
     // Daemon
     pub daemon: DaemonConfig,
 
+    // Startup self-test gating `/ready`
+    pub readiness: ReadinessConfig,
+
+    // W3C traceparent propagation
+    pub trace_propagation: TracePropagationConfig,
+
+    // Inbound headers forwarded upstream and headers never sent upstream
+    pub outbound_headers: OutboundHeadersConfig,
+
     // Thinking signature cache
     pub thinking_cache: ThinkingCacheConfig,
 
+    // Credential cooldowns and quota counters persisted across restarts
+    pub router_state: RouterStateConfig,
+
+    // Start/stop history of the proxy process, for spotting unexpected restarts
+    pub process_history: ProcessHistoryConfig,
+
+    // Rate-limit usage, cooldowns, and quota counters shared between instances
+    pub shared_state: SharedStateConfig,
+
+    // Peer instances aggregated into the dashboard cluster view
+    pub cluster: ClusterConfig,
+
+    // Scheduled usage summaries delivered to webhooks
+    pub reports: Vec<UsageReportConfig>,
+
     // Quota-aware credential cooldown duration in seconds (default: 60).
     pub quota_cooldown_default_secs: u64,
 
+    // Max time (ms) a request waits for a credential cooldown to expire when every
+    // planned credential is cooling down (0 = fail immediately).
+    pub queue_on_exhaustion_ms: u64,
+
     // Provider credentials (unified)
     #[serde(default)]
     pub providers: Vec<ProviderKeyEntry>,
@@ -119,29 +186,53 @@ impl Default for Config {
             debug: false,
             logging_to_file: false,
             log_dir: None,
+            log_rotation: LogRotationConfig::default(),
+            request_debug: RequestDebugConfig::default(),
+            redaction: RedactionConfig::default(),
             routing: RoutingConfig::default(),
             request_retry: 3,
             max_retry_interval: 30,
             connect_timeout: 30,
             request_timeout: 300,
+            dns: DnsConfig::default(),
             streaming: StreamingConfig::default(),
             body_limit_mb: 10,
+            routes: Vec::new(),
             retry: RetryConfig::default(),
             payload: PayloadConfig::default(),
+            translation_rules: Vec::new(),
+            vision: VisionConfig::default(),
+            shadow: Vec::new(),
+            experiments: Vec::new(),
+            preset_models: Vec::new(),
+            moderation: ModerationConfig::default(),
+            openai_passthrough: OpenAIPassthroughConfig::default(),
             passthrough_headers: Vec::new(),
             claude_header_defaults: HashMap::new(),
             force_model_prefix: false,
+            routing_hint_headers: false,
             non_stream_keepalive_secs: 0,
             model_prices: HashMap::new(),
             rate_limit: RateLimitConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            fault_injection: FaultInjectionConfig::default(),
             circuit_breaker: CircuitBreakerConfig::default(),
             cache: CacheConfig::default(),
             log_store: LogStoreConfig::default(),
             dashboard: DashboardConfig::default(),
             managed_auth: ManagedAuthConfig::default(),
             daemon: DaemonConfig::default(),
+            readiness: ReadinessConfig::default(),
+            trace_propagation: TracePropagationConfig::default(),
+            outbound_headers: OutboundHeadersConfig::default(),
             thinking_cache: ThinkingCacheConfig::default(),
+            router_state: RouterStateConfig::default(),
+            process_history: ProcessHistoryConfig::default(),
+            shared_state: SharedStateConfig::default(),
+            cluster: ClusterConfig::default(),
+            reports: Vec::new(),
             quota_cooldown_default_secs: 60,
+            queue_on_exhaustion_ms: 0,
             providers: Vec::new(),
         }
     }
@@ -149,8 +240,9 @@ impl Default for Config {
 
 impl Config {
     /// Load config from a YAML file, sanitize, and validate.
+    /// Providers and auth keys come from the managed store when one is configured.
     pub fn load(path: &str) -> Result<Self, anyhow::Error> {
-        let contents = std::fs::read_to_string(path)?;
+        let contents = crate::managed_store::read_effective_yaml(path)?;
         Self::load_from_str(&contents)
     }
 
@@ -162,6 +254,13 @@ impl Config {
         Ok(config)
     }
 
+    /// Sanitize and validate a config built in code, as loading from YAML does.
+    pub fn prepare(mut self) -> Result<Self, anyhow::Error> {
+        self.sanitize()?;
+        self.validate()?;
+        Ok(self)
+    }
+
     /// Deserialize config from a YAML string with sanitization (but no validation).
     /// Used by dashboard config editing where the caller may mutate before writing back.
     pub fn from_yaml(yaml: &str) -> Result<Self, anyhow::Error> {
@@ -201,8 +300,289 @@ impl Config {
         if let Some(ref proxy) = self.managed_auth.proxy_url {
             crate::proxy::validate_proxy_url(proxy)?;
         }
-        // Provider name uniqueness
+        for route in &self.routes {
+            anyhow::ensure!(
+                route.path.starts_with('/'),
+                "route path must start with '/': {}",
+                route.path
+            );
+            anyhow::ensure!(
+                route.body_limit_mb != Some(0),
+                "route '{}' body-limit-mb must be greater than 0",
+                route.path
+            );
+        }
+        if self.vision.downscale {
+            anyhow::ensure!(
+                self.vision.max_dimension > 0,
+                "vision max-dimension must be greater than 0"
+            );
+            anyhow::ensure!(
+                self.vision.max_image_bytes > 0,
+                "vision max-image-bytes must be greater than 0"
+            );
+            anyhow::ensure!(
+                self.vision.max_source_pixels > 0,
+                "vision max-source-pixels must be greater than 0"
+            );
+        }
+        if self.vision.fetch_remote {
+            anyhow::ensure!(
+                !self.vision.fetch_allowed_hosts.is_empty(),
+                "vision fetch-remote requires fetch-allowed-hosts"
+            );
+            anyhow::ensure!(
+                self.vision.fetch_max_bytes > 0 && self.vision.fetch_timeout_secs > 0,
+                "vision fetch-max-bytes and fetch-timeout-secs must be greater than 0"
+            );
+        }
+        for rule in &self.payload.max_output_tokens {
+            anyhow::ensure!(
+                rule.default.is_some() || rule.max.is_some(),
+                "payload max-output-tokens rule needs a default or max"
+            );
+            anyhow::ensure!(
+                rule.default != Some(0) && rule.max != Some(0),
+                "payload max-output-tokens default and max must be greater than 0"
+            );
+        }
+        for rule in &self.shadow {
+            anyhow::ensure!(
+                self.providers.iter().any(|p| p.name == rule.provider),
+                "shadow rule references unknown provider '{}'",
+                rule.provider
+            );
+            anyhow::ensure!(
+                rule.percent > 0.0 && rule.percent <= 100.0,
+                "shadow rule for provider '{}' percent must be in (0, 100]",
+                rule.provider
+            );
+        }
+        let mut experiment_names = std::collections::HashSet::new();
+        for experiment in &self.experiments {
+            anyhow::ensure!(
+                !experiment.name.is_empty(),
+                "experiment name must not be empty"
+            );
+            anyhow::ensure!(
+                experiment_names.insert(experiment.name.as_str()),
+                "duplicate experiment name '{}'",
+                experiment.name
+            );
+            anyhow::ensure!(
+                !experiment.variant.is_empty(),
+                "experiment '{}' variant must not be empty",
+                experiment.name
+            );
+            anyhow::ensure!(
+                (0.0..=100.0).contains(&experiment.percent),
+                "experiment '{}' percent must be between 0 and 100",
+                experiment.name
+            );
+        }
+        let mut preset_names = std::collections::HashSet::new();
+        for preset in &self.preset_models {
+            anyhow::ensure!(
+                !preset.name.is_empty(),
+                "preset model name must not be empty"
+            );
+            anyhow::ensure!(
+                preset_names.insert(preset.name.as_str()),
+                "duplicate preset model name '{}'",
+                preset.name
+            );
+            anyhow::ensure!(
+                !preset.model.is_empty() && preset.model != preset.name,
+                "preset model '{}' needs a base model other than itself",
+                preset.name
+            );
+            for key in ["model", "stream"] {
+                anyhow::ensure!(
+                    !preset.params.contains_key(key),
+                    "preset model '{}' params must not set '{key}'",
+                    preset.name
+                );
+            }
+        }
+        if self.moderation.enabled {
+            anyhow::ensure!(
+                self.moderation.url.starts_with("http://")
+                    || self.moderation.url.starts_with("https://"),
+                "moderation url must be an http(s) URL"
+            );
+            anyhow::ensure!(
+                self.moderation.timeout_secs > 0,
+                "moderation timeout-secs must be greater than 0"
+            );
+        }
+        if self.openai_passthrough.enabled {
+            let provider = &self.openai_passthrough.provider;
+            anyhow::ensure!(
+                self.providers
+                    .iter()
+                    .any(|p| &p.name == provider && p.format == crate::provider::Format::OpenAI),
+                "openai-passthrough provider '{provider}' must name an openai-format provider"
+            );
+        }
+        if self.readiness.probe_credentials {
+            anyhow::ensure!(
+                self.readiness.probe_timeout_secs > 0 && self.readiness.probe_interval_secs > 0,
+                "readiness probe-timeout-secs and probe-interval-secs must be greater than 0"
+            );
+        }
+        if self.readiness.warm_up {
+            anyhow::ensure!(
+                self.readiness.probe_timeout_secs > 0,
+                "readiness probe-timeout-secs must be greater than 0"
+            );
+        }
+        if self.trace_propagation.enabled {
+            anyhow::ensure!(
+                is_valid_header_name(&self.trace_propagation.upstream_header),
+                "trace-propagation upstream-header must be a valid header name"
+            );
+        }
+        self.validate_outbound_headers()?;
+        if self.router_state.enabled {
+            anyhow::ensure!(
+                !self.router_state.path.trim().is_empty(),
+                "router-state path must not be empty"
+            );
+            anyhow::ensure!(
+                self.router_state.flush_interval_secs > 0,
+                "router-state flush-interval-secs must be greater than 0"
+            );
+        }
+        for (i, pattern) in self.redaction.patterns.iter().enumerate() {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("redaction.patterns[{i}]: {e}"))?;
+        }
+        if self.request_debug.enabled {
+            anyhow::ensure!(
+                self.request_debug.max_records > 0,
+                "request-debug max-records must be greater than 0"
+            );
+        }
+        if self.process_history.enabled {
+            anyhow::ensure!(
+                !self.process_history.path.trim().is_empty(),
+                "process-history path must not be empty"
+            );
+            anyhow::ensure!(
+                self.process_history.max_runs > 0,
+                "process-history max-runs must be greater than 0"
+            );
+        }
+        if self.shared_state.enabled() {
+            anyhow::ensure!(
+                self.shared_state.sync_interval_ms > 0,
+                "shared-state sync-interval-ms must be greater than 0"
+            );
+            anyhow::ensure!(
+                self.shared_state.retention_secs > 0,
+                "shared-state retention-secs must be greater than 0"
+            );
+        }
+        self.validate_cluster()?;
+        let mut report_names = std::collections::HashSet::new();
+        for report in &self.reports {
+            anyhow::ensure!(
+                !report.name.trim().is_empty(),
+                "report name must not be empty"
+            );
+            anyhow::ensure!(
+                report_names.insert(report.name.as_str()),
+                "duplicate report name '{}'",
+                report.name
+            );
+            anyhow::ensure!(
+                report.webhook_url.starts_with("http://")
+                    || report.webhook_url.starts_with("https://"),
+                "report '{}' webhook-url must start with http:// or https://",
+                report.name
+            );
+            anyhow::ensure!(
+                report.hour_utc < 24,
+                "report '{}' hour-utc must be between 0 and 23",
+                report.name
+            );
+            anyhow::ensure!(
+                report.top > 0,
+                "report '{}' top must be greater than 0",
+                report.name
+            );
+        }
+        for (host, addrs) in &self.dns.overrides {
+            anyhow::ensure!(
+                !host.trim().is_empty() && !addrs.is_empty(),
+                "dns.overrides: '{host}' needs a host name and at least one address"
+            );
+        }
+        anyhow::ensure!(
+            self.dns.serve_stale_secs == 0 || self.dns.serve_stale_secs >= self.dns.cache_ttl_secs,
+            "dns serve-stale-secs must be 0 or at least cache-ttl-secs"
+        );
+        if self.fault_injection.enabled {
+            let faults = &self.fault_injection;
+            for (name, rate) in [
+                ("latency-rate", faults.latency_rate),
+                ("error-rate", faults.error_rate),
+                ("stream-drop-rate", faults.stream_drop_rate),
+            ] {
+                anyhow::ensure!(
+                    (0.0..=1.0).contains(&rate),
+                    "fault-injection {name} must be between 0.0 and 1.0"
+                );
+            }
+            anyhow::ensure!(
+                faults.latency_min_ms <= faults.latency_max_ms,
+                "fault-injection latency-min-ms must not exceed latency-max-ms"
+            );
+            if faults.error_rate > 0.0 {
+                anyhow::ensure!(
+                    !faults.error_statuses.is_empty(),
+                    "fault-injection error-statuses must not be empty"
+                );
+                anyhow::ensure!(
+                    faults
+                        .error_statuses
+                        .iter()
+                        .all(|s| (400..=599).contains(s)),
+                    "fault-injection error-statuses must be 4xx or 5xx"
+                );
+            }
+        }
+        for (i, limit) in self.streaming.limits.iter().enumerate() {
+            anyhow::ensure!(
+                limit.models.iter().any(|m| !m.trim().is_empty()),
+                "streaming.limits[{i}]: models must not be empty"
+            );
+            anyhow::ensure!(
+                limit.max_bytes > 0 || limit.max_duration_secs > 0,
+                "streaming.limits[{i}]: set max-bytes or max-duration-secs"
+            );
+        }
+        for (i, coalesce) in self.streaming.coalesce.iter().enumerate() {
+            anyhow::ensure!(
+                coalesce.max_delay_ms > 0,
+                "streaming.coalesce[{i}]: max-delay-ms must be greater than 0"
+            );
+        }
+        for (i, rule) in self.translation_rules.iter().enumerate() {
+            rule.validate()
+                .map_err(|e| anyhow::anyhow!("translation-rules[{i}]: {e}"))?;
+        }
+        for entry in &self.auth_keys {
+            validate_tags(&entry.tags).map_err(|e| {
+                anyhow::anyhow!(
+                    "auth-key '{}' {e}",
+                    entry.name.as_deref().unwrap_or("unnamed")
+                )
+            })?;
+        }
+        // Provider name and id uniqueness
         let mut seen_names = std::collections::HashSet::new();
+        let mut seen_ids = std::collections::HashSet::new();
         for entry in &self.providers {
             anyhow::ensure!(!entry.name.is_empty(), "provider name must not be empty");
             anyhow::ensure!(
@@ -210,7 +590,32 @@ impl Config {
                 "duplicate provider name: {}",
                 entry.name
             );
+            if let Some(ref id) = entry.id {
+                anyhow::ensure!(seen_ids.insert(id), "duplicate provider id: {id}");
+            }
             entry.validate_shape().map_err(|e| anyhow::anyhow!("{e}"))?;
+            for quota in std::iter::once(&entry.quota)
+                .chain(entry.auth_profiles.iter().map(|p| &p.quota))
+                .flatten()
+            {
+                for limits in [&quota.daily, &quota.monthly] {
+                    anyhow::ensure!(
+                        limits.cost_usd.is_finite() && limits.cost_usd >= 0.0,
+                        "provider '{}' quota cost-usd must be a non-negative number",
+                        entry.name
+                    );
+                }
+            }
+            for group in std::iter::once(&entry.group)
+                .chain(entry.auth_profiles.iter().map(|p| &p.group))
+                .flatten()
+            {
+                anyhow::ensure!(
+                    self.routing.has_credential_group(group),
+                    "provider '{}' group '{group}' is not listed in routing credential-groups",
+                    entry.name
+                );
+            }
             let mut seen_profile_ids = std::collections::HashSet::new();
             for profile in &entry.auth_profiles {
                 profile
@@ -241,12 +646,103 @@ impl Config {
     /// Safe for the persistence path (dashboard config writes).
     fn normalize(&mut self) {
         sanitize_entries(&mut self.providers);
+        self.assign_provider_ids();
+        self.assign_auth_key_ids();
+        for name in self
+            .outbound_headers
+            .forward
+            .iter_mut()
+            .chain(self.outbound_headers.strip.iter_mut())
+        {
+            *name = name.trim().to_ascii_lowercase();
+        }
         normalize_optional_string(&mut self.managed_auth.storage_dir);
         normalize_optional_string(&mut self.managed_auth.codex_auth_file);
         normalize_optional_string(&mut self.managed_auth.proxy_url);
         self.migrate_legacy_presentation();
     }
 
+    /// Validate the outbound header policy and every configured upstream header.
+    fn validate_cluster(&self) -> Result<(), anyhow::Error> {
+        anyhow::ensure!(
+            self.cluster.timeout_ms > 0,
+            "cluster timeout-ms must be greater than 0"
+        );
+        let mut names = std::collections::HashSet::new();
+        names.insert(self.cluster.node_name.as_str());
+        for peer in &self.cluster.peers {
+            anyhow::ensure!(
+                !peer.name.trim().is_empty(),
+                "cluster peer name must not be empty"
+            );
+            anyhow::ensure!(
+                names.insert(peer.name.as_str()),
+                "duplicate cluster node name '{}'",
+                peer.name
+            );
+            anyhow::ensure!(
+                peer.url.starts_with("http://") || peer.url.starts_with("https://"),
+                "cluster peer '{}' url must start with http:// or https://",
+                peer.name
+            );
+        }
+        Ok(())
+    }
+
+    fn validate_outbound_headers(&self) -> Result<(), anyhow::Error> {
+        let policy = &self.outbound_headers;
+        for name in &policy.forward {
+            anyhow::ensure!(
+                is_valid_header_name(name),
+                "outbound-headers forward entry '{name}' is not a valid header name"
+            );
+            anyhow::ensure!(
+                !crate::presentation::protected::is_protected(name),
+                "outbound-headers cannot forward protected header '{name}'"
+            );
+            anyhow::ensure!(
+                !policy.is_stripped(name),
+                "outbound-headers header '{name}' is both forwarded and stripped"
+            );
+        }
+        for name in &policy.strip {
+            anyhow::ensure!(
+                is_valid_header_name(name),
+                "outbound-headers strip entry '{name}' is not a valid header name"
+            );
+        }
+
+        let check = |scope: &str, headers: &HashMap<String, String>| {
+            for (name, value) in headers {
+                anyhow::ensure!(
+                    is_valid_header_name(name),
+                    "{scope}: header '{name}' is not a valid header name"
+                );
+                anyhow::ensure!(
+                    is_valid_header_value(value),
+                    "{scope}: header '{name}' has a value with control characters"
+                );
+                anyhow::ensure!(
+                    !policy.is_stripped(name),
+                    "{scope}: header '{name}' is listed in outbound-headers strip"
+                );
+            }
+            Ok(())
+        };
+        check("claude-header-defaults", &self.claude_header_defaults)?;
+        for entry in &self.providers {
+            let scope = format!("provider '{}'", entry.name);
+            check(&scope, &entry.headers)?;
+            check(&scope, &entry.upstream_presentation.custom_headers)?;
+            for profile in &entry.auth_profiles {
+                let scope = format!("provider '{}' auth profile '{}'", entry.name, profile.id);
+                check(&scope, &profile.headers)?;
+                check(&scope, &profile.upstream_presentation.custom_headers)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Migrate legacy `cloak` + `headers` config into `upstream-presentation`.
     fn migrate_legacy_presentation(&mut self) {
         use crate::cloak::CloakMode;
@@ -315,6 +811,27 @@ impl Config {
             );
         }
 
+        if let Some(ref secret) = self.moderation.api_key {
+            self.moderation.api_key = Some(
+                crate::secret::resolve(secret)
+                    .map_err(|e| anyhow::anyhow!("moderation.api-key: {e}"))?,
+            );
+        }
+
+        self.shared_state.redis_url = crate::secret::resolve(&self.shared_state.redis_url)
+            .map_err(|e| anyhow::anyhow!("shared-state.redis-url: {e}"))?;
+        for report in &mut self.reports {
+            report.webhook_url = crate::secret::resolve(&report.webhook_url)
+                .map_err(|e| anyhow::anyhow!("report '{}' webhook-url: {e}", report.name))?;
+        }
+        for peer in &mut self.cluster.peers {
+            if let Some(ref secret) = peer.jwt_secret {
+                peer.jwt_secret = Some(crate::secret::resolve(secret).map_err(|e| {
+                    anyhow::anyhow!("cluster peer '{}' jwt-secret: {e}", peer.name)
+                })?);
+            }
+        }
+
         // Build AuthKeyStore for O(1) auth key lookups
         self.auth_key_store = AuthKeyStore::new(self.auth_keys.clone());
         Ok(())
@@ -324,6 +841,168 @@ impl Config {
     pub fn all_provider_keys(&self) -> impl Iterator<Item = &ProviderKeyEntry> {
         self.providers.iter()
     }
+
+    /// Look up a provider by its persistent `id`, falling back to its `name`.
+    pub fn find_provider(&self, id_or_name: &str) -> Option<&ProviderKeyEntry> {
+        self.providers
+            .iter()
+            .find(|entry| entry.id.as_deref() == Some(id_or_name))
+            .or_else(|| self.providers.iter().find(|entry| entry.name == id_or_name))
+    }
+
+    /// Give every provider without an `id` one derived from its name. Runs on load, so
+    /// every instance reports the same IDs before any dashboard write has persisted them;
+    /// once written, an ID stays with the entry across renames.
+    pub fn assign_provider_ids(&mut self) {
+        for entry in &mut self.providers {
+            if entry.id.as_deref().is_none_or(|id| id.trim().is_empty()) {
+                entry.id = Some(derived_id("provider", &entry.name));
+            }
+        }
+    }
+
+    /// Give every auth key without an `id` one derived from the key as written, so
+    /// it is the same in the raw and the resolved config.
+    pub fn assign_auth_key_ids(&mut self) {
+        for entry in &mut self.auth_keys {
+            if entry.id.as_deref().is_none_or(|id| id.trim().is_empty()) {
+                entry.id = Some(derived_id("auth-key", &entry.key));
+            }
+        }
+    }
+
+    /// Position in `auth-keys` of the key with this `id`.
+    pub fn auth_key_position(&self, id: &str) -> Option<usize> {
+        self.auth_keys
+            .iter()
+            .position(|entry| entry.id.as_deref() == Some(id))
+    }
+
+    /// First `routes` entry whose path glob matches `path`.
+    pub fn route_limits(&self, path: &str) -> Option<&RouteLimitConfig> {
+        self.routes
+            .iter()
+            .find(|route| crate::glob::glob_match(&route.path, path))
+    }
+
+    /// Body limit in bytes for `path`, honoring per-route overrides.
+    pub fn body_limit_bytes(&self, path: &str) -> usize {
+        self.route_limits(path)
+            .and_then(|route| route.body_limit_mb)
+            .unwrap_or(self.body_limit_mb)
+            .saturating_mul(1024 * 1024)
+    }
+
+    /// Global upstream proxy and timeouts used by the shared HTTP client pool.
+    pub fn network_settings(&self) -> crate::proxy::NetworkSettings {
+        crate::proxy::NetworkSettings {
+            proxy_url: self.proxy_url.clone(),
+            connect_timeout_secs: self.connect_timeout,
+            request_timeout_secs: self.request_timeout,
+            dns: self.dns.clone(),
+        }
+    }
+
+    /// Work out which runtime subsystems are affected by moving from `self` to `next`.
+    pub fn reload_delta(&self, next: &Config) -> ReloadDelta {
+        fn section<T: Serialize>(value: &T) -> serde_json::Value {
+            serde_json::to_value(value).unwrap_or_default()
+        }
+        let changed = |a: serde_json::Value, b: serde_json::Value| a != b;
+
+        let credentials = changed(section(&self.providers), section(&next.providers))
+            || changed(
+                section(&self.circuit_breaker),
+                section(&next.circuit_breaker),
+            )
+            || changed(section(&self.managed_auth), section(&next.managed_auth));
+        let network = self.network_settings() != next.network_settings()
+            || changed(
+                section(&self.fault_injection),
+                section(&next.fault_injection),
+            );
+
+        // Everything not covered by a dedicated subsystem is reported as `other`.
+        let rest = |config: &Config| {
+            let mut value = section(config);
+            if let Some(map) = value.as_object_mut() {
+                for key in [
+                    "providers",
+                    "circuit-breaker",
+                    "managed-auth",
+                    "auth-keys",
+                    "rate-limit",
+                    "model-prices",
+                    "payload",
+                    "translation-rules",
+                    "routing",
+                    "proxy-url",
+                    "connect-timeout",
+                    "request-timeout",
+                    "dns",
+                    "fault-injection",
+                ] {
+                    map.remove(key);
+                }
+            }
+            value
+        };
+
+        ReloadDelta {
+            credentials,
+            auth_keys: changed(section(&self.auth_keys), section(&next.auth_keys)),
+            rate_limits: changed(section(&self.rate_limit), section(&next.rate_limit)),
+            prices: changed(section(&self.model_prices), section(&next.model_prices)),
+            payload: changed(section(&self.payload), section(&next.payload)),
+            translation: changed(
+                section(&self.translation_rules),
+                section(&next.translation_rules),
+            ),
+            routing: changed(section(&self.routing), section(&next.routing)),
+            network,
+            other: changed(rest(self), rest(next)),
+        }
+    }
+}
+
+/// Runtime subsystems touched by a config reload; see [`Config::reload_delta`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReloadDelta {
+    /// Providers, auth profiles, circuit breaker, or managed auth settings.
+    pub credentials: bool,
+    pub auth_keys: bool,
+    pub rate_limits: bool,
+    pub prices: bool,
+    pub payload: bool,
+    pub translation: bool,
+    pub routing: bool,
+    /// Global proxy URL, upstream timeouts, DNS, or fault injection.
+    pub network: bool,
+    pub other: bool,
+}
+
+impl ReloadDelta {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Names of the touched subsystems, for logs and API responses.
+    pub fn touched(&self) -> Vec<&'static str> {
+        [
+            (self.credentials, "credentials"),
+            (self.auth_keys, "auth_keys"),
+            (self.rate_limits, "rate_limits"),
+            (self.prices, "prices"),
+            (self.payload, "payload"),
+            (self.translation, "translation"),
+            (self.routing, "routing"),
+            (self.network, "network"),
+            (self.other, "other"),
+        ]
+        .into_iter()
+        .filter_map(|(touched, name)| touched.then_some(name))
+        .collect()
+    }
 }
 
 /// Resolve env:// and file:// secrets in provider API keys,
@@ -350,6 +1029,10 @@ fn resolve_provider_secrets(entries: &mut [ProviderKeyEntry]) -> Result<(), anyh
             entry.api_key = crate::secret::resolve(&entry.api_key)
                 .map_err(|e| anyhow::anyhow!("provider '{}': {e}", entry.name))?;
         }
+        if let Some(signing) = &mut entry.signing {
+            signing.secret = crate::secret::resolve(&signing.secret)
+                .map_err(|e| anyhow::anyhow!("provider '{}' signing secret: {e}", entry.name))?;
+        }
 
         for profile in &mut entry.auth_profiles {
             profile.resolve_secrets().map_err(|e| {
@@ -364,6 +1047,16 @@ fn resolve_provider_secrets(entries: &mut [ProviderKeyEntry]) -> Result<(), anyh
     Ok(())
 }
 
+/// UUID-formatted ID for an entry loaded without one, stable for a given `seed`.
+fn derived_id(kind: &str, seed: &str) -> String {
+    let digest = sha2::Sha256::digest(format!("{kind}:{seed}").as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
 /// Normalize provider entries for runtime and persistence.
 fn sanitize_entries(entries: &mut [ProviderKeyEntry]) {
     // Normalize entries
@@ -399,8 +1092,290 @@ fn normalize_optional_string(value: &mut Option<String>) {
     }
 }
 
+// ─── Vision config ─────────────────────────────────────────────────────────
+
+/// Processing of image content before it is sent upstream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct VisionConfig {
+    /// Re-encode images that exceed either limit below.
+    pub downscale: bool,
+    /// Longest edge in pixels; larger images are resized to fit, keeping aspect ratio.
+    pub max_dimension: u32,
+    /// Decoded size limit per image; larger images are resized and re-encoded until they fit.
+    pub max_image_bytes: usize,
+    /// Images whose header declares more pixels than this are not decoded and go out as sent.
+    pub max_source_pixels: u64,
+    /// Download remote image URLs and inline them for Claude and Gemini upstreams.
+    pub fetch_remote: bool,
+    /// Host globs allowed for remote fetches, e.g. `*.example.com`. Use `*` to allow any host.
+    pub fetch_allowed_hosts: Vec<String>,
+    /// Largest remote image downloaded, in bytes.
+    pub fetch_max_bytes: usize,
+    /// Per-image download timeout.
+    pub fetch_timeout_secs: u64,
+}
+
+impl Default for VisionConfig {
+    fn default() -> Self {
+        Self {
+            downscale: false,
+            max_dimension: 2048,
+            max_image_bytes: 4 * 1024 * 1024,
+            max_source_pixels: 4096 * 4096,
+            fetch_remote: false,
+            fetch_allowed_hosts: Vec::new(),
+            fetch_max_bytes: 10 * 1024 * 1024,
+            fetch_timeout_secs: 10,
+        }
+    }
+}
+
+impl VisionConfig {
+    /// Whether remote fetches may contact `host`.
+    pub fn fetch_allows_host(&self, host: &str) -> bool {
+        self.fetch_allowed_hosts
+            .iter()
+            .any(|pattern| crate::glob::glob_match(pattern, host))
+    }
+}
+
+// ─── Shadow traffic ────────────────────────────────────────────────────────
+
+/// Mirror a share of requests for matching models to a secondary provider.
+/// Mirrored responses are discarded; only latency and usage are logged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ShadowRule {
+    /// Glob patterns for the requested model; empty matches every model.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Name of the provider that receives the mirrored request.
+    pub provider: String,
+    /// Model sent to the shadow provider; defaults to the requested model.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Percentage of matching requests to mirror, in (0, 100].
+    pub percent: f64,
+}
+
+impl ShadowRule {
+    pub fn matches(&self, model: &str) -> bool {
+        self.models.is_empty()
+            || self
+                .models
+                .iter()
+                .any(|pattern| crate::glob::glob_match(pattern, model))
+    }
+}
+
+// ─── Experiments ───────────────────────────────────────────────────────────
+
+/// A/B experiment: send a share of traffic for a requested model to a variant model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Experiment {
+    /// Unique name used in request logs, debug headers, and metrics.
+    pub name: String,
+    /// Glob for the requested model (after alias rewrites).
+    pub model: String,
+    /// Model served to the variant arm.
+    pub variant: String,
+    /// Percentage of matching requests assigned to the variant arm, in [0, 100].
+    pub percent: f64,
+}
+
+impl Experiment {
+    pub fn matches(&self, model: &str) -> bool {
+        crate::glob::glob_match(&self.model, model)
+    }
+
+    /// Pick an arm from a uniform `roll` in `[0, 1)`.
+    pub fn assign(&self, roll: f64) -> ExperimentArm {
+        if roll * 100.0 < self.percent {
+            ExperimentArm::Variant
+        } else {
+            ExperimentArm::Control
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExperimentArm {
+    /// The requested model, unchanged.
+    Control,
+    /// The experiment's variant model.
+    Variant,
+}
+
+impl ExperimentArm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Control => "control",
+            Self::Variant => "variant",
+        }
+    }
+}
+
+// ─── Preset models ─────────────────────────────────────────────────────────
+
+/// A synthetic model name that expands to a base model with a pinned system
+/// prompt and default parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PresetModel {
+    /// Model name clients request.
+    pub name: String,
+    /// Model the request is sent to.
+    pub model: String,
+    /// Prepended to the request's own system prompt.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Top-level body fields set when the request does not provide them.
+    #[serde(default)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl PresetModel {
+    /// Apply the preset to a request body in `format`, rewriting its model.
+    pub fn apply(&self, body: &mut serde_json::Value, format: crate::provider::Format) {
+        use crate::provider::Format;
+        use serde_json::{Value, json};
+
+        let Some(obj) = body.as_object_mut() else {
+            return;
+        };
+        if obj.contains_key("model") {
+            obj.insert("model".to_string(), Value::String(self.model.clone()));
+        }
+        for (key, value) in &self.params {
+            obj.entry(key.as_str()).or_insert_with(|| value.clone());
+        }
+        let Some(prompt) = self.system_prompt.as_deref().filter(|p| !p.is_empty()) else {
+            return;
+        };
+        match format {
+            Format::OpenAI => {
+                if let Some(messages) = obj.get_mut("messages").and_then(Value::as_array_mut) {
+                    messages.insert(0, json!({"role": "system", "content": prompt}));
+                } else {
+                    // Responses API bodies carry the system prompt in `instructions`.
+                    let combined = match obj.get("instructions").and_then(Value::as_str) {
+                        Some(existing) if !existing.is_empty() => format!("{prompt}\n\n{existing}"),
+                        _ => prompt.to_string(),
+                    };
+                    obj.insert("instructions".to_string(), Value::String(combined));
+                }
+            }
+            Format::Claude => {
+                let system = match obj.remove("system") {
+                    Some(Value::Array(mut blocks)) => {
+                        blocks.insert(0, json!({"type": "text", "text": prompt}));
+                        Value::Array(blocks)
+                    }
+                    Some(Value::String(existing)) if !existing.is_empty() => {
+                        Value::String(format!("{prompt}\n\n{existing}"))
+                    }
+                    _ => Value::String(prompt.to_string()),
+                };
+                obj.insert("system".to_string(), system);
+            }
+            Format::Gemini => {
+                let key = if obj.contains_key("system_instruction") {
+                    "system_instruction"
+                } else {
+                    "systemInstruction"
+                };
+                let instruction = obj.entry(key).or_insert_with(|| json!({"parts": []}));
+                if let Some(parts) = instruction
+                    .as_object_mut()
+                    .map(|o| o.entry("parts").or_insert_with(|| json!([])))
+                    .and_then(Value::as_array_mut)
+                {
+                    parts.insert(0, json!({"text": prompt}));
+                }
+            }
+        }
+    }
+}
+
+// ─── Moderation ────────────────────────────────────────────────────────────
+
+/// Send the prompt to an OpenAI-compatible moderation endpoint before dispatch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ModerationConfig {
+    pub enabled: bool,
+    /// Endpoint accepting `{"input": ...}` and returning OpenAI moderation results.
+    pub url: String,
+    /// Bearer token for the endpoint (supports `env://` and `file://`).
+    pub api_key: Option<String>,
+    /// Moderation model, e.g. `omni-moderation-latest`; omitted when unset.
+    pub model: Option<String>,
+    /// Action for flagged prompts; auth keys can override it.
+    pub action: ModerationAction,
+    pub timeout_secs: u64,
+    /// Let requests through when the moderation call fails.
+    pub fail_open: bool,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "https://api.openai.com/v1/moderations".to_string(),
+            api_key: None,
+            model: None,
+            action: ModerationAction::Block,
+            timeout_secs: 5,
+            fail_open: true,
+        }
+    }
+}
+
+/// Raw passthrough of `/v1/files`, `/v1/assistants`, and `/v1/threads` to a
+/// designated OpenAI credential.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct OpenAIPassthroughConfig {
+    pub enabled: bool,
+    /// OpenAI-format provider whose credential serves the routes.
+    pub provider: String,
+    /// Credential name (glob) within the provider; the first available one when unset.
+    pub credential: Option<String>,
+    /// Also forward any other `/v1` path the proxy has no route for.
+    pub catch_all: bool,
+}
+
+/// What to do with a prompt the moderation endpoint flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModerationAction {
+    /// Skip the moderation call.
+    Off,
+    /// Forward the request and tell the client via the `x-prism-moderation` header.
+    Annotate,
+    /// Forward the request; only log and count the verdict.
+    Flag,
+    /// Reject the request.
+    #[default]
+    Block,
+}
+
 // ─── Rate limit config ─────────────────────────────────────────────────────
 
+/// Per-route limit overrides, e.g. larger bodies for `/v1/audio/*`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RouteLimitConfig {
+    /// Request path glob (`*` matches any characters), e.g. `/v1/audio/*`.
+    pub path: String,
+    /// Body size limit in MB for matching requests; falls back to `body-limit-mb`.
+    pub body_limit_mb: Option<usize>,
+    /// Requests per minute shared by all requests matching this entry (0 = unlimited).
+    pub rpm: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default)]
 pub struct RateLimitConfig {
@@ -431,6 +1406,109 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// Process-wide caps on concurrent work. Requests past a cap are shed with 503
+/// and `Retry-After` instead of queueing, so traffic spikes cannot exhaust memory
+/// or file descriptors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ConcurrencyConfig {
+    /// API requests in flight, counted until their response body ends (0 = unlimited).
+    pub max_requests: usize,
+    /// Streamed responses in flight (0 = unlimited).
+    pub max_streams: usize,
+    /// `Retry-After` seconds sent with shed requests.
+    pub retry_after_secs: u64,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_requests: 0,
+            max_streams: 0,
+            retry_after_secs: 1,
+        }
+    }
+}
+
+/// How upstream clients resolve host names. The defaults leave resolution to the
+/// system resolver on every new connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct DnsConfig {
+    /// Fixed addresses per host name, used instead of DNS.
+    pub overrides: HashMap<String, Vec<std::net::IpAddr>>,
+    /// Seconds a resolved answer is reused before resolving again (0 = no caching).
+    pub cache_ttl_secs: u64,
+    /// Seconds after resolution an answer is still used when a new lookup fails
+    /// (0 = fail with the lookup).
+    pub serve_stale_secs: u64,
+    /// Which address family is tried first when a host has both.
+    pub ip_preference: IpPreference,
+}
+
+/// Address family order for upstream connections. The connector tries the first
+/// family and races the other after a short delay (happy eyeballs), unless only
+/// one family is allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IpPreference {
+    /// Keep the resolver's order.
+    #[default]
+    System,
+    Ipv4First,
+    Ipv6First,
+    Ipv4Only,
+    Ipv6Only,
+}
+
+/// Faults injected into upstream calls so retry, cooldown, bootstrap retry, and
+/// fallback paths can be exercised against real providers. For staging only:
+/// every injected fault fails or slows a real request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct FaultInjectionConfig {
+    pub enabled: bool,
+    /// Provider names faults apply to (empty = all providers).
+    pub providers: Vec<String>,
+    /// Fraction of upstream calls delayed before they are sent (0.0-1.0).
+    pub latency_rate: f64,
+    pub latency_min_ms: u64,
+    pub latency_max_ms: u64,
+    /// Fraction of upstream calls answered with an injected error instead of
+    /// being sent (0.0-1.0).
+    pub error_rate: f64,
+    /// Statuses injected errors are drawn from.
+    pub error_statuses: Vec<u16>,
+    /// Fraction of streams cut off with a network error (0.0-1.0).
+    pub stream_drop_rate: f64,
+    /// Chunks delivered before a stream is cut off. 0 drops it before the first
+    /// chunk, which exercises bootstrap retry.
+    pub stream_drop_after_chunks: usize,
+}
+
+impl Default for FaultInjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            providers: Vec::new(),
+            latency_rate: 0.0,
+            latency_min_ms: 500,
+            latency_max_ms: 5000,
+            error_rate: 0.0,
+            error_statuses: vec![429, 500],
+            stream_drop_rate: 0.0,
+            stream_drop_after_chunks: 3,
+        }
+    }
+}
+
+impl FaultInjectionConfig {
+    /// Whether faults may be injected into calls to `provider`.
+    pub fn applies_to(&self, provider: &str) -> bool {
+        self.enabled && (self.providers.is_empty() || self.providers.iter().any(|p| p == provider))
+    }
+}
+
 // ─── Log store config ─────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -446,6 +1524,8 @@ pub struct LogStoreConfig {
     pub max_body_bytes: usize,
     /// Optional file audit (JSONL persistence).
     pub file_audit: FileAuditConfig,
+    /// Requests taking at least this long get phase timings in their log entry. 0 = off.
+    pub slow_request_threshold_ms: u64,
 }
 
 impl Default for LogStoreConfig {
@@ -456,6 +1536,7 @@ impl Default for LogStoreConfig {
             detail_level: LogDetailLevel::Metadata,
             max_body_bytes: 1_048_576,
             file_audit: FileAuditConfig::default(),
+            slow_request_threshold_ms: 0,
         }
     }
 }
@@ -501,52 +1582,397 @@ pub struct DashboardConfig {
     pub login_lockout_secs: u64,
     /// Restrict dashboard access to localhost only.
     pub localhost_only: bool,
+    /// Backing store for dashboard-managed providers and auth keys.
+    pub managed_store: crate::managed_store::ManagedStoreConfig,
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            username: "admin".to_string(),
+            password_hash: String::new(),
+            jwt_secret: None,
+            jwt_ttl_secs: 3600,
+            max_login_attempts: 5,
+            login_lockout_secs: 300,
+            localhost_only: true,
+            managed_store: crate::managed_store::ManagedStoreConfig::default(),
+        }
+    }
+}
+
+impl DashboardConfig {
+    /// Resolve the JWT secret from config or env.
+    pub fn resolve_jwt_secret(&self) -> Option<String> {
+        self.jwt_secret
+            .clone()
+            .or_else(|| std::env::var("DASHBOARD_JWT_SECRET").ok())
+    }
+}
+
+// ─── Daemon config ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct DaemonConfig {
+    /// Path to the PID file.
+    pub pid_file: String,
+    /// Graceful shutdown timeout in seconds.
+    pub shutdown_timeout: u64,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            pid_file: "./prism.pid".to_string(),
+            shutdown_timeout: 30,
+        }
+    }
+}
+
+/// Startup self-test that gates `GET /ready` and the lifecycle ready notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ReadinessConfig {
+    /// Stay not-ready until at least one credential's upstream answers a connectivity probe.
+    pub probe_credentials: bool,
+    /// Timeout for each credential probe or warm-up request, in seconds.
+    pub probe_timeout_secs: u64,
+    /// Delay between probe rounds while no credential has passed, in seconds.
+    pub probe_interval_secs: u64,
+    /// Open a connection to every enabled credential's upstream before reporting
+    /// ready, so first requests skip connection and TLS setup.
+    pub warm_up: bool,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            probe_credentials: false,
+            probe_timeout_secs: 5,
+            probe_interval_secs: 10,
+            warm_up: false,
+        }
+    }
+}
+
+/// W3C `traceparent` propagation to upstream providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct TracePropagationConfig {
+    /// Send the gateway's `traceparent` on upstream requests. Off by default so
+    /// trace ids only reach providers the operator chose to share them with.
+    pub enabled: bool,
+    /// Header name used for the upstream trace context.
+    pub upstream_header: String,
+}
+
+impl Default for TracePropagationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            upstream_header: "traceparent".to_string(),
+        }
+    }
+}
+
+/// Persistence of credential cooldowns and quota counters, so a restart does
+/// not send traffic straight back to rate-limited or capped credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RouterStateConfig {
+    pub enabled: bool,
+    /// State file path. Relative paths are resolved against the config file directory.
+    pub path: String,
+    /// How often changed state is written out, in seconds.
+    pub flush_interval_secs: u64,
+}
+
+impl Default for RouterStateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "prism-router-state.json".to_string(),
+            flush_interval_secs: 5,
+        }
+    }
+}
+
+/// Rotation and retention of `logging-to-file` output. Zero limits mean unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct LogRotationConfig {
+    /// Start a new file every hour, every day, or never.
+    pub interval: LogRotationInterval,
+    /// Start a new file once the current one reaches this size.
+    pub max_file_size_mb: u64,
+    /// Log files kept, including the one being written.
+    pub max_files: usize,
+    /// Delete log files last written more than this many days ago.
+    pub max_age_days: u64,
+    /// Delete the oldest log files once all of them together exceed this size.
+    pub max_total_size_mb: u64,
+}
+
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        Self {
+            interval: LogRotationInterval::Daily,
+            max_file_size_mb: 0,
+            max_files: 0,
+            max_age_days: 0,
+            max_total_size_mb: 0,
+        }
+    }
+}
+
+impl LogRotationConfig {
+    pub fn policy(&self) -> prism_lifecycle::logging::LogRotation {
+        use prism_lifecycle::logging::RotationInterval;
+        const MB: u64 = 1024 * 1024;
+        prism_lifecycle::logging::LogRotation {
+            interval: match self.interval {
+                LogRotationInterval::Hourly => RotationInterval::Hourly,
+                LogRotationInterval::Daily => RotationInterval::Daily,
+                LogRotationInterval::Never => RotationInterval::Never,
+            },
+            max_file_bytes: self.max_file_size_mb.saturating_mul(MB),
+            max_files: self.max_files,
+            max_age: (self.max_age_days > 0)
+                .then(|| std::time::Duration::from_secs(self.max_age_days.saturating_mul(86_400))),
+            max_total_bytes: self.max_total_size_mb.saturating_mul(MB),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogRotationInterval {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+/// Masking of credentials in log output, request logs, and error messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Extra regexes to mask. A `keep` capture group stays visible.
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Per-request debug traces, requested with the `x-debug: trace` header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RequestDebugConfig {
+    /// Honor `x-debug: trace`. When off, the header only adds debug response headers.
+    pub enabled: bool,
+    /// Traces kept in memory, newest first.
+    pub max_records: usize,
+    /// Cap on each recorded payload.
+    pub max_body_bytes: usize,
+}
+
+impl Default for RequestDebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_records: 100,
+            max_body_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Persisted start/stop history of the proxy process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ProcessHistoryConfig {
+    pub enabled: bool,
+    /// History file path. Relative paths are resolved against the config file directory.
+    pub path: String,
+    /// Number of most recent runs kept, including the current one.
+    pub max_runs: usize,
+}
+
+impl Default for ProcessHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "prism-process-history.json".to_string(),
+            max_runs: 50,
+        }
+    }
+}
+
+/// Redis-backed sharing of rate-limit usage, budget spend, credential cooldowns,
+/// and quota counters, so that several replicas enforce limits as one fleet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct SharedStateConfig {
+    /// `redis://` or `rediss://` URL (supports `env://` and `file://`). Empty disables sharing.
+    pub redis_url: String,
+    /// Prefix for every Redis key written by the proxy.
+    pub key_prefix: String,
+    /// How often local changes are published and peer changes applied, in milliseconds.
+    pub sync_interval_ms: u64,
+    /// How long shared records are kept, and how far back a starting instance replays them.
+    pub retention_secs: u64,
+}
+
+impl SharedStateConfig {
+    pub fn enabled(&self) -> bool {
+        !self.redis_url.is_empty()
+    }
+}
+
+impl Default for SharedStateConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: String::new(),
+            key_prefix: "prism:".to_string(),
+            sync_interval_ms: 500,
+            retention_secs: 86400,
+        }
+    }
+}
+
+/// Other instances of the same deployment, shown together in the dashboard
+/// cluster view (`GET /api/dashboard/cluster`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ClusterConfig {
+    /// Name of this instance in the cluster view.
+    pub node_name: String,
+    pub peers: Vec<ClusterPeer>,
+    /// Per-peer request timeout, in milliseconds.
+    pub timeout_ms: u64,
 }
 
-impl Default for DashboardConfig {
+impl Default for ClusterConfig {
     fn default() -> Self {
         Self {
-            enabled: false,
-            username: "admin".to_string(),
-            password_hash: String::new(),
-            jwt_secret: None,
-            jwt_ttl_secs: 3600,
-            max_login_attempts: 5,
-            login_lockout_secs: 300,
-            localhost_only: true,
+            node_name: "local".to_string(),
+            peers: Vec::new(),
+            timeout_ms: 2000,
         }
     }
 }
 
-impl DashboardConfig {
-    /// Resolve the JWT secret from config or env.
-    pub fn resolve_jwt_secret(&self) -> Option<String> {
-        self.jwt_secret
-            .clone()
-            .or_else(|| std::env::var("DASHBOARD_JWT_SECRET").ok())
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ClusterPeer {
+    pub name: String,
+    /// Base URL of the peer, e.g. `http://10.0.0.2:8317`.
+    pub url: String,
+    /// Dashboard JWT secret of the peer (supports `env://` and `file://`).
+    /// Defaults to this instance's dashboard secret.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwt_secret: Option<String>,
+}
+
+/// A usage summary (requests, tokens, and cost by model and API key) posted to
+/// a webhook once per period, built from the request log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UsageReportConfig {
+    pub name: String,
+    #[serde(default)]
+    pub period: ReportPeriod,
+    /// Hour of the day (UTC) the report is sent; weekly reports go out on Mondays.
+    #[serde(default)]
+    pub hour_utc: u32,
+    /// Webhook receiving the report (supports `env://` and `file://`).
+    pub webhook_url: String,
+    #[serde(default)]
+    pub format: ReportFormat,
+    /// Rows per breakdown (models, API keys).
+    #[serde(default = "default_report_top")]
+    pub top: usize,
+}
+
+fn default_report_top() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportPeriod {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl ReportPeriod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+        }
     }
 }
 
-// ─── Daemon config ─────────────────────────────────────────────────────────
+/// Webhook payload shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportFormat {
+    /// The report as JSON with a rendered `text` summary, for email gateways and scripts.
+    #[default]
+    Json,
+    /// Slack incoming webhook (`{"text": ...}`).
+    Slack,
+    /// Discord webhook (`{"content": ...}`).
+    Discord,
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `passthrough-headers` entry selecting normalized rate-limit headers.
+pub const PASSTHROUGH_RATE_LIMITS: &str = "rate-limits";
+
+/// Which inbound client headers reach upstream providers, and which headers
+/// are never sent upstream whatever their source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default)]
-pub struct DaemonConfig {
-    /// Path to the PID file.
-    pub pid_file: String,
-    /// Graceful shutdown timeout in seconds.
-    pub shutdown_timeout: u64,
+pub struct OutboundHeadersConfig {
+    /// Inbound client headers copied to upstream requests (e.g. `x-request-id`).
+    /// Configured provider headers take precedence over forwarded values.
+    pub forward: Vec<String>,
+    /// Headers removed from every upstream request, including profile defaults.
+    pub strip: Vec<String>,
 }
 
-impl Default for DaemonConfig {
-    fn default() -> Self {
-        Self {
-            pid_file: "./prism.pid".to_string(),
-            shutdown_timeout: 30,
-        }
+impl OutboundHeadersConfig {
+    /// Whether `name` is in the strip list (case-insensitive).
+    pub fn is_stripped(&self, name: &str) -> bool {
+        self.strip.iter().any(|s| s.eq_ignore_ascii_case(name))
     }
 }
 
+/// RFC 9110 token characters for a header field name.
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Header values may not contain control characters other than horizontal tab.
+fn is_valid_header_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b == b'\t' || (b >= 0x20 && b != 0x7f))
+}
+
 // ─── Sub-configs ───────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -563,6 +1989,14 @@ pub struct StreamingConfig {
     pub keepalive_seconds: u64,
     /// Max retries before first byte is sent to client (streaming bootstrap retry).
     pub bootstrap_retries: u32,
+    /// Per-model caps on streamed responses; the first matching entry applies.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<StreamLimit>,
+    /// Per-route batching of small SSE events; the first matching entry applies.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub coalesce: Vec<StreamCoalesce>,
+    /// Bounded buffer between upstream reads and client writes.
+    pub buffer: StreamBufferConfig,
 }
 
 impl Default for StreamingConfig {
@@ -570,10 +2004,84 @@ impl Default for StreamingConfig {
         Self {
             keepalive_seconds: 15,
             bootstrap_retries: 1,
+            limits: Vec::new(),
+            coalesce: Vec::new(),
+            buffer: StreamBufferConfig::default(),
         }
     }
 }
 
+impl StreamingConfig {
+    /// The limit for an upstream model id, if any entry matches it.
+    pub fn limit_for(&self, model: &str) -> Option<&StreamLimit> {
+        self.limits
+            .iter()
+            .find(|l| l.models.iter().any(|m| crate::glob::glob_match(m, model)))
+    }
+
+    /// The coalescing entry for a request path and upstream model id, if any.
+    pub fn coalesce_for(&self, path: &str, model: &str) -> Option<&StreamCoalesce> {
+        let matches = |patterns: &[String], value: &str| {
+            patterns.is_empty() || patterns.iter().any(|p| crate::glob::glob_match(p, value))
+        };
+        self.coalesce
+            .iter()
+            .find(|c| matches(&c.paths, path) && matches(&c.models, model))
+    }
+}
+
+/// Buffer SSE output and send it in fewer, larger writes. A buffer is flushed
+/// once it reaches `max_bytes` or its oldest byte has waited `max_delay_ms`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct StreamCoalesce {
+    /// Request paths, e.g. `/v1/chat/completions`; `*` and `?` wildcards allowed.
+    /// Empty matches every path.
+    pub paths: Vec<String>,
+    /// Upstream model ids; `*` and `?` wildcards allowed. Empty matches every model.
+    pub models: Vec<String>,
+    /// Longest time buffered output waits before it is sent.
+    pub max_delay_ms: u64,
+    /// Buffer size that triggers a send before the delay is up (0 = time only).
+    pub max_bytes: usize,
+}
+
+/// Queue of stream chunks read ahead from the upstream while the client is
+/// written to. Once the queued chunks would exceed `high_water_mark_bytes` the
+/// client is too slow, and `on_full` decides what happens.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct StreamBufferConfig {
+    /// Bytes queued for the client before `on-full` applies (0 = no buffer; the
+    /// upstream is read only as fast as the client is written).
+    pub high_water_mark_bytes: usize,
+    pub on_full: SlowClientAction,
+}
+
+/// What to do with a stream whose client cannot keep up with the upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SlowClientAction {
+    /// Stop reading the upstream until the client catches up.
+    #[default]
+    Backpressure,
+    /// Abort the response and drop the upstream.
+    Disconnect,
+}
+
+/// Stream cut-off for runaway upstreams. When reached, the client gets a
+/// finish chunk with a length stop reason and the upstream is dropped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct StreamLimit {
+    /// Upstream model ids; `*` and `?` wildcards allowed.
+    pub models: Vec<String>,
+    /// Bytes sent to the client before the stream is cut (0 = unlimited).
+    pub max_bytes: u64,
+    /// Seconds from the first upstream byte before the stream is cut (0 = unlimited).
+    pub max_duration_secs: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default)]
 pub struct RetryConfig {
@@ -614,6 +2122,10 @@ pub struct ModelMapping {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ProviderKeyEntry {
+    /// Persistent opaque identifier used by the dashboard API. Assigned on the first
+    /// dashboard write so it survives renames and reordering of the providers list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// Unique provider name (used as identity for routing).
     pub name: String,
     /// Wire protocol format.
@@ -621,6 +2133,10 @@ pub struct ProviderKeyEntry {
     /// Upstream executor family. When omitted, defaults to the wire format family.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub upstream: Option<crate::provider::UpstreamKind>,
+    /// Name of a custom executor registered with the `ExecutorRegistry`, e.g. through
+    /// `ProxyBuilder::executor`. Takes precedence over `upstream` when dispatching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executor: Option<String>,
     #[serde(default)]
     pub api_key: String,
     #[serde(default)]
@@ -648,6 +2164,16 @@ pub struct ProviderKeyEntry {
     /// Region identifier for geo-aware routing.
     #[serde(default)]
     pub region: Option<String>,
+    /// Credential group (priority tier) listed in `routing.credential-groups`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Free-form labels (e.g. `team:ml`, `env:prod`) copied into the request log
+    /// of every request this provider serves.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Daily/monthly usage caps for each credential of this provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<CredentialQuotaConfig>,
     /// Optional credential source (defaults to static API key from `api_key` field).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub credential_source: Option<crate::credential_source::CredentialSource>,
@@ -666,6 +2192,26 @@ pub struct ProviderKeyEntry {
     /// Vertex AI location (required when `vertex: true`, e.g. "us-central1").
     #[serde(default)]
     pub vertex_location: Option<String>,
+    /// Claude server tools (e.g. `web_search`, `computer_*`) this credential may receive.
+    /// Matches tool names or types, glob wildcards allowed. `None` allows all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_server_tools: Option<Vec<String>>,
+    /// `anthropic-beta` flags forwarded from clients to this credential, glob
+    /// wildcards allowed. `None` allows all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_betas: Option<Vec<String>>,
+    /// HMAC signature added to every request, for OpenAI-compatible gateways
+    /// that authenticate the payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing: Option<crate::signing::RequestSigningConfig>,
+    /// Expiring bearer token fetched and renewed in the background, in place of
+    /// `api-key` (e.g. a Vertex AI token from the GCE metadata server).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_source: Option<crate::token_provider::TokenSourceConfig>,
+    /// Reassemble JSON payloads (e.g. tool-call arguments) that this upstream
+    /// splits or concatenates across stream events. Off by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub json_repair: bool,
 }
 
 impl ProviderKeyEntry {
@@ -674,7 +2220,57 @@ impl ProviderKeyEntry {
     }
 
     pub fn validate_shape(&self) -> Result<(), String> {
+        if self
+            .executor
+            .as_deref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(format!(
+                "provider '{}' executor must not be empty",
+                self.name
+            ));
+        }
+        for model in &self.models {
+            if crate::glob::is_pattern(&model.id) && model.alias.is_some() {
+                return Err(format!(
+                    "provider '{}' model pattern '{}' cannot have an alias",
+                    self.name, model.id
+                ));
+            }
+        }
+        validate_tags(&self.tags).map_err(|e| format!("provider '{}' {e}", self.name))?;
         let upstream = self.upstream_kind();
+        if let Some(signing) = &self.signing {
+            if upstream != crate::provider::UpstreamKind::OpenAI {
+                return Err(format!(
+                    "provider '{}' signing is only supported for openai upstreams",
+                    self.name
+                ));
+            }
+            signing
+                .validate()
+                .map_err(|e| format!("provider '{}': {e}", self.name))?;
+        }
+        if let Some(source) = &self.token_source {
+            if !self.api_key.is_empty()
+                || !self.auth_profiles.is_empty()
+                || self.credential_source.is_some()
+            {
+                return Err(format!(
+                    "provider '{}' token-source replaces api-key, credential-source, and auth-profiles",
+                    self.name
+                ));
+            }
+            if upstream == crate::provider::UpstreamKind::Codex {
+                return Err(format!(
+                    "provider '{}' Codex upstream does not support token-source",
+                    self.name
+                ));
+            }
+            source
+                .validate()
+                .map_err(|e| format!("provider '{}': {e}", self.name))?;
+        }
         if upstream.wire_format() != self.format {
             return Err(format!(
                 "provider '{}' upstream '{}' requires '{}' wire format",
@@ -709,19 +2305,26 @@ impl ProviderKeyEntry {
             return self.auth_profiles.clone();
         }
 
-        if self.api_key.is_empty() {
+        if self.api_key.is_empty() && self.token_source.is_none() {
             return Vec::new();
         }
 
         vec![crate::auth_profile::AuthProfileEntry {
             id: self.name.clone(),
             mode: crate::auth_profile::AuthMode::ApiKey,
-            header: crate::auth_profile::AuthHeaderKind::Auto,
+            // Token sources mint OAuth access tokens, always sent as bearer tokens.
+            header: if self.token_source.is_some() {
+                crate::auth_profile::AuthHeaderKind::Bearer
+            } else {
+                crate::auth_profile::AuthHeaderKind::Auto
+            },
             secret: (!self.api_key.is_empty()).then(|| self.api_key.clone()),
             headers: self.headers.clone(),
             disabled: self.disabled,
             weight: self.weight.max(1),
             region: self.region.clone(),
+            group: self.group.clone(),
+            quota: self.quota.clone(),
             prefix: self.prefix.clone(),
             upstream_presentation: self.upstream_presentation.clone(),
             ..Default::default()
@@ -733,6 +2336,80 @@ fn default_weight() -> u32 {
     1
 }
 
+/// Tags are free-form, but must be non-blank and free of commas, which
+/// separate tags in query filters.
+pub fn validate_tags(tags: &[String]) -> Result<(), String> {
+    match tags
+        .iter()
+        .find(|tag| tag.trim().is_empty() || tag.contains(','))
+    {
+        Some(tag) => Err(format!(
+            "tag '{tag}' must be non-empty and contain no commas"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Whether `tags` holds every tag in the comma-separated `filter`.
+pub fn matches_tags(tags: &[String], filter: &str) -> bool {
+    filter
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .all(|wanted| tags.iter().any(|tag| tag == wanted))
+}
+
+/// Usage caps for one credential. A credential that reaches any cap is skipped
+/// until its period resets (UTC midnight, or the first of the month).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CredentialQuotaConfig {
+    pub daily: QuotaLimits,
+    pub monthly: QuotaLimits,
+}
+
+/// Caps for one quota period. `0` leaves a dimension uncapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct QuotaLimits {
+    pub requests: u64,
+    pub tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl QuotaLimits {
+    /// Whether `requests`, `tokens`, or `cost_usd` used so far reaches a cap.
+    pub fn reached(&self, requests: u64, tokens: u64, cost_usd: f64) -> bool {
+        (self.requests > 0 && requests >= self.requests)
+            || (self.tokens > 0 && tokens >= self.tokens)
+            || (self.cost_usd > 0.0 && cost_usd >= self.cost_usd)
+    }
+}
+
+/// Usage counted within one quota period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeriodUsage {
+    pub requests: u64,
+    pub tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Consumption against the caps of one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodReport {
+    pub used: PeriodUsage,
+    pub limits: QuotaLimits,
+    pub resets_at: chrono::DateTime<chrono::Utc>,
+    pub exhausted: bool,
+}
+
+/// Consumption against quota for one credential, as shown on the dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub daily: PeriodReport,
+    pub monthly: PeriodReport,
+}
+
 // ─── Config Watcher ────────────────────────────────────────────────────────
 
 pub struct ConfigWatcher {
@@ -741,11 +2418,12 @@ pub struct ConfigWatcher {
 
 impl ConfigWatcher {
     /// Start watching a config file. On changes (debounced 150ms, SHA256 dedup),
-    /// reload the config and atomically swap it in via ArcSwap.
+    /// load the config and hand it to `on_reload`. The watcher never swaps the
+    /// config in itself: `on_reload` applies it, or rejects it and keeps the
+    /// current one.
     pub fn start(
         path: String,
-        config: Arc<ArcSwap<Config>>,
-        on_reload: impl Fn(&Config) + Send + Sync + 'static,
+        on_reload: impl Fn(Config) + Send + Sync + 'static,
     ) -> Result<Self, anyhow::Error> {
         let (tx, mut rx) = tokio::sync::mpsc::channel::<()>(16);
 
@@ -783,12 +2461,10 @@ impl ConfigWatcher {
                                 }
                                 last_hash = Some(hash);
 
-                                match Config::load_from_str(&contents) {
-                                    Ok(new_cfg) => {
-                                        tracing::info!("Configuration reloaded successfully");
-                                        on_reload(&new_cfg);
-                                        config.store(Arc::new(new_cfg));
-                                    }
+                                let loaded = crate::managed_store::read_effective_yaml(&path_clone)
+                                    .and_then(|effective| Config::load_from_str(&effective));
+                                match loaded {
+                                    Ok(new_cfg) => on_reload(new_cfg),
                                     Err(e) => {
                                         tracing::error!("Config reload failed: {e}");
                                     }
@@ -809,6 +2485,92 @@ impl ConfigWatcher {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_reload_delta_reports_only_changed_sections() {
+        let base = Config::default();
+        assert!(base.reload_delta(&base.clone()).is_empty());
+
+        let mut next = base.clone();
+        next.rate_limit.enabled = !next.rate_limit.enabled;
+        next.providers
+            .push(make_test_entry("openai", "https://api.openai.com"));
+        let delta = base.reload_delta(&next);
+        assert_eq!(delta.touched(), vec!["credentials", "rate_limits"]);
+
+        let mut next = base.clone();
+        next.request_timeout += 1;
+        next.debug = !next.debug;
+        assert_eq!(base.reload_delta(&next).touched(), vec!["network", "other"]);
+
+        let next = Config::load_from_str(
+            "translation-rules:\n  - models: [\"o3*\"]\n    drop-fields: [temperature]\n",
+        )
+        .unwrap();
+        assert_eq!(next.translation_rules[0].drop_fields, vec!["temperature"]);
+        assert_eq!(base.reload_delta(&next).touched(), vec!["translation"]);
+    }
+
+    #[test]
+    fn test_streaming_limits_match_first_and_validate() {
+        let cfg = Config::load_from_str(
+            "streaming:\n  limits:\n    - models: [\"o1*\"]\n      max-bytes: 100\n    - models: [\"*\"]\n      max-duration-secs: 60\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.streaming.limit_for("o1-mini").unwrap().max_bytes, 100);
+        assert_eq!(
+            cfg.streaming.limit_for("gpt-4o").unwrap().max_duration_secs,
+            60
+        );
+
+        let err =
+            Config::load_from_str("streaming:\n  limits:\n    - models: [\"*\"]\n").unwrap_err();
+        assert!(err.to_string().contains("streaming.limits[0]"), "{err}");
+    }
+
+    #[test]
+    fn test_streaming_coalesce_matches_path_and_model() {
+        let cfg = Config::load_from_str(
+            "streaming:\n  coalesce:\n    - paths: [\"/v1/chat/completions\"]\n      models: [\"gpt-4o*\"]\n      max-delay-ms: 30\n      max-bytes: 512\n    - paths: [\"/api/provider/*\"]\n      max-delay-ms: 50\n",
+        )
+        .unwrap();
+        let streaming = &cfg.streaming;
+        let chat = streaming
+            .coalesce_for("/v1/chat/completions", "gpt-4o-mini")
+            .unwrap();
+        assert_eq!((chat.max_delay_ms, chat.max_bytes), (30, 512));
+        assert!(
+            streaming
+                .coalesce_for("/v1/chat/completions", "claude-sonnet-4")
+                .is_none()
+        );
+        assert_eq!(
+            streaming
+                .coalesce_for("/api/provider/openai/v1/chat/completions", "any")
+                .unwrap()
+                .max_delay_ms,
+            50
+        );
+        assert!(streaming.coalesce_for("/v1/messages", "gpt-4o").is_none());
+
+        let err =
+            Config::load_from_str("streaming:\n  coalesce:\n    - max-bytes: 512\n").unwrap_err();
+        assert!(err.to_string().contains("streaming.coalesce[0]"), "{err}");
+    }
+
+    #[test]
+    fn test_streaming_buffer_defaults_off() {
+        let cfg = Config::default();
+        assert_eq!(cfg.streaming.buffer.high_water_mark_bytes, 0);
+        assert_eq!(cfg.streaming.buffer.on_full, SlowClientAction::Backpressure);
+
+        let cfg = Config::load_from_str(
+            "streaming:\n  buffer:\n    high-water-mark-bytes: 65536\n    on-full: disconnect\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.streaming.buffer.high_water_mark_bytes, 65536);
+        assert_eq!(cfg.streaming.buffer.on_full, SlowClientAction::Disconnect);
+    }
+
     #[test]
     fn test_default_config() {
         let cfg = Config::default();
@@ -827,15 +2589,18 @@ mod tests {
         assert_eq!(cfg.retry.cooldown_5xx_secs, 15);
         assert_eq!(cfg.retry.cooldown_network_secs, 10);
         assert!(!cfg.cache.enabled);
+        assert!(!cfg.trace_propagation.enabled);
         assert!(!cfg.log_store.file_audit.enabled);
         assert!(cfg.circuit_breaker.enabled);
     }
 
     fn make_test_entry(name: &str, api_key: &str) -> ProviderKeyEntry {
         ProviderKeyEntry {
+            id: None,
             name: name.to_string(),
             format: crate::provider::Format::OpenAI,
             upstream: None,
+            executor: None,
             api_key: api_key.into(),
             base_url: None,
             proxy_url: None,
@@ -849,11 +2614,19 @@ mod tests {
             wire_api: crate::provider::WireApi::default(),
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             credential_source: None,
+            tags: Vec::new(),
+            token_source: None,
             auth_profiles: vec![],
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
@@ -956,6 +2729,161 @@ rate-limit:
         assert_eq!(config.rate_limit.per_key_cost_per_day_usd, 10.0);
     }
 
+    #[test]
+    fn test_concurrency_config() {
+        let config = Config::default();
+        assert_eq!(config.concurrency.max_requests, 0);
+        assert_eq!(config.concurrency.retry_after_secs, 1);
+
+        let yaml = "concurrency:\n  max-requests: 512\n  max-streams: 128\n";
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        assert_eq!(config.concurrency.max_requests, 512);
+        assert_eq!(config.concurrency.max_streams, 128);
+        assert_eq!(config.concurrency.retry_after_secs, 1);
+    }
+
+    #[test]
+    fn test_provider_signing_resolves_secret_and_requires_openai_upstream() {
+        // SAFETY: test-only variable name, not read by other tests
+        unsafe {
+            std::env::set_var("_TEST_SIGNING_SECRET", "resolved-signing-key");
+        }
+        let config = Config::load_from_str(
+            "providers:\n  - name: gateway\n    format: openai\n    api-key: sk-test\n    signing:\n      secret: env://_TEST_SIGNING_SECRET\n",
+        )
+        .unwrap();
+        let signing = config.providers[0].signing.as_ref().unwrap();
+        assert_eq!(signing.secret, "resolved-signing-key");
+        assert_eq!(signing.header, "x-signature");
+
+        let err = Config::load_from_str(
+            "providers:\n  - name: claude\n    format: claude\n    api-key: sk-test\n    signing:\n      secret: key\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("signing"), "{err}");
+    }
+
+    #[test]
+    fn test_token_source_provider_gets_bearer_profile_without_api_key() {
+        let config = Config::load_from_str(
+            "providers:\n  - name: vertex\n    format: gemini\n    vertex: true\n    token-source:\n      type: gcp-metadata\n      refresh-before-secs: 600\n",
+        )
+        .unwrap();
+        let entry = &config.providers[0];
+        assert_eq!(
+            entry.token_source.as_ref().unwrap().refresh_before_secs,
+            600
+        );
+        let profiles = entry.expanded_auth_profiles();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, "vertex");
+        assert_eq!(
+            profiles[0].header,
+            crate::auth_profile::AuthHeaderKind::Bearer
+        );
+
+        let err = Config::load_from_str(
+            "providers:\n  - name: vertex\n    format: gemini\n    api-key: k\n    token-source:\n      type: gcp-metadata\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("token-source"), "{err}");
+    }
+
+    #[test]
+    fn test_provider_and_auth_key_tags() {
+        let config = Config::load_from_str(
+            "providers:\n  - name: openai\n    format: openai\n    api-key: sk\n    tags: [\"team:ml\", \"env:prod\"]\nauth-keys:\n  - key: k\n    tags: [\"team:ml\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.providers[0].tags, ["team:ml", "env:prod"]);
+        assert_eq!(config.auth_keys[0].tags, ["team:ml"]);
+        assert!(matches_tags(&config.providers[0].tags, "env:prod,team:ml"));
+        assert!(!matches_tags(
+            &config.providers[0].tags,
+            "env:prod,team:search"
+        ));
+
+        let err = Config::load_from_str(
+            "providers:\n  - name: openai\n    format: openai\n    api-key: sk\n    tags: [\"a,b\"]\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("tag 'a,b'"), "{err}");
+        assert!(Config::load_from_str("auth-keys:\n  - key: k\n    tags: [\" \"]\n").is_err());
+    }
+
+    #[test]
+    fn test_upstream_openai_uses_the_api_spelling() {
+        let config = Config::load_from_str(
+            "providers:\n  - name: a\n    format: openai\n    upstream: openai\n    api-key: sk\n  - name: b\n    format: openai\n    upstream: open-a-i\n    api-key: sk\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.providers[0].upstream,
+            Some(crate::provider::UpstreamKind::OpenAI)
+        );
+        assert_eq!(config.providers[1].upstream, config.providers[0].upstream);
+        assert!(config.to_yaml().unwrap().contains("upstream: openai"));
+    }
+
+    #[test]
+    fn test_json_repair_is_written_only_when_on() {
+        let mut config =
+            Config::load_from_str("providers:\n  - name: a\n    format: openai\n    api-key: sk\n")
+                .unwrap();
+        assert!(!config.to_yaml().unwrap().contains("json-repair"));
+        config.providers[0].json_repair = true;
+        assert!(config.to_yaml().unwrap().contains("json-repair: true"));
+    }
+
+    #[test]
+    fn test_dns_config() {
+        let config = Config::load_from_str(
+            "dns:\n  overrides:\n    api.example.com: [\"192.0.2.1\", \"2001:db8::1\"]\n  cache-ttl-secs: 30\n  serve-stale-secs: 600\n  ip-preference: ipv4-first\n",
+        )
+        .unwrap();
+        assert_eq!(config.dns.overrides["api.example.com"].len(), 2);
+        assert_eq!(config.dns.ip_preference, IpPreference::Ipv4First);
+        assert_eq!(config.network_settings().dns, config.dns);
+        assert_eq!(
+            Config::default().reload_delta(&config).touched(),
+            vec!["network"]
+        );
+
+        let err = Config::load_from_str("dns:\n  cache-ttl-secs: 60\n  serve-stale-secs: 30\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("serve-stale-secs"), "{err}");
+        assert!(Config::load_from_str("dns:\n  overrides:\n    api.example.com: []\n").is_err());
+    }
+
+    #[test]
+    fn test_fault_injection_config() {
+        let config = Config::default();
+        assert!(!config.fault_injection.applies_to("openai"));
+
+        let config = Config::load_from_str(
+            "fault-injection:\n  enabled: true\n  providers: [staging-openai]\n  error-rate: 0.1\n",
+        )
+        .unwrap();
+        assert_eq!(config.fault_injection.error_statuses, vec![429, 500]);
+        assert!(config.fault_injection.applies_to("staging-openai"));
+        assert!(!config.fault_injection.applies_to("openai"));
+        assert_eq!(
+            Config::default().reload_delta(&config).touched(),
+            vec!["network"]
+        );
+
+        let err = Config::load_from_str("fault-injection:\n  enabled: true\n  error-rate: 1.5\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("error-rate"), "{err}");
+        let err = Config::load_from_str(
+            "fault-injection:\n  enabled: true\n  error-rate: 0.5\n  error-statuses: [200]\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("error-statuses"), "{err}");
+        // Nothing is checked while disabled.
+        assert!(Config::load_from_str("fault-injection:\n  error-rate: 1.5\n").is_ok());
+    }
+
     #[test]
     fn test_routing_config_defaults_in_config() {
         let config = Config::default();
@@ -1116,6 +3044,276 @@ providers:
         );
     }
 
+    #[test]
+    fn test_provider_ids_assigned_once_and_resolvable() {
+        let mut config = Config {
+            providers: vec![make_test_entry("p1", "key1"), make_test_entry("p2", "key2")],
+            ..Config::default()
+        };
+        config.providers[1].id = Some("fixed-id".into());
+        config.assign_provider_ids();
+
+        let first_id = config.providers[0].id.clone().expect("id assigned");
+        assert_eq!(config.providers[1].id.as_deref(), Some("fixed-id"));
+        config.assign_provider_ids();
+        assert_eq!(config.providers[0].id.as_deref(), Some(first_id.as_str()));
+
+        assert_eq!(config.find_provider(&first_id).unwrap().name, "p1");
+        assert_eq!(config.find_provider("fixed-id").unwrap().name, "p2");
+        assert_eq!(config.find_provider("p2").unwrap().name, "p2");
+        assert!(config.find_provider("missing").is_none());
+
+        // Round-trip keeps IDs
+        let reloaded = Config::from_yaml_raw(&config.to_yaml().unwrap()).unwrap();
+        assert_eq!(reloaded.providers[0].id.as_deref(), Some(first_id.as_str()));
+
+        // Loading assigns IDs, the same ones on every load of the same file.
+        let yaml = "providers:\n  - name: p1\n    format: openai\n    api-key: sk-1\n";
+        let loaded = Config::load_from_str(yaml).unwrap();
+        assert_eq!(loaded.providers[0].id.as_deref(), Some(first_id.as_str()));
+        let raw = Config::from_yaml_raw(yaml).unwrap();
+        assert_eq!(raw.providers[0].id, loaded.providers[0].id);
+    }
+
+    #[test]
+    fn test_auth_key_ids_assigned_on_load_and_resolvable() {
+        let yaml = "auth-keys:\n  - key: sk-a\n  - key: sk-b\n    id: fixed\n";
+        let config = Config::load_from_str(yaml).unwrap();
+        let first_id = config.auth_keys[0].id.clone().expect("id assigned");
+        assert_eq!(config.auth_keys[1].id.as_deref(), Some("fixed"));
+        assert_eq!(config.auth_key_position(&first_id), Some(0));
+        assert_eq!(config.auth_key_position("fixed"), Some(1));
+        assert_eq!(config.auth_key_position("0"), None);
+
+        let again = Config::load_from_str(yaml).unwrap();
+        assert_eq!(again.auth_keys[0].id.as_deref(), Some(first_id.as_str()));
+    }
+
+    #[test]
+    fn test_duplicate_provider_ids_rejected() {
+        let mut config = Config {
+            providers: vec![make_test_entry("p1", "key1"), make_test_entry("p2", "key2")],
+            ..Config::default()
+        };
+        config.providers[0].id = Some("same".into());
+        config.providers[1].id = Some("same".into());
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("duplicate provider id"));
+    }
+
+    #[test]
+    fn test_shadow_rules_validated() {
+        let mut config = Config {
+            providers: vec![
+                make_test_entry("primary", "key1"),
+                make_test_entry("candidate", "key2"),
+            ],
+            shadow: vec![ShadowRule {
+                models: vec!["gpt-*".into()],
+                provider: "candidate".into(),
+                model: None,
+                percent: 10.0,
+            }],
+            ..Config::default()
+        };
+        assert!(Config::load_from_str(&config.to_yaml().unwrap()).is_ok());
+        assert!(config.shadow[0].matches("gpt-4o"));
+        assert!(!config.shadow[0].matches("claude-sonnet-4"));
+
+        config.shadow[0].provider = "missing".into();
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("unknown provider 'missing'"));
+
+        config.shadow[0].provider = "candidate".into();
+        config.shadow[0].percent = 0.0;
+        assert!(Config::load_from_str(&config.to_yaml().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_outbound_headers_validated() {
+        let mut config = Config {
+            providers: vec![make_test_entry("p1", "key1")],
+            ..Config::default()
+        };
+        config.outbound_headers.forward = vec!["X-Request-Id".into()];
+        config.outbound_headers.strip = vec!["user-agent".into()];
+        let loaded = Config::load_from_str(&config.to_yaml().unwrap()).unwrap();
+        assert_eq!(loaded.outbound_headers.forward, ["x-request-id"]);
+        assert!(loaded.outbound_headers.is_stripped("User-Agent"));
+
+        let rejected = |config: &Config, needle: &str| {
+            let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+            assert!(err.to_string().contains(needle), "{err}");
+        };
+        let mut bad = config.clone();
+        bad.outbound_headers.forward = vec!["authorization".into()];
+        rejected(&bad, "cannot forward protected header");
+        let mut bad = config.clone();
+        bad.outbound_headers.forward = vec!["user-agent".into()];
+        rejected(&bad, "both forwarded and stripped");
+        let mut bad = config.clone();
+        bad.outbound_headers.strip = vec!["bad header".into()];
+        rejected(&bad, "not a valid header name");
+
+        let mut bad = config.clone();
+        bad.providers[0]
+            .upstream_presentation
+            .custom_headers
+            .insert("x-note".into(), "a\r\nx-injected: 1".into());
+        rejected(
+            &bad,
+            "provider 'p1': header 'x-note' has a value with control characters",
+        );
+        let mut bad = config.clone();
+        bad.providers[0]
+            .upstream_presentation
+            .custom_headers
+            .insert("user-agent".into(), "custom".into());
+        rejected(&bad, "listed in outbound-headers strip");
+    }
+
+    #[test]
+    fn test_credential_groups_validated() {
+        use crate::routing::config::{CredentialGroup, CredentialStrategy};
+
+        let mut config = Config {
+            providers: vec![make_test_entry("p1", "key1")],
+            ..Config::default()
+        };
+        config.providers[0].group = Some("overflow".into());
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("group 'overflow' is not listed"));
+
+        config.routing.credential_groups = vec![
+            CredentialGroup::new("primary"),
+            CredentialGroup {
+                name: "overflow".into(),
+                strategy: Some(CredentialStrategy::FillFirst),
+            },
+        ];
+        let loaded = Config::load_from_str(&config.to_yaml().unwrap()).unwrap();
+        assert_eq!(loaded.routing.credential_tier(Some("overflow")), 1);
+        assert_eq!(loaded.routing.credential_tier(None), 2);
+        assert_eq!(
+            loaded.routing.credential_groups[1].strategy,
+            Some(CredentialStrategy::FillFirst)
+        );
+
+        config
+            .routing
+            .credential_groups
+            .push(CredentialGroup::new("primary"));
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("listed twice"), "{err}");
+    }
+
+    #[test]
+    fn test_cluster_peers_validated() {
+        let mut config = Config::default();
+        config.cluster.peers = vec![ClusterPeer {
+            name: "node-b".into(),
+            url: "http://10.0.0.2:8317".into(),
+            jwt_secret: None,
+        }];
+        assert!(Config::load_from_str(&config.to_yaml().unwrap()).is_ok());
+
+        config.cluster.peers[0].name = "local".into();
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(
+            err.to_string().contains("duplicate cluster node name"),
+            "{err}"
+        );
+
+        config.cluster.peers[0].name = "node-b".into();
+        config.cluster.peers[0].url = "10.0.0.2:8317".into();
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("http://"), "{err}");
+    }
+
+    #[test]
+    fn test_experiment_assignment_and_validation() {
+        let experiment = Experiment {
+            name: "sonnet-vs-gpt".into(),
+            model: "smart*".into(),
+            variant: "gpt-4.1".into(),
+            percent: 25.0,
+        };
+        assert!(experiment.matches("smart"));
+        assert_eq!(experiment.assign(0.1), ExperimentArm::Variant);
+        assert_eq!(experiment.assign(0.25), ExperimentArm::Control);
+
+        let config = Config {
+            experiments: vec![experiment.clone(), experiment],
+            ..Config::default()
+        };
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("duplicate experiment name"));
+    }
+
+    #[test]
+    fn test_preset_model_apply_and_validation() {
+        use crate::provider::Format;
+
+        let yaml = r#"
+preset-models:
+  - name: team-reviewer
+    model: claude-sonnet-4
+    system-prompt: "Review code tersely."
+    params:
+      temperature: 0.2
+      max_tokens: 2048
+"#;
+        let config = Config::load_from_str(yaml).unwrap();
+        let preset = &config.preset_models[0];
+        assert_eq!(preset.model, "claude-sonnet-4");
+
+        let mut openai = serde_json::json!({
+            "model": "team-reviewer",
+            "max_tokens": 100,
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        preset.apply(&mut openai, Format::OpenAI);
+        assert_eq!(openai["model"], "claude-sonnet-4");
+        assert_eq!(openai["temperature"], 0.2);
+        assert_eq!(openai["max_tokens"], 100);
+        assert_eq!(openai["messages"][0]["role"], "system");
+        assert_eq!(openai["messages"][0]["content"], "Review code tersely.");
+        assert_eq!(openai["messages"][1]["role"], "user");
+
+        let mut claude = serde_json::json!({"model": "team-reviewer", "system": "Be kind."});
+        preset.apply(&mut claude, Format::Claude);
+        assert_eq!(claude["system"], "Review code tersely.\n\nBe kind.");
+
+        let mut gemini = serde_json::json!({"contents": []});
+        preset.apply(&mut gemini, Format::Gemini);
+        assert_eq!(
+            gemini["systemInstruction"]["parts"][0]["text"],
+            "Review code tersely."
+        );
+        assert!(gemini.get("model").is_none());
+
+        let mut config = config;
+        config.preset_models[0]
+            .params
+            .insert("stream".into(), true.into());
+        let err = Config::load_from_str(&config.to_yaml().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("must not set 'stream'"), "{err}");
+    }
+
+    #[test]
+    fn test_model_pattern_alias_rejected() {
+        let mut entry = make_test_entry("patterns", "sk-test");
+        entry.models = vec![ModelMapping {
+            id: "gpt-4*".into(),
+            alias: None,
+        }];
+        assert!(entry.validate_shape().is_ok());
+
+        entry.models[0].alias = Some("gpt4".into());
+        let err = entry.validate_shape().unwrap_err();
+        assert!(err.contains("cannot have an alias"), "{err}");
+    }
+
     #[test]
     fn test_expanded_auth_profiles_legacy() {
         let entry = make_test_entry("legacy", "sk-legacy");
@@ -19,6 +19,8 @@ pub struct RequestContext {
     pub auth_key: Option<AuthKeyEntry>,
     /// Client region from X-Client-Region / CDN headers.
     pub client_region: Option<String>,
+    /// W3C trace context continued from the inbound `traceparent`, or freshly started.
+    pub trace: TraceContext,
 }
 
 impl RequestContext {
@@ -31,6 +33,7 @@ impl RequestContext {
             tenant_id: None,
             auth_key: None,
             client_region: None,
+            trace: TraceContext::new(),
         }
     }
 
@@ -39,3 +42,134 @@ impl RequestContext {
         self.start_time.elapsed().as_millis()
     }
 }
+
+/// W3C trace context (`traceparent`) for one request as it passes through the gateway.
+///
+/// `span_id` identifies the gateway's own hop; it is sent upstream and echoed back to
+/// the client so both sides can correlate with the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 32 lowercase hex chars shared by every hop of the trace.
+    pub trace_id: String,
+    /// 16 lowercase hex chars identifying the gateway span.
+    pub span_id: String,
+    /// Trace flags (bit 0 = sampled).
+    pub flags: u8,
+}
+
+impl TraceContext {
+    /// Start a new sampled trace.
+    pub fn new() -> Self {
+        Self {
+            trace_id: uuid::Uuid::new_v4().simple().to_string(),
+            span_id: new_span_id(),
+            flags: 0x01,
+        }
+    }
+
+    /// Continue the trace from an inbound `traceparent` header, or start a new one if
+    /// it is absent or malformed.
+    pub fn from_traceparent(header: Option<&str>) -> Self {
+        header
+            .and_then(Self::parse)
+            .map(|parent| Self {
+                trace_id: parent.trace_id,
+                span_id: new_span_id(),
+                flags: parent.flags,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parse a `traceparent` value: `{version}-{trace-id}-{parent-id}-{flags}`.
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+        // Version 00 has exactly four fields; later versions may append more.
+        if !is_lower_hex(version, 2)
+            || version == "ff"
+            || (version == "00" && parts.next().is_some())
+        {
+            return None;
+        }
+        if !is_lower_hex(trace_id, 32) || trace_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+        if !is_lower_hex(span_id, 16) || span_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+        if !is_lower_hex(flags, 2) {
+            return None;
+        }
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+        })
+    }
+
+    /// Render as a version-00 `traceparent` header value.
+    pub fn to_traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, self.flags)
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn new_span_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(16);
+    id
+}
+
+fn is_lower_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context_continues_valid_traceparent() {
+        let inbound = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let parsed = TraceContext::parse(inbound).unwrap();
+        assert_eq!(parsed.to_traceparent(), inbound);
+
+        let ctx = TraceContext::from_traceparent(Some(inbound));
+        assert_eq!(ctx.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_ne!(ctx.span_id, "00f067aa0ba902b7");
+        assert_eq!(ctx.flags, 0x01);
+        assert!(TraceContext::parse(&ctx.to_traceparent()).is_some());
+    }
+
+    #[test]
+    fn test_trace_context_rejects_malformed_traceparent() {
+        for bad in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(TraceContext::parse(bad).is_none(), "{bad}");
+        }
+        // Future versions may carry extra fields.
+        assert!(
+            TraceContext::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra")
+                .is_some()
+        );
+
+        let fresh = TraceContext::from_traceparent(Some("garbage"));
+        assert_eq!(fresh.trace_id.len(), 32);
+        assert_eq!(fresh.span_id.len(), 16);
+    }
+}
@@ -0,0 +1,188 @@
+//! Response bodies of the dashboard API that are shaped by the handlers
+//! rather than by a config or log type. The server serializes these and
+//! `prism-client` deserializes them, so both sides share one definition.
+
+use crate::auth_key::{BudgetConfig, KeyRateLimitConfig};
+use crate::auth_profile::{AuthHeaderKind, AuthMode};
+use crate::config::{CredentialQuotaConfig, ModelMapping, ModerationAction, QuotaReport};
+use crate::presentation::UpstreamPresentationConfig;
+use crate::provider::{Format, UpstreamKind, WireApi};
+use crate::request_log::UsageSummary;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// `POST /api/dashboard/auth/login` and `/auth/refresh`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginResponse {
+    pub authenticated: bool,
+    pub username: String,
+    pub expires_in: u64,
+}
+
+/// A bare `{ "message": ... }` acknowledgement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub message: String,
+}
+
+impl Message {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// `GET /api/dashboard/providers`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderList {
+    pub providers: Vec<ProviderSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSummary {
+    pub id: Option<String>,
+    pub name: String,
+    pub format: Format,
+    pub upstream: UpstreamKind,
+    pub api_key_masked: String,
+    pub base_url: Option<String>,
+    pub models: Vec<ModelMapping>,
+    pub disabled: bool,
+    pub tags: Vec<String>,
+    pub wire_api: WireApi,
+    pub upstream_presentation: UpstreamPresentationConfig,
+    pub auth_profiles: Vec<AuthProfileSummary>,
+    pub throughput: Option<serde_json::Value>,
+    pub attempts: Option<serde_json::Value>,
+}
+
+/// `GET /api/dashboard/providers/{name}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderDetail {
+    pub id: Option<String>,
+    pub name: String,
+    pub format: Format,
+    pub upstream: UpstreamKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executor: Option<String>,
+    pub api_key_masked: String,
+    pub base_url: Option<String>,
+    pub proxy_url: Option<String>,
+    pub prefix: Option<String>,
+    pub models: Vec<ModelMapping>,
+    pub excluded_models: Vec<String>,
+    pub headers: HashMap<String, String>,
+    pub disabled: bool,
+    pub wire_api: WireApi,
+    pub weight: u32,
+    pub region: Option<String>,
+    pub group: Option<String>,
+    pub tags: Vec<String>,
+    pub quota: Option<CredentialQuotaConfig>,
+    pub upstream_presentation: UpstreamPresentationConfig,
+    pub vertex: bool,
+    pub vertex_project: Option<String>,
+    pub vertex_location: Option<String>,
+    pub allowed_server_tools: Option<Vec<String>>,
+    pub allowed_betas: Option<Vec<String>>,
+    pub auth_profiles: Vec<AuthProfileSummary>,
+    /// Streamed output throughput (`streams`, `output_tokens`, `tokens_per_sec`), if any.
+    pub throughput: Option<serde_json::Value>,
+    /// Upstream attempt outcomes (`attempts`, `failures`, `retries`, `failure_rate`,
+    /// `retry_rate`), if any.
+    pub attempts: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthProfileSummary {
+    pub id: String,
+    pub qualified_name: String,
+    pub mode: AuthMode,
+    pub header: AuthHeaderKind,
+    pub secret_masked: Option<String>,
+    pub access_token_masked: Option<String>,
+    pub refresh_token_present: bool,
+    pub id_token_present: bool,
+    pub expires_at: Option<String>,
+    pub account_id: Option<String>,
+    pub email: Option<String>,
+    pub last_refresh: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub disabled: bool,
+    pub weight: u32,
+    pub region: Option<String>,
+    pub prefix: Option<String>,
+    pub upstream_presentation: UpstreamPresentationConfig,
+    /// Daily/monthly consumption against the effective quota.
+    pub quota: QuotaReport,
+}
+
+/// `POST /api/dashboard/providers`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderCreated {
+    pub message: String,
+    pub id: Option<String>,
+}
+
+/// `POST /api/dashboard/providers/{name}/disable` and `/enable`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderToggled {
+    pub name: String,
+    pub disabled: bool,
+    /// False when the provider was already in the requested state.
+    pub changed: bool,
+}
+
+/// `GET /api/dashboard/auth-keys`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthKeyList {
+    pub auth_keys: Vec<AuthKeySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthKeySummary {
+    /// Persistent id; used in auth-key paths.
+    pub id: String,
+    pub key_masked: String,
+    pub name: Option<String>,
+    pub tenant_id: Option<String>,
+    pub allowed_models: Vec<String>,
+    pub allowed_credentials: Vec<String>,
+    pub rate_limit: Option<KeyRateLimitConfig>,
+    pub budget: Option<BudgetConfig>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub metadata: HashMap<String, String>,
+    pub moderation: Option<ModerationAction>,
+    pub routing_hints: bool,
+    pub disabled: bool,
+    pub tags: Vec<String>,
+}
+
+/// `POST /api/dashboard/auth-keys`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthKeyCreated {
+    /// Id used in auth-key paths.
+    pub id: String,
+    /// The full key; the server does not return it again.
+    pub key: String,
+    pub message: String,
+}
+
+/// `POST /api/dashboard/auth-keys/{id}/disable` and `/enable`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthKeyToggled {
+    pub id: String,
+    pub disabled: bool,
+    /// False when the key was already in the requested state.
+    pub changed: bool,
+}
+
+/// `POST /api/dashboard/reports/{name}/send`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportDelivery {
+    pub name: String,
+    pub delivered: bool,
+    pub usage: UsageSummary,
+}
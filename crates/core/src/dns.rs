@@ -0,0 +1,246 @@
+//! DNS resolver for upstream clients, configured by the `dns` section.
+//!
+//! Answers come from static overrides first, then from a TTL cache, then from the
+//! system resolver. When a lookup fails, the last good answer is served for up
+//! to `serve-stale-secs`, so a flapping provider DNS does not turn into
+//! `Network` errors and credential cooldowns.
+
+use crate::config::{DnsConfig, IpPreference};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct CachedAnswer {
+    addrs: Vec<SocketAddr>,
+    resolved_at: Instant,
+}
+
+struct Inner {
+    config: DnsConfig,
+    cache: Mutex<HashMap<String, CachedAnswer>>,
+}
+
+/// Resolver shared by every pooled client; cloning shares the cache.
+#[derive(Clone)]
+pub struct DnsResolver {
+    inner: Arc<Inner>,
+}
+
+impl DnsResolver {
+    pub fn new(config: DnsConfig) -> Self {
+        let overrides = config
+            .overrides
+            .iter()
+            .map(|(host, addrs)| (host.to_ascii_lowercase(), addrs.clone()))
+            .collect();
+        Self {
+            inner: Arc::new(Inner {
+                config: DnsConfig {
+                    overrides,
+                    ..config
+                },
+                cache: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Resolve `host` to addresses with port 0, ordered by the configured preference.
+    pub async fn lookup(&self, host: &str) -> io::Result<Vec<SocketAddr>> {
+        self.lookup_with(host, |host| async move {
+            Ok(tokio::net::lookup_host((host.as_str(), 0)).await?.collect())
+        })
+        .await
+    }
+
+    async fn lookup_with<F, Fut>(&self, host: &str, resolve: F) -> io::Result<Vec<SocketAddr>>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = io::Result<Vec<SocketAddr>>>,
+    {
+        let config = &self.inner.config;
+        let host = host.to_ascii_lowercase();
+        if let Some(ips) = config.overrides.get(&host) {
+            let addrs = ips.iter().map(|ip| SocketAddr::new(*ip, 0)).collect();
+            return order(addrs, config.ip_preference, &host);
+        }
+
+        let cache_ttl = Duration::from_secs(config.cache_ttl_secs);
+        if let Some(addrs) = self.cached(&host, cache_ttl) {
+            return Ok(addrs);
+        }
+
+        match resolve(host.clone()).await {
+            Ok(addrs) => {
+                let addrs = order(addrs, config.ip_preference, &host)?;
+                if config.cache_ttl_secs > 0 || config.serve_stale_secs > 0 {
+                    self.store(&host, addrs.clone());
+                }
+                Ok(addrs)
+            }
+            Err(e) => {
+                let stale = Duration::from_secs(config.serve_stale_secs);
+                match self.cached(&host, stale) {
+                    Some(addrs) => {
+                        tracing::warn!(host, error = %e, "DNS lookup failed, serving stale answer");
+                        Ok(addrs)
+                    }
+                    None => Err(e),
+                }
+            }
+        }
+    }
+
+    /// A cached answer for `host` no older than `max_age`.
+    fn cached(&self, host: &str, max_age: Duration) -> Option<Vec<SocketAddr>> {
+        if max_age.is_zero() {
+            return None;
+        }
+        let cache = self.inner.cache.lock().ok()?;
+        let answer = cache.get(host)?;
+        (answer.resolved_at.elapsed() < max_age).then(|| answer.addrs.clone())
+    }
+
+    fn store(&self, host: &str, addrs: Vec<SocketAddr>) {
+        if let Ok(mut cache) = self.inner.cache.lock() {
+            cache.insert(
+                host.to_string(),
+                CachedAnswer {
+                    addrs,
+                    resolved_at: Instant::now(),
+                },
+            );
+        }
+    }
+}
+
+impl Resolve for DnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.lookup(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Order or filter `addrs` by address family. The connector tries the family of
+/// the first address first, so a stable partition is enough.
+fn order(
+    addrs: Vec<SocketAddr>,
+    preference: IpPreference,
+    host: &str,
+) -> io::Result<Vec<SocketAddr>> {
+    let (v4, v6): (Vec<_>, Vec<_>) = addrs.iter().partition(|a| a.is_ipv4());
+    let ordered = match preference {
+        IpPreference::System => addrs,
+        IpPreference::Ipv4First => v4.into_iter().chain(v6).collect(),
+        IpPreference::Ipv6First => v6.into_iter().chain(v4).collect(),
+        IpPreference::Ipv4Only => v4,
+        IpPreference::Ipv6Only => v6,
+    };
+    if ordered.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no usable address for {host} with ip-preference {preference:?}"),
+        ));
+    }
+    Ok(ordered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::IpAddr;
+
+    fn addr(ip: &str) -> SocketAddr {
+        SocketAddr::new(ip.parse().unwrap(), 0)
+    }
+
+    #[test]
+    fn test_order_by_preference() {
+        let addrs = vec![addr("2001:db8::1"), addr("192.0.2.1"), addr("2001:db8::2")];
+        assert_eq!(
+            order(addrs.clone(), IpPreference::Ipv4First, "h").unwrap(),
+            vec![addr("192.0.2.1"), addr("2001:db8::1"), addr("2001:db8::2")]
+        );
+        assert_eq!(
+            order(addrs.clone(), IpPreference::System, "h").unwrap(),
+            addrs
+        );
+        assert_eq!(
+            order(addrs.clone(), IpPreference::Ipv4Only, "h").unwrap(),
+            vec![addr("192.0.2.1")]
+        );
+        assert!(order(vec![addr("192.0.2.1")], IpPreference::Ipv6Only, "h").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_overrides_skip_dns_and_ignore_case() {
+        let ip: IpAddr = "192.0.2.7".parse().unwrap();
+        let resolver = DnsResolver::new(DnsConfig {
+            overrides: HashMap::from([("API.Example.com".to_string(), vec![ip])]),
+            ..Default::default()
+        });
+        let addrs = resolver
+            .lookup_with("api.example.com", |_| async {
+                panic!("overridden hosts must not be resolved")
+            })
+            .await
+            .unwrap();
+        assert_eq!(addrs, vec![SocketAddr::new(ip, 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_reuses_answer_within_ttl() {
+        let resolver = DnsResolver::new(DnsConfig {
+            cache_ttl_secs: 60,
+            ..Default::default()
+        });
+        let first = resolver
+            .lookup_with("h", |_| async { Ok(vec![addr("192.0.2.1")]) })
+            .await
+            .unwrap();
+        let second = resolver
+            .lookup_with("h", |_| async { panic!("cached answer must be reused") })
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_failed_lookup_serves_stale_answer() {
+        let failing = |_| async { Err(io::Error::other("SERVFAIL")) };
+        let resolver = DnsResolver::new(DnsConfig {
+            serve_stale_secs: 300,
+            ..Default::default()
+        });
+        resolver
+            .lookup_with("h", |_| async { Ok(vec![addr("192.0.2.1")]) })
+            .await
+            .unwrap();
+        assert_eq!(
+            resolver.lookup_with("h", failing).await.unwrap(),
+            vec![addr("192.0.2.1")]
+        );
+
+        let resolver = DnsResolver::new(DnsConfig::default());
+        resolver
+            .lookup_with("h", |_| async { Ok(vec![addr("192.0.2.1")]) })
+            .await
+            .unwrap();
+        assert!(resolver.lookup_with("h", failing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_system_lookup_resolves_localhost() {
+        let resolver = DnsResolver::new(DnsConfig {
+            ip_preference: IpPreference::Ipv4Only,
+            ..Default::default()
+        });
+        let addrs = resolver.lookup("localhost").await.unwrap();
+        assert!(addrs.iter().all(|a| a.ip().is_loopback() && a.is_ipv4()));
+    }
+}
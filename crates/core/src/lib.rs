@@ -7,22 +7,31 @@ pub mod config;
 pub mod context;
 pub mod cost;
 pub mod credential_source;
+pub mod dashboard_api;
+pub mod dns;
 pub mod error;
 pub mod file_audit;
 pub mod glob;
+pub mod managed_store;
 // Re-export lifecycle from dedicated crate for backward compatibility.
 pub use prism_lifecycle as lifecycle;
 pub mod memory_log_store;
 pub mod metrics;
+pub mod model_alias;
 pub mod payload;
 pub mod presentation;
 pub mod prometheus;
 pub mod provider;
 pub mod proxy;
 pub mod rate_limit;
+pub mod redact;
 pub mod request_log;
 pub mod request_record;
 pub mod routing;
 pub mod secret;
+pub mod signing;
 pub mod thinking_cache;
+pub mod token_provider;
 pub mod types;
+pub mod usage_rollup;
+pub mod yaml_edit;
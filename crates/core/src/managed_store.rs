@@ -0,0 +1,268 @@
+use crate::auth_key::AuthKeyEntry;
+use crate::config::{Config, ProviderKeyEntry};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Where dashboard-managed providers and auth keys live.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagedStoreBackend {
+    /// Providers and auth keys are read from and written back to the YAML file.
+    #[default]
+    Yaml,
+    /// Providers and auth keys live in a SQLite database; the YAML lists only seed it.
+    Sqlite,
+}
+
+/// Configuration for the dashboard managed-state store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ManagedStoreConfig {
+    pub backend: ManagedStoreBackend,
+    /// Database path. Relative paths are resolved against the config file directory.
+    pub path: String,
+}
+
+impl Default for ManagedStoreConfig {
+    fn default() -> Self {
+        Self {
+            backend: ManagedStoreBackend::Yaml,
+            path: "prism-state.db".to_string(),
+        }
+    }
+}
+
+/// SQLite-backed source of truth for providers and auth keys.
+///
+/// Entries are stored as JSON documents in their configured order. [`initialize`]
+/// creates the database at startup and copies the YAML lists into an unseeded one;
+/// after that the YAML lists are ignored.
+pub struct ManagedStore {
+    conn: Connection,
+}
+
+impl ManagedStore {
+    pub fn open(path: &Path) -> Result<Self, anyhow::Error> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             CREATE TABLE IF NOT EXISTS providers (
+                 position INTEGER PRIMARY KEY,
+                 name TEXT NOT NULL UNIQUE,
+                 entry TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS auth_keys (
+                 position INTEGER PRIMARY KEY,
+                 entry TEXT NOT NULL
+             );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Open an existing database without creating or migrating it; `None` when
+    /// there is no database file yet.
+    pub fn open_read_only(path: &Path) -> Result<Option<Self>, anyhow::Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        Ok(Some(Self { conn }))
+    }
+
+    /// Open the store configured by `config`, or `None` when the YAML backend is active.
+    pub fn open_configured(
+        config_path: &str,
+        config: &Config,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let store = &config.dashboard.managed_store;
+        if store.backend != ManagedStoreBackend::Sqlite {
+            return Ok(None);
+        }
+        Self::open(&resolve_path(config_path, &store.path)).map(Some)
+    }
+
+    pub fn is_seeded(&self) -> Result<bool, anyhow::Error> {
+        let has_meta: bool = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'meta')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_meta {
+            return Ok(false);
+        }
+        let seeded: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'seeded_at'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(seeded.is_some())
+    }
+
+    pub fn load(&self) -> Result<(Vec<ProviderKeyEntry>, Vec<AuthKeyEntry>), anyhow::Error> {
+        let providers = self.load_table("SELECT entry FROM providers ORDER BY position")?;
+        let auth_keys = self.load_table("SELECT entry FROM auth_keys ORDER BY position")?;
+        Ok((providers, auth_keys))
+    }
+
+    fn load_table<T: serde::de::DeserializeOwned>(
+        &self,
+        sql: &str,
+    ) -> Result<Vec<T>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|row| Ok(serde_json::from_str(&row?)?)).collect()
+    }
+
+    /// Replace all stored providers and auth keys in one transaction and mark the store seeded.
+    pub fn replace(
+        &mut self,
+        providers: &[ProviderKeyEntry],
+        auth_keys: &[AuthKeyEntry],
+    ) -> Result<(), anyhow::Error> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM providers", [])?;
+        tx.execute("DELETE FROM auth_keys", [])?;
+        for (position, entry) in providers.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO providers (position, name, entry) VALUES (?1, ?2, ?3)",
+                params![position as i64, entry.name, serde_json::to_string(entry)?],
+            )?;
+        }
+        for (position, entry) in auth_keys.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO auth_keys (position, entry) VALUES (?1, ?2)",
+                params![position as i64, serde_json::to_string(entry)?],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('seeded_at', ?1)",
+            params![chrono::Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Resolve a store path relative to the directory of the config file.
+pub fn resolve_path(config_path: &str, store_path: &str) -> PathBuf {
+    let store_path = Path::new(store_path);
+    if store_path.is_absolute() {
+        return store_path.to_path_buf();
+    }
+    Path::new(config_path)
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(store_path)
+}
+
+/// Create the store configured in `config_path` and seed it from the YAML lists
+/// if it has not been seeded yet. Called once at startup; a no-op with the YAML
+/// backend.
+pub fn initialize(config_path: &str) -> Result<(), anyhow::Error> {
+    let raw = Config::from_yaml_raw(&std::fs::read_to_string(config_path)?)?;
+    let Some(mut store) = ManagedStore::open_configured(config_path, &raw)? else {
+        return Ok(());
+    };
+    if !store.is_seeded()? {
+        store.replace(&raw.providers, &raw.auth_keys)?;
+        tracing::info!(
+            providers = raw.providers.len(),
+            auth_keys = raw.auth_keys.len(),
+            "Seeded managed store from YAML config"
+        );
+    }
+    Ok(())
+}
+
+/// Return the effective raw YAML for `config_path`.
+///
+/// With the YAML backend this is the file contents unchanged. With the SQLite backend the
+/// providers and auth keys are replaced by the store contents. Reading never creates or
+/// seeds the store: until [`initialize`] or a dashboard write has seeded it, the YAML
+/// lists are in effect.
+pub fn read_effective_yaml(config_path: &str) -> Result<String, anyhow::Error> {
+    let contents = std::fs::read_to_string(config_path)?;
+    let mut raw = Config::from_yaml_raw(&contents)?;
+    let store = &raw.dashboard.managed_store;
+    if store.backend != ManagedStoreBackend::Sqlite {
+        return Ok(contents);
+    }
+    let path = resolve_path(config_path, &store.path);
+    match ManagedStore::open_read_only(&path)? {
+        Some(store) if store.is_seeded()? => {
+            (raw.providers, raw.auth_keys) = store.load()?;
+            raw.to_yaml()
+        }
+        _ => Ok(contents),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(dir: &Path, body: &str) -> String {
+        let path = dir.join("config.yaml");
+        std::fs::write(&path, body).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_yaml_backend_returns_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "# comment\nport: 9000\n";
+        let path = write_config(dir.path(), body);
+        assert_eq!(read_effective_yaml(&path).unwrap(), body);
+        assert!(!dir.path().join("prism-state.db").exists());
+    }
+
+    #[test]
+    fn test_sqlite_backend_seeds_once_then_wins_over_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(
+            dir.path(),
+            "dashboard:\n  managed-store:\n    backend: sqlite\nauth-keys:\n  - key: sk-seed\n",
+        );
+        initialize(&path).unwrap();
+        let seeded = Config::from_yaml_raw(&read_effective_yaml(&path).unwrap()).unwrap();
+        assert_eq!(seeded.auth_keys[0].key, "sk-seed");
+
+        let mut store = ManagedStore::open(&dir.path().join("prism-state.db")).unwrap();
+        assert!(store.is_seeded().unwrap());
+        let mut keys = seeded.auth_keys.clone();
+        keys[0].key = "sk-from-store".to_string();
+        store.replace(&[], &keys).unwrap();
+
+        initialize(&path).unwrap();
+        let effective = Config::from_yaml_raw(&read_effective_yaml(&path).unwrap()).unwrap();
+        assert_eq!(effective.auth_keys.len(), 1);
+        assert_eq!(effective.auth_keys[0].key, "sk-from-store");
+    }
+
+    #[test]
+    fn test_read_does_not_create_or_seed_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let body =
+            "dashboard:\n  managed-store:\n    backend: sqlite\nauth-keys:\n  - key: sk-seed\n";
+        let path = write_config(dir.path(), body);
+        assert_eq!(read_effective_yaml(&path).unwrap(), body);
+        assert!(!dir.path().join("prism-state.db").exists());
+
+        ManagedStore::open(&dir.path().join("prism-state.db")).unwrap();
+        assert_eq!(read_effective_yaml(&path).unwrap(), body);
+        let store = ManagedStore::open(&dir.path().join("prism-state.db")).unwrap();
+        assert!(!store.is_seeded().unwrap());
+    }
+}
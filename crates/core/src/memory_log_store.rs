@@ -9,6 +9,7 @@ use tokio::sync::broadcast;
 use crate::file_audit::FileAuditWriter;
 use crate::request_log::*;
 use crate::request_record::{RequestRecord, TokenUsage};
+use crate::usage_rollup::UsageRollup;
 
 #[derive(Default)]
 struct TimeBucket {
@@ -27,6 +28,9 @@ struct ModelAccum {
     cost: f64,
 }
 
+/// Example request IDs kept per error group.
+const ERROR_GROUP_EXAMPLES: usize = 5;
+
 /// In-memory ring buffer implementation of [`LogStore`].
 pub struct InMemoryLogStore {
     entries: RwLock<VecDeque<RequestRecord>>,
@@ -36,6 +40,8 @@ pub struct InMemoryLogStore {
     /// Monotonic counter incremented on each `push` so pagination
     /// clients can detect stale snapshots across requests.
     version: AtomicU64,
+    /// Hourly usage of every pushed record, also of those the ring has dropped.
+    usage: UsageRollup,
 }
 
 fn field_contains(field: Option<&str>, needle: &str) -> bool {
@@ -51,6 +57,7 @@ impl InMemoryLogStore {
             tx,
             file_writer,
             version: AtomicU64::new(0),
+            usage: UsageRollup::new(),
         }
     }
 
@@ -103,6 +110,11 @@ impl InMemoryLogStore {
         {
             return false;
         }
+        if let Some(ref tags) = q.tags
+            && !crate::config::matches_tags(&e.tags, tags)
+        {
+            return false;
+        }
         let ts = e.timestamp.timestamp_millis();
         if let Some(from) = q.from
             && ts < from
@@ -178,6 +190,7 @@ impl LogStore for InMemoryLogStore {
         if let Some(ref writer) = self.file_writer {
             writer.write(&entry).await;
         }
+        self.usage.record(&entry);
 
         if let Ok(mut entries) = self.entries.write() {
             if entries.len() >= self.capacity {
@@ -295,6 +308,7 @@ impl LogStore for InMemoryLogStore {
             to: q.to,
             provider: q.provider.clone(),
             model: q.model.clone(),
+            tags: q.tags.clone(),
             ..Default::default()
         };
 
@@ -405,11 +419,7 @@ impl LogStore for InMemoryLogStore {
                     timestamp: dt.to_rfc3339(),
                     requests: b.requests,
                     errors: b.errors,
-                    avg_latency_ms: if b.requests > 0 {
-                        b.latency_sum / b.requests
-                    } else {
-                        0
-                    },
+                    avg_latency_ms: b.latency_sum.checked_div(b.requests).unwrap_or(0),
                     tokens: b.tokens,
                     cost: b.cost,
                 }
@@ -422,16 +432,12 @@ impl LogStore for InMemoryLogStore {
             .map(|(m, a)| ModelStats {
                 model: m.to_string(),
                 requests: a.requests,
-                avg_latency_ms: if a.requests > 0 {
-                    a.latency_sum / a.requests
-                } else {
-                    0
-                },
+                avg_latency_ms: a.latency_sum.checked_div(a.requests).unwrap_or(0),
                 total_tokens: a.tokens,
                 total_cost: a.cost,
             })
             .collect();
-        top_models.sort_by(|a, b| b.requests.cmp(&a.requests));
+        top_models.sort_by_key(|m| std::cmp::Reverse(m.requests));
         top_models.truncate(10);
 
         // Build top errors
@@ -443,7 +449,7 @@ impl LogStore for InMemoryLogStore {
                 last_seen: last.to_rfc3339(),
             })
             .collect();
-        top_errors.sort_by(|a, b| b.count.cmp(&a.count));
+        top_errors.sort_by_key(|e| std::cmp::Reverse(e.count));
         top_errors.truncate(10);
 
         // Build provider distribution
@@ -460,7 +466,7 @@ impl LogStore for InMemoryLogStore {
                 },
             })
             .collect();
-        provider_distribution.sort_by(|a, b| b.requests.cmp(&a.requests));
+        provider_distribution.sort_by_key(|p| std::cmp::Reverse(p.requests));
 
         // All &str borrows from entries have been consumed; release the read lock.
         drop(entries);
@@ -482,6 +488,75 @@ impl LogStore for InMemoryLogStore {
         }
     }
 
+    async fn error_groups(&self, q: &ErrorGroupQuery) -> ErrorGroups {
+        let lq = LogQuery {
+            from: q.from,
+            to: q.to,
+            provider: q.provider.clone(),
+            model: q.model.clone(),
+            tags: q.tags.clone(),
+            ..Default::default()
+        };
+        let limit = q.limit.unwrap_or(50).clamp(1, 500);
+
+        type GroupKey<'a> = (Option<&'a str>, Option<&'a str>, Option<&'a str>, u16);
+        let entries = self.entries.read().unwrap();
+        let mut total_errors = 0usize;
+        let mut groups: HashMap<GroupKey<'_>, ErrorGroup> = HashMap::new();
+
+        // Newest first, so the first entry into a group is its latest occurrence.
+        for e in entries
+            .iter()
+            .rev()
+            .filter(|e| (e.status >= 400 || e.error.is_some()) && Self::matches(e, &lq, None))
+        {
+            total_errors += 1;
+            let key = (
+                e.error_type.as_deref(),
+                e.provider.as_deref(),
+                e.model.as_deref(),
+                e.status,
+            );
+            let group = groups.entry(key).or_insert_with(|| ErrorGroup {
+                error_type: e.error_type.clone(),
+                provider: e.provider.clone(),
+                model: e.model.clone(),
+                status: e.status,
+                count: 0,
+                last_seen: e.timestamp.to_rfc3339(),
+                example_request_ids: Vec::new(),
+            });
+            group.count += 1;
+            if group.example_request_ids.len() < ERROR_GROUP_EXAMPLES {
+                group.example_request_ids.push(e.request_id.clone());
+            }
+        }
+        let mut groups: Vec<ErrorGroup> = groups.into_values().collect();
+        drop(entries);
+
+        // RFC 3339 timestamps in UTC sort lexicographically.
+        groups.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+        });
+        groups.truncate(limit);
+
+        ErrorGroups {
+            total_errors,
+            groups,
+        }
+    }
+
+    async fn cost_by_tag(&self, q: &TagCostQuery) -> TagCostReport {
+        self.usage
+            .tag_costs(q.from, q.to, q.prefix.as_deref().unwrap_or(""))
+    }
+
+    async fn usage_summary(&self, from: i64, to: i64, top: usize) -> UsageSummary {
+        self.usage.summary(from, to, top)
+    }
+
     async fn filter_options(&self) -> FilterOptions {
         // Use &str sets to avoid cloning every string; only allocate at the end
         let entries = self.entries.read().unwrap();
@@ -489,6 +564,7 @@ impl LogStore for InMemoryLogStore {
         let mut models: HashSet<&str> = HashSet::new();
         let mut error_types: HashSet<&str> = HashSet::new();
         let mut tenant_ids: HashSet<&str> = HashSet::new();
+        let mut tags: HashSet<&str> = HashSet::new();
 
         for e in entries.iter() {
             if let Some(ref p) = e.provider {
@@ -503,6 +579,7 @@ impl LogStore for InMemoryLogStore {
             if let Some(ref t) = e.tenant_id {
                 tenant_ids.insert(t.as_str());
             }
+            tags.extend(e.tags.iter().map(String::as_str));
         }
 
         let mut providers: Vec<String> = providers.into_iter().map(String::from).collect();
@@ -513,12 +590,15 @@ impl LogStore for InMemoryLogStore {
         error_types.sort();
         let mut tenant_ids: Vec<String> = tenant_ids.into_iter().map(String::from).collect();
         tenant_ids.sort();
+        let mut tags: Vec<String> = tags.into_iter().map(String::from).collect();
+        tags.sort();
 
         FilterOptions {
             providers,
             models,
             error_types,
             tenant_ids,
+            tags,
         }
     }
 
@@ -534,6 +614,46 @@ impl LogStore for InMemoryLogStore {
             entry.cost = cost;
         }
     }
+
+    fn buffer_usage(&self) -> Option<BufferUsage> {
+        let entries = self.entries.read().ok()?;
+        Some(BufferUsage {
+            entries: entries.len(),
+            capacity: self.capacity,
+            approx_bytes: entries.iter().map(approx_record_bytes).sum(),
+        })
+    }
+}
+
+/// Size of a record and the strings it owns.
+fn approx_record_bytes(record: &RequestRecord) -> u64 {
+    let owned = [
+        Some(&record.request_id),
+        Some(&record.method),
+        Some(&record.path),
+        record.trace_id.as_ref(),
+        record.requested_model.as_ref(),
+        record.request_body.as_ref(),
+        record.upstream_request_body.as_ref(),
+        record.provider.as_ref(),
+        record.model.as_ref(),
+        record.credential_name.as_ref(),
+        record.response_body.as_ref(),
+        record.stream_content_preview.as_ref(),
+        record.error.as_ref(),
+        record.error_type.as_ref(),
+        record.api_key_id.as_ref(),
+        record.tenant_id.as_ref(),
+        record.client_ip.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(&record.tags)
+    .map(String::capacity)
+    .sum::<usize>();
+    let attempts =
+        record.attempts.capacity() * std::mem::size_of::<crate::request_record::AttemptSummary>();
+    (std::mem::size_of::<RequestRecord>() + owned + attempts) as u64
 }
 
 #[cfg(test)]
@@ -544,6 +664,7 @@ mod tests {
     fn make_entry(status: u16, provider: &str, model: &str) -> RequestRecord {
         RequestRecord {
             request_id: uuid::Uuid::new_v4().to_string(),
+            trace_id: None,
             timestamp: Utc::now(),
             method: "POST".to_string(),
             path: "/v1/chat/completions".to_string(),
@@ -581,7 +702,13 @@ mod tests {
             tenant_id: None,
             client_ip: None,
             client_region: None,
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         }
     }
 
@@ -606,6 +733,10 @@ mod tests {
         }
         let page = store.query(&LogQuery::default()).await;
         assert_eq!(page.total, 5);
+
+        let usage = store.buffer_usage().unwrap();
+        assert_eq!((usage.entries, usage.capacity), (5, 5));
+        assert!(usage.approx_bytes >= 5 * std::mem::size_of::<RequestRecord>() as u64);
     }
 
     #[tokio::test]
@@ -635,6 +766,125 @@ mod tests {
         assert_eq!(page.total, 2);
     }
 
+    #[tokio::test]
+    async fn test_filter_by_tags() {
+        let store = InMemoryLogStore::new(100, None);
+        let tagged = |tags: &[&str]| {
+            let mut entry = make_entry(200, "openai", "gpt-4");
+            entry.tags = tags.iter().map(|t| t.to_string()).collect();
+            entry
+        };
+        store.push(tagged(&["team:ml", "env:prod"])).await;
+        store.push(tagged(&["team:ml", "env:dev"])).await;
+        store.push(tagged(&[])).await;
+
+        let query = |tags: &str| LogQuery {
+            tags: Some(tags.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.query(&query("team:ml")).await.total, 2);
+        assert_eq!(store.query(&query("team:ml,env:prod")).await.total, 1);
+        assert_eq!(store.query(&query("team:search")).await.total, 0);
+
+        let stats = store
+            .stats(&StatsQuery {
+                tags: Some("env:dev".to_string()),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(stats.total_entries, 1);
+        assert_eq!(
+            store.filter_options().await.tags,
+            ["env:dev", "env:prod", "team:ml"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_by_tag() {
+        // Records the ring buffer has dropped still count.
+        let store = InMemoryLogStore::new(2, None);
+        for tags in [
+            &["team:ml", "env:prod"][..],
+            &["team:ml"],
+            &["env:dev"],
+            &[],
+        ] {
+            let mut entry = make_entry(200, "openai", "gpt-4");
+            entry.tags = tags.iter().map(|t| t.to_string()).collect();
+            store.push(entry).await;
+        }
+
+        let report = store.cost_by_tag(&TagCostQuery::default()).await;
+        assert_eq!(report.total_requests, 4);
+        assert_eq!(report.total_tokens, 120);
+        assert!(report.since.is_some());
+        let tags: Vec<(&str, u64)> = report
+            .tags
+            .iter()
+            .map(|t| (t.tag.as_str(), t.requests))
+            .collect();
+        assert_eq!(tags, [("team:ml", 2), ("env:dev", 1), ("env:prod", 1)]);
+        assert_eq!(report.tags[0].input_tokens, 20);
+        assert!((report.tags[0].cost - 0.002).abs() < 1e-9);
+        assert_eq!(report.untagged.requests, 1);
+
+        let report = store
+            .cost_by_tag(&TagCostQuery {
+                prefix: Some("team:".to_string()),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(report.tags.len(), 1);
+        assert_eq!(report.untagged.requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_usage_summary_breakdowns() {
+        let store = InMemoryLogStore::new(100, None);
+        for (model, key) in [("gpt-4", "sk-a"), ("gpt-4", "sk-b"), ("claude-3", "sk-a")] {
+            let mut entry = make_entry(200, "openai", model);
+            entry.api_key_id = Some(key.to_string());
+            store.push(entry).await;
+        }
+        store.push(make_entry(500, "openai", "gpt-4")).await;
+
+        let now = Utc::now().timestamp_millis();
+        let hour = now - now.rem_euclid(3_600_000);
+        let summary = store
+            .usage_summary(hour - 3_600_000, hour + 3_600_000, 2)
+            .await;
+        assert_eq!(summary.requests, 4);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.input_tokens, 40);
+        assert_eq!(summary.by_model[0].name, "gpt-4");
+        assert_eq!(summary.by_model[0].requests, 3);
+        assert_eq!(summary.by_model.len(), 2);
+        let keys: Vec<&str> = summary.by_api_key.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(keys, ["sk-a", "-"]);
+
+        let summary = store
+            .usage_summary(hour + 3_600_000, hour + 7_200_000, 2)
+            .await;
+        assert_eq!(summary.requests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_usage_summary_counts_records_evicted_from_the_ring() {
+        let store = InMemoryLogStore::new(2, None);
+        for _ in 0..5 {
+            store.push(make_entry(200, "openai", "gpt-4")).await;
+        }
+        assert_eq!(store.entries.read().unwrap().len(), 2);
+
+        let now = Utc::now().timestamp_millis();
+        let hour = now - now.rem_euclid(3_600_000);
+        let summary = store
+            .usage_summary(hour - 3_600_000, hour + 3_600_000, 10)
+            .await;
+        assert_eq!(summary.requests, 5);
+        assert_eq!(summary.by_model[0].requests, 5);
+    }
+
     #[tokio::test]
     async fn test_filter_by_status() {
         let store = InMemoryLogStore::new(100, None);
@@ -692,6 +942,47 @@ mod tests {
         assert!(!stats.top_models.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_error_groups() {
+        let store = InMemoryLogStore::new(100, None);
+        store.push(make_entry(200, "openai", "gpt-4")).await;
+        store.push(make_entry(500, "openai", "gpt-4")).await;
+        let latest = make_entry(500, "openai", "gpt-4");
+        let latest_id = latest.request_id.clone();
+        store.push(latest).await;
+        store.push(make_entry(429, "claude", "claude-3")).await;
+        for _ in 0..7 {
+            store.push(make_entry(500, "claude", "claude-3")).await;
+        }
+
+        let groups = store.error_groups(&ErrorGroupQuery::default()).await;
+        assert_eq!(groups.total_errors, 10);
+        assert_eq!(groups.groups.len(), 3);
+
+        let top = &groups.groups[0];
+        assert_eq!(top.provider.as_deref(), Some("claude"));
+        assert_eq!(top.status, 500);
+        assert_eq!(top.count, 7);
+        assert_eq!(top.example_request_ids.len(), ERROR_GROUP_EXAMPLES);
+
+        let openai = &groups.groups[1];
+        assert_eq!(openai.error_type.as_deref(), Some("upstream_5xx"));
+        assert_eq!(openai.count, 2);
+        assert_eq!(openai.example_request_ids[0], latest_id);
+
+        assert_eq!(groups.groups[2].error_type.as_deref(), Some("rate_limited"));
+
+        let filtered = store
+            .error_groups(&ErrorGroupQuery {
+                provider: Some("openai".to_string()),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(filtered.total_errors, 2);
+        assert_eq!(filtered.groups.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_filter_options() {
         let store = InMemoryLogStore::new(100, None);
@@ -2,12 +2,221 @@ use std::collections::HashMap;
 use std::sync::Mutex;
 use std::sync::RwLock;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// Upper bounds for request body size buckets (bytes): 1KB .. 16MB.
+pub const REQUEST_BYTES_BOUNDS: &[u64] = &[
+    1024,
+    4 * 1024,
+    16 * 1024,
+    64 * 1024,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+    16 * 1024 * 1024,
+];
+
+/// Upper bounds for per-request token count buckets.
+pub const TOKEN_BOUNDS: &[u64] = &[100, 500, 1_000, 4_000, 16_000, 64_000, 128_000];
+
+/// Upper bounds for per-model/provider latency buckets (ms), matching the global histogram.
+pub const LATENCY_BOUNDS_MS: &[u64] = &[100, 500, 1_000, 5_000, 30_000];
+
+/// Upper bounds for dispatch phase duration buckets (µs): 100µs .. 10s.
+pub const PHASE_BOUNDS_US: &[u64] = &[100, 1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+
+/// Dispatch phases timed separately so gateway overhead can be told apart from upstream time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchPhase {
+    /// Route planning and credential selection.
+    CredentialPick,
+    /// Request translation and payload rewriting before the upstream call.
+    Translate,
+    /// Upstream call until response headers (the full response for non-stream requests).
+    UpstreamConnect,
+    /// Upstream call until the first stream chunk.
+    FirstByte,
+    /// Translating one stream chunk into the client's format.
+    ChunkTranslate,
+}
+
+impl DispatchPhase {
+    pub const ALL: [Self; 5] = [
+        Self::CredentialPick,
+        Self::Translate,
+        Self::UpstreamConnect,
+        Self::FirstByte,
+        Self::ChunkTranslate,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CredentialPick => "credential_pick",
+            Self::Translate => "translate",
+            Self::UpstreamConnect => "upstream_connect",
+            Self::FirstByte => "first_byte",
+            Self::ChunkTranslate => "chunk_translate",
+        }
+    }
+}
+
+/// Maximum distinct models (and providers) reported with their own latency histogram.
+/// The highest-volume series are reported; the rest share the [`OTHER_SERIES`]
+/// histogram to bound cardinality.
+pub const MAX_LATENCY_SERIES: usize = 20;
+
+/// Latency series held in memory. When a new key arrives at the limit, the
+/// lowest-volume series is folded into [`OTHER_SERIES`] to make room, so a key
+/// that becomes busy later still earns its own series.
+const MAX_TRACKED_LATENCY_SERIES: usize = MAX_LATENCY_SERIES * 4;
+
+/// Series name for observations beyond [`MAX_LATENCY_SERIES`].
+pub const OTHER_SERIES: &str = "other";
+
+/// Fixed-bucket histogram over `u64` observations with an implicit overflow bucket.
+pub struct Histogram {
+    bounds: &'static [u64],
+    /// One counter per bound plus a final overflow bucket (non-cumulative).
+    counts: Vec<AtomicU64>,
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [u64]) -> Self {
+        Self {
+            bounds,
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, value: u64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|&bound| value <= bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn bounds(&self) -> &'static [u64] {
+        self.bounds
+    }
+
+    /// Non-cumulative bucket counts; the last entry is the overflow bucket.
+    pub fn bucket_values(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.bucket_values().iter().sum()
+    }
+
+    pub fn sum(&self) -> u64 {
+        self.sum.load(Ordering::Relaxed)
+    }
+
+    /// Add every observation of `other` to this histogram.
+    fn absorb(&self, other: &Histogram) {
+        for (count, value) in self.counts.iter().zip(other.bucket_values()) {
+            count.fetch_add(value, Ordering::Relaxed);
+        }
+        self.sum.fetch_add(other.sum(), Ordering::Relaxed);
+    }
+
+    /// Zero every bucket and the running sum.
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+        self.sum.store(0, Ordering::Relaxed);
+    }
+
+    /// JSON snapshot with `<=bound` bucket keys plus a `>last` overflow key.
+    pub fn snapshot(&self) -> serde_json::Value {
+        let values = self.bucket_values();
+        let mut buckets = serde_json::Map::new();
+        for (bound, count) in self.bounds.iter().zip(&values) {
+            buckets.insert(format!("<={bound}"), (*count).into());
+        }
+        if let (Some(last), Some(overflow)) = (self.bounds.last(), values.last()) {
+            buckets.insert(format!(">{last}"), (*overflow).into());
+        }
+        serde_json::json!({
+            "buckets": buckets,
+            "count": values.iter().sum::<u64>(),
+            "sum": self.sum(),
+        })
+    }
+}
+
+/// Aggregate output throughput of streamed responses for one provider.
+#[derive(Default)]
+struct StreamThroughput {
+    streams: AtomicU64,
+    output_tokens: AtomicU64,
+    /// Total generation time across streams, in microseconds.
+    generation_micros: AtomicU64,
+}
+
+impl StreamThroughput {
+    fn snapshot(&self) -> serde_json::Value {
+        let tokens = self.output_tokens.load(Ordering::Relaxed);
+        let micros = self.generation_micros.load(Ordering::Relaxed);
+        let tokens_per_sec = if micros > 0 {
+            tokens as f64 / (micros as f64 / 1_000_000.0)
+        } else {
+            0.0
+        };
+        serde_json::json!({
+            "streams": self.streams.load(Ordering::Relaxed),
+            "output_tokens": tokens,
+            "tokens_per_sec": tokens_per_sec,
+        })
+    }
+}
+
+/// Upstream attempt outcomes for one provider.
+#[derive(Default)]
+struct AttemptCounters {
+    attempts: AtomicU64,
+    failures: AtomicU64,
+    /// Attempts made after an earlier attempt of the same request failed.
+    retries: AtomicU64,
+}
+
+impl AttemptCounters {
+    fn snapshot(&self) -> serde_json::Value {
+        let attempts = self.attempts.load(Ordering::Relaxed);
+        let failures = self.failures.load(Ordering::Relaxed);
+        let retries = self.retries.load(Ordering::Relaxed);
+        let rate = |n: u64| {
+            if attempts > 0 {
+                n as f64 / attempts as f64
+            } else {
+                0.0
+            }
+        };
+        serde_json::json!({
+            "attempts": attempts,
+            "failures": failures,
+            "retries": retries,
+            "failure_rate": rate(failures),
+            "retry_rate": rate(retries),
+        })
+    }
+}
 
 /// Lightweight in-memory metrics using atomic counters.
 pub struct Metrics {
     pub total_requests: AtomicU64,
     pub total_errors: AtomicU64,
+    /// Upstream attempts made after an earlier attempt of the same request failed.
+    pub total_retries: AtomicU64,
     pub total_input_tokens: AtomicU64,
     pub total_output_tokens: AtomicU64,
     /// Total cost in USD (stored as millionths of a cent for atomic precision).
@@ -30,11 +239,46 @@ pub struct Metrics {
     tenant_token_counts: RwLock<HashMap<String, AtomicU64>>,
     /// Per-tenant cost tracking (micro-USD).
     tenant_cost_micro: RwLock<HashMap<String, AtomicU64>>,
+    /// Per-tag request counts (auth key tags and provider tags).
+    tag_request_counts: RwLock<HashMap<String, AtomicU64>>,
+    /// Per-tag error counts.
+    tag_error_counts: RwLock<HashMap<String, AtomicU64>>,
+    /// Per-experiment, per-arm counters (experiment name -> arm -> counters).
+    experiment_arms: RwLock<HashMap<String, HashMap<&'static str, ExperimentArmCounters>>>,
+    /// Per-model latency histograms (bounded, see [`MAX_LATENCY_SERIES`]).
+    model_latency: RwLock<HashMap<String, Histogram>>,
+    /// Per-provider latency histograms (bounded, see [`MAX_LATENCY_SERIES`]).
+    provider_latency: RwLock<HashMap<String, Histogram>>,
+    /// Per-provider streamed output throughput (bounded, see [`MAX_LATENCY_SERIES`]).
+    provider_throughput: RwLock<HashMap<String, StreamThroughput>>,
+    /// Per-provider upstream attempt outcomes (bounded, see [`MAX_LATENCY_SERIES`]).
+    provider_attempts: RwLock<HashMap<String, AttemptCounters>>,
+    /// Client request body size distribution (bytes).
+    pub request_bytes: Histogram,
+    /// Per-request prompt (input) token distribution.
+    pub prompt_tokens: Histogram,
+    /// Per-request completion (output) token distribution.
+    pub completion_tokens: Histogram,
+    /// Dispatch phase durations (µs), indexed by [`DispatchPhase`].
+    phase_durations: [Histogram; 5],
     /// Cache hit/miss counters.
     pub cache_hits: AtomicU64,
     pub cache_misses: AtomicU64,
+    /// Moderation outcomes: prompts checked, flagged, blocked, and failed checks.
+    pub moderation_checked: AtomicU64,
+    pub moderation_flagged: AtomicU64,
+    pub moderation_blocked: AtomicU64,
+    pub moderation_errors: AtomicU64,
+    /// Streams that filled their buffer: paused upstream reads, and dropped clients.
+    pub stream_backpressure_stalls: AtomicU64,
+    pub stream_slow_client_disconnects: AtomicU64,
+    /// Requests rejected by the `concurrency` caps, by which cap was hit.
+    pub shed_requests: AtomicU64,
+    pub shed_streams: AtomicU64,
     /// When the metrics instance was created (for uptime).
     created_at: Instant,
+    /// When counters last started accumulating (creation or last reset), for rates.
+    window_start: Mutex<Instant>,
 }
 
 impl Metrics {
@@ -42,6 +286,7 @@ impl Metrics {
         Self {
             total_requests: AtomicU64::new(0),
             total_errors: AtomicU64::new(0),
+            total_retries: AtomicU64::new(0),
             total_input_tokens: AtomicU64::new(0),
             total_output_tokens: AtomicU64::new(0),
             total_cost_micro: AtomicU64::new(0),
@@ -68,12 +313,102 @@ impl Metrics {
             tenant_request_counts: RwLock::new(HashMap::new()),
             tenant_token_counts: RwLock::new(HashMap::new()),
             tenant_cost_micro: RwLock::new(HashMap::new()),
+            tag_request_counts: RwLock::new(HashMap::new()),
+            tag_error_counts: RwLock::new(HashMap::new()),
+            experiment_arms: RwLock::new(HashMap::new()),
+            model_latency: RwLock::new(HashMap::new()),
+            provider_latency: RwLock::new(HashMap::new()),
+            provider_throughput: RwLock::new(HashMap::new()),
+            provider_attempts: RwLock::new(HashMap::new()),
+            request_bytes: Histogram::new(REQUEST_BYTES_BOUNDS),
+            prompt_tokens: Histogram::new(TOKEN_BOUNDS),
+            completion_tokens: Histogram::new(TOKEN_BOUNDS),
+            phase_durations: DispatchPhase::ALL.map(|_| Histogram::new(PHASE_BOUNDS_US)),
             cache_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
+            moderation_checked: AtomicU64::new(0),
+            moderation_flagged: AtomicU64::new(0),
+            moderation_blocked: AtomicU64::new(0),
+            moderation_errors: AtomicU64::new(0),
+            stream_backpressure_stalls: AtomicU64::new(0),
+            stream_slow_client_disconnects: AtomicU64::new(0),
+            shed_requests: AtomicU64::new(0),
+            shed_streams: AtomicU64::new(0),
             created_at: Instant::now(),
+            window_start: Mutex::new(Instant::now()),
         }
     }
 
+    /// Zero all counters, histograms, and per-key series, returning the snapshot taken
+    /// just before. Uptime is unaffected; `requests_per_minute` restarts from the reset.
+    pub fn reset(&self) -> serde_json::Value {
+        let previous = self.snapshot();
+        for counter in [
+            &self.total_requests,
+            &self.total_errors,
+            &self.total_retries,
+            &self.total_input_tokens,
+            &self.total_output_tokens,
+            &self.total_cost_micro,
+            &self.total_latency_ms,
+            &self.cache_hits,
+            &self.cache_misses,
+            &self.moderation_checked,
+            &self.moderation_flagged,
+            &self.moderation_blocked,
+            &self.moderation_errors,
+            &self.stream_backpressure_stalls,
+            &self.stream_slow_client_disconnects,
+            &self.shed_requests,
+            &self.shed_streams,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        for bucket in self.latency_buckets.iter().chain(&self.ttft_buckets) {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.request_bytes.reset();
+        self.prompt_tokens.reset();
+        self.completion_tokens.reset();
+        for histogram in &self.phase_durations {
+            histogram.reset();
+        }
+        if let Ok(mut costs) = self.model_costs.lock() {
+            costs.clear();
+        }
+        for map in [
+            &self.model_counts,
+            &self.provider_counts,
+            &self.tenant_request_counts,
+            &self.tenant_token_counts,
+            &self.tenant_cost_micro,
+            &self.tag_request_counts,
+            &self.tag_error_counts,
+        ] {
+            if let Ok(mut m) = map.write() {
+                m.clear();
+            }
+        }
+        for map in [&self.model_latency, &self.provider_latency] {
+            if let Ok(mut m) = map.write() {
+                m.clear();
+            }
+        }
+        if let Ok(mut m) = self.provider_throughput.write() {
+            m.clear();
+        }
+        if let Ok(mut m) = self.provider_attempts.write() {
+            m.clear();
+        }
+        if let Ok(mut m) = self.experiment_arms.write() {
+            m.clear();
+        }
+        if let Ok(mut start) = self.window_start.lock() {
+            *start = Instant::now();
+        }
+        previous
+    }
+
     pub fn record_request(&self, model: &str, provider: &str) {
         self.total_requests.fetch_add(1, Ordering::Relaxed);
         increment_map(&self.model_counts, model);
@@ -98,10 +433,167 @@ impl Metrics {
             .fetch_add(ms as u64, Ordering::Relaxed);
     }
 
+    /// Record upstream latency globally and in the per-model/provider histograms.
+    pub fn record_target_latency_ms(&self, model: &str, provider: &str, ms: u128) {
+        self.record_latency_ms(ms);
+        let ms = ms as u64;
+        observe_latency_series(&self.model_latency, model, ms);
+        observe_latency_series(&self.provider_latency, provider, ms);
+    }
+
+    /// Record output tokens generated by one streamed response over `generation`
+    /// (first to last upstream chunk). Empty or instantaneous streams are ignored.
+    pub fn record_stream_throughput(
+        &self,
+        provider: &str,
+        output_tokens: u64,
+        generation: Duration,
+    ) {
+        let micros = generation.as_micros() as u64;
+        if output_tokens == 0 || micros == 0 {
+            return;
+        }
+        with_series(
+            &self.provider_throughput,
+            provider,
+            StreamThroughput::default,
+            |t| {
+                t.streams.fetch_add(1, Ordering::Relaxed);
+                t.output_tokens.fetch_add(output_tokens, Ordering::Relaxed);
+                t.generation_micros.fetch_add(micros, Ordering::Relaxed);
+            },
+        );
+    }
+
+    /// Streamed throughput for one provider, if any streams were recorded for it.
+    pub fn provider_throughput(&self, provider: &str) -> Option<serde_json::Value> {
+        self.provider_throughput
+            .read()
+            .ok()
+            .and_then(|m| m.get(provider).map(StreamThroughput::snapshot))
+    }
+
+    /// Streamed throughput keyed by provider.
+    pub fn throughput_snapshot(&self) -> serde_json::Value {
+        let mut result = serde_json::Map::new();
+        if let Ok(m) = self.provider_throughput.read() {
+            for (k, v) in m.iter() {
+                result.insert(k.clone(), v.snapshot());
+            }
+        }
+        serde_json::Value::Object(result)
+    }
+
+    /// Record the outcome of one upstream attempt against a config provider.
+    /// `retry` marks attempts made after an earlier attempt of the request failed.
+    pub fn record_upstream_attempt(&self, provider: &str, retry: bool, failed: bool) {
+        if retry {
+            self.total_retries.fetch_add(1, Ordering::Relaxed);
+        }
+        with_series(
+            &self.provider_attempts,
+            provider,
+            AttemptCounters::default,
+            |c| {
+                c.attempts.fetch_add(1, Ordering::Relaxed);
+                if failed {
+                    c.failures.fetch_add(1, Ordering::Relaxed);
+                }
+                if retry {
+                    c.retries.fetch_add(1, Ordering::Relaxed);
+                }
+            },
+        );
+    }
+
+    /// Attempt outcomes for one provider, if any attempts were recorded for it.
+    pub fn provider_attempts(&self, provider: &str) -> Option<serde_json::Value> {
+        self.provider_attempts
+            .read()
+            .ok()
+            .and_then(|m| m.get(provider).map(AttemptCounters::snapshot))
+    }
+
+    /// Attempt outcomes keyed by provider.
+    pub fn attempts_snapshot(&self) -> serde_json::Value {
+        let mut result = serde_json::Map::new();
+        if let Ok(m) = self.provider_attempts.read() {
+            for (k, v) in m.iter() {
+                result.insert(k.clone(), v.snapshot());
+            }
+        }
+        serde_json::Value::Object(result)
+    }
+
+    /// Latency histogram snapshot for one model, if it has a tracked series.
+    pub fn model_latency_snapshot(&self, model: &str) -> Option<serde_json::Value> {
+        self.model_latency
+            .read()
+            .ok()
+            .and_then(|m| m.get(model).map(Histogram::snapshot))
+    }
+
+    /// Latency histogram snapshots keyed by model and by provider.
+    pub fn latency_breakdown(&self) -> serde_json::Value {
+        serde_json::json!({
+            "by_model": snapshot_series(&self.model_latency),
+            "by_provider": snapshot_series(&self.provider_latency),
+        })
+    }
+
+    /// Visit each per-model (`"model"`) and per-provider (`"provider"`) latency series.
+    pub fn for_each_latency_series(&self, mut visit: impl FnMut(&str, &str, &Histogram)) {
+        for (label, map) in [
+            ("model", &self.model_latency),
+            ("provider", &self.provider_latency),
+        ] {
+            if let Ok(m) = map.read() {
+                let (mut top, other) = top_latency_series(&m);
+                top.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, histogram) in top {
+                    visit(label, key, histogram);
+                }
+                if let Some(other) = other {
+                    visit(label, OTHER_SERIES, &other);
+                }
+            }
+        }
+    }
+
+    /// Record one request's token usage (totals and per-request histograms).
     pub fn record_tokens(&self, input: u64, output: u64) {
         self.total_input_tokens.fetch_add(input, Ordering::Relaxed);
         self.total_output_tokens
             .fetch_add(output, Ordering::Relaxed);
+        self.prompt_tokens.observe(input);
+        self.completion_tokens.observe(output);
+    }
+
+    /// Record how long one dispatch phase took.
+    pub fn record_phase(&self, phase: DispatchPhase, elapsed: Duration) {
+        self.phase_durations[phase as usize].observe(elapsed.as_micros() as u64);
+    }
+
+    /// Duration histogram (µs) for one dispatch phase.
+    pub fn phase_histogram(&self, phase: DispatchPhase) -> &Histogram {
+        &self.phase_durations[phase as usize]
+    }
+
+    /// Phase duration histograms keyed by phase name.
+    pub fn phase_snapshot(&self) -> serde_json::Value {
+        let mut result = serde_json::Map::new();
+        for phase in DispatchPhase::ALL {
+            result.insert(
+                phase.as_str().to_string(),
+                self.phase_histogram(phase).snapshot(),
+            );
+        }
+        serde_json::Value::Object(result)
+    }
+
+    /// Record the size of a client request body in bytes.
+    pub fn record_request_bytes(&self, bytes: u64) {
+        self.request_bytes.observe(bytes);
     }
 
     /// Record cost in USD for a request.
@@ -143,6 +635,104 @@ impl Metrics {
         increment_map_by(&self.tenant_cost_micro, tenant_id, micro);
     }
 
+    /// Record a finished request under each of its tags.
+    pub fn record_tag_request(&self, tags: &[String], failed: bool) {
+        for tag in tags {
+            increment_map(&self.tag_request_counts, tag);
+            if failed {
+                increment_map(&self.tag_error_counts, tag);
+            }
+        }
+    }
+
+    /// Record a completed request for an experiment arm.
+    pub fn record_experiment_request(
+        &self,
+        experiment: &str,
+        arm: &'static str,
+        latency_ms: u128,
+        is_error: bool,
+    ) {
+        self.with_experiment_arm(experiment, arm, |c| {
+            c.requests.fetch_add(1, Ordering::Relaxed);
+            c.latency_ms.fetch_add(latency_ms as u64, Ordering::Relaxed);
+            if is_error {
+                c.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Record token usage and cost for an experiment arm.
+    pub fn record_experiment_usage(
+        &self,
+        experiment: &str,
+        arm: &'static str,
+        tokens: u64,
+        cost: Option<f64>,
+    ) {
+        self.with_experiment_arm(experiment, arm, |c| {
+            c.tokens.fetch_add(tokens, Ordering::Relaxed);
+            if let Some(cost) = cost {
+                c.cost_micro
+                    .fetch_add((cost * 1_000_000.0) as u64, Ordering::Relaxed);
+            }
+        });
+    }
+
+    fn with_experiment_arm(
+        &self,
+        experiment: &str,
+        arm: &'static str,
+        update: impl Fn(&ExperimentArmCounters),
+    ) {
+        if let Ok(m) = self.experiment_arms.read()
+            && let Some(counters) = m.get(experiment).and_then(|arms| arms.get(arm))
+        {
+            update(counters);
+            return;
+        }
+        if let Ok(mut m) = self.experiment_arms.write() {
+            update(
+                m.entry(experiment.to_string())
+                    .or_default()
+                    .entry(arm)
+                    .or_default(),
+            );
+        }
+    }
+
+    /// Per-experiment, per-arm metrics snapshot.
+    pub fn experiment_snapshot(&self) -> serde_json::Value {
+        let mut experiments = serde_json::Map::new();
+        if let Ok(m) = self.experiment_arms.read() {
+            for (name, arms) in m.iter() {
+                let arms: serde_json::Map<String, serde_json::Value> = arms
+                    .iter()
+                    .map(|(arm, c)| {
+                        let requests = c.requests.load(Ordering::Relaxed);
+                        let avg_latency = if requests > 0 {
+                            c.latency_ms.load(Ordering::Relaxed) as f64 / requests as f64
+                        } else {
+                            0.0
+                        };
+                        (
+                            arm.to_string(),
+                            serde_json::json!({
+                                "requests": requests,
+                                "errors": c.errors.load(Ordering::Relaxed),
+                                "tokens": c.tokens.load(Ordering::Relaxed),
+                                "cost_usd": c.cost_micro.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+                                "avg_latency_ms": avg_latency,
+                            }),
+                        )
+                    })
+                    .collect();
+                experiments.insert(name.clone(), serde_json::Value::Object(arms));
+            }
+        }
+        serde_json::Value::Object(experiments)
+    }
+
     /// Record a cache hit.
     pub fn record_cache_hit(&self) {
         self.cache_hits.fetch_add(1, Ordering::Relaxed);
@@ -153,6 +743,42 @@ impl Metrics {
         self.cache_misses.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a completed moderation check.
+    pub fn record_moderation_check(&self, flagged: bool, blocked: bool) {
+        self.moderation_checked.fetch_add(1, Ordering::Relaxed);
+        if flagged {
+            self.moderation_flagged.fetch_add(1, Ordering::Relaxed);
+        }
+        if blocked {
+            self.moderation_blocked.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a moderation call that failed or timed out.
+    pub fn record_moderation_error(&self) {
+        self.moderation_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a stream whose buffer filled up, by the action taken.
+    pub fn record_slow_client(&self, disconnected: bool) {
+        if disconnected {
+            self.stream_slow_client_disconnects
+                .fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stream_backpressure_stalls
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a request shed by the request (`stream = false`) or stream cap.
+    pub fn record_load_shed(&self, stream: bool) {
+        if stream {
+            self.shed_streams.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.shed_requests.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
     /// Raw latency bucket values for Prometheus rendering.
     pub fn latency_bucket_values(&self) -> [u64; 6] {
         [
@@ -207,6 +833,29 @@ impl Metrics {
         serde_json::Value::Object(tenants)
     }
 
+    /// Per-tag metrics snapshot.
+    pub fn tag_snapshot(&self) -> serde_json::Value {
+        let mut tags = serde_json::Map::new();
+        if let Ok(req_counts) = self.tag_request_counts.read() {
+            for (tag, count) in req_counts.iter() {
+                let errors = self
+                    .tag_error_counts
+                    .read()
+                    .ok()
+                    .and_then(|m| m.get(tag).map(|v| v.load(Ordering::Relaxed)))
+                    .unwrap_or(0);
+                tags.insert(
+                    tag.clone(),
+                    serde_json::json!({
+                        "requests": count.load(Ordering::Relaxed),
+                        "errors": errors,
+                    }),
+                );
+            }
+        }
+        serde_json::Value::Object(tags)
+    }
+
     /// Snapshot current metrics as a JSON-serializable value.
     pub fn snapshot(&self) -> serde_json::Value {
         let model_counts = snapshot_map(&self.model_counts);
@@ -225,6 +874,11 @@ impl Metrics {
         let total_reqs = self.total_requests.load(Ordering::Relaxed);
         let total_errs = self.total_errors.load(Ordering::Relaxed);
         let uptime_secs = self.created_at.elapsed().as_secs();
+        let window_secs = self
+            .window_start
+            .lock()
+            .map(|start| start.elapsed().as_secs())
+            .unwrap_or(uptime_secs);
 
         // Computed fields for dashboard frontend
         let error_rate = if total_reqs > 0 {
@@ -237,8 +891,8 @@ impl Metrics {
         } else {
             0.0
         };
-        let rpm = if uptime_secs > 0 {
-            (total_reqs as f64 / uptime_secs as f64) * 60.0
+        let rpm = if window_secs > 0 {
+            (total_reqs as f64 / window_secs as f64) * 60.0
         } else {
             0.0
         };
@@ -253,6 +907,7 @@ impl Metrics {
         serde_json::json!({
             "total_requests": total_reqs,
             "total_errors": total_errs,
+            "total_retries": self.total_retries.load(Ordering::Relaxed),
             "total_input_tokens": self.total_input_tokens.load(Ordering::Relaxed),
             "total_output_tokens": self.total_output_tokens.load(Ordering::Relaxed),
             "total_cost_usd": total_cost,
@@ -272,14 +927,36 @@ impl Metrics {
                 "1000-4999": self.ttft_buckets[4].load(Ordering::Relaxed),
                 ">=5000": self.ttft_buckets[5].load(Ordering::Relaxed),
             },
+            "request_bytes": self.request_bytes.snapshot(),
+            "stream_throughput": self.throughput_snapshot(),
+            "attempts_by_provider": self.attempts_snapshot(),
+            "prompt_tokens": self.prompt_tokens.snapshot(),
+            "completion_tokens": self.completion_tokens.snapshot(),
+            "phase_us": self.phase_snapshot(),
             "cache": {
                 "hits": self.cache_hits.load(Ordering::Relaxed),
                 "misses": self.cache_misses.load(Ordering::Relaxed),
             },
+            "moderation": {
+                "checked": self.moderation_checked.load(Ordering::Relaxed),
+                "flagged": self.moderation_flagged.load(Ordering::Relaxed),
+                "blocked": self.moderation_blocked.load(Ordering::Relaxed),
+                "errors": self.moderation_errors.load(Ordering::Relaxed),
+            },
+            "slow_clients": {
+                "backpressure_stalls": self.stream_backpressure_stalls.load(Ordering::Relaxed),
+                "disconnects": self.stream_slow_client_disconnects.load(Ordering::Relaxed),
+            },
+            "load_shed": {
+                "requests": self.shed_requests.load(Ordering::Relaxed),
+                "streams": self.shed_streams.load(Ordering::Relaxed),
+            },
             "by_model": model_counts,
             "by_provider": provider_counts,
             "cost_by_model": model_costs,
             "by_tenant": self.tenant_snapshot(),
+            "by_tag": self.tag_snapshot(),
+            "by_experiment": self.experiment_snapshot(),
             // Computed fields for dashboard frontend
             "total_tokens": total_tokens,
             "active_providers": active_providers,
@@ -291,6 +968,15 @@ impl Metrics {
     }
 }
 
+#[derive(Default)]
+struct ExperimentArmCounters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    tokens: AtomicU64,
+    cost_micro: AtomicU64,
+    latency_ms: AtomicU64,
+}
+
 impl Default for Metrics {
     fn default() -> Self {
         Self::new()
@@ -332,6 +1018,98 @@ fn increment_map_by(map: &RwLock<HashMap<String, AtomicU64>>, key: &str, amount:
     }
 }
 
+/// Apply `update` to the series for `key`, folding new keys into
+/// [`OTHER_SERIES`] once [`MAX_LATENCY_SERIES`] distinct keys exist.
+fn with_series<T>(
+    map: &RwLock<HashMap<String, T>>,
+    key: &str,
+    create: impl FnOnce() -> T,
+    update: impl Fn(&T),
+) {
+    if let Ok(m) = map.read()
+        && let Some(series) = m.get(key)
+    {
+        update(series);
+        return;
+    }
+    if let Ok(mut m) = map.write() {
+        let tracked = m.keys().filter(|k| k.as_str() != OTHER_SERIES).count();
+        let key = if m.contains_key(key) || tracked < MAX_LATENCY_SERIES {
+            key
+        } else {
+            OTHER_SERIES
+        };
+        update(m.entry(key.to_string()).or_insert_with(create));
+    }
+}
+
+/// Record `ms` in the latency series for `key`, evicting the lowest-volume
+/// series into [`OTHER_SERIES`] when [`MAX_TRACKED_LATENCY_SERIES`] are held.
+fn observe_latency_series(map: &RwLock<HashMap<String, Histogram>>, key: &str, ms: u64) {
+    if let Ok(m) = map.read()
+        && let Some(series) = m.get(key)
+    {
+        series.observe(ms);
+        return;
+    }
+    let Ok(mut m) = map.write() else { return };
+    let tracked = m.keys().filter(|k| k.as_str() != OTHER_SERIES).count();
+    if !m.contains_key(key) && tracked >= MAX_TRACKED_LATENCY_SERIES {
+        let quietest = m
+            .iter()
+            .filter(|(k, _)| k.as_str() != OTHER_SERIES)
+            .min_by_key(|(_, h)| h.count())
+            .map(|(k, _)| k.clone());
+        if let Some(evicted) = quietest.and_then(|k| m.remove(&k)) {
+            m.entry(OTHER_SERIES.to_string())
+                .or_insert_with(|| Histogram::new(LATENCY_BOUNDS_MS))
+                .absorb(&evicted);
+        }
+    }
+    m.entry(key.to_string())
+        .or_insert_with(|| Histogram::new(LATENCY_BOUNDS_MS))
+        .observe(ms);
+}
+
+/// The [`MAX_LATENCY_SERIES`] busiest series, plus an [`OTHER_SERIES`]
+/// histogram holding everything else when anything is left over.
+fn top_latency_series(
+    m: &HashMap<String, Histogram>,
+) -> (Vec<(&String, &Histogram)>, Option<Histogram>) {
+    let mut series: Vec<(&String, &Histogram)> = m
+        .iter()
+        .filter(|(k, _)| k.as_str() != OTHER_SERIES)
+        .collect();
+    series.sort_by(|a, b| b.1.count().cmp(&a.1.count()).then_with(|| a.0.cmp(b.0)));
+    let rest = series.split_off(series.len().min(MAX_LATENCY_SERIES));
+    let evicted = m.get(OTHER_SERIES);
+    if rest.is_empty() && evicted.is_none() {
+        return (series, None);
+    }
+    let other = Histogram::new(LATENCY_BOUNDS_MS);
+    for (_, histogram) in rest {
+        other.absorb(histogram);
+    }
+    if let Some(evicted) = evicted {
+        other.absorb(evicted);
+    }
+    (series, Some(other))
+}
+
+fn snapshot_series(map: &RwLock<HashMap<String, Histogram>>) -> serde_json::Value {
+    let mut result = serde_json::Map::new();
+    if let Ok(m) = map.read() {
+        let (top, other) = top_latency_series(&m);
+        for (k, v) in top {
+            result.insert(k.clone(), v.snapshot());
+        }
+        if let Some(other) = other {
+            result.insert(OTHER_SERIES.to_string(), other.snapshot());
+        }
+    }
+    serde_json::Value::Object(result)
+}
+
 fn snapshot_map(map: &RwLock<HashMap<String, AtomicU64>>) -> serde_json::Value {
     let mut result = serde_json::Map::new();
     if let Ok(m) = map.read() {
@@ -405,6 +1183,40 @@ mod tests {
         assert_eq!(snap["by_tenant"]["beta"]["requests"], 1);
     }
 
+    #[test]
+    fn test_tag_metrics() {
+        let m = Metrics::new();
+        let tags = vec!["team-a".to_string(), "prod".to_string()];
+        m.record_tag_request(&tags, false);
+        m.record_tag_request(&tags[..1], true);
+
+        let snap = m.snapshot();
+        assert_eq!(snap["by_tag"]["team-a"]["requests"], 2);
+        assert_eq!(snap["by_tag"]["team-a"]["errors"], 1);
+        assert_eq!(snap["by_tag"]["prod"]["requests"], 1);
+        assert_eq!(snap["by_tag"]["prod"]["errors"], 0);
+
+        m.reset();
+        assert!(m.snapshot()["by_tag"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_experiment_metrics() {
+        let m = Metrics::new();
+        m.record_experiment_request("exp", "control", 100, false);
+        m.record_experiment_request("exp", "variant", 300, false);
+        m.record_experiment_request("exp", "variant", 100, true);
+        m.record_experiment_usage("exp", "variant", 500, Some(0.02));
+
+        let snap = m.snapshot();
+        let variant = &snap["by_experiment"]["exp"]["variant"];
+        assert_eq!(variant["requests"], 2);
+        assert_eq!(variant["errors"], 1);
+        assert_eq!(variant["tokens"], 500);
+        assert_eq!(variant["avg_latency_ms"], 200.0);
+        assert_eq!(snap["by_experiment"]["exp"]["control"]["requests"], 1);
+    }
+
     #[test]
     fn test_cache_counters() {
         let m = Metrics::new();
@@ -416,4 +1228,178 @@ mod tests {
         assert_eq!(snap["cache"]["hits"], 2);
         assert_eq!(snap["cache"]["misses"], 1);
     }
+
+    #[test]
+    fn test_slow_client_counters() {
+        let m = Metrics::new();
+        m.record_slow_client(false);
+        m.record_slow_client(false);
+        m.record_slow_client(true);
+
+        let snap = m.snapshot();
+        assert_eq!(snap["slow_clients"]["backpressure_stalls"], 2);
+        assert_eq!(snap["slow_clients"]["disconnects"], 1);
+    }
+
+    #[test]
+    fn test_load_shed_counters() {
+        let m = Metrics::new();
+        m.record_load_shed(false);
+        m.record_load_shed(true);
+        m.record_load_shed(true);
+
+        let snap = m.snapshot();
+        assert_eq!(snap["load_shed"]["requests"], 1);
+        assert_eq!(snap["load_shed"]["streams"], 2);
+    }
+
+    #[test]
+    fn test_reset_returns_previous_snapshot() {
+        let m = Metrics::new();
+        m.record_request("gpt-4", "openai");
+        m.record_error();
+        m.record_target_latency_ms("gpt-4", "openai", 120);
+        m.record_request_bytes(512);
+        m.record_cost("gpt-4", 0.5);
+
+        let previous = m.reset();
+        assert_eq!(previous["total_requests"], 1);
+        assert_eq!(previous["by_model"]["gpt-4"], 1);
+
+        let snap = m.snapshot();
+        assert_eq!(snap["total_requests"], 0);
+        assert_eq!(snap["total_errors"], 0);
+        assert_eq!(snap["total_cost_usd"], 0.0);
+        assert_eq!(snap["request_bytes"]["count"], 0);
+        assert!(snap["by_model"].as_object().unwrap().is_empty());
+        assert!(m.model_latency_snapshot("gpt-4").is_none());
+    }
+
+    #[test]
+    fn test_size_and_token_histograms() {
+        let m = Metrics::new();
+        m.record_request_bytes(512);
+        m.record_request_bytes(2048);
+        m.record_request_bytes(64 * 1024 * 1024);
+        m.record_tokens(50, 800);
+        m.record_tokens(200_000, 10);
+
+        let snap = m.snapshot();
+        assert_eq!(snap["request_bytes"]["buckets"]["<=1024"], 1);
+        assert_eq!(snap["request_bytes"]["buckets"]["<=4096"], 1);
+        assert_eq!(snap["request_bytes"]["buckets"][">16777216"], 1);
+        assert_eq!(snap["request_bytes"]["count"], 3);
+        assert_eq!(snap["prompt_tokens"]["buckets"]["<=100"], 1);
+        assert_eq!(snap["prompt_tokens"]["buckets"][">128000"], 1);
+        assert_eq!(snap["completion_tokens"]["buckets"]["<=1000"], 1);
+        assert_eq!(snap["completion_tokens"]["sum"], 810);
+    }
+
+    #[test]
+    fn test_phase_durations() {
+        let m = Metrics::new();
+        m.record_phase(DispatchPhase::Translate, Duration::from_micros(250));
+        m.record_phase(DispatchPhase::ChunkTranslate, Duration::from_micros(40));
+        m.record_phase(DispatchPhase::ChunkTranslate, Duration::from_micros(60));
+        m.record_phase(DispatchPhase::UpstreamConnect, Duration::from_secs(20));
+
+        let snap = m.snapshot();
+        assert_eq!(snap["phase_us"]["translate"]["buckets"]["<=1000"], 1);
+        assert_eq!(snap["phase_us"]["chunk_translate"]["count"], 2);
+        assert_eq!(snap["phase_us"]["chunk_translate"]["sum"], 100);
+        assert_eq!(
+            snap["phase_us"]["upstream_connect"]["buckets"][">10000000"],
+            1
+        );
+        assert_eq!(snap["phase_us"]["credential_pick"]["count"], 0);
+
+        m.reset();
+        assert_eq!(m.phase_histogram(DispatchPhase::ChunkTranslate).count(), 0);
+    }
+
+    #[test]
+    fn test_per_model_latency_is_bounded() {
+        let m = Metrics::new();
+        m.record_target_latency_ms("gpt-4", "openai", 250);
+        m.record_target_latency_ms("gpt-4", "openai", 2_000);
+        for i in 0..MAX_LATENCY_SERIES + 5 {
+            m.record_target_latency_ms(&format!("model-{i}"), "openai", 50);
+        }
+
+        let gpt4 = m.model_latency_snapshot("gpt-4").unwrap();
+        assert_eq!(gpt4["count"], 2);
+        assert_eq!(gpt4["buckets"]["<=500"], 1);
+        assert_eq!(gpt4["buckets"]["<=5000"], 1);
+
+        let breakdown = m.latency_breakdown();
+        let by_model = breakdown["by_model"].as_object().unwrap();
+        assert_eq!(by_model.len(), MAX_LATENCY_SERIES + 1);
+        assert_eq!(by_model[OTHER_SERIES]["count"], 6);
+        assert_eq!(breakdown["by_provider"]["openai"]["count"], 27);
+        // The global histogram still sees every observation.
+        assert_eq!(m.latency_bucket_values().iter().sum::<u64>(), 27);
+    }
+
+    #[test]
+    fn test_latency_series_keep_the_busiest_keys() {
+        let m = Metrics::new();
+        for i in 0..MAX_TRACKED_LATENCY_SERIES {
+            m.record_target_latency_ms(&format!("model-{i}"), "openai", 50);
+        }
+        // A key first seen after the limit still gets a series once it is busy.
+        for _ in 0..3 {
+            m.record_target_latency_ms("late-but-busy", "claude-prod", 50);
+        }
+
+        let breakdown = m.latency_breakdown();
+        let by_model = breakdown["by_model"].as_object().unwrap();
+        assert_eq!(by_model.len(), MAX_LATENCY_SERIES + 1);
+        assert_eq!(by_model["late-but-busy"]["count"], 3);
+        assert_eq!(
+            by_model[OTHER_SERIES]["count"],
+            (MAX_TRACKED_LATENCY_SERIES - MAX_LATENCY_SERIES + 1) as u64
+        );
+        assert_eq!(breakdown["by_provider"]["claude-prod"]["count"], 3);
+    }
+
+    #[test]
+    fn test_upstream_attempts_per_provider() {
+        let m = Metrics::new();
+        m.record_upstream_attempt("flaky", false, true);
+        m.record_upstream_attempt("flaky", true, true);
+        m.record_upstream_attempt("backup", true, false);
+
+        let flaky = m.provider_attempts("flaky").unwrap();
+        assert_eq!(flaky["attempts"], 2);
+        assert_eq!(flaky["failures"], 2);
+        assert_eq!(flaky["failure_rate"], 1.0);
+        assert_eq!(flaky["retry_rate"], 0.5);
+        assert_eq!(m.provider_attempts("backup").unwrap()["retries"], 1);
+        assert!(m.provider_attempts("idle").is_none());
+
+        let snap = m.reset();
+        assert_eq!(snap["total_retries"], 2);
+        assert_eq!(snap["attempts_by_provider"]["backup"]["attempts"], 1);
+        assert_eq!(m.snapshot()["total_retries"], 0);
+        assert!(m.provider_attempts("flaky").is_none());
+    }
+
+    #[test]
+    fn test_stream_throughput() {
+        let m = Metrics::new();
+        m.record_stream_throughput("openai-main", 100, Duration::from_secs(2));
+        m.record_stream_throughput("openai-main", 50, Duration::from_secs(1));
+        m.record_stream_throughput("openai-main", 0, Duration::from_secs(5));
+        m.record_stream_throughput("claude", 10, Duration::ZERO);
+
+        let stats = m.provider_throughput("openai-main").unwrap();
+        assert_eq!(stats["streams"], 2);
+        assert_eq!(stats["output_tokens"], 150);
+        assert_eq!(stats["tokens_per_sec"], 50.0);
+        assert!(m.provider_throughput("claude").is_none());
+        assert_eq!(
+            m.snapshot()["stream_throughput"]["openai-main"]["streams"],
+            2
+        );
+    }
 }
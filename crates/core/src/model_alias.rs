@@ -0,0 +1,206 @@
+//! Effective model alias map across credentials, with collision detection.
+//!
+//! Aliases are resolved per credential, so two providers can claim the same public
+//! name. Precedence is deterministic: a plain model id always wins over an alias of
+//! the same name, and otherwise the first provider in config order owns the alias.
+//! Losing aliases are shadowed: the credential still serves the model under its id.
+
+use crate::config::Config;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One alias of one credential.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AliasEntry {
+    /// Name clients request, including the credential prefix.
+    pub alias: String,
+    pub provider: String,
+    pub credential: String,
+    /// Upstream model id the alias resolves to.
+    pub model: String,
+    /// True when another credential owns the name; requests for it never reach
+    /// this credential.
+    pub shadowed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AliasMap {
+    pub entries: Vec<AliasEntry>,
+    pub warnings: Vec<String>,
+}
+
+impl AliasMap {
+    /// Build the map from enabled providers and credentials, in config order.
+    pub fn from_config(config: &Config) -> Self {
+        // (public name, provider, credential, model id, alias)
+        let mut mappings = Vec::new();
+        for entry in config.providers.iter().filter(|e| !e.disabled) {
+            for profile in entry.expanded_auth_profiles() {
+                if profile.disabled {
+                    continue;
+                }
+                let prefix = profile
+                    .prefix
+                    .or_else(|| entry.prefix.clone())
+                    .unwrap_or_default();
+                for model in entry
+                    .models
+                    .iter()
+                    .filter(|m| !crate::glob::is_pattern(&m.id))
+                {
+                    mappings.push((
+                        prefix.clone(),
+                        entry.name.as_str(),
+                        profile.id.clone(),
+                        model.id.as_str(),
+                        model.alias.as_deref(),
+                    ));
+                }
+            }
+        }
+
+        let mut ids: HashMap<String, &str> = HashMap::new();
+        for (prefix, provider, _, id, _) in &mappings {
+            ids.entry(format!("{prefix}{id}")).or_insert(provider);
+        }
+
+        let mut map = Self::default();
+        // Owning (provider, model) per public alias name.
+        let mut owners: HashMap<String, (&str, &str)> = HashMap::new();
+        // Aliases seen per public model id, with the provider that declared each.
+        let mut exposed: HashMap<String, Vec<(&str, &str)>> = HashMap::new();
+        for (prefix, provider, credential, id, alias) in &mappings {
+            let Some(alias) = alias.filter(|a| a != id) else {
+                continue;
+            };
+            let name = format!("{prefix}{alias}");
+            let shadowed = if let Some(owner) = ids.get(&name) {
+                map.warn(format!(
+                    "alias '{name}' of provider '{provider}' shadows model id '{name}' of provider '{owner}'; the model id wins"
+                ));
+                true
+            } else if let Some((owner, model)) = owners.get(&name) {
+                if model != id {
+                    map.warn(format!(
+                        "alias '{name}' maps to '{model}' on provider '{owner}' and to '{id}' on provider '{provider}'; '{owner}' wins as it is declared first"
+                    ));
+                }
+                model != id
+            } else {
+                owners.insert(name.clone(), (provider, id));
+                false
+            };
+            if !shadowed {
+                let aliases = exposed.entry(format!("{prefix}{id}")).or_default();
+                if !aliases.contains(&(provider, alias)) {
+                    aliases.push((provider, alias));
+                }
+            }
+            map.entries.push(AliasEntry {
+                alias: name,
+                provider: provider.to_string(),
+                credential: credential.clone(),
+                model: id.to_string(),
+                shadowed,
+            });
+        }
+
+        let mut exposed: Vec<_> = exposed.into_iter().collect();
+        exposed.sort();
+        for (model, aliases) in exposed {
+            let mut providers: Vec<&str> = aliases.iter().map(|(p, _)| *p).collect();
+            providers.dedup();
+            if providers.len() > 1 {
+                let listed: Vec<String> = aliases
+                    .iter()
+                    .map(|(provider, alias)| format!("'{alias}' ({provider})"))
+                    .collect();
+                map.warn(format!(
+                    "model '{model}' is exposed under different aliases: {}; each alias only routes to its own provider",
+                    listed.join(", ")
+                ));
+            }
+        }
+        map
+    }
+
+    /// Whether `alias` (with prefix) of `model` on this credential lost to another owner.
+    pub fn is_shadowed(&self, provider: &str, credential: &str, model: &str, alias: &str) -> bool {
+        self.entries.iter().any(|e| {
+            e.shadowed
+                && e.provider == provider
+                && e.credential == credential
+                && e.model == model
+                && e.alias == alias
+        })
+    }
+
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ModelMapping, ProviderKeyEntry};
+
+    fn provider(name: &str, models: &[(&str, Option<&str>)]) -> ProviderKeyEntry {
+        let mut entry: ProviderKeyEntry =
+            serde_yaml_ng::from_str(&format!("name: {name}\nformat: openai\napi-key: sk-{name}"))
+                .unwrap();
+        entry.models = models
+            .iter()
+            .map(|(id, alias)| ModelMapping {
+                id: id.to_string(),
+                alias: alias.map(str::to_string),
+            })
+            .collect();
+        entry
+    }
+
+    #[test]
+    fn test_alias_collisions_resolve_deterministically() {
+        let config = Config {
+            providers: vec![
+                provider(
+                    "alpha",
+                    &[("gpt-4o-mini", Some("fast")), ("gpt-4o", Some("main"))],
+                ),
+                provider(
+                    "beta",
+                    &[("claude-haiku", Some("fast")), ("gpt-4o", Some("primary"))],
+                ),
+                provider(
+                    "gamma",
+                    &[("gpt-4o-mini", None), ("o3", Some("gpt-4o-mini"))],
+                ),
+            ],
+            ..Config::default()
+        };
+        let map = AliasMap::from_config(&config);
+
+        assert!(!map.is_shadowed("alpha", "alpha", "gpt-4o-mini", "fast"));
+        assert!(map.is_shadowed("beta", "beta", "claude-haiku", "fast"));
+        assert!(map.is_shadowed("gamma", "gamma", "o3", "gpt-4o-mini"));
+        assert!(!map.is_shadowed("beta", "beta", "gpt-4o", "primary"));
+        assert_eq!(map.warnings.len(), 3, "{:?}", map.warnings);
+        assert!(map.warnings[0].contains("'alpha' wins"));
+        assert!(map.warnings[1].contains("the model id wins"));
+        assert!(map.warnings[2].contains("'main' (alpha), 'primary' (beta)"));
+
+        // Prefixes namespace aliases, so the same names no longer collide.
+        let mut config = config;
+        config.providers[1].prefix = Some("b/".into());
+        config.providers.truncate(2);
+        let map = AliasMap::from_config(&config);
+        assert!(map.warnings.is_empty(), "{:?}", map.warnings);
+        assert!(
+            map.entries
+                .iter()
+                .any(|e| e.alias == "b/fast" && !e.shadowed)
+        );
+    }
+}
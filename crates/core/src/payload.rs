@@ -10,6 +10,9 @@ pub struct PayloadConfig {
     pub r#override: Vec<PayloadRule>,
     #[serde(default)]
     pub filter: Vec<FilterRule>,
+    /// Per-model defaults and hard caps for the requested output token limit.
+    #[serde(default)]
+    pub max_output_tokens: Vec<OutputTokenRule>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -34,6 +37,43 @@ pub struct FilterRule {
     pub params: Vec<String>,
 }
 
+/// Default and cap for the output token limit of matching models.
+/// The first matching rule wins.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OutputTokenRule {
+    pub models: Vec<ModelMatcher>,
+    /// Limit inserted when the request does not set one.
+    #[serde(default)]
+    pub default: Option<u64>,
+    /// Hard cap; larger requested limits are lowered to this value.
+    #[serde(default)]
+    pub max: Option<u64>,
+}
+
+/// Describes an output token limit the proxy set or lowered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTokenClamp {
+    /// Dot-separated payload path that was written.
+    pub field: &'static str,
+    /// Limit the client asked for, `None` when the default was inserted.
+    pub requested: Option<u64>,
+    pub applied: u64,
+}
+
+impl OutputTokenClamp {
+    /// Value for the `x-prism-output-tokens` debug header.
+    pub fn header_value(&self) -> String {
+        let requested = self
+            .requested
+            .map_or_else(|| "none".to_string(), |n| n.to_string());
+        format!(
+            "field={} requested={requested} applied={}",
+            self.field, self.applied
+        )
+    }
+}
+
 /// Check if a rule matches the given model and protocol.
 fn matches_rule(matchers: &[ModelMatcher], model: &str, protocol: Option<&str>) -> bool {
     matchers.iter().any(|m| {
@@ -133,6 +173,89 @@ pub fn apply_payload_rules(
     }
 }
 
+/// Payload paths that carry the output token limit for a target protocol, in lookup
+/// order. The first path is used when a default has to be inserted.
+fn output_token_fields(body: &Value, protocol: Option<&str>) -> &'static [&'static str] {
+    match protocol {
+        Some("claude") => &["max_tokens"],
+        Some("gemini") => &["generationConfig.maxOutputTokens"],
+        Some("codex") => &["max_output_tokens"],
+        // OpenAI upstreams receive either a Chat Completions or a Responses body.
+        _ if body.get("input").is_some() && body.get("messages").is_none() => {
+            &["max_output_tokens"]
+        }
+        _ => &["max_completion_tokens", "max_tokens"],
+    }
+}
+
+fn get_nested<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(root, |current, part| current.get(part))
+}
+
+/// Smallest `thinking.budget_tokens` Anthropic accepts.
+const CLAUDE_MIN_THINKING_BUDGET: u64 = 1024;
+
+/// Apply the first matching `max-output-tokens` rule to a translated payload.
+///
+/// Inserts the rule's default when the payload has no limit and lowers limits above the
+/// cap. For Claude targets an extended thinking budget is kept below the new limit, and
+/// thinking is dropped when the limit leaves no room for the minimum budget.
+/// Returns what changed so it can be surfaced to the client, or `None` if nothing did.
+pub fn clamp_output_tokens(
+    body: &mut Value,
+    rules: &[OutputTokenRule],
+    model: &str,
+    protocol: Option<&str>,
+) -> Option<OutputTokenClamp> {
+    let rule = rules
+        .iter()
+        .find(|rule| matches_rule(&rule.models, model, protocol))?;
+    let fields = output_token_fields(body, protocol);
+
+    let current = fields
+        .iter()
+        .find_map(|field| get_nested(body, field).map(|value| (*field, value.as_u64())));
+    let clamp = match current {
+        Some((field, Some(requested))) => {
+            let max = rule.max.filter(|max| requested > *max)?;
+            OutputTokenClamp {
+                field,
+                requested: Some(requested),
+                applied: max,
+            }
+        }
+        // Non-numeric limits are left for the upstream to reject.
+        Some((_, None)) => return None,
+        None => {
+            let default = rule.default?;
+            OutputTokenClamp {
+                field: fields[0],
+                requested: None,
+                applied: rule.max.map_or(default, |max| default.min(max)),
+            }
+        }
+    };
+    if !set_nested(body, clamp.field, Value::from(clamp.applied), false) {
+        return None;
+    }
+
+    if protocol == Some("claude")
+        && body
+            .pointer("/thinking/budget_tokens")
+            .and_then(Value::as_u64)
+            .is_some_and(|budget| budget >= clamp.applied)
+    {
+        let budget = clamp.applied.saturating_sub(1);
+        if budget >= CLAUDE_MIN_THINKING_BUDGET {
+            body["thinking"]["budget_tokens"] = Value::from(budget);
+        } else if let Some(obj) = body.as_object_mut() {
+            obj.remove("thinking");
+        }
+    }
+    Some(clamp)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +378,81 @@ mod tests {
         apply_payload_rules(&mut body, &config, "any-model", Some("openai"));
         assert_eq!(body["stream_options"]["include_usage"], true);
     }
+
+    fn output_rule(name: &str, default: Option<u64>, max: Option<u64>) -> OutputTokenRule {
+        OutputTokenRule {
+            models: vec![ModelMatcher {
+                name: name.into(),
+                protocol: None,
+            }],
+            default,
+            max,
+        }
+    }
+
+    #[test]
+    fn test_clamp_output_tokens_caps_and_defaults() {
+        let rules = vec![
+            output_rule("claude-*", Some(4096), Some(16000)),
+            output_rule("*", None, Some(8192)),
+        ];
+
+        let mut body =
+            json!({"max_tokens": 100000, "thinking": {"type": "enabled", "budget_tokens": 20000}});
+        let clamp =
+            clamp_output_tokens(&mut body, &rules, "claude-sonnet-4", Some("claude")).unwrap();
+        assert_eq!(clamp.requested, Some(100000));
+        assert_eq!(body["max_tokens"], 16000);
+        assert_eq!(body["thinking"]["budget_tokens"], 15999);
+        assert_eq!(
+            clamp.header_value(),
+            "field=max_tokens requested=100000 applied=16000"
+        );
+
+        // Room for the minimum budget: lowered to it.
+        let rules_small = vec![output_rule("claude-*", None, Some(1025))];
+        let mut body =
+            json!({"max_tokens": 8000, "thinking": {"type": "enabled", "budget_tokens": 4000}});
+        clamp_output_tokens(&mut body, &rules_small, "claude-sonnet-4", Some("claude")).unwrap();
+        assert_eq!(body["thinking"]["budget_tokens"], 1024);
+
+        // No room for the minimum budget: thinking is dropped.
+        let rules_small = vec![output_rule("claude-*", None, Some(1000))];
+        let mut body =
+            json!({"max_tokens": 8000, "thinking": {"type": "enabled", "budget_tokens": 4000}});
+        clamp_output_tokens(&mut body, &rules_small, "claude-sonnet-4", Some("claude")).unwrap();
+        assert_eq!(body["max_tokens"], 1000);
+        assert!(body.get("thinking").is_none());
+
+        let mut body = json!({"messages": []});
+        let clamp = clamp_output_tokens(&mut body, &rules, "claude-haiku", Some("claude")).unwrap();
+        assert_eq!(clamp.requested, None);
+        assert_eq!(body["max_tokens"], 4096);
+
+        // Within the cap: untouched.
+        let mut body = json!({"generationConfig": {"maxOutputTokens": 1024}});
+        assert!(clamp_output_tokens(&mut body, &rules, "gemini-2.5-pro", Some("gemini")).is_none());
+
+        // No default on the matching rule: nothing inserted.
+        let mut body = json!({"generationConfig": {}});
+        assert!(clamp_output_tokens(&mut body, &rules, "gemini-2.5-pro", Some("gemini")).is_none());
+        assert!(body["generationConfig"].get("maxOutputTokens").is_none());
+    }
+
+    #[test]
+    fn test_clamp_output_tokens_openai_fields() {
+        let rules = vec![output_rule("gpt-*", Some(2048), Some(8192))];
+
+        let mut chat = json!({"messages": [], "max_completion_tokens": 50000});
+        let clamp = clamp_output_tokens(&mut chat, &rules, "gpt-5", Some("openai")).unwrap();
+        assert_eq!(clamp.field, "max_completion_tokens");
+        assert_eq!(chat["max_completion_tokens"], 8192);
+
+        let mut responses = json!({"input": "hi"});
+        let clamp = clamp_output_tokens(&mut responses, &rules, "gpt-5", Some("openai")).unwrap();
+        assert_eq!(clamp.field, "max_output_tokens");
+        assert_eq!(responses["max_output_tokens"], 2048);
+
+        assert!(clamp_output_tokens(&mut json!({}), &rules, "o3", Some("openai")).is_none());
+    }
 }
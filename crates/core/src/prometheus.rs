@@ -1,6 +1,7 @@
 use crate::cache::CacheStats;
-use crate::metrics::Metrics;
+use crate::metrics::{DispatchPhase, Histogram, Metrics};
 use std::fmt::Write;
+use std::sync::atomic::Ordering;
 
 /// Write a Prometheus counter line.
 fn write_counter(out: &mut String, name: &str, labels: &str, value: u64) {
@@ -25,6 +26,25 @@ fn write_histogram_bucket(out: &mut String, name: &str, le: &str, count: u64) {
     let _ = writeln!(out, "{name}_bucket{{le=\"{le}\"}} {count}");
 }
 
+/// Write a full Prometheus histogram (cumulative buckets, `_sum`, `_count`).
+fn write_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} histogram");
+    let values = histogram.bucket_values();
+    let mut cumulative = 0u64;
+    for (i, &count) in values.iter().enumerate() {
+        cumulative += count;
+        let le = histogram
+            .bounds()
+            .get(i)
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "+Inf".to_string());
+        write_histogram_bucket(out, name, &le, cumulative);
+    }
+    let _ = writeln!(out, "{name}_sum {}", histogram.sum());
+    let _ = writeln!(out, "{name}_count {cumulative}");
+}
+
 /// Render all metrics in Prometheus text exposition format.
 pub fn render_metrics(
     metrics: &Metrics,
@@ -72,6 +92,19 @@ pub fn render_metrics(
         snap["total_errors"].as_u64().unwrap_or(0),
     );
 
+    // ── prism_upstream_retries_total ──
+    let _ = writeln!(
+        out,
+        "# HELP prism_upstream_retries_total Upstream attempts made after an earlier attempt failed."
+    );
+    let _ = writeln!(out, "# TYPE prism_upstream_retries_total counter");
+    write_counter(
+        &mut out,
+        "prism_upstream_retries_total",
+        "",
+        snap["total_retries"].as_u64().unwrap_or(0),
+    );
+
     // ── prism_tokens_total ──
     let _ = writeln!(out, "# HELP prism_tokens_total Total tokens processed.");
     let _ = writeln!(out, "# TYPE prism_tokens_total counter");
@@ -121,6 +154,81 @@ pub fn render_metrics(
     let total_reqs = snap["total_requests"].as_u64().unwrap_or(0);
     let _ = writeln!(out, "prism_request_duration_seconds_count {total_reqs}");
 
+    // ── Per-model / per-provider latency (bounded cardinality) ──
+    let mut wrote_header = false;
+    metrics.for_each_latency_series(|label, key, histogram| {
+        if !wrote_header {
+            let _ = writeln!(
+                out,
+                "# HELP prism_target_duration_seconds Upstream latency by model and provider."
+            );
+            let _ = writeln!(out, "# TYPE prism_target_duration_seconds histogram");
+            wrote_header = true;
+        }
+        let mut cumulative = 0u64;
+        for (i, count) in histogram.bucket_values().into_iter().enumerate() {
+            cumulative += count;
+            let le = histogram
+                .bounds()
+                .get(i)
+                .map(|ms| (*ms as f64 / 1000.0).to_string())
+                .unwrap_or_else(|| "+Inf".to_string());
+            let _ = writeln!(
+                out,
+                "prism_target_duration_seconds_bucket{{{label}=\"{key}\",le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "prism_target_duration_seconds_count{{{label}=\"{key}\"}} {cumulative}"
+        );
+    });
+
+    // ── Streamed output throughput ──
+    if let Some(by_provider) = snap["stream_throughput"].as_object()
+        && !by_provider.is_empty()
+    {
+        let _ = writeln!(
+            out,
+            "# HELP prism_stream_tokens_per_second Average streamed output tokens per second."
+        );
+        let _ = writeln!(out, "# TYPE prism_stream_tokens_per_second gauge");
+        for (provider, stats) in by_provider {
+            write_gauge(
+                &mut out,
+                "prism_stream_tokens_per_second",
+                &format!("provider=\"{provider}\""),
+                stats["tokens_per_sec"].as_f64().unwrap_or(0.0),
+            );
+        }
+    }
+
+    // ── Upstream attempts by provider and outcome ──
+    if let Some(by_provider) = snap["attempts_by_provider"].as_object()
+        && !by_provider.is_empty()
+    {
+        let _ = writeln!(
+            out,
+            "# HELP prism_upstream_attempts_total Upstream attempts by provider and outcome."
+        );
+        let _ = writeln!(out, "# TYPE prism_upstream_attempts_total counter");
+        for (provider, stats) in by_provider {
+            let attempts = stats["attempts"].as_u64().unwrap_or(0);
+            let failures = stats["failures"].as_u64().unwrap_or(0);
+            for (outcome, count) in [
+                ("success", attempts.saturating_sub(failures)),
+                ("failure", failures),
+            ] {
+                write_counter(
+                    &mut out,
+                    "prism_upstream_attempts_total",
+                    &format!("provider=\"{provider}\",outcome=\"{outcome}\""),
+                    count,
+                );
+            }
+        }
+    }
+
     // ── TTFT ──
     let ttft_buckets = metrics.ttft_bucket_values();
     if ttft_buckets.iter().any(|&v| v > 0) {
@@ -137,6 +245,64 @@ pub fn render_metrics(
         }
     }
 
+    // ── Workload shape ──
+    write_histogram(
+        &mut out,
+        "prism_request_body_bytes",
+        "Client request body size histogram.",
+        &metrics.request_bytes,
+    );
+    write_histogram(
+        &mut out,
+        "prism_prompt_tokens",
+        "Prompt tokens per request histogram.",
+        &metrics.prompt_tokens,
+    );
+    write_histogram(
+        &mut out,
+        "prism_completion_tokens",
+        "Completion tokens per request histogram.",
+        &metrics.completion_tokens,
+    );
+
+    // ── Dispatch phase durations ──
+    if DispatchPhase::ALL
+        .iter()
+        .any(|&phase| metrics.phase_histogram(phase).count() > 0)
+    {
+        let _ = writeln!(
+            out,
+            "# HELP prism_dispatch_phase_seconds Time spent in each dispatch phase."
+        );
+        let _ = writeln!(out, "# TYPE prism_dispatch_phase_seconds histogram");
+        for phase in DispatchPhase::ALL {
+            let histogram = metrics.phase_histogram(phase);
+            let name = phase.as_str();
+            let mut cumulative = 0u64;
+            for (i, count) in histogram.bucket_values().into_iter().enumerate() {
+                cumulative += count;
+                let le = histogram
+                    .bounds()
+                    .get(i)
+                    .map(|us| (*us as f64 / 1_000_000.0).to_string())
+                    .unwrap_or_else(|| "+Inf".to_string());
+                let _ = writeln!(
+                    out,
+                    "prism_dispatch_phase_seconds_bucket{{phase=\"{name}\",le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "prism_dispatch_phase_seconds_sum{{phase=\"{name}\"}} {}",
+                histogram.sum() as f64 / 1_000_000.0
+            );
+            let _ = writeln!(
+                out,
+                "prism_dispatch_phase_seconds_count{{phase=\"{name}\"}} {cumulative}"
+            );
+        }
+    }
+
     // ── prism_cache_hits_total / misses ──
     if let Some(stats) = cache_stats {
         let _ = writeln!(out, "# HELP prism_cache_hits_total Total cache hits.");
@@ -147,6 +313,91 @@ pub fn render_metrics(
         write_counter(&mut out, "prism_cache_misses_total", "", stats.misses);
     }
 
+    // ── prism_moderation_total ──
+    let _ = writeln!(
+        out,
+        "# HELP prism_moderation_total Moderation checks by outcome."
+    );
+    let _ = writeln!(out, "# TYPE prism_moderation_total counter");
+    for (outcome, counter) in [
+        ("checked", &metrics.moderation_checked),
+        ("flagged", &metrics.moderation_flagged),
+        ("blocked", &metrics.moderation_blocked),
+        ("error", &metrics.moderation_errors),
+    ] {
+        write_counter(
+            &mut out,
+            "prism_moderation_total",
+            &format!("outcome=\"{outcome}\""),
+            counter.load(Ordering::Relaxed),
+        );
+    }
+
+    // ── prism_stream_slow_client_total ──
+    let _ = writeln!(
+        out,
+        "# HELP prism_stream_slow_client_total Streams whose client buffer filled, by action."
+    );
+    let _ = writeln!(out, "# TYPE prism_stream_slow_client_total counter");
+    for (action, counter) in [
+        ("backpressure", &metrics.stream_backpressure_stalls),
+        ("disconnect", &metrics.stream_slow_client_disconnects),
+    ] {
+        write_counter(
+            &mut out,
+            "prism_stream_slow_client_total",
+            &format!("action=\"{action}\""),
+            counter.load(Ordering::Relaxed),
+        );
+    }
+
+    // ── prism_load_shed_total ──
+    let _ = writeln!(
+        out,
+        "# HELP prism_load_shed_total Requests rejected by concurrency caps, by cap."
+    );
+    let _ = writeln!(out, "# TYPE prism_load_shed_total counter");
+    for (cap, counter) in [
+        ("requests", &metrics.shed_requests),
+        ("streams", &metrics.shed_streams),
+    ] {
+        write_counter(
+            &mut out,
+            "prism_load_shed_total",
+            &format!("cap=\"{cap}\""),
+            counter.load(Ordering::Relaxed),
+        );
+    }
+
+    // ── prism_tag_requests_total / prism_tag_errors_total ──
+    if let Some(by_tag) = snap["by_tag"].as_object()
+        && !by_tag.is_empty()
+    {
+        for (name, field, help) in [
+            (
+                "prism_tag_requests_total",
+                "requests",
+                "Requests by auth key or provider tag.",
+            ),
+            (
+                "prism_tag_errors_total",
+                "errors",
+                "Failed requests by auth key or provider tag.",
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (tag, counts) in by_tag {
+                write_counter(
+                    &mut out,
+                    name,
+                    &format!("tag=\"{tag}\""),
+                    counts[field].as_u64().unwrap_or(0),
+                );
+            }
+        }
+    }
+
     // ── prism_circuit_breaker_open ──
     if !circuit_breaker_states.is_empty() {
         let _ = writeln!(
@@ -185,6 +436,89 @@ mod tests {
         assert!(output.contains("prism_request_duration_seconds"));
     }
 
+    #[test]
+    fn test_render_size_and_token_histograms() {
+        let metrics = Metrics::new();
+        metrics.record_request_bytes(2048);
+        metrics.record_tokens(300, 40);
+
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("# TYPE prism_request_body_bytes histogram"));
+        assert!(output.contains("prism_request_body_bytes_bucket{le=\"1024\"} 0"));
+        assert!(output.contains("prism_request_body_bytes_bucket{le=\"4096\"} 1"));
+        assert!(output.contains("prism_request_body_bytes_bucket{le=\"+Inf\"} 1"));
+        assert!(output.contains("prism_request_body_bytes_sum 2048"));
+        assert!(output.contains("prism_prompt_tokens_bucket{le=\"500\"} 1"));
+        assert!(output.contains("prism_completion_tokens_count 1"));
+    }
+
+    #[test]
+    fn test_render_target_latency() {
+        let metrics = Metrics::new();
+        metrics.record_target_latency_ms("gpt-4", "openai", 700);
+
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(
+            output.contains("prism_target_duration_seconds_bucket{model=\"gpt-4\",le=\"0.5\"} 0")
+        );
+        assert!(
+            output.contains("prism_target_duration_seconds_bucket{model=\"gpt-4\",le=\"1\"} 1")
+        );
+        assert!(output.contains("prism_target_duration_seconds_count{provider=\"openai\"} 1"));
+    }
+
+    #[test]
+    fn test_render_stream_throughput() {
+        let metrics = Metrics::new();
+        metrics.record_stream_throughput("openai", 40, std::time::Duration::from_secs(2));
+
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("prism_stream_tokens_per_second{provider=\"openai\"} 20"));
+    }
+
+    #[test]
+    fn test_render_upstream_attempts() {
+        let metrics = Metrics::new();
+        metrics.record_upstream_attempt("flaky", false, true);
+        metrics.record_upstream_attempt("backup", true, false);
+
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("prism_upstream_retries_total 1"));
+        assert!(
+            output.contains(
+                "prism_upstream_attempts_total{provider=\"flaky\",outcome=\"failure\"} 1"
+            )
+        );
+        assert!(
+            output.contains(
+                "prism_upstream_attempts_total{provider=\"backup\",outcome=\"success\"} 1"
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_dispatch_phases() {
+        let metrics = Metrics::new();
+        assert!(!render_metrics(&metrics, None, &[]).contains("prism_dispatch_phase_seconds"));
+
+        metrics.record_phase(
+            DispatchPhase::Translate,
+            std::time::Duration::from_micros(500),
+        );
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(
+            output.contains(
+                "prism_dispatch_phase_seconds_bucket{phase=\"translate\",le=\"0.0001\"} 0"
+            )
+        );
+        assert!(
+            output.contains(
+                "prism_dispatch_phase_seconds_bucket{phase=\"translate\",le=\"0.001\"} 1"
+            )
+        );
+        assert!(output.contains("prism_dispatch_phase_seconds_count{phase=\"first_byte\"} 0"));
+    }
+
     #[test]
     fn test_render_with_cache_stats() {
         let metrics = Metrics::new();
@@ -199,6 +533,33 @@ mod tests {
         assert!(output.contains("prism_cache_misses_total 8"));
     }
 
+    #[test]
+    fn test_render_slow_client_streams() {
+        let metrics = Metrics::new();
+        metrics.record_slow_client(true);
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("prism_stream_slow_client_total{action=\"backpressure\"} 0"));
+        assert!(output.contains("prism_stream_slow_client_total{action=\"disconnect\"} 1"));
+    }
+
+    #[test]
+    fn test_render_load_shed() {
+        let metrics = Metrics::new();
+        metrics.record_load_shed(false);
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("prism_load_shed_total{cap=\"requests\"} 1"));
+        assert!(output.contains("prism_load_shed_total{cap=\"streams\"} 0"));
+    }
+
+    #[test]
+    fn test_render_tag_counters() {
+        let metrics = Metrics::new();
+        metrics.record_tag_request(&["team-a".to_string()], true);
+        let output = render_metrics(&metrics, None, &[]);
+        assert!(output.contains("prism_tag_requests_total{tag=\"team-a\"} 1"));
+        assert!(output.contains("prism_tag_errors_total{tag=\"team-a\"} 1"));
+    }
+
     #[test]
     fn test_render_with_circuit_breaker() {
         let metrics = Metrics::new();
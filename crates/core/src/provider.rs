@@ -18,6 +18,7 @@ use prism_domain::capability::UpstreamProtocol;
 #[serde(rename_all = "kebab-case")]
 pub enum UpstreamKind {
     #[default]
+    #[serde(rename = "openai", alias = "open-a-i")]
     OpenAI,
     Codex,
     Claude,
@@ -101,6 +102,8 @@ pub struct AuthRecord {
     pub id: String,
     pub provider: Format,
     pub upstream: UpstreamKind,
+    /// Custom executor name from config; `None` dispatches by `upstream`.
+    pub executor: Option<String>,
     /// Provider name from config (used for routing identity).
     pub provider_name: String,
     pub api_key: String,
@@ -129,6 +132,10 @@ pub struct AuthRecord {
     pub weight: u32,
     /// Region for geo-aware routing.
     pub region: Option<String>,
+    /// Credential group; its position in `routing.credential-groups` is the tier.
+    pub group: Option<String>,
+    /// Daily/monthly usage caps.
+    pub quota: Option<crate::config::CredentialQuotaConfig>,
     /// Upstream presentation configuration.
     pub upstream_presentation: crate::presentation::UpstreamPresentationConfig,
     /// Whether this is a Vertex AI credential.
@@ -137,6 +144,15 @@ pub struct AuthRecord {
     pub vertex_project: Option<String>,
     /// Vertex AI location (e.g. "us-central1").
     pub vertex_location: Option<String>,
+    /// Claude server tools this credential may receive (`None` allows all).
+    pub allowed_server_tools: Option<Vec<String>>,
+    /// Client `anthropic-beta` flags this credential may receive (`None` allows all).
+    pub allowed_betas: Option<Vec<String>>,
+    /// HMAC request signing applied by the OpenAI-compatible executor.
+    pub signing: Option<crate::signing::RequestSigningConfig>,
+    /// Reassemble JSON payloads that this upstream splits or concatenates
+    /// across stream events.
+    pub json_repair: bool,
 }
 
 impl std::fmt::Debug for AuthRecord {
@@ -160,6 +176,13 @@ pub struct ModelEntry {
     pub alias: Option<String>,
 }
 
+impl ModelEntry {
+    /// Glob ids (`gpt-4*`) include matching models but are never listed or aliased.
+    pub fn is_pattern(&self) -> bool {
+        crate::glob::is_pattern(&self.id)
+    }
+}
+
 impl AuthRecord {
     /// Return the base URL or the provider's default.
     pub fn base_url_or_default(&self, default: &str) -> String {
@@ -170,6 +193,13 @@ impl AuthRecord {
             .to_string()
     }
 
+    /// Registry name of the executor that serves this credential.
+    pub fn executor_name(&self) -> &str {
+        self.executor
+            .as_deref()
+            .unwrap_or_else(|| self.upstream.as_str())
+    }
+
     /// Resolve base URL using the format's canonical default.
     pub fn resolved_base_url(&self) -> String {
         self.base_url_or_default(self.upstream.default_base_url())
@@ -287,6 +317,25 @@ impl AuthRecord {
             .any(|pattern| crate::glob::glob_match(pattern, model))
     }
 
+    /// Check if a Claude server tool (by name or versioned type) may be sent to this credential.
+    pub fn allows_server_tool(&self, name: &str, tool_type: &str) -> bool {
+        self.allowed_server_tools.as_ref().is_none_or(|allowed| {
+            allowed.iter().any(|pattern| {
+                crate::glob::glob_match(pattern, name)
+                    || crate::glob::glob_match(pattern, tool_type)
+            })
+        })
+    }
+
+    /// Check if a client `anthropic-beta` flag may be forwarded to this credential.
+    pub fn allows_beta(&self, beta: &str) -> bool {
+        self.allowed_betas.as_ref().is_none_or(|allowed| {
+            allowed
+                .iter()
+                .any(|pattern| crate::glob::glob_match(pattern, beta))
+        })
+    }
+
     /// Get human-readable name for this credential.
     pub fn name(&self) -> Option<&str> {
         self.credential_name.as_deref()
@@ -332,8 +381,8 @@ pub struct ProviderResponse {
 pub struct StreamChunk {
     /// SSE event type (e.g. "message_start" for Claude).
     pub event_type: Option<String>,
-    /// The JSON data payload.
-    pub data: String,
+    /// The JSON data payload, shared with the upstream body rather than copied.
+    pub data: Bytes,
 }
 
 /// The result of a streaming provider execution.
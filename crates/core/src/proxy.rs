@@ -1,6 +1,8 @@
+use crate::config::{DnsConfig, FaultInjectionConfig};
+use crate::dns::DnsResolver;
 use reqwest::{Client, Proxy};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 /// Default User-Agent for upstream requests.
@@ -17,12 +19,40 @@ const DEFAULT_USER_AGENT: &str = "prism/0.1.0";
 /// Cache key for pooled HTTP clients: (proxy_url, connect_timeout, request_timeout).
 type ClientKey = (Option<String>, u64, u64);
 
+/// Global upstream network settings (`proxy-url`, `connect-timeout`, `request-timeout`, `dns`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkSettings {
+    pub proxy_url: Option<String>,
+    pub connect_timeout_secs: u64,
+    pub request_timeout_secs: u64,
+    pub dns: DnsConfig,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy_url: None,
+            connect_timeout_secs: 30,
+            request_timeout_secs: 300,
+            dns: DnsConfig::default(),
+        }
+    }
+}
+
 /// A pool of reusable `reqwest::Client` instances keyed by transport configuration.
 /// `reqwest::Client` internally manages a connection pool, so reusing the same
 /// client for identical transport settings avoids repeated TLS handshakes and
 /// DNS resolution.
+///
+/// The pool also holds the current global [`NetworkSettings`] so executors pick up
+/// hot-reloaded proxy and timeout changes without being rebuilt, and the current
+/// [`FaultInjectionConfig`] applied to upstream calls.
 pub struct HttpClientPool {
     clients: RwLock<HashMap<ClientKey, Client>>,
+    network: RwLock<NetworkSettings>,
+    /// Custom resolver for non-default `dns` settings, shared by all clients.
+    resolver: RwLock<Option<DnsResolver>>,
+    faults: RwLock<Arc<FaultInjectionConfig>>,
 }
 
 impl Default for HttpClientPool {
@@ -33,11 +63,73 @@ impl Default for HttpClientPool {
 
 impl HttpClientPool {
     pub fn new() -> Self {
+        Self::with_network(NetworkSettings::default())
+    }
+
+    pub fn with_network(network: NetworkSettings) -> Self {
         Self {
             clients: RwLock::new(HashMap::new()),
+            resolver: RwLock::new(resolver_for(&network.dns)),
+            network: RwLock::new(network),
+            faults: RwLock::new(Arc::default()),
         }
     }
 
+    /// Current fault injection settings.
+    pub fn fault_injection(&self) -> Arc<FaultInjectionConfig> {
+        self.faults
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the fault injection settings.
+    pub fn set_fault_injection(&self, faults: FaultInjectionConfig) {
+        if let Ok(mut guard) = self.faults.write() {
+            *guard = Arc::new(faults);
+        }
+    }
+
+    /// Current global network settings.
+    pub fn network(&self) -> NetworkSettings {
+        self.network
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the global network settings. Cached clients are dropped when they change.
+    pub fn set_network(&self, network: NetworkSettings) {
+        let Ok(mut guard) = self.network.write() else {
+            return;
+        };
+        if *guard != network {
+            if guard.dns != network.dns
+                && let Ok(mut resolver) = self.resolver.write()
+            {
+                *resolver = resolver_for(&network.dns);
+            }
+            *guard = network;
+            drop(guard);
+            self.clear();
+        }
+    }
+
+    /// Get or create a client using the current global proxy and timeouts.
+    /// `entry_proxy` is the per-credential proxy override, if any.
+    pub fn get_or_create_current(
+        &self,
+        entry_proxy: Option<&str>,
+    ) -> Result<Client, anyhow::Error> {
+        let network = self.network();
+        self.get_or_create(
+            entry_proxy,
+            network.proxy_url.as_deref(),
+            network.connect_timeout_secs,
+            network.request_timeout_secs,
+        )
+    }
+
     /// Get or create a client for the given transport configuration.
     pub fn get_or_create(
         &self,
@@ -57,11 +149,13 @@ impl HttpClientPool {
         }
 
         // Slow path: build client and insert
-        let client = build_http_client_with_timeout(
+        let resolver = self.resolver.read().ok().and_then(|r| r.clone());
+        let client = build_client(
             entry_proxy,
             global_proxy,
             connect_timeout_secs,
             request_timeout_secs,
+            resolver,
         )?;
 
         if let Ok(mut guard) = self.clients.write() {
@@ -72,15 +166,6 @@ impl HttpClientPool {
         Ok(client)
     }
 
-    /// Get or create a client with default timeouts (30s connect, 300s request).
-    pub fn get_or_create_default(
-        &self,
-        entry_proxy: Option<&str>,
-        global_proxy: Option<&str>,
-    ) -> Result<Client, anyhow::Error> {
-        self.get_or_create(entry_proxy, global_proxy, 30, 300)
-    }
-
     /// Clear all cached clients (e.g., after config reload changes proxy settings).
     pub fn clear(&self) {
         if let Ok(mut guard) = self.clients.write() {
@@ -109,6 +194,27 @@ pub fn build_http_client_with_timeout(
     global_proxy: Option<&str>,
     connect_timeout_secs: u64,
     request_timeout_secs: u64,
+) -> Result<Client, anyhow::Error> {
+    build_client(
+        entry_proxy,
+        global_proxy,
+        connect_timeout_secs,
+        request_timeout_secs,
+        None,
+    )
+}
+
+/// The pool's resolver for `dns`, or `None` to keep reqwest's system resolver.
+fn resolver_for(dns: &DnsConfig) -> Option<DnsResolver> {
+    (*dns != DnsConfig::default()).then(|| DnsResolver::new(dns.clone()))
+}
+
+fn build_client(
+    entry_proxy: Option<&str>,
+    global_proxy: Option<&str>,
+    connect_timeout_secs: u64,
+    request_timeout_secs: u64,
+    resolver: Option<DnsResolver>,
 ) -> Result<Client, anyhow::Error> {
     let proxy_url = match entry_proxy {
         Some("") => None,       // Explicit direct connection
@@ -127,6 +233,9 @@ pub fn build_http_client_with_timeout(
     } else {
         builder = builder.no_proxy(); // Don't read system proxy env vars
     }
+    if let Some(resolver) = resolver {
+        builder = builder.dns_resolver(resolver);
+    }
 
     Ok(builder.build()?)
 }
@@ -195,4 +304,65 @@ mod tests {
         assert!(validate_proxy_url("ftp://proxy:21").is_err());
         assert!(validate_proxy_url("not-a-url").is_err());
     }
+
+    #[test]
+    fn test_set_network_replaces_settings_and_clears_clients() {
+        let pool = HttpClientPool::new();
+        pool.get_or_create_current(None).unwrap();
+        assert_eq!(pool.clients.read().unwrap().len(), 1);
+
+        // Unchanged settings keep cached clients.
+        pool.set_network(NetworkSettings::default());
+        assert_eq!(pool.clients.read().unwrap().len(), 1);
+
+        let next = NetworkSettings {
+            proxy_url: Some("http://proxy:8080".to_string()),
+            connect_timeout_secs: 5,
+            request_timeout_secs: 60,
+            dns: DnsConfig::default(),
+        };
+        pool.set_network(next.clone());
+        assert_eq!(pool.network(), next);
+        assert!(pool.clients.read().unwrap().is_empty());
+
+        pool.get_or_create_current(None).unwrap();
+        let keys: Vec<_> = pool.clients.read().unwrap().keys().cloned().collect();
+        assert_eq!(keys, vec![(Some("http://proxy:8080".to_string()), 5, 60)]);
+    }
+
+    #[tokio::test]
+    async fn test_dns_overrides_apply_to_pooled_clients() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let pool = HttpClientPool::new();
+        pool.set_network(NetworkSettings {
+            dns: DnsConfig {
+                overrides: HashMap::from([(
+                    "upstream.invalid".to_string(),
+                    vec!["127.0.0.1".parse().unwrap()],
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let client = pool.get_or_create_current(None).unwrap();
+        let resp = client
+            .get(format!("http://upstream.invalid:{port}/"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 204);
+    }
 }
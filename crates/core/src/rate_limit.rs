@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 use std::sync::{Mutex, RwLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::config::RateLimitConfig;
 
@@ -42,8 +47,11 @@ impl SlidingWindow {
         self.timestamps.iter().map(|&(_, amount)| amount).sum()
     }
 
-    fn record(&mut self, now: Instant, amount: u64) {
-        self.timestamps.push((now, amount));
+    /// Insert keeping timestamps in order, since usage replayed from other
+    /// instances can be older than local entries.
+    fn record(&mut self, at: Instant, amount: u64) {
+        let pos = self.timestamps.partition_point(|&(t, _)| t <= at);
+        self.timestamps.insert(pos, (at, amount));
     }
 
     fn estimate_reset(&self, now: Instant, window_secs: u64) -> u64 {
@@ -216,7 +224,16 @@ impl RateLimitDimension for SlidingWindowLimiter {
     }
 
     fn record(&self, key: Option<&str>, amount: u64) {
-        let now = Instant::now();
+        self.record_at(key, amount, Instant::now());
+    }
+
+    fn dimension_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl SlidingWindowLimiter {
+    fn record_at(&self, key: Option<&str>, amount: u64, now: Instant) {
         let global_limit = self.global_limit.read().map(|g| *g).unwrap_or(0);
 
         if global_limit > 0
@@ -252,10 +269,6 @@ impl RateLimitDimension for SlidingWindowLimiter {
             }
         }
     }
-
-    fn dimension_name(&self) -> &str {
-        &self.name
-    }
 }
 
 type CostEntries = Vec<(Instant, f64)>;
@@ -369,14 +382,21 @@ impl CostLimiter {
 
     /// Record cost for a key (in USD).
     pub fn record_cost(&self, key: &str, cost: f64) {
-        let now = Instant::now();
+        self.record_cost_at(key, cost, Instant::now());
+    }
+
+    fn record_cost_at(&self, key: &str, cost: f64, at: Instant) {
+        let insert = |entries: &mut CostEntries| {
+            let pos = entries.partition_point(|&(t, _)| t <= at);
+            entries.insert(pos, (at, cost));
+        };
         // Fast path
         {
             if let Ok(per_key) = self.per_key.read()
                 && let Some(entries) = per_key.get(key)
             {
                 if let Ok(mut entries) = entries.lock() {
-                    entries.push((now, cost));
+                    insert(&mut entries);
                 }
                 return;
             }
@@ -388,19 +408,64 @@ impl CostLimiter {
                     .entry(key.to_string())
                     .or_insert_with(|| Mutex::new(Vec::new()));
                 if let Ok(entries) = entries.get_mut() {
-                    entries.push((now, cost));
+                    insert(entries);
                 }
             }
         }
     }
 }
 
+/// Rate-limit dimension of a [`UsageRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UsageDimension {
+    Requests,
+    Tokens,
+    Cost,
+    Route,
+}
+
+/// Usage recorded by one instance, replayed by the others when state is shared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub dimension: UsageDimension,
+    /// [`key_digest`] of the API key, or route pattern for [`UsageDimension::Route`].
+    /// Raw keys never leave the instance.
+    pub key: Option<String>,
+    pub amount: f64,
+    /// When the usage was recorded, in Unix milliseconds.
+    pub at_ms: i64,
+}
+
+/// Stable digest of an API key, shared between replicas in place of the key.
+pub fn key_digest(key: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Fleet-wide spend per API key [digest](key_digest), read back from the shared
+/// store so budgets also count usage older than the replayed window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SharedSpend {
+    /// Spend in the current UTC day.
+    pub daily: HashMap<String, f64>,
+    /// Spend in the last 30 UTC days, today included.
+    pub monthly: HashMap<String, f64>,
+}
+
 /// Composite rate limiter — checks all dimensions, returns most restrictive.
 pub struct CompositeRateLimiter {
     rpm: SlidingWindowLimiter,
     tpm: SlidingWindowLimiter,
     cost: CostLimiter,
+    /// Per-route RPM windows keyed by the matching `routes` path pattern.
+    route_rpm: SlidingWindowLimiter,
     enabled: RwLock<bool>,
+    /// Local usage not yet taken for sharing; `None` while sharing is off.
+    shared_outbox: Mutex<Option<Vec<UsageRecord>>>,
+    /// Fleet spend for budgets, empty while sharing is off.
+    shared_spend: RwLock<SharedSpend>,
+    /// API key per [`key_digest`], to count usage replayed from peers.
+    known_keys: RwLock<HashMap<String, String>>,
 }
 
 impl CompositeRateLimiter {
@@ -414,7 +479,105 @@ impl CompositeRateLimiter {
             ),
             tpm: SlidingWindowLimiter::new("tpm", 60, config.global_tpm, config.per_key_tpm),
             cost: CostLimiter::new(config.per_key_cost_per_day_usd),
+            route_rpm: SlidingWindowLimiter::new("route_rpm", 60, 0, 0),
             enabled: RwLock::new(config.enabled),
+            shared_outbox: Mutex::new(None),
+            shared_spend: RwLock::new(SharedSpend::default()),
+            known_keys: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// API keys whose usage replayed from peers is counted here; usage for other
+    /// digests is ignored. Keys recorded locally are added as they are seen.
+    pub fn set_known_keys<'a>(&self, keys: impl IntoIterator<Item = &'a str>) {
+        let known = keys
+            .into_iter()
+            .map(|key| (key_digest(key), key.to_string()))
+            .collect();
+        if let Ok(mut known_keys) = self.known_keys.write() {
+            *known_keys = known;
+        }
+    }
+
+    /// Start collecting local usage for [`Self::take_shared_usage`].
+    pub fn share_usage(&self) {
+        if let Ok(mut outbox) = self.shared_outbox.lock() {
+            outbox.get_or_insert_with(Vec::new);
+        }
+    }
+
+    /// Local usage recorded since the last call.
+    pub fn take_shared_usage(&self) -> Vec<UsageRecord> {
+        self.shared_outbox
+            .lock()
+            .ok()
+            .and_then(|mut outbox| outbox.as_mut().map(std::mem::take))
+            .unwrap_or_default()
+    }
+
+    /// Count usage recorded by another instance as if it had happened here.
+    pub fn apply_shared_usage(&self, record: &UsageRecord) {
+        let age_ms = chrono::Utc::now().timestamp_millis() - record.at_ms;
+        let now = Instant::now();
+        let at = now
+            .checked_sub(Duration::from_millis(age_ms.max(0) as u64))
+            .unwrap_or(now);
+        let key = match (record.dimension, record.key.as_deref()) {
+            (UsageDimension::Route, key) | (_, key @ None) => key.map(str::to_string),
+            (_, Some(digest)) => {
+                let Some(key) = self
+                    .known_keys
+                    .read()
+                    .ok()
+                    .and_then(|known| known.get(digest).cloned())
+                else {
+                    return;
+                };
+                Some(key)
+            }
+        };
+        let key = key.as_deref();
+        match record.dimension {
+            UsageDimension::Requests => self.rpm.record_at(key, record.amount as u64, at),
+            UsageDimension::Tokens => self.tpm.record_at(key, record.amount as u64, at),
+            UsageDimension::Route => self.route_rpm.record_at(key, record.amount as u64, at),
+            UsageDimension::Cost => {
+                if let Some(key) = key {
+                    self.cost.record_cost_at(key, record.amount, at);
+                }
+            }
+        }
+    }
+
+    /// Replace the fleet spend that budgets are checked against.
+    pub fn set_shared_spend(&self, spend: SharedSpend) {
+        if let Ok(mut shared) = self.shared_spend.write() {
+            *shared = spend;
+        }
+    }
+
+    fn share(&self, dimension: UsageDimension, key: Option<&str>, amount: f64) {
+        if let Ok(mut outbox) = self.shared_outbox.lock()
+            && let Some(outbox) = outbox.as_mut()
+        {
+            let key = match (dimension, key) {
+                (UsageDimension::Route, key) | (_, key @ None) => key.map(str::to_string),
+                (_, Some(key)) => {
+                    let digest = key_digest(key);
+                    if let Ok(mut known) = self.known_keys.write() {
+                        known
+                            .entry(digest.clone())
+                            .or_insert_with(|| key.to_string());
+                    }
+                    Some(digest)
+                }
+            };
+            outbox.push(UsageRecord {
+                dimension,
+                key,
+                amount,
+                at_ms: chrono::Utc::now().timestamp_millis(),
+            });
         }
     }
 
@@ -470,6 +633,7 @@ impl CompositeRateLimiter {
             return;
         }
         self.rpm.record(api_key, 1);
+        self.share(UsageDimension::Requests, api_key, 1.0);
     }
 
     /// Record tokens (TPM dimension). Call after response is received.
@@ -478,6 +642,19 @@ impl CompositeRateLimiter {
             return;
         }
         self.tpm.record(api_key, tokens);
+        self.share(UsageDimension::Tokens, api_key, tokens as f64);
+    }
+
+    /// Check a per-route RPM override. Route limits apply even when global rate
+    /// limiting is disabled, since they are configured explicitly per route.
+    pub fn check_route(&self, route: &str, rpm: u32) -> RateLimitInfo {
+        self.route_rpm.check_key_with_limit(route, rpm as u64)
+    }
+
+    /// Record a request against a per-route RPM window.
+    pub fn record_route(&self, route: &str) {
+        self.route_rpm.record(Some(route), 1);
+        self.share(UsageDimension::Route, Some(route), 1.0);
     }
 
     /// Check per-key rate limit overrides from AuthKeyEntry config.
@@ -518,8 +695,33 @@ impl CompositeRateLimiter {
             crate::auth_key::BudgetPeriod::Daily => 86400u64,
             crate::auth_key::BudgetPeriod::Monthly => 30 * 86400u64,
         };
-        self.cost
-            .check_cost_within_window(key, budget.total_usd, window_secs)
+        let info = self
+            .cost
+            .check_cost_within_window(key, budget.total_usd, window_secs);
+        if !info.allowed || budget.total_usd <= 0.0 {
+            return info;
+        }
+        let digest = key_digest(key);
+        let shared = self
+            .shared_spend
+            .read()
+            .ok()
+            .and_then(|spend| match budget.period {
+                crate::auth_key::BudgetPeriod::Daily => spend.daily.get(&digest).copied(),
+                crate::auth_key::BudgetPeriod::Monthly => spend.monthly.get(&digest).copied(),
+            })
+            .unwrap_or(0.0);
+        if shared >= budget.total_usd {
+            // Shared spend is kept per UTC day, so the oldest day drops out at midnight.
+            let into_day = chrono::Utc::now().timestamp().rem_euclid(86400) as u64;
+            return RateLimitInfo {
+                allowed: false,
+                remaining: 0,
+                limit: (budget.total_usd * 100.0) as u32,
+                reset_secs: 86400 - into_day,
+            };
+        }
+        info
     }
 
     /// Record cost (Cost dimension). Call after response is received.
@@ -529,6 +731,7 @@ impl CompositeRateLimiter {
         }
         if let Some(key) = api_key {
             self.cost.record_cost(key, cost);
+            self.share(UsageDimension::Cost, Some(key), cost);
         }
     }
 }
@@ -598,6 +801,82 @@ mod tests {
         assert!(info.allowed);
     }
 
+    #[test]
+    fn test_budget_counts_shared_spend() {
+        let limiter = CompositeRateLimiter::new(&RateLimitConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let budget = |period| crate::auth_key::BudgetConfig {
+            total_usd: 10.0,
+            period,
+        };
+        limiter.record_cost(Some("key1"), 2.0);
+        assert!(
+            limiter
+                .check_budget("key1", &budget(crate::auth_key::BudgetPeriod::Monthly))
+                .allowed
+        );
+
+        limiter.set_shared_spend(SharedSpend {
+            daily: HashMap::from([(key_digest("key1"), 4.0)]),
+            monthly: HashMap::from([(key_digest("key1"), 12.0)]),
+        });
+        let info = limiter.check_budget("key1", &budget(crate::auth_key::BudgetPeriod::Monthly));
+        assert!(!info.allowed);
+        assert!(info.reset_secs > 0 && info.reset_secs <= 86400);
+        assert!(
+            limiter
+                .check_budget("key1", &budget(crate::auth_key::BudgetPeriod::Daily))
+                .allowed
+        );
+        assert!(
+            limiter
+                .check_budget("key2", &budget(crate::auth_key::BudgetPeriod::Monthly))
+                .allowed
+        );
+    }
+
+    #[test]
+    fn test_shared_usage_replayed_on_peer() {
+        let config = RateLimitConfig {
+            enabled: true,
+            per_key_rpm: 2,
+            ..Default::default()
+        };
+        let local = CompositeRateLimiter::new(&config);
+        let peer = CompositeRateLimiter::new(&config);
+        local.record_request(Some("key1"));
+        assert!(local.take_shared_usage().is_empty(), "sharing is off");
+
+        local.share_usage();
+        local.record_request(Some("key1"));
+        local.record_request(Some("key1"));
+        let shared = local.take_shared_usage();
+        assert_eq!(shared.len(), 2);
+        assert!(local.take_shared_usage().is_empty());
+        assert_eq!(shared[0].key, Some(key_digest("key1")));
+
+        // Usage for a key the peer does not know is ignored.
+        for record in &shared {
+            peer.apply_shared_usage(record);
+        }
+        assert!(peer.check(Some("key1")).allowed);
+        peer.set_known_keys(["key1"]);
+
+        // Usage older than the window is replayed but no longer counts.
+        let mut stale = shared[0].clone();
+        stale.at_ms -= 120_000;
+        peer.apply_shared_usage(&stale);
+        assert!(peer.check(Some("key1")).allowed);
+
+        for record in &shared {
+            peer.apply_shared_usage(record);
+        }
+        assert!(!peer.check(Some("key1")).allowed);
+        assert!(peer.take_shared_usage().is_empty());
+    }
+
     #[test]
     fn test_tpm_limit() {
         let config = RateLimitConfig {
@@ -743,4 +1022,15 @@ mod tests {
         let info = limiter.check(Some("key1"));
         assert!(!info.allowed);
     }
+
+    #[test]
+    fn test_route_rpm_applies_without_global_limits() {
+        let limiter = CompositeRateLimiter::new(&RateLimitConfig::default());
+        assert!(limiter.check_route("/v1/audio/*", 2).allowed);
+        limiter.record_route("/v1/audio/*");
+        limiter.record_route("/v1/audio/*");
+        assert!(!limiter.check_route("/v1/audio/*", 2).allowed);
+        // Other routes keep their own window.
+        assert!(limiter.check_route("/v1/embeddings", 2).allowed);
+    }
 }
@@ -23,7 +23,7 @@ pub enum SortOrder {
 
 // ── Query ──
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LogQuery {
     pub page: Option<usize>,
     pub page_size: Option<usize>,
@@ -40,6 +40,8 @@ pub struct LogQuery {
     pub status: Option<String>,
     pub error_type: Option<String>,
     pub stream: Option<bool>,
+    /// Comma-separated; a record must carry every listed tag.
+    pub tags: Option<String>,
 
     // Range
     pub from: Option<i64>,
@@ -57,7 +59,7 @@ pub struct LogQuery {
 
 // ── Paged response ──
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogPage {
     pub data: Vec<RequestRecord>,
     pub total: usize,
@@ -71,15 +73,17 @@ pub struct LogPage {
 
 // ── Stats ──
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StatsQuery {
     pub from: Option<i64>,
     pub to: Option<i64>,
     pub provider: Option<String>,
     pub model: Option<String>,
+    /// Comma-separated; only records carrying every listed tag are counted.
+    pub tags: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogStats {
     pub total_entries: usize,
     pub error_count: usize,
@@ -96,7 +100,7 @@ pub struct LogStats {
     pub status_distribution: StatusDistribution,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TimeSeriesBucket {
     pub timestamp: String,
     pub requests: u64,
@@ -106,7 +110,7 @@ pub struct TimeSeriesBucket {
     pub cost: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ModelStats {
     pub model: String,
     pub requests: u64,
@@ -115,35 +119,141 @@ pub struct ModelStats {
     pub total_cost: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorStats {
     pub error_type: String,
     pub count: u64,
     pub last_seen: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ProviderDistribution {
     pub provider: String,
     pub requests: u64,
     pub percentage: f64,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct StatusDistribution {
     pub success: u64,
     pub client_error: u64,
     pub server_error: u64,
 }
 
+// ── Error groups ──
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorGroupQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// Comma-separated; only records carrying every listed tag are grouped.
+    pub tags: Option<String>,
+    /// Maximum number of groups returned (default 50).
+    pub limit: Option<usize>,
+}
+
+/// Failed requests sharing an error type, provider, model, and status.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorGroup {
+    pub error_type: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub status: u16,
+    pub count: u64,
+    pub last_seen: String,
+    /// Most recent request IDs in the group, newest first.
+    pub example_request_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorGroups {
+    pub total_errors: usize,
+    /// Sorted by count, then by most recently seen.
+    pub groups: Vec<ErrorGroup>,
+}
+
+// ── Tag costs ──
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagCostQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Only report tags starting with this prefix (e.g. `team:`).
+    pub prefix: Option<String>,
+}
+
+/// Spend and tokens attributed to one tag.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TagCost {
+    pub tag: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagCostReport {
+    pub total_requests: u64,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+    /// Requests carrying several tags count in full under each of them, so
+    /// these can sum to more than the totals. Sorted by cost, then by tag.
+    pub tags: Vec<TagCost>,
+    /// Requests without a (matching) tag, reported under an empty `tag`.
+    pub untagged: TagCost,
+    /// Start of the earliest hour counted (Unix milliseconds), `None` when
+    /// nothing was. Usage older than the hourly rollup's retention is not reported.
+    pub since: Option<i64>,
+}
+
+// ── Usage summary ──
+
+/// Totals for a time window with per-model and per-key breakdowns, used by
+/// scheduled usage reports.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub requests: u64,
+    pub errors: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+    /// Sorted by cost, then by requests.
+    pub by_model: Vec<UsageBreakdown>,
+    /// Keyed by masked API key; requests without a key are under `-`.
+    pub by_api_key: Vec<UsageBreakdown>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageBreakdown {
+    pub name: String,
+    pub requests: u64,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
 // ── Filter options ──
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct FilterOptions {
     pub providers: Vec<String>,
     pub models: Vec<String>,
     pub error_types: Vec<String>,
     pub tenant_ids: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+// ── Buffer usage ──
+
+/// Occupancy of an in-memory log buffer.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct BufferUsage {
+    pub entries: usize,
+    pub capacity: usize,
+    /// Records plus their bodies and labels; allocator overhead is not included.
+    pub approx_bytes: u64,
 }
 
 // ── Trait ──
@@ -162,6 +272,17 @@ pub trait LogStore: Send + Sync {
     /// Aggregated statistics over a time range.
     async fn stats(&self, q: &StatsQuery) -> LogStats;
 
+    /// Failed requests grouped by error type, provider, model, and status.
+    async fn error_groups(&self, q: &ErrorGroupQuery) -> ErrorGroups;
+
+    /// Spend and tokens per tag over a time range.
+    async fn cost_by_tag(&self, q: &TagCostQuery) -> TagCostReport;
+
+    /// Usage of the UTC hours starting between `from` and `to` (Unix
+    /// milliseconds), with at most `top` rows per breakdown. Counts every
+    /// logged request, including those the store no longer holds.
+    async fn usage_summary(&self, from: i64, to: i64, top: usize) -> UsageSummary;
+
     /// Distinct values available for filter dropdowns.
     async fn filter_options(&self) -> FilterOptions;
 
@@ -170,4 +291,9 @@ pub trait LogStore: Send + Sync {
 
     /// Update usage and cost for a streaming request after completion.
     async fn update_usage(&self, request_id: &str, usage: TokenUsage, cost: Option<f64>);
+
+    /// Occupancy of the in-memory buffer, for stores that keep one.
+    fn buffer_usage(&self) -> Option<BufferUsage> {
+        None
+    }
 }
@@ -59,6 +59,26 @@ pub struct AttemptSummary {
     pub latency_ms: u64,
     pub error: Option<String>,
     pub error_type: Option<String>,
+    /// Phase breakdown, kept only for slow requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<PhaseTimings>,
+}
+
+/// Per-phase timings captured for requests over `log-store.slow-request-threshold-ms`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTimings {
+    /// Credential preparation (e.g., OAuth token refresh).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_ms: Option<u64>,
+    /// Request translation and payload rewriting before the upstream call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translate_ms: Option<u64>,
+    /// Upstream call until the response starts (the full body for non-stream requests).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream_ttfb_ms: Option<u64>,
+    /// Streaming from the first upstream response until the stream ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_ms: Option<u64>,
 }
 
 /// A single request record used for both in-memory log store and persistent audit.
@@ -66,6 +86,9 @@ pub struct AttemptSummary {
 pub struct RequestRecord {
     // ── Identity ──
     pub request_id: String,
+    /// W3C trace id shared with the client and upstream (`traceparent`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
     pub timestamp: DateTime<Utc>,
 
     // ── Request ──
@@ -120,9 +143,36 @@ pub struct RequestRecord {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub client_region: Option<String>,
 
+    // ── Experiment ──
+    /// A/B experiment the request was assigned to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experiment: Option<String>,
+    /// Arm within the experiment (`control` or `variant`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experiment_arm: Option<String>,
+
+    // ── Tags ──
+    /// Tags of the API key, then of the provider that served the request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     // ── Per-attempt details ──
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub attempts: Vec<AttemptSummary>,
+
+    // ── Slow request capture ──
+    /// Total duration reached `log-store.slow-request-threshold-ms`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub slow: bool,
+    /// Phase breakdown for slow requests: auth and translate summed over attempts,
+    /// upstream TTFB of the final attempt, and stream duration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<PhaseTimings>,
+
+    // ── Stream limits ──
+    /// Which `streaming.limits` cap cut the stream short (`max-bytes` or `max-duration`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_truncated: Option<String>,
 }
 
 /// Truncate a body string to `max_bytes`, appending "...[truncated]" if truncated.
@@ -154,6 +204,8 @@ pub fn classify_error(error: &crate::error::ProxyError) -> &'static str {
         ProxyError::ModelCooldown { .. } | ProxyError::RateLimited { .. } => "rate_limited",
         ProxyError::Translation(_) => "translation",
         ProxyError::BadRequest(_) => "bad_request",
+        ProxyError::ContentBlocked(_) => "content_blocked",
+        ProxyError::Overloaded { .. } => "overloaded",
         _ => "internal",
     }
 }
@@ -205,6 +257,7 @@ mod tests {
     fn request_record_serialization_roundtrip() {
         let record = RequestRecord {
             request_id: "req-123".to_string(),
+            trace_id: None,
             timestamp: Utc::now(),
             method: "POST".to_string(),
             path: "/v1/chat/completions".to_string(),
@@ -233,7 +286,13 @@ mod tests {
             tenant_id: Some("alpha".to_string()),
             client_ip: Some("1.2.3.4".to_string()),
             client_region: None,
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         };
         let json = serde_json::to_string(&record).unwrap();
         let deserialized: RequestRecord = serde_json::from_str(&json).unwrap();
@@ -285,6 +344,7 @@ mod tests {
             latency_ms: 50,
             error: Some("rate limited".to_string()),
             error_type: Some("rate_limited".to_string()),
+            timings: None,
         };
         let json = serde_json::to_string(&summary).unwrap();
         let de: AttemptSummary = serde_json::from_str(&json).unwrap();
@@ -16,6 +16,10 @@ pub struct RoutingConfig {
     /// Model resolution config (aliases, rewrites, fallbacks, provider pins).
     #[serde(default)]
     pub model_resolution: ModelResolution,
+    /// Credential groups in priority order. A lower tier is only used once
+    /// every credential in the tiers before it is unavailable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub credential_groups: Vec<CredentialGroup>,
 }
 
 impl Default for RoutingConfig {
@@ -25,6 +29,7 @@ impl Default for RoutingConfig {
             profiles: Self::default_profiles(),
             rules: Vec::new(),
             model_resolution: ModelResolution::default(),
+            credential_groups: Vec::new(),
         }
     }
 }
@@ -64,8 +69,63 @@ impl RoutingConfig {
                 .validate()
                 .map_err(|e| format!("profile '{}': {}", name, e))?;
         }
+        self.validate_credential_groups()
+    }
+
+    /// Credential group names must be non-empty and unique.
+    pub fn validate_credential_groups(&self) -> Result<(), String> {
+        for (i, group) in self.credential_groups.iter().enumerate() {
+            if group.name.trim().is_empty() {
+                return Err("credential-groups names must not be empty".to_string());
+            }
+            if self.credential_groups[..i]
+                .iter()
+                .any(|earlier| earlier.name == group.name)
+            {
+                return Err(format!("credential group '{}' is listed twice", group.name));
+            }
+        }
         Ok(())
     }
+
+    /// Whether `name` is listed in `credential-groups`.
+    pub fn has_credential_group(&self, name: &str) -> bool {
+        self.credential_groups
+            .iter()
+            .any(|group| group.name == name)
+    }
+
+    /// Priority tier of a credential group (0 = first). Credentials without a
+    /// listed group share the tier after the last listed one.
+    pub fn credential_tier(&self, group: Option<&str>) -> usize {
+        credential_tier(&self.credential_groups, group)
+    }
+}
+
+/// Priority tier of `group` within `groups`; unlisted groups rank last.
+pub fn credential_tier(groups: &[CredentialGroup], group: Option<&str>) -> usize {
+    group
+        .and_then(|g| groups.iter().position(|listed| listed.name == g))
+        .unwrap_or(groups.len())
+}
+
+/// One credential tier in `routing.credential-groups`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CredentialGroup {
+    pub name: String,
+    /// Credential strategy within this tier; unset uses the default profile's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<CredentialStrategy>,
+}
+
+impl CredentialGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            strategy: None,
+        }
+    }
 }
 
 // ─── Route profile ──────────────────────────────────────────────────────────
@@ -77,6 +137,7 @@ pub struct RouteProfile {
     pub credential_policy: CredentialPolicy,
     pub health: HealthConfig,
     pub failover: FailoverConfig,
+    pub race: RaceConfig,
 }
 
 impl Default for RouteProfile {
@@ -94,6 +155,7 @@ impl RouteProfile {
             },
             credential_policy: CredentialPolicy {
                 strategy: CredentialStrategy::PriorityWeightedRR,
+                ..Default::default()
             },
             health: HealthConfig::default(),
             failover: FailoverConfig {
@@ -102,6 +164,7 @@ impl RouteProfile {
                 model_attempts: 2,
                 ..Default::default()
             },
+            race: RaceConfig::default(),
         }
     }
 
@@ -113,6 +176,7 @@ impl RouteProfile {
             },
             credential_policy: CredentialPolicy {
                 strategy: CredentialStrategy::FillFirst,
+                ..Default::default()
             },
             health: HealthConfig::default(),
             failover: FailoverConfig {
@@ -121,6 +185,7 @@ impl RouteProfile {
                 model_attempts: 1,
                 ..Default::default()
             },
+            race: RaceConfig::default(),
         }
     }
 
@@ -132,6 +197,7 @@ impl RouteProfile {
             },
             credential_policy: CredentialPolicy {
                 strategy: CredentialStrategy::LeastInflight,
+                ..Default::default()
             },
             health: HealthConfig::default(),
             failover: FailoverConfig {
@@ -140,6 +206,7 @@ impl RouteProfile {
                 model_attempts: 1,
                 ..Default::default()
             },
+            race: RaceConfig::default(),
         }
     }
 
@@ -151,6 +218,7 @@ impl RouteProfile {
             },
             credential_policy: CredentialPolicy {
                 strategy: CredentialStrategy::PriorityWeightedRR,
+                ..Default::default()
             },
             health: HealthConfig::default(),
             failover: FailoverConfig {
@@ -159,11 +227,16 @@ impl RouteProfile {
                 model_attempts: 1,
                 ..Default::default()
             },
+            race: RaceConfig::default(),
         }
     }
 
     pub fn validate(&self) -> Result<(), String> {
         self.provider_policy.validate()?;
+        self.credential_policy.validate()?;
+        if self.race.enabled && self.race.fanout < 2 {
+            return Err("race fanout must be at least 2".to_string());
+        }
         Ok(())
     }
 }
@@ -201,10 +274,8 @@ impl ProviderPolicy {
             ProviderStrategy::OrderedFallback => {
                 // Empty order is valid — means "all providers in config order"
             }
-            ProviderStrategy::StickyHash => {
-                if self.sticky_key.is_none() {
-                    return Err("sticky-hash strategy requires 'sticky-key' to be set".to_string());
-                }
+            ProviderStrategy::StickyHash if self.sticky_key.is_none() => {
+                return Err("sticky-hash strategy requires 'sticky-key' to be set".to_string());
             }
             _ => {}
         }
@@ -229,12 +300,59 @@ pub enum ProviderStrategy {
 #[serde(rename_all = "kebab-case", default)]
 pub struct CredentialPolicy {
     pub strategy: CredentialStrategy,
+    /// Request attribute hashed by `sticky-hash`: `api-key` (default),
+    /// `tenant-id`, or `header:<name>`.
+    pub sticky_key: Option<String>,
 }
 
 impl Default for CredentialPolicy {
     fn default() -> Self {
         Self {
             strategy: CredentialStrategy::PriorityWeightedRR,
+            sticky_key: None,
+        }
+    }
+}
+
+/// Request attribute that `sticky-hash` credential selection hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StickyKey<'a> {
+    ApiKey,
+    TenantId,
+    /// Inbound header, matched case-insensitively.
+    Header(&'a str),
+}
+
+impl CredentialPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(key) = &self.sticky_key {
+            StickyKey::parse(key).ok_or_else(|| {
+                format!(
+                    "credential sticky-key '{key}' must be 'api-key', 'tenant-id', or 'header:<name>'"
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The attribute hashed by `sticky-hash`; unparseable keys fall back to the API key.
+    pub fn sticky_key(&self) -> StickyKey<'_> {
+        self.sticky_key
+            .as_deref()
+            .and_then(StickyKey::parse)
+            .unwrap_or(StickyKey::ApiKey)
+    }
+}
+
+impl<'a> StickyKey<'a> {
+    fn parse(key: &'a str) -> Option<Self> {
+        match key {
+            "api-key" => Some(Self::ApiKey),
+            "tenant-id" => Some(Self::TenantId),
+            _ => {
+                let name = key.strip_prefix("header:")?.trim();
+                (!name.is_empty()).then_some(Self::Header(name))
+            }
         }
     }
 }
@@ -356,6 +474,27 @@ pub enum RetryCondition {
     ServerError,
 }
 
+// ─── Race dispatch ──────────────────────────────────────────────────────────
+
+/// Concurrent fan-out: send the request to several planned attempts at once and
+/// keep the first success (first token for streams), canceling the rest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct RaceConfig {
+    pub enabled: bool,
+    /// Number of attempts dispatched concurrently.
+    pub fanout: u32,
+}
+
+impl Default for RaceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fanout: 2,
+        }
+    }
+}
+
 // ─── Route rules ────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -716,6 +855,38 @@ model-resolution:
         assert!(err.contains("sticky-key"));
     }
 
+    #[test]
+    fn test_validate_credential_sticky_key() {
+        let mut profile = RouteProfile::default();
+        profile.credential_policy.sticky_key = Some("header:x-session-id".to_string());
+        assert!(profile.validate().is_ok());
+        assert_eq!(
+            profile.credential_policy.sticky_key(),
+            StickyKey::Header("x-session-id")
+        );
+        profile.credential_policy.sticky_key = Some("cookie".to_string());
+        assert!(profile.validate().unwrap_err().contains("sticky-key"));
+        profile.credential_policy.sticky_key = Some("header: ".to_string());
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_race_fanout() {
+        let mut config = RoutingConfig::default();
+        config.profiles.insert(
+            "race".to_string(),
+            RouteProfile {
+                race: RaceConfig {
+                    enabled: true,
+                    fanout: 1,
+                },
+                ..Default::default()
+            },
+        );
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("fanout"));
+    }
+
     #[test]
     fn test_resolve_model_rewrite_alias() {
         let config = RoutingConfig {
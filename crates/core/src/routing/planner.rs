@@ -1,4 +1,4 @@
-use super::config::{ProviderStrategy, RouteProfile};
+use super::config::{CredentialStrategy, ProviderStrategy, RouteProfile, StickyKey};
 use super::match_engine;
 use super::model_resolver;
 use super::types::*;
@@ -35,6 +35,25 @@ pub struct CredentialEntry {
     pub region: Option<String>,
     pub weight: u32,
     pub disabled: bool,
+    /// Credential group, ranked by `RoutingConfig::credential_groups`.
+    pub group: Option<String>,
+    /// Model name prefix (e.g. `myteam/`) stripped before matching `models`.
+    pub prefix: Option<String>,
+}
+
+impl CredentialEntry {
+    /// The model name this credential would serve for `model`, or `None` when the
+    /// model carries another credential's prefix (`foreign_prefix`).
+    fn effective_model<'a>(&self, model: &'a str, foreign_prefix: bool) -> Option<&'a str> {
+        if let Some(stripped) = self
+            .prefix
+            .as_deref()
+            .and_then(|prefix| model.strip_prefix(prefix))
+        {
+            return Some(stripped);
+        }
+        (!foreign_prefix).then_some(model)
+    }
 }
 
 // ─── Health snapshot ───────────────────────────────────────────────────────
@@ -125,15 +144,23 @@ impl RoutePlanner {
             .collect();
         trace.rejections = all_rejections;
 
-        // 4. Score and rank candidates
-        let mut scored = score_candidates(&all_candidates, profile, health);
+        // 4. Score and rank candidates. Credential tiers come first: a lower
+        // tier is only attempted after every candidate of the tiers above it.
+        let mut scored = score_candidates(&all_candidates, config, profile, health);
         // Stable sort to preserve deterministic ordering
         scored.sort_by(|a, b| {
-            b.score
-                .weight
-                .partial_cmp(&a.score.weight)
-                .unwrap_or(std::cmp::Ordering::Equal)
+            a.tier.cmp(&b.tier).then_with(|| {
+                b.score
+                    .weight
+                    .partial_cmp(&a.score.weight)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
         });
+        if profile.credential_policy.strategy == CredentialStrategy::StickyHash
+            && let Some(sticky) = sticky_value(features, profile.credential_policy.sticky_key())
+        {
+            order_sticky(&mut scored, sticky);
+        }
 
         // Assign ranks
         for (i, entry) in scored.iter_mut().enumerate() {
@@ -198,6 +225,7 @@ struct CandidateInfo {
     model: String,
     weight: u32,
     _region: Option<String>,
+    group: Option<String>,
     upstream_protocol: prism_domain::capability::UpstreamProtocol,
 }
 
@@ -210,6 +238,7 @@ struct ScoredCandidate {
     model: String,
     score: RouteScore,
     rank: u32,
+    tier: usize,
     upstream_protocol: prism_domain::capability::UpstreamProtocol,
 }
 
@@ -235,6 +264,13 @@ fn collect_candidates(
     candidates: &mut Vec<CandidateInfo>,
     rejections: &mut Vec<RouteRejection>,
 ) {
+    let prefixed = inventory
+        .providers
+        .iter()
+        .flat_map(|p| &p.credentials)
+        .filter_map(|c| c.prefix.as_deref())
+        .any(|prefix| model.starts_with(prefix));
+
     for provider in &inventory.providers {
         // Check provider pin
         if pinned_providers
@@ -284,10 +320,14 @@ fn collect_candidates(
                 continue;
             }
 
-            // Model support
-            let supports =
-                cred.models.is_empty() || cred.models.iter().any(|m| glob_match(m, model));
-            let excluded = cred.excluded_models.iter().any(|m| glob_match(m, model));
+            // Model support; prefixed names only reach the credentials owning the prefix
+            let (supports, excluded) = match cred.effective_model(model, prefixed) {
+                Some(model) => (
+                    cred.models.is_empty() || cred.models.iter().any(|m| glob_match(m, model)),
+                    cred.excluded_models.iter().any(|m| glob_match(m, model)),
+                ),
+                None => (false, false),
+            };
             if !supports || excluded {
                 rejections.push(RouteRejection {
                     candidate: cand_label(),
@@ -341,6 +381,7 @@ fn collect_candidates(
                 model: model.to_string(),
                 weight: cred.weight,
                 _region: cred.region.clone(),
+                group: cred.group.clone(),
                 upstream_protocol: provider.upstream_protocol,
             });
         }
@@ -349,6 +390,7 @@ fn collect_candidates(
 
 fn score_candidates(
     candidates: &[CandidateInfo],
+    config: &RoutingConfig,
     profile: &RouteProfile,
     health: &HealthSnapshot,
 ) -> Vec<ScoredCandidate> {
@@ -375,6 +417,7 @@ fn score_candidates(
                     health_penalty: 0.0,
                 },
                 rank: 0,
+                tier: config.credential_tier(c.group.as_deref()),
                 upstream_protocol: c.upstream_protocol,
             }
         })
@@ -425,10 +468,84 @@ fn compute_weight(
     }
 }
 
+/// The request attribute `sticky-hash` hashes, if the request carries it.
+fn sticky_value<'a>(features: &'a RouteRequestFeatures, key: StickyKey<'_>) -> Option<&'a str> {
+    match key {
+        StickyKey::ApiKey => features.api_key_id.as_deref(),
+        StickyKey::TenantId => features.tenant_id.as_deref(),
+        StickyKey::Header(name) => features
+            .headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str),
+    }
+    .filter(|v| !v.is_empty())
+}
+
+/// Reorder each provider's credentials by weighted rendezvous hashing of the
+/// sticky value, keeping tier and provider order. Credential names hash the
+/// same on every replica, so a client keeps its credential without shared
+/// state, and removing a credential only moves the clients that were on it.
+fn order_sticky(scored: &mut Vec<ScoredCandidate>, sticky: &str) {
+    let mut provider_order: HashMap<String, usize> = HashMap::new();
+    for candidate in scored.iter() {
+        let next = provider_order.len();
+        provider_order
+            .entry(candidate.provider_name.clone())
+            .or_insert(next);
+    }
+    let mut keyed: Vec<_> = std::mem::take(scored)
+        .into_iter()
+        .map(|c| {
+            let key = (
+                c.tier,
+                provider_order[&c.provider_name],
+                rendezvous_score(sticky, &c.provider_name, &c.credential_name, c.score.weight),
+            );
+            (key, c)
+        })
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.cmp(&b.1))
+            .then_with(|| b.2.total_cmp(&a.2))
+    });
+    *scored = keyed.into_iter().map(|(_, c)| c).collect();
+}
+
+/// Weighted rendezvous score `-weight / ln(u)`, with `u` in (0, 1) taken from
+/// a fixed hash (FNV-1a and a splitmix64 finalizer) so every build agrees.
+/// `CredentialRouter::pick` scores with the same function, so both paths put
+/// a client on the same credential.
+pub fn rendezvous_score(
+    sticky: &str,
+    provider_name: &str,
+    credential_name: &str,
+    weight: f64,
+) -> f64 {
+    let bytes = sticky
+        .bytes()
+        .chain([0])
+        .chain(provider_name.bytes())
+        .chain([b'/'])
+        .chain(credential_name.bytes());
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^= hash >> 31;
+    let unit = ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    -weight.max(0.0) / unit.ln()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::routing::config::RoutingConfig;
+    use crate::routing::config::{CredentialGroup, RoutingConfig};
     use crate::routing::types::RouteEndpoint;
     use std::collections::BTreeMap;
 
@@ -462,6 +579,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     }],
                     capabilities: default_capabilities_for_protocol(UpstreamProtocol::OpenAi),
                     upstream_protocol: UpstreamProtocol::OpenAi,
@@ -477,6 +596,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     }],
                     capabilities: default_capabilities_for_protocol(UpstreamProtocol::Anthropic),
                     upstream_protocol: UpstreamProtocol::Anthropic,
@@ -703,6 +824,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     },
                     CredentialEntry {
                         id: "slow".to_string(),
@@ -712,6 +835,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     },
                 ],
                 capabilities: prism_domain::capability::default_capabilities_for_protocol(
@@ -781,6 +906,8 @@ mod tests {
                     region: None,
                     weight: 100,
                     disabled: false,
+                    group: None,
+                    prefix: None,
                 }],
                 capabilities: prism_domain::capability::default_capabilities_for_protocol(
                     prism_domain::capability::UpstreamProtocol::OpenAi,
@@ -812,6 +939,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     },
                     CredentialEntry {
                         id: "cred-2".to_string(),
@@ -821,6 +950,8 @@ mod tests {
                         region: None,
                         weight: 100,
                         disabled: false,
+                        group: None,
+                        prefix: None,
                     },
                 ],
                 capabilities: prism_domain::capability::default_capabilities_for_protocol(
@@ -841,4 +972,167 @@ mod tests {
                 .any(|rejection| rejection.reason == RejectReason::AccessDenied)
         );
     }
+
+    #[test]
+    fn test_plan_orders_credential_tiers() {
+        let features = test_features("gpt-4");
+        let config = RoutingConfig {
+            credential_groups: vec![
+                CredentialGroup::new("primary"),
+                CredentialGroup::new("overflow"),
+            ],
+            ..Default::default()
+        };
+        let cred = |id: &str, weight: u32, group: Option<&str>| CredentialEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            models: vec!["gpt-4".to_string()],
+            excluded_models: vec![],
+            region: None,
+            weight,
+            disabled: false,
+            group: group.map(str::to_string),
+            prefix: None,
+        };
+        let inventory = InventorySnapshot {
+            providers: vec![ProviderEntry {
+                format: Format::OpenAI,
+                name: "openai".to_string(),
+                credentials: vec![
+                    cred("ungrouped", 100, None),
+                    cred("overflow", 100, Some("overflow")),
+                    cred("primary-light", 1, Some("primary")),
+                    cred("primary-heavy", 10, Some("primary")),
+                ],
+                capabilities: prism_domain::capability::default_capabilities_for_protocol(
+                    prism_domain::capability::UpstreamProtocol::OpenAi,
+                ),
+                upstream_protocol: prism_domain::capability::UpstreamProtocol::OpenAi,
+            }],
+        };
+
+        let plan = RoutePlanner::plan(&features, &config, &inventory, &healthy());
+        let order: Vec<&str> = plan
+            .attempts
+            .iter()
+            .map(|a| a.credential_id.as_str())
+            .collect();
+        // Tiers first, weight within a tier.
+        assert_eq!(
+            order,
+            ["primary-heavy", "primary-light", "overflow", "ungrouped"]
+        );
+    }
+
+    #[test]
+    fn test_prefixed_model_only_reaches_owning_credential() {
+        let config = RoutingConfig::default();
+        let cred = |id: &str, prefix: Option<&str>| CredentialEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            models: vec!["gpt-4".to_string()],
+            excluded_models: vec![],
+            region: None,
+            weight: 100,
+            disabled: false,
+            group: None,
+            prefix: prefix.map(str::to_string),
+        };
+        let inventory = InventorySnapshot {
+            providers: vec![ProviderEntry {
+                format: Format::OpenAI,
+                name: "compat".to_string(),
+                credentials: vec![
+                    cred("team-a", Some("team-a/")),
+                    cred("team-b", Some("team-b/")),
+                    CredentialEntry {
+                        models: Vec::new(),
+                        ..cred("shared", None)
+                    },
+                ],
+                capabilities: prism_domain::capability::default_capabilities_for_protocol(
+                    prism_domain::capability::UpstreamProtocol::OpenAi,
+                ),
+                upstream_protocol: prism_domain::capability::UpstreamProtocol::OpenAi,
+            }],
+        };
+        let credentials = |model: &str| {
+            let plan = RoutePlanner::plan(&test_features(model), &config, &inventory, &healthy());
+            let mut ids: Vec<String> = plan
+                .attempts
+                .iter()
+                .map(|a| a.credential_id.clone())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // `shared` accepts any model, but not one namespaced to another credential.
+        assert_eq!(credentials("team-a/gpt-4"), ["team-a"]);
+        assert_eq!(credentials("team-c/gpt-4"), ["shared"]);
+        // Unprefixed names still reach every credential serving the model.
+        assert_eq!(credentials("gpt-4"), ["shared", "team-a", "team-b"]);
+    }
+
+    #[test]
+    fn test_sticky_hash_keeps_client_on_credential() {
+        let mut config = RoutingConfig::default();
+        let profile = config.profiles.get_mut(&config.default_profile).unwrap();
+        profile.credential_policy.strategy = CredentialStrategy::StickyHash;
+        profile.credential_policy.sticky_key = Some("header:X-Session-Id".to_string());
+
+        let cred = |name: &str| CredentialEntry {
+            id: format!("id-{name}"),
+            name: name.to_string(),
+            models: vec!["gpt-4".to_string()],
+            excluded_models: vec![],
+            region: None,
+            weight: 100,
+            disabled: false,
+            group: None,
+            prefix: None,
+        };
+        let inventory = |names: &[&str]| InventorySnapshot {
+            providers: vec![ProviderEntry {
+                format: Format::OpenAI,
+                name: "openai".to_string(),
+                credentials: names.iter().map(|n| cred(n)).collect(),
+                capabilities: prism_domain::capability::default_capabilities_for_protocol(
+                    prism_domain::capability::UpstreamProtocol::OpenAi,
+                ),
+                upstream_protocol: prism_domain::capability::UpstreamProtocol::OpenAi,
+            }],
+        };
+        let first = |session: &str, names: &[&str]| {
+            let mut features = test_features("gpt-4");
+            features
+                .headers
+                .insert("x-session-id".to_string(), session.to_string());
+            RoutePlanner::plan(&features, &config, &inventory(names), &healthy()).attempts[0]
+                .credential_name
+                .clone()
+        };
+
+        let all = ["a", "b", "c", "d"];
+        let picks: Vec<String> = (0..32).map(|i| first(&format!("s{i}"), &all)).collect();
+        assert_eq!(
+            picks,
+            (0..32)
+                .map(|i| first(&format!("s{i}"), &all))
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            all.iter().all(|n| picks.iter().any(|p| p == n)),
+            "{picks:?}"
+        );
+
+        // Dropping a credential only moves the sessions that were on it.
+        for (i, pick) in picks.iter().enumerate() {
+            let remaining: Vec<&str> = all.iter().copied().filter(|n| *n != "a").collect();
+            let moved = first(&format!("s{i}"), &remaining);
+            if pick != "a" {
+                assert_eq!(&moved, pick);
+            }
+        }
+    }
 }
@@ -149,6 +149,10 @@ pub enum RejectReason {
     CredentialDisabled,
     AccessDenied,
     CooldownActive,
+    /// Credential reached its daily or monthly quota.
+    QuotaExhausted,
+    /// Provider format is outside the formats the caller allowed.
+    FormatNotAllowed,
     /// Provider is missing one or more required capabilities.
     MissingCapability {
         capabilities: Vec<String>,
@@ -0,0 +1,246 @@
+//! HMAC request signing for OpenAI-compatible gateways that authenticate the
+//! payload, configured per provider with `signing`.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Placeholders accepted in `string-to-sign`.
+const PLACEHOLDERS: &[&str] = &["method", "path", "timestamp", "body", "body-sha256"];
+
+/// Signs each upstream request body into a header.
+///
+/// `string-to-sign` is a template over `{method}`, `{path}` (path and query),
+/// `{timestamp}` (Unix seconds), `{body}` (raw payload), and `{body-sha256}`
+/// (hex digest of the payload).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RequestSigningConfig {
+    #[serde(default)]
+    pub algorithm: SigningAlgorithm,
+    /// HMAC key; accepts `env://` and `file://` references.
+    pub secret: String,
+    /// Header carrying the signature.
+    #[serde(default = "default_signature_header")]
+    pub header: String,
+    #[serde(default = "default_string_to_sign")]
+    pub string_to_sign: String,
+    #[serde(default)]
+    pub encoding: SignatureEncoding,
+    /// Header carrying the `{timestamp}` used, for gateways that check freshness.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_header: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SigningAlgorithm {
+    #[default]
+    HmacSha256,
+    HmacSha512,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+fn default_signature_header() -> String {
+    "x-signature".to_string()
+}
+
+fn default_string_to_sign() -> String {
+    "{timestamp}.{body}".to_string()
+}
+
+/// One request to sign.
+pub struct SigningInput<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub body: &'a [u8],
+    pub timestamp: i64,
+}
+
+impl RequestSigningConfig {
+    /// Check the header names and the template's placeholders.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.secret.is_empty() {
+            return Err("signing secret must not be empty".to_string());
+        }
+        for header in std::iter::once(&self.header).chain(&self.timestamp_header) {
+            if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err() {
+                return Err(format!(
+                    "signing header '{header}' is not a valid header name"
+                ));
+            }
+        }
+        let mut rest = self.string_to_sign.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Err("signing string-to-sign has an unclosed '{'".to_string());
+            };
+            let name = &rest[start + 1..start + len];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(format!(
+                    "signing string-to-sign has unknown placeholder '{{{name}}}'"
+                ));
+            }
+            rest = &rest[start + len + 1..];
+        }
+        Ok(())
+    }
+
+    /// Whether `name` is one of the headers this config sets.
+    pub fn sets_header(&self, name: &str) -> bool {
+        name.eq_ignore_ascii_case(&self.header)
+            || self
+                .timestamp_header
+                .as_deref()
+                .is_some_and(|h| name.eq_ignore_ascii_case(h))
+    }
+
+    /// Headers to add to the request: the signature, then the timestamp if configured.
+    pub fn sign(&self, input: &SigningInput<'_>) -> Vec<(String, String)> {
+        let timestamp = input.timestamp.to_string();
+        let message = self.string_to_sign(input, &timestamp);
+        let mac = match self.algorithm {
+            SigningAlgorithm::HmacSha256 => hmac::<Hmac<Sha256>>(&self.secret, &message),
+            SigningAlgorithm::HmacSha512 => hmac::<Hmac<Sha512>>(&self.secret, &message),
+        };
+        let signature = match self.encoding {
+            SignatureEncoding::Hex => hex(&mac),
+            SignatureEncoding::Base64 => {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.encode(&mac)
+            }
+        };
+        let mut headers = vec![(self.header.clone(), signature)];
+        if let Some(name) = &self.timestamp_header {
+            headers.push((name.clone(), timestamp));
+        }
+        headers
+    }
+
+    /// Expand the template. `{body}` is copied as raw bytes, so non-UTF-8
+    /// payloads sign correctly.
+    fn string_to_sign(&self, input: &SigningInput<'_>, timestamp: &str) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.string_to_sign.len() + input.body.len());
+        let mut rest = self.string_to_sign.as_str();
+        while let Some(start) = rest.find('{') {
+            out.extend_from_slice(&rest.as_bytes()[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            match &rest[start + 1..start + len] {
+                "method" => out.extend_from_slice(input.method.as_bytes()),
+                "path" => out.extend_from_slice(input.path.as_bytes()),
+                "timestamp" => out.extend_from_slice(timestamp.as_bytes()),
+                "body" => out.extend_from_slice(input.body),
+                "body-sha256" => out.extend_from_slice(hex(&Sha256::digest(input.body)).as_bytes()),
+                other => out.extend_from_slice(format!("{{{other}}}").as_bytes()),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        out.extend_from_slice(rest.as_bytes());
+        out
+    }
+}
+
+fn hmac<M: Mac + hmac::digest::KeyInit>(secret: &str, message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> RequestSigningConfig {
+        serde_yaml_ng::from_str(yaml).unwrap()
+    }
+
+    fn input(body: &[u8]) -> SigningInput<'_> {
+        SigningInput {
+            method: "POST",
+            path: "/v1/chat/completions",
+            body,
+            timestamp: 1700000000,
+        }
+    }
+
+    #[test]
+    fn test_default_signs_timestamp_and_body_as_hex_sha256() {
+        let signing = config("secret: key\n");
+        assert!(signing.validate().is_ok());
+        let headers = signing.sign(&input(b"{}"));
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].0, "x-signature");
+        assert_eq!(
+            headers[0].1,
+            hex(&hmac::<Hmac<Sha256>>("key", b"1700000000.{}"))
+        );
+        assert_eq!(headers[0].1.len(), 64);
+    }
+
+    #[test]
+    fn test_known_hmac_sha256_vector() {
+        // RFC 4231 test case 2.
+        let mac = hmac::<Hmac<Sha256>>("Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_template_placeholders_and_timestamp_header() {
+        let signing = config(
+            "secret: key\nalgorithm: hmac-sha512\nencoding: base64\nheader: X-Gateway-Sig\nstring-to-sign: \"{method}\\n{path}\\n{timestamp}\\n{body-sha256}\"\ntimestamp-header: x-gateway-ts\n",
+        );
+        assert!(signing.validate().is_ok());
+        let expected_message = format!(
+            "POST\n/v1/chat/completions\n1700000000\n{}",
+            hex(&Sha256::digest(b"{}"))
+        );
+        assert_eq!(
+            signing.string_to_sign(&input(b"{}"), "1700000000"),
+            expected_message.as_bytes()
+        );
+        let headers = signing.sign(&input(b"{}"));
+        use base64::Engine;
+        assert_eq!(
+            headers[0].1,
+            base64::engine::general_purpose::STANDARD
+                .encode(hmac::<Hmac<Sha512>>("key", expected_message.as_bytes()))
+        );
+        assert_eq!(
+            headers[1],
+            ("x-gateway-ts".to_string(), "1700000000".to_string())
+        );
+        assert!(signing.sets_header("x-gateway-sig"));
+        assert!(!signing.sets_header("authorization"));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_placeholders_and_bad_headers() {
+        let err = config("secret: key\nstring-to-sign: \"{nonce}.{body}\"\n")
+            .validate()
+            .unwrap_err();
+        assert!(err.contains("{nonce}"), "{err}");
+        assert!(
+            config("secret: key\nheader: \"bad header\"\n")
+                .validate()
+                .is_err()
+        );
+        assert!(config("secret: \"\"\n").validate().is_err());
+    }
+}
@@ -0,0 +1,593 @@
+//! Background refresh for credentials whose tokens expire, configured per
+//! provider with `token-source`.
+//!
+//! A [`TokenProvider`] fetches a token and its expiry. [`TokenRefresher`] keeps one
+//! [`SharedOAuthTokenState`] per credential filled, and executors read it through
+//! [`AuthRecord::current_secret`](crate::provider::AuthRecord::current_secret) like
+//! any managed token. Renewal happens `refresh-before-secs` ahead of expiry, up to
+//! `refresh-jitter-secs` earlier still so replicas do not refresh in step. A failed
+//! refresh keeps the current token, retries with backoff, and is logged as an error
+//! once `alarm-after-failures` attempts in a row have failed.
+
+use crate::auth_profile::{OAuthTokenState, SharedOAuthTokenState};
+use crate::config::Config;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// First retry delay after a failed refresh; doubles per failure.
+const RETRY_BASE: Duration = Duration::from_secs(5);
+/// Longest delay between retries of a failing refresh.
+const RETRY_MAX: Duration = Duration::from_secs(300);
+/// Timeout for one token fetch.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A token and the time it stops being accepted.
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Fetches expiring tokens for one credential.
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    async fn fetch(&self, client: &reqwest::Client) -> Result<AccessToken, String>;
+}
+
+/// Where a provider's token comes from and when to renew it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenSourceConfig {
+    #[serde(flatten)]
+    pub kind: TokenSourceKind,
+    /// Renew this long before the token expires.
+    #[serde(default = "default_refresh_before_secs")]
+    pub refresh_before_secs: u64,
+    /// Renew up to this much earlier again, chosen at random per refresh.
+    #[serde(default = "default_refresh_jitter_secs")]
+    pub refresh_jitter_secs: u64,
+    /// Consecutive failed refreshes before they are logged as errors.
+    #[serde(default = "default_alarm_after_failures")]
+    pub alarm_after_failures: u32,
+}
+
+/// Built-in token sources. AWS role credentials sign requests with SigV4 rather
+/// than acting as bearer tokens, so they wait on a Bedrock executor (SPEC-072).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "kebab-case"
+)]
+pub enum TokenSourceKind {
+    /// Service account access token from the GCE/GKE metadata server.
+    GcpMetadata {
+        #[serde(default = "default_service_account")]
+        service_account: String,
+        /// OAuth scopes to request; empty uses the instance's scopes.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        scopes: Vec<String>,
+        #[serde(default = "default_metadata_url")]
+        metadata_url: String,
+    },
+}
+
+fn default_refresh_before_secs() -> u64 {
+    300
+}
+
+fn default_refresh_jitter_secs() -> u64 {
+    60
+}
+
+fn default_alarm_after_failures() -> u32 {
+    3
+}
+
+fn default_service_account() -> String {
+    "default".to_string()
+}
+
+fn default_metadata_url() -> String {
+    "http://metadata.google.internal".to_string()
+}
+
+impl TokenSourceConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.alarm_after_failures == 0 {
+            return Err("token-source alarm-after-failures must be at least 1".to_string());
+        }
+        match &self.kind {
+            TokenSourceKind::GcpMetadata {
+                service_account,
+                metadata_url,
+                ..
+            } => {
+                if service_account.trim().is_empty() {
+                    return Err("token-source service-account must not be empty".to_string());
+                }
+                url::Url::parse(metadata_url).map_err(|e| {
+                    format!("token-source metadata-url '{metadata_url}' is invalid: {e}")
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn provider(&self) -> Arc<dyn TokenProvider> {
+        match &self.kind {
+            TokenSourceKind::GcpMetadata {
+                service_account,
+                scopes,
+                metadata_url,
+            } => Arc::new(GcpMetadataTokenProvider::new(
+                metadata_url,
+                service_account,
+                scopes.clone(),
+            )),
+        }
+    }
+}
+
+/// Reads `instance/service-accounts/<account>/token` from the metadata server.
+pub struct GcpMetadataTokenProvider {
+    url: String,
+}
+
+impl GcpMetadataTokenProvider {
+    pub fn new(metadata_url: &str, service_account: &str, scopes: Vec<String>) -> Self {
+        let mut url = format!(
+            "{}/computeMetadata/v1/instance/service-accounts/{service_account}/token",
+            metadata_url.trim_end_matches('/')
+        );
+        if !scopes.is_empty() {
+            url.push('?');
+            url.push_str(
+                &url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("scopes", &scopes.join(","))
+                    .finish(),
+            );
+        }
+        Self { url }
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataToken {
+    access_token: String,
+    expires_in: i64,
+}
+
+#[async_trait]
+impl TokenProvider for GcpMetadataTokenProvider {
+    async fn fetch(&self, client: &reqwest::Client) -> Result<AccessToken, String> {
+        let response = client
+            .get(&self.url)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .map_err(|e| format!("metadata server request failed: {e}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("metadata server returned {status}: {body}"));
+        }
+        let token: MetadataToken = response
+            .json()
+            .await
+            .map_err(|e| format!("invalid metadata token response: {e}"))?;
+        Ok(AccessToken {
+            token: token.access_token,
+            expires_at: Utc::now() + chrono::Duration::seconds(token.expires_in),
+        })
+    }
+}
+
+/// Refresh state of one credential, as reported by [`TokenRefresher::status`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenStatus {
+    pub credential: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_refresh: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct Schedule {
+    /// Next refresh; `None` refreshes on the next pass.
+    due: Option<Instant>,
+    failures: u32,
+    last_error: Option<String>,
+}
+
+struct ManagedToken {
+    credential: String,
+    /// Built from a provider's `token-source` rather than [`TokenRefresher::register`].
+    from_config: bool,
+    config: TokenSourceConfig,
+    provider: Arc<dyn TokenProvider>,
+    state: SharedOAuthTokenState,
+    schedule: Mutex<Schedule>,
+}
+
+impl ManagedToken {
+    fn due_at(&self) -> Option<Instant> {
+        self.schedule.lock().ok().map(|s| s.due)?
+    }
+
+    async fn refresh(&self, client: &reqwest::Client) {
+        let result = self.provider.fetch(client).await;
+        let Ok(mut schedule) = self.schedule.lock() else {
+            return;
+        };
+        match result {
+            Ok(token) => {
+                let lifetime = (token.expires_at - Utc::now()).to_std().unwrap_or_default();
+                if let Ok(mut state) = self.state.write() {
+                    state.access_token = token.token;
+                    state.expires_at = Some(token.expires_at);
+                    state.last_refresh = Some(Utc::now());
+                }
+                if schedule.failures > 0 {
+                    tracing::info!(
+                        credential = %self.credential,
+                        failures = schedule.failures,
+                        "token refresh recovered"
+                    );
+                }
+                let jitter = rand::random_range(0..=self.config.refresh_jitter_secs);
+                let lead = Duration::from_secs(self.config.refresh_before_secs + jitter);
+                *schedule = Schedule {
+                    due: Some(Instant::now() + refresh_delay(lifetime, lead)),
+                    ..Default::default()
+                };
+            }
+            Err(e) => {
+                schedule.failures += 1;
+                schedule.due = Some(Instant::now() + retry_delay(schedule.failures));
+                let expired = self
+                    .state
+                    .read()
+                    .map(|s| s.expires_at.is_none_or(|at| at <= Utc::now()))
+                    .unwrap_or(true);
+                if schedule.failures >= self.config.alarm_after_failures {
+                    tracing::error!(
+                        credential = %self.credential,
+                        failures = schedule.failures,
+                        expired,
+                        error = %e,
+                        "token refresh keeps failing"
+                    );
+                } else {
+                    tracing::warn!(
+                        credential = %self.credential,
+                        failures = schedule.failures,
+                        expired,
+                        error = %e,
+                        "token refresh failed"
+                    );
+                }
+                schedule.last_error = Some(e);
+            }
+        }
+    }
+}
+
+/// Time to wait before renewing a token that is valid for `lifetime`, renewing
+/// `lead` ahead of expiry. Tokens shorter-lived than `lead` renew at half-life.
+fn refresh_delay(lifetime: Duration, lead: Duration) -> Duration {
+    lifetime
+        .checked_sub(lead)
+        .filter(|d| !d.is_zero())
+        .unwrap_or(lifetime / 2)
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_BASE
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(RETRY_MAX)
+}
+
+/// Keeps the tokens of every `token-source` credential fresh.
+pub struct TokenRefresher {
+    client: reqwest::Client,
+    tokens: RwLock<HashMap<String, Arc<ManagedToken>>>,
+    wake: tokio::sync::Notify,
+}
+
+impl Default for TokenRefresher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenRefresher {
+    pub fn new() -> Self {
+        // Metadata servers are link-local; never route them through a proxy.
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            tokens: RwLock::new(HashMap::new()),
+            wake: tokio::sync::Notify::new(),
+        }
+    }
+
+    /// Track the `token-source` of every provider in `config`, keyed like auth
+    /// profiles (`<provider>/<provider>`). Credentials whose source is unchanged
+    /// keep their token; removed ones are dropped. Registered providers are kept.
+    pub fn sync(&self, config: &Config) {
+        let Ok(mut tokens) = self.tokens.write() else {
+            return;
+        };
+        let mut next = HashMap::new();
+        for entry in &config.providers {
+            let Some(source) = &entry.token_source else {
+                continue;
+            };
+            let credential = format!("{}/{}", entry.name, entry.name);
+            let token = match tokens.remove(&credential) {
+                Some(token) if token.from_config && token.config == *source => token,
+                _ => Arc::new(Self::managed(
+                    &credential,
+                    true,
+                    source.clone(),
+                    source.provider(),
+                )),
+            };
+            next.insert(credential, token);
+        }
+        for (credential, token) in tokens.drain() {
+            if !token.from_config {
+                next.entry(credential).or_insert(token);
+            }
+        }
+        *tokens = next;
+        drop(tokens);
+        self.wake.notify_one();
+    }
+
+    /// Track `credential` (`<provider>/<auth profile id>`) with a custom provider,
+    /// e.g. for a custom executor. A `token-source` in config takes precedence.
+    pub fn register(
+        &self,
+        credential: &str,
+        config: TokenSourceConfig,
+        provider: Arc<dyn TokenProvider>,
+    ) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.insert(
+                credential.to_string(),
+                Arc::new(Self::managed(credential, false, config, provider)),
+            );
+        }
+        self.wake.notify_one();
+    }
+
+    fn managed(
+        credential: &str,
+        from_config: bool,
+        config: TokenSourceConfig,
+        provider: Arc<dyn TokenProvider>,
+    ) -> ManagedToken {
+        ManagedToken {
+            credential: credential.to_string(),
+            from_config,
+            config,
+            provider,
+            state: Arc::new(RwLock::new(OAuthTokenState {
+                access_token: String::new(),
+                refresh_token: String::new(),
+                id_token: None,
+                account_id: None,
+                email: None,
+                expires_at: None,
+                last_refresh: None,
+            })),
+            schedule: Mutex::new(Schedule::default()),
+        }
+    }
+
+    /// The token state of each tracked credential, for the credential router.
+    pub fn states(&self) -> HashMap<String, SharedOAuthTokenState> {
+        self.tokens
+            .read()
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .map(|(credential, token)| (credential.clone(), token.state.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Refresh every credential that is due, concurrently. Returns how long until
+    /// the next one is due, `None` when nothing is tracked.
+    pub async fn refresh_due(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let due: Vec<_> = self
+            .tokens
+            .read()
+            .map(|tokens| {
+                tokens
+                    .values()
+                    .filter(|token| token.due_at().is_none_or(|at| at <= now))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        futures::future::join_all(due.iter().map(|token| token.refresh(&self.client))).await;
+
+        let tokens = self.tokens.read().ok()?;
+        tokens
+            .values()
+            .map(|token| {
+                token.due_at().map_or(Duration::ZERO, |at| {
+                    at.saturating_duration_since(Instant::now())
+                })
+            })
+            .min()
+    }
+
+    /// Run refreshes until the runtime shuts down. Call once.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.refresh_due().await {
+                    Some(wait) => {
+                        tokio::select! {
+                            _ = tokio::time::sleep(wait) => {}
+                            _ = self.wake.notified() => {}
+                        }
+                    }
+                    None => self.wake.notified().await,
+                }
+            }
+        })
+    }
+
+    pub fn status(&self) -> Vec<TokenStatus> {
+        let Ok(tokens) = self.tokens.read() else {
+            return Vec::new();
+        };
+        let mut status: Vec<_> = tokens
+            .values()
+            .map(|token| {
+                let (expires_at, last_refresh) = token
+                    .state
+                    .read()
+                    .map(|s| (s.expires_at, s.last_refresh))
+                    .unwrap_or_default();
+                let (consecutive_failures, last_error) = token
+                    .schedule
+                    .lock()
+                    .map(|s| (s.failures, s.last_error.clone()))
+                    .unwrap_or_default();
+                TokenStatus {
+                    credential: token.credential.clone(),
+                    expires_at,
+                    last_refresh,
+                    consecutive_failures,
+                    last_error,
+                }
+            })
+            .collect();
+        status.sort_by(|a, b| a.credential.cmp(&b.credential));
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Serves `tok-1`, `tok-2`, ... valid for `lifetime`, failing while `fail` is set.
+    struct Scripted {
+        calls: AtomicU32,
+        lifetime: chrono::Duration,
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl TokenProvider for Scripted {
+        async fn fetch(&self, _client: &reqwest::Client) -> Result<AccessToken, String> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.fail.load(Ordering::SeqCst) {
+                return Err("metadata server unavailable".to_string());
+            }
+            Ok(AccessToken {
+                token: format!("tok-{n}"),
+                expires_at: Utc::now() + self.lifetime,
+            })
+        }
+    }
+
+    fn source() -> TokenSourceConfig {
+        serde_yaml_ng::from_str("type: gcp-metadata\n").unwrap()
+    }
+
+    #[test]
+    fn test_config_defaults_and_validation() {
+        let config = source();
+        assert_eq!(
+            config.kind,
+            TokenSourceKind::GcpMetadata {
+                service_account: "default".to_string(),
+                scopes: Vec::new(),
+                metadata_url: "http://metadata.google.internal".to_string(),
+            }
+        );
+        assert_eq!(config.refresh_before_secs, 300);
+        assert!(config.validate().is_ok());
+
+        let config: TokenSourceConfig = serde_yaml_ng::from_str(
+            "type: gcp-metadata\nservice-account: \"\"\nalarm-after-failures: 1\n",
+        )
+        .unwrap();
+        assert!(config.validate().unwrap_err().contains("service-account"));
+    }
+
+    #[test]
+    fn test_refresh_delay_and_retry_backoff() {
+        let lead = Duration::from_secs(300);
+        assert_eq!(
+            refresh_delay(Duration::from_secs(3600), lead),
+            Duration::from_secs(3300)
+        );
+        // Shorter-lived than the lead: renew at half-life instead of immediately.
+        assert_eq!(
+            refresh_delay(Duration::from_secs(200), lead),
+            Duration::from_secs(100)
+        );
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(3), Duration::from_secs(20));
+        assert_eq!(retry_delay(40), RETRY_MAX);
+    }
+
+    #[tokio::test]
+    async fn test_refresher_fills_state_and_keeps_token_on_failure() {
+        let provider = Arc::new(Scripted {
+            calls: AtomicU32::new(0),
+            lifetime: chrono::Duration::hours(1),
+            fail: false.into(),
+        });
+        let refresher = TokenRefresher::new();
+        refresher.register("vertex/vertex", source(), provider.clone());
+        let state = refresher.states()["vertex/vertex"].clone();
+
+        let wait = refresher.refresh_due().await.unwrap();
+        assert_eq!(state.read().unwrap().access_token, "tok-1");
+        // Renewed 5-6 minutes ahead of the hour.
+        assert!(wait > Duration::from_secs(3240) && wait <= Duration::from_secs(3300));
+
+        // Not due yet: no fetch.
+        refresher.refresh_due().await;
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        // Force a refresh that fails: the old token stays in use.
+        provider.fail.store(true, Ordering::SeqCst);
+        refresher.tokens.read().unwrap()["vertex/vertex"]
+            .schedule
+            .lock()
+            .unwrap()
+            .due = None;
+        let wait = refresher.refresh_due().await.unwrap();
+        assert!(wait <= RETRY_BASE);
+        assert_eq!(state.read().unwrap().access_token, "tok-1");
+        let status = refresher.status();
+        assert_eq!(status[0].consecutive_failures, 1);
+        assert_eq!(
+            status[0].last_error.as_deref(),
+            Some("metadata server unavailable")
+        );
+    }
+}
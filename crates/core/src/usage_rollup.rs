@@ -0,0 +1,215 @@
+//! Hourly usage totals per model, API key, and tag set.
+//!
+//! The request log keeps a bounded number of records, so on a busy gateway it
+//! no longer reaches back a full day. Usage reports read these totals instead:
+//! every record is added to the bucket of its hour when it is logged, and
+//! buckets are kept for [`RETENTION_HOURS`], enough for a weekly report.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::request_log::{TagCost, TagCostReport, UsageBreakdown, UsageSummary};
+use crate::request_record::RequestRecord;
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// Hours of buckets kept: a week plus a day of margin.
+pub const RETENTION_HOURS: i64 = 8 * 24;
+
+#[derive(Default)]
+struct Row {
+    requests: u64,
+    tokens: u64,
+    cost: f64,
+}
+
+#[derive(Default)]
+struct HourBucket {
+    requests: u64,
+    errors: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    cost: f64,
+    by_model: HashMap<String, Row>,
+    by_api_key: HashMap<String, Row>,
+    /// Keyed by the request's sorted tags, so a report can tell which requests
+    /// carry none of the tags it is asked about.
+    by_tags: HashMap<Vec<String>, TagCost>,
+}
+
+/// Usage bucketed by UTC hour, keyed by the hour's start in Unix milliseconds.
+#[derive(Default)]
+pub struct UsageRollup {
+    hours: Mutex<BTreeMap<i64, HourBucket>>,
+}
+
+impl UsageRollup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a logged request to the bucket of its hour. Opening a new hour drops
+    /// the buckets that fell out of retention.
+    pub fn record(&self, e: &RequestRecord) {
+        let ts = e.timestamp.timestamp_millis();
+        let hour = ts - ts.rem_euclid(HOUR_MS);
+        let tokens = e.usage.as_ref().map_or(0, |u| u.total());
+        let cost = e.cost.unwrap_or(0.0);
+        let model = e
+            .model
+            .as_deref()
+            .or(e.requested_model.as_deref())
+            .unwrap_or("-");
+        let api_key = e.api_key_id.as_deref().unwrap_or("-");
+        let mut tags = e.tags.clone();
+        tags.sort();
+        tags.dedup();
+
+        let Ok(mut hours) = self.hours.lock() else {
+            return;
+        };
+        if !hours.contains_key(&hour) {
+            let cutoff = hour - RETENTION_HOURS * HOUR_MS;
+            hours.retain(|&start, _| start >= cutoff);
+        }
+        let bucket = hours.entry(hour).or_default();
+        bucket.requests += 1;
+        if e.status >= 400 {
+            bucket.errors += 1;
+        }
+        if let Some(ref u) = e.usage {
+            bucket.input_tokens += u.total_input();
+            bucket.output_tokens += u.output_tokens;
+        }
+        bucket.cost += cost;
+        for (rows, name) in [
+            (&mut bucket.by_model, model),
+            (&mut bucket.by_api_key, api_key),
+        ] {
+            let row = rows.entry(name.to_string()).or_default();
+            row.requests += 1;
+            row.tokens += tokens;
+            row.cost += cost;
+        }
+        let row = bucket.by_tags.entry(tags).or_default();
+        row.requests += 1;
+        row.cost += cost;
+        if let Some(ref u) = e.usage {
+            row.input_tokens += u.total_input();
+            row.output_tokens += u.output_tokens;
+        }
+    }
+
+    /// Spend per tag starting with `prefix`, over every hour that overlaps
+    /// `[from, to)` (Unix milliseconds; unbounded when unset).
+    pub fn tag_costs(&self, from: Option<i64>, to: Option<i64>, prefix: &str) -> TagCostReport {
+        let mut report = TagCostReport {
+            total_requests: 0,
+            total_tokens: 0,
+            total_cost: 0.0,
+            tags: Vec::new(),
+            untagged: TagCost::default(),
+            since: None,
+        };
+        let from = from.map_or(i64::MIN, |ts| ts - ts.rem_euclid(HOUR_MS));
+        let to = to.unwrap_or(i64::MAX);
+        let Ok(hours) = self.hours.lock() else {
+            return report;
+        };
+        if from >= to {
+            return report;
+        }
+
+        let mut by_tag: HashMap<&str, TagCost> = HashMap::new();
+        for (&start, bucket) in hours.range(from..to) {
+            report.since.get_or_insert(start);
+            report.total_requests += bucket.requests;
+            report.total_tokens += bucket.input_tokens + bucket.output_tokens;
+            report.total_cost += bucket.cost;
+            for (tags, row) in &bucket.by_tags {
+                let mut tagged = false;
+                for tag in tags.iter().filter(|t| t.starts_with(prefix)) {
+                    tagged = true;
+                    add_tag_cost(by_tag.entry(tag.as_str()).or_default(), row);
+                }
+                if !tagged {
+                    add_tag_cost(&mut report.untagged, row);
+                }
+            }
+        }
+
+        report.tags = by_tag
+            .into_iter()
+            .map(|(tag, cost)| TagCost {
+                tag: tag.to_string(),
+                ..cost
+            })
+            .collect();
+        report
+            .tags
+            .sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.tag.cmp(&b.tag)));
+        report
+    }
+
+    /// Usage of the hours that start in `[from, to)` (Unix milliseconds), with
+    /// at most `top` rows per breakdown.
+    pub fn summary(&self, from: i64, to: i64, top: usize) -> UsageSummary {
+        let mut summary = UsageSummary::default();
+        let mut models: HashMap<&str, Row> = HashMap::new();
+        let mut keys: HashMap<&str, Row> = HashMap::new();
+
+        let Ok(hours) = self.hours.lock() else {
+            return summary;
+        };
+        if from < to {
+            for bucket in hours.range(from..to).map(|(_, b)| b) {
+                summary.requests += bucket.requests;
+                summary.errors += bucket.errors;
+                summary.input_tokens += bucket.input_tokens;
+                summary.output_tokens += bucket.output_tokens;
+                summary.cost += bucket.cost;
+                for (totals, rows) in [
+                    (&mut models, &bucket.by_model),
+                    (&mut keys, &bucket.by_api_key),
+                ] {
+                    for (name, row) in rows {
+                        let total = totals.entry(name.as_str()).or_default();
+                        total.requests += row.requests;
+                        total.tokens += row.tokens;
+                        total.cost += row.cost;
+                    }
+                }
+            }
+        }
+
+        let ranked = |rows: HashMap<&str, Row>| {
+            let mut rows: Vec<UsageBreakdown> = rows
+                .into_iter()
+                .map(|(name, row)| UsageBreakdown {
+                    name: name.to_string(),
+                    requests: row.requests,
+                    tokens: row.tokens,
+                    cost: row.cost,
+                })
+                .collect();
+            rows.sort_by(|a, b| {
+                b.cost
+                    .total_cmp(&a.cost)
+                    .then_with(|| b.requests.cmp(&a.requests))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            rows.truncate(top);
+            rows
+        };
+        summary.by_model = ranked(models);
+        summary.by_api_key = ranked(keys);
+        summary
+    }
+}
+
+fn add_tag_cost(acc: &mut TagCost, row: &TagCost) {
+    acc.requests += row.requests;
+    acc.input_tokens += row.input_tokens;
+    acc.output_tokens += row.output_tokens;
+    acc.cost += row.cost;
+}
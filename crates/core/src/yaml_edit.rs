@@ -0,0 +1,484 @@
+use serde_yaml_ng::{Mapping, Value};
+
+/// Rewrite a hand-written YAML document so it deserializes like `updated`, while keeping
+/// comments, key order, and layout of every part that did not change.
+///
+/// `previous` and `updated` are full serializations of the config before and after a
+/// change (they include defaulted keys the file may omit); only paths that differ between
+/// them are applied to `original`. Unchanged entries are copied verbatim, changed entries
+/// are re-serialized in place, and new keys are appended to their parent block.
+///
+/// Returns `None` when the document uses a layout this editor does not handle (flow
+/// collections at the top level, document markers, ...) or when the result would not
+/// round-trip; callers should then fall back to writing `updated` as-is.
+pub fn rewrite_preserving(original: &str, previous: &str, updated: &str) -> Option<String> {
+    let file: Value = serde_yaml_ng::from_str(original).ok()?;
+    let previous: Value = serde_yaml_ng::from_str(previous).ok()?;
+    let updated: Value = serde_yaml_ng::from_str(updated).ok()?;
+    if !file.is_mapping() {
+        return None;
+    }
+
+    let mut target = file.clone();
+    patch(&mut target, &previous, &updated);
+    if target == file {
+        return Some(original.to_string());
+    }
+
+    let lines: Vec<&str> = original.lines().collect();
+    let rendered = render_mapping(&lines, 0, &file, &target)?;
+    let reparsed: Value = serde_yaml_ng::from_str(&rendered).ok()?;
+    (reparsed == target).then_some(rendered)
+}
+
+// ─── Semantic patch ────────────────────────────────────────────────────────
+
+/// Apply the difference between `old` and `new` onto `file`.
+fn patch(file: &mut Value, old: &Value, new: &Value) {
+    if old == new {
+        return;
+    }
+    match (file, old, new) {
+        (Value::Mapping(file), Value::Mapping(old), Value::Mapping(new)) => {
+            for (key, new_value) in new {
+                match (file.get_mut(key), old.get(key)) {
+                    (Some(slot), Some(old_value)) => patch(slot, old_value, new_value),
+                    (Some(slot), None) => *slot = new_value.clone(),
+                    (None, old_value) => {
+                        if old_value != Some(new_value) {
+                            file.insert(key.clone(), new_value.clone());
+                        }
+                    }
+                }
+            }
+            for key in old.keys() {
+                if !new.contains_key(key) {
+                    file.remove(key);
+                }
+            }
+        }
+        (Value::Sequence(file), Value::Sequence(old), Value::Sequence(new)) => {
+            patch_sequence(file, old, new)
+        }
+        (file, _, new) => *file = new.clone(),
+    }
+}
+
+fn patch_sequence(file: &mut Vec<Value>, old: &[Value], new: &[Value]) {
+    if all_named(file) && all_named(old) && all_named(new) {
+        let next = new
+            .iter()
+            .map(|new_item| {
+                let name = item_name(new_item);
+                let old_item = old.iter().find(|item| item_name(item) == name);
+                let file_item = file.iter().find(|item| item_name(item) == name);
+                match (file_item, old_item) {
+                    (Some(file_item), Some(old_item)) => {
+                        let mut merged = file_item.clone();
+                        patch(&mut merged, old_item, new_item);
+                        merged
+                    }
+                    _ => new_item.clone(),
+                }
+            })
+            .collect();
+        *file = next;
+    } else if file.len() == old.len() && old.len() == new.len() {
+        for ((file_item, old_item), new_item) in file.iter_mut().zip(old).zip(new) {
+            patch(file_item, old_item, new_item);
+        }
+    } else {
+        *file = new.to_vec();
+    }
+}
+
+fn item_name(value: &Value) -> Option<&str> {
+    value.get("name")?.as_str()
+}
+
+fn all_named(items: &[Value]) -> bool {
+    items.iter().all(|item| item_name(item).is_some())
+}
+
+// ─── Text rendering ────────────────────────────────────────────────────────
+
+/// A block-level child of a mapping or sequence: leading comments plus its own lines.
+struct Segment<'a> {
+    leading: Vec<&'a str>,
+    lines: Vec<&'a str>,
+}
+
+struct Segments<'a> {
+    head: Vec<&'a str>,
+    items: Vec<Segment<'a>>,
+    tail: Vec<&'a str>,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_trivia(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn is_sequence_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Split `block` into children starting at `indent`. Comments and blank lines directly
+/// above a child travel with it.
+fn segment<'a>(block: &[&'a str], indent: usize, sequence: bool) -> Option<Segments<'a>> {
+    let mut head = Vec::new();
+    let mut items: Vec<Segment<'a>> = Vec::new();
+    let mut pending = Vec::new();
+
+    for &line in block {
+        if is_trivia(line) {
+            pending.push(line);
+            continue;
+        }
+        let line_indent = indent_of(line);
+        let content = &line[line_indent..];
+        let starts_child = line_indent == indent && is_sequence_item(content) == sequence;
+        if starts_child {
+            items.push(Segment {
+                leading: std::mem::take(&mut pending),
+                lines: vec![line],
+            });
+        } else if let Some(current) = items.last_mut() {
+            if line_indent < indent {
+                return None;
+            }
+            current.lines.append(&mut pending);
+            current.lines.push(line);
+        } else {
+            return None;
+        }
+    }
+    if items.is_empty() {
+        head = pending;
+        pending = Vec::new();
+    }
+    Some(Segments {
+        head,
+        items,
+        tail: pending,
+    })
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Parse the key of a `key: value` line.
+fn entry_key(content: &str) -> Option<Value> {
+    let end = if content.starts_with('"') || content.starts_with('\'') {
+        let quote = content.chars().next()?;
+        content[1..].find(quote)? + 2
+    } else {
+        content
+            .find(": ")
+            .or_else(|| content.strip_suffix(':').map(str::len))?
+    };
+    if !content[end..].starts_with(':') {
+        return None;
+    }
+    serde_yaml_ng::from_str(&content[..end]).ok()
+}
+
+/// Text after the `key:` on a header line, ignoring a trailing comment.
+fn inline_value(content: &str) -> &str {
+    let Some(colon) = content
+        .find(": ")
+        .or_else(|| content.strip_suffix(':').map(str::len))
+    else {
+        return "";
+    };
+    let rest = content[colon + 1..].trim();
+    if rest.starts_with('#') { "" } else { rest }
+}
+
+fn indent_text(yaml: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = String::new();
+    for line in yaml.lines() {
+        if !line.is_empty() {
+            out.push_str(&pad);
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn serialize_entry(key: &Value, value: &Value, indent: usize) -> Option<String> {
+    let mut mapping = Mapping::new();
+    mapping.insert(key.clone(), value.clone());
+    Some(indent_text(
+        &serde_yaml_ng::to_string(&mapping).ok()?,
+        indent,
+    ))
+}
+
+fn serialize_item(value: &Value, indent: usize) -> Option<String> {
+    let sequence = Value::Sequence(vec![value.clone()]);
+    Some(indent_text(
+        &serde_yaml_ng::to_string(&sequence).ok()?,
+        indent,
+    ))
+}
+
+fn render_mapping(block: &[&str], indent: usize, file: &Value, target: &Value) -> Option<String> {
+    let (Value::Mapping(file_map), Value::Mapping(target_map)) = (file, target) else {
+        return None;
+    };
+    let segments = segment(block, indent, false)?;
+    let mut out = String::new();
+    push_lines(&mut out, &segments.head);
+
+    let mut seen = Vec::new();
+    for entry in &segments.items {
+        let key = entry_key(&entry.lines[0][indent..])?;
+        let file_value = file_map.get(&key)?;
+        seen.push(key.clone());
+        let Some(target_value) = target_map.get(&key) else {
+            continue;
+        };
+        push_lines(&mut out, &entry.leading);
+        if file_value == target_value {
+            push_lines(&mut out, &entry.lines);
+        } else {
+            out.push_str(&render_entry(
+                &entry.lines,
+                indent,
+                &key,
+                file_value,
+                target_value,
+            )?);
+        }
+    }
+    for (key, value) in target_map {
+        if !seen.contains(key) {
+            out.push_str(&serialize_entry(key, value, indent)?);
+        }
+    }
+    push_lines(&mut out, &segments.tail);
+    Some(out)
+}
+
+fn render_entry(
+    lines: &[&str],
+    indent: usize,
+    key: &Value,
+    file_value: &Value,
+    target_value: &Value,
+) -> Option<String> {
+    let header = lines[0];
+    let rest = &lines[1..];
+    let child_indent = rest
+        .iter()
+        .find(|line| !is_trivia(line))
+        .map(|line| (indent_of(line), &line[indent_of(line)..]));
+    let empty_target = match target_value {
+        Value::Mapping(map) => map.is_empty(),
+        Value::Sequence(items) => items.is_empty(),
+        _ => false,
+    };
+
+    let rendered_children = match (file_value, target_value, child_indent) {
+        _ if empty_target || !inline_value(&header[indent..]).is_empty() => None,
+        (Value::Mapping(_), Value::Mapping(_), Some((child, content)))
+            if child > indent && !is_sequence_item(content) =>
+        {
+            render_mapping(rest, child, file_value, target_value)
+        }
+        (Value::Sequence(file_items), Value::Sequence(target_items), Some((child, content)))
+            if child >= indent && is_sequence_item(content) =>
+        {
+            render_sequence(rest, child, file_items, target_items)
+        }
+        _ => None,
+    };
+    match rendered_children {
+        Some(children) => Some(format!("{header}\n{children}")),
+        None => serialize_entry(key, target_value, indent),
+    }
+}
+
+fn render_sequence(
+    block: &[&str],
+    indent: usize,
+    file_items: &[Value],
+    target_items: &[Value],
+) -> Option<String> {
+    let segments = segment(block, indent, true)?;
+    if segments.items.len() != file_items.len() {
+        return None;
+    }
+    let named = all_named(file_items) && all_named(target_items);
+    if !named && file_items.len() != target_items.len() {
+        return None;
+    }
+
+    let mut out = String::new();
+    push_lines(&mut out, &segments.head);
+    for (position, target_item) in target_items.iter().enumerate() {
+        let source = if named {
+            let name = item_name(target_item);
+            file_items.iter().position(|item| item_name(item) == name)
+        } else {
+            Some(position)
+        };
+        let Some(source) = source else {
+            out.push_str(&serialize_item(target_item, indent)?);
+            continue;
+        };
+        let item = &segments.items[source];
+        let file_item = &file_items[source];
+        push_lines(&mut out, &item.leading);
+        if file_item == target_item {
+            push_lines(&mut out, &item.lines);
+        } else {
+            out.push_str(&render_item(&item.lines, indent, file_item, target_item)?);
+        }
+    }
+    push_lines(&mut out, &segments.tail);
+    Some(out)
+}
+
+/// Re-render one `- ` sequence item, recursing into mapping items so untouched fields keep
+/// their text.
+fn render_item(
+    lines: &[&str],
+    indent: usize,
+    file_item: &Value,
+    target_item: &Value,
+) -> Option<String> {
+    let first = &lines[0][indent..];
+    let nested = file_item.is_mapping()
+        && target_item.as_mapping().is_some_and(|map| !map.is_empty())
+        && first.len() > 2
+        && entry_key(&first[2..]).is_some();
+    if !nested {
+        return serialize_item(target_item, indent);
+    }
+
+    let opened = format!("{}  {}", " ".repeat(indent), &first[2..]);
+    let mut as_mapping = vec![opened.as_str()];
+    as_mapping.extend_from_slice(&lines[1..]);
+    let Some(rendered) = render_mapping(&as_mapping, indent + 2, file_item, target_item) else {
+        return serialize_item(target_item, indent);
+    };
+
+    // Put the `- ` marker back on the first content line.
+    let mut out = String::new();
+    let mut marked = false;
+    for line in rendered.lines() {
+        if !marked && !is_trivia(line) {
+            out.push_str(&" ".repeat(indent));
+            out.push_str("- ");
+            out.push_str(&line[indent + 2..]);
+            marked = true;
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_document_is_returned_verbatim() {
+        let original = "# top comment\nport: 8317   # listen port\nhost: 0.0.0.0\n";
+        let full = "host: 0.0.0.0\nport: 8317\ndebug: false\n";
+        assert_eq!(
+            rewrite_preserving(original, full, full).as_deref(),
+            Some(original)
+        );
+    }
+
+    #[test]
+    fn test_changed_scalar_keeps_other_comments_and_order() {
+        let original = "\
+# Server
+port: 8317
+# Bind address
+host: 0.0.0.0
+";
+        let previous = "host: 0.0.0.0\nport: 8317\ndebug: false\n";
+        let updated = "host: 127.0.0.1\nport: 8317\ndebug: false\n";
+        let rewritten = rewrite_preserving(original, previous, updated).unwrap();
+        assert_eq!(
+            rewritten,
+            "# Server\nport: 8317\n# Bind address\nhost: 127.0.0.1\n"
+        );
+    }
+
+    #[test]
+    fn test_provider_edit_only_touches_that_provider() {
+        let original = "\
+providers:
+  # primary upstream
+  - name: alpha
+    format: openai
+    api-key: env://ALPHA_KEY   # from vault
+  - name: beta
+    format: claude
+    api-key: sk-beta
+";
+        let previous = "\
+providers:
+- name: alpha
+  format: openai
+  api-key: env://ALPHA_KEY
+  disabled: false
+- name: beta
+  format: claude
+  api-key: sk-beta
+  disabled: false
+";
+        let updated = format!(
+            "{}- name: gamma\n  format: gemini\n  api-key: sk-gamma\n  disabled: false\n",
+            previous.replacen("sk-beta", "sk-beta-2", 1)
+        );
+        let rewritten = rewrite_preserving(original, previous, &updated).unwrap();
+        assert!(rewritten.contains("  # primary upstream\n  - name: alpha\n"));
+        assert!(rewritten.contains("api-key: env://ALPHA_KEY   # from vault\n"));
+        assert!(rewritten.contains("  - name: beta\n    format: claude\n    api-key: sk-beta-2\n"));
+        assert!(rewritten.contains("name: gamma"));
+
+        let reparsed: Value = serde_yaml_ng::from_str(&rewritten).unwrap();
+        let providers = reparsed["providers"].as_sequence().unwrap();
+        assert_eq!(providers.len(), 3);
+        assert!(providers[0].get("disabled").is_none());
+        assert_eq!(providers[2]["disabled"], Value::Bool(false));
+    }
+
+    #[test]
+    fn test_removed_key_is_dropped_with_its_comment() {
+        let original = "port: 8317\n# proxy for all upstreams\nproxy-url: http://p:8080\n";
+        let previous = "port: 8317\nproxy-url: http://p:8080\n";
+        let updated = "port: 8317\n";
+        assert_eq!(
+            rewrite_preserving(original, previous, updated).as_deref(),
+            Some("port: 8317\n")
+        );
+    }
+
+    #[test]
+    fn test_unsupported_layout_falls_back() {
+        let original = "---\n{port: 8317}\n";
+        let previous = "port: 8317\n";
+        let updated = "port: 9000\n";
+        assert_eq!(rewrite_preserving(original, previous, updated), None);
+    }
+}
@@ -29,6 +29,8 @@ pub enum Operation {
     CountTokens,
     /// List available models.
     ListModels,
+    /// Classify input with a moderation model.
+    Moderate,
 }
 
 /// The specific public endpoint that received the request.
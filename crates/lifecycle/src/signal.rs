@@ -14,16 +14,17 @@ impl SignalHandler {
         (Self { shutdown_tx: tx }, rx)
     }
 
-    /// Run the signal loop. Blocks until a shutdown signal is received.
+    /// Run the signal loop. Blocks until a shutdown signal is received and returns
+    /// its name (`sigint` or `sigterm`).
     ///
     /// - SIGTERM / SIGINT / Ctrl+C → triggers shutdown
     /// - SIGHUP (unix only) → calls `reload_fn`
-    pub async fn run<F>(self, reload_fn: F)
+    pub async fn run<F>(self, reload_fn: F) -> &'static str
     where
         F: Fn() + Send + Sync + 'static,
     {
         #[cfg(unix)]
-        {
+        let received = {
             use tokio::signal::unix::{SignalKind, signal};
 
             let mut sigterm =
@@ -35,11 +36,11 @@ impl SignalHandler {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {
                         tracing::info!("Received SIGINT, initiating shutdown...");
-                        break;
+                        break "sigint";
                     }
                     _ = sigterm.recv() => {
                         tracing::info!("Received SIGTERM, initiating shutdown...");
-                        break;
+                        break "sigterm";
                     }
                     _ = sighup.recv() => {
                         tracing::info!("Received SIGHUP, reloading configuration...");
@@ -47,18 +48,20 @@ impl SignalHandler {
                     }
                 }
             }
-        }
+        };
 
         #[cfg(not(unix))]
-        {
+        let received = {
             let _ = &reload_fn; // suppress unused warning
             tokio::signal::ctrl_c()
                 .await
                 .expect("failed to install Ctrl+C handler");
             tracing::info!("Received Ctrl+C, initiating shutdown...");
-        }
+            "sigint"
+        };
 
         let _ = self.shutdown_tx.send(true);
+        received
     }
 }
 
@@ -54,6 +54,8 @@ impl ProviderCatalog {
                                 region: c.record.region.clone(),
                                 weight: c.record.weight,
                                 disabled: c.record.disabled,
+                                group: c.record.group.clone(),
+                                prefix: c.record.prefix.clone(),
                             })
                             .collect(),
                         capabilities: default_capabilities_for_protocol(up),
@@ -142,6 +144,7 @@ mod tests {
             id: id.to_string(),
             provider: format,
             upstream: UpstreamKind::from(format),
+            executor: None,
             provider_name: format!("provider-{id}"),
             api_key: format!("key-{id}"),
             base_url: None,
@@ -167,10 +170,16 @@ mod tests {
             oauth_state: None,
             weight: 100,
             region: None,
+            group: None,
+            quota: None,
             upstream_presentation: Default::default(),
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
@@ -10,16 +10,12 @@ const ANTHROPIC_VERSION: &str = "2023-06-01";
 const ANTHROPIC_BETA: &str = "output-128k-2025-02-19";
 
 pub struct ClaudeExecutor {
-    pub global_proxy: Option<String>,
     pub client_pool: Arc<HttpClientPool>,
 }
 
 impl ClaudeExecutor {
-    pub fn new(global_proxy: Option<String>, client_pool: Arc<HttpClientPool>) -> Self {
-        Self {
-            global_proxy,
-            client_pool,
-        }
+    pub fn new(client_pool: Arc<HttpClientPool>) -> Self {
+        Self { client_pool }
     }
 
     /// Build a POST request with Claude-specific auth and version headers.
@@ -29,20 +25,39 @@ impl ClaudeExecutor {
         url: &str,
         request: &ProviderRequest,
     ) -> Result<reqwest::RequestBuilder, ProxyError> {
-        let client = common::build_client(auth, self.global_proxy.as_deref(), &self.client_pool)?;
+        let client = common::build_client(auth, &self.client_pool)?;
+
+        // Client betas are merged into one header with the default flag.
+        let mut headers = request.headers.clone();
+        let betas = beta_header(headers.remove("anthropic-beta").as_deref());
 
         let mut req = client
             .post(url)
             .header("content-type", "application/json")
             .header("anthropic-version", ANTHROPIC_VERSION)
-            .header("anthropic-beta", ANTHROPIC_BETA);
+            .header("anthropic-beta", betas);
         let _base_url = auth.base_url_or_default(DEFAULT_BASE_URL);
         req = common::apply_auth(req, auth);
-        req = common::apply_headers(req, &request.headers, auth);
+        req = common::apply_headers(req, &headers, auth);
         Ok(req.body(request.payload.to_vec()))
     }
 }
 
+/// `anthropic-beta` value: the default flag followed by the requested ones.
+fn beta_header(requested: Option<&str>) -> String {
+    let mut betas = vec![ANTHROPIC_BETA];
+    for beta in requested
+        .into_iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+    {
+        if !beta.is_empty() && !betas.contains(&beta) {
+            betas.push(beta);
+        }
+    }
+    betas.join(",")
+}
+
 #[async_trait]
 impl ProviderExecutor for ClaudeExecutor {
     fn identifier(&self) -> &str {
@@ -62,7 +77,8 @@ impl ProviderExecutor for ClaudeExecutor {
         let url = format!("{base_url}/v1/messages");
         let req = self.build_request(auth, &url, &request)?;
 
-        let (body, headers) = common::handle_response(req.send().await?).await?;
+        let (body, headers) =
+            common::handle_response(common::send(req, auth, &self.client_pool).await?).await?;
         Ok(ProviderResponse {
             payload: body,
             headers,
@@ -78,10 +94,24 @@ impl ProviderExecutor for ClaudeExecutor {
         let url = format!("{base_url}/v1/messages");
         let req = self.build_request(auth, &url, &request)?;
 
-        common::handle_stream_response(req.send().await?).await
+        common::send_stream(req, auth, &self.client_pool).await
     }
 
     fn supported_models(&self, auth: &AuthRecord) -> Vec<ModelInfo> {
         common::supported_models_from_auth(auth, "claude", "anthropic")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beta_header_merges_requested_flags() {
+        assert_eq!(beta_header(None), ANTHROPIC_BETA);
+        assert_eq!(
+            beta_header(Some("prompt-caching-2024-07-31, output-128k-2025-02-19,")),
+            format!("{ANTHROPIC_BETA},prompt-caching-2024-07-31")
+        );
+    }
+}
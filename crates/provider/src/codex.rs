@@ -1,5 +1,5 @@
 use crate::common;
-use crate::openai_compat::{chat_to_responses, responses_to_chat, synthesize_chat_stream_chunks};
+use crate::openai_compat::{chat_to_responses, responses_stream_to_chat, responses_to_chat};
 use crate::sse::parse_sse_stream;
 use async_trait::async_trait;
 use prism_core::error::ProxyError;
@@ -13,16 +13,12 @@ const CODEX_CLIENT_VERSION: &str = "0.101.0";
 const CODEX_USER_AGENT: &str = "codex_cli_rs/0.101.0 (Mac OS 26.0.1; arm64) Apple_Terminal/464";
 
 pub struct CodexExecutor {
-    pub global_proxy: Option<String>,
     pub client_pool: Arc<HttpClientPool>,
 }
 
 impl CodexExecutor {
-    pub fn new(global_proxy: Option<String>, client_pool: Arc<HttpClientPool>) -> Self {
-        Self {
-            global_proxy,
-            client_pool,
-        }
+    pub fn new(client_pool: Arc<HttpClientPool>) -> Self {
+        Self { client_pool }
     }
 
     fn build_request(
@@ -33,7 +29,7 @@ impl CodexExecutor {
         request_headers: &std::collections::HashMap<String, String>,
         stream: bool,
     ) -> Result<reqwest::RequestBuilder, ProxyError> {
-        let client = common::build_client(auth, self.global_proxy.as_deref(), &self.client_pool)?;
+        let client = common::build_client(auth, &self.client_pool)?;
         let mut req = client
             .post(url)
             .header("content-type", "application/json")
@@ -117,6 +113,7 @@ impl CodexExecutor {
                 status,
                 body: String::from_utf8_lossy(&body).to_string(),
                 retry_after_secs: crate::parse_retry_after(&headers),
+                headers,
             });
         }
 
@@ -185,7 +182,9 @@ impl ProviderExecutor for CodexExecutor {
         let ProviderResponse {
             payload: resp_body,
             headers,
-        } = self.collect_completed_response(req.send().await?).await?;
+        } = self
+            .collect_completed_response(common::send(req, auth, &self.client_pool).await?)
+            .await?;
         let payload = if request.responses_passthrough {
             resp_body
         } else {
@@ -204,20 +203,17 @@ impl ProviderExecutor for CodexExecutor {
             let url = format!("{base_url}/responses");
             let body = self.normalize_payload(&request, true)?;
             let req = self.build_request(auth, &url, &body, &request.headers, true)?;
-            return common::handle_stream_response(req.send().await?).await;
+            return common::send_stream(req, auth, &self.client_pool).await;
         }
 
-        // Codex chat-completions streaming is synthesized from compact responses.
-        let response = self.execute(auth, request).await?;
-        let v: serde_json::Value = serde_json::from_slice(&response.payload)
-            .map_err(|e| ProxyError::Internal(e.to_string()))?;
-
-        let chunks = synthesize_chat_stream_chunks(&v)?;
-
-        Ok(StreamResult {
-            headers: response.headers,
-            stream: Box::pin(tokio_stream::iter(chunks)),
-        })
+        // Codex chat-completions streaming translates `response.*` events as they arrive.
+        let base_url = auth.resolved_base_url();
+        let url = format!("{base_url}/responses");
+        let body = self.normalize_payload(&request, true)?;
+        let req = self.build_request(auth, &url, &body, &request.headers, true)?;
+        common::send_stream(req, auth, &self.client_pool)
+            .await
+            .map(responses_stream_to_chat)
     }
 
     fn supported_models(&self, auth: &AuthRecord) -> Vec<ModelInfo> {
@@ -236,6 +232,7 @@ mod tests {
             id: "codex-auth".into(),
             provider: Format::OpenAI,
             upstream: UpstreamKind::Codex,
+            executor: None,
             provider_name: "codex".into(),
             api_key: "access".into(),
             base_url: None,
@@ -255,16 +252,22 @@ mod tests {
             oauth_state: None,
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             upstream_presentation: Default::default(),
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
     #[test]
     fn test_normalize_payload_injects_instructions_and_removes_stream_for_compact() {
-        let exec = CodexExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = CodexExecutor::new(Arc::new(HttpClientPool::new()));
         let request = ProviderRequest {
             model: "gpt-5.4".into(),
             payload: bytes::Bytes::from_static(
@@ -286,7 +289,7 @@ mod tests {
 
     #[test]
     fn test_normalize_payload_coerces_string_input_and_drops_max_output_tokens() {
-        let exec = CodexExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = CodexExecutor::new(Arc::new(HttpClientPool::new()));
         let request = ProviderRequest {
             model: "gpt-5.4".into(),
             payload: bytes::Bytes::from_static(
@@ -308,7 +311,7 @@ mod tests {
 
     #[test]
     fn test_build_request_adds_codex_headers() {
-        let exec = CodexExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = CodexExecutor::new(Arc::new(HttpClientPool::new()));
         let req = exec
             .build_request(
                 &make_auth(),
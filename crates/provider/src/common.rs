@@ -1,4 +1,6 @@
-use crate::sse::parse_sse_stream;
+use crate::json_repair::repair_json_events;
+use crate::ndjson::parse_event_stream;
+use bytes::Bytes;
 use prism_core::auth_profile::AuthHeaderKind;
 use prism_core::error::ProxyError;
 use prism_core::presentation::protected::is_protected;
@@ -6,13 +8,13 @@ use prism_core::provider::*;
 use prism_core::proxy::HttpClientPool;
 use std::collections::HashMap;
 
-/// Build an HTTP client for a provider request using a shared pool.
+/// Build an HTTP client for a provider request using a shared pool and its
+/// current global network settings.
 pub fn build_client(
     auth: &AuthRecord,
-    global_proxy: Option<&str>,
     pool: &HttpClientPool,
 ) -> Result<reqwest::Client, ProxyError> {
-    pool.get_or_create_default(auth.effective_proxy(global_proxy), global_proxy)
+    pool.get_or_create_current(auth.proxy_url.as_deref())
         .map_err(|e| ProxyError::Internal(format!("failed to build HTTP client: {e}")))
 }
 
@@ -54,10 +56,40 @@ pub fn apply_auth(mut req: reqwest::RequestBuilder, auth: &AuthRecord) -> reqwes
     req
 }
 
+/// Send a request upstream, applying the pool's fault injection settings first.
+pub async fn send(
+    req: reqwest::RequestBuilder,
+    auth: &AuthRecord,
+    pool: &HttpClientPool,
+) -> Result<reqwest::Response, ProxyError> {
+    crate::fault::before_send(&pool.fault_injection(), &auth.provider_name).await?;
+    Ok(req.send().await?)
+}
+
+/// Send a streaming request upstream and parse its stream with
+/// [`handle_stream_response`], applying fault injection to both.
+pub async fn send_stream(
+    req: reqwest::RequestBuilder,
+    auth: &AuthRecord,
+    pool: &HttpClientPool,
+) -> Result<StreamResult, ProxyError> {
+    let result = handle_stream_response(send(req, auth, pool).await?, auth.json_repair).await?;
+    Ok(inject_stream_faults(result, auth, pool))
+}
+
+/// Apply the pool's stream fault injection settings to a parsed stream.
+pub fn inject_stream_faults(
+    result: StreamResult,
+    auth: &AuthRecord,
+    pool: &HttpClientPool,
+) -> StreamResult {
+    crate::fault::drop_stream(&pool.fault_injection(), &auth.provider_name, result)
+}
+
 /// Handle a non-streaming response: check status, extract body and headers.
 pub async fn handle_response(
     resp: reqwest::Response,
-) -> Result<(bytes::Bytes, HashMap<String, String>), ProxyError> {
+) -> Result<(Bytes, HashMap<String, String>), ProxyError> {
     let status = resp.status().as_u16();
     let headers = crate::extract_headers(&resp);
     let body = resp.bytes().await?;
@@ -67,14 +99,20 @@ pub async fn handle_response(
             status,
             body: String::from_utf8_lossy(&body).to_string(),
             retry_after_secs: crate::parse_retry_after(&headers),
+            headers,
         });
     }
 
     Ok((body, headers))
 }
 
-/// Handle a streaming response: check status, parse SSE stream.
-pub async fn handle_stream_response(resp: reqwest::Response) -> Result<StreamResult, ProxyError> {
+/// Handle a streaming response: check status and parse the SSE or NDJSON
+/// stream. With `json_repair` (the provider's `json-repair` setting), JSON
+/// payloads split or concatenated across events are reassembled.
+pub async fn handle_stream_response(
+    resp: reqwest::Response,
+    json_repair: bool,
+) -> Result<StreamResult, ProxyError> {
     let status = resp.status().as_u16();
     let headers = crate::extract_headers(&resp);
 
@@ -84,23 +122,35 @@ pub async fn handle_stream_response(resp: reqwest::Response) -> Result<StreamRes
             status,
             body: String::from_utf8_lossy(&body).to_string(),
             retry_after_secs: crate::parse_retry_after(&headers),
+            headers,
         });
     }
 
-    let byte_stream = resp.bytes_stream();
-    let sse_stream = parse_sse_stream(byte_stream);
+    let content_type = headers.get("content-type").cloned();
+    let stream = stream_chunks(resp.bytes_stream(), content_type.as_deref(), json_repair);
+    Ok(StreamResult { headers, stream })
+}
 
-    let chunk_stream = tokio_stream::StreamExt::map(sse_stream, |result| {
+/// Parse an upstream body into chunks, repairing split or concatenated JSON
+/// payloads when `json_repair` is set.
+fn stream_chunks<E>(
+    byte_stream: impl futures::Stream<Item = Result<Bytes, E>> + Send + 'static,
+    content_type: Option<&str>,
+    json_repair: bool,
+) -> std::pin::Pin<Box<dyn futures::Stream<Item = Result<StreamChunk, ProxyError>> + Send>>
+where
+    E: std::fmt::Display + Send + 'static,
+{
+    let mut events = parse_event_stream(byte_stream, content_type);
+    if json_repair {
+        events = repair_json_events(events);
+    }
+    Box::pin(tokio_stream::StreamExt::map(events, |result| {
         result.map(|event| StreamChunk {
             event_type: event.event,
-            data: event.data,
+            data: Bytes::from(event.data),
         })
-    });
-
-    Ok(StreamResult {
-        headers,
-        stream: Box::pin(chunk_stream),
-    })
+    }))
 }
 
 /// Build a model list from an auth record's configured models.
@@ -111,11 +161,11 @@ pub fn supported_models_from_auth(
 ) -> Vec<ModelInfo> {
     auth.models
         .iter()
-        .filter(|m| auth.supports_model(&m.id))
+        .filter(|m| !m.is_pattern() && auth.supports_model(&m.id))
         .map(|m| {
-            let id = m.alias.as_deref().unwrap_or(&m.id);
+            let id = auth.prefixed_model_id(m.alias.as_deref().unwrap_or(&m.id));
             ModelInfo {
-                id: id.to_string(),
+                id,
                 provider: provider.to_string(),
                 owned_by: owned_by.to_string(),
             }
@@ -136,6 +186,7 @@ mod tests {
             id: "auth-1".into(),
             provider: Format::OpenAI,
             upstream: UpstreamKind::OpenAI,
+            executor: None,
             provider_name: "openai".into(),
             api_key: "secret".into(),
             base_url: None,
@@ -158,10 +209,16 @@ mod tests {
             oauth_state: None,
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             upstream_presentation: Default::default(),
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
@@ -182,4 +239,52 @@ mod tests {
         assert!(headers.get("authorization").is_none());
         assert!(headers.get("x-api-key").is_none());
     }
+
+    #[test]
+    fn test_model_patterns_include_and_exclude() {
+        let mut auth = make_auth();
+        auth.models = vec![
+            ModelEntry {
+                id: "gpt-4*".into(),
+                alias: None,
+            },
+            ModelEntry {
+                id: "o3-mini".into(),
+                alias: Some("reasoner".into()),
+            },
+        ];
+        auth.excluded_models = vec!["gpt-4o-audio*".into()];
+
+        assert!(auth.supports_model("gpt-4o"));
+        assert!(auth.supports_model("gpt-4.1-mini"));
+        assert!(!auth.supports_model("gpt-4o-audio-preview"));
+        assert!(!auth.supports_model("gpt-3.5-turbo"));
+        // Pattern matches are sent as requested, not rewritten.
+        assert_eq!(auth.resolve_model_id("gpt-4o"), "gpt-4o");
+        assert_eq!(auth.resolve_model_id("reasoner"), "o3-mini");
+
+        let listed: Vec<String> = supported_models_from_auth(&auth, "openai", "openai")
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(listed, vec!["reasoner".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_chunks_repairs_json_only_when_enabled() {
+        use tokio_stream::StreamExt;
+
+        async fn chunks(json_repair: bool) -> Vec<String> {
+            let body = futures::stream::iter([Ok::<_, std::io::Error>(Bytes::from_static(
+                b"data: {\"a\":\n\ndata: 1}{\"b\":2}\n\n",
+            ))]);
+            stream_chunks(body, Some("text/event-stream"), json_repair)
+                .map(|c| String::from_utf8(c.unwrap().data.to_vec()).unwrap())
+                .collect()
+                .await
+        }
+
+        assert_eq!(chunks(false).await, ["{\"a\":", "1}{\"b\":2}"]);
+        assert_eq!(chunks(true).await, ["{\"a\":1}", "{\"b\":2}"]);
+    }
 }
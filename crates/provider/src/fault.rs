@@ -0,0 +1,150 @@
+//! Fault injection for upstream calls, driven by the `fault-injection` config.
+//!
+//! Faults are rolled independently per call: a delay before sending, an injected
+//! error instead of sending, and for streams a cut-off after a few chunks. They
+//! surface as the same errors real upstream failures produce, so retry,
+//! cooldown, bootstrap retry, and fallback handle them unchanged.
+
+use prism_core::config::FaultInjectionConfig;
+use prism_core::error::ProxyError;
+use prism_core::provider::StreamResult;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// Apply pre-send faults: maybe sleep, then maybe fail with an injected error.
+pub async fn before_send(faults: &FaultInjectionConfig, provider: &str) -> Result<(), ProxyError> {
+    if !faults.applies_to(provider) {
+        return Ok(());
+    }
+    if roll(faults.latency_rate) {
+        let ms = rand::random_range(faults.latency_min_ms..=faults.latency_max_ms);
+        tracing::warn!(
+            provider,
+            latency_ms = ms,
+            "fault injection: delaying upstream call"
+        );
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+    }
+    if roll(faults.error_rate) && !faults.error_statuses.is_empty() {
+        let status = faults.error_statuses[rand::random_range(0..faults.error_statuses.len())];
+        tracing::warn!(provider, status, "fault injection: failing upstream call");
+        return Err(ProxyError::Upstream {
+            status,
+            body: serde_json::json!({
+                "error": {"message": "injected by fault-injection", "type": "fault_injection"}
+            })
+            .to_string(),
+            retry_after_secs: None,
+            headers: Default::default(),
+        });
+    }
+    Ok(())
+}
+
+/// Maybe cut `result`'s stream off with a network error after
+/// `stream-drop-after-chunks` chunks.
+pub fn drop_stream(
+    faults: &FaultInjectionConfig,
+    provider: &str,
+    result: StreamResult,
+) -> StreamResult {
+    if !faults.applies_to(provider) || !roll(faults.stream_drop_rate) {
+        return result;
+    }
+    let after = faults.stream_drop_after_chunks;
+    tracing::warn!(
+        provider,
+        after_chunks = after,
+        "fault injection: dropping upstream stream"
+    );
+    let dropped = futures::stream::once(async {
+        Err(ProxyError::Network(
+            "stream dropped by fault-injection".to_string(),
+        ))
+    });
+    StreamResult {
+        headers: result.headers,
+        stream: Box::pin(result.stream.take(after).chain(dropped)),
+    }
+}
+
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && rand::random::<f64>() < rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prism_core::provider::StreamChunk;
+    use std::collections::HashMap;
+
+    fn faults() -> FaultInjectionConfig {
+        FaultInjectionConfig {
+            enabled: true,
+            latency_min_ms: 0,
+            latency_max_ms: 0,
+            ..Default::default()
+        }
+    }
+
+    fn stream_of(n: usize) -> StreamResult {
+        let chunks = (0..n).map(|i| {
+            Ok(StreamChunk {
+                event_type: None,
+                data: i.to_string().into(),
+            })
+        });
+        StreamResult {
+            headers: HashMap::new(),
+            stream: Box::pin(futures::stream::iter(chunks.collect::<Vec<_>>())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_before_send_injects_configured_status() {
+        let mut config = faults();
+        config.error_rate = 1.0;
+        config.error_statuses = vec![429];
+        match before_send(&config, "openai").await {
+            Err(ProxyError::Upstream { status, .. }) => assert_eq!(status, 429),
+            other => panic!("expected injected 429, got {other:?}"),
+        }
+
+        config.error_rate = 0.0;
+        assert!(before_send(&config, "openai").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_faults_skip_disabled_and_unlisted_providers() {
+        let mut config = faults();
+        config.error_rate = 1.0;
+        config.providers = vec!["staging".to_string()];
+        assert!(before_send(&config, "openai").await.is_ok());
+        assert!(before_send(&config, "staging").await.is_err());
+
+        config.enabled = false;
+        assert!(before_send(&config, "staging").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_drop_stream_ends_with_network_error_after_chunks() {
+        let mut config = faults();
+        config.stream_drop_rate = 1.0;
+        config.stream_drop_after_chunks = 2;
+        let items: Vec<_> = drop_stream(&config, "openai", stream_of(5))
+            .stream
+            .collect()
+            .await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].as_ref().unwrap().data, "1");
+        assert!(matches!(items[2], Err(ProxyError::Network(_))));
+
+        config.stream_drop_rate = 0.0;
+        let items: Vec<_> = drop_stream(&config, "openai", stream_of(5))
+            .stream
+            .collect()
+            .await;
+        assert!(items.iter().all(Result::is_ok));
+        assert_eq!(items.len(), 5);
+    }
+}
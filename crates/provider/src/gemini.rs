@@ -8,16 +8,12 @@ use std::sync::Arc;
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 
 pub struct GeminiExecutor {
-    pub global_proxy: Option<String>,
     pub client_pool: Arc<HttpClientPool>,
 }
 
 impl GeminiExecutor {
-    pub fn new(global_proxy: Option<String>, client_pool: Arc<HttpClientPool>) -> Self {
-        Self {
-            global_proxy,
-            client_pool,
-        }
+    pub fn new(client_pool: Arc<HttpClientPool>) -> Self {
+        Self { client_pool }
     }
 
     /// Build a POST request with Gemini-specific auth header.
@@ -28,7 +24,7 @@ impl GeminiExecutor {
         url: &str,
         request: &ProviderRequest,
     ) -> Result<reqwest::RequestBuilder, ProxyError> {
-        let client = common::build_client(auth, self.global_proxy.as_deref(), &self.client_pool)?;
+        let client = common::build_client(auth, &self.client_pool)?;
 
         let req = client.post(url).header("content-type", "application/json");
         let req = common::apply_auth(req, auth);
@@ -83,7 +79,8 @@ impl ProviderExecutor for GeminiExecutor {
         let url = self.build_url(auth, &request.model, false);
         let req = self.build_request(auth, &url, &request)?;
 
-        let (body, headers) = common::handle_response(req.send().await?).await?;
+        let (body, headers) =
+            common::handle_response(common::send(req, auth, &self.client_pool).await?).await?;
         Ok(ProviderResponse {
             payload: body,
             headers,
@@ -102,7 +99,7 @@ impl ProviderExecutor for GeminiExecutor {
         }
         let req = self.build_request(auth, &url, &request)?;
 
-        common::handle_stream_response(req.send().await?).await
+        common::send_stream(req, auth, &self.client_pool).await
     }
 
     fn supported_models(&self, auth: &AuthRecord) -> Vec<ModelInfo> {
@@ -122,6 +119,7 @@ mod tests {
             id: "test-gemini".to_string(),
             provider: Format::Gemini,
             upstream: UpstreamKind::Gemini,
+            executor: None,
             provider_name: "gemini".to_string(),
             api_key: "AIzaSyTest".to_string(),
             base_url: None,
@@ -141,10 +139,16 @@ mod tests {
             oauth_state: None,
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             upstream_presentation: Default::default(),
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
@@ -158,9 +162,34 @@ mod tests {
         auth
     }
 
+    #[test]
+    fn test_gemini_key_sent_in_header_not_url() {
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
+        let auth = make_gemini_auth();
+        let url = exec.build_url(&auth, "gemini-2.0-flash", true);
+        let request = ProviderRequest {
+            model: "gemini-2.0-flash".to_string(),
+            payload: bytes::Bytes::from_static(b"{}"),
+            source_format: Format::Gemini,
+            stream: true,
+            headers: Default::default(),
+            original_request: None,
+            responses_passthrough: false,
+        };
+        let built = exec
+            .build_request(&auth, &url, &request)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(built.headers()["x-goog-api-key"], "AIzaSyTest");
+        assert!(built.headers().get("authorization").is_none());
+        assert!(!built.url().as_str().contains("AIzaSyTest"));
+        assert!(!built.url().query_pairs().any(|(name, _)| name == "key"));
+    }
+
     #[test]
     fn test_gemini_url_non_stream() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let auth = make_gemini_auth();
         let url = exec.build_url(&auth, "gemini-2.0-flash", false);
         assert_eq!(
@@ -171,7 +200,7 @@ mod tests {
 
     #[test]
     fn test_gemini_url_stream() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let auth = make_gemini_auth();
         let url = exec.build_url(&auth, "gemini-2.0-flash", true);
         assert_eq!(
@@ -182,7 +211,7 @@ mod tests {
 
     #[test]
     fn test_gemini_url_custom_base() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let mut auth = make_gemini_auth();
         auth.base_url = Some("https://custom.api.example.com".to_string());
         let url = exec.build_url(&auth, "gemini-1.5-pro", false);
@@ -194,7 +223,7 @@ mod tests {
 
     #[test]
     fn test_vertex_url_non_stream() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let auth = make_vertex_auth();
         let url = exec.build_url(&auth, "gemini-2.0-flash", false);
         assert_eq!(
@@ -205,7 +234,7 @@ mod tests {
 
     #[test]
     fn test_vertex_url_stream() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let auth = make_vertex_auth();
         let url = exec.build_url(&auth, "gemini-2.0-flash", true);
         assert_eq!(
@@ -216,7 +245,7 @@ mod tests {
 
     #[test]
     fn test_vertex_url_custom_base() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let mut auth = make_vertex_auth();
         auth.base_url = Some("https://europe-west1-aiplatform.googleapis.com".to_string());
         auth.vertex_location = Some("europe-west1".to_string());
@@ -229,7 +258,7 @@ mod tests {
 
     #[test]
     fn test_vertex_supported_models_provider_name() {
-        let exec = GeminiExecutor::new(None, Arc::new(HttpClientPool::new()));
+        let exec = GeminiExecutor::new(Arc::new(HttpClientPool::new()));
         let mut auth = make_vertex_auth();
         auth.models = vec![ModelEntry {
             id: "gemini-2.0-flash".to_string(),
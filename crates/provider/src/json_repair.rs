@@ -0,0 +1,261 @@
+use futures::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use tokio_stream::StreamExt;
+
+use crate::sse::SseEvent;
+
+type EventStream =
+    Pin<Box<dyn Stream<Item = Result<SseEvent, prism_core::error::ProxyError>> + Send>>;
+
+/// Most bytes held while waiting for a split JSON document to close. Past
+/// this the fragments are released unrepaired.
+pub const MAX_REPAIR_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Reassembles JSON event payloads that upstreams split or concatenate.
+///
+/// A payload that opens an object or array without closing it is held and
+/// joined with the following events until brace depth returns to zero.
+/// A payload carrying several documents (`{..}{..}`) is split into one event
+/// per document. Well-formed events and non-JSON payloads pass through as-is;
+/// `[DONE]` flushes anything still held. Reassembled events keep the event
+/// type of their first fragment.
+#[derive(Debug, Default)]
+pub struct JsonRepair {
+    pending: String,
+    event: Option<String>,
+}
+
+impl JsonRepair {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one decoded event; returns the events ready to forward.
+    pub fn push(&mut self, event: SseEvent) -> Vec<SseEvent> {
+        let data = event.data.trim();
+        if self.pending.is_empty() {
+            if !data.starts_with(['{', '[']) || document_len(data.as_bytes()) == Some(data.len()) {
+                return vec![event];
+            }
+            self.event = event.event;
+            self.pending = event.data;
+            return self.drain();
+        }
+        if data == "[DONE]" {
+            let mut out: Vec<SseEvent> = self.finish().into_iter().collect();
+            out.push(event);
+            return out;
+        }
+        self.pending.push_str(&event.data);
+        self.drain()
+    }
+
+    /// Release whatever is still held at end of stream, unrepaired.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if self.pending.trim().is_empty() {
+            self.pending.clear();
+            return None;
+        }
+        tracing::debug!(
+            bytes = self.pending.len(),
+            "Releasing unterminated JSON stream payload"
+        );
+        Some(SseEvent {
+            event: self.event.clone(),
+            data: std::mem::take(&mut self.pending),
+        })
+    }
+
+    /// Emit every complete document at the front of `pending`.
+    fn drain(&mut self) -> Vec<SseEvent> {
+        let mut out = Vec::new();
+        loop {
+            let start = self.pending.len() - self.pending.trim_start().len();
+            let rest = &self.pending[start..];
+            if rest.is_empty() {
+                self.pending.clear();
+                break;
+            }
+            if !rest.starts_with(['{', '[']) {
+                // Trailing garbage cannot be repaired; forward it for the translator to report.
+                out.extend(self.finish());
+                break;
+            }
+            match document_len(rest.as_bytes()) {
+                Some(len) => {
+                    out.push(SseEvent {
+                        event: self.event.clone(),
+                        data: rest[..len].to_string(),
+                    });
+                    self.pending.drain(..start + len);
+                }
+                None => {
+                    self.pending.drain(..start);
+                    break;
+                }
+            }
+        }
+        if self.pending.len() > MAX_REPAIR_BUFFER_SIZE {
+            tracing::warn!(
+                bytes = self.pending.len(),
+                "Split JSON stream payload exceeded repair buffer; forwarding as-is"
+            );
+            out.extend(self.finish());
+        }
+        out
+    }
+}
+
+/// Byte length of the object or array at the start of `bytes`, or `None` when
+/// it is not closed yet. Brackets inside strings are not counted.
+fn document_len(bytes: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Wrap a decoded event stream with [`JsonRepair`].
+pub fn repair_json_events(events: EventStream) -> EventStream {
+    struct State {
+        inner: EventStream,
+        repair: JsonRepair,
+        ready: VecDeque<SseEvent>,
+        done: bool,
+    }
+
+    let state = State {
+        inner: events,
+        repair: JsonRepair::new(),
+        ready: VecDeque::new(),
+        done: false,
+    };
+    Box::pin(futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.ready.pop_front() {
+                return Some((Ok(event), state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.inner.next().await {
+                Some(Ok(event)) => state.ready.extend(state.repair.push(event)),
+                Some(Err(e)) => return Some((Err(e), state)),
+                None => {
+                    state.done = true;
+                    state.ready.extend(state.repair.finish());
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: Option<&str>, data: &str) -> SseEvent {
+        SseEvent {
+            event: event.map(str::to_string),
+            data: data.to_string(),
+        }
+    }
+
+    fn run(events: &[(Option<&str>, &str)]) -> Vec<(Option<String>, String)> {
+        let mut repair = JsonRepair::new();
+        let mut out = Vec::new();
+        for (ty, data) in events {
+            out.extend(repair.push(event(*ty, data)));
+        }
+        out.extend(repair.finish());
+        out.into_iter().map(|e| (e.event, e.data)).collect()
+    }
+
+    #[test]
+    fn test_well_formed_and_non_json_pass_through() {
+        let out = run(&[(Some("a"), "{\"x\":1}"), (None, "ping"), (None, "[DONE]")]);
+        assert_eq!(
+            out,
+            [
+                (Some("a".into()), "{\"x\":1}".into()),
+                (None, "ping".into()),
+                (None, "[DONE]".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_document_split_across_events_is_joined() {
+        let out = run(&[
+            (Some("delta"), "{\"text\":\"a {"),
+            (None, "b\\\"}\",\"n\":"),
+            (None, "[1,2]}"),
+        ]);
+        let expected = "{\"text\":\"a {b\\\"}\",\"n\":[1,2]}";
+        assert_eq!(out, [(Some("delta".into()), expected.into())]);
+        serde_json::from_str::<serde_json::Value>(&out[0].1).unwrap();
+    }
+
+    #[test]
+    fn test_concatenated_documents_are_split() {
+        let out = run(&[(None, "{\"a\":1}{\"b\":\"}\"}\n{\"c\":")]);
+        assert_eq!(out[0].1, "{\"a\":1}");
+        assert_eq!(out[1].1, "{\"b\":\"}\"}");
+        // The unterminated tail is released at end of stream.
+        assert_eq!(out[2].1, "{\"c\":");
+    }
+
+    #[test]
+    fn test_done_flushes_pending_fragment() {
+        let out = run(&[(None, "{\"a\":"), (None, "[DONE]")]);
+        assert_eq!(out, [(None, "{\"a\":".into()), (None, "[DONE]".into())]);
+    }
+
+    #[test]
+    fn test_buffer_limit_releases_fragments() {
+        let mut repair = JsonRepair::new();
+        assert!(repair.push(event(None, "{\"a\":\"")).is_empty());
+        let big = "x".repeat(MAX_REPAIR_BUFFER_SIZE);
+        let out = repair.push(event(None, &big));
+        assert_eq!(out.len(), 1);
+        assert!(out[0].data.starts_with("{\"a\":\"x"));
+        assert!(repair.finish().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repair_json_events_stream() {
+        let inner: EventStream = Box::pin(futures::stream::iter(vec![
+            Ok(event(None, "{\"a\":")),
+            Ok(event(None, "1}{\"b\":2}")),
+        ]));
+        let out: Vec<String> = repair_json_events(inner)
+            .map(|e| e.unwrap().data)
+            .collect()
+            .await;
+        assert_eq!(out, ["{\"a\":1}", "{\"b\":2}"]);
+    }
+}
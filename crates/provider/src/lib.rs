@@ -2,13 +2,18 @@ pub mod catalog;
 pub mod claude;
 pub mod codex;
 pub mod common;
+pub mod fault;
 pub mod gemini;
 pub mod health;
+pub mod json_repair;
+pub mod ndjson;
 pub mod openai_compat;
+pub mod quota;
+pub mod router_state;
 pub mod routing;
 pub mod sse;
 
-use prism_core::provider::{ProviderExecutor, UpstreamKind};
+use prism_core::provider::{AuthRecord, ProviderExecutor, UpstreamKind};
 use prism_core::proxy::HttpClientPool;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -45,35 +50,70 @@ impl ExecutorRegistry {
         self.get(upstream.as_str())
     }
 
+    /// Executor for `auth`: its custom `executor`, else the one for its upstream.
+    pub fn for_credential(&self, auth: &AuthRecord) -> Option<Arc<dyn ProviderExecutor>> {
+        self.get(auth.executor_name())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.executors.contains_key(name)
+    }
+
+    /// Reject a config whose providers name an executor that is not registered.
+    pub fn check_config(&self, config: &prism_core::config::Config) -> Result<(), String> {
+        for entry in &config.providers {
+            if let Some(name) = entry.executor.as_deref()
+                && !self.contains(name)
+            {
+                return Err(format!(
+                    "provider '{}' uses unregistered executor '{name}'",
+                    entry.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn all(&self) -> impl Iterator<Item = (&String, &Arc<dyn ProviderExecutor>)> {
         self.executors.iter()
     }
+
+    /// Add `executor` under its `identifier()`, replacing any executor of that name.
+    pub fn register(&mut self, executor: Arc<dyn ProviderExecutor>) {
+        let name = executor.identifier().to_string();
+        self.register_as(name, executor);
+    }
+
+    /// Add `executor` under `name`, so one implementation can back several
+    /// provider types. Replaces any executor of that name.
+    pub fn register_as(&mut self, name: impl Into<String>, executor: Arc<dyn ProviderExecutor>) {
+        self.executors.insert(name.into(), executor);
+    }
 }
 
-pub fn build_registry(
-    global_proxy: Option<String>,
-    client_pool: Arc<HttpClientPool>,
-) -> ExecutorRegistry {
+/// Build the executor registry. Executors read the global proxy and timeouts from
+/// `client_pool` on every request, so updating its network settings takes effect
+/// without rebuilding the registry.
+pub fn build_registry(client_pool: Arc<HttpClientPool>) -> ExecutorRegistry {
     let mut executors: HashMap<String, Arc<dyn ProviderExecutor>> = HashMap::new();
 
     // OpenAI executor (handles all OpenAI-format providers)
     let openai = openai_compat::OpenAICompatExecutor {
         name: "openai".to_string(),
         format: prism_core::provider::Format::OpenAI,
-        global_proxy: global_proxy.clone(),
         client_pool: client_pool.clone(),
     };
     executors.insert("openai".to_string(), Arc::new(openai));
 
-    let codex = codex::CodexExecutor::new(global_proxy.clone(), client_pool.clone());
+    let codex = codex::CodexExecutor::new(client_pool.clone());
     executors.insert("codex".to_string(), Arc::new(codex));
 
     // Claude executor
-    let claude = claude::ClaudeExecutor::new(global_proxy.clone(), client_pool.clone());
+    let claude = claude::ClaudeExecutor::new(client_pool.clone());
     executors.insert("claude".to_string(), Arc::new(claude));
 
     // Gemini executor
-    let gemini = gemini::GeminiExecutor::new(global_proxy.clone(), client_pool.clone());
+    let gemini = gemini::GeminiExecutor::new(client_pool.clone());
     executors.insert("gemini".to_string(), Arc::new(gemini));
 
     ExecutorRegistry { executors }
@@ -0,0 +1,300 @@
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
+use tokio_stream::StreamExt;
+
+use crate::sse::{MAX_SSE_BUFFER_SIZE, SseEvent, parse_sse_stream};
+
+type EventStream =
+    Pin<Box<dyn Stream<Item = Result<SseEvent, prism_core::error::ProxyError>> + Send>>;
+
+/// Wire framing of an upstream streaming body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFraming {
+    Sse,
+    Ndjson,
+}
+
+impl StreamFraming {
+    /// Framing implied by a `Content-Type` value, if it names one.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        let mime = mime.to_ascii_lowercase();
+        if mime == "text/event-stream" {
+            Some(Self::Sse)
+        } else if mime.ends_with("ndjson")
+            || mime.ends_with("jsonl")
+            || mime.ends_with("jsonlines")
+            || mime.ends_with("json-seq")
+        {
+            Some(Self::Ndjson)
+        } else {
+            None
+        }
+    }
+
+    /// Framing implied by the first meaningful byte of the body: a JSON value
+    /// starts NDJSON, anything else (`data:`, `event:`, `:` comments) is SSE.
+    /// Returns `None` while only whitespace or a BOM has been seen.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        let first = bytes
+            .iter()
+            .find(|b| !b.is_ascii_whitespace() && !matches!(b, 0xEF | 0xBB | 0xBF | 0x1E))?;
+        Some(if matches!(first, b'{' | b'[') {
+            Self::Ndjson
+        } else {
+            Self::Sse
+        })
+    }
+}
+
+/// Parse an upstream streaming body as SSE or NDJSON.
+///
+/// The `Content-Type` decides when it names a framing. Otherwise the first
+/// non-whitespace byte is sniffed; the body is only read once it is polled.
+pub fn parse_event_stream<E>(
+    byte_stream: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+    content_type: Option<&str>,
+) -> EventStream
+where
+    E: std::fmt::Display + Send + 'static,
+{
+    match content_type.and_then(StreamFraming::from_content_type) {
+        Some(StreamFraming::Sse) => parse_sse_stream(byte_stream),
+        Some(StreamFraming::Ndjson) => parse_ndjson_stream(byte_stream),
+        None => {
+            let detect = async move {
+                let mut byte_stream = Box::pin(byte_stream);
+                let mut head = Vec::new();
+                let framing = loop {
+                    match byte_stream.next().await {
+                        Some(Ok(bytes)) => {
+                            let framing = StreamFraming::sniff(&bytes);
+                            head.push(Ok(bytes));
+                            if let Some(framing) = framing {
+                                break framing;
+                            }
+                        }
+                        Some(Err(e)) => {
+                            head.push(Err(e));
+                            break StreamFraming::Sse;
+                        }
+                        None => break StreamFraming::Sse,
+                    }
+                };
+                let replay = futures::stream::iter(head).chain(byte_stream);
+                match framing {
+                    StreamFraming::Sse => parse_sse_stream(replay),
+                    StreamFraming::Ndjson => parse_ndjson_stream(replay),
+                }
+            };
+            Box::pin(futures::StreamExt::flatten(futures::stream::once(detect)))
+        }
+    }
+}
+
+/// Parse a newline-delimited JSON byte stream into events.
+/// Each non-blank line becomes one event with no event type, so downstream
+/// translation sees the same chunks an SSE upstream would produce.
+pub fn parse_ndjson_stream<E>(
+    byte_stream: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+) -> EventStream
+where
+    E: std::fmt::Display + Send + 'static,
+{
+    struct NdjsonState<E> {
+        stream: Pin<Box<dyn Stream<Item = Result<Bytes, E>> + Send>>,
+        decoder: NdjsonDecoder,
+        done: bool,
+    }
+
+    let state = NdjsonState {
+        stream: Box::pin(byte_stream),
+        decoder: NdjsonDecoder::default(),
+        done: false,
+    };
+
+    Box::pin(futures::stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        loop {
+            if let Some(event) = state.decoder.next_event(false) {
+                return Some((Ok(event), state));
+            }
+            match state.stream.next().await {
+                Some(Ok(bytes)) => {
+                    if let Err(e) = state.decoder.feed(&bytes) {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+                Some(Err(e)) => {
+                    return Some((
+                        Err(prism_core::error::ProxyError::Network(e.to_string())),
+                        state,
+                    ));
+                }
+                None => {
+                    // A final event may still be buffered without its newline.
+                    state.done = true;
+                    return state
+                        .decoder
+                        .next_event(true)
+                        .map(|event| (Ok(event), state));
+                }
+            }
+        }
+    }))
+}
+
+#[derive(Debug, Default)]
+struct NdjsonDecoder {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl NdjsonDecoder {
+    fn feed(&mut self, bytes: &[u8]) -> Result<(), prism_core::error::ProxyError> {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        if self.buf.len() + bytes.len() > MAX_SSE_BUFFER_SIZE {
+            return Err(prism_core::error::ProxyError::Internal(
+                "NDJSON buffer exceeded maximum size".to_string(),
+            ));
+        }
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Next non-blank line. With `eof`, an unterminated trailing line counts.
+    fn next_event(&mut self, eof: bool) -> Option<SseEvent> {
+        loop {
+            let rest = &self.buf[self.pos..];
+            let (line, consumed) = match rest.iter().position(|&b| b == b'\n') {
+                Some(i) => (&rest[..i], i + 1),
+                None if eof && !rest.is_empty() => (rest, rest.len()),
+                None => return None,
+            };
+            let line = String::from_utf8_lossy(line);
+            // CR, a leading BOM, and JSON text sequence record separators are framing.
+            let line =
+                line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}' || c == '\u{1e}');
+            let event = (!line.is_empty()).then(|| SseEvent {
+                event: None,
+                data: line.to_string(),
+            });
+            self.pos += consumed;
+            if event.is_some() {
+                return event;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect(stream: EventStream) -> Vec<String> {
+        stream.map(|e| e.unwrap().data).collect().await
+    }
+
+    fn chunks(parts: &[&'static [u8]]) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send {
+        futures::stream::iter(
+            parts
+                .iter()
+                .map(|p| Ok(Bytes::from_static(p)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_framing_from_content_type() {
+        let cases = [
+            ("text/event-stream; charset=utf-8", Some(StreamFraming::Sse)),
+            ("application/x-ndjson", Some(StreamFraming::Ndjson)),
+            ("Application/JSONL", Some(StreamFraming::Ndjson)),
+            ("application/json-seq", Some(StreamFraming::Ndjson)),
+            ("application/json", None),
+            ("", None),
+        ];
+        for (ct, expected) in cases {
+            assert_eq!(StreamFraming::from_content_type(ct), expected, "{ct}");
+        }
+    }
+
+    #[test]
+    fn test_framing_sniff() {
+        assert_eq!(
+            StreamFraming::sniff(b"  \r\n{\"a\":1}"),
+            Some(StreamFraming::Ndjson)
+        );
+        assert_eq!(
+            StreamFraming::sniff(b"\xEF\xBB\xBF[1]"),
+            Some(StreamFraming::Ndjson)
+        );
+        assert_eq!(StreamFraming::sniff(b"data: {}"), Some(StreamFraming::Sse));
+        assert_eq!(StreamFraming::sniff(b": ping"), Some(StreamFraming::Sse));
+        assert_eq!(StreamFraming::sniff(b"\n\n "), None);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_lines_split_across_chunks() {
+        let stream = parse_ndjson_stream(chunks(&[
+            b"{\"id\":1}\r\n\n{\"i",
+            b"d\":2}\n",
+            b"{\"id\":3}",
+        ]));
+        assert_eq!(
+            collect(stream).await,
+            ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_utf8_split_across_chunks() {
+        let raw = "{\"t\":\"世\"}\n".as_bytes();
+        let (a, b) = raw.split_at(7);
+        let stream = futures::stream::iter(vec![
+            Ok::<_, std::io::Error>(Bytes::copy_from_slice(a)),
+            Ok(Bytes::copy_from_slice(b)),
+        ]);
+        assert_eq!(
+            collect(parse_ndjson_stream(stream)).await,
+            ["{\"t\":\"世\"}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_sniffs_ndjson() {
+        let stream = parse_event_stream(chunks(&[b"\n", b"{\"a\":1}\n{\"a\":2}\n"]), None);
+        assert_eq!(collect(stream).await, ["{\"a\":1}", "{\"a\":2}"]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_sniffs_sse() {
+        let stream = parse_event_stream(
+            chunks(&[b"data: {\"a\":1}\n\n"]),
+            Some("application/octet-stream"),
+        );
+        assert_eq!(collect(stream).await, ["{\"a\":1}"]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_content_type_wins() {
+        // Declared SSE is never sniffed, even when a line looks like JSON.
+        let stream = parse_event_stream(chunks(&[b"{\"a\":1}\n\n"]), Some("text/event-stream"));
+        assert!(collect(stream).await.is_empty());
+        let stream = parse_event_stream(chunks(&[b"{\"a\":1}\n"]), Some("application/x-ndjson"));
+        assert_eq!(collect(stream).await, ["{\"a\":1}"]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_empty_body() {
+        let stream = parse_event_stream(chunks(&[]), None);
+        assert!(collect(stream).await.is_empty());
+    }
+}
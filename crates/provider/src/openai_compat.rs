@@ -1,15 +1,16 @@
 use crate::common;
 use async_trait::async_trait;
+use bytes::Bytes;
 use prism_core::error::ProxyError;
 use prism_core::provider::*;
 use prism_core::proxy::HttpClientPool;
+use prism_core::signing::SigningInput;
 use serde_json::{Value, json};
 use std::sync::Arc;
 
 pub struct OpenAICompatExecutor {
     pub name: String,
     pub format: Format,
-    pub global_proxy: Option<String>,
     pub client_pool: Arc<HttpClientPool>,
 }
 
@@ -22,13 +23,38 @@ impl OpenAICompatExecutor {
         body: &[u8],
         request_headers: &std::collections::HashMap<String, String>,
     ) -> Result<reqwest::RequestBuilder, ProxyError> {
-        let client = common::build_client(auth, self.global_proxy.as_deref(), &self.client_pool)?;
+        let client = common::build_client(auth, &self.client_pool)?;
         let req = client
             .post(url)
             .header("content-type", "application/json")
             .body(body.to_vec());
         let req = common::apply_auth(req, auth);
-        Ok(common::apply_headers(req, request_headers, auth))
+        let Some(signing) = &auth.signing else {
+            return Ok(common::apply_headers(req, request_headers, auth));
+        };
+
+        // Signature headers are set last and cannot be overridden by clients.
+        let request_headers: std::collections::HashMap<String, String> = request_headers
+            .iter()
+            .filter(|(k, _)| !signing.sets_header(k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let mut req = common::apply_headers(req, &request_headers, auth);
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| ProxyError::Internal(format!("invalid upstream URL {url}: {e}")))?;
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{query}", parsed.path()),
+            None => parsed.path().to_string(),
+        };
+        for (name, value) in signing.sign(&SigningInput {
+            method: "POST",
+            path: &path,
+            body,
+            timestamp: chrono::Utc::now().timestamp(),
+        }) {
+            req = req.header(name, value);
+        }
+        Ok(req)
     }
 }
 
@@ -147,6 +173,68 @@ fn normalize_tool_choice(tool_choice: Value) -> Value {
     tool_choice
 }
 
+fn normalize_response_format(format: Value) -> Value {
+    if format.get("type").and_then(|value| value.as_str()) == Some("json_schema")
+        && let Some(schema) = format
+            .get("json_schema")
+            .and_then(|value| value.as_object())
+    {
+        let mut normalized = schema.clone();
+        normalized.insert("type".into(), json!("json_schema"));
+        return Value::Object(normalized);
+    }
+    format
+}
+
+/// Concatenate the summary text of a Responses `reasoning` output item.
+fn reasoning_summary_text(item: &Value) -> Option<String> {
+    if item.get("type").and_then(|value| value.as_str()) != Some("reasoning") {
+        return None;
+    }
+    let text = item
+        .get("summary")
+        .and_then(|value| value.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|value| value.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .unwrap_or_default();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Map Responses usage to Chat Completions usage, keeping reasoning and cached token details.
+fn responses_usage_to_chat(usage: Option<&Value>) -> Value {
+    let tokens = |key: &str| {
+        usage
+            .and_then(|u| u.get(key))
+            .and_then(|value| value.as_u64())
+            .unwrap_or(0)
+    };
+    let prompt_tokens = tokens("input_tokens");
+    let completion_tokens = tokens("output_tokens");
+    let mut chat_usage = json!({
+        "prompt_tokens": prompt_tokens,
+        "completion_tokens": completion_tokens,
+        "total_tokens": prompt_tokens + completion_tokens,
+    });
+    if let Some(reasoning) = usage
+        .and_then(|u| u.pointer("/output_tokens_details/reasoning_tokens"))
+        .and_then(|value| value.as_u64())
+    {
+        chat_usage["completion_tokens_details"] = json!({"reasoning_tokens": reasoning});
+    }
+    if let Some(cached) = usage
+        .and_then(|u| u.pointer("/input_tokens_details/cached_tokens"))
+        .and_then(|value| value.as_u64())
+    {
+        chat_usage["prompt_tokens_details"] = json!({"cached_tokens": cached});
+    }
+    chat_usage
+}
+
 fn stringify_tool_output(content: Option<&Value>) -> Value {
     match content {
         Some(Value::String(text)) => Value::String(text.clone()),
@@ -279,6 +367,26 @@ pub(crate) fn chat_to_responses(payload: &[u8]) -> Result<Vec<u8>, ProxyError> {
         obj.insert("tool_choice".into(), normalize_tool_choice(tool_choice));
     }
 
+    // reasoning_effort -> reasoning.effort, asking for a summary so it can be surfaced
+    if let Some(effort) = obj.remove("reasoning_effort")
+        && !obj.contains_key("reasoning")
+    {
+        obj.insert(
+            "reasoning".into(),
+            json!({"effort": effort, "summary": "auto"}),
+        );
+    }
+
+    // response_format -> text.format (json_schema fields are flattened)
+    if let Some(format) = obj.remove("response_format")
+        && !obj.contains_key("text")
+    {
+        obj.insert(
+            "text".into(),
+            json!({"format": normalize_response_format(format)}),
+        );
+    }
+
     // max_tokens -> max_output_tokens
     if let Some(max_tokens) = obj.remove("max_tokens")
         && !obj.contains_key("max_output_tokens")
@@ -299,9 +407,13 @@ pub(crate) fn responses_to_chat(payload: &[u8]) -> Result<bytes::Bytes, ProxyErr
 
     // Extract content from output[].content[].text
     let mut content = String::new();
+    let mut reasoning = Vec::new();
     let mut tool_calls = Vec::new();
     if let Some(output) = v.get("output").and_then(|o| o.as_array()) {
         for item in output {
+            if let Some(summary) = reasoning_summary_text(item) {
+                reasoning.push(summary);
+            }
             if item.get("type").and_then(|t| t.as_str()) == Some("message")
                 && let Some(contents) = item.get("content").and_then(|c| c.as_array())
             {
@@ -323,19 +435,7 @@ pub(crate) fn responses_to_chat(payload: &[u8]) -> Result<bytes::Bytes, ProxyErr
     let id = v.get("id").and_then(|i| i.as_str()).unwrap_or("");
     let created = v.get("created_at").and_then(|c| c.as_u64()).unwrap_or(0);
 
-    // Extract usage
-    let usage = v
-        .get("usage")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    let prompt_tokens = usage
-        .get("input_tokens")
-        .and_then(|t| t.as_u64())
-        .unwrap_or(0);
-    let completion_tokens = usage
-        .get("output_tokens")
-        .and_then(|t| t.as_u64())
-        .unwrap_or(0);
+    let usage = responses_usage_to_chat(v.get("usage"));
 
     let finish_reason = match v.get("status").and_then(|s| s.as_str()) {
         _ if !tool_calls.is_empty() => "tool_calls",
@@ -354,6 +454,9 @@ pub(crate) fn responses_to_chat(payload: &[u8]) -> Result<bytes::Bytes, ProxyErr
         "role": "assistant",
         "content": content_value,
     });
+    if !reasoning.is_empty() {
+        message["reasoning_content"] = Value::String(reasoning.join("\n\n"));
+    }
     if !tool_calls.is_empty() {
         message["tool_calls"] = Value::Array(tool_calls);
     }
@@ -368,11 +471,7 @@ pub(crate) fn responses_to_chat(payload: &[u8]) -> Result<bytes::Bytes, ProxyErr
             "message": message,
             "finish_reason": finish_reason,
         }],
-        "usage": {
-            "prompt_tokens": prompt_tokens,
-            "completion_tokens": completion_tokens,
-            "total_tokens": prompt_tokens + completion_tokens,
-        }
+        "usage": usage,
     });
 
     serde_json::to_vec(&chat_response)
@@ -434,9 +533,31 @@ pub(crate) fn synthesize_chat_stream_chunks(
 
     let mut chunks = vec![Ok(StreamChunk {
         event_type: None,
-        data: role_chunk.to_string(),
+        data: role_chunk.to_string().into(),
     })];
 
+    if let Some(reasoning) = message
+        .get("reasoning_content")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())
+    {
+        let reasoning_chunk = json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "delta": {"reasoning_content": reasoning},
+                "finish_reason": null
+            }]
+        });
+        chunks.push(Ok(StreamChunk {
+            event_type: None,
+            data: reasoning_chunk.to_string().into(),
+        }));
+    }
+
     if !content.is_empty() {
         let content_chunk = json!({
             "id": id,
@@ -451,7 +572,7 @@ pub(crate) fn synthesize_chat_stream_chunks(
         });
         chunks.push(Ok(StreamChunk {
             event_type: None,
-            data: content_chunk.to_string(),
+            data: content_chunk.to_string().into(),
         }));
     }
 
@@ -487,7 +608,7 @@ pub(crate) fn synthesize_chat_stream_chunks(
         });
         chunks.push(Ok(StreamChunk {
             event_type: None,
-            data: tool_chunk.to_string(),
+            data: tool_chunk.to_string().into(),
         }));
     }
 
@@ -506,16 +627,222 @@ pub(crate) fn synthesize_chat_stream_chunks(
     });
     chunks.push(Ok(StreamChunk {
         event_type: None,
-        data: stop_chunk.to_string(),
+        data: stop_chunk.to_string().into(),
     }));
     chunks.push(Ok(StreamChunk {
         event_type: None,
-        data: "[DONE]".to_string(),
+        data: Bytes::from_static(b"[DONE]"),
     }));
 
     Ok(chunks)
 }
 
+/// Incrementally translates Responses API SSE events into Chat Completions chunks.
+#[derive(Default)]
+pub(crate) struct ResponsesChatStream {
+    id: String,
+    model: String,
+    created: u64,
+    started: bool,
+    /// Output item ids of function calls, in the order their chat `index` was assigned.
+    tool_items: Vec<String>,
+    done: bool,
+}
+
+impl ResponsesChatStream {
+    fn capture_response(&mut self, response: Option<&Value>) {
+        let Some(response) = response else { return };
+        if let Some(id) = response.get("id").and_then(|v| v.as_str()) {
+            self.id = format!("chatcmpl-{id}");
+        }
+        if let Some(model) = response.get("model").and_then(|v| v.as_str()) {
+            self.model = model.to_string();
+        }
+        if let Some(created) = response.get("created_at").and_then(|v| v.as_u64()) {
+            self.created = created;
+        }
+    }
+
+    fn chunk(&self, delta: Value, finish_reason: Value, usage: Option<Value>) -> StreamChunk {
+        let mut chunk = json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{
+                "index": 0,
+                "delta": delta,
+                "finish_reason": finish_reason,
+            }]
+        });
+        if let Some(usage) = usage {
+            chunk["usage"] = usage;
+        }
+        StreamChunk {
+            event_type: None,
+            data: chunk.to_string().into(),
+        }
+    }
+
+    fn ensure_started(&mut self, out: &mut Vec<StreamChunk>) {
+        if !self.started {
+            self.started = true;
+            out.push(self.chunk(
+                json!({"role": "assistant", "content": ""}),
+                Value::Null,
+                None,
+            ));
+        }
+    }
+
+    /// Translate one upstream event into zero or more chat chunks.
+    pub(crate) fn translate(
+        &mut self,
+        event: &StreamChunk,
+    ) -> Result<Vec<StreamChunk>, ProxyError> {
+        let mut out = Vec::new();
+        if self.done {
+            return Ok(out);
+        }
+        if event.data == "[DONE]" {
+            self.done = true;
+            out.push(StreamChunk {
+                event_type: None,
+                data: Bytes::from_static(b"[DONE]"),
+            });
+            return Ok(out);
+        }
+        let payload: Value = serde_json::from_slice(&event.data)
+            .map_err(|e| ProxyError::Internal(format!("invalid Responses SSE JSON: {e}")))?;
+        let event_type = payload
+            .get("type")
+            .and_then(|v| v.as_str())
+            .or(event.event_type.as_deref())
+            .unwrap_or("");
+
+        match event_type {
+            "response.created" | "response.in_progress" => {
+                self.capture_response(payload.get("response"));
+                self.ensure_started(&mut out);
+            }
+            "response.output_text.delta" => {
+                self.ensure_started(&mut out);
+                let delta = payload.get("delta").and_then(|v| v.as_str()).unwrap_or("");
+                if !delta.is_empty() {
+                    out.push(self.chunk(json!({"content": delta}), Value::Null, None));
+                }
+            }
+            "response.reasoning_summary_text.delta" => {
+                self.ensure_started(&mut out);
+                let delta = payload.get("delta").and_then(|v| v.as_str()).unwrap_or("");
+                if !delta.is_empty() {
+                    out.push(self.chunk(json!({"reasoning_content": delta}), Value::Null, None));
+                }
+            }
+            "response.output_item.added" => {
+                let item = payload.get("item").unwrap_or(&Value::Null);
+                if item.get("type").and_then(|v| v.as_str()) == Some("function_call") {
+                    self.ensure_started(&mut out);
+                    let index = self.tool_items.len();
+                    self.tool_items.push(
+                        item.get("id")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                    );
+                    let call_id = item
+                        .get("call_id")
+                        .and_then(|v| v.as_str())
+                        .or_else(|| item.get("id").and_then(|v| v.as_str()))
+                        .unwrap_or("");
+                    out.push(self.chunk(
+                        json!({"tool_calls": [{
+                            "index": index,
+                            "id": call_id,
+                            "type": "function",
+                            "function": {
+                                "name": item.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+                                "arguments": "",
+                            }
+                        }]}),
+                        Value::Null,
+                        None,
+                    ));
+                }
+            }
+            "response.function_call_arguments.delta" => {
+                let item_id = payload.get("item_id").and_then(|v| v.as_str());
+                let index = item_id
+                    .and_then(|id| self.tool_items.iter().position(|item| item == id))
+                    .or_else(|| self.tool_items.len().checked_sub(1));
+                let delta = payload.get("delta").and_then(|v| v.as_str()).unwrap_or("");
+                if let Some(index) = index
+                    && !delta.is_empty()
+                {
+                    out.push(self.chunk(
+                        json!({"tool_calls": [{
+                            "index": index,
+                            "function": {"arguments": delta},
+                        }]}),
+                        Value::Null,
+                        None,
+                    ));
+                }
+            }
+            "response.completed" | "response.incomplete" => {
+                let response = payload.get("response");
+                self.capture_response(response);
+                self.ensure_started(&mut out);
+                let finish_reason = if !self.tool_items.is_empty() {
+                    "tool_calls"
+                } else if event_type == "response.incomplete" {
+                    "length"
+                } else {
+                    "stop"
+                };
+                let usage = responses_usage_to_chat(response.and_then(|r| r.get("usage")));
+                out.push(self.chunk(json!({}), json!(finish_reason), Some(usage)));
+                out.push(StreamChunk {
+                    event_type: None,
+                    data: Bytes::from_static(b"[DONE]"),
+                });
+                self.done = true;
+            }
+            "response.failed" | "error" => {
+                self.done = true;
+                return Err(ProxyError::Upstream {
+                    status: 502,
+                    body: payload.to_string(),
+                    retry_after_secs: None,
+                    headers: Default::default(),
+                });
+            }
+            _ => {}
+        }
+        Ok(out)
+    }
+}
+
+/// Wrap a Responses API event stream so it yields Chat Completions chunks.
+pub(crate) fn responses_stream_to_chat(result: StreamResult) -> StreamResult {
+    use futures::StreamExt;
+    let stream = result
+        .stream
+        .scan(ResponsesChatStream::default(), |state, event| {
+            let chunks: Vec<Result<StreamChunk, ProxyError>> =
+                match event.and_then(|event| state.translate(&event)) {
+                    Ok(chunks) => chunks.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+            futures::future::ready(Some(futures::stream::iter(chunks)))
+        })
+        .flatten();
+    StreamResult {
+        headers: result.headers,
+        stream: Box::pin(stream),
+    }
+}
+
 #[async_trait]
 impl ProviderExecutor for OpenAICompatExecutor {
     fn identifier(&self) -> &str {
@@ -549,7 +876,8 @@ impl ProviderExecutor for OpenAICompatExecutor {
         };
 
         let req = self.build_request(auth, &url, &body, &request.headers)?;
-        let (resp_body, headers) = common::handle_response(req.send().await?).await?;
+        let (resp_body, headers) =
+            common::handle_response(common::send(req, auth, &self.client_pool).await?).await?;
 
         // Convert response back to Chat Completions format (unless passthrough)
         let payload = if request.responses_passthrough {
@@ -573,26 +901,46 @@ impl ProviderExecutor for OpenAICompatExecutor {
             let base_url = auth.resolved_base_url();
             let url = format!("{base_url}/v1/responses");
             let req = self.build_request(auth, &url, &request.payload, &request.headers)?;
-            return common::handle_stream_response(req.send().await?).await;
+            return common::send_stream(req, auth, &self.client_pool).await;
         }
 
         if use_responses_api(auth) {
-            // Responses API: execute non-streaming, then emit as streaming chunks.
-            let response = self.execute(auth, request).await?;
-            let v: Value = serde_json::from_slice(&response.payload)
+            // Responses API: stream `response.*` events and translate them incrementally.
+            let base_url = auth.resolved_base_url();
+            let url = format!("{base_url}/v1/responses");
+            let mut body: Value = serde_json::from_slice(&chat_to_responses(&request.payload)?)
                 .map_err(|e| ProxyError::Internal(e.to_string()))?;
-            let chunks = synthesize_chat_stream_chunks(&v)?;
-            return Ok(StreamResult {
-                headers: response.headers,
-                stream: Box::pin(futures::stream::iter(chunks)),
-            });
+            body["stream"] = Value::Bool(true);
+            let body =
+                serde_json::to_vec(&body).map_err(|e| ProxyError::Internal(e.to_string()))?;
+            let req = self.build_request(auth, &url, &body, &request.headers)?;
+            let resp = common::send(req, auth, &self.client_pool).await?;
+
+            // Upstreams that ignore `stream` answer with a complete JSON body; replay it.
+            let is_json = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.starts_with("application/json"));
+            if is_json {
+                let (resp_body, headers) = common::handle_response(resp).await?;
+                let v: Value = serde_json::from_slice(&responses_to_chat(&resp_body)?)
+                    .map_err(|e| ProxyError::Internal(e.to_string()))?;
+                return Ok(StreamResult {
+                    headers,
+                    stream: Box::pin(futures::stream::iter(synthesize_chat_stream_chunks(&v)?)),
+                });
+            }
+            let result = common::handle_stream_response(resp, auth.json_repair).await?;
+            let result = common::inject_stream_faults(result, auth, &self.client_pool);
+            return Ok(responses_stream_to_chat(result));
         }
 
         let base_url = auth.resolved_base_url();
         let url = format!("{base_url}/v1/chat/completions");
 
         let req = self.build_request(auth, &url, &request.payload, &request.headers)?;
-        common::handle_stream_response(req.send().await?).await
+        common::send_stream(req, auth, &self.client_pool).await
     }
 
     fn supported_models(&self, auth: &AuthRecord) -> Vec<ModelInfo> {
@@ -922,7 +1270,7 @@ mod tests {
         let chunks = synthesize_chat_stream_chunks(&chat_response).unwrap();
         let serialized = chunks
             .into_iter()
-            .map(|chunk| chunk.unwrap().data)
+            .map(|chunk| String::from_utf8(chunk.unwrap().data.to_vec()).unwrap())
             .collect::<Vec<_>>();
 
         assert!(
@@ -942,4 +1290,177 @@ mod tests {
         );
         assert_eq!(serialized.last().unwrap(), "[DONE]");
     }
+
+    fn sse(event: &str, data: Value) -> StreamChunk {
+        StreamChunk {
+            event_type: Some(event.to_string()),
+            data: data.to_string().into(),
+        }
+    }
+
+    fn translate_all(events: Vec<StreamChunk>) -> Vec<String> {
+        let mut state = ResponsesChatStream::default();
+        events
+            .iter()
+            .flat_map(|event| state.translate(event).unwrap())
+            .map(|chunk| String::from_utf8(chunk.data.to_vec()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_responses_stream_translates_text_deltas() {
+        let out = translate_all(vec![
+            sse(
+                "response.created",
+                json!({"type": "response.created", "response": {"id": "resp_1", "model": "gpt-4o", "created_at": 1700000000u64}}),
+            ),
+            sse(
+                "response.output_text.delta",
+                json!({"type": "response.output_text.delta", "delta": "Hel"}),
+            ),
+            sse(
+                "response.output_text.delta",
+                json!({"type": "response.output_text.delta", "delta": "lo"}),
+            ),
+            sse(
+                "response.completed",
+                json!({"type": "response.completed", "response": {"id": "resp_1", "status": "completed", "usage": {"input_tokens": 4, "output_tokens": 2}}}),
+            ),
+        ]);
+
+        assert_eq!(out.len(), 5);
+        let role: Value = serde_json::from_str(&out[0]).unwrap();
+        assert_eq!(role["id"], "chatcmpl-resp_1");
+        assert_eq!(role["choices"][0]["delta"]["role"], "assistant");
+        let first: Value = serde_json::from_str(&out[1]).unwrap();
+        assert_eq!(first["choices"][0]["delta"]["content"], "Hel");
+        let stop: Value = serde_json::from_str(&out[3]).unwrap();
+        assert_eq!(stop["choices"][0]["finish_reason"], "stop");
+        assert_eq!(stop["usage"]["total_tokens"], 6);
+        assert_eq!(out[4], "[DONE]");
+    }
+
+    #[test]
+    fn test_responses_stream_translates_function_calls() {
+        let out = translate_all(vec![
+            sse(
+                "response.output_item.added",
+                json!({"type": "response.output_item.added", "item": {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "lookup"}}),
+            ),
+            sse(
+                "response.function_call_arguments.delta",
+                json!({"type": "response.function_call_arguments.delta", "item_id": "fc_1", "delta": "{\"q\":"}),
+            ),
+            sse(
+                "response.function_call_arguments.delta",
+                json!({"type": "response.function_call_arguments.delta", "item_id": "fc_1", "delta": "1}"}),
+            ),
+            sse(
+                "response.completed",
+                json!({"type": "response.completed", "response": {"status": "completed"}}),
+            ),
+        ]);
+
+        let added: Value = serde_json::from_str(&out[1]).unwrap();
+        let call = &added["choices"][0]["delta"]["tool_calls"][0];
+        assert_eq!(call["id"], "call_1");
+        assert_eq!(call["function"]["name"], "lookup");
+        let args: Value = serde_json::from_str(&out[3]).unwrap();
+        assert_eq!(
+            args["choices"][0]["delta"]["tool_calls"][0]["function"]["arguments"],
+            "1}"
+        );
+        let stop: Value = serde_json::from_str(&out[4]).unwrap();
+        assert_eq!(stop["choices"][0]["finish_reason"], "tool_calls");
+    }
+
+    #[test]
+    fn test_responses_stream_failure_is_an_error() {
+        let mut state = ResponsesChatStream::default();
+        let failed = sse(
+            "response.failed",
+            json!({"type": "response.failed", "response": {"error": {"message": "boom"}}}),
+        );
+        assert!(matches!(
+            state.translate(&failed),
+            Err(ProxyError::Upstream { status: 502, .. })
+        ));
+    }
+
+    #[test]
+    fn test_chat_to_responses_maps_reasoning_effort_and_response_format() {
+        let chat_req = json!({
+            "model": "gpt-5",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "reasoning_effort": "high",
+            "parallel_tool_calls": false,
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {"name": "answer", "schema": {"type": "object"}, "strict": true}
+            }
+        });
+        let payload = serde_json::to_vec(&chat_req).unwrap();
+        let result: Value = serde_json::from_slice(&chat_to_responses(&payload).unwrap()).unwrap();
+
+        assert_eq!(result["reasoning"]["effort"], "high");
+        assert_eq!(result["reasoning"]["summary"], "auto");
+        assert!(result.get("reasoning_effort").is_none());
+        assert_eq!(result["parallel_tool_calls"], false);
+        assert_eq!(result["text"]["format"]["type"], "json_schema");
+        assert_eq!(result["text"]["format"]["name"], "answer");
+        assert_eq!(result["text"]["format"]["strict"], true);
+        assert!(result.get("response_format").is_none());
+    }
+
+    #[test]
+    fn test_responses_to_chat_maps_reasoning_summary_and_usage_details() {
+        let resp = json!({
+            "id": "resp_r",
+            "model": "gpt-5",
+            "status": "completed",
+            "output": [
+                {"type": "reasoning", "id": "rs_1", "summary": [
+                    {"type": "summary_text", "text": "Considered options."}
+                ]},
+                {"type": "message", "content": [{"type": "output_text", "text": "Done"}]}
+            ],
+            "usage": {
+                "input_tokens": 10,
+                "output_tokens": 30,
+                "input_tokens_details": {"cached_tokens": 4},
+                "output_tokens_details": {"reasoning_tokens": 20}
+            }
+        });
+        let payload = serde_json::to_vec(&resp).unwrap();
+        let result: Value = serde_json::from_slice(&responses_to_chat(&payload).unwrap()).unwrap();
+
+        let message = &result["choices"][0]["message"];
+        assert_eq!(message["content"], "Done");
+        assert_eq!(message["reasoning_content"], "Considered options.");
+        assert_eq!(
+            result["usage"]["completion_tokens_details"]["reasoning_tokens"],
+            20
+        );
+        assert_eq!(result["usage"]["prompt_tokens_details"]["cached_tokens"], 4);
+
+        let chunks = synthesize_chat_stream_chunks(&result).unwrap();
+        assert!(chunks.iter().any(|chunk| {
+            std::str::from_utf8(&chunk.as_ref().unwrap().data)
+                .unwrap()
+                .contains("\"reasoning_content\"")
+        }));
+    }
+
+    #[test]
+    fn test_responses_stream_translates_reasoning_summary() {
+        let out = translate_all(vec![sse(
+            "response.reasoning_summary_text.delta",
+            json!({"type": "response.reasoning_summary_text.delta", "delta": "Thinking"}),
+        )]);
+        let chunk: Value = serde_json::from_str(&out[1]).unwrap();
+        assert_eq!(
+            chunk["choices"][0]["delta"]["reasoning_content"],
+            "Thinking"
+        );
+    }
 }
@@ -0,0 +1,291 @@
+//! Daily and monthly usage counters per credential.
+//!
+//! Counters are keyed by credential name (`provider/profile`) so they survive
+//! config reloads, which mint new credential ids. Periods follow the UTC
+//! calendar and roll over lazily on the next read or write.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use dashmap::DashMap;
+use prism_core::config::{CredentialQuotaConfig, QuotaLimits};
+pub use prism_core::config::{PeriodReport, PeriodUsage, QuotaReport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuotaPeriod {
+    Daily,
+    Monthly,
+}
+
+impl QuotaPeriod {
+    /// First day of the period containing `now`.
+    fn start(self, now: DateTime<Utc>) -> NaiveDate {
+        let today = now.date_naive();
+        match self {
+            Self::Daily => today,
+            Self::Monthly => today.with_day(1).unwrap_or(today),
+        }
+    }
+
+    /// When the period starting on `start` ends.
+    fn resets_at(self, start: NaiveDate) -> DateTime<Utc> {
+        let next = match self {
+            Self::Daily => start.succ_opt(),
+            Self::Monthly => start.checked_add_months(Months::new(1)),
+        };
+        next.unwrap_or(NaiveDate::MAX)
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PeriodCounter {
+    start: NaiveDate,
+    usage: PeriodUsage,
+}
+
+impl PeriodCounter {
+    /// Usage in the period containing `now`; a stale counter reads as empty.
+    fn current(&self, period: QuotaPeriod, now: DateTime<Utc>) -> PeriodUsage {
+        if self.start == period.start(now) {
+            self.usage
+        } else {
+            PeriodUsage::default()
+        }
+    }
+
+    fn add(&mut self, period: QuotaPeriod, now: DateTime<Utc>, tokens: u64, cost_usd: f64) {
+        let start = period.start(now);
+        if self.start != start {
+            self.start = start;
+            self.usage = PeriodUsage::default();
+        }
+        self.usage.requests += 1;
+        self.usage.tokens += tokens;
+        self.usage.cost_usd += cost_usd;
+    }
+}
+
+struct CredentialUsage {
+    daily: PeriodCounter,
+    monthly: PeriodCounter,
+}
+
+/// Counters of one credential as persisted across restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuotaCounters {
+    pub daily_start: NaiveDate,
+    pub daily: PeriodUsage,
+    pub monthly_start: NaiveDate,
+    pub monthly: PeriodUsage,
+}
+
+/// A credential over its quota, and when it becomes available again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaExhausted {
+    pub period: QuotaPeriod,
+    pub resets_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct CredentialQuotaTracker {
+    usage: DashMap<String, CredentialUsage>,
+}
+
+impl CredentialQuotaTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one served request with its tokens and cost.
+    pub fn record(&self, key: &str, tokens: u64, cost_usd: f64) {
+        self.record_at(key, tokens, cost_usd, Utc::now());
+    }
+
+    /// Count a request served at `now`, e.g. one replayed from another instance.
+    pub fn record_at(&self, key: &str, tokens: u64, cost_usd: f64, now: DateTime<Utc>) {
+        let mut entry = self
+            .usage
+            .entry(key.to_string())
+            .or_insert_with(|| CredentialUsage {
+                daily: PeriodCounter {
+                    start: QuotaPeriod::Daily.start(now),
+                    usage: PeriodUsage::default(),
+                },
+                monthly: PeriodCounter {
+                    start: QuotaPeriod::Monthly.start(now),
+                    usage: PeriodUsage::default(),
+                },
+            });
+        entry.daily.add(QuotaPeriod::Daily, now, tokens, cost_usd);
+        entry
+            .monthly
+            .add(QuotaPeriod::Monthly, now, tokens, cost_usd);
+    }
+
+    /// The period whose cap `key` has reached, if any. The monthly period wins
+    /// when both are reached, since it resets last.
+    pub fn exhausted(&self, key: &str, quota: &CredentialQuotaConfig) -> Option<QuotaExhausted> {
+        self.exhausted_at(key, quota, Utc::now())
+    }
+
+    fn exhausted_at(
+        &self,
+        key: &str,
+        quota: &CredentialQuotaConfig,
+        now: DateTime<Utc>,
+    ) -> Option<QuotaExhausted> {
+        let usage = self.usage.get(key)?;
+        [
+            (QuotaPeriod::Monthly, &quota.monthly, &usage.monthly),
+            (QuotaPeriod::Daily, &quota.daily, &usage.daily),
+        ]
+        .into_iter()
+        .find_map(|(period, limits, counter)| {
+            let used = counter.current(period, now);
+            limits
+                .reached(used.requests, used.tokens, used.cost_usd)
+                .then(|| QuotaExhausted {
+                    period,
+                    resets_at: period.resets_at(period.start(now)),
+                })
+        })
+    }
+
+    /// Snapshot of every credential's counters.
+    pub fn export(&self) -> HashMap<String, QuotaCounters> {
+        self.usage
+            .iter()
+            .map(|entry| {
+                let usage = entry.value();
+                let counters = QuotaCounters {
+                    daily_start: usage.daily.start,
+                    daily: usage.daily.usage,
+                    monthly_start: usage.monthly.start,
+                    monthly: usage.monthly.usage,
+                };
+                (entry.key().clone(), counters)
+            })
+            .collect()
+    }
+
+    /// Replace counters with a snapshot; periods that have since ended read as empty.
+    pub fn restore(&self, counters: HashMap<String, QuotaCounters>) {
+        for (key, c) in counters {
+            self.usage.insert(
+                key,
+                CredentialUsage {
+                    daily: PeriodCounter {
+                        start: c.daily_start,
+                        usage: c.daily,
+                    },
+                    monthly: PeriodCounter {
+                        start: c.monthly_start,
+                        usage: c.monthly,
+                    },
+                },
+            );
+        }
+    }
+
+    /// Consumption of `key` against `quota` (uncapped when `None`).
+    pub fn report(&self, key: &str, quota: Option<&CredentialQuotaConfig>) -> QuotaReport {
+        let now = Utc::now();
+        let usage = self.usage.get(key);
+        let quota = quota.cloned().unwrap_or_default();
+        let period_report = |period: QuotaPeriod, limits: QuotaLimits| {
+            let used = usage
+                .as_ref()
+                .map(|u| match period {
+                    QuotaPeriod::Daily => u.daily.current(period, now),
+                    QuotaPeriod::Monthly => u.monthly.current(period, now),
+                })
+                .unwrap_or_default();
+            PeriodReport {
+                used,
+                limits,
+                resets_at: period.resets_at(period.start(now)),
+                exhausted: limits.reached(used.requests, used.tokens, used.cost_usd),
+            }
+        };
+        QuotaReport {
+            daily: period_report(QuotaPeriod::Daily, quota.daily),
+            monthly: period_report(QuotaPeriod::Monthly, quota.monthly),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_daily_cap_resets_at_utc_midnight() {
+        let tracker = CredentialQuotaTracker::new();
+        let quota = CredentialQuotaConfig {
+            daily: QuotaLimits {
+                requests: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let now = at(2026, 3, 14, 10);
+        tracker.record_at("openai/main", 10, 0.0, now);
+        assert_eq!(tracker.exhausted_at("openai/main", &quota, now), None);
+        tracker.record_at("openai/main", 10, 0.0, now);
+        assert_eq!(
+            tracker.exhausted_at("openai/main", &quota, now),
+            Some(QuotaExhausted {
+                period: QuotaPeriod::Daily,
+                resets_at: at(2026, 3, 15, 0),
+            })
+        );
+        assert_eq!(
+            tracker.exhausted_at("openai/main", &quota, at(2026, 3, 15, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_monthly_token_and_cost_caps() {
+        let tracker = CredentialQuotaTracker::new();
+        let quota = CredentialQuotaConfig {
+            daily: QuotaLimits {
+                cost_usd: 1.0,
+                ..Default::default()
+            },
+            monthly: QuotaLimits {
+                tokens: 1000,
+                ..Default::default()
+            },
+        };
+        tracker.record_at("k", 600, 0.5, at(2026, 12, 1, 8));
+        tracker.record_at("k", 600, 0.6, at(2026, 12, 31, 23));
+        // Both caps are reached on Dec 31; the monthly reset comes later.
+        assert_eq!(
+            tracker.exhausted_at("k", &quota, at(2026, 12, 31, 23)),
+            Some(QuotaExhausted {
+                period: QuotaPeriod::Monthly,
+                resets_at: at(2027, 1, 1, 0),
+            })
+        );
+        assert_eq!(tracker.exhausted_at("k", &quota, at(2027, 1, 1, 0)), None);
+    }
+
+    #[test]
+    fn test_report_rolls_over_stale_counters() {
+        let tracker = CredentialQuotaTracker::new();
+        tracker.record_at("k", 5, 0.25, at(2020, 1, 1, 0));
+        let report = tracker.report("k", None);
+        assert_eq!(report.daily.used, PeriodUsage::default());
+        assert!(!report.monthly.exhausted);
+        assert!(report.daily.resets_at > Utc::now());
+    }
+}
@@ -0,0 +1,80 @@
+//! Credential cooldowns and quota counters as they leave the process: an
+//! on-disk snapshot for restarts, and change events for sharing between instances.
+
+use crate::quota::QuotaCounters;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Router state that outlives a restart, keyed by credential name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RouterState {
+    /// Quota cooldown expiry per credential.
+    pub cooldowns: HashMap<String, DateTime<Utc>>,
+    /// Daily/monthly quota counters per credential.
+    pub quotas: HashMap<String, QuotaCounters>,
+}
+
+impl RouterState {
+    /// Read the state file. A missing file yields `None`.
+    pub fn load(path: &Path) -> Result<Option<Self>, anyhow::Error> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the state file atomically (temp file, then rename).
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Cooldown or quota change, as exchanged between instances sharing state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum RouterEvent {
+    /// A credential went into quota cooldown.
+    Cooldown {
+        credential: String,
+        until: DateTime<Utc>,
+    },
+    /// A request was counted against a credential's quota.
+    Usage {
+        credential: String,
+        tokens: u64,
+        cost_usd: f64,
+        at: DateTime<Utc>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("prism-router-state-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("state.json");
+        assert_eq!(RouterState::load(&path).unwrap(), None);
+
+        let state = RouterState {
+            cooldowns: HashMap::from([("openai/main".to_string(), Utc::now())]),
+            quotas: HashMap::new(),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(RouterState::load(&path).unwrap(), Some(state));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
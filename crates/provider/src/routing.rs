@@ -1,18 +1,35 @@
+use crate::quota::{CredentialQuotaTracker, QuotaExhausted, QuotaReport};
+use crate::router_state::{RouterEvent, RouterState};
 use dashmap::DashMap;
-use prism_core::auth_profile::{AuthHeaderKind, AuthProfileEntry, OAuthTokenState};
+use prism_core::auth_profile::{
+    AuthHeaderKind, AuthProfileEntry, OAuthTokenState, SharedOAuthTokenState,
+};
 use prism_core::circuit_breaker::{
     CircuitBreakerConfig, CircuitBreakerPolicy, CircuitState, NoopCircuitBreaker,
     ThreeStateCircuitBreaker,
 };
 use prism_core::config::Config;
 use prism_core::provider::{AuthRecord, Format, ModelEntry, ModelInfo};
-use prism_core::routing::config::CredentialStrategy;
+use prism_core::routing::config::{CredentialGroup, CredentialStrategy, credential_tier};
+use prism_core::routing::planner::rendezvous_score;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::RwLock;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
 use std::time::{Duration, Instant};
 
+/// Runtime routing counters for one credential, as reported by a stats reset.
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialStats {
+    pub id: String,
+    pub provider: String,
+    pub credential_name: Option<String>,
+    pub circuit_state: CircuitState,
+    pub latency_ewma_ms: Option<f64>,
+    pub cooldown_remaining_secs: Option<u64>,
+}
+
 /// Tracks when a credential's quota cooldown expires.
 pub struct QuotaCooldown {
     pub until: Instant,
@@ -40,6 +57,9 @@ pub struct CredentialRouter {
     /// Index: credential_id → (provider_name, index in Vec) for O(1) lookup.
     credential_index: RwLock<HashMap<String, (String, usize)>>,
     runtime_oauth_states: RwLock<HashMap<String, OAuthTokenState>>,
+    /// Background-refreshed tokens by `<provider>/<profile id>`, shared with the
+    /// token refresher.
+    token_states: RwLock<HashMap<String, SharedOAuthTokenState>>,
     counters: RwLock<HashMap<String, AtomicUsize>>,
     strategy: RwLock<CredentialStrategy>,
     /// EWMA latency per credential_id (ms).
@@ -50,6 +70,14 @@ pub struct CredentialRouter {
     cb_config: RwLock<CircuitBreakerConfig>,
     /// Quota cooldowns: credential_id → cooldown expiry.
     cooldowns: DashMap<String, QuotaCooldown>,
+    /// Credential groups in tier order (`routing.credential-groups`).
+    credential_groups: RwLock<Vec<CredentialGroup>>,
+    /// Daily/monthly usage per credential name.
+    quotas: CredentialQuotaTracker,
+    /// Bumped whenever cooldowns or quota counters change, for persistence.
+    state_version: AtomicU64,
+    /// Local changes not yet taken for sharing; `None` while sharing is off.
+    shared_outbox: Mutex<Option<Vec<RouterEvent>>>,
 }
 
 impl CredentialRouter {
@@ -58,12 +86,17 @@ impl CredentialRouter {
             credentials: RwLock::new(HashMap::new()),
             credential_index: RwLock::new(HashMap::new()),
             runtime_oauth_states: RwLock::new(HashMap::new()),
+            token_states: RwLock::new(HashMap::new()),
             counters: RwLock::new(HashMap::new()),
             strategy: RwLock::new(strategy),
             latency_ewma: RwLock::new(HashMap::new()),
             ewma_alpha: RwLock::new(0.3),
             cb_config: RwLock::new(CircuitBreakerConfig::default()),
             cooldowns: DashMap::new(),
+            credential_groups: RwLock::new(Vec::new()),
+            quotas: CredentialQuotaTracker::new(),
+            state_version: AtomicU64::new(0),
+            shared_outbox: Mutex::new(None),
         }
     }
 
@@ -73,10 +106,20 @@ impl CredentialRouter {
         }
     }
 
+    pub fn set_token_states(&self, states: HashMap<String, SharedOAuthTokenState>) {
+        if let Ok(mut guard) = self.token_states.write() {
+            *guard = states;
+        }
+    }
+
     /// Pick the next available credential for the given provider and model.
     /// Skips credentials whose IDs are in `tried`.
     /// If `allowed_credentials` is non-empty, only credentials matching those
     /// glob patterns (by credential name) are considered.
+    /// Only the highest-priority credential tier with an available credential
+    /// is considered, using that tier's strategy or else the default one.
+    /// `sticky` is the request attribute `sticky-hash` hashes, if the request
+    /// carries it.
     pub fn pick(
         &self,
         provider_name: &str,
@@ -84,18 +127,20 @@ impl CredentialRouter {
         tried: &[String],
         _client_region: Option<&str>,
         allowed_credentials: &[String],
+        sticky: Option<&str>,
     ) -> Option<AuthRecord> {
         let creds = self.credentials.read().ok()?;
         let entries = creds.get(provider_name)?;
 
         // Filter to available credentials that support the model and haven't been tried
-        let candidates: Vec<&AuthRecord> = entries
+        let mut candidates: Vec<&AuthRecord> = entries
             .iter()
             .filter(|a| {
                 a.is_available()
                     && a.supports_model(model)
                     && !tried.contains(&a.id)
                     && !self.is_cooled_down(&a.id)
+                    && self.quota_exhausted_for(a).is_none()
                     && check_credential_access(allowed_credentials, a.credential_name.as_deref())
             })
             .collect();
@@ -104,16 +149,28 @@ impl CredentialRouter {
             return None;
         }
 
-        let strategy = self.strategy.read().ok().map(|s| *s)?;
+        let groups = self.credential_groups.read().ok()?;
+        let mut tier_strategy = None;
+        if !groups.is_empty() {
+            let tier_of = |a: &AuthRecord| credential_tier(&groups, a.group.as_deref());
+            let top = candidates.iter().map(|a| tier_of(a)).min()?;
+            candidates.retain(|a| tier_of(a) == top);
+            tier_strategy = groups.get(top).and_then(|group| group.strategy);
+        }
+        drop(groups);
+
+        let strategy = match tier_strategy {
+            Some(strategy) => strategy,
+            None => self.strategy.read().ok().map(|s| *s)?,
+        };
         match strategy {
             CredentialStrategy::FillFirst => candidates.first().cloned().cloned(),
             CredentialStrategy::PriorityWeightedRR => {
                 self.pick_round_robin(provider_name, model, &candidates)
             }
             CredentialStrategy::EwmaLatency => self.pick_latency_aware(&candidates),
-            CredentialStrategy::LeastInflight
-            | CredentialStrategy::StickyHash
-            | CredentialStrategy::RandomTwoChoices => {
+            CredentialStrategy::StickyHash => pick_sticky(provider_name, &candidates, sticky),
+            CredentialStrategy::LeastInflight | CredentialStrategy::RandomTwoChoices => {
                 // These strategies will be fully implemented in SPEC-050.
                 // For now, fall back to round-robin behavior.
                 self.pick_round_robin(provider_name, model, &candidates)
@@ -211,6 +268,15 @@ impl CredentialRouter {
                 until: Instant::now() + duration,
             },
         );
+        self.state_version.fetch_add(1, Ordering::Relaxed);
+        if let Ok(chrono_duration) = chrono::Duration::from_std(duration) {
+            self.share_with(|| {
+                Some(RouterEvent::Cooldown {
+                    credential: self.find_credential(credential_id)?.credential_name?,
+                    until: chrono::Utc::now() + chrono_duration,
+                })
+            });
+        }
     }
 
     /// Check if a credential is currently in quota cooldown.
@@ -226,6 +292,215 @@ impl CredentialRouter {
         false
     }
 
+    /// Time left on a credential's quota cooldown, if one is active.
+    pub fn cooldown_remaining(&self, credential_id: &str) -> Option<Duration> {
+        self.cooldowns
+            .get(credential_id)?
+            .until
+            .checked_duration_since(Instant::now())
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Count a served request against the quota of `credential_name`.
+    pub fn record_usage(&self, credential_name: &str, tokens: u64, cost_usd: f64) {
+        self.quotas.record(credential_name, tokens, cost_usd);
+        self.state_version.fetch_add(1, Ordering::Relaxed);
+        self.share_with(|| {
+            Some(RouterEvent::Usage {
+                credential: credential_name.to_string(),
+                tokens,
+                cost_usd,
+                at: chrono::Utc::now(),
+            })
+        });
+    }
+
+    /// Start collecting cooldown and quota changes for [`Self::take_shared_events`].
+    pub fn share_events(&self) {
+        if let Ok(mut outbox) = self.shared_outbox.lock() {
+            outbox.get_or_insert_with(Vec::new);
+        }
+    }
+
+    /// Cooldown and quota changes made locally since the last call.
+    pub fn take_shared_events(&self) -> Vec<RouterEvent> {
+        self.shared_outbox
+            .lock()
+            .ok()
+            .and_then(|mut outbox| outbox.as_mut().map(std::mem::take))
+            .unwrap_or_default()
+    }
+
+    /// Apply a change made by another instance without sharing it again.
+    pub fn apply_shared_event(&self, event: RouterEvent) {
+        match event {
+            RouterEvent::Cooldown { credential, until } => {
+                self.extend_cooldowns(&HashMap::from([(credential, until)]));
+            }
+            RouterEvent::Usage {
+                credential,
+                tokens,
+                cost_usd,
+                at,
+            } => self.quotas.record_at(&credential, tokens, cost_usd, at),
+        }
+        self.state_version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn share_with(&self, event: impl FnOnce() -> Option<RouterEvent>) {
+        if let Ok(mut outbox) = self.shared_outbox.lock()
+            && let Some(outbox) = outbox.as_mut()
+            && let Some(event) = event()
+        {
+            outbox.push(event);
+        }
+    }
+
+    /// Put every credential named in `until` into cooldown until the given
+    /// time, unless it is already cooling down for longer.
+    fn extend_cooldowns(&self, until: &HashMap<String, chrono::DateTime<chrono::Utc>>) {
+        let now = Instant::now();
+        let wall_now = chrono::Utc::now();
+        let Ok(creds) = self.credentials.read() else {
+            return;
+        };
+        for auth in creds.values().flatten() {
+            let Some(until) = auth.credential_name.as_ref().and_then(|n| until.get(n)) else {
+                continue;
+            };
+            let Ok(remaining) = (*until - wall_now).to_std() else {
+                continue;
+            };
+            let until = now + remaining;
+            if remaining.is_zero()
+                || self
+                    .cooldowns
+                    .get(&auth.id)
+                    .is_some_and(|c| c.until >= until)
+            {
+                continue;
+            }
+            self.cooldowns
+                .insert(auth.id.clone(), QuotaCooldown { until });
+        }
+    }
+
+    /// Config provider name of a credential.
+    pub fn provider_name(&self, credential_id: &str) -> Option<String> {
+        let index = self.credential_index.read().ok()?;
+        let (provider_name, idx) = index.get(credential_id)?;
+        let creds = self.credentials.read().ok()?;
+        Some(creds.get(provider_name)?.get(*idx)?.provider_name.clone())
+    }
+
+    /// The quota period a credential has used up, if any.
+    pub fn quota_exhausted(&self, credential_id: &str) -> Option<QuotaExhausted> {
+        let index = self.credential_index.read().ok()?;
+        let (provider_name, idx) = index.get(credential_id)?;
+        let creds = self.credentials.read().ok()?;
+        self.quota_exhausted_for(creds.get(provider_name)?.get(*idx)?)
+    }
+
+    fn quota_exhausted_for(&self, auth: &AuthRecord) -> Option<QuotaExhausted> {
+        let quota = auth.quota.as_ref()?;
+        let key = auth.credential_name.as_deref().unwrap_or(&auth.id);
+        self.quotas.exhausted(key, quota)
+    }
+
+    /// Consumption vs quota for a credential name, for the dashboard.
+    pub fn quota_report(
+        &self,
+        credential_name: &str,
+        quota: Option<&prism_core::config::CredentialQuotaConfig>,
+    ) -> QuotaReport {
+        self.quotas.report(credential_name, quota)
+    }
+
+    /// Changes whenever persisted state (cooldowns, quota counters) changes.
+    pub fn state_version(&self) -> u64 {
+        self.state_version.load(Ordering::Relaxed)
+    }
+
+    /// Active cooldowns and quota counters, keyed by credential name so they
+    /// can be matched up again after a restart mints new credential ids.
+    pub fn export_state(&self) -> RouterState {
+        let now = Instant::now();
+        let wall_now = chrono::Utc::now();
+        let names: HashMap<String, String> = self
+            .credentials
+            .read()
+            .map(|creds| {
+                creds
+                    .values()
+                    .flatten()
+                    .filter_map(|a| Some((a.id.clone(), a.credential_name.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let cooldowns = self
+            .cooldowns
+            .iter()
+            .filter_map(|entry| {
+                let name = names.get(entry.key())?;
+                let remaining = entry.until.checked_duration_since(now)?;
+                let until = wall_now + chrono::Duration::from_std(remaining).ok()?;
+                Some((name.clone(), until))
+            })
+            .collect();
+        RouterState {
+            cooldowns,
+            quotas: self.quotas.export(),
+        }
+    }
+
+    /// Reapply persisted state to the current credentials. Cooldowns that
+    /// have expired or belong to removed credentials are dropped.
+    pub fn restore_state(&self, state: RouterState) {
+        self.extend_cooldowns(&state.cooldowns);
+        self.quotas.restore(state.quotas);
+    }
+
+    /// Clear circuit breaker, latency EWMA, and quota cooldown for every credential whose
+    /// id or name matches `credential`. Returns the stats from before the reset.
+    pub fn reset_credential_stats(&self, credential: &str) -> Vec<CredentialStats> {
+        let matching: Vec<AuthRecord> = self
+            .credentials
+            .read()
+            .map(|creds| {
+                creds
+                    .values()
+                    .flatten()
+                    .filter(|a| {
+                        a.id == credential || a.credential_name.as_deref() == Some(credential)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut ewma = self.latency_ewma.write().ok();
+        matching
+            .into_iter()
+            .map(|auth| {
+                let cooldown_remaining_secs = self.cooldowns.remove(&auth.id).and_then(|(_, c)| {
+                    c.until
+                        .checked_duration_since(Instant::now())
+                        .map(|d| d.as_secs())
+                });
+                let stats = CredentialStats {
+                    id: auth.id.clone(),
+                    provider: auth.provider_name.clone(),
+                    credential_name: auth.credential_name.clone(),
+                    circuit_state: auth.circuit_state(),
+                    latency_ewma_ms: ewma.as_mut().and_then(|m| m.remove(&auth.id)),
+                    cooldown_remaining_secs,
+                };
+                auth.circuit_breaker.reset();
+                stats
+            })
+            .collect()
+    }
+
     /// O(1) credential lookup by ID using the index.
     pub fn find_credential(&self, auth_id: &str) -> Option<AuthRecord> {
         let index = self.credential_index.read().ok()?;
@@ -266,10 +541,26 @@ impl CredentialRouter {
             .map(|guard| guard.clone())
             .unwrap_or_default();
         let mut map: HashMap<String, Vec<AuthRecord>> = HashMap::new();
+        let aliases = prism_core::model_alias::AliasMap::from_config(config);
+        for warning in &aliases.warnings {
+            tracing::warn!("Model alias collision: {warning}");
+        }
 
         for entry in &config.providers {
             for profile in entry.expanded_auth_profiles() {
-                let auth = build_auth_record(entry, &profile, &cb_config, &runtime_oauth_states);
+                let mut auth =
+                    build_auth_record(entry, &profile, &cb_config, &runtime_oauth_states);
+                // Shadowed aliases are served under the model id only.
+                let prefix = auth.prefix.clone().unwrap_or_default();
+                for model in &mut auth.models {
+                    let shadowed = model.alias.as_deref().is_some_and(|alias| {
+                        let alias = format!("{prefix}{alias}");
+                        aliases.is_shadowed(&entry.name, &profile.id, &model.id, &alias)
+                    });
+                    if shadowed {
+                        model.alias = None;
+                    }
+                }
                 map.entry(entry.name.clone()).or_default().push(auth);
             }
         }
@@ -299,6 +590,17 @@ impl CredentialRouter {
                     }
                 }
             }
+            // Refreshed tokens are written in place, so records share the refresher's state.
+            if let Ok(token_states) = self.token_states.read() {
+                for (provider_name, entries) in map.iter_mut() {
+                    for auth in entries.iter_mut() {
+                        let key = format!("{provider_name}/{}", auth.auth_profile_id);
+                        if let Some(shared) = token_states.get(&key) {
+                            auth.oauth_state = Some(shared.clone());
+                        }
+                    }
+                }
+            }
             *creds = map;
 
             // Rebuild credential index for O(1) lookups
@@ -312,6 +614,10 @@ impl CredentialRouter {
             }
         }
 
+        if let Ok(mut groups) = self.credential_groups.write() {
+            *groups = config.routing.credential_groups.clone();
+        }
+
         // Update credential strategy from default profile
         if let Ok(mut strategy) = self.strategy.write() {
             let profile_name = &config.routing.default_profile;
@@ -330,12 +636,10 @@ impl CredentialRouter {
                     if !auth.is_available() {
                         continue;
                     }
-                    for model_entry in &auth.models {
-                        let model_id = if let Some(ref alias) = model_entry.alias {
-                            alias.clone()
-                        } else {
-                            model_entry.id.clone()
-                        };
+                    for model_entry in auth.models.iter().filter(|m| !m.is_pattern()) {
+                        let model_id = auth.prefixed_model_id(
+                            model_entry.alias.as_deref().unwrap_or(&model_entry.id),
+                        );
                         // Avoid duplicates
                         if !models.iter().any(|m: &ModelInfo| m.id == model_id) {
                             models.push(ModelInfo {
@@ -351,12 +655,19 @@ impl CredentialRouter {
         models
     }
 
-    /// Check if the model name matches any credential that has a prefix configured.
+    /// Check if the model name carries the prefix of an available credential that
+    /// supports it.
     pub fn model_has_prefix(&self, model: &str) -> bool {
         if let Ok(creds) = self.credentials.read() {
             for entries in creds.values() {
                 for auth in entries {
-                    if auth.prefix.is_some() && auth.is_available() && auth.supports_model(model) {
+                    if auth
+                        .prefix
+                        .as_deref()
+                        .is_some_and(|prefix| !prefix.is_empty() && model.starts_with(prefix))
+                        && auth.is_available()
+                        && auth.supports_model(model)
+                    {
                         return true;
                     }
                 }
@@ -395,6 +706,32 @@ impl CredentialRouter {
     }
 }
 
+/// The candidate with the highest weighted rendezvous score for `sticky`, the
+/// same score the route planner orders by. Without a sticky value the highest
+/// weight wins, first one on ties.
+fn pick_sticky(
+    provider_name: &str,
+    candidates: &[&AuthRecord],
+    sticky: Option<&str>,
+) -> Option<AuthRecord> {
+    let Some(sticky) = sticky.filter(|s| !s.is_empty()) else {
+        return candidates
+            .iter()
+            .rev()
+            .max_by_key(|a| a.weight)
+            .map(|a| (*a).clone());
+    };
+    candidates
+        .iter()
+        .map(|a| {
+            let name = a.credential_name.as_deref().unwrap_or(&a.id);
+            let score = rendezvous_score(sticky, provider_name, name, a.weight as f64);
+            (score, a)
+        })
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, a)| (*a).clone())
+}
+
 fn build_auth_record(
     entry: &prism_core::config::ProviderKeyEntry,
     profile: &AuthProfileEntry,
@@ -438,6 +775,7 @@ fn build_auth_record(
         id: uuid::Uuid::new_v4().to_string(),
         provider: entry.format,
         upstream: entry.upstream_kind(),
+        executor: entry.executor.clone(),
         provider_name: entry.name.clone(),
         api_key: profile
             .secret
@@ -475,6 +813,8 @@ fn build_auth_record(
         oauth_state: effective_oauth_state.map(|state| Arc::new(RwLock::new(state))),
         weight: profile.weight.max(1),
         region: profile.region.clone().or_else(|| entry.region.clone()),
+        group: profile.group.clone().or_else(|| entry.group.clone()),
+        quota: profile.quota.clone().or_else(|| entry.quota.clone()),
         upstream_presentation: if use_profile_presentation {
             profile.upstream_presentation.clone()
         } else {
@@ -483,6 +823,10 @@ fn build_auth_record(
         vertex: entry.vertex,
         vertex_project: entry.vertex_project.clone(),
         vertex_location: entry.vertex_location.clone(),
+        allowed_server_tools: entry.allowed_server_tools.clone(),
+        allowed_betas: entry.allowed_betas.clone(),
+        signing: entry.signing.clone(),
+        json_repair: entry.json_repair,
     }
 }
 
@@ -498,6 +842,7 @@ mod tests {
             id: id.to_string(),
             provider: format,
             upstream: prism_core::provider::UpstreamKind::from(format),
+            executor: None,
             provider_name: provider_name.to_string(),
             api_key: format!("key-{id}"),
             base_url: None,
@@ -523,10 +868,16 @@ mod tests {
             oauth_state: None,
             weight: 1,
             region: None,
+            group: None,
+            quota: None,
             upstream_presentation: Default::default(),
             vertex: false,
             vertex_project: None,
             vertex_location: None,
+            allowed_server_tools: None,
+            allowed_betas: None,
+            signing: None,
+            json_repair: false,
         }
     }
 
@@ -538,6 +889,15 @@ mod tests {
                 .or_default()
                 .push(auth);
         }
+        *router.credential_index.write().unwrap() = map
+            .iter()
+            .flat_map(|(provider, creds)| {
+                creds
+                    .iter()
+                    .enumerate()
+                    .map(|(i, auth)| (auth.id.clone(), (provider.clone(), i)))
+            })
+            .collect();
         *router.credentials.write().unwrap() = map;
         router
     }
@@ -553,7 +913,9 @@ mod tests {
                 make_auth("b", "openai", Format::OpenAI, vec!["gpt-4"]),
             ],
         );
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "a");
     }
 
@@ -567,7 +929,7 @@ mod tests {
             ],
         );
         let picked = router
-            .pick("openai", "gpt-4", &["a".to_string()], None, &[])
+            .pick("openai", "gpt-4", &["a".to_string()], None, &[], None)
             .unwrap();
         assert_eq!(picked.id, "b");
     }
@@ -578,7 +940,7 @@ mod tests {
             CredentialStrategy::FillFirst,
             vec![make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"])],
         );
-        let picked = router.pick("openai", "gpt-4", &["a".to_string()], None, &[]);
+        let picked = router.pick("openai", "gpt-4", &["a".to_string()], None, &[], None);
         assert!(picked.is_none());
     }
 
@@ -588,7 +950,7 @@ mod tests {
             CredentialStrategy::FillFirst,
             vec![make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"])],
         );
-        let picked = router.pick("openai", "gpt-3.5", &[], None, &[]);
+        let picked = router.pick("openai", "gpt-3.5", &[], None, &[], None);
         assert!(picked.is_none());
     }
 
@@ -598,7 +960,7 @@ mod tests {
             CredentialStrategy::FillFirst,
             vec![make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"])],
         );
-        let picked = router.pick("claude", "gpt-4", &[], None, &[]);
+        let picked = router.pick("claude", "gpt-4", &[], None, &[], None);
         assert!(picked.is_none());
     }
 
@@ -615,10 +977,18 @@ mod tests {
             ],
         );
 
-        let first = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
-        let second = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
-        let third = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
-        let fourth = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let first = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
+        let second = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
+        let third = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
+        let fourth = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
 
         assert_eq!(first.id, "a");
         assert_eq!(second.id, "b");
@@ -637,11 +1007,183 @@ mod tests {
         // With weights 2:1, total weight = 3
         // slots: a(0), a(1), b(2)
         let picks: Vec<String> = (0..6)
-            .map(|_| router.pick("openai", "gpt-4", &[], None, &[]).unwrap().id)
+            .map(|_| {
+                router
+                    .pick("openai", "gpt-4", &[], None, &[], None)
+                    .unwrap()
+                    .id
+            })
             .collect();
         assert_eq!(picks, vec!["a", "a", "b", "a", "a", "b"]);
     }
 
+    // === Credential Tiers ===
+
+    #[test]
+    fn test_credential_tiers_exhaust_primary_first() {
+        let grouped = |id: &str, group: &str| {
+            let mut auth = make_auth(id, "openai", Format::OpenAI, vec!["gpt-4"]);
+            auth.group = Some(group.to_string());
+            auth
+        };
+        let router = setup_router(
+            CredentialStrategy::PriorityWeightedRR,
+            vec![
+                grouped("overflow", "overflow"),
+                grouped("p1", "primary"),
+                grouped("p2", "primary"),
+                make_auth("ungrouped", "openai", Format::OpenAI, vec!["gpt-4"]),
+            ],
+        );
+        *router.credential_groups.write().unwrap() = vec![
+            CredentialGroup::new("primary"),
+            CredentialGroup::new("overflow"),
+        ];
+
+        // Round-robin stays within the primary tier.
+        let picks: Vec<String> = (0..4)
+            .map(|_| {
+                router
+                    .pick("openai", "gpt-4", &[], None, &[], None)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        assert_eq!(picks, vec!["p1", "p2", "p1", "p2"]);
+
+        // Overflow only once the primary tier is unavailable, ungrouped last.
+        router.set_quota_cooldown("p1", Duration::from_secs(60));
+        let tried = vec!["p2".to_string()];
+        let picked = router
+            .pick("openai", "gpt-4", &tried, None, &[], None)
+            .unwrap();
+        assert_eq!(picked.id, "overflow");
+        let tried = vec!["p2".to_string(), "overflow".to_string()];
+        let picked = router
+            .pick("openai", "gpt-4", &tried, None, &[], None)
+            .unwrap();
+        assert_eq!(picked.id, "ungrouped");
+    }
+
+    #[test]
+    fn test_credential_tier_uses_its_own_strategy() {
+        let grouped = |id: &str, group: &str| {
+            let mut auth = make_auth(id, "openai", Format::OpenAI, vec!["gpt-4"]);
+            auth.group = Some(group.to_string());
+            auth
+        };
+        let router = setup_router(
+            CredentialStrategy::PriorityWeightedRR,
+            vec![
+                grouped("p1", "primary"),
+                grouped("p2", "primary"),
+                grouped("o1", "overflow"),
+                grouped("o2", "overflow"),
+            ],
+        );
+        *router.credential_groups.write().unwrap() = vec![
+            CredentialGroup {
+                name: "primary".to_string(),
+                strategy: Some(CredentialStrategy::FillFirst),
+            },
+            CredentialGroup::new("overflow"),
+        ];
+
+        // Fill-first in the primary tier.
+        let picks: Vec<String> = (0..3)
+            .map(|_| {
+                router
+                    .pick("openai", "gpt-4", &[], None, &[], None)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        assert_eq!(picks, vec!["p1", "p1", "p1"]);
+
+        // The overflow tier falls back to the default round-robin.
+        let tried = vec!["p1".to_string(), "p2".to_string()];
+        let picks: Vec<String> = (0..2)
+            .map(|_| {
+                router
+                    .pick("openai", "gpt-4", &tried, None, &[], None)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        assert_eq!(picks, vec!["o1", "o2"]);
+    }
+
+    // === StickyHash Strategy ===
+
+    #[test]
+    fn test_sticky_hash_keeps_client_on_credential() {
+        let creds: Vec<AuthRecord> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|id| make_auth(id, "openai", Format::OpenAI, vec!["gpt-4"]))
+            .collect();
+        let router = setup_router(CredentialStrategy::StickyHash, creds.clone());
+
+        let pick = |sticky: &str| {
+            router
+                .pick("openai", "gpt-4", &[], None, &[], Some(sticky))
+                .unwrap()
+                .id
+        };
+        let first = pick("session-1");
+        assert!((0..5).all(|_| pick("session-1") == first));
+
+        // The same score as the route planner.
+        let expected = creds
+            .iter()
+            .max_by(|a, b| {
+                let score = |c: &AuthRecord| rendezvous_score("session-1", "openai", &c.id, 1.0);
+                score(a).total_cmp(&score(b))
+            })
+            .unwrap();
+        assert_eq!(first, expected.id);
+
+        // Different clients spread over the credentials.
+        let picked: std::collections::HashSet<String> =
+            (0..32).map(|i| pick(&format!("session-{i}"))).collect();
+        assert!(picked.len() > 1);
+
+        // Failover goes to another credential.
+        let next = router
+            .pick(
+                "openai",
+                "gpt-4",
+                std::slice::from_ref(&first),
+                None,
+                &[],
+                Some("session-1"),
+            )
+            .unwrap();
+        assert_ne!(next.id, first);
+    }
+
+    #[test]
+    fn test_sticky_hash_without_value_uses_weight_order() {
+        let mut heavy = make_auth("b", "openai", Format::OpenAI, vec!["gpt-4"]);
+        heavy.weight = 5;
+        let router = setup_router(
+            CredentialStrategy::StickyHash,
+            vec![
+                make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"]),
+                heavy,
+                make_auth("c", "openai", Format::OpenAI, vec!["gpt-4"]),
+            ],
+        );
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
+        assert_eq!(picked.id, "b");
+        let tried = vec!["b".to_string()];
+        let picked = router
+            .pick("openai", "gpt-4", &tried, None, &[], None)
+            .unwrap();
+        assert_eq!(picked.id, "a");
+    }
+
     // === LatencyAware Strategy ===
 
     #[test]
@@ -657,7 +1199,9 @@ mod tests {
         router.record_latency("slow", 500.0);
         router.record_latency("fast", 100.0);
 
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "fast");
     }
 
@@ -673,7 +1217,9 @@ mod tests {
 
         router.record_latency("recorded", 200.0);
         // unrecorded defaults to 0.0, so should be picked
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "unrecorded");
     }
 
@@ -687,7 +1233,9 @@ mod tests {
 
         let router = setup_router(CredentialStrategy::FillFirst, vec![disabled, enabled]);
 
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "enabled");
     }
 
@@ -768,6 +1316,20 @@ mod tests {
         assert!(models.iter().any(|m| m.id == "claude-3"));
     }
 
+    #[test]
+    fn test_all_models_lists_prefixed_ids() {
+        let mut team_a = make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"]);
+        team_a.prefix = Some("team-a/".to_string());
+        let mut team_b = make_auth("b", "openai", Format::OpenAI, vec!["gpt-4"]);
+        team_b.prefix = Some("team-b/".to_string());
+
+        let router = setup_router(CredentialStrategy::FillFirst, vec![team_a, team_b]);
+
+        let mut ids: Vec<String> = router.all_models().into_iter().map(|m| m.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["team-a/gpt-4", "team-b/gpt-4"]);
+    }
+
     #[test]
     fn test_all_models_dedup() {
         let router = setup_router(
@@ -791,8 +1353,9 @@ mod tests {
 
         let router = setup_router(CredentialStrategy::FillFirst, vec![auth]);
 
-        assert!(router.model_has_prefix("gpt-4"));
-        assert!(!router.model_has_prefix("nonexistent"));
+        assert!(router.model_has_prefix("myprefixgpt-4"));
+        assert!(!router.model_has_prefix("gpt-4"));
+        assert!(!router.model_has_prefix("myprefixnonexistent"));
     }
 
     // === check_credential_access ===
@@ -844,17 +1407,99 @@ mod tests {
 
         // With restriction, only "b" matches
         let picked = router
-            .pick("openai", "gpt-4", &[], None, &["b".to_string()])
+            .pick("openai", "gpt-4", &[], None, &["b".to_string()], None)
             .unwrap();
         assert_eq!(picked.id, "b");
 
         // With restriction that matches nothing
-        let picked = router.pick("openai", "gpt-4", &[], None, &["nonexistent".to_string()]);
+        let picked = router.pick(
+            "openai",
+            "gpt-4",
+            &[],
+            None,
+            &["nonexistent".to_string()],
+            None,
+        );
         assert!(picked.is_none());
     }
 
     // === Quota cooldown ===
 
+    #[test]
+    fn test_reset_credential_stats() {
+        let router = setup_router(
+            CredentialStrategy::FillFirst,
+            vec![
+                make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"]),
+                make_auth("b", "openai", Format::OpenAI, vec!["gpt-4"]),
+            ],
+        );
+        router.set_quota_cooldown("a", Duration::from_secs(60));
+        router.record_latency("a", 250.0);
+
+        let stats = router.reset_credential_stats("a");
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].latency_ewma_ms, Some(250.0));
+        assert!(stats[0].cooldown_remaining_secs.is_some());
+        assert!(!router.is_cooled_down("a"));
+        assert!(
+            router.reset_credential_stats("a")[0]
+                .latency_ewma_ms
+                .is_none()
+        );
+        assert!(router.reset_credential_stats("missing").is_empty());
+    }
+
+    #[test]
+    fn test_export_and_restore_state_by_credential_name() {
+        let router = setup_router(
+            CredentialStrategy::FillFirst,
+            vec![
+                make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"]),
+                make_auth("b", "openai", Format::OpenAI, vec!["gpt-4"]),
+            ],
+        );
+        let version = router.state_version();
+        router.set_quota_cooldown("a", Duration::from_secs(60));
+        router.record_usage("b", 100, 0.5);
+        assert!(router.state_version() > version);
+        let state = router.export_state();
+        assert_eq!(state.cooldowns.len(), 1);
+
+        // After a restart the credential ids differ but the names match.
+        let mut renamed = make_auth("new-a", "openai", Format::OpenAI, vec!["gpt-4"]);
+        renamed.credential_name = Some("a".to_string());
+        let restarted = setup_router(CredentialStrategy::FillFirst, vec![renamed]);
+        restarted.restore_state(state);
+        let remaining = restarted.cooldown_remaining("new-a").unwrap();
+        assert!(remaining > Duration::from_secs(58));
+        assert_eq!(restarted.quota_report("b", None).daily.used.requests, 1);
+    }
+
+    #[test]
+    fn test_shared_events_apply_on_peer() {
+        let creds = || vec![make_auth("a", "openai", Format::OpenAI, vec!["gpt-4"])];
+        let local = setup_router(CredentialStrategy::FillFirst, creds());
+        let peer = setup_router(CredentialStrategy::FillFirst, creds());
+        local.set_quota_cooldown("a", Duration::from_secs(5));
+        assert!(local.take_shared_events().is_empty(), "sharing is off");
+
+        local.share_events();
+        local.set_quota_cooldown("a", Duration::from_secs(60));
+        local.record_usage("a", 10, 0.1);
+        let events = local.take_shared_events();
+        assert_eq!(events.len(), 2);
+
+        peer.set_quota_cooldown("a", Duration::from_secs(120));
+        for event in events {
+            peer.apply_shared_event(event);
+        }
+        // A shorter shared cooldown never cuts a local one short.
+        assert!(peer.cooldown_remaining("a").unwrap() > Duration::from_secs(100));
+        assert_eq!(peer.quota_report("a", None).daily.used.tokens, 10);
+        assert!(peer.take_shared_events().is_empty());
+    }
+
     #[test]
     fn test_set_and_check_cooldown() {
         let router = CredentialRouter::new(CredentialStrategy::FillFirst);
@@ -865,6 +1510,20 @@ mod tests {
         assert!(!router.is_cooled_down("cred-2"));
     }
 
+    #[test]
+    fn test_cooldown_remaining() {
+        let router = CredentialRouter::new(CredentialStrategy::FillFirst);
+        assert!(router.cooldown_remaining("cred-1").is_none());
+
+        router.set_quota_cooldown("cred-1", Duration::from_secs(60));
+        let remaining = router.cooldown_remaining("cred-1").unwrap();
+        assert!(remaining > Duration::from_secs(58) && remaining <= Duration::from_secs(60));
+
+        router.set_quota_cooldown("cred-2", Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(router.cooldown_remaining("cred-2").is_none());
+    }
+
     #[test]
     fn test_cooldown_expires() {
         let router = CredentialRouter::new(CredentialStrategy::FillFirst);
@@ -889,7 +1548,9 @@ mod tests {
         router.set_quota_cooldown("a", Duration::from_secs(60));
 
         // Should skip "a" and pick "b"
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "b");
     }
 
@@ -906,7 +1567,7 @@ mod tests {
         router.set_quota_cooldown("a", Duration::from_secs(60));
         router.set_quota_cooldown("b", Duration::from_secs(60));
 
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]);
+        let picked = router.pick("openai", "gpt-4", &[], None, &[], None);
         assert!(picked.is_none());
     }
 
@@ -920,7 +1581,9 @@ mod tests {
         router.set_quota_cooldown("a", Duration::from_millis(1));
         std::thread::sleep(Duration::from_millis(5));
 
-        let picked = router.pick("openai", "gpt-4", &[], None, &[]).unwrap();
+        let picked = router
+            .pick("openai", "gpt-4", &[], None, &[], None)
+            .unwrap();
         assert_eq!(picked.id, "a");
     }
 
@@ -5,7 +5,9 @@ use tokio_stream::StreamExt;
 
 /// Maximum SSE buffer size (16 MB). Prevents unbounded memory growth from
 /// malicious or misbehaving upstream providers.
-const MAX_SSE_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+pub(crate) const MAX_SSE_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
 
 #[derive(Debug, Clone)]
 pub struct SseEvent {
@@ -25,9 +27,157 @@ where
     Box::pin(stream)
 }
 
+/// Incremental SSE decoder.
+///
+/// Bytes are fed as they arrive and complete events are pulled out with
+/// [`SseDecoder::next_event`]. Lines may end in LF, CRLF, or a bare CR; a
+/// leading UTF-8 BOM is dropped; `:` comment lines are skipped. Lines are only
+/// decoded once complete, so a multi-byte character split across network
+/// chunks is reassembled; invalid UTF-8 is replaced rather than failing the
+/// stream. Successive `data:` lines of one event are joined with `\n`.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: Vec<u8>,
+    /// Offset of the first unconsumed byte in `buf`.
+    pos: usize,
+    bom_checked: bool,
+    event: Option<String>,
+    data: String,
+    has_data: bool,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append raw bytes from the upstream.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), prism_core::error::ProxyError> {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        if self.buf.len() + self.data.len() + bytes.len() > MAX_SSE_BUFFER_SIZE {
+            return Err(prism_core::error::ProxyError::Internal(
+                "SSE buffer exceeded maximum size".to_string(),
+            ));
+        }
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Return the next complete event, or `None` until more bytes are fed.
+    pub fn next_event(&mut self) -> Option<SseEvent> {
+        self.drain_lines(false)
+    }
+
+    /// Flush at end of stream: a trailing unterminated line and a pending
+    /// event without its closing blank line are still emitted.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if let Some(event) = self.drain_lines(true) {
+            return Some(event);
+        }
+        self.dispatch()
+    }
+
+    fn drain_lines(&mut self, eof: bool) -> Option<SseEvent> {
+        if !self.strip_bom(eof) {
+            return None;
+        }
+        while let Some((start, end)) = self.next_line(eof) {
+            let line = String::from_utf8_lossy(&self.buf[start..end]).into_owned();
+            if let Some(event) = self.apply_line(&line) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Drop a leading BOM. Returns false while too few bytes have arrived to tell.
+    fn strip_bom(&mut self, eof: bool) -> bool {
+        if self.bom_checked {
+            return true;
+        }
+        let pending = &self.buf[self.pos..];
+        if pending.starts_with(UTF8_BOM) {
+            self.pos += UTF8_BOM.len();
+        } else if UTF8_BOM.starts_with(pending) && !eof {
+            return false;
+        }
+        self.bom_checked = true;
+        true
+    }
+
+    /// Consume the next complete line and return its byte range, excluding the terminator.
+    fn next_line(&mut self, eof: bool) -> Option<(usize, usize)> {
+        let start = self.pos;
+        let rest = &self.buf[start..];
+        match rest.iter().position(|&b| b == b'\n' || b == b'\r') {
+            Some(i) => {
+                let end = start + i;
+                let terminator = if rest[i] == b'\r' {
+                    match rest.get(i + 1) {
+                        Some(b'\n') => 2,
+                        Some(_) => 1,
+                        // A CR at the end of the buffer may be the first half of CRLF.
+                        None if !eof => return None,
+                        None => 1,
+                    }
+                } else {
+                    1
+                };
+                self.pos = end + terminator;
+                Some((start, end))
+            }
+            None if eof && !rest.is_empty() => {
+                self.pos = self.buf.len();
+                Some((start, self.buf.len()))
+            }
+            None => None,
+        }
+    }
+
+    fn apply_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            // Comment line, skip
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        match field {
+            "event" => self.event = Some(value.trim().to_string()),
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value.trim_start());
+                self.has_data = true;
+            }
+            // id, retry, and unknown fields are ignored
+            _ => {}
+        }
+        None
+    }
+
+    /// End the current event. Blocks without data (e.g. a bare `event: ping`) are dropped.
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if !std::mem::take(&mut self.has_data) {
+            return None;
+        }
+        Some(SseEvent {
+            event,
+            data: std::mem::take(&mut self.data),
+        })
+    }
+}
+
 struct SseState<E> {
     stream: Pin<Box<dyn Stream<Item = Result<Bytes, E>> + Send>>,
-    buffer: String,
+    decoder: SseDecoder,
+    done: bool,
 }
 
 fn async_stream<E>(
@@ -39,51 +189,26 @@ where
     futures::stream::unfold(
         SseState {
             stream: Box::pin(byte_stream),
-            buffer: String::new(),
+            decoder: SseDecoder::new(),
+            done: false,
         },
         |mut state| async move {
+            if state.done {
+                return None;
+            }
             loop {
-                // Check if we have a complete event block in the buffer (double newline)
-                if let Some(pos) = find_event_boundary(&state.buffer) {
-                    let block = state.buffer[..pos].to_string();
-                    // Skip past the double newline
-                    let skip = if state.buffer[pos..].starts_with("\r\n\r\n") {
-                        4
-                    } else {
-                        2
-                    };
-                    drop(state.buffer.drain(..pos + skip));
-
-                    if let Some(event) = parse_event_block(&block) {
-                        return Some((Ok(event), state));
-                    }
-                    // Empty event block, continue looking
-                    continue;
+                if let Some(event) = state.decoder.next_event() {
+                    return Some((Ok(event), state));
                 }
 
                 // Need more data
                 match state.stream.next().await {
-                    Some(Ok(bytes)) => match std::str::from_utf8(&bytes) {
-                        Ok(text) => {
-                            if state.buffer.len() + text.len() > MAX_SSE_BUFFER_SIZE {
-                                return Some((
-                                    Err(prism_core::error::ProxyError::Internal(
-                                        "SSE buffer exceeded maximum size".to_string(),
-                                    )),
-                                    state,
-                                ));
-                            }
-                            state.buffer.push_str(text);
+                    Some(Ok(bytes)) => {
+                        if let Err(e) = state.decoder.feed(&bytes) {
+                            state.done = true;
+                            return Some((Err(e), state));
                         }
-                        Err(e) => {
-                            return Some((
-                                Err(prism_core::error::ProxyError::Internal(format!(
-                                    "invalid UTF-8 in SSE stream: {e}"
-                                ))),
-                                state,
-                            ));
-                        }
-                    },
+                    }
                     Some(Err(e)) => {
                         return Some((
                             Err(prism_core::error::ProxyError::Network(e.to_string())),
@@ -92,13 +217,8 @@ where
                     }
                     None => {
                         // Stream ended. Process any remaining data in the buffer.
-                        if !state.buffer.trim().is_empty() {
-                            let block = std::mem::take(&mut state.buffer);
-                            if let Some(event) = parse_event_block(&block) {
-                                return Some((Ok(event), state));
-                            }
-                        }
-                        return None;
+                        state.done = true;
+                        return state.decoder.finish().map(|event| (Ok(event), state));
                     }
                 }
             }
@@ -106,55 +226,35 @@ where
     )
 }
 
-/// Find the position of a double-newline event boundary.
-fn find_event_boundary(s: &str) -> Option<usize> {
-    if let Some(pos) = s.find("\n\n") {
-        return Some(pos);
-    }
-    if let Some(pos) = s.find("\r\n\r\n") {
-        return Some(pos);
-    }
-    None
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// Parse a single SSE event block into an SseEvent.
-/// Returns None for empty/comment-only blocks and [DONE] sentinels.
-fn parse_event_block(block: &str) -> Option<SseEvent> {
-    let mut event_type: Option<String> = None;
-    let mut data_lines: Vec<String> = Vec::new();
+    /// Decode a single block as if it were the whole stream.
+    fn parse_event_block(block: &str) -> Option<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        decoder.feed(block.as_bytes()).unwrap();
+        decoder.next_event().or_else(|| decoder.finish())
+    }
 
-    for line in block.lines() {
-        let line = line.trim_start_matches('\r');
-        if line.starts_with(':') {
-            // Comment line, skip
-            continue;
+    /// Decode a whole stream split at the given byte offsets.
+    fn decode_split(raw: &[u8], splits: &[usize]) -> Vec<(Option<String>, String)> {
+        let mut decoder = SseDecoder::new();
+        let mut events = Vec::new();
+        let mut last = 0;
+        for &at in splits.iter().chain(std::iter::once(&raw.len())) {
+            decoder.feed(&raw[last..at]).unwrap();
+            last = at;
+            while let Some(event) = decoder.next_event() {
+                events.push((event.event, event.data));
+            }
         }
-        if let Some(value) = line.strip_prefix("event:") {
-            event_type = Some(value.trim().to_string());
-        } else if let Some(value) = line.strip_prefix("data:") {
-            let value = value.trim_start();
-            data_lines.push(value.to_string());
-        } else if line.starts_with("id:") || line.starts_with("retry:") {
-            // Ignore id and retry fields
+        if let Some(event) = decoder.finish() {
+            events.push((event.event, event.data));
         }
+        events
     }
 
-    if data_lines.is_empty() {
-        return None;
-    }
-
-    let data = data_lines.join("\n");
-
-    Some(SseEvent {
-        event: event_type,
-        data,
-    })
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[test]
     fn test_parse_event_block_basic() {
         let block = "data: {\"hello\": \"world\"}";
@@ -212,18 +312,147 @@ mod tests {
     }
 
     #[test]
-    fn test_find_event_boundary_lf() {
-        assert_eq!(find_event_boundary("data: x\n\ndata: y"), Some(7));
+    fn test_event_boundary_lf() {
+        let events = decode_split(b"data: x\n\ndata: y", &[]);
+        assert_eq!(events, [(None, "x".into()), (None, "y".into())]);
+    }
+
+    #[test]
+    fn test_event_boundary_crlf() {
+        let events = decode_split(b"data: x\r\n\r\ndata: y", &[]);
+        assert_eq!(events, [(None, "x".into()), (None, "y".into())]);
+    }
+
+    #[test]
+    fn test_event_boundary_none() {
+        let mut decoder = SseDecoder::new();
+        decoder.feed(b"data: x\ndata: y").unwrap();
+        assert!(decoder.next_event().is_none());
+    }
+
+    #[test]
+    fn test_bare_cr_and_mixed_line_endings() {
+        let events = decode_split(b"event: a\rdata: 1\r\rdata: 2\r\n\ndata: 3\n\r\n", &[]);
+        assert_eq!(
+            events,
+            [
+                (Some("a".into()), "1".into()),
+                (None, "2".into()),
+                (None, "3".into())
+            ]
+        );
     }
 
     #[test]
-    fn test_find_event_boundary_crlf() {
-        assert_eq!(find_event_boundary("data: x\r\n\r\ndata: y"), Some(7));
+    fn test_crlf_split_between_chunks() {
+        // The CR arrives alone; it must not be taken as a bare-CR line end
+        // followed by an empty line when the LF shows up.
+        let raw = b"data: a\r\ndata: b\r\n\r\n";
+        let events = decode_split(raw, &[7, 16, 17]);
+        assert_eq!(events, [(None, "a\nb".into())]);
     }
 
     #[test]
-    fn test_find_event_boundary_none() {
-        assert_eq!(find_event_boundary("data: x\ndata: y"), None);
+    fn test_bom_stripped_even_when_split() {
+        let raw = b"\xEF\xBB\xBFdata: hi\n\n";
+        assert_eq!(decode_split(raw, &[]), [(None, "hi".into())]);
+        assert_eq!(decode_split(raw, &[1, 2]), [(None, "hi".into())]);
+        // Only a leading BOM is special.
+        let mut decoder = SseDecoder::new();
+        decoder.feed(b"data: \xEF\xBB\xBFx\n\n").unwrap();
+        assert_eq!(decoder.next_event().unwrap().data, "\u{feff}x");
+    }
+
+    #[test]
+    fn test_utf8_split_across_chunks() {
+        let raw = "data: {\"t\":\"héllo 世界\"}\n\n".as_bytes();
+        let mid = raw.iter().position(|&b| b == 0xE4).unwrap() + 1;
+        let events = decode_split(raw, &[mid - 3, mid, mid + 1]);
+        assert_eq!(events, [(None, "{\"t\":\"héllo 世界\"}".into())]);
+    }
+
+    #[test]
+    fn test_invalid_utf8_replaced() {
+        let events = decode_split(b"data: a\xFFb\n\n", &[]);
+        assert_eq!(events, [(None, "a\u{fffd}b".into())]);
+    }
+
+    #[test]
+    fn test_field_without_colon_and_empty_data() {
+        // `data` with no colon is a data line with an empty value.
+        let events = decode_split(b"data\ndata: x\n\ndata:\n\n", &[]);
+        assert_eq!(events, [(None, "\nx".into()), (None, String::new())]);
+    }
+
+    #[test]
+    fn test_comments_between_fields() {
+        let raw = b": keepalive\n\nevent: delta\n: note\ndata: 1\n:\ndata: 2\n\n";
+        let events = decode_split(raw, &[]);
+        assert_eq!(events, [(Some("delta".into()), "1\n2".into())]);
+    }
+
+    #[test]
+    fn test_buffer_limit() {
+        let mut decoder = SseDecoder::new();
+        let big = vec![b'a'; MAX_SSE_BUFFER_SIZE];
+        decoder.feed(b"data: ").unwrap();
+        assert!(decoder.feed(&big).is_err());
+    }
+
+    /// Property: splitting a stream at arbitrary byte offsets, with any mix of
+    /// line endings, never changes the decoded events.
+    #[test]
+    fn test_random_splits_and_line_endings_match_canonical() {
+        // xorshift64*: deterministic, no extra dev-dependency.
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move |bound: usize| {
+            seed ^= seed >> 12;
+            seed ^= seed << 25;
+            seed ^= seed >> 27;
+            (seed.wrapping_mul(0x2545_F491_4F6C_DD1D) % bound as u64) as usize
+        };
+        let payloads = [
+            "{\"choices\":[{\"delta\":{\"content\":\"ünï 🚀\"}}]}",
+            "line one",
+            "[DONE]",
+            "",
+            "é",
+        ];
+        for _ in 0..500 {
+            let mut expected = Vec::new();
+            let mut raw = Vec::new();
+            if next(4) == 0 {
+                raw.extend_from_slice(UTF8_BOM);
+            }
+            for _ in 0..1 + next(5) {
+                let eol: &[u8] = [&b"\n"[..], b"\r\n", b"\r"][next(3)];
+                if next(3) == 0 {
+                    raw.extend_from_slice(b": comment");
+                    raw.extend_from_slice(eol);
+                }
+                let event = (next(2) == 0).then(|| format!("e{}", next(10)));
+                if let Some(name) = &event {
+                    raw.extend_from_slice(format!("event: {name}").as_bytes());
+                    raw.extend_from_slice(eol);
+                }
+                let lines: Vec<&str> = (0..1 + next(3))
+                    .map(|_| payloads[next(payloads.len())])
+                    .collect();
+                for line in &lines {
+                    raw.extend_from_slice(format!("data: {line}").as_bytes());
+                    raw.extend_from_slice(eol);
+                }
+                raw.extend_from_slice(eol);
+                expected.push((event, lines.join("\n")));
+            }
+            let mut splits: Vec<usize> = (0..next(8)).map(|_| next(raw.len() + 1)).collect();
+            splits.sort_unstable();
+            assert_eq!(
+                decode_split(&raw, &splits),
+                expected,
+                "raw={raw:?} splits={splits:?}"
+            );
+        }
     }
 
     #[test]
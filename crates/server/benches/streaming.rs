@@ -0,0 +1,175 @@
+//! Criterion benchmarks for the streaming response path: upstream chunks
+//! framed into the SSE body a client receives, untranslated and through a
+//! stream translator.
+//!
+//! Streams are shaped like a chat answer: ~300 text deltas, a usage chunk,
+//! and the end-of-stream marker. Baselines: `docs/reference/benchmarks.md`.
+
+use bytes::Bytes;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use futures::StreamExt;
+use prism_core::error::ProxyError;
+use prism_core::provider::{Format, StreamChunk};
+use prism_server::streaming::build_sse_response;
+use prism_translator::{TranslateState, build_registry};
+use serde_json::json;
+use std::hint::black_box;
+use std::sync::Arc;
+
+const MODEL: &str = "bench-model";
+const DELTAS: usize = 300;
+
+fn chunk(event_type: Option<&str>, data: String) -> StreamChunk {
+    StreamChunk {
+        event_type: event_type.map(String::from),
+        data: Bytes::from(data),
+    }
+}
+
+fn openai_stream() -> Vec<StreamChunk> {
+    let mut chunks = vec![chunk(
+        None,
+        json!({"id": "chatcmpl-1", "object": "chat.completion.chunk", "created": 1, "model": MODEL,
+            "choices": [{"index": 0, "delta": {"role": "assistant"}, "finish_reason": null}]})
+        .to_string(),
+    )];
+    for i in 0..DELTAS {
+        chunks.push(chunk(
+            None,
+            json!({"id": "chatcmpl-1", "object": "chat.completion.chunk", "created": 1, "model": MODEL,
+                "choices": [{"index": 0, "delta": {"content": format!("token {i} of the answer ")},
+                    "finish_reason": null}]})
+            .to_string(),
+        ));
+    }
+    chunks.push(chunk(
+        None,
+        json!({"id": "chatcmpl-1", "object": "chat.completion.chunk", "created": 1, "model": MODEL,
+            "choices": [{"index": 0, "delta": {}, "finish_reason": "stop"}]})
+        .to_string(),
+    ));
+    chunks.push(chunk(
+        None,
+        json!({"id": "chatcmpl-1", "object": "chat.completion.chunk", "created": 1, "model": MODEL,
+            "choices": [], "usage": {"prompt_tokens": 1200, "completion_tokens": 900}})
+        .to_string(),
+    ));
+    chunks.push(chunk(None, "[DONE]".to_string()));
+    chunks
+}
+
+fn claude_stream() -> Vec<StreamChunk> {
+    let mut chunks = vec![
+        chunk(
+            Some("message_start"),
+            json!({"type": "message_start", "message": {"id": "msg_1", "type": "message",
+                "role": "assistant", "model": MODEL, "content": [],
+                "usage": {"input_tokens": 1200, "output_tokens": 1}}})
+            .to_string(),
+        ),
+        chunk(
+            Some("content_block_start"),
+            json!({"type": "content_block_start", "index": 0,
+                "content_block": {"type": "text", "text": ""}})
+            .to_string(),
+        ),
+    ];
+    for i in 0..DELTAS {
+        chunks.push(chunk(
+            Some("content_block_delta"),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "text_delta", "text": format!("token {i} of the answer ")}})
+            .to_string(),
+        ));
+    }
+    chunks.push(chunk(
+        Some("content_block_stop"),
+        json!({"type": "content_block_stop", "index": 0}).to_string(),
+    ));
+    chunks.push(chunk(
+        Some("message_delta"),
+        json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"},
+            "usage": {"output_tokens": 900}})
+        .to_string(),
+    ));
+    chunks.push(chunk(
+        Some("message_stop"),
+        json!({"type": "message_stop"}).to_string(),
+    ));
+    chunks
+}
+
+fn stream_bytes(chunks: &[StreamChunk]) -> u64 {
+    chunks.iter().map(|c| c.data.len() as u64).sum()
+}
+
+/// Frame `chunks` into an SSE body and drain it, returning the body length.
+async fn drain<S>(chunks: S) -> usize
+where
+    S: futures::Stream<Item = Result<StreamChunk, ProxyError>> + Send + 'static,
+{
+    let mut body = build_sse_response(chunks, 15)
+        .into_body()
+        .into_data_stream();
+    let mut len = 0;
+    while let Some(frame) = body.next().await {
+        len += frame.unwrap().len();
+    }
+    len
+}
+
+fn sse(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("sse");
+
+    for (name, chunks) in [("openai", openai_stream()), ("claude", claude_stream())] {
+        group.throughput(Throughput::Bytes(stream_bytes(&chunks)));
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let chunks = futures::stream::iter(black_box(&chunks).clone().into_iter().map(Ok));
+                runtime.block_on(drain(chunks))
+            })
+        });
+    }
+
+    // The translated path: one stream translator call per upstream chunk, as
+    // the dispatcher does, then the same framing.
+    let registry = Arc::new(build_registry());
+    let original_req = Bytes::from(
+        json!({"model": MODEL, "max_tokens": 1024, "stream": true,
+            "messages": [{"role": "user", "content": "hi"}]})
+        .to_string(),
+    );
+    let chunks = openai_stream();
+    group.throughput(Throughput::Bytes(stream_bytes(&chunks)));
+    group.bench_function("openai->claude", |b| {
+        b.iter(|| {
+            let registry = registry.clone();
+            let original_req = original_req.clone();
+            let mut state = TranslateState::default();
+            let translated = futures::stream::iter(black_box(&chunks).clone()).map(move |chunk| {
+                let lines = registry.translate_stream(
+                    Format::OpenAI,
+                    Format::Claude,
+                    MODEL,
+                    &original_req,
+                    chunk.event_type.as_deref(),
+                    &chunk.data,
+                    &mut state,
+                )?;
+                Ok(StreamChunk {
+                    event_type: None,
+                    data: Bytes::from(lines.join("\n")),
+                })
+            });
+            runtime.block_on(drain(translated))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sse);
+criterion_main!(benches);
@@ -0,0 +1,216 @@
+//! Version negotiation for the dashboard and admin APIs.
+//!
+//! `/api/v{N}/dashboard/*` and `/api/v{N}/admin/*` are the versioned paths.
+//! The unversioned `/api/dashboard/*` and `/admin/*` stay as aliases of the
+//! current version and answer with `Deprecation` (RFC 9745) and `Sunset`
+//! (RFC 8594) headers plus a `Link` to the versioned path. Versioned requests are rewritten to the unversioned route
+//! table before routing, so every handler is registered once; a handler whose
+//! response shape changes in a later version reads the negotiated
+//! [`ApiVersion`] from the request extensions.
+
+use axum::Json;
+use axum::extract::Request;
+use axum::http::{HeaderValue, StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+
+/// Version served by the unversioned aliases.
+pub const CURRENT: u32 = 1;
+pub const SUPPORTED: &[u32] = &[1];
+/// Request header selecting a version on an unversioned path; every versioned
+/// or aliased response carries the version that served it.
+pub const VERSION_HEADER: &str = "x-prism-api-version";
+
+/// When the unversioned aliases were deprecated, in Unix seconds (2026-10-14).
+pub const ALIAS_DEPRECATED_AT: i64 = 1_791_936_000;
+/// When the unversioned aliases may stop being served, in Unix seconds (2027-04-14).
+pub const ALIAS_SUNSET_AT: i64 = 1_807_660_800;
+
+/// `(family, unversioned prefix)`: `/api/v1/{family}/x` is `{prefix}/x`.
+const FAMILIES: &[(&str, &str)] = &[("dashboard", "/api/dashboard"), ("admin", "/admin")];
+
+/// API version negotiated for a dashboard or admin request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiVersion(pub u32);
+
+#[derive(Debug, PartialEq, Eq)]
+enum Resolved {
+    /// A versioned path and the unversioned route it is served by.
+    Versioned { version: u32, route: String },
+    /// An unversioned alias and its versioned successor.
+    Alias { successor: String },
+}
+
+/// Classify a request path; `None` for paths outside the versioned families.
+fn resolve(path: &str) -> Option<Resolved> {
+    if let Some(rest) = path.strip_prefix("/api/v")
+        && let Some((digits, rest)) = rest.split_once('/')
+        && let Ok(version) = digits.parse::<u32>()
+    {
+        for (family, prefix) in FAMILIES {
+            if let Some(tail) = strip_segment(rest, family) {
+                return Some(Resolved::Versioned {
+                    version,
+                    route: format!("{prefix}{tail}"),
+                });
+            }
+        }
+    }
+    FAMILIES.iter().find_map(|(family, prefix)| {
+        let tail = strip_segment(path, prefix)?;
+        Some(Resolved::Alias {
+            successor: format!("/api/v{CURRENT}/{family}{tail}"),
+        })
+    })
+}
+
+/// `path` without a leading `segment` that is followed by `/` or the end.
+fn strip_segment<'a>(path: &'a str, segment: &str) -> Option<&'a str> {
+    let tail = path.strip_prefix(segment)?;
+    (tail.is_empty() || tail.starts_with('/')).then_some(tail)
+}
+
+/// The current-version path for an unversioned dashboard or admin route, used
+/// as the canonical path in the OpenAPI document.
+pub fn versioned_path(path: &str) -> Option<String> {
+    match resolve(path)? {
+        Resolved::Alias { successor } => Some(successor),
+        Resolved::Versioned { .. } => None,
+    }
+}
+
+fn unsupported(version: u32) -> Response {
+    let supported: Vec<String> = SUPPORTED.iter().map(u32::to_string).collect();
+    let mut response = (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "unsupported_api_version",
+            "message": format!(
+                "API version {version} is not supported; supported versions: {}",
+                supported.join(", ")
+            ),
+        })),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(VERSION_HEADER, HeaderValue::from(CURRENT));
+    response
+}
+
+/// Rewrite versioned paths to their routes, reject unsupported versions, and
+/// mark aliased responses as deprecated. Runs before routing.
+pub async fn negotiate(mut request: Request, next: Next) -> Response {
+    let Some(resolved) = resolve(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let (version, successor) = match resolved {
+        Resolved::Versioned { version, route } => {
+            let query = request.uri().query().map(|q| format!("?{q}"));
+            if let Ok(uri) = format!("{route}{}", query.unwrap_or_default()).parse::<Uri>() {
+                *request.uri_mut() = uri;
+            }
+            (version, None)
+        }
+        Resolved::Alias { successor } => {
+            let requested = request
+                .headers()
+                .get(VERSION_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().parse::<u32>().unwrap_or(0));
+            (requested.unwrap_or(CURRENT), Some(successor))
+        }
+    };
+    if !SUPPORTED.contains(&version) {
+        return unsupported(version);
+    }
+
+    request.extensions_mut().insert(ApiVersion(version));
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(VERSION_HEADER, HeaderValue::from(version));
+    if let Some(successor) = successor
+        && let Ok(link) =
+            HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\""))
+    {
+        for (name, value) in [
+            ("deprecation", deprecation_value()),
+            ("sunset", sunset_value()),
+        ] {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(name, value);
+            }
+        }
+        headers.insert(axum::http::header::LINK, link);
+    }
+    response
+}
+
+/// RFC 9745 structured-field date: `@` and Unix seconds.
+fn deprecation_value() -> String {
+    format!("@{ALIAS_DEPRECATED_AT}")
+}
+
+/// RFC 8594 HTTP-date, e.g. `Wed, 14 Apr 2027 00:00:00 GMT`.
+fn sunset_value() -> String {
+    chrono::DateTime::from_timestamp(ALIAS_SUNSET_AT, 0)
+        .unwrap_or_default()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_versioned_and_alias_paths() {
+        assert_eq!(
+            resolve("/api/v1/dashboard/providers/p1"),
+            Some(Resolved::Versioned {
+                version: 1,
+                route: "/api/dashboard/providers/p1".to_string()
+            })
+        );
+        assert_eq!(
+            resolve("/api/v2/admin"),
+            Some(Resolved::Versioned {
+                version: 2,
+                route: "/admin".to_string()
+            })
+        );
+        assert_eq!(
+            resolve("/admin/metrics"),
+            Some(Resolved::Alias {
+                successor: "/api/v1/admin/metrics".to_string()
+            })
+        );
+        assert_eq!(
+            versioned_path("/api/dashboard/logs"),
+            Some("/api/v1/dashboard/logs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deprecation_and_sunset_values() {
+        assert_eq!(deprecation_value(), "@1791936000");
+        assert_eq!(sunset_value(), "Wed, 14 Apr 2027 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_resolve_ignores_other_paths() {
+        for path in [
+            "/v1/chat/completions",
+            "/api/openapi.json",
+            "/api/provider/openai/v1/messages",
+            "/api/v1/dashboards",
+            "/api/vx/dashboard",
+            "/administrator",
+            "/health",
+        ] {
+            assert_eq!(resolve(path), None, "{path}");
+        }
+    }
+}
@@ -1,16 +1,13 @@
 //! Application struct that encapsulates server assembly and serving logic.
 
 use arc_swap::ArcSwap;
-use prism_core::cache::{MokaCache, ResponseCacheBackend};
+use axum::serve::ListenerExt;
 use prism_core::config::{Config, ConfigWatcher};
-use prism_core::rate_limit::CompositeRateLimiter;
 use prism_lifecycle::signal::SignalHandler;
 use prism_lifecycle::{self, Lifecycle};
-use prism_provider::catalog::ProviderCatalog;
-use prism_provider::health::HealthManager;
-use prism_provider::routing::CredentialRouter;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::future::IntoFuture;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Configuration for running the server, decoupled from CLI parsing.
 pub struct RunConfig {
@@ -27,13 +24,8 @@ pub struct Application {
     config: Arc<ArcSwap<Config>>,
     app_router: axum::Router,
     config_path: String,
-    credential_router: Arc<CredentialRouter>,
-    catalog: Arc<ProviderCatalog>,
-    health_manager: Arc<HealthManager>,
-    auth_runtime: Arc<crate::auth_runtime::AuthRuntimeManager>,
-    rate_limiter: Arc<CompositeRateLimiter>,
-    cost_calculator: Arc<prism_core::cost::CostCalculator>,
-    http_client_pool: Arc<prism_core::proxy::HttpClientPool>,
+    state: crate::AppState,
+    proxy: crate::builder::Proxy,
     lifecycle: Box<dyn Lifecycle>,
     shutdown_timeout: u64,
     #[cfg(unix)]
@@ -41,16 +33,17 @@ pub struct Application {
 }
 
 impl Application {
-    /// Build the application from a `RunConfig`: load config, build executors,
-    /// router, translators, metrics, and acquire PID file.
+    /// Build the application from a `RunConfig`: apply CLI overrides, acquire the
+    /// PID file, and assemble the proxy with [`crate::builder::ProxyBuilder`].
     ///
     /// `log_store` is created externally so it can be shared with the
     /// `GatewayLogLayer` (which must be registered before the application
-    /// is built).
+    /// is built), as is `log_filter` by the logging init.
     pub fn build(
         args: &RunConfig,
         preloaded_config: Config,
         log_store: Arc<dyn prism_core::request_log::LogStore>,
+        log_filter: prism_lifecycle::logging::LogFilterHandle,
     ) -> anyhow::Result<Self> {
         let mut config = preloaded_config;
 
@@ -80,93 +73,25 @@ impl Application {
             None
         };
 
-        // Build shared HTTP client pool and provider components
-        let http_client_pool = Arc::new(prism_core::proxy::HttpClientPool::new());
-        let executors =
-            prism_provider::build_registry(config.proxy_url.clone(), http_client_pool.clone());
-        let default_cred_strategy = config
-            .routing
-            .profiles
-            .get(&config.routing.default_profile)
-            .map(|p| p.credential_policy.strategy)
-            .unwrap_or_default();
-        let auth_runtime = Arc::new(crate::auth_runtime::AuthRuntimeManager::new());
-        auth_runtime
-            .initialize(&args.config_path, &config)
-            .map_err(anyhow::Error::msg)?;
-        let credential_router = Arc::new(CredentialRouter::new(default_cred_strategy));
-        credential_router.set_oauth_states(auth_runtime.oauth_snapshot());
-        credential_router.update_from_config(&config);
-
-        // Build catalog and health manager (from same credential data as router)
-        let catalog = Arc::new(ProviderCatalog::new());
-        let health_manager = Arc::new(HealthManager::new(Default::default()));
-        {
-            let cred_map = credential_router.credential_map();
-            catalog.update_from_credentials(&cred_map);
-        }
-
-        let translators = Arc::new(prism_translator::build_registry());
-        let executors = Arc::new(executors);
-
-        tracing::info!("Loaded {} provider entries", config.providers.len(),);
-
-        let rate_limiter = Arc::new(CompositeRateLimiter::new(&config.rate_limit));
-        let cost_calculator = Arc::new(prism_core::cost::CostCalculator::new(&config.model_prices));
-
-        // Initialize thinking signature cache (if enabled)
-        let thinking_cache = if config.thinking_cache.enabled {
-            tracing::info!(
-                "Thinking signature cache enabled (max_entries={}, ttl={}s)",
-                config.thinking_cache.max_entries,
-                config.thinking_cache.ttl_secs
-            );
-            Some(Arc::new(prism_core::thinking_cache::ThinkingCache::new(
-                &config.thinking_cache,
-            )))
-        } else {
-            None
-        };
-
-        // Initialize response cache (if enabled)
-        let response_cache: Option<Arc<dyn ResponseCacheBackend>> = if config.cache.enabled {
-            tracing::info!(
-                "Response cache enabled (max_entries={}, ttl={}s)",
-                config.cache.max_entries,
-                config.cache.ttl_secs
-            );
-            Some(Arc::new(MokaCache::new(&config.cache)))
-        } else {
-            None
-        };
-
-        let config = Arc::new(ArcSwap::from_pointee(config));
-        let metrics = Arc::new(prism_core::metrics::Metrics::new());
-
-        // Build AppState and router
-        let state = crate::AppState {
-            config: config.clone(),
-            router: credential_router.clone(),
-            executors,
-            translators,
-            metrics,
-            log_store,
-            config_path: Arc::new(Mutex::new(args.config_path.clone())),
-            rate_limiter: rate_limiter.clone(),
-            cost_calculator: cost_calculator.clone(),
-            response_cache,
-            thinking_cache,
-            http_client_pool: http_client_pool.clone(),
-            start_time: Instant::now(),
-            login_limiter: Arc::new(crate::handler::dashboard::auth::LoginRateLimiter::new()),
-            catalog: catalog.clone(),
-            health_manager: health_manager.clone(),
-            auth_runtime: auth_runtime.clone(),
-            oauth_sessions: Arc::new(dashmap::DashMap::new()),
-            device_sessions: Arc::new(dashmap::DashMap::new()),
-            provider_probe_cache: Arc::new(dashmap::DashMap::new()),
-        };
-        let app_router = crate::build_router(state);
+        let proxy = crate::builder::ProxyBuilder::new(config)
+            .config_path(args.config_path.clone())
+            .log_store(log_store)
+            .log_filter(log_filter)
+            .build()?;
+        let state = proxy.state().clone();
+        let config = state.config.clone();
+        let app_router = proxy.router();
+
+        // Write a crash report and mark the run in the process history on any panic
+        let history = state.process_history.clone();
+        let crash_reporter = state.crash_reporter.clone();
+        let inflight = state.inflight.clone();
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            crash_reporter.capture(info, inflight.len());
+            history.record_panic(&info.to_string());
+            default_hook(info);
+        }));
 
         // Detect lifecycle
         let lc = prism_lifecycle::detect_lifecycle();
@@ -175,13 +100,8 @@ impl Application {
             config,
             app_router,
             config_path: args.config_path.clone(),
-            credential_router,
-            catalog,
-            health_manager,
-            auth_runtime,
-            rate_limiter,
-            cost_calculator,
-            http_client_pool,
+            state,
+            proxy,
             lifecycle: lc,
             shutdown_timeout,
             #[cfg(unix)]
@@ -195,83 +115,48 @@ impl Application {
             config,
             app_router,
             config_path,
-            credential_router,
-            catalog,
-            health_manager: _health_manager,
-            auth_runtime,
-            rate_limiter,
-            cost_calculator,
-            http_client_pool,
+            state,
+            proxy,
             lifecycle,
             shutdown_timeout,
             #[cfg(unix)]
             _pid_file,
         } = self;
 
-        // Start config file watcher
-        let watcher_router = credential_router.clone();
-        let watcher_catalog = catalog.clone();
-        let watcher_rate_limiter = rate_limiter.clone();
-        let watcher_cost_calculator = cost_calculator.clone();
-        let watcher_pool = http_client_pool.clone();
-        let watcher_auth_runtime = auth_runtime.clone();
-        let _watcher = ConfigWatcher::start(config_path.clone(), config.clone(), move |new_cfg| {
-            if let Err(err) = watcher_auth_runtime.sync_with_config(new_cfg) {
-                tracing::error!("Auth runtime sync failed on config reload: {err}");
+        proxy.spawn_background_tasks();
+
+        // Start config file watcher. `apply_config` is the only place the new config is
+        // swapped in, so a config it rejects never becomes live.
+        let watcher_state = state.clone();
+        let _watcher = ConfigWatcher::start(config_path.clone(), move |new_cfg| {
+            let providers = new_cfg.providers.len();
+            match crate::reload::apply_config(&watcher_state, new_cfg) {
+                Ok(delta) => tracing::info!(
+                    source = "file-watcher",
+                    providers,
+                    touched = ?delta.touched(),
+                    "Config reloaded"
+                ),
+                Err(err) => tracing::error!("Config reload failed to apply: {err}"),
             }
-            watcher_router.set_oauth_states(watcher_auth_runtime.oauth_snapshot());
-            watcher_router.update_from_config(new_cfg);
-            watcher_catalog.update_from_credentials(&watcher_router.credential_map());
-            watcher_rate_limiter.update_config(&new_cfg.rate_limit);
-            watcher_cost_calculator.update_prices(&new_cfg.model_prices);
-            watcher_pool.clear();
-            tracing::info!(
-                "Config reloaded: {} provider entries",
-                new_cfg.providers.len(),
-            );
         });
 
         // Setup signal handler
         let (signal_handler, shutdown_rx) = SignalHandler::new();
 
         // SIGHUP reload function
-        let reload_config = config.clone();
-        let reload_router = credential_router.clone();
-        let reload_catalog = catalog.clone();
-        let reload_rate_limiter = rate_limiter.clone();
-        let reload_cost_calculator = cost_calculator.clone();
-        let reload_pool = http_client_pool;
-        let reload_path = config_path.clone();
-        let reload_auth_runtime = auth_runtime.clone();
+        let reload_state = state.clone();
         let reload_lifecycle: Arc<dyn Lifecycle> = Arc::from(prism_lifecycle::detect_lifecycle());
         let reload_fn = move || {
             reload_lifecycle.on_reloading();
-            match Config::load(&reload_path) {
-                Ok(new_cfg) => {
-                    if let Err(err) = reload_auth_runtime.sync_with_config(&new_cfg) {
-                        tracing::error!("Auth runtime sync failed on SIGHUP reload: {err}");
-                    }
-                    reload_router.set_oauth_states(reload_auth_runtime.oauth_snapshot());
-                    reload_router.update_from_config(&new_cfg);
-                    reload_catalog.update_from_credentials(&reload_router.credential_map());
-                    reload_rate_limiter.update_config(&new_cfg.rate_limit);
-                    reload_cost_calculator.update_prices(&new_cfg.model_prices);
-                    reload_pool.clear();
-                    tracing::info!(
-                        "SIGHUP reload: {} provider entries",
-                        new_cfg.providers.len(),
-                    );
-                    reload_config.store(Arc::new(new_cfg));
-                    reload_lifecycle.on_reloaded();
-                }
-                Err(e) => {
-                    tracing::error!("SIGHUP config reload failed: {e}");
-                }
+            match crate::reload::reload_from_disk(&reload_state, "sighup") {
+                Ok(_) => reload_lifecycle.on_reloaded(),
+                Err(e) => tracing::error!("SIGHUP config reload failed: {e}"),
             }
         };
 
         // Spawn signal handler
-        tokio::spawn(signal_handler.run(reload_fn));
+        let signal_task = tokio::spawn(signal_handler.run(reload_fn));
 
         // Bind and serve
         let cfg = config.load();
@@ -281,6 +166,7 @@ impl Application {
             serve_tls(
                 &addr,
                 &cfg,
+                &state,
                 app_router,
                 shutdown_rx,
                 &*lifecycle,
@@ -290,6 +176,7 @@ impl Application {
         } else {
             serve_http(
                 &addr,
+                &state,
                 app_router,
                 shutdown_rx,
                 &*lifecycle,
@@ -298,6 +185,14 @@ impl Application {
             .await?;
         }
 
+        proxy.persist_router_state();
+        let reason = if signal_task.is_finished() {
+            signal_task.await.unwrap_or("shutdown")
+        } else {
+            "shutdown"
+        };
+        state.process_history.record_stop(reason);
+
         tracing::info!("Server shut down.");
         Ok(())
     }
@@ -337,10 +232,11 @@ pub fn run(args: RunConfig) -> anyhow::Result<()> {
 
     let gateway_layer = crate::telemetry::GatewayLogLayer::new(log_store.clone());
 
-    let _guard = prism_lifecycle::logging::init_logging_with_layer(
+    let (_guard, log_filter) = prism_lifecycle::logging::init_logging_with_layer(
         &args.log_level,
         to_file,
         log_dir.as_deref(),
+        &config.log_rotation.policy(),
         Box::new(gateway_layer),
     );
 
@@ -357,32 +253,57 @@ pub fn run(args: RunConfig) -> anyhow::Result<()> {
                 config.log_store.file_audit.retention_days,
             );
         }
-        let application = Application::build(&args, config, log_store)?;
+        let application = Application::build(&args, config, log_store, log_filter)?;
         application.serve().await
     })
 }
 
+/// Mark the listener bound, run the startup self-test, then notify the lifecycle.
+async fn become_ready(state: &crate::AppState, lifecycle: &dyn Lifecycle) {
+    state.readiness.mark_listening();
+    crate::readiness::run_self_test(state).await;
+    lifecycle.on_ready();
+}
+
 async fn serve_http(
     addr: &str,
+    state: &crate::AppState,
     app_router: axum::Router,
     mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
     lifecycle: &dyn Lifecycle,
     shutdown_timeout: u64,
 ) -> anyhow::Result<()> {
     tracing::info!("Starting HTTP server on {addr}");
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    lifecycle.on_ready();
+    let listener = crate::resource_stats::CountedListener::new(
+        tokio::net::TcpListener::bind(addr).await?,
+        state.connections.clone(),
+    )
+    // `TapIo` is what provides `ConnectInfo<SocketAddr>` for a custom listener.
+    .tap_io(|_| {});
 
     let shutdown = async move {
         let _ = shutdown_rx.wait_for(|v| *v).await;
     };
 
-    axum::serve(
+    let server = axum::serve(
         listener,
         app_router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
     )
     .with_graceful_shutdown(shutdown)
-    .await?;
+    .into_future();
+    let ready = become_ready(state, lifecycle);
+    tokio::pin!(ready);
+    tokio::pin!(server);
+    let mut ready_done = false;
+    loop {
+        tokio::select! {
+            result = &mut server => {
+                result?;
+                break;
+            }
+            _ = &mut ready, if !ready_done => ready_done = true,
+        }
+    }
 
     lifecycle.on_stopping();
     tokio::time::sleep(Duration::from_secs(shutdown_timeout.min(1))).await;
@@ -392,6 +313,7 @@ async fn serve_http(
 async fn serve_tls(
     addr: &str,
     cfg: &Config,
+    state: &crate::AppState,
     app_router: axum::Router,
     mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
     lifecycle: &dyn Lifecycle,
@@ -406,22 +328,33 @@ async fn serve_tls(
         CertificateDer::pem_file_iter(cert_path)?.collect::<Result<Vec<_>, _>>()?;
     let key = PrivateKeyDer::from_pem_file(key_path)?;
 
-    let tls_config = rustls::ServerConfig::builder()
+    #[cfg_attr(not(feature = "grpc"), allow(unused_mut))]
+    let mut tls_config = rustls::ServerConfig::builder()
         .with_no_client_auth()
         .with_single_cert(certs, key)?;
+    // gRPC clients only speak HTTP/2 and need it offered during the handshake.
+    #[cfg(feature = "grpc")]
+    {
+        tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    }
     let tls_acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls_config));
 
     tracing::info!("Starting HTTPS server on {addr}");
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    lifecycle.on_ready();
+    let ready = become_ready(state, lifecycle);
+    tokio::pin!(ready);
+    let mut ready_done = false;
 
     loop {
         tokio::select! {
+            _ = &mut ready, if !ready_done => ready_done = true,
             result = listener.accept() => {
                 let (stream, peer_addr) = result?;
                 let acceptor = tls_acceptor.clone();
                 let router = app_router.clone();
+                let connection = state.connections.open();
                 tokio::spawn(async move {
+                    let _connection = connection;
                     match acceptor.accept(stream).await {
                         Ok(tls_stream) => {
                             let io = hyper_util::rt::TokioIo::new(tls_stream);
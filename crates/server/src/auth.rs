@@ -60,6 +60,10 @@ pub async fn auth_middleware(
         None => return Err(ProxyError::Auth("Invalid API key".to_string())),
     };
 
+    if entry.disabled {
+        return Err(ProxyError::Auth("API key is disabled".to_string()));
+    }
+
     // Check expiry
     if AuthKeyStore::is_expired(entry) {
         return Err(ProxyError::KeyExpired);
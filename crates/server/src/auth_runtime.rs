@@ -8,6 +8,7 @@ use prism_core::auth_profile::{AuthProfileEntry, OAuthTokenState, SharedOAuthTok
 use prism_core::config::Config;
 use prism_core::error::ProxyError;
 use prism_core::provider::AuthRecord;
+use prism_core::token_provider::TokenRefresher;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
@@ -100,6 +101,8 @@ pub struct AuthRuntimeManager {
     persist_lock: Mutex<()>,
     oauth_profiles: DashMap<String, SharedOAuthTokenState>,
     refresh_locks: DashMap<String, Arc<AsyncMutex<()>>>,
+    /// Providers with a `token-source`, refreshed in the background.
+    tokens: Arc<TokenRefresher>,
 }
 
 impl Default for AuthRuntimeManager {
@@ -138,6 +141,7 @@ impl AuthRuntimeManager {
             persist_lock: Mutex::new(()),
             oauth_profiles: DashMap::new(),
             refresh_locks: DashMap::new(),
+            tokens: Arc::new(TokenRefresher::new()),
         }
     }
 
@@ -175,6 +179,7 @@ impl AuthRuntimeManager {
             persist_lock: Mutex::new(()),
             oauth_profiles: DashMap::new(),
             refresh_locks: DashMap::new(),
+            tokens: Arc::new(TokenRefresher::new()),
         }
     }
 
@@ -197,6 +202,7 @@ impl AuthRuntimeManager {
     pub fn sync_with_config(&self, config: &Config) -> Result<(), String> {
         self.refresh_runtime_paths(config)?;
         self.load_store_dir()?;
+        self.tokens.sync(config);
 
         let mut valid_keys = HashSet::new();
         let mut imported_states = Vec::new();
@@ -238,6 +244,10 @@ impl AuthRuntimeManager {
         Ok(())
     }
 
+    pub fn tokens(&self) -> &Arc<TokenRefresher> {
+        &self.tokens
+    }
+
     pub fn profile_key(provider: &str, profile_id: &str) -> String {
         format!("{provider}/{profile_id}")
     }
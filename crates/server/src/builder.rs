@@ -0,0 +1,385 @@
+//! Assembly of the proxy's shared state, for the `prism` binary and for services
+//! that embed the proxy as an axum `Router`.
+//!
+//! ```no_run
+//! # async fn embed() -> anyhow::Result<()> {
+//! use prism_server::builder::ProxyBuilder;
+//!
+//! let proxy = ProxyBuilder::from_yaml("providers: []")?.build()?;
+//! proxy.spawn_background_tasks();
+//! let app = axum::Router::new().nest("/llm", proxy.router());
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+//! proxy.mark_serving().await;
+//! axum::serve(listener, app).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::AppState;
+use arc_swap::ArcSwap;
+use prism_core::cache::{MokaCache, ResponseCacheBackend};
+use prism_core::config::{Config, ReloadDelta};
+use prism_core::provider::{Format, ProviderExecutor};
+use prism_core::rate_limit::CompositeRateLimiter;
+use prism_core::request_log::LogStore;
+use prism_lifecycle::logging::LogFilterHandle;
+use prism_provider::catalog::ProviderCatalog;
+use prism_provider::health::HealthManager;
+use prism_provider::router_state::RouterState;
+use prism_provider::routing::CredentialRouter;
+use prism_translator::{RequestTranslator, ResponseTranslator, TranslatorRegistry};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Config path assumed when the config was not read from a file. Managed auth
+/// and router state paths resolve relative to its directory.
+const DEFAULT_CONFIG_PATH: &str = "config.yaml";
+
+/// Builds a [`Proxy`] from a config struct, YAML, or a config file, with
+/// optional custom executors and translators on top of the built-in ones.
+pub struct ProxyBuilder {
+    config: Config,
+    /// `None` until [`config_path`](Self::config_path) or [`from_file`](Self::from_file) sets it.
+    config_path: Option<String>,
+    log_store: Option<Arc<dyn LogStore>>,
+    log_filter: Option<LogFilterHandle>,
+    executors: Vec<(String, Arc<dyn ProviderExecutor>)>,
+    translators: TranslatorRegistry,
+}
+
+impl ProxyBuilder {
+    /// Start from a config built in code. It is sanitized and validated in
+    /// [`build`](Self::build), as a loaded YAML file would be.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            config_path: None,
+            log_store: None,
+            log_filter: None,
+            executors: Vec::new(),
+            translators: prism_translator::build_registry(),
+        }
+    }
+
+    pub fn from_yaml(yaml: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(Config::load_from_str(yaml)?))
+    }
+
+    /// Load `path`. Dashboard config writes and [`crate::reload::reload_from_disk`]
+    /// then target this file.
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(Config::load(path)?).config_path(path))
+    }
+
+    /// File that dashboard writes and reloads use, and that relative store paths
+    /// resolve against.
+    pub fn config_path(mut self, path: impl Into<String>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    /// Request log store; defaults to an in-memory store sized by `log-store.capacity`.
+    pub fn log_store(mut self, log_store: Arc<dyn LogStore>) -> Self {
+        self.log_store = Some(log_store);
+        self
+    }
+
+    /// Handle from [`prism_lifecycle::logging::init_logging`], so the dashboard can
+    /// change the log level. Without one, level changes are accepted but have no effect.
+    pub fn log_filter(mut self, log_filter: LogFilterHandle) -> Self {
+        self.log_filter = Some(log_filter);
+        self
+    }
+
+    /// Add an executor under its `identifier()`. It serves providers whose
+    /// `executor` names it, and replaces the built-in executor for an
+    /// `upstream` of the same name.
+    pub fn executor(self, executor: Arc<dyn ProviderExecutor>) -> Self {
+        let name = executor.identifier().to_string();
+        self.executor_as(name, executor)
+    }
+
+    /// Add an executor under `name`, e.g. to back several provider types with
+    /// one implementation.
+    pub fn executor_as(
+        mut self,
+        name: impl Into<String>,
+        executor: Arc<dyn ProviderExecutor>,
+    ) -> Self {
+        self.executors.push((name.into(), executor));
+        self
+    }
+
+    /// Register request and response translation between two formats,
+    /// replacing any built-in pair.
+    pub fn translator(
+        mut self,
+        from: Format,
+        to: Format,
+        request: impl RequestTranslator + 'static,
+        response: impl ResponseTranslator + 'static,
+    ) -> Self {
+        self.translators.register(from, to, request, response);
+        self
+    }
+
+    /// Register request-only translation between two formats.
+    pub fn request_translator(
+        mut self,
+        from: Format,
+        to: Format,
+        request: impl RequestTranslator + 'static,
+    ) -> Self {
+        self.translators.register_request(from, to, request);
+        self
+    }
+
+    /// Assemble the shared state: executors, credential router, caches, limiters.
+    /// No listener is bound and no background task is started.
+    pub fn build(self) -> anyhow::Result<Proxy> {
+        let config = self.config.prepare()?;
+        prism_core::redact::install_for(&config);
+        if config.dashboard.managed_store.backend
+            == prism_core::managed_store::ManagedStoreBackend::Sqlite
+        {
+            // The store is seeded from, and resolved against, a real config file.
+            let Some(path) = &self.config_path else {
+                anyhow::bail!(
+                    "dashboard managed-store backend 'sqlite' needs a config file; use ProxyBuilder::from_file or config_path"
+                );
+            };
+            prism_core::managed_store::initialize(path)?;
+        }
+        let config_path = self
+            .config_path
+            .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+
+        // Build shared HTTP client pool and provider components
+        let http_client_pool = Arc::new(prism_core::proxy::HttpClientPool::with_network(
+            config.network_settings(),
+        ));
+        if config.fault_injection.enabled {
+            tracing::warn!(
+                providers = ?config.fault_injection.providers,
+                "fault injection is enabled; upstream calls will be delayed, failed, or cut off"
+            );
+        }
+        http_client_pool.set_fault_injection(config.fault_injection.clone());
+        let mut executors = prism_provider::build_registry(http_client_pool.clone());
+        for (name, executor) in self.executors {
+            executors.register_as(name, executor);
+        }
+        executors
+            .check_config(&config)
+            .map_err(anyhow::Error::msg)?;
+        let default_cred_strategy = config
+            .routing
+            .profiles
+            .get(&config.routing.default_profile)
+            .map(|p| p.credential_policy.strategy)
+            .unwrap_or_default();
+        let auth_runtime = Arc::new(crate::auth_runtime::AuthRuntimeManager::new());
+        auth_runtime
+            .initialize(&config_path, &config)
+            .map_err(anyhow::Error::msg)?;
+        let credential_router = Arc::new(CredentialRouter::new(default_cred_strategy));
+        credential_router.set_oauth_states(auth_runtime.oauth_snapshot());
+        credential_router.set_token_states(auth_runtime.tokens().states());
+        credential_router.update_from_config(&config);
+        let router_state_path = config.router_state.enabled.then(|| {
+            prism_core::managed_store::resolve_path(&config_path, &config.router_state.path)
+        });
+        if let Some(path) = &router_state_path {
+            match RouterState::load(path) {
+                Ok(Some(saved)) => {
+                    credential_router.restore_state(saved);
+                    tracing::info!(path = %path.display(), "Restored router state");
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), "Ignoring unreadable router state: {e}")
+                }
+            }
+        }
+
+        let process_history = if config.process_history.enabled {
+            let path =
+                prism_core::managed_store::resolve_path(&config_path, &config.process_history.path);
+            crate::process_history::ProcessHistory::open(
+                path.clone(),
+                config.process_history.max_runs,
+            )
+            .unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), "Ignoring unusable process history: {e}");
+                crate::process_history::ProcessHistory::new()
+            })
+        } else {
+            crate::process_history::ProcessHistory::new()
+        };
+
+        let crash_dir = PathBuf::from(config.log_dir.as_deref().unwrap_or("./logs"));
+        let crash_reporter = crate::crash_report::CrashReporter::new(Some(crash_dir));
+
+        // Build catalog and health manager (from same credential data as router)
+        let catalog = Arc::new(ProviderCatalog::new());
+        let health_manager = Arc::new(HealthManager::new(Default::default()));
+        {
+            let cred_map = credential_router.credential_map();
+            catalog.update_from_credentials(&cred_map);
+        }
+
+        let translators = Arc::new(self.translators);
+        translators.set_rules(config.translation_rules.clone());
+        let executors = Arc::new(executors);
+
+        tracing::info!("Loaded {} provider entries", config.providers.len(),);
+
+        let rate_limiter = Arc::new(CompositeRateLimiter::new(&config.rate_limit));
+        let cost_calculator = Arc::new(prism_core::cost::CostCalculator::new(&config.model_prices));
+
+        // Initialize thinking signature cache (if enabled)
+        let thinking_cache = if config.thinking_cache.enabled {
+            tracing::info!(
+                "Thinking signature cache enabled (max_entries={}, ttl={}s)",
+                config.thinking_cache.max_entries,
+                config.thinking_cache.ttl_secs
+            );
+            Some(Arc::new(prism_core::thinking_cache::ThinkingCache::new(
+                &config.thinking_cache,
+            )))
+        } else {
+            None
+        };
+
+        // Initialize response cache (if enabled)
+        let response_cache: Option<Arc<dyn ResponseCacheBackend>> = if config.cache.enabled {
+            tracing::info!(
+                "Response cache enabled (max_entries={}, ttl={}s)",
+                config.cache.max_entries,
+                config.cache.ttl_secs
+            );
+            Some(Arc::new(MokaCache::new(&config.cache)))
+        } else {
+            None
+        };
+
+        let log_store = self.log_store.unwrap_or_else(|| {
+            Arc::new(prism_core::memory_log_store::InMemoryLogStore::new(
+                config.log_store.capacity,
+                None,
+            ))
+        });
+        let config = Arc::new(ArcSwap::from_pointee(config));
+        let metrics = Arc::new(prism_core::metrics::Metrics::new());
+
+        let state = AppState {
+            config,
+            router: credential_router,
+            executors,
+            translators,
+            metrics,
+            log_store,
+            config_path: Arc::new(Mutex::new(config_path)),
+            rate_limiter,
+            cost_calculator,
+            response_cache,
+            thinking_cache,
+            http_client_pool,
+            start_time: Instant::now(),
+            login_limiter: Arc::new(crate::handler::dashboard::auth::LoginRateLimiter::new()),
+            catalog,
+            health_manager,
+            auth_runtime,
+            oauth_sessions: Arc::new(dashmap::DashMap::new()),
+            device_sessions: Arc::new(dashmap::DashMap::new()),
+            provider_probe_cache: Arc::new(dashmap::DashMap::new()),
+            readiness: Arc::new(crate::readiness::Readiness::new()),
+            inflight: Arc::new(crate::inflight::InflightRegistry::new()),
+            load_shedder: Arc::new(crate::load_shed::LoadShedder::new()),
+            process_history: Arc::new(process_history),
+            crash_reporter: Arc::new(crash_reporter),
+            connections: Arc::new(crate::resource_stats::ConnectionCounter::new()),
+            debug_traces: Arc::new(crate::debug_trace::DebugTraceStore::new()),
+            gemini_caches: Arc::new(dashmap::DashMap::new()),
+            log_filter: Arc::new(
+                self.log_filter
+                    .unwrap_or_else(|| LogFilterHandle::detached("info")),
+            ),
+        };
+
+        Ok(Proxy {
+            state,
+            router_state_path,
+        })
+    }
+}
+
+/// An assembled proxy. Mount [`router`](Self::router) in any axum app.
+pub struct Proxy {
+    state: AppState,
+    /// Where cooldowns and quota counters are persisted, when enabled.
+    router_state_path: Option<PathBuf>,
+}
+
+impl Proxy {
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    /// The full route tree: provider APIs, admin, and dashboard routes.
+    pub fn router(&self) -> axum::Router {
+        crate::build_router(self.state.clone())
+    }
+
+    /// Swap in a new config, rebuilding only the subsystems it changes.
+    pub fn apply_config(&self, config: Config) -> Result<ReloadDelta, String> {
+        let config = config.prepare().map_err(|e| e.to_string())?;
+        crate::reload::apply_config(&self.state, config)
+    }
+
+    /// Start `shared-state` replication, `token-source` refreshes, and periodic
+    /// `router-state` flushes. Call once, from within a Tokio runtime.
+    pub fn spawn_background_tasks(&self) {
+        let config = self.state.config.load();
+        crate::shared_state::spawn(&config.shared_state, self.state.clone());
+        self.state.auth_runtime.tokens().clone().spawn();
+        crate::reports::spawn(self.state.clone());
+
+        // Periodically flush router state when it has changed.
+        if let Some(path) = self.router_state_path.clone() {
+            let router = self.state.router.clone();
+            let interval = Duration::from_secs(config.router_state.flush_interval_secs);
+            tokio::spawn(async move {
+                let mut saved_version = router.state_version();
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    let version = router.state_version();
+                    if version == saved_version {
+                        continue;
+                    }
+                    match router.export_state().save(&path) {
+                        Ok(()) => saved_version = version,
+                        Err(e) => tracing::warn!("Failed to persist router state: {e}"),
+                    }
+                }
+            });
+        }
+    }
+
+    /// Report the listener as bound and run the startup self-test that gates `/ready`.
+    pub async fn mark_serving(&self) {
+        self.state.readiness.mark_listening();
+        crate::readiness::run_self_test(&self.state).await;
+    }
+
+    /// Write router state to disk, e.g. on shutdown.
+    pub fn persist_router_state(&self) {
+        if let Some(path) = &self.router_state_path
+            && let Err(e) = self.state.router.export_state().save(path)
+        {
+            tracing::warn!("Failed to persist router state: {e}");
+        }
+    }
+}
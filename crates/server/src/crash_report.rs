@@ -0,0 +1,189 @@
+//! Structured crash reports written by the panic hook.
+//!
+//! Each panic writes `crash-<timestamp>-<pid>.json` to the log directory and bumps
+//! the counter in `crash-count`. Both survive restarts, so the last crash shown in
+//! `GET /api/dashboard/system/health` may come from an earlier process.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const COUNT_FILE: &str = "crash-count";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub timestamp: DateTime<Utc>,
+    pub pid: u32,
+    pub version: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic.
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// In-flight API requests when the panic happened.
+    pub active_requests: usize,
+    pub last_config_reload: Option<DateTime<Utc>>,
+}
+
+pub struct CrashReporter {
+    /// Where reports are written; `None` keeps them in memory only.
+    dir: Option<PathBuf>,
+    count: Mutex<u64>,
+    last: Mutex<Option<CrashReport>>,
+    last_config_reload: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Default for CrashReporter {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl CrashReporter {
+    /// Pick up the crash count and newest report left in `dir` by earlier runs.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let count = dir.as_deref().map(read_count).unwrap_or(0);
+        let last = dir.as_deref().and_then(newest_report);
+        Self {
+            dir,
+            count: Mutex::new(count),
+            last: Mutex::new(last),
+            last_config_reload: Mutex::new(None),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.lock().map(|count| *count).unwrap_or(0)
+    }
+
+    pub fn last(&self) -> Option<CrashReport> {
+        self.last.lock().ok().and_then(|last| last.clone())
+    }
+
+    pub fn last_config_reload(&self) -> Option<DateTime<Utc>> {
+        self.last_config_reload.lock().ok().and_then(|at| *at)
+    }
+
+    /// Remember when the config was last applied, for the next report.
+    pub fn note_config_reload(&self) {
+        if let Ok(mut at) = self.last_config_reload.lock() {
+            *at = Some(Utc::now());
+        }
+    }
+
+    /// Build, persist, and remember a report. Called from the panic hook, so it
+    /// never blocks on a held lock and only logs write failures.
+    pub fn capture(&self, info: &std::panic::PanicHookInfo<'_>, active_requests: usize) {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".to_string());
+        let report = CrashReport {
+            timestamp: Utc::now(),
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            thread: std::thread::current().name().map(str::to_string),
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            active_requests,
+            last_config_reload: self.last_config_reload.try_lock().ok().and_then(|at| *at),
+        };
+        self.record(report);
+    }
+
+    fn record(&self, report: CrashReport) {
+        let count = match self.count.try_lock() {
+            Ok(mut count) => {
+                *count += 1;
+                *count
+            }
+            Err(_) => return,
+        };
+        if let Some(dir) = &self.dir
+            && let Err(e) = write_report(dir, &report, count)
+        {
+            tracing::error!(dir = %dir.display(), "Failed to write crash report: {e}");
+        }
+        if let Ok(mut last) = self.last.try_lock() {
+            *last = Some(report);
+        }
+    }
+}
+
+fn write_report(dir: &Path, report: &CrashReport, count: u64) -> Result<(), anyhow::Error> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!(
+        "crash-{}-{}.json",
+        report.timestamp.format("%Y%m%dT%H%M%S%.3fZ"),
+        report.pid
+    );
+    std::fs::write(dir.join(name), serde_json::to_vec_pretty(report)?)?;
+    std::fs::write(dir.join(COUNT_FILE), count.to_string())?;
+    Ok(())
+}
+
+fn read_count(dir: &Path) -> u64 {
+    std::fs::read_to_string(dir.join(COUNT_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Report names embed their timestamp, so the lexically last one is the newest.
+fn newest_report(dir: &Path) -> Option<CrashReport> {
+    let newest = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("crash-") && n.ends_with(".json"))
+        })
+        .max()?;
+    serde_json::from_slice(&std::fs::read(newest).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(message: &str) -> CrashReport {
+        CrashReport {
+            timestamp: Utc::now(),
+            pid: 7,
+            version: "0.1.0".into(),
+            thread: Some("tokio-runtime-worker".into()),
+            message: message.into(),
+            location: Some("src/lib.rs:1:1".into()),
+            backtrace: String::new(),
+            active_requests: 3,
+            last_config_reload: None,
+        }
+    }
+
+    #[test]
+    fn test_reports_and_count_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let reporter = CrashReporter::new(Some(dir.path().to_path_buf()));
+        assert_eq!(reporter.count(), 0);
+        assert!(reporter.last().is_none());
+
+        reporter.record(report("first"));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        reporter.record(report("second"));
+        assert_eq!(reporter.count(), 2);
+
+        let restarted = CrashReporter::new(Some(dir.path().to_path_buf()));
+        assert_eq!(restarted.count(), 2);
+        let last = restarted.last().unwrap();
+        assert_eq!(last.message, "second");
+        assert_eq!(last.active_requests, 3);
+    }
+}
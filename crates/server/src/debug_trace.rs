@@ -0,0 +1,195 @@
+//! Per-request debug traces for requests sent with `x-debug: trace`.
+//!
+//! Traces are only recorded when `request-debug.enabled` is set. They hold the
+//! client request, the route plan, every upstream attempt with its translated
+//! payload and outcome, and the final status. Secret-bearing fields and headers
+//! are masked, and bodies are cut to `request-debug.max-body-bytes`. The newest
+//! `request-debug.max-records` traces are kept in memory, keyed by request ID.
+
+use chrono::{DateTime, Utc};
+use prism_core::request_record::truncate_body;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugEvent {
+    pub at: DateTime<Utc>,
+    /// `client_request`, `route_plan`, `upstream_request`, `upstream_result`, or `completed`.
+    pub kind: String,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugTrace {
+    pub request_id: String,
+    pub started_at: DateTime<Utc>,
+    pub events: Vec<DebugEvent>,
+}
+
+#[derive(Default)]
+pub struct DebugTraceStore {
+    traces: Mutex<VecDeque<DebugTrace>>,
+}
+
+impl DebugTraceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin a trace for `request_id`, dropping the oldest past `max_records`.
+    pub fn start(&self, request_id: &str, max_records: usize) {
+        let Ok(mut traces) = self.traces.lock() else {
+            return;
+        };
+        traces.retain(|t| t.request_id != request_id);
+        traces.push_back(DebugTrace {
+            request_id: request_id.to_string(),
+            started_at: Utc::now(),
+            events: Vec::new(),
+        });
+        while traces.len() > max_records.max(1) {
+            traces.pop_front();
+        }
+    }
+
+    /// Append an event to a started trace; ignored once the trace was evicted.
+    pub fn record(&self, request_id: &str, kind: &str, data: Value) {
+        let Ok(mut traces) = self.traces.lock() else {
+            return;
+        };
+        if let Some(trace) = traces.iter_mut().rev().find(|t| t.request_id == request_id) {
+            trace.events.push(DebugEvent {
+                at: Utc::now(),
+                kind: kind.to_string(),
+                data,
+            });
+        }
+    }
+
+    pub fn get(&self, request_id: &str) -> Option<DebugTrace> {
+        let traces = self.traces.lock().ok()?;
+        traces.iter().find(|t| t.request_id == request_id).cloned()
+    }
+}
+
+/// Field and header names whose values are credentials.
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase().replace('-', "_");
+    matches!(
+        name.as_str(),
+        "authorization"
+            | "proxy_authorization"
+            | "cookie"
+            | "set_cookie"
+            | "api_key"
+            | "apikey"
+            | "x_api_key"
+            | "x_goog_api_key"
+            | "access_token"
+            | "refresh_token"
+            | "id_token"
+            | "client_secret"
+            | "secret"
+            | "password"
+            | "token"
+    )
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_name(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Mask secrets in a JSON body and cap its size; an oversized or non-JSON body is
+/// kept as truncated text.
+pub fn redact_body(body: &[u8], max_bytes: usize) -> Value {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            let text = value.to_string();
+            if max_bytes > 0 && text.len() > max_bytes {
+                Value::String(truncate_body(&text, max_bytes).into_owned())
+            } else {
+                value
+            }
+        }
+        Err(_) => {
+            Value::String(truncate_body(&String::from_utf8_lossy(body), max_bytes).into_owned())
+        }
+    }
+}
+
+/// Headers sorted by name, with credential values masked.
+pub fn redact_headers(headers: &HashMap<String, String>) -> Value {
+    let sorted: BTreeMap<&str, &str> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_secret_name(name) {
+                REDACTED
+            } else {
+                value.as_str()
+            };
+            (name.as_str(), value)
+        })
+        .collect();
+    serde_json::to_value(sorted).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redacts_nested_secrets_and_truncates() {
+        let body = json!({
+            "model": "gpt-4o",
+            "metadata": {"api_key": "sk-live", "user": "u1"},
+            "tools": [{"auth": {"access-token": "t0k"}}],
+            "max_tokens": 10
+        });
+        let redacted = redact_body(body.to_string().as_bytes(), 0);
+        assert_eq!(redacted["metadata"]["api_key"], REDACTED);
+        assert_eq!(redacted["metadata"]["user"], "u1");
+        assert_eq!(redacted["tools"][0]["auth"]["access-token"], REDACTED);
+        assert_eq!(redacted["max_tokens"], 10);
+
+        assert!(redact_body(body.to_string().as_bytes(), 16).is_string());
+
+        let headers = HashMap::from([
+            ("x-api-key".to_string(), "sk-live".to_string()),
+            ("anthropic-version".to_string(), "2023-06-01".to_string()),
+        ]);
+        let headers = redact_headers(&headers);
+        assert_eq!(headers["x-api-key"], REDACTED);
+        assert_eq!(headers["anthropic-version"], "2023-06-01");
+    }
+
+    #[test]
+    fn test_store_keeps_newest_traces() {
+        let store = DebugTraceStore::new();
+        store.start("a", 2);
+        store.record("a", "client_request", json!({}));
+        store.start("b", 2);
+        store.start("c", 2);
+        store.record("a", "completed", json!({}));
+        assert!(store.get("a").is_none());
+        assert_eq!(store.get("b").unwrap().events.len(), 0);
+        assert!(store.get("c").is_some());
+    }
+}
@@ -1,6 +1,10 @@
+mod chunk_probe;
 mod executor;
 mod features;
 mod helpers;
+mod image_fetch;
+mod moderation;
+mod shadow;
 mod streaming;
 
 use crate::AppState;
@@ -8,12 +12,19 @@ use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
 use executor::ExecutionController;
 use features::extract_features;
-use helpers::{inject_route_headers, rewrite_model_in_body};
+pub(crate) use helpers::rewrite_model_in_body;
+use helpers::{append_timing_header, inject_route_headers, narrow_error_headers};
+use prism_core::config::{Config, ExperimentArm};
+use prism_core::context::TraceContext;
 use prism_core::error::ProxyError;
+use prism_core::metrics::DispatchPhase;
 use prism_core::provider::Format;
 use prism_core::request_record::{LogDetailLevel, classify_error, truncate_body};
 use prism_core::routing::planner::RoutePlanner;
-use std::time::Instant;
+use prism_core::routing::types::{RejectReason, RouteRejection};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// A dispatch request encapsulating all information needed to route and execute an API call.
 pub struct DispatchRequest {
@@ -35,12 +46,23 @@ pub struct DispatchRequest {
     pub user_agent: Option<String>,
     /// Debug mode: return routing details in response headers.
     pub debug: bool,
+    /// Record a debug trace (`x-debug: trace`); cleared by `dispatch` unless
+    /// `request-debug.enabled` is set.
+    pub debug_trace: bool,
     /// API key (for per-key rate limiting post-check).
     pub api_key: Option<String>,
     /// Client region (for geo-aware routing).
     pub client_region: Option<String>,
     /// Request ID for correlating streaming usage updates with log entries.
     pub request_id: Option<String>,
+    /// W3C trace context, propagated upstream and recorded on the request log.
+    pub trace: Option<TraceContext>,
+    /// Inbound client headers selected by `outbound-headers.forward`.
+    pub forwarded_headers: HashMap<String, String>,
+    /// Inbound headers the route planner reads (`sticky-key: header:<name>`), lowercased.
+    pub routing_headers: HashMap<String, String>,
+    /// Flags from the client's `anthropic-beta` header, in order.
+    pub anthropic_betas: Vec<String>,
     /// Masked API key ID for logging.
     pub api_key_id: Option<String>,
     /// Tenant ID for logging.
@@ -50,6 +72,15 @@ pub struct DispatchRequest {
     /// When true, the request body is already in OpenAI Responses API format.
     /// The executor should forward it directly to `/v1/responses` without conversion.
     pub responses_passthrough: bool,
+    /// A/B experiment arm, assigned by `dispatch` (handlers pass `None`).
+    pub experiment: Option<ExperimentAssignment>,
+}
+
+/// Experiment arm chosen for a request.
+#[derive(Debug, Clone)]
+pub struct ExperimentAssignment {
+    pub name: String,
+    pub arm: ExperimentArm,
 }
 
 /// Unified dispatch: plans route via RoutePlanner, then executes via ExecutionController.
@@ -58,7 +89,85 @@ pub struct DispatchRequest {
 /// `GatewayLogLayer` to produce structured request records.
 ///
 /// Flow: extract features → plan route → cache check → execute plan → debug headers → log.
-pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Response, ProxyError> {
+///
+/// With `debug_trace` set, each step is also recorded in [`AppState::debug_traces`].
+///
+/// Streaming requests take a `concurrency.max-streams` slot first and hold it until the
+/// response body ends.
+pub async fn dispatch(state: &AppState, req: DispatchRequest) -> Result<Response, ProxyError> {
+    if !req.stream {
+        return traced_dispatch(state, req).await;
+    }
+    let slot = state
+        .load_shedder
+        .acquire_stream(&state.config.load().concurrency, &state.metrics)?;
+    traced_dispatch(state, req)
+        .await
+        .map(|resp| crate::load_shed::hold_until_body_end(resp, slot))
+}
+
+async fn traced_dispatch(
+    state: &AppState,
+    mut req: DispatchRequest,
+) -> Result<Response, ProxyError> {
+    let start = Instant::now();
+    let debug = state.config.load().request_debug.clone();
+    req.debug_trace &= debug.enabled && req.request_id.is_some();
+    let Some(request_id) = req.request_id.clone().filter(|_| req.debug_trace) else {
+        return run_dispatch(state, req).await;
+    };
+
+    state.debug_traces.start(&request_id, debug.max_records);
+    state.debug_traces.record(
+        &request_id,
+        "client_request",
+        json!({
+            "path": req.request_path,
+            "source_format": req.source_format.as_str(),
+            "model": req.model,
+            "stream": req.stream,
+            "body": crate::debug_trace::redact_body(&req.body, debug.max_body_bytes),
+        }),
+    );
+    let result = run_dispatch(state, req).await;
+    let completed = match &result {
+        Ok(resp) => json!({
+            "status": resp.status().as_u16(),
+            "latency_ms": start.elapsed().as_millis() as u64,
+        }),
+        Err(err) => json!({
+            "status": err.status_code_u16(),
+            "latency_ms": start.elapsed().as_millis() as u64,
+            "error": err.to_string(),
+            "error_type": classify_error(err),
+        }),
+    };
+    state
+        .debug_traces
+        .record(&request_id, "completed", completed);
+    result.map(|mut resp| {
+        if let Ok(value) = request_id.parse() {
+            resp.headers_mut().insert("x-prism-debug-trace", value);
+        }
+        resp
+    })
+}
+
+/// Record a debug trace event when the request asked for one.
+pub(crate) fn trace_event(
+    state: &AppState,
+    req: &DispatchRequest,
+    kind: &str,
+    data: impl FnOnce() -> serde_json::Value,
+) {
+    if req.debug_trace
+        && let Some(ref request_id) = req.request_id
+    {
+        state.debug_traces.record(request_id, kind, data());
+    }
+}
+
+async fn run_dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Response, ProxyError> {
     let start = Instant::now();
     let config = state.config.load();
     let detail_level = config.log_store.detail_level;
@@ -70,6 +179,11 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
     let request_span = tracing::info_span!(
         "gateway.request",
         request_id = request_id.as_str(),
+        trace_id = req
+            .trace
+            .as_ref()
+            .map(|t| t.trace_id.as_str())
+            .unwrap_or(""),
         method = "POST",
         path = tracing::field::Empty,
         stream = req.stream,
@@ -95,8 +209,26 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
         tenant_id = req.tenant_id.as_deref().unwrap_or(""),
         client_ip = tracing::field::Empty,
         client_region = req.client_region.as_deref().unwrap_or(""),
+        experiment = tracing::field::Empty,
+        experiment_arm = tracing::field::Empty,
+        key_tags = tracing::field::Empty,
+        provider_tags = tracing::field::Empty,
+        slow_threshold_ms = config.log_store.slow_request_threshold_ms,
+        stream_ms = tracing::field::Empty,
+        stream_truncated = tracing::field::Empty,
     );
     request_span.record("path", req.request_path.as_str());
+    let key_tags = req
+        .api_key
+        .as_ref()
+        .and_then(|k| config.auth_key_store.lookup(k))
+        .map(|entry| entry.tags.clone())
+        .unwrap_or_default();
+    if !key_tags.is_empty() {
+        request_span.record("key_tags", key_tags.join(",").as_str());
+    }
+    state.inflight.set_target(&request_id, None, &req.model);
+    state.metrics.record_request_bytes(req.body.len() as u64);
 
     // Record client request body if detail level allows
     if detail_level >= LogDetailLevel::Standard
@@ -128,6 +260,17 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
         )));
     }
 
+    // ── Preset models: expand to the base model, prompt, and default params ──
+    if let Some(preset) = config.preset_models.iter().find(|p| p.name == req.model)
+        && let Ok(mut body) = serde_json::from_slice::<serde_json::Value>(&req.body)
+    {
+        preset.apply(&mut body, req.source_format);
+        if let Ok(bytes) = serde_json::to_vec(&body) {
+            req.body = Bytes::from(bytes);
+        }
+        req.model = preset.model.clone();
+    }
+
     // ── Model rewrite (aliases + glob rewrites) ──
     if let Some(rewritten) = config.routing.resolve_model_rewrite(&req.model) {
         let rewritten = rewritten.to_string();
@@ -135,6 +278,38 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
         req.model = rewritten;
     }
 
+    // ── Prefix enforcement: require a credential namespace in the model name ──
+    if config.force_model_prefix && !state.router.model_has_prefix(&req.model) {
+        return Err(ProxyError::BadRequest(format!(
+            "model '{}' must include a provider prefix",
+            req.model
+        )));
+    }
+
+    // ── Experiments: assign an arm, rewriting the model for the variant ──
+    if let Some(experiment) = config.experiments.iter().find(|e| e.matches(&req.model)) {
+        let arm = experiment.assign(rand::random::<f64>());
+        if arm == ExperimentArm::Variant {
+            req.body = rewrite_model_in_body(&req.body, &experiment.variant);
+            req.model = experiment.variant.clone();
+        }
+        request_span.record("experiment", experiment.name.as_str());
+        request_span.record("experiment_arm", arm.as_str());
+        req.experiment = Some(ExperimentAssignment {
+            name: experiment.name.clone(),
+            arm,
+        });
+    }
+
+    // ── Moderation (external check, per-key policy) ──
+    let moderation_note = match moderation::check(state, &req).await {
+        Ok(note) => note,
+        Err(err) => {
+            record_dispatch_error(state, &request_span, start, &err, 0, &key_tags);
+            return Err(err);
+        }
+    };
+
     // ── Cache lookup (non-stream, temperature=0) ──
     if !req.stream
         && let Some(ref cache) = state.response_cache
@@ -154,18 +329,23 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
             request_span.record("status", 200u64);
             request_span.record("latency_ms", start.elapsed().as_millis() as u64);
             request_span.record("total_attempts", 0u64);
-            let resp = axum::http::Response::builder()
+            let mut resp = axum::http::Response::builder()
                 .header(axum::http::header::CONTENT_TYPE, "application/json")
                 .header("x-cache", "HIT")
                 .body(axum::body::Body::from(cached.payload))
                 .map_err(|e| ProxyError::Internal(format!("failed to build response: {e}")))?
                 .into_response();
+            insert_moderation_header(&mut resp, moderation_note.as_deref());
             return Ok(resp);
         }
         state.metrics.record_cache_miss();
     }
 
+    // ── Shadow traffic (async, response discarded) ──
+    shadow::maybe_mirror(state, &req);
+
     // ── Extract features and plan route ──
+    let pick_start = Instant::now();
     let features = extract_features(&req);
 
     // Merge client-provided model chain with planner's model resolution
@@ -188,48 +368,120 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
         plan.model_chain = chain;
     }
 
-    // Resolve failover config from the matched profile
+    let quota_reset = drop_quota_exhausted(state, &mut plan);
+
+    // Resolve failover and race config from the matched profile
     let profile_name = &plan.profile;
-    let failover = config
-        .routing
-        .profiles
-        .get(profile_name)
-        .map(|p| p.failover.clone())
-        .unwrap_or_default();
+    let profile = config.routing.profiles.get(profile_name);
+    let failover = profile.map(|p| p.failover.clone()).unwrap_or_default();
+    let race = profile.map(|p| p.race.clone()).unwrap_or_default();
+    let pick_elapsed = pick_start.elapsed();
+    state
+        .metrics
+        .record_phase(DispatchPhase::CredentialPick, pick_elapsed);
+    trace_event(state, &req, "route_plan", || {
+        json!({
+            "profile": plan.profile,
+            "model_chain": plan.model_chain,
+            "attempts": plan.attempts.iter().map(|a| json!({
+                "provider": a.provider.as_str(),
+                "credential": a.credential_name,
+                "model": a.model,
+            })).collect::<Vec<_>>(),
+        })
+    });
 
     if plan.attempts.is_empty() {
-        state.metrics.record_error();
-        state.metrics.record_latency_ms(start.elapsed().as_millis());
-        let err = ProxyError::NoCredentials {
-            provider: "all".to_string(),
-            model: plan.model_chain.join(","),
+        let err = match quota_reset {
+            Some(resets_at) => ProxyError::RateLimited {
+                message: format!(
+                    "all credentials for {} have exhausted their quota",
+                    plan.model_chain.join(",")
+                ),
+                retry_after_secs: (resets_at - chrono::Utc::now()).num_seconds().max(1) as u64,
+            },
+            None => ProxyError::NoCredentials {
+                provider: "all".to_string(),
+                model: plan.model_chain.join(","),
+            },
         };
-        request_span.record("total_attempts", 0u64);
-        request_span.record("status", err.status_code_u16() as u64);
-        request_span.record("latency_ms", start.elapsed().as_millis() as u64);
-        request_span.record("error", err.to_string());
-        request_span.record("error_type", classify_error(&err));
+        record_dispatch_error(state, &request_span, start, &err, 0, &key_tags);
         return Err(err);
     }
 
     // ── Execute plan ──
     let controller = ExecutionController::new(state);
-    match controller
-        .execute(
-            &plan,
-            &req,
-            &failover,
-            &request_span,
-            detail_level,
-            max_body_bytes,
-        )
-        .await
-    {
+    let queue_budget = Duration::from_millis(config.queue_on_exhaustion_ms);
+    let deadline = Instant::now() + queue_budget;
+    let outcome = loop {
+        // Queue-then-serve: while every credential is cooling down, wait for the
+        // earliest expiry if it lands within the budget; otherwise fail fast. A
+        // request giving back its concurrency slot wakes the wait early so the
+        // cooldowns are checked again.
+        if !queue_budget.is_zero()
+            && let Some(wait) = controller.all_cooling_down(&plan)
+        {
+            if Instant::now() + wait > deadline {
+                break Err(ProxyError::RateLimited {
+                    message: format!(
+                        "all credentials for {} are cooling down",
+                        plan.model_chain.join(",")
+                    ),
+                    retry_after_secs: wait.as_secs_f64().ceil().max(1.0) as u64,
+                });
+            }
+            tracing::debug!(
+                wait_ms = wait.as_millis() as u64,
+                "All credentials cooling down, queueing request"
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.load_shedder.slot_released() => {}
+            }
+            continue;
+        }
+        let result = controller
+            .execute(
+                &plan,
+                &req,
+                &failover,
+                &race,
+                &request_span,
+                detail_level,
+                max_body_bytes,
+            )
+            .await;
+        // Attempts that ended on rate limits are retried once the cooldown they
+        // set expires, as long as that is still inside the budget.
+        if let Err(ref err) = result
+            && !queue_budget.is_zero()
+            && err.status_code_u16() == 429
+            && controller
+                .all_cooling_down(&plan)
+                .is_some_and(|wait| Instant::now() + wait <= deadline)
+        {
+            continue;
+        }
+        break result;
+    };
+    let tags = merge_tags(&key_tags, &controller.provider_tags());
+    match outcome {
         Ok(result) => {
+            state.metrics.record_tag_request(&tags, false);
             request_span.record("total_attempts", result.total_attempts as u64);
+            if let Some(ref experiment) = req.experiment {
+                state.metrics.record_experiment_request(
+                    &experiment.name,
+                    experiment.arm.as_str(),
+                    start.elapsed().as_millis(),
+                    false,
+                );
+            }
 
             let mut resp = result.response;
+            insert_moderation_header(&mut resp, moderation_note.as_deref());
             if req.debug {
+                append_timing_header(&mut resp, DispatchPhase::CredentialPick, pick_elapsed);
                 inject_route_headers(
                     &mut resp,
                     &plan.profile,
@@ -238,24 +490,211 @@ pub async fn dispatch(state: &AppState, mut req: DispatchRequest) -> Result<Resp
                     result.model.as_deref(),
                     result.total_attempts,
                 );
+                if let Some(ref race) = result.race
+                    && let Ok(value) = race.parse()
+                {
+                    resp.headers_mut().insert("x-prism-route-race", value);
+                }
+                if let Some(ref experiment) = req.experiment
+                    && let Ok(value) =
+                        format!("{}/{}", experiment.name, experiment.arm.as_str()).parse()
+                {
+                    resp.headers_mut().insert("x-prism-experiment", value);
+                }
             }
             Ok(resp)
         }
-        Err(err) => {
-            state.metrics.record_error();
-            state.metrics.record_latency_ms(start.elapsed().as_millis());
-
-            request_span.record("total_attempts", plan.attempts.len() as u64);
-            request_span.record("status", err.status_code_u16() as u64);
-            request_span.record("latency_ms", start.elapsed().as_millis() as u64);
-            request_span.record("error", err.to_string());
-            request_span.record("error_type", classify_error(&err));
+        Err(mut err) => {
+            narrow_error_headers(&mut err, &config.passthrough_headers);
+            if let Some(ref experiment) = req.experiment {
+                state.metrics.record_experiment_request(
+                    &experiment.name,
+                    experiment.arm.as_str(),
+                    start.elapsed().as_millis(),
+                    true,
+                );
+            }
 
+            record_dispatch_error(
+                state,
+                &request_span,
+                start,
+                &err,
+                plan.attempts.len() as u64,
+                &tags,
+            );
             Err(err)
         }
     }
 }
 
+/// Count a request that ends in `err` and record the outcome on its span, so the
+/// request log entry carries the status and error.
+fn record_dispatch_error(
+    state: &AppState,
+    request_span: &tracing::Span,
+    start: Instant,
+    err: &ProxyError,
+    total_attempts: u64,
+    tags: &[String],
+) {
+    state.metrics.record_error();
+    state.metrics.record_latency_ms(start.elapsed().as_millis());
+    state.metrics.record_tag_request(tags, true);
+    request_span.record("total_attempts", total_attempts);
+    request_span.record("status", err.status_code_u16() as u64);
+    request_span.record("latency_ms", start.elapsed().as_millis() as u64);
+    request_span.record("error", err.to_string());
+    request_span.record("error_type", classify_error(err));
+}
+
+/// Key tags followed by the provider tags not already among them.
+fn merge_tags(key_tags: &[String], provider_tags: &[String]) -> Vec<String> {
+    let mut tags = key_tags.to_vec();
+    for tag in provider_tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+/// Tell the client its prompt was flagged (moderation `annotate` action).
+fn insert_moderation_header(response: &mut Response, note: Option<&str>) {
+    if let Some(value) = note.and_then(|note| note.parse().ok()) {
+        response.headers_mut().insert("x-prism-moderation", value);
+    }
+}
+
+/// Add the upstream trace header per `trace-propagation`, keeping any explicit value.
+pub(crate) fn insert_trace_header(
+    headers: &mut HashMap<String, String>,
+    config: &Config,
+    trace: Option<&TraceContext>,
+) {
+    if let Some(trace) = trace
+        && config.trace_propagation.enabled
+    {
+        headers
+            .entry(config.trace_propagation.upstream_header.clone())
+            .or_insert_with(|| trace.to_traceparent());
+    }
+}
+
+/// Collect the inbound headers listed in `outbound-headers.forward`.
+pub(crate) fn forwarded_headers(
+    headers: &axum::http::HeaderMap,
+    config: &Config,
+) -> HashMap<String, String> {
+    config
+        .outbound_headers
+        .forward
+        .iter()
+        .filter_map(|name| {
+            let value = headers.get(name.as_str())?.to_str().ok()?;
+            Some((name.clone(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Split every inbound `anthropic-beta` header into its comma-separated flags.
+pub(crate) fn anthropic_betas(headers: &axum::http::HeaderMap) -> Vec<String> {
+    let mut betas: Vec<String> = Vec::new();
+    for value in headers.get_all("anthropic-beta") {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for beta in value.split(',').map(str::trim).filter(|b| !b.is_empty()) {
+            if !betas.iter().any(|b| b == beta) {
+                betas.push(beta.to_string());
+            }
+        }
+    }
+    betas
+}
+
+/// Collect the inbound headers named by `header:` sticky keys in routing profiles.
+pub(crate) fn routing_headers(
+    headers: &axum::http::HeaderMap,
+    config: &Config,
+) -> HashMap<String, String> {
+    config
+        .routing
+        .profiles
+        .values()
+        .filter_map(|profile| match profile.credential_policy.sticky_key() {
+            prism_core::routing::config::StickyKey::Header(name) => {
+                let name = name.to_ascii_lowercase();
+                let value = headers.get(name.as_str())?.to_str().ok()?.to_string();
+                Some((name, value))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The default profile's `sticky-key` value for a request, for handlers that
+/// pick a credential directly instead of through the route planner.
+pub(crate) fn sticky_value(
+    config: &Config,
+    ctx: &prism_core::context::RequestContext,
+    headers: &axum::http::HeaderMap,
+) -> Option<String> {
+    let profile = config
+        .routing
+        .profiles
+        .get(&config.routing.default_profile)?;
+    match profile.credential_policy.sticky_key() {
+        prism_core::routing::config::StickyKey::ApiKey => ctx.api_key_id.clone(),
+        prism_core::routing::config::StickyKey::TenantId => ctx.tenant_id.clone(),
+        prism_core::routing::config::StickyKey::Header(name) => headers
+            .get(name.to_ascii_lowercase().as_str())?
+            .to_str()
+            .ok()
+            .map(str::to_string),
+    }
+    .filter(|v| !v.is_empty())
+}
+
+/// Merge forwarded client headers under the configured ones, then apply
+/// `outbound-headers.strip` to the final upstream header set.
+pub(crate) fn apply_outbound_headers(
+    headers: &mut HashMap<String, String>,
+    config: &Config,
+    forwarded: &HashMap<String, String>,
+) {
+    for (name, value) in forwarded {
+        headers.entry(name.clone()).or_insert_with(|| value.clone());
+    }
+    headers.retain(|name, _| !config.outbound_headers.is_stripped(name));
+}
+
+/// Remove attempts whose credential is over its daily or monthly quota; they sit out
+/// until the period resets. Returns the earliest reset among the removed credentials.
+pub(crate) fn drop_quota_exhausted(
+    state: &AppState,
+    plan: &mut prism_core::routing::types::RoutePlan,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    let mut quota_reset: Option<chrono::DateTime<chrono::Utc>> = None;
+    let mut quota_rejections = Vec::new();
+    plan.attempts.retain(
+        |attempt| match state.router.quota_exhausted(&attempt.credential_id) {
+            Some(exhausted) => {
+                quota_reset =
+                    Some(quota_reset.map_or(exhausted.resets_at, |r| r.min(exhausted.resets_at)));
+                quota_rejections.push(RouteRejection {
+                    candidate: attempt.credential_name.clone(),
+                    reason: RejectReason::QuotaExhausted,
+                });
+                false
+            }
+            None => true,
+        },
+    );
+    plan.trace.rejections.extend(quota_rejections);
+    quota_reset
+}
+
 /// Record attempt success fields on an attempt span, then drop it.
 fn record_attempt_success(attempt_span: tracing::Span, latency_ms: u64) {
     attempt_span.record("status", 200u64);
@@ -326,7 +765,10 @@ fn inject_thinking_budget(body: &Bytes, budget: u64) -> Bytes {
 
 #[cfg(test)]
 mod tests {
-    use super::helpers::{extract_usage, inject_stream_usage_option};
+    use super::helpers::{
+        build_json_response, claude_server_tools, extract_usage, inject_stream_usage_option,
+        is_usage_only_chunk, normalized_rate_limit_headers,
+    };
     use super::streaming::keepalive_error_json;
     use super::*;
 
@@ -375,6 +817,15 @@ mod tests {
         assert_eq!(usage.output_tokens, 8);
     }
 
+    #[test]
+    fn test_extract_usage_gemini_cached_content() {
+        let payload = r#"{"usageMetadata":{"promptTokenCount":1200,"candidatesTokenCount":8,"cachedContentTokenCount":1000}}"#;
+        let usage = extract_usage(payload).unwrap();
+        assert_eq!(usage.input_tokens, 200);
+        assert_eq!(usage.cache_read_tokens, 1000);
+        assert_eq!(usage.total_input(), 1200);
+    }
+
     #[test]
     fn test_extract_usage_no_usage() {
         let payload = r#"{"choices":[{"message":{"content":"hi"}}]}"#;
@@ -454,6 +905,102 @@ mod tests {
         assert!(response.headers().get("x-prism-route-id").is_some());
     }
 
+    #[test]
+    fn test_append_timing_header() {
+        let mut response = axum::http::Response::builder()
+            .body(axum::body::Body::empty())
+            .unwrap()
+            .into_response();
+
+        append_timing_header(
+            &mut response,
+            DispatchPhase::Translate,
+            std::time::Duration::from_micros(1250),
+        );
+        append_timing_header(
+            &mut response,
+            DispatchPhase::CredentialPick,
+            std::time::Duration::from_micros(40),
+        );
+
+        let values: Vec<&str> = response
+            .headers()
+            .get_all("x-prism-timing")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert_eq!(values, ["translate;dur=1.250", "credential_pick;dur=0.040"]);
+    }
+
+    // === rate-limit passthrough ===
+
+    #[test]
+    fn test_normalized_rate_limit_headers_anthropic() {
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let upstream = HashMap::from([
+            (
+                "anthropic-ratelimit-requests-limit".to_string(),
+                "50".to_string(),
+            ),
+            (
+                "anthropic-ratelimit-requests-remaining".to_string(),
+                "49".to_string(),
+            ),
+            ("anthropic-ratelimit-requests-reset".to_string(), reset),
+            (
+                "anthropic-ratelimit-tokens-remaining".to_string(),
+                "9000".to_string(),
+            ),
+            (
+                "anthropic-ratelimit-tokens-reset".to_string(),
+                "soon".to_string(),
+            ),
+            ("retry-after".to_string(), "3".to_string()),
+        ]);
+        let headers: HashMap<String, String> = normalized_rate_limit_headers(&upstream)
+            .into_iter()
+            .collect();
+        assert_eq!(headers["x-ratelimit-limit-requests"], "50");
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "49");
+        let secs: u64 = headers["x-ratelimit-reset-requests"]
+            .trim_end_matches('s')
+            .parse()
+            .unwrap();
+        assert!((28..=30).contains(&secs));
+        assert_eq!(headers["x-ratelimit-remaining-tokens"], "9000");
+        // Unparseable reset values are passed through unchanged.
+        assert_eq!(headers["x-ratelimit-reset-tokens"], "soon");
+        assert_eq!(headers["retry-after"], "3");
+        assert!(!headers.contains_key("x-ratelimit-limit-tokens"));
+    }
+
+    #[test]
+    fn test_rate_limits_passthrough_entry() {
+        let upstream = HashMap::from([
+            (
+                "x-ratelimit-remaining-requests".to_string(),
+                "7".to_string(),
+            ),
+            ("x-ratelimit-reset-requests".to_string(), "6m0s".to_string()),
+            (
+                "anthropic-ratelimit-requests-remaining".to_string(),
+                "1".to_string(),
+            ),
+            ("x-custom".to_string(), "kept".to_string()),
+            ("x-other".to_string(), "dropped".to_string()),
+        ]);
+        let passthrough = vec!["rate-limits".to_string(), "x-custom".to_string()];
+        let response = build_json_response("{}", &passthrough, &upstream).unwrap();
+        let headers = response.headers();
+        // OpenAI-style headers win over the Anthropic equivalents.
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "7");
+        assert_eq!(headers["x-ratelimit-reset-requests"], "6m0s");
+        assert_eq!(headers["x-custom"], "kept");
+        assert!(!headers.contains_key("x-other"));
+        assert!(!headers.contains_key("anthropic-ratelimit-requests-remaining"));
+        assert_eq!(headers["content-type"], "application/json");
+    }
+
     // === rewrite_model_in_body ===
 
     #[test]
@@ -533,6 +1080,21 @@ mod tests {
         assert_eq!(val["stream_options"]["other"], 1);
     }
 
+    #[test]
+    fn test_is_usage_only_chunk() {
+        assert!(is_usage_only_chunk(
+            r#"{"id":"c","choices":[],"usage":{"prompt_tokens":3,"completion_tokens":1}}"#
+        ));
+        // Content chunks sent with include_usage carry `usage: null`.
+        assert!(!is_usage_only_chunk(
+            r#"{"id":"c","choices":[{"index":0,"delta":{"content":"hi"}}],"usage":null}"#
+        ));
+        assert!(!is_usage_only_chunk(
+            r#"{"id":"c","choices":[{"index":0,"delta":{},"finish_reason":"stop"}],"usage":{"total_tokens":4}}"#
+        ));
+        assert!(!is_usage_only_chunk("[DONE]"));
+    }
+
     #[test]
     fn test_inject_stream_usage_option_invalid_json() {
         let payload = b"not json".to_vec();
@@ -609,4 +1171,24 @@ mod tests {
         let result = inject_thinking_budget(&body, 10000);
         assert_eq!(result, body);
     }
+
+    #[test]
+    fn test_claude_server_tools_skips_client_tools() {
+        let body = serde_json::json!({
+            "tools": [
+                {"name": "get_weather", "input_schema": {"type": "object"}},
+                {"type": "custom", "name": "lookup", "input_schema": {"type": "object"}},
+                {"type": "web_search_20250305", "name": "web_search", "max_uses": 3},
+                {"type": "computer_20250124", "name": "computer", "display_width_px": 1024}
+            ]
+        });
+        let tools = claude_server_tools(&serde_json::to_vec(&body).unwrap());
+        assert_eq!(
+            tools,
+            vec![
+                ("web_search".to_string(), "web_search_20250305".to_string()),
+                ("computer".to_string(), "computer_20250124".to_string()),
+            ]
+        );
+    }
 }
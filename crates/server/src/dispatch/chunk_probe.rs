@@ -0,0 +1,259 @@
+//! Single-pass probe over a stream chunk for the fields usage capture needs.
+//!
+//! Stream capture used to parse every chunk into a `serde_json::Value` twice
+//! (once for usage, once for the content preview). The probe deserializes only
+//! the handful of fields it cares about, borrows content text straight from
+//! the chunk when it has no escapes, and skips parsing entirely for chunks that
+//! cannot carry usage or text.
+
+use prism_core::request_record::TokenUsage;
+use serde::Deserialize;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::helpers::usage_from_fields;
+
+/// Usage and content text found in one stream chunk.
+#[derive(Debug, Default)]
+pub(super) struct ChunkProbe<'a> {
+    /// OpenAI `choices[0].delta.content` or Claude `delta.text`.
+    pub text: Option<Cow<'a, str>>,
+    pub usage: Option<TokenUsage>,
+}
+
+/// Probe a chunk's SSE data for usage and content text.
+pub(super) fn probe_chunk(data: &str) -> ChunkProbe<'_> {
+    // Quick string check: keepalives, role deltas, and stop events carry neither.
+    if !data.contains("usage") && !data.contains("content") && !data.contains("text") {
+        return ChunkProbe::default();
+    }
+    match serde_json::from_str::<Fields<'_>>(data) {
+        Ok(fields) => {
+            let usage_obj = fields
+                .usage
+                .as_ref()
+                .or_else(|| fields.message.as_ref().and_then(|m| m.usage.as_ref()));
+            let usage = usage_from_fields(usage_obj, fields.usage_metadata.as_ref());
+            let text = fields
+                .choices
+                .0
+                .and_then(|c| c.delta.content)
+                .or(fields.delta.text);
+            ChunkProbe { text, usage }
+        }
+        // Unusual shapes (e.g. a non-object choice) fall back to a full parse.
+        Err(_) => probe_value(data),
+    }
+}
+
+fn probe_value(data: &str) -> ChunkProbe<'static> {
+    let Ok(val) = serde_json::from_str::<serde_json::Value>(data) else {
+        return ChunkProbe::default();
+    };
+    let usage_obj = val
+        .get("usage")
+        .or_else(|| val.get("message").and_then(|m| m.get("usage")));
+    let usage = usage_from_fields(usage_obj, val.get("usageMetadata"));
+    let text = val
+        .get("choices")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("delta"))
+        .and_then(|d| d.get("content"))
+        .and_then(|c| c.as_str())
+        .or_else(|| {
+            val.get("delta")
+                .and_then(|d| d.get("text"))
+                .and_then(|t| t.as_str())
+        })
+        .map(|t| Cow::Owned(t.to_string()));
+    ChunkProbe { text, usage }
+}
+
+#[derive(Deserialize)]
+struct Fields<'a> {
+    #[serde(default, borrow)]
+    choices: FirstChoice<'a>,
+    #[serde(default, borrow)]
+    delta: Delta<'a>,
+    #[serde(default)]
+    usage: Option<serde_json::Value>,
+    #[serde(default)]
+    message: Option<Message>,
+    #[serde(default, rename = "usageMetadata")]
+    usage_metadata: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    usage: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Choice<'a> {
+    #[serde(default, borrow)]
+    delta: Delta<'a>,
+}
+
+/// The first element of `choices`; the rest are skipped without allocating.
+#[derive(Default)]
+struct FirstChoice<'a>(Option<Choice<'a>>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for FirstChoice<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FirstChoiceVisitor<'a>(PhantomData<Choice<'a>>);
+
+        impl<'de: 'a, 'a> Visitor<'de> for FirstChoiceVisitor<'a> {
+            type Value = FirstChoice<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a choices array")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let first = seq.next_element::<Choice<'a>>()?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(FirstChoice(first))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(FirstChoice(None))
+            }
+        }
+
+        deserializer.deserialize_any(FirstChoiceVisitor(PhantomData))
+    }
+}
+
+/// A `delta` object. Responses API events send `delta` as a bare string,
+/// which is treated as empty rather than failing the probe.
+#[derive(Default)]
+struct Delta<'a> {
+    content: Option<Cow<'a, str>>,
+    text: Option<Cow<'a, str>>,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Delta<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct DeltaFields<'a> {
+            #[serde(default, borrow, deserialize_with = "borrow_opt_str")]
+            content: Option<Cow<'a, str>>,
+            #[serde(default, borrow, deserialize_with = "borrow_opt_str")]
+            text: Option<Cow<'a, str>>,
+        }
+
+        struct DeltaVisitor<'a>(PhantomData<Delta<'a>>);
+
+        impl<'de: 'a, 'a> Visitor<'de> for DeltaVisitor<'a> {
+            type Value = Delta<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a delta object or string")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let fields = DeltaFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(Delta {
+                    content: fields.content,
+                    text: fields.text,
+                })
+            }
+
+            fn visit_str<E: de::Error>(self, _: &str) -> Result<Self::Value, E> {
+                Ok(Delta::default())
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(Delta::default())
+            }
+        }
+
+        deserializer.deserialize_any(DeltaVisitor(PhantomData))
+    }
+}
+
+/// serde only borrows a bare `Cow<str>` field, not one wrapped in `Option`.
+fn borrow_opt_str<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Cow<'a, str>>, D::Error> {
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    Ok(Option::<Borrowed<'a>>::deserialize(deserializer)?.map(|b| b.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_openai_content_borrowed() {
+        let probe = probe_chunk(r#"{"choices":[{"index":0,"delta":{"content":"Hello"}}]}"#);
+        assert!(matches!(probe.text, Some(Cow::Borrowed("Hello"))));
+        assert!(probe.usage.is_none());
+    }
+
+    #[test]
+    fn test_probe_openai_final_usage_chunk() {
+        let probe = probe_chunk(
+            r#"{"choices":[],"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}"#,
+        );
+        assert!(probe.text.is_none());
+        let usage = probe.usage.unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 5);
+    }
+
+    #[test]
+    fn test_probe_claude_text_with_escapes() {
+        let probe = probe_chunk(
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"a\nb"}}"#,
+        );
+        assert_eq!(probe.text.as_deref(), Some("a\nb"));
+    }
+
+    #[test]
+    fn test_probe_claude_message_start_usage() {
+        let probe = probe_chunk(
+            r#"{"type":"message_start","message":{"id":"m","content":[],"usage":{"input_tokens":15,"output_tokens":0}}}"#,
+        );
+        assert_eq!(probe.usage.unwrap().input_tokens, 15);
+    }
+
+    #[test]
+    fn test_probe_gemini_usage_metadata() {
+        let probe = probe_chunk(
+            r#"{"candidates":[{"content":{"parts":[{"text":"hi"}]}}],"usageMetadata":{"promptTokenCount":7,"candidatesTokenCount":3}}"#,
+        );
+        let usage = probe.usage.unwrap();
+        assert_eq!(usage.input_tokens, 7);
+        assert_eq!(usage.output_tokens, 3);
+    }
+
+    #[test]
+    fn test_probe_responses_string_delta() {
+        let probe =
+            probe_chunk(r#"{"type":"response.output_text.delta","delta":"hi","content_index":0}"#);
+        assert!(probe.text.is_none());
+        assert!(probe.usage.is_none());
+    }
+
+    #[test]
+    fn test_probe_skips_irrelevant_and_invalid_chunks() {
+        let probe = probe_chunk(r#"{"type":"ping"}"#);
+        assert!(probe.text.is_none() && probe.usage.is_none());
+        let probe = probe_chunk("[DONE]");
+        assert!(probe.text.is_none() && probe.usage.is_none());
+        let probe = probe_chunk(r#"{"content": "#);
+        assert!(probe.text.is_none() && probe.usage.is_none());
+    }
+
+    #[test]
+    fn test_probe_falls_back_on_unusual_shapes() {
+        let probe = probe_chunk(r#"{"choices":["x"],"usage":{"prompt_tokens":2}}"#);
+        assert_eq!(probe.usage.unwrap().input_tokens, 2);
+    }
+}
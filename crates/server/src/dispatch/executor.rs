@@ -1,21 +1,30 @@
 use crate::AppState;
 use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
 use prism_core::error::ProxyError;
+use prism_core::metrics::DispatchPhase;
+use prism_core::payload::OutputTokenClamp;
 use prism_core::provider::{Format, ProviderRequest, ProviderResponse};
 use prism_core::request_record::{LogDetailLevel, truncate_body};
-use prism_core::routing::config::FailoverConfig;
+use prism_core::routing::config::{FailoverConfig, RaceConfig};
 use prism_core::routing::types::{RouteAttemptPlan, RouteFallbackEvent, RoutePlan, RouteTrace};
+use prism_provider::routing::CredentialRouter;
 use std::time::{Duration, Instant};
 
 use super::helpers::{
-    build_json_response, extract_usage, inject_stream_usage_option_value, rewrite_model_in_body,
+    append_timing_header, build_json_response, check_server_tools, extract_usage,
+    forward_anthropic_betas, inject_stream_usage_option_value, insert_passthrough_headers,
+    is_usage_only_chunk, rewrite_model_in_body,
 };
 use super::streaming::{
-    StreamDoneContext, build_keepalive_body, translate_stream, with_usage_capture,
+    StreamDoneContext, build_keepalive_body, translate_stream, with_stream_limit,
+    with_usage_capture,
 };
 use super::{
-    DispatchRequest, record_attempt_failure, record_attempt_success, record_usage_on_span,
+    DispatchRequest, apply_outbound_headers, insert_trace_header, record_attempt_failure,
+    record_attempt_success, record_usage_on_span,
 };
 
 /// Result of executing a route plan.
@@ -30,6 +39,8 @@ pub(super) struct ExecutionResult {
     pub model: Option<String>,
     /// Credential name of the successful attempt.
     pub credential_name: Option<String>,
+    /// Race summary (`racers=<n> winner=<credential>`) when race dispatch produced the response.
+    pub race: Option<String>,
 }
 
 /// Executes a pre-computed `RoutePlan` with stage-aware failover.
@@ -38,19 +49,53 @@ pub(super) struct ExecutionResult {
 /// each with independent attempt limits from `FailoverConfig`.
 pub(super) struct ExecutionController<'a> {
     state: &'a AppState,
+    /// Tags of the provider behind the last attempt that settled the request.
+    provider_tags: std::sync::Mutex<Vec<String>>,
+    /// Remote image downloads and downscaling shared by all attempts. Held across
+    /// the work so concurrent racers wait for one download instead of repeating it.
+    images: tokio::sync::Mutex<super::image_fetch::ImageMemo>,
 }
 
 impl<'a> ExecutionController<'a> {
     pub fn new(state: &'a AppState) -> Self {
-        Self { state }
+        Self {
+            state,
+            provider_tags: std::sync::Mutex::new(Vec::new()),
+            images: tokio::sync::Mutex::default(),
+        }
+    }
+
+    /// Tags of the provider that served the request, or of the last one that
+    /// failed it.
+    pub fn provider_tags(&self) -> Vec<String> {
+        self.provider_tags
+            .lock()
+            .map(|tags| tags.clone())
+            .unwrap_or_default()
+    }
+
+    /// Time until the first credential leaves quota cooldown, when every
+    /// credential in the plan is cooling down.
+    pub fn all_cooling_down(&self, plan: &RoutePlan) -> Option<Duration> {
+        plan.attempts
+            .iter()
+            .map(|attempt| self.state.router.cooldown_remaining(&attempt.credential_id))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
     }
 
     /// Execute the route plan, trying attempts in order with stage-aware limits.
+    ///
+    /// With race dispatch enabled, the first attempts are sent concurrently and the
+    /// first success wins; if every racer fails, the remaining attempts run in order.
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         &self,
         plan: &RoutePlan,
         req: &DispatchRequest,
         failover: &FailoverConfig,
+        race: &RaceConfig,
         request_span: &tracing::Span,
         detail_level: LogDetailLevel,
         max_body_bytes: usize,
@@ -58,10 +103,70 @@ impl<'a> ExecutionController<'a> {
         let mut trace = plan.trace.clone();
         let mut total_attempts: u32 = 0;
         let mut last_error: Option<ProxyError> = None;
+        let mut failures = FailureSummary::default();
 
         // Group attempts by model, then by provider within each model
         let model_groups = group_attempts_by_model(&plan.model_chain, &plan.attempts);
 
+        let racers = if race.enabled {
+            select_race_attempts(&model_groups, race.fanout as usize)
+        } else {
+            Vec::new()
+        };
+        if racers.len() >= 2 {
+            let outcome = self
+                .execute_race(
+                    &racers,
+                    req,
+                    request_span,
+                    detail_level,
+                    max_body_bytes,
+                    total_attempts,
+                )
+                .await;
+            total_attempts += racers.len() as u32;
+            // Racers run side by side, so none of them counts as a retry.
+            let (winner, errors) = match outcome {
+                Ok((winner, response, errors)) => (Some((winner, response)), errors),
+                Err(errors) => (None, errors),
+            };
+            let next_model =
+                race_fallback_model(&racers, winner.as_ref().map(|(idx, _)| *idx), &model_groups);
+            for (idx, err) in errors {
+                let failed = racers[idx].1;
+                self.record_attempt_outcome(failed, false, true);
+                self.record_provider_tags(request_span, &failed.credential_id);
+                failures.record(&failed.credential_id, &err);
+                trace.fallback_events.push(RouteFallbackEvent {
+                    from_model: failed.model.clone(),
+                    to_model: next_model.unwrap_or(&failed.model).to_string(),
+                    reason: format!("race: {err}"),
+                });
+                last_error = Some(err);
+            }
+            if let Some((winner, response)) = winner {
+                let (provider, attempt) = racers[winner];
+                self.record_attempt_outcome(attempt, false, false);
+                request_span.record("provider", provider.as_str());
+                request_span.record("model", attempt.model.as_str());
+                request_span.record("credential_name", attempt.credential_name.as_str());
+                self.record_provider_tags(request_span, &attempt.credential_id);
+                return Ok(ExecutionResult {
+                    response,
+                    trace,
+                    total_attempts,
+                    provider: Some(provider.as_str().to_string()),
+                    model: Some(attempt.model.clone()),
+                    credential_name: Some(attempt.credential_name.clone()),
+                    race: Some(format!(
+                        "racers={} winner={}",
+                        racers.len(),
+                        attempt.credential_name
+                    )),
+                });
+            }
+        }
+
         for (model_idx, (model, provider_groups)) in model_groups.iter().enumerate() {
             if model_idx >= failover.model_attempts as usize {
                 break;
@@ -76,10 +181,18 @@ impl<'a> ExecutionController<'a> {
                     if cred_idx >= failover.credential_attempts as usize {
                         break;
                     }
+                    if racers
+                        .iter()
+                        .any(|(_, raced)| std::ptr::eq(*raced, *attempt))
+                    {
+                        continue;
+                    }
 
                     total_attempts += 1;
+                    let mut output_clamp = None;
+                    let mut phases = Vec::new();
 
-                    match self
+                    let result = self
                         .execute_single_attempt(
                             attempt,
                             model,
@@ -89,10 +202,22 @@ impl<'a> ExecutionController<'a> {
                             detail_level,
                             max_body_bytes,
                             total_attempts,
+                            &mut output_clamp,
+                            &mut phases,
                         )
-                        .await
-                    {
-                        Ok(response) => {
+                        .await;
+                    self.record_attempt_outcome(attempt, total_attempts > 1, result.is_err());
+                    self.trace_attempt_result(req, attempt, total_attempts, &result);
+                    match result {
+                        Ok(mut response) => {
+                            if req.debug {
+                                insert_attempt_debug_headers(
+                                    &mut response,
+                                    output_clamp.as_ref(),
+                                    &phases,
+                                );
+                            }
+                            self.record_provider_tags(request_span, &attempt.credential_id);
                             return Ok(ExecutionResult {
                                 response,
                                 trace,
@@ -100,9 +225,12 @@ impl<'a> ExecutionController<'a> {
                                 provider: Some(provider.as_str().to_string()),
                                 model: Some(attempt.model.clone()),
                                 credential_name: Some(attempt.credential_name.clone()),
+                                race: None,
                             });
                         }
                         Err(err) => {
+                            self.record_provider_tags(request_span, &attempt.credential_id);
+                            failures.record(&attempt.credential_id, &err);
                             trace.fallback_events.push(RouteFallbackEvent {
                                 from_model: model.clone(),
                                 to_model: model.clone(),
@@ -125,10 +253,69 @@ impl<'a> ExecutionController<'a> {
             }
         }
 
-        Err(last_error.unwrap_or_else(|| ProxyError::NoCredentials {
-            provider: "all".to_string(),
-            model: plan.model_chain.join(","),
-        }))
+        Err(match last_error {
+            Some(err) => failures.into_error(err, &self.state.router),
+            None => ProxyError::NoCredentials {
+                provider: "all".to_string(),
+                model: plan.model_chain.join(","),
+            },
+        })
+    }
+
+    /// Run the racers concurrently and return the index and response of the first
+    /// success. Streaming racers only count as successful once their first chunk
+    /// arrives. Dropping the pending futures cancels the losing upstream requests.
+    async fn execute_race(
+        &self,
+        racers: &[(Format, &RouteAttemptPlan)],
+        req: &DispatchRequest,
+        request_span: &tracing::Span,
+        detail_level: LogDetailLevel,
+        max_body_bytes: usize,
+        attempts_before: u32,
+    ) -> Result<(usize, Response, Vec<(usize, ProxyError)>), Vec<(usize, ProxyError)>> {
+        let mut pending: FuturesUnordered<_> = racers
+            .iter()
+            .enumerate()
+            .map(|(idx, (provider, attempt))| async move {
+                let mut output_clamp = None;
+                let mut phases = Vec::new();
+                let result = self
+                    .execute_single_attempt(
+                        attempt,
+                        &attempt.model,
+                        *provider,
+                        req,
+                        request_span,
+                        detail_level,
+                        max_body_bytes,
+                        attempts_before + idx as u32 + 1,
+                        &mut output_clamp,
+                        &mut phases,
+                    )
+                    .await;
+                let result = match result {
+                    Ok(response) if req.stream => wait_for_first_chunk(response).await,
+                    other => other,
+                };
+                self.trace_attempt_result(req, attempt, attempts_before + idx as u32 + 1, &result);
+                (idx, result, output_clamp, phases)
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        while let Some((idx, result, output_clamp, phases)) = pending.next().await {
+            match result {
+                Ok(mut response) => {
+                    if req.debug {
+                        insert_attempt_debug_headers(&mut response, output_clamp.as_ref(), &phases);
+                    }
+                    return Ok((idx, response, errors));
+                }
+                Err(err) => errors.push((idx, err)),
+            }
+        }
+        Err(errors)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -142,6 +329,8 @@ impl<'a> ExecutionController<'a> {
         detail_level: LogDetailLevel,
         max_body_bytes: usize,
         attempt_number: u32,
+        output_clamp: &mut Option<OutputTokenClamp>,
+        phases: &mut Vec<(DispatchPhase, Duration)>,
     ) -> Result<Response, ProxyError> {
         let config = self.state.config.load();
         let start = Instant::now();
@@ -155,26 +344,27 @@ impl<'a> ExecutionController<'a> {
                 provider: target_format.as_str().to_string(),
                 model: attempt.model.clone(),
             })?;
+        check_server_tools(&req.body, req.source_format, target_format, &auth)?;
+        let auth_start = Instant::now();
         self.state
             .auth_runtime
             .prepare_auth(self.state, &auth)
             .await?;
+        let auth_ms = auth_start.elapsed().as_millis() as u64;
 
         let actual_model = auth.resolve_model_id(&attempt.model);
         let auth_secret = auth.current_secret();
 
-        let executor = self
-            .state
-            .executors
-            .get_by_upstream(auth.upstream)
-            .ok_or_else(|| {
-                ProxyError::Internal(format!(
-                    "no executor for upstream {}",
-                    auth.upstream.as_str()
-                ))
-            })?;
+        let executor = self.state.executors.for_credential(&auth).ok_or_else(|| {
+            ProxyError::Internal(format!("no executor named '{}'", auth.executor_name()))
+        })?;
 
         let attempt_start = Instant::now();
+        if let Some(ref request_id) = req.request_id {
+            self.state
+                .inflight
+                .set_target(request_id, Some(&auth.provider_name), &actual_model);
+        }
 
         // Create attempt span
         let attempt_span = tracing::info_span!(
@@ -188,6 +378,9 @@ impl<'a> ExecutionController<'a> {
             latency_ms = tracing::field::Empty,
             error = tracing::field::Empty,
             error_type = tracing::field::Empty,
+            auth_ms,
+            translate_ms = tracing::field::Empty,
+            upstream_ttfb_ms = tracing::field::Empty,
         );
 
         // Record metrics
@@ -203,6 +396,7 @@ impl<'a> ExecutionController<'a> {
         };
 
         // Translate request
+        let translate_start = Instant::now();
         let translated_payload = self.state.translators.translate_request(
             req.source_format,
             target_format,
@@ -215,6 +409,56 @@ impl<'a> ExecutionController<'a> {
         let mut payload_value: serde_json::Value =
             serde_json::from_slice(&translated_payload).unwrap_or(serde_json::Value::Null);
 
+        let fetch_remote =
+            config.vision.fetch_remote && matches!(target_format, Format::Claude | Format::Gemini);
+        let downscale = config.vision.downscale && payload_value.is_object();
+        if fetch_remote || downscale {
+            let mut images = self.images.lock().await;
+
+            // Inline remote images for upstreams that only accept inline image data
+            if fetch_remote {
+                let network = self.state.http_client_pool.network();
+                let proxy_url = prism_core::proxy::resolve_proxy_url(
+                    auth.proxy_url.as_deref(),
+                    network.proxy_url.as_deref(),
+                );
+                let inlined = super::image_fetch::inline_remote_images(
+                    &config.vision,
+                    proxy_url,
+                    &mut payload_value,
+                    &mut images,
+                )
+                .await;
+                if inlined > 0 {
+                    tracing::debug!(inlined, "Inlined remote request images");
+                }
+            }
+
+            // Downscale oversized inline images (CPU-bound, off the async workers)
+            if downscale {
+                let limits = prism_translator::content::ImageLimits {
+                    max_dimension: config.vision.max_dimension,
+                    max_bytes: config.vision.max_image_bytes,
+                    max_pixels: config.vision.max_source_pixels,
+                };
+                let mut memo = std::mem::take(&mut images.downscaled);
+                (payload_value, memo) = tokio::task::spawn_blocking(move || {
+                    let rewritten = prism_translator::content::downscale_images_memoized(
+                        &mut payload_value,
+                        &limits,
+                        &mut memo,
+                    );
+                    if rewritten > 0 {
+                        tracing::debug!(rewritten, "Downscaled oversized request images");
+                    }
+                    (payload_value, memo)
+                })
+                .await
+                .map_err(|e| ProxyError::Internal(format!("image processing failed: {e}")))?;
+                images.downscaled = memo;
+            }
+        }
+
         // Apply payload manipulation rules
         if payload_value.is_object() {
             prism_core::payload::apply_payload_rules(
@@ -223,6 +467,20 @@ impl<'a> ExecutionController<'a> {
                 &actual_model,
                 Some(target_format.as_str()),
             );
+            *output_clamp = prism_core::payload::clamp_output_tokens(
+                &mut payload_value,
+                &config.payload.max_output_tokens,
+                &actual_model,
+                Some(target_format.as_str()),
+            );
+            if let Some(ref clamp) = *output_clamp {
+                tracing::debug!(
+                    field = clamp.field,
+                    requested = clamp.requested,
+                    applied = clamp.applied,
+                    "Adjusted output token limit"
+                );
+            }
         }
 
         // Apply upstream presentation (unified headers + body mutations)
@@ -277,12 +535,48 @@ impl<'a> ExecutionController<'a> {
             );
         }
 
+        let translate_elapsed = translate_start.elapsed();
+        self.state
+            .metrics
+            .record_phase(DispatchPhase::Translate, translate_elapsed);
+        phases.push((DispatchPhase::Translate, translate_elapsed));
+        attempt_span.record("translate_ms", translate_elapsed.as_millis() as u64);
+
+        let mut upstream_headers = presentation_result.headers;
+        insert_trace_header(&mut upstream_headers, &config, req.trace.as_ref());
+        // Beta flags only mean something to Anthropic; other targets get a translated
+        // body without the beta-only fields.
+        if target_format == Format::Claude {
+            forward_anthropic_betas(&mut upstream_headers, &req.anthropic_betas, &auth);
+        } else if !req.anthropic_betas.is_empty() {
+            tracing::debug!(
+                target = target_format.as_str(),
+                betas = ?req.anthropic_betas,
+                "Dropping anthropic-beta flags for non-Claude target"
+            );
+        }
+        apply_outbound_headers(&mut upstream_headers, &config, &req.forwarded_headers);
+        super::trace_event(self.state, req, "upstream_request", || {
+            serde_json::json!({
+                "attempt": attempt_number,
+                "provider": auth.provider_name,
+                "credential": auth.name(),
+                "model": actual_model,
+                "format": target_format.as_str(),
+                "headers": crate::debug_trace::redact_headers(&upstream_headers),
+                "body": crate::debug_trace::redact_body(
+                    &final_payload,
+                    config.request_debug.max_body_bytes,
+                ),
+            })
+        });
+
         let provider_request = ProviderRequest {
             model: actual_model.clone(),
             payload: Bytes::from(final_payload),
             source_format: req.source_format,
             stream: req.stream,
-            headers: presentation_result.headers,
+            headers: upstream_headers,
             original_request: Some(body.clone()),
             responses_passthrough: req.responses_passthrough,
         };
@@ -296,10 +590,17 @@ impl<'a> ExecutionController<'a> {
 
         if req.stream {
             // ── Streaming path ──
-            match executor.execute_stream(&auth, provider_request).await {
+            let upstream_start = Instant::now();
+            let result = executor.execute_stream(&auth, provider_request).await;
+            self.record_upstream_connect(&attempt_span, upstream_start.elapsed(), phases);
+            match result {
                 Ok(stream_result) => {
                     let latency_ms = start.elapsed().as_millis();
-                    self.state.metrics.record_latency_ms(latency_ms);
+                    self.state.metrics.record_target_latency_ms(
+                        &actual_model,
+                        &auth.provider_name,
+                        latency_ms,
+                    );
                     self.state.router.record_success(&auth.id);
                     self.state
                         .router
@@ -326,16 +627,36 @@ impl<'a> ExecutionController<'a> {
                         .has_response_translator(req.source_format, target_format);
 
                     let keepalive = config.streaming.keepalive_seconds;
+                    let stream_limit = config.streaming.limit_for(&actual_model).cloned();
+                    let coalesce = config
+                        .streaming
+                        .coalesce_for(&req.request_path, &actual_model)
+                        .cloned();
+                    let buffer = config.streaming.buffer.clone();
+                    let metrics = self.state.metrics.clone();
+                    let finish_response = |resp: Response| {
+                        crate::streaming::buffer_response(
+                            crate::streaming::coalesce_response(resp, coalesce.as_ref()),
+                            &buffer,
+                            metrics.clone(),
+                        )
+                    };
+                    let upstream_headers = stream_result.headers;
 
                     let captured_stream = with_usage_capture(
                         stream_result.stream,
                         StreamDoneContext {
                             model: Some(debug_model.clone()),
+                            provider: auth.provider_name.clone(),
                             cost_calculator: self.state.cost_calculator.clone(),
                             metrics: self.state.metrics.clone(),
                             rate_limiter: self.state.rate_limiter.clone(),
+                            router: self.state.router.clone(),
+                            credential_name: auth.credential_name.clone(),
                             api_key: req.api_key.clone(),
                             tenant_id: req.tenant_id.clone(),
+                            experiment: req.experiment.clone(),
+                            upstream_started_at: upstream_start,
                         },
                         request_span.clone(),
                         detail_level,
@@ -343,40 +664,85 @@ impl<'a> ExecutionController<'a> {
                     );
 
                     if !need_translate {
+                        // Claude chunks keep their event type and go out unparsed.
                         if req.source_format == Format::Claude {
-                            let data_stream =
-                                tokio_stream::StreamExt::map(captured_stream, |result| {
-                                    result.map(|chunk| {
-                                        if let Some(ref event_type) = chunk.event_type {
-                                            format!("event: {event_type}\ndata: {}", chunk.data)
-                                        } else {
-                                            chunk.data
-                                        }
-                                    })
-                                });
-                            let resp = crate::streaming::build_sse_response(data_stream, keepalive)
-                                .into_response();
+                            let data_stream = with_stream_limit(
+                                captured_stream,
+                                stream_limit,
+                                req.source_format,
+                                request_span.clone(),
+                            );
+                            let mut resp = finish_response(crate::streaming::build_sse_response(
+                                data_stream,
+                                keepalive,
+                            ));
+                            insert_passthrough_headers(
+                                &mut resp,
+                                &config.passthrough_headers,
+                                &upstream_headers,
+                            );
                             return Ok(resp);
                         }
-                        let data_stream = tokio_stream::StreamExt::map(captured_stream, |result| {
-                            result.map(|chunk| chunk.data)
-                        });
-                        let resp = crate::streaming::build_sse_response(data_stream, keepalive)
-                            .into_response();
+                        // Usage is requested upstream for accounting; clients that did
+                        // not ask for it themselves do not get the usage-only chunk.
+                        let drop_usage_chunk = req.source_format == Format::OpenAI
+                            && !prism_translator::common::stream_usage_requested(&body);
+                        let data_stream =
+                            tokio_stream::StreamExt::filter_map(captured_stream, move |result| {
+                                match result {
+                                    Ok(chunk)
+                                        if drop_usage_chunk
+                                            && std::str::from_utf8(&chunk.data)
+                                                .is_ok_and(is_usage_only_chunk) =>
+                                    {
+                                        None
+                                    }
+                                    other => Some(other),
+                                }
+                            });
+                        let data_stream = with_stream_limit(
+                            data_stream,
+                            stream_limit,
+                            req.source_format,
+                            request_span.clone(),
+                        );
+                        let mut resp = finish_response(crate::streaming::build_sse_response(
+                            data_stream,
+                            keepalive,
+                        ));
+                        insert_passthrough_headers(
+                            &mut resp,
+                            &config.passthrough_headers,
+                            &upstream_headers,
+                        );
                         return Ok(resp);
                     }
 
                     let translated_stream = translate_stream(
                         captured_stream,
                         self.state.translators.clone(),
+                        self.state.metrics.clone(),
                         req.source_format,
                         target_format,
                         actual_model.clone(),
                         body.clone(),
                     );
+                    let translated_stream = with_stream_limit(
+                        translated_stream,
+                        stream_limit,
+                        req.source_format,
+                        request_span.clone(),
+                    );
 
-                    let resp = crate::streaming::build_sse_response(translated_stream, keepalive)
-                        .into_response();
+                    let mut resp = finish_response(crate::streaming::build_sse_response(
+                        translated_stream,
+                        keepalive,
+                    ));
+                    insert_passthrough_headers(
+                        &mut resp,
+                        &config.passthrough_headers,
+                        &upstream_headers,
+                    );
                     Ok(resp)
                 }
                 Err(e) => {
@@ -394,23 +760,35 @@ impl<'a> ExecutionController<'a> {
             }
         } else if keepalive_secs > 0 {
             // ── Non-stream with keepalive ──
-            let (result_tx, result_rx) =
+            let (mut result_tx, result_rx) =
                 tokio::sync::oneshot::channel::<Result<ProviderResponse, ProxyError>>();
             let exec = executor.clone();
             let auth_clone = auth.clone();
+            let upstream_start = Instant::now();
             tokio::spawn(async move {
-                let result = exec.execute(&auth_clone, provider_request).await;
-                let _ = result_tx.send(result);
+                // Abandon the upstream call once the keepalive body is dropped
+                // (client disconnect or dashboard cancellation).
+                tokio::select! {
+                    result = exec.execute(&auth_clone, provider_request) => {
+                        let _ = result_tx.send(result);
+                    }
+                    () = result_tx.closed() => {}
+                }
             });
 
             let mut result_rx = Box::pin(result_rx);
 
             tokio::select! {
                 result = &mut result_rx => {
+                    self.record_upstream_connect(&attempt_span, upstream_start.elapsed(), phases);
                     match result {
                         Ok(Ok(response)) => {
                             let latency_ms = start.elapsed().as_millis();
-                            self.state.metrics.record_latency_ms(latency_ms);
+                            self.state.metrics.record_target_latency_ms(
+                                &actual_model,
+                                &auth.provider_name,
+                                latency_ms,
+                            );
                             self.state.router.record_success(&auth.id);
                             self.state.router.record_latency(&auth.id, latency_ms as f64);
 
@@ -477,7 +855,11 @@ impl<'a> ExecutionController<'a> {
                     tracing::debug!(
                         "Non-stream request exceeded {keepalive_secs}s, enabling keepalive"
                     );
-                    self.state.metrics.record_latency_ms(start.elapsed().as_millis());
+                    self.state.metrics.record_target_latency_ms(
+                        &actual_model,
+                        &auth.provider_name,
+                        start.elapsed().as_millis(),
+                    );
 
                     request_span.record("provider", debug_provider.as_str());
                     request_span.record("model", debug_model.as_str());
@@ -502,10 +884,17 @@ impl<'a> ExecutionController<'a> {
             }
         } else {
             // ── Non-stream standard path ──
-            match executor.execute(&auth, provider_request).await {
+            let upstream_start = Instant::now();
+            let result = executor.execute(&auth, provider_request).await;
+            self.record_upstream_connect(&attempt_span, upstream_start.elapsed(), phases);
+            match result {
                 Ok(response) => {
                     let latency_ms = start.elapsed().as_millis();
-                    self.state.metrics.record_latency_ms(latency_ms);
+                    self.state.metrics.record_target_latency_ms(
+                        &actual_model,
+                        &auth.provider_name,
+                        latency_ms,
+                    );
                     self.state.router.record_success(&auth.id);
                     self.state
                         .router
@@ -575,6 +964,55 @@ impl<'a> ExecutionController<'a> {
         }
     }
 
+    /// Record time to the upstream response on the attempt span, metrics, and debug phases.
+    fn record_upstream_connect(
+        &self,
+        attempt_span: &tracing::Span,
+        elapsed: Duration,
+        phases: &mut Vec<(DispatchPhase, Duration)>,
+    ) {
+        self.state
+            .metrics
+            .record_phase(DispatchPhase::UpstreamConnect, elapsed);
+        phases.push((DispatchPhase::UpstreamConnect, elapsed));
+        attempt_span.record("upstream_ttfb_ms", elapsed.as_millis() as u64);
+    }
+
+    /// Upstream status of one attempt, for `x-debug: trace`.
+    fn trace_attempt_result(
+        &self,
+        req: &DispatchRequest,
+        attempt: &RouteAttemptPlan,
+        attempt_number: u32,
+        result: &Result<Response, ProxyError>,
+    ) {
+        super::trace_event(self.state, req, "upstream_result", || {
+            let mut data = serde_json::json!({
+                "attempt": attempt_number,
+                "provider": self.state.router.provider_name(&attempt.credential_id),
+                "credential": attempt.credential_name,
+                "model": attempt.model,
+            });
+            match result {
+                Ok(response) => data["status"] = response.status().as_u16().into(),
+                Err(err) => {
+                    data["status"] = err.status_code_u16().into();
+                    data["error"] = err.to_string().into();
+                }
+            }
+            data
+        });
+    }
+
+    /// Count an attempt in the per-provider attempt metrics.
+    fn record_attempt_outcome(&self, attempt: &RouteAttemptPlan, retry: bool, failed: bool) {
+        if let Some(provider) = self.state.router.provider_name(&attempt.credential_id) {
+            self.state
+                .metrics
+                .record_upstream_attempt(&provider, retry, failed);
+        }
+    }
+
     fn handle_attempt_error(&self, auth_id: &str, error: &ProxyError) {
         self.state.metrics.record_error();
         match error {
@@ -608,6 +1046,24 @@ impl<'a> ExecutionController<'a> {
         }
     }
 
+    /// Record the tags of the provider entry behind `credential_id` on the request span
+    /// and keep them for the per-tag metrics.
+    fn record_provider_tags(&self, request_span: &tracing::Span, credential_id: &str) {
+        let config = self.state.config.load();
+        let tags = self
+            .state
+            .router
+            .provider_name(credential_id)
+            .and_then(|name| config.providers.iter().find(|e| e.name == name))
+            .map(|entry| entry.tags.clone())
+            .unwrap_or_default();
+        // An empty value clears tags left by an earlier failed attempt.
+        request_span.record("provider_tags", tags.join(",").as_str());
+        if let Ok(mut current) = self.provider_tags.lock() {
+            *current = tags;
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn record_non_stream_success(
         &self,
@@ -629,6 +1085,15 @@ impl<'a> ExecutionController<'a> {
         if let Some(ref tenant_id) = req.tenant_id {
             self.state.metrics.record_tenant_request(tenant_id);
         }
+        if let Some(name) = credential_name {
+            let tokens = usage
+                .as_ref()
+                .map(|u| u.total_input() + u.output_tokens)
+                .unwrap_or(0);
+            self.state
+                .router
+                .record_usage(name, tokens, cost.unwrap_or(0.0));
+        }
 
         if let Some(ref u) = usage {
             self.state
@@ -643,6 +1108,14 @@ impl<'a> ExecutionController<'a> {
                 .rate_limiter
                 .record_tokens(req.api_key.as_deref(), u.total_input() + u.output_tokens);
         }
+        if let (Some(experiment), Some(u)) = (&req.experiment, &usage) {
+            self.state.metrics.record_experiment_usage(
+                &experiment.name,
+                experiment.arm.as_str(),
+                u.total_input() + u.output_tokens,
+                cost,
+            );
+        }
         if let Some(c) = cost {
             self.state.metrics.record_cost(model, c);
             if let Some(ref tenant_id) = req.tenant_id {
@@ -691,6 +1164,172 @@ impl<'a> ExecutionController<'a> {
     }
 }
 
+/// Failures across a dispatch's attempts, used to shape the error returned
+/// once every attempt has failed.
+#[derive(Default)]
+struct FailureSummary<'a> {
+    /// Credentials that answered with a rate limit.
+    rate_limited: Vec<&'a str>,
+    /// Copy of the most recent rate-limit error.
+    last_rate_limit: Option<ProxyError>,
+    /// Whether any attempt failed with a client error other than 429.
+    client_error: bool,
+}
+
+impl<'a> FailureSummary<'a> {
+    fn record(&mut self, credential_id: &'a str, err: &ProxyError) {
+        let copy = match err {
+            ProxyError::Upstream {
+                status: 429,
+                body,
+                retry_after_secs,
+                headers,
+            } => ProxyError::Upstream {
+                status: 429,
+                body: body.clone(),
+                retry_after_secs: *retry_after_secs,
+                headers: headers.clone(),
+            },
+            ProxyError::RateLimited {
+                message,
+                retry_after_secs,
+            } => ProxyError::RateLimited {
+                message: message.clone(),
+                retry_after_secs: *retry_after_secs,
+            },
+            other => {
+                self.client_error |= (400..500).contains(&other.status_code_u16());
+                return;
+            }
+        };
+        self.rate_limited.push(credential_id);
+        self.last_rate_limit = Some(copy);
+    }
+
+    /// When attempts failed only on rate limits and server-side errors, answer
+    /// 429 with a `Retry-After` taken from the earliest credential cooldown
+    /// expiry, so clients back off instead of seeing a 5xx.
+    fn into_error(self, last_error: ProxyError, router: &CredentialRouter) -> ProxyError {
+        if self.client_error {
+            return last_error;
+        }
+        let Some(rate_limit) = self.last_rate_limit else {
+            return last_error;
+        };
+        let earliest = self
+            .rate_limited
+            .iter()
+            .filter_map(|id| router.cooldown_remaining(id))
+            .min();
+        let computed = earliest.map(|d| d.as_secs_f64().ceil().max(1.0) as u64);
+        match rate_limit {
+            ProxyError::Upstream {
+                body,
+                retry_after_secs,
+                headers,
+                ..
+            } => ProxyError::Upstream {
+                status: 429,
+                body,
+                retry_after_secs: computed.or(retry_after_secs).or(Some(1)),
+                headers,
+            },
+            ProxyError::RateLimited {
+                message,
+                retry_after_secs,
+            } => ProxyError::RateLimited {
+                message,
+                retry_after_secs: computed.unwrap_or(retry_after_secs),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Debug headers for the winning attempt: output-token clamp and phase timings.
+fn insert_attempt_debug_headers(
+    response: &mut Response,
+    clamp: Option<&OutputTokenClamp>,
+    phases: &[(DispatchPhase, Duration)],
+) {
+    if let Some(value) = clamp.and_then(|clamp| clamp.header_value().parse().ok()) {
+        response
+            .headers_mut()
+            .insert("x-prism-output-tokens", value);
+    }
+    for &(phase, elapsed) in phases {
+        append_timing_header(response, phase, elapsed);
+    }
+}
+
+/// Wait for the first body chunk of a streaming response, then hand back a response
+/// that replays it ahead of the rest of the stream.
+async fn wait_for_first_chunk(response: Response) -> Result<Response, ProxyError> {
+    let (parts, body) = response.into_parts();
+    let mut data = body.into_data_stream();
+    let first = match data.next().await {
+        Some(Ok(chunk)) => chunk,
+        Some(Err(e)) => return Err(ProxyError::Network(e.to_string())),
+        None => {
+            return Err(ProxyError::Network(
+                "stream ended before the first chunk".to_string(),
+            ));
+        }
+    };
+    let replay = futures::stream::once(async move { Ok::<_, axum::Error>(first) }).chain(data);
+    Ok(Response::from_parts(
+        parts,
+        axum::body::Body::from_stream(replay),
+    ))
+}
+
+/// Pick up to `fanout` attempts for race dispatch from the first model: the first
+/// attempt of each provider, then further credentials in plan order.
+fn select_race_attempts<'a>(
+    model_groups: &ModelProviderGroups<'a>,
+    fanout: usize,
+) -> Vec<(Format, &'a RouteAttemptPlan)> {
+    let Some((_, provider_groups)) = model_groups.first() else {
+        return Vec::new();
+    };
+    let mut selected: Vec<(Format, &RouteAttemptPlan)> = provider_groups
+        .iter()
+        .filter_map(|(provider, attempts)| attempts.first().map(|a| (*provider, *a)))
+        .take(fanout)
+        .collect();
+    for (provider, attempts) in provider_groups {
+        for attempt in attempts.iter().skip(1) {
+            if selected.len() >= fanout {
+                return selected;
+            }
+            selected.push((*provider, *attempt));
+        }
+    }
+    selected
+}
+
+/// Model a failed racer falls back to: the winner's, or that of the first attempt
+/// run in order after the race.
+fn race_fallback_model<'a>(
+    racers: &[(Format, &'a RouteAttemptPlan)],
+    winner: Option<usize>,
+    model_groups: &ModelProviderGroups<'a>,
+) -> Option<&'a str> {
+    if let Some(idx) = winner {
+        return Some(racers[idx].1.model.as_str());
+    }
+    model_groups
+        .iter()
+        .flat_map(|(_, provider_groups)| provider_groups)
+        .flat_map(|(_, attempts)| attempts)
+        .find(|attempt| {
+            !racers
+                .iter()
+                .any(|(_, raced)| std::ptr::eq(*raced, **attempt))
+        })
+        .map(|attempt| attempt.model.as_str())
+}
+
 type ModelProviderGroups<'a> = Vec<(String, Vec<(Format, Vec<&'a RouteAttemptPlan>)>)>;
 
 /// Group attempts by model, then by provider within each model.
@@ -726,3 +1365,86 @@ fn group_attempts_by_model<'a>(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(model: &str, provider: Format, credential: &str) -> RouteAttemptPlan {
+        RouteAttemptPlan {
+            model: model.to_string(),
+            provider,
+            credential_id: credential.to_string(),
+            credential_name: credential.to_string(),
+            rank: 0,
+            score: Default::default(),
+            execution_mode: None,
+            upstream_protocol: None,
+        }
+    }
+
+    #[test]
+    fn test_select_race_attempts_prefers_distinct_providers() {
+        let attempts = vec![
+            attempt("m", Format::OpenAI, "openai-a"),
+            attempt("m", Format::OpenAI, "openai-b"),
+            attempt("m", Format::Claude, "claude-a"),
+            attempt("fallback", Format::Gemini, "gemini-a"),
+        ];
+        let chain = vec!["m".to_string(), "fallback".to_string()];
+        let groups = group_attempts_by_model(&chain, &attempts);
+
+        let names = |fanout| {
+            select_race_attempts(&groups, fanout)
+                .into_iter()
+                .map(|(_, a)| a.credential_name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(2), ["openai-a", "claude-a"]);
+        assert_eq!(names(5), ["openai-a", "claude-a", "openai-b"]);
+    }
+
+    #[test]
+    fn test_race_fallback_model_targets_winner_then_next_attempt() {
+        let attempts = vec![
+            attempt("m", Format::OpenAI, "openai-a"),
+            attempt("m", Format::Claude, "claude-a"),
+            attempt("fallback", Format::Gemini, "gemini-a"),
+        ];
+        let chain = vec!["m".to_string(), "fallback".to_string()];
+        let groups = group_attempts_by_model(&chain, &attempts);
+        let racers = vec![
+            (Format::OpenAI, &attempts[0]),
+            (Format::Claude, &attempts[2]),
+        ];
+
+        assert_eq!(
+            race_fallback_model(&racers, Some(1), &groups),
+            Some("fallback")
+        );
+        assert_eq!(race_fallback_model(&racers, None, &groups), Some("m"));
+        let all = vec![
+            (Format::OpenAI, &attempts[0]),
+            (Format::Claude, &attempts[1]),
+            (Format::Gemini, &attempts[2]),
+        ];
+        assert_eq!(race_fallback_model(&all, None, &groups), None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_first_chunk_replays_body() {
+        let chunks = futures::stream::iter(vec![
+            Ok::<_, std::io::Error>("data: one\n\n"),
+            Ok("data: two\n\n"),
+        ]);
+        let response = axum::body::Body::from_stream(chunks).into_response();
+        let response = wait_for_first_chunk(response).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"data: one\n\ndata: two\n\n");
+
+        let empty = axum::body::Body::empty().into_response();
+        assert!(wait_for_first_chunk(empty).await.is_err());
+    }
+}
@@ -19,7 +19,11 @@ pub(super) fn extract_features(req: &DispatchRequest) -> RouteRequestFeatures {
         api_key_id: req.api_key_id.clone(),
         region: req.client_region.clone(),
         stream: req.stream,
-        headers: BTreeMap::new(),
+        headers: req
+            .routing_headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<BTreeMap<_, _>>(),
         allowed_credentials: req.allowed_credentials.clone(),
         required_capabilities: None,
     }
@@ -41,13 +45,19 @@ mod tests {
             allowed_formats: None,
             user_agent: None,
             debug: false,
+            debug_trace: false,
             api_key: None,
             client_region: None,
             request_id: None,
+            trace: None,
+            forwarded_headers: std::collections::HashMap::new(),
+            routing_headers: std::collections::HashMap::new(),
+            anthropic_betas: Vec::new(),
             api_key_id: None,
             tenant_id: None,
             allowed_credentials: Vec::new(),
             responses_passthrough: false,
+            experiment: None,
         }
     }
 
@@ -1,7 +1,12 @@
+use axum::http::{HeaderName, HeaderValue};
 use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
+use prism_core::config::PASSTHROUGH_RATE_LIMITS;
 use prism_core::error::ProxyError;
+use prism_core::metrics::DispatchPhase;
+use prism_core::provider::{AuthRecord, Format};
 use prism_core::request_record::TokenUsage;
+use std::collections::HashMap;
 
 /// Extract token usage from a response payload (any format), including cache tokens.
 pub(super) fn extract_usage(payload: &str) -> Option<TokenUsage> {
@@ -16,7 +21,14 @@ pub(super) fn extract_usage(payload: &str) -> Option<TokenUsage> {
     let usage_obj = val
         .get("usage")
         .or_else(|| val.get("message").and_then(|m| m.get("usage")));
+    usage_from_fields(usage_obj, val.get("usageMetadata"))
+}
 
+/// Build usage from an OpenAI/Claude `usage` object or a Gemini `usageMetadata` object.
+pub(super) fn usage_from_fields(
+    usage_obj: Option<&serde_json::Value>,
+    usage_metadata: Option<&serde_json::Value>,
+) -> Option<TokenUsage> {
     // OpenAI format: usage.prompt_tokens / usage.completion_tokens
     if let Some(usage) = usage_obj {
         let input = usage
@@ -58,7 +70,7 @@ pub(super) fn extract_usage(payload: &str) -> Option<TokenUsage> {
     }
 
     // Gemini format: usageMetadata
-    if let Some(usage) = val.get("usageMetadata") {
+    if let Some(usage) = usage_metadata {
         let input = usage.get("promptTokenCount").and_then(|v| v.as_u64());
         let output = usage.get("candidatesTokenCount").and_then(|v| v.as_u64());
 
@@ -66,13 +78,14 @@ pub(super) fn extract_usage(payload: &str) -> Option<TokenUsage> {
             return None;
         }
 
+        // `promptTokenCount` includes the cached tokens; bill those at the cache rate.
         let cache_read = usage
             .get("cachedContentTokenCount")
             .and_then(|v| v.as_u64())
             .unwrap_or(0);
 
         return Some(TokenUsage {
-            input_tokens: input.unwrap_or(0),
+            input_tokens: input.unwrap_or(0).saturating_sub(cache_read),
             output_tokens: output.unwrap_or(0),
             cache_read_tokens: cache_read,
             cache_creation_tokens: 0,
@@ -86,21 +99,101 @@ pub(super) fn extract_usage(payload: &str) -> Option<TokenUsage> {
 pub(super) fn build_json_response(
     translated: &str,
     passthrough_headers: &[String],
-    upstream_headers: &std::collections::HashMap<String, String>,
+    upstream_headers: &HashMap<String, String>,
 ) -> Result<Response, ProxyError> {
-    let mut builder = axum::http::Response::builder()
-        .header(axum::http::header::CONTENT_TYPE, "application/json");
+    let mut response = axum::http::Response::builder()
+        .header(axum::http::header::CONTENT_TYPE, "application/json")
+        .body(axum::body::Body::from(translated.to_string()))
+        .map_err(|e| ProxyError::Internal(format!("failed to build response: {e}")))?
+        .into_response();
+    insert_passthrough_headers(&mut response, passthrough_headers, upstream_headers);
+    Ok(response)
+}
+
+/// Copy `passthrough-headers` from the upstream response onto the client response.
+pub(super) fn insert_passthrough_headers(
+    response: &mut Response,
+    passthrough_headers: &[String],
+    upstream_headers: &HashMap<String, String>,
+) {
+    for (name, value) in passthrough_header_values(passthrough_headers, upstream_headers) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+}
 
+/// The `passthrough-headers` present in the upstream headers.
+/// The `rate-limits` entry expands to [`normalized_rate_limit_headers`].
+pub(super) fn passthrough_header_values(
+    passthrough_headers: &[String],
+    upstream_headers: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut out = Vec::new();
     for header_name in passthrough_headers {
-        if let Some(val) = upstream_headers.get(header_name) {
-            builder = builder.header(header_name.as_str(), val.as_str());
+        if header_name == PASSTHROUGH_RATE_LIMITS {
+            out.extend(normalized_rate_limit_headers(upstream_headers));
+        } else if let Some(val) = upstream_headers.get(header_name) {
+            out.push((header_name.clone(), val.clone()));
         }
     }
+    out
+}
 
-    builder
-        .body(axum::body::Body::from(translated.to_string()))
-        .map_err(|e| ProxyError::Internal(format!("failed to build response: {e}")))
-        .map(IntoResponse::into_response)
+/// Keep only the `passthrough-headers` on an upstream error, so the error
+/// response forwards them (rate limits on a 429 in particular) and nothing else.
+pub(super) fn narrow_error_headers(error: &mut ProxyError, passthrough_headers: &[String]) {
+    if let ProxyError::Upstream { headers, .. } = error {
+        *headers = passthrough_header_values(passthrough_headers, headers)
+            .into_iter()
+            .collect();
+    }
+}
+
+/// Map provider rate-limit headers onto the OpenAI `x-ratelimit-*` set that
+/// client SDKs understand, plus `retry-after` / `retry-after-ms`.
+///
+/// OpenAI-style headers pass through unchanged. Anthropic's
+/// `anthropic-ratelimit-{requests,tokens}-{limit,remaining,reset}` fill the
+/// same names, with the RFC 3339 reset time turned into a `<n>s` duration.
+pub(super) fn normalized_rate_limit_headers(
+    upstream_headers: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    for kind in ["requests", "tokens"] {
+        for field in ["limit", "remaining", "reset"] {
+            let name = format!("x-ratelimit-{field}-{kind}");
+            let value = upstream_headers.get(&name).cloned().or_else(|| {
+                let value = upstream_headers.get(&format!("anthropic-ratelimit-{kind}-{field}"))?;
+                Some(if field == "reset" {
+                    reset_as_duration(value).unwrap_or_else(|| value.clone())
+                } else {
+                    value.clone()
+                })
+            });
+            if let Some(value) = value {
+                out.push((name, value));
+            }
+        }
+    }
+    for name in ["retry-after", "retry-after-ms"] {
+        if let Some(value) = upstream_headers.get(name) {
+            out.push((name.to_string(), value.clone()));
+        }
+    }
+    out
+}
+
+/// Seconds from now until an RFC 3339 reset timestamp, formatted like OpenAI's `6s`.
+fn reset_as_duration(timestamp: &str) -> Option<String> {
+    let reset = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let secs = (reset.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .num_seconds()
+        .max(0);
+    Some(format!("{secs}s"))
 }
 
 /// Inject route debug headers into a response (x-prism-route-* format).
@@ -141,6 +234,22 @@ pub(super) fn inject_route_headers(
     );
 }
 
+/// Append a dispatch phase to the `x-prism-timing` debug header (Server-Timing syntax).
+pub(super) fn append_timing_header(
+    response: &mut Response,
+    phase: DispatchPhase,
+    elapsed: std::time::Duration,
+) {
+    let value = format!(
+        "{};dur={:.3}",
+        phase.as_str(),
+        elapsed.as_secs_f64() * 1000.0
+    );
+    if let Ok(value) = value.parse() {
+        response.headers_mut().append("x-prism-timing", value);
+    }
+}
+
 /// Inject `stream_options.include_usage = true` into an OpenAI-format streaming request
 /// payload so that the final SSE chunk includes token usage data.
 #[cfg(test)]
@@ -175,8 +284,115 @@ pub(super) fn inject_stream_usage_option_value(val: &mut serde_json::Value) {
     }
 }
 
+/// An OpenAI stream chunk that carries only usage (`choices: []`), the terminal
+/// chunk sent for `stream_options.include_usage`.
+pub(super) fn is_usage_only_chunk(data: &str) -> bool {
+    data.contains("\"usage\"")
+        && serde_json::from_str::<serde_json::Value>(data).is_ok_and(|chunk| {
+            chunk
+                .get("choices")
+                .and_then(|c| c.as_array())
+                .is_some_and(Vec::is_empty)
+                && chunk.get("usage").is_some_and(|u| !u.is_null())
+        })
+}
+
+/// Claude server tools declared in a Messages request, as `(name, type)` pairs.
+///
+/// Server tools carry a versioned `type` (e.g. `web_search_20250305`, `computer_20250124`);
+/// client tools have no type or `type: "custom"`.
+pub(super) fn claude_server_tools(body: &[u8]) -> Vec<(String, String)> {
+    let Ok(val) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return Vec::new();
+    };
+    val.get("tools")
+        .and_then(|tools| tools.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| {
+            let tool_type = tool.get("type")?.as_str()?;
+            if tool_type == "custom" {
+                return None;
+            }
+            let name = tool
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or(tool_type);
+            Some((name.to_string(), tool_type.to_string()))
+        })
+        .collect()
+}
+
+/// Reject Claude server tools the target cannot execute.
+///
+/// Server tools only pass through to Claude upstreams; other formats have no equivalent, so
+/// translating them would silently drop or turn them into client function tools.
+pub(super) fn check_server_tools(
+    body: &[u8],
+    source_format: Format,
+    target_format: Format,
+    auth: &AuthRecord,
+) -> Result<(), ProxyError> {
+    if source_format != Format::Claude {
+        return Ok(());
+    }
+    for (name, tool_type) in claude_server_tools(body) {
+        if target_format != Format::Claude {
+            return Err(ProxyError::BadRequest(format!(
+                "server tool '{tool_type}' is not supported by {} providers",
+                target_format.as_str()
+            )));
+        }
+        if !auth.allows_server_tool(&name, &tool_type) {
+            return Err(ProxyError::BadRequest(format!(
+                "server tool '{tool_type}' is not allowed for provider '{}'",
+                auth.provider_name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Add the client's `anthropic-beta` flags that this credential allows to the
+/// upstream headers, after any flags already set there.
+pub(super) fn forward_anthropic_betas(
+    headers: &mut HashMap<String, String>,
+    betas: &[String],
+    auth: &AuthRecord,
+) {
+    let (allowed, dropped): (Vec<&String>, Vec<&String>) =
+        betas.iter().partition(|beta| auth.allows_beta(beta));
+    if !dropped.is_empty() {
+        tracing::debug!(
+            credential = auth.name().unwrap_or(&auth.provider_name),
+            dropped = ?dropped,
+            "Dropping anthropic-beta flags not allowed for credential"
+        );
+    }
+    if allowed.is_empty() {
+        return;
+    }
+    let mut merged: Vec<String> = headers
+        .get("anthropic-beta")
+        .map(|existing| {
+            existing
+                .split(',')
+                .map(str::trim)
+                .filter(|b| !b.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    for beta in allowed {
+        if !merged.contains(beta) {
+            merged.push(beta.clone());
+        }
+    }
+    headers.insert("anthropic-beta".to_string(), merged.join(","));
+}
+
 /// Rewrite the `model` field in a JSON request body to use a different model name.
-pub(super) fn rewrite_model_in_body(body: &Bytes, new_model: &str) -> Bytes {
+pub(crate) fn rewrite_model_in_body(body: &Bytes, new_model: &str) -> Bytes {
     if let Ok(mut val) = serde_json::from_slice::<serde_json::Value>(body)
         && let Some(obj) = val.as_object_mut()
     {
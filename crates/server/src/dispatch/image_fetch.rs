@@ -0,0 +1,284 @@
+//! Opt-in download of remote image URLs for upstreams that only accept inline data.
+
+use futures::future::join_all;
+use prism_core::config::VisionConfig;
+use prism_translator::content::{self, DownscaleMemo, InlineImage};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+const MAX_REDIRECTS: usize = 5;
+
+/// Fetch clients by proxy URL, with the allowlist their redirect policy checks.
+type FetchClients = HashMap<Option<String>, (Vec<String>, reqwest::Client)>;
+
+static FETCH_CLIENTS: LazyLock<Mutex<FetchClients>> = LazyLock::new(Mutex::default);
+
+/// Image work done for one request, reused by its later attempts and racers.
+#[derive(Debug, Default)]
+pub(super) struct ImageMemo {
+    /// Downloaded image per remote URL.
+    fetched: HashMap<String, InlineImage>,
+    /// Remote URLs already tried without success.
+    failed: HashSet<String>,
+    /// Downscaling outcome per inline image.
+    pub downscaled: DownscaleMemo,
+}
+
+/// Fetch the remote images `payload` references and inline them in place.
+///
+/// URLs outside the allowlist, non-image responses, oversized bodies, and failed or
+/// timed-out downloads keep their remote reference. URLs already in `memo` are not
+/// downloaded again. Returns how many images were inlined.
+pub(super) async fn inline_remote_images(
+    vision: &VisionConfig,
+    proxy_url: Option<&str>,
+    payload: &mut serde_json::Value,
+    memo: &mut ImageMemo,
+) -> usize {
+    let urls: Vec<String> = content::remote_image_urls(payload)
+        .into_iter()
+        .filter(|url| {
+            !memo.fetched.contains_key(url) && !memo.failed.contains(url) && is_allowed(vision, url)
+        })
+        .collect();
+    if !urls.is_empty() {
+        match fetch_client(vision, proxy_url) {
+            Ok(client) => download(vision, &client, urls, memo).await,
+            Err(e) => tracing::warn!(error = %e, "Failed to build image fetch client"),
+        }
+    }
+    content::inline_remote_images(payload, &memo.fetched)
+}
+
+async fn download(
+    vision: &VisionConfig,
+    client: &reqwest::Client,
+    urls: Vec<String>,
+    memo: &mut ImageMemo,
+) {
+    let timeout = Duration::from_secs(vision.fetch_timeout_secs);
+    let downloads = urls.iter().map(|url| async move {
+        let result = tokio::time::timeout(timeout, fetch(client, url, vision.fetch_max_bytes))
+            .await
+            .unwrap_or_else(|_| Err("timed out".to_string()));
+        if let Err(reason) = &result {
+            tracing::warn!(url = url.as_str(), reason, "Remote image not inlined");
+        }
+        (url, result)
+    });
+    for (url, result) in join_all(downloads).await {
+        match result {
+            Ok(image) => {
+                memo.fetched.insert(url.clone(), image);
+            }
+            Err(_) => {
+                memo.failed.insert(url.clone());
+            }
+        }
+    }
+}
+
+/// Client for image downloads, shared while the allowlist stays the same. Unlike
+/// the shared pool clients it checks every redirect hop against the allowlist,
+/// so an allowed host cannot bounce the download to an internal address.
+fn fetch_client(
+    vision: &VisionConfig,
+    proxy_url: Option<&str>,
+) -> Result<reqwest::Client, reqwest::Error> {
+    let key = proxy_url.map(str::to_string);
+    let mut clients = FETCH_CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((hosts, client)) = clients.get(&key)
+        && *hosts == vision.fetch_allowed_hosts
+    {
+        return Ok(client.clone());
+    }
+    let client = build_fetch_client(vision, proxy_url)?;
+    clients.insert(key, (vision.fetch_allowed_hosts.clone(), client.clone()));
+    Ok(client)
+}
+
+fn build_fetch_client(
+    vision: &VisionConfig,
+    proxy_url: Option<&str>,
+) -> Result<reqwest::Client, reqwest::Error> {
+    let allowlist = vision.clone();
+    let policy = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if is_allowed(&allowlist, attempt.url().as_str()) {
+            attempt.follow()
+        } else {
+            attempt.error("redirect target is not in fetch-allowed-hosts")
+        }
+    });
+    let builder = reqwest::Client::builder().redirect(policy);
+    match proxy_url {
+        Some(url) => builder.proxy(reqwest::Proxy::all(url)?),
+        None => builder.no_proxy(),
+    }
+    .build()
+}
+
+fn is_allowed(vision: &VisionConfig, url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(|host| vision.fetch_allows_host(host)))
+        .unwrap_or(false)
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    max_bytes: usize,
+) -> Result<InlineImage, String> {
+    let mut response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("status {}", response.status()));
+    }
+    let mime_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !mime_type.starts_with("image/") {
+        return Err(format!("content type '{mime_type}' is not an image"));
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err("exceeds fetch-max-bytes".to_string());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err("exceeds fetch-max-bytes".to_string());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(InlineImage::new(mime_type, &bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_allowed_matches_host_globs() {
+        let vision = VisionConfig {
+            fetch_allowed_hosts: vec!["*.example.com".to_string(), "cdn.test".to_string()],
+            ..VisionConfig::default()
+        };
+        assert!(is_allowed(&vision, "https://img.example.com/cat.png"));
+        assert!(is_allowed(&vision, "http://cdn.test/a.jpg?size=2"));
+        assert!(!is_allowed(&vision, "https://example.org/cat.png"));
+        assert!(!is_allowed(&vision, "not a url"));
+    }
+
+    #[tokio::test]
+    async fn test_inline_remote_images_fetches_allowed_images() {
+        let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new()
+            .route(
+                "/cat.png",
+                axum::routing::get(move || async move {
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    ([("content-type", "image/png")], "png-bytes")
+                }),
+            )
+            .route(
+                "/page",
+                axum::routing::get(|| async { ([("content-type", "text/html")], "<html>") }),
+            )
+            .route(
+                "/huge.png",
+                axum::routing::get(|| async { ([("content-type", "image/png")], vec![0u8; 64]) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let vision = VisionConfig {
+            fetch_remote: true,
+            fetch_allowed_hosts: vec!["127.0.0.1".to_string()],
+            fetch_max_bytes: 32,
+            ..VisionConfig::default()
+        };
+        let image = |url: String| json!({"type": "image", "source": {"type": "url", "url": url}});
+        let mut payload = json!({"messages": [{"content": [
+            image(format!("{base}/cat.png")),
+            image(format!("{base}/page")),
+            image(format!("{base}/huge.png")),
+            image("https://blocked.example/cat.png".to_string()),
+        ]}]});
+
+        let original = payload.clone();
+        let mut memo = ImageMemo::default();
+
+        let inlined = inline_remote_images(&vision, None, &mut payload, &mut memo).await;
+
+        assert_eq!(inlined, 1);
+        let content = &payload["messages"][0]["content"];
+        assert_eq!(content[0]["source"]["type"], "base64");
+        assert_eq!(content[0]["source"]["media_type"], "image/png");
+        for blocked in 1..4 {
+            assert_eq!(content[blocked]["source"]["type"], "url");
+        }
+
+        // A later attempt reuses the download instead of fetching again.
+        let mut retry = original;
+        assert_eq!(
+            inline_remote_images(&vision, None, &mut retry, &mut memo).await,
+            1
+        );
+        assert_eq!(retry, payload);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_blocked_host_is_not_followed() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = axum::Router::new()
+            .route(
+                "/internal",
+                axum::routing::get(|| async {
+                    axum::response::Redirect::temporary("http://169.254.169.254/latest/meta-data")
+                }),
+            )
+            .route(
+                "/hop",
+                axum::routing::get(move || async move {
+                    axum::response::Redirect::temporary(&format!("http://{addr}/cat.png"))
+                }),
+            )
+            .route(
+                "/cat.png",
+                axum::routing::get(|| async { ([("content-type", "image/png")], "png-bytes") }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let vision = VisionConfig {
+            fetch_remote: true,
+            fetch_allowed_hosts: vec!["127.0.0.1".to_string()],
+            fetch_timeout_secs: 2,
+            ..VisionConfig::default()
+        };
+        let image = |path: &str| json!({"type": "image", "source": {"type": "url", "url": format!("http://{addr}{path}")}});
+        let mut payload = json!({"messages": [{"content": [image("/internal"), image("/hop")]}]});
+
+        let inlined =
+            inline_remote_images(&vision, None, &mut payload, &mut ImageMemo::default()).await;
+
+        assert_eq!(inlined, 1);
+        let content = &payload["messages"][0]["content"];
+        assert_eq!(content[0]["source"]["type"], "url");
+        assert_eq!(content[1]["source"]["type"], "base64");
+    }
+}
@@ -0,0 +1,237 @@
+//! Pre-dispatch prompt moderation against an OpenAI-compatible moderation endpoint.
+
+use crate::AppState;
+use prism_core::config::{ModerationAction, ModerationConfig};
+use prism_core::error::ProxyError;
+use serde_json::Value;
+use std::time::Duration;
+
+use super::DispatchRequest;
+
+/// Keys whose string (or string array) values are prompt text.
+const TEXT_KEYS: &[&str] = &[
+    "content",
+    "text",
+    "input",
+    "prompt",
+    "instructions",
+    "system",
+];
+
+/// Keys that never carry user prompt text (tool schemas, media, generation settings).
+const SKIPPED_KEYS: &[&str] = &[
+    "tools",
+    "functions",
+    "tool_choice",
+    "response_format",
+    "metadata",
+    "generationConfig",
+    "image_url",
+    "source",
+    "inlineData",
+    "fileData",
+    "signature",
+];
+
+/// Moderate the request prompt per the global config and the caller's key policy.
+///
+/// Returns the `x-prism-moderation` header value for annotated requests, `None` when
+/// the request passes or was only flagged, and `ContentBlocked` when it is blocked.
+pub(super) async fn check(
+    state: &AppState,
+    req: &DispatchRequest,
+) -> Result<Option<String>, ProxyError> {
+    let config = state.config.load();
+    let moderation = &config.moderation;
+    if !moderation.enabled {
+        return Ok(None);
+    }
+    let action = req
+        .api_key
+        .as_deref()
+        .and_then(|key| config.auth_key_store.lookup(key))
+        .and_then(|entry| entry.moderation)
+        .unwrap_or(moderation.action);
+    if action == ModerationAction::Off {
+        return Ok(None);
+    }
+    let Ok(body) = serde_json::from_slice::<Value>(&req.body) else {
+        return Ok(None);
+    };
+    let text = prompt_text(&body);
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let categories = match call(state, moderation, &text).await {
+        Ok(categories) => categories,
+        Err(reason) => {
+            state.metrics.record_moderation_error();
+            if moderation.fail_open {
+                tracing::warn!(reason, "Moderation check failed, allowing request");
+                return Ok(None);
+            }
+            return Err(ProxyError::Network(format!(
+                "moderation check failed: {reason}"
+            )));
+        }
+    };
+    let Some(categories) = categories else {
+        state.metrics.record_moderation_check(false, false);
+        return Ok(None);
+    };
+
+    let blocked = action == ModerationAction::Block;
+    state.metrics.record_moderation_check(true, blocked);
+    let summary = if categories.is_empty() {
+        "flagged".to_string()
+    } else {
+        format!("flagged categories={}", categories.join(","))
+    };
+    tracing::warn!(
+        request_id = req.request_id.as_deref().unwrap_or("-"),
+        api_key_id = req.api_key_id.as_deref().unwrap_or(""),
+        action = ?action,
+        categories = categories.join(",").as_str(),
+        "Moderation flagged request"
+    );
+    match action {
+        ModerationAction::Block => Err(ProxyError::ContentBlocked(if categories.is_empty() {
+            "prompt was flagged".to_string()
+        } else {
+            categories.join(", ")
+        })),
+        ModerationAction::Annotate => Ok(Some(summary)),
+        ModerationAction::Flag | ModerationAction::Off => Ok(None),
+    }
+}
+
+/// Call the moderation endpoint. Returns the flagged categories, or `None` if not flagged.
+async fn call(
+    state: &AppState,
+    moderation: &ModerationConfig,
+    text: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let client = state
+        .http_client_pool
+        .get_or_create_current(None)
+        .map_err(|e| e.to_string())?;
+    let mut payload = serde_json::json!({ "input": text });
+    if let Some(ref model) = moderation.model {
+        payload["model"] = Value::String(model.clone());
+    }
+    let mut request = client
+        .post(&moderation.url)
+        .timeout(Duration::from_secs(moderation.timeout_secs))
+        .json(&payload);
+    if let Some(ref api_key) = moderation.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("status {}", response.status()));
+    }
+    let verdict: Value = response.json().await.map_err(|e| e.to_string())?;
+    parse_verdict(&verdict).ok_or_else(|| "unrecognized moderation response".to_string())
+}
+
+/// Read an OpenAI moderation response: `{"results": [{"flagged": bool, "categories": {..}}]}`.
+fn parse_verdict(verdict: &Value) -> Option<Option<Vec<String>>> {
+    let results = verdict.get("results")?.as_array()?;
+    let mut flagged = false;
+    let mut categories = Vec::new();
+    for result in results {
+        if !result.get("flagged")?.as_bool()? {
+            continue;
+        }
+        flagged = true;
+        if let Some(map) = result.get("categories").and_then(Value::as_object) {
+            for (name, hit) in map {
+                if hit.as_bool() == Some(true) && !categories.contains(name) {
+                    categories.push(name.clone());
+                }
+            }
+        }
+    }
+    Some(flagged.then_some(categories))
+}
+
+/// Collect prompt text from any supported request body shape, one segment per line.
+fn prompt_text(body: &Value) -> String {
+    let mut segments = Vec::new();
+    collect_text(body, false, &mut segments);
+    segments.join("\n")
+}
+
+fn collect_text<'a>(value: &'a Value, text_key: bool, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) if text_key && !s.is_empty() => out.push(s),
+        Value::Array(items) => {
+            for item in items {
+                collect_text(item, text_key, out);
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map {
+                if SKIPPED_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                collect_text(child, TEXT_KEYS.contains(&key.as_str()), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sorted_lines(body: &Value) -> Vec<String> {
+        let mut lines: Vec<String> = prompt_text(body).lines().map(String::from).collect();
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn test_prompt_text_covers_request_formats() {
+        let chat = json!({
+            "model": "gpt-4o",
+            "messages": [
+                {"role": "system", "content": "be nice"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "hello"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}}
+                ]}
+            ],
+            "tools": [{"type": "function", "function": {"description": "not a prompt"}}]
+        });
+        assert_eq!(sorted_lines(&chat), ["be nice", "hello"]);
+
+        let gemini = json!({
+            "systemInstruction": {"parts": [{"text": "sys"}]},
+            "contents": [{"role": "user", "parts": [{"text": "hi"}]}],
+            "generationConfig": {"temperature": 0.2}
+        });
+        assert_eq!(sorted_lines(&gemini), ["hi", "sys"]);
+
+        let responses = json!({"instructions": "rules", "input": "question"});
+        assert_eq!(sorted_lines(&responses), ["question", "rules"]);
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        let flagged = json!({"results": [{
+            "flagged": true,
+            "categories": {"hate": true, "violence": false, "harassment": true}
+        }]});
+        let mut categories = parse_verdict(&flagged).unwrap().unwrap();
+        categories.sort();
+        assert_eq!(categories, ["harassment", "hate"]);
+
+        let clean = json!({"results": [{"flagged": false, "categories": {}}]});
+        assert_eq!(parse_verdict(&clean), Some(None));
+        assert_eq!(parse_verdict(&json!({"error": "nope"})), None);
+    }
+}
@@ -0,0 +1,185 @@
+//! Shadow traffic: fire-and-forget copies of requests sent to a secondary provider.
+//!
+//! Mirrored responses never reach the client. Only latency, usage, and cost are logged,
+//! and the shadow provider's health is not recorded so evaluation cannot affect routing.
+
+use crate::AppState;
+use bytes::Bytes;
+use prism_core::config::ShadowRule;
+use prism_core::error::ProxyError;
+use prism_core::provider::ProviderRequest;
+use prism_core::request_record::TokenUsage;
+use std::time::Instant;
+
+use super::DispatchRequest;
+use super::helpers::{extract_usage, rewrite_model_in_body};
+
+/// Mirror `req` to the first matching shadow rule's provider, sampled by its percentage.
+pub(super) fn maybe_mirror(state: &AppState, req: &DispatchRequest) {
+    if req.responses_passthrough {
+        return;
+    }
+    let config = state.config.load();
+    let Some(rule) = config.shadow.iter().find(|rule| rule.matches(&req.model)) else {
+        return;
+    };
+    if rand::random::<f64>() * 100.0 >= rule.percent {
+        return;
+    }
+
+    let state = state.clone();
+    let rule = rule.clone();
+    let source_format = req.source_format;
+    let primary_model = req.model.clone();
+    let body = req.body.clone();
+    let request_id = req.request_id.clone().unwrap_or_else(|| "-".to_string());
+    tokio::spawn(async move {
+        let start = Instant::now();
+        let outcome = mirror(&state, source_format, &primary_model, &body, &rule).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+        match outcome {
+            Ok((model, usage)) => {
+                let cost = usage
+                    .as_ref()
+                    .and_then(|u| state.cost_calculator.calculate(&model, u));
+                tracing::info!(
+                    request_id = request_id.as_str(),
+                    provider = rule.provider.as_str(),
+                    model = model.as_str(),
+                    primary_model = primary_model.as_str(),
+                    latency_ms,
+                    input_tokens = usage.as_ref().map(|u| u.total_input()),
+                    output_tokens = usage.as_ref().map(|u| u.output_tokens),
+                    cost,
+                    "Shadow request completed"
+                );
+            }
+            Err(e) => tracing::warn!(
+                request_id = request_id.as_str(),
+                provider = rule.provider.as_str(),
+                primary_model = primary_model.as_str(),
+                latency_ms,
+                error = %e,
+                "Shadow request failed"
+            ),
+        }
+    });
+}
+
+/// Send one non-stream copy of the request; returns the upstream model id and usage.
+async fn mirror(
+    state: &AppState,
+    source_format: prism_core::provider::Format,
+    primary_model: &str,
+    body: &Bytes,
+    rule: &ShadowRule,
+) -> Result<(String, Option<TokenUsage>), ProxyError> {
+    let model = rule.model.as_deref().unwrap_or(primary_model);
+    let auth = state
+        .router
+        .pick(&rule.provider, model, &[], None, &[], None)
+        .ok_or_else(|| ProxyError::NoCredentials {
+            provider: rule.provider.clone(),
+            model: model.to_string(),
+        })?;
+    state.auth_runtime.prepare_auth(state, &auth).await?;
+
+    let target_format = auth.provider;
+    let actual_model = auth.resolve_model_id(model);
+    let body = if model != primary_model {
+        non_stream_body(&rewrite_model_in_body(body, model))
+    } else {
+        non_stream_body(body)
+    };
+    let translated = state.translators.translate_request(
+        source_format,
+        target_format,
+        &actual_model,
+        &body,
+        false,
+    )?;
+
+    let config = state.config.load();
+    let mut payload: serde_json::Value =
+        serde_json::from_slice(&translated).unwrap_or(serde_json::Value::Null);
+    let mut headers = Default::default();
+    if payload.is_object() {
+        prism_core::payload::apply_payload_rules(
+            &mut payload,
+            &config.payload,
+            &actual_model,
+            Some(target_format.as_str()),
+        );
+        let secret = auth.current_secret();
+        let presentation_ctx = prism_core::presentation::PresentationContext {
+            target_format,
+            model: &actual_model,
+            user_agent: None,
+            api_key: &secret,
+        };
+        headers = prism_core::presentation::apply(
+            &auth.upstream_presentation,
+            &presentation_ctx,
+            &mut payload,
+        )
+        .headers;
+    }
+    let payload = serde_json::to_vec(&payload).unwrap_or_else(|_| translated.clone());
+
+    let executor = state.executors.for_credential(&auth).ok_or_else(|| {
+        ProxyError::Internal(format!("no executor named '{}'", auth.executor_name()))
+    })?;
+    let response = executor
+        .execute(
+            &auth,
+            ProviderRequest {
+                model: actual_model.clone(),
+                payload: Bytes::from(payload),
+                source_format,
+                stream: false,
+                headers,
+                original_request: Some(body),
+                responses_passthrough: false,
+            },
+        )
+        .await?;
+
+    let usage = extract_usage(std::str::from_utf8(&response.payload).unwrap_or(""));
+    Ok((actual_model, usage))
+}
+
+/// Drop streaming flags so the mirror is a single non-stream call.
+fn non_stream_body(body: &Bytes) -> Bytes {
+    let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return body.clone();
+    };
+    let Some(obj) = value.as_object_mut() else {
+        return body.clone();
+    };
+    if obj.contains_key("stream") {
+        obj.insert("stream".to_string(), serde_json::Value::Bool(false));
+    }
+    obj.remove("stream_options");
+    serde_json::to_vec(&value)
+        .map(Bytes::from)
+        .unwrap_or_else(|_| body.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_stream_body_clears_stream_flags() {
+        let body = Bytes::from_static(
+            br#"{"model":"gpt-4o","stream":true,"stream_options":{"include_usage":true}}"#,
+        );
+        let value: serde_json::Value = serde_json::from_slice(&non_stream_body(&body)).unwrap();
+        assert_eq!(value["stream"], false);
+        assert!(value.get("stream_options").is_none());
+
+        let gemini = Bytes::from_static(br#"{"contents":[]}"#);
+        let value: serde_json::Value = serde_json::from_slice(&non_stream_body(&gemini)).unwrap();
+        assert!(value.get("stream").is_none());
+    }
+}
@@ -1,12 +1,14 @@
 use bytes::Bytes;
+use prism_core::config::StreamLimit;
 use prism_core::error::ProxyError;
+use prism_core::metrics::{DispatchPhase, Metrics};
 use prism_core::provider::{Format, ProviderResponse, StreamChunk};
-use prism_core::request_record::{LogDetailLevel, TokenUsage, truncate_body};
+use prism_core::request_record::{LogDetailLevel, TokenUsage, classify_error, truncate_body};
 use prism_translator::TranslateState;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use super::helpers::extract_usage;
+use super::chunk_probe::probe_chunk;
 
 /// Maximum characters to capture for stream content preview.
 const STREAM_PREVIEW_MAX_CHARS: usize = 500;
@@ -19,15 +21,17 @@ pub(super) fn translate_stream(
         Box<dyn tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send>,
     >,
     translators: std::sync::Arc<prism_translator::TranslatorRegistry>,
+    metrics: Arc<Metrics>,
     from: Format,
     to: Format,
     model: String,
     orig_req: Bytes,
-) -> impl tokio_stream::Stream<Item = Result<String, ProxyError>> + Send {
+) -> impl tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send {
     futures::stream::unfold(
         (upstream, TranslateState::default(), true),
         move |(mut stream, mut state, active)| {
             let translators = translators.clone();
+            let metrics = metrics.clone();
             let model = model.clone();
             let orig_req = orig_req.clone();
             async move {
@@ -38,23 +42,33 @@ pub(super) fn translate_stream(
                 use tokio_stream::StreamExt;
                 match stream.next().await {
                     Some(Ok(chunk)) => {
-                        match translators.translate_stream(
+                        let translate_start = Instant::now();
+                        let translated = translators.translate_stream(
                             from,
                             to,
                             &model,
                             &orig_req,
                             chunk.event_type.as_deref(),
-                            chunk.data.as_bytes(),
+                            &chunk.data,
                             &mut state,
-                        ) {
-                            Ok(lines) => {
+                        );
+                        metrics
+                            .record_phase(DispatchPhase::ChunkTranslate, translate_start.elapsed());
+                        match translated {
+                            Ok(mut lines) => {
                                 let has_done = lines.iter().any(|l| l == "[DONE]");
-                                let combined = lines.join("\n");
-                                if combined.is_empty() {
-                                    Some((Ok(String::new()), (stream, state, !has_done)))
-                                } else {
-                                    Some((Ok(combined), (stream, state, !has_done)))
-                                }
+                                // Most chunks translate to a single line; move it out
+                                // instead of copying it through `join`.
+                                let combined = match lines.len() {
+                                    0 => String::new(),
+                                    1 => lines.pop().unwrap_or_default(),
+                                    _ => lines.join("\n"),
+                                };
+                                let chunk = StreamChunk {
+                                    event_type: None,
+                                    data: Bytes::from(combined),
+                                };
+                                Some((Ok(chunk), (stream, state, !has_done)))
                             }
                             Err(e) => Some((Err(e), (stream, state, false))),
                         }
@@ -67,6 +81,177 @@ pub(super) fn translate_stream(
     )
 }
 
+/// Cut a client-format stream at the `streaming.limits` entry's byte or
+/// duration cap. On cut-off the client gets a finish chunk with a length stop
+/// reason, the upstream is dropped, and the span records `stream_truncated`.
+pub(super) fn with_stream_limit<S>(
+    stream: S,
+    limit: Option<StreamLimit>,
+    format: Format,
+    request_span: tracing::Span,
+) -> impl tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send
+where
+    S: tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send + 'static,
+{
+    struct State<S> {
+        inner: std::pin::Pin<Box<S>>,
+        limit: StreamLimit,
+        deadline: Option<tokio::time::Instant>,
+        sent_bytes: u64,
+        finish: FinishContext,
+        request_span: tracing::Span,
+        done: bool,
+    }
+
+    impl<S> State<S> {
+        fn truncate(mut self, reason: &'static str) -> (Result<StreamChunk, ProxyError>, Self) {
+            self.done = true;
+            tracing::warn!(
+                reason,
+                sent_bytes = self.sent_bytes,
+                "Stream cut off by streaming limit"
+            );
+            self.request_span.record("stream_truncated", reason);
+            let chunk = StreamChunk {
+                event_type: None,
+                data: Bytes::from(self.finish.finish_chunk()),
+            };
+            (Ok(chunk), self)
+        }
+    }
+
+    let limit = limit.unwrap_or_default();
+    let state = State {
+        inner: Box::pin(stream),
+        deadline: (limit.max_duration_secs > 0)
+            .then(|| tokio::time::Instant::now() + Duration::from_secs(limit.max_duration_secs)),
+        limit,
+        sent_bytes: 0,
+        finish: FinishContext::new(format),
+        request_span,
+        done: false,
+    };
+    futures::stream::unfold(state, |mut state| async move {
+        use tokio_stream::StreamExt;
+        if state.done {
+            return None;
+        }
+        let next = match state.deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, state.inner.next()).await {
+                Ok(next) => next,
+                Err(_) => return Some(state.truncate("max-duration")),
+            },
+            None => state.inner.next().await,
+        };
+        match next {
+            Some(Ok(chunk)) => {
+                let bytes =
+                    (chunk.data.len() + chunk.event_type.as_ref().map_or(0, String::len)) as u64;
+                if state.limit.max_bytes > 0 && state.sent_bytes + bytes > state.limit.max_bytes {
+                    return Some(state.truncate("max-bytes"));
+                }
+                state.sent_bytes += bytes;
+                state.finish.observe(&chunk.data);
+                Some((Ok(chunk), state))
+            }
+            Some(Err(e)) => Some((Err(e), state)),
+            None => None,
+        }
+    })
+}
+
+/// What a synthetic finish chunk needs from the stream so far.
+struct FinishContext {
+    format: Format,
+    /// First OpenAI chunk, reused for the finish chunk's `id`, `created` and `model`.
+    first_chunk: Option<serde_json::Value>,
+    /// Index of the Claude content block still open.
+    open_block: Option<u64>,
+}
+
+impl FinishContext {
+    fn new(format: Format) -> Self {
+        Self {
+            format,
+            first_chunk: None,
+            open_block: None,
+        }
+    }
+
+    fn observe(&mut self, data: &[u8]) {
+        match self.format {
+            Format::OpenAI if self.first_chunk.is_none() => {
+                self.first_chunk = data.split(|&b| b == b'\n').find_map(|line| {
+                    serde_json::from_slice(line.strip_prefix(b"data: ").unwrap_or(line)).ok()
+                });
+            }
+            Format::Claude => {
+                let Ok(data) = std::str::from_utf8(data) else {
+                    return;
+                };
+                for line in data.lines() {
+                    let json = line.trim_start_matches("data: ");
+                    if json.contains("\"content_block_start\"") {
+                        self.open_block = serde_json::from_str::<serde_json::Value>(json)
+                            .ok()
+                            .and_then(|v| v["index"].as_u64())
+                            .or(Some(0));
+                    } else if json.contains("\"content_block_stop\"") {
+                        self.open_block = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Lines ending the stream in the client's format with a length stop reason.
+    fn finish_chunk(&self) -> String {
+        match self.format {
+            Format::OpenAI => {
+                let first = self.first_chunk.as_ref();
+                let field = |key: &str| {
+                    first
+                        .and_then(|v| v.get(key))
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null)
+                };
+                let chunk = serde_json::json!({
+                    "id": field("id"),
+                    "object": "chat.completion.chunk",
+                    "created": field("created"),
+                    "model": field("model"),
+                    "choices": [{"index": 0, "delta": {}, "finish_reason": "length"}],
+                });
+                format!("{chunk}\n[DONE]")
+            }
+            Format::Claude => {
+                let mut lines = Vec::new();
+                if let Some(index) = self.open_block {
+                    let stop = serde_json::json!({"type": "content_block_stop", "index": index});
+                    lines.push(format!("event: content_block_stop\ndata: {stop}"));
+                }
+                let delta = serde_json::json!({
+                    "type": "message_delta",
+                    "delta": {"stop_reason": "max_tokens", "stop_sequence": null},
+                    "usage": {"output_tokens": 0},
+                });
+                lines.push(format!("event: message_delta\ndata: {delta}"));
+                lines.push("event: message_stop\ndata: {\"type\":\"message_stop\"}".to_string());
+                lines.join("\n")
+            }
+            Format::Gemini => serde_json::json!({
+                "candidates": [{
+                    "content": {"role": "model", "parts": [{"text": ""}]},
+                    "finishReason": "MAX_TOKENS",
+                    "index": 0,
+                }],
+            })
+            .to_string(),
+        }
+    }
+}
+
 /// Build a chunked response body that sends periodic whitespace while waiting
 /// for the upstream response. Leading whitespace is valid JSON and is ignored
 /// by parsers, so the client receives ` ` ` ` `{"choices":[...]}`.
@@ -144,11 +329,19 @@ pub(super) fn keepalive_error_json(msg: &str) -> String {
 /// Callback context for updating metrics after a stream completes.
 pub(super) struct StreamDoneContext {
     pub model: Option<String>,
+    /// Provider name from config, used to key streamed throughput.
+    pub provider: String,
     pub cost_calculator: Arc<prism_core::cost::CostCalculator>,
     pub metrics: Arc<prism_core::metrics::Metrics>,
     pub rate_limiter: Arc<prism_core::rate_limit::CompositeRateLimiter>,
+    /// Credential quotas are charged on the router under this name.
+    pub router: Arc<prism_provider::routing::CredentialRouter>,
+    pub credential_name: Option<String>,
     pub api_key: Option<String>,
     pub tenant_id: Option<String>,
+    pub experiment: Option<super::ExperimentAssignment>,
+    /// When the upstream request was sent, for the first-byte phase.
+    pub upstream_started_at: Instant,
 }
 
 /// Wrap an upstream `StreamChunk` stream to capture token usage from SSE events.
@@ -156,7 +349,9 @@ pub(super) struct StreamDoneContext {
 /// Each chunk's `data` is inspected for usage fields (supports OpenAI, Claude, and Gemini
 /// response formats). When the stream is dropped (either after natural completion or due to
 /// client disconnect), the captured usage is recorded on the `request_span` and written
-/// back to metrics. The span's delayed close triggers GatewayLogLayer::on_close.
+/// back to metrics. The first mid-stream error replaces the span's status and error fields,
+/// since the 200 recorded at stream start no longer describes the outcome. The span's
+/// delayed close triggers GatewayLogLayer::on_close.
 pub(super) fn with_usage_capture(
     stream: std::pin::Pin<
         Box<dyn tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send>,
@@ -174,10 +369,19 @@ pub(super) fn with_usage_capture(
         ctx: Option<StreamDoneContext>,
         request_span: tracing::Span,
         content_preview: String,
+        /// Total content characters seen, for estimating output tokens without usage.
+        content_chars: usize,
+        /// Arrival of the first and latest upstream chunks (the generation window).
+        first_chunk_at: Option<std::time::Instant>,
+        last_chunk_at: Option<std::time::Instant>,
+        /// When the upstream stream was handed over, for the slow-request stream phase.
+        started_at: std::time::Instant,
         /// Accumulated raw SSE data for full response body logging.
         /// `None` when detail_level < Full.
         response_body: Option<String>,
         max_body_bytes: usize,
+        /// Set once a mid-stream error has been recorded on the request span.
+        failed: bool,
     }
 
     impl Drop for State {
@@ -186,11 +390,35 @@ pub(super) fn with_usage_capture(
                 if let Some(ref tenant_id) = ctx.tenant_id {
                     ctx.metrics.record_tenant_request(tenant_id);
                 }
-                if let Some(ref usage) = self.usage {
-                    let cost = ctx
-                        .model
+                // Throughput: reported output tokens, or ~4 characters per token when the
+                // upstream sent no usage, over the first-to-last chunk window.
+                if let (Some(first), Some(last)) = (self.first_chunk_at, self.last_chunk_at) {
+                    let output_tokens = self
+                        .usage
+                        .as_ref()
+                        .map(|u| u.output_tokens)
+                        .filter(|&t| t > 0)
+                        .unwrap_or((self.content_chars / 4) as u64);
+                    ctx.metrics.record_stream_throughput(
+                        &ctx.provider,
+                        output_tokens,
+                        last.duration_since(first),
+                    );
+                }
+                let cost = self.usage.as_ref().and_then(|usage| {
+                    ctx.model
                         .as_deref()
-                        .and_then(|m| ctx.cost_calculator.calculate(m, usage));
+                        .and_then(|m| ctx.cost_calculator.calculate(m, usage))
+                });
+                if let Some(ref name) = ctx.credential_name {
+                    let tokens = self
+                        .usage
+                        .as_ref()
+                        .map(|u| u.total_input() + u.output_tokens)
+                        .unwrap_or(0);
+                    ctx.router.record_usage(name, tokens, cost.unwrap_or(0.0));
+                }
+                if let Some(ref usage) = self.usage {
                     ctx.metrics
                         .record_tokens(usage.total_input(), usage.output_tokens);
                     if let Some(ref tenant_id) = ctx.tenant_id {
@@ -199,6 +427,14 @@ pub(super) fn with_usage_capture(
                             usage.total_input() + usage.output_tokens,
                         );
                     }
+                    if let Some(ref experiment) = ctx.experiment {
+                        ctx.metrics.record_experiment_usage(
+                            &experiment.name,
+                            experiment.arm.as_str(),
+                            usage.total_input() + usage.output_tokens,
+                            cost,
+                        );
+                    }
                     if let (Some(m), Some(c)) = (ctx.model.as_deref(), cost) {
                         ctx.metrics.record_cost(m, c);
                         if let Some(ref tenant_id) = ctx.tenant_id {
@@ -228,6 +464,8 @@ pub(super) fn with_usage_capture(
                         truncate_body(body, self.max_body_bytes).as_ref(),
                     );
                 }
+                self.request_span
+                    .record("stream_ms", self.started_at.elapsed().as_millis() as u64);
                 // Span drops here → GatewayLogLayer::on_close fires
             }
         }
@@ -240,32 +478,63 @@ pub(super) fn with_usage_capture(
         ctx: Some(ctx),
         request_span,
         content_preview: String::with_capacity(STREAM_PREVIEW_MAX_CHARS),
+        content_chars: 0,
+        first_chunk_at: None,
+        last_chunk_at: None,
+        started_at: std::time::Instant::now(),
         response_body: if capture_body {
             Some(String::with_capacity(max_body_bytes.min(64 * 1024)))
         } else {
             None
         },
         max_body_bytes,
+        failed: false,
     };
 
     Box::pin(futures::stream::unfold(state, |mut state| async move {
         use tokio_stream::StreamExt;
         match state.inner.next().await {
             Some(result) => {
+                if let Err(ref err) = result
+                    && !state.failed
+                {
+                    state.failed = true;
+                    state
+                        .request_span
+                        .record("status", err.status_code_u16() as u64);
+                    state.request_span.record("error", err.to_string());
+                    state.request_span.record("error_type", classify_error(err));
+                }
                 if let Ok(ref chunk) = result {
-                    if let Some(u) = extract_usage(&chunk.data) {
+                    // One borrowed pass over the chunk for both usage and preview text.
+                    let probe = std::str::from_utf8(&chunk.data)
+                        .map(probe_chunk)
+                        .unwrap_or_default();
+                    if let Some(u) = probe.usage {
                         match state.usage.as_mut() {
                             Some(existing) => existing.merge(&u),
                             None => state.usage = Some(u),
                         }
                     }
-                    // Capture content preview from SSE data (reuse parsed JSON if possible)
-                    if state.content_preview.len() < STREAM_PREVIEW_MAX_CHARS
-                        && let Some(text) = extract_content_text(&chunk.data)
+                    let now = std::time::Instant::now();
+                    if state.first_chunk_at.is_none()
+                        && let Some(ref ctx) = state.ctx
                     {
-                        let remaining = STREAM_PREVIEW_MAX_CHARS - state.content_preview.len();
-                        let truncated = truncate_body(&text, remaining);
-                        state.content_preview.push_str(&truncated);
+                        ctx.metrics.record_phase(
+                            DispatchPhase::FirstByte,
+                            now.duration_since(ctx.upstream_started_at),
+                        );
+                    }
+                    state.first_chunk_at.get_or_insert(now);
+                    state.last_chunk_at = Some(now);
+                    // Capture content preview from SSE data
+                    if let Some(text) = probe.text {
+                        state.content_chars += text.chars().count();
+                        if state.content_preview.len() < STREAM_PREVIEW_MAX_CHARS {
+                            let remaining = STREAM_PREVIEW_MAX_CHARS - state.content_preview.len();
+                            let truncated = truncate_body(&text, remaining);
+                            state.content_preview.push_str(&truncated);
+                        }
                     }
                     // Accumulate raw SSE data for full response body logging.
                     // max_body_bytes 0 = unlimited (truncate_body treats 0 as no-op).
@@ -281,7 +550,8 @@ pub(super) fn with_usage_capture(
                         } else {
                             limit.saturating_sub(body.len())
                         };
-                        body.push_str(&truncate_body(&chunk.data, remaining));
+                        let data = String::from_utf8_lossy(&chunk.data);
+                        body.push_str(&truncate_body(&data, remaining));
                     }
                 }
                 Some((result, state))
@@ -295,30 +565,164 @@ pub(super) fn with_usage_capture(
     }))
 }
 
-/// Extract content text from an SSE chunk data string.
-/// Supports OpenAI (choices[0].delta.content) and Claude (delta.text) formats.
-fn extract_content_text(data: &str) -> Option<String> {
-    let val: serde_json::Value = serde_json::from_str(data).ok()?;
-
-    // OpenAI format: choices[0].delta.content
-    if let Some(content) = val
-        .get("choices")
-        .and_then(|c| c.get(0))
-        .and_then(|c| c.get("delta"))
-        .and_then(|d| d.get("content"))
-        .and_then(|c| c.as_str())
-    {
-        return Some(content.to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+
+    fn limit(max_bytes: u64, max_duration_secs: u64) -> Option<StreamLimit> {
+        Some(StreamLimit {
+            models: vec!["*".to_string()],
+            max_bytes,
+            max_duration_secs,
+        })
+    }
+
+    fn data(data: &str) -> Result<StreamChunk, ProxyError> {
+        Ok(StreamChunk {
+            event_type: None,
+            data: Bytes::copy_from_slice(data.as_bytes()),
+        })
+    }
+
+    async fn collect(
+        stream: impl tokio_stream::Stream<Item = Result<StreamChunk, ProxyError>> + Send,
+    ) -> Vec<String> {
+        stream
+            .map(|r| String::from_utf8(r.unwrap().data.to_vec()).unwrap())
+            .collect()
+            .await
     }
 
-    // Claude format: delta.text
-    if let Some(text) = val
-        .get("delta")
-        .and_then(|d| d.get("text"))
-        .and_then(|t| t.as_str())
-    {
-        return Some(text.to_string());
+    #[tokio::test]
+    async fn test_stream_limit_bytes_emits_openai_length_finish() {
+        let chunk = r#"{"id":"c1","created":7,"model":"m","choices":[{"index":0,"delta":{"content":"hi"}}]}"#;
+        let upstream = futures::stream::iter((0..5).map(|_| data(chunk)));
+        let span = tracing::info_span!("gateway.request", stream_truncated = tracing::field::Empty);
+        let budget = chunk.len() as u64 * 2;
+        let out = collect(with_stream_limit(
+            upstream,
+            limit(budget, 0),
+            Format::OpenAI,
+            span,
+        ))
+        .await;
+
+        assert_eq!(out.len(), 3);
+        let (finish, done) = out[2].split_once('\n').unwrap();
+        let finish: serde_json::Value = serde_json::from_str(finish).unwrap();
+        assert_eq!(finish["id"], "c1");
+        assert_eq!(finish["model"], "m");
+        assert_eq!(finish["choices"][0]["finish_reason"], "length");
+        assert_eq!(done, "[DONE]");
+    }
+
+    #[tokio::test]
+    async fn test_stream_limit_duration_closes_open_claude_block() {
+        let start = "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":2,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}";
+        let upstream = futures::stream::iter(vec![data(start)]).chain(futures::stream::pending());
+        let span = tracing::info_span!("gateway.request", stream_truncated = tracing::field::Empty);
+        let out = collect(with_stream_limit(
+            upstream,
+            limit(0, 1),
+            Format::Claude,
+            span,
+        ))
+        .await;
+
+        assert_eq!(out.len(), 2);
+        let mut lines = out[1].lines();
+        assert_eq!(lines.next(), Some("event: content_block_stop"));
+        let stop: serde_json::Value =
+            serde_json::from_str(lines.next().unwrap().trim_start_matches("data: ")).unwrap();
+        assert_eq!(stop["index"], 2);
+        assert!(out[1].contains("\"stop_reason\":\"max_tokens\""));
+        assert!(out[1].ends_with("event: message_stop\ndata: {\"type\":\"message_stop\"}"));
     }
 
-    None
+    #[tokio::test]
+    async fn test_usage_capture_patches_log_entry_at_stream_end() {
+        use prism_core::memory_log_store::InMemoryLogStore;
+        use prism_core::request_log::LogStore;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let logs: Arc<dyn LogStore> = Arc::new(InMemoryLogStore::new(10, None));
+        let subscriber = tracing_subscriber::registry()
+            .with(crate::telemetry::GatewayLogLayer::new(logs.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let span = tracing::info_span!(
+            "gateway.request",
+            request_id = "stream-req",
+            stream = true,
+            status = tracing::field::Empty,
+            usage_input = tracing::field::Empty,
+            usage_output = tracing::field::Empty,
+            usage_cache_read = tracing::field::Empty,
+            usage_cache_creation = tracing::field::Empty,
+            cost = tracing::field::Empty,
+            error = tracing::field::Empty,
+            error_type = tracing::field::Empty,
+            stream_content_preview = tracing::field::Empty,
+            stream_ms = tracing::field::Empty,
+        );
+        span.record("status", 200u64);
+
+        let config = prism_core::config::Config::default();
+        let ctx = StreamDoneContext {
+            model: Some("gpt-4o".to_string()),
+            provider: "openai".to_string(),
+            cost_calculator: Arc::new(prism_core::cost::CostCalculator::new(&Default::default())),
+            metrics: Arc::new(Metrics::new()),
+            rate_limiter: Arc::new(prism_core::rate_limit::CompositeRateLimiter::new(
+                &config.rate_limit,
+            )),
+            router: Arc::new(prism_provider::routing::CredentialRouter::new(
+                Default::default(),
+            )),
+            credential_name: None,
+            api_key: None,
+            tenant_id: None,
+            experiment: None,
+            upstream_started_at: Instant::now(),
+        };
+        let upstream = futures::stream::iter(vec![
+            data(r#"{"choices":[{"index":0,"delta":{"content":"hi"}}]}"#),
+            data(r#"{"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":3}}"#),
+            Err(ProxyError::Upstream {
+                status: 502,
+                body: "connection reset".to_string(),
+                retry_after_secs: None,
+                headers: Default::default(),
+            }),
+        ]);
+
+        let out: Vec<_> =
+            with_usage_capture(Box::pin(upstream), ctx, span, LogDetailLevel::Metadata, 0)
+                .collect()
+                .await;
+        assert_eq!(out.len(), 3);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let record = logs.get("stream-req").await.expect("log entry written");
+        let usage = record.usage.expect("stream usage recorded");
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 3);
+        assert_eq!(record.status, 502);
+        assert!(record.error.is_some());
+        assert_eq!(record.stream_content_preview.as_deref(), Some("hi"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_without_limit_passes_through() {
+        let upstream = futures::stream::iter(vec![data("a"), data("[DONE]")]);
+        let out = collect(with_stream_limit(
+            upstream,
+            None,
+            Format::Gemini,
+            tracing::Span::none(),
+        ))
+        .await;
+        assert_eq!(out, ["a", "[DONE]"]);
+    }
 }
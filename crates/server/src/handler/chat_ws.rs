@@ -0,0 +1,133 @@
+//! Chat completions over WebSocket (`GET /v1/ws/chat`).
+//!
+//! Each text message is one chat completions request. It goes through the same
+//! validation, routing, and dispatch as `POST /v1/chat/completions` with
+//! `stream` forced on, and every SSE chunk comes back as one text message,
+//! ending with `[DONE]`. Failures are sent as an error message and the socket
+//! stays open for the next request.
+
+use crate::AppState;
+use crate::handler::responses_ws::send_ws_error;
+use axum::Extension;
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{State, WebSocketUpgrade};
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use bytes::Bytes;
+use prism_core::context::RequestContext;
+use prism_core::provider::Format;
+use prism_provider::sse::parse_sse_stream;
+use serde_json::Value;
+use tokio_stream::StreamExt;
+
+const REQUEST_PATH: &str = "/v1/ws/chat";
+
+pub async fn chat_ws(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_ws(socket, state, ctx, headers))
+}
+
+async fn handle_ws(
+    mut socket: WebSocket,
+    state: AppState,
+    ctx: RequestContext,
+    headers: HeaderMap,
+) {
+    let mut request_index = 0u64;
+    while let Some(message) = socket.recv().await {
+        let payload = match message {
+            Ok(Message::Text(text)) => Bytes::copy_from_slice(text.as_bytes()),
+            Ok(Message::Binary(bytes)) => bytes,
+            Err(_) => return,
+            // The next `recv` flushes the queued close reply, then ends the loop.
+            Ok(Message::Close(_)) => continue,
+            _ => continue,
+        };
+        let body = match normalize_ws_request(&payload) {
+            Ok(body) => body,
+            Err(message) => {
+                if send_ws_error(&mut socket, &message).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        request_index += 1;
+        let mut request_ctx = ctx.clone();
+        request_ctx.request_id = format!("{}:ws:{request_index}", ctx.request_id);
+        request_ctx.start_time = std::time::Instant::now();
+        let response = match super::dispatch_api_request(
+            &state,
+            &request_ctx,
+            &headers,
+            body,
+            REQUEST_PATH,
+            Format::OpenAI,
+            None,
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(err) => {
+                if send_ws_error(&mut socket, &err.to_string()).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        let mut sse_stream = parse_sse_stream(response.into_body().into_data_stream());
+        while let Some(event) = sse_stream.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    let _ = send_ws_error(&mut socket, &err.to_string()).await;
+                    return;
+                }
+            };
+            if socket.send(Message::Text(event.data.into())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Parse one message as a chat completions body and force streaming on.
+fn normalize_ws_request(raw: &[u8]) -> Result<Bytes, String> {
+    let mut value: Value =
+        serde_json::from_slice(raw).map_err(|e| format!("invalid websocket JSON: {e}"))?;
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| "expected websocket request object".to_string())?;
+    obj.insert("stream".into(), Value::Bool(true));
+    serde_json::to_vec(&value)
+        .map(Bytes::from)
+        .map_err(|e| format!("failed to serialize request: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_forces_stream() {
+        let body = normalize_ws_request(
+            br#"{"model":"gpt-4o","stream":false,"messages":[{"role":"user","content":"hi"}]}"#,
+        )
+        .unwrap();
+        let value: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(value.get("stream"), Some(&Value::Bool(true)));
+        assert_eq!(value.get("model").and_then(|v| v.as_str()), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn normalize_rejects_non_object() {
+        assert!(normalize_ws_request(b"[1,2]").is_err());
+        assert!(normalize_ws_request(b"not json").is_err());
+    }
+}
@@ -1,29 +1,71 @@
 use crate::AppState;
 use axum::Extension;
+use axum::body::Body;
 use axum::extract::State;
 use axum::http::HeaderMap;
 use axum::response::Response;
 use bytes::Bytes;
+use http_body_util::BodyExt;
 use prism_core::context::RequestContext;
 use prism_core::error::ProxyError;
 use prism_core::provider::Format;
+use prism_provider::sse::parse_sse_stream;
+use prism_translator::legacy_completions;
+use tokio_stream::StreamExt;
 
 /// POST /v1/completions — Legacy OpenAI Completions API.
-/// Routes through the same dispatch pipeline as chat completions.
+/// The prompt is sent as a chat request through the same dispatch pipeline as
+/// chat completions, and the chat response is translated back to `text_completion`.
 pub async fn completions(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     headers: HeaderMap,
     body: Bytes,
 ) -> Result<Response, ProxyError> {
-    super::dispatch_api_request(
+    super::validation::validate_request(super::validation::RequestKind::Completions, &body)?;
+    let chat_body = Bytes::from(legacy_completions::translate_request(&body)?);
+    let response = super::dispatch_api_request(
         &state,
         &ctx,
         &headers,
-        body,
+        chat_body,
         "/v1/completions",
         Format::OpenAI,
         None,
     )
-    .await
+    .await?;
+    translate_response(response).await
+}
+
+async fn translate_response(response: Response) -> Result<Response, ProxyError> {
+    let (mut parts, body) = response.into_parts();
+    let is_stream = parts
+        .headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if is_stream {
+        let events = parse_sse_stream(body.into_data_stream()).filter_map(|event| match event {
+            Ok(event) if event.data == "[DONE]" => Some(Ok(Bytes::from("data: [DONE]\n\n"))),
+            Ok(event) => match legacy_completions::translate_chunk(&event.data) {
+                Ok(Some(chunk)) => Some(Ok(Bytes::from(format!("data: {chunk}\n\n")))),
+                Ok(None) => None,
+                Err(err) => Some(Err(err)),
+            },
+            Err(err) => Some(Err(err)),
+        });
+        return Ok(Response::from_parts(parts, Body::from_stream(events)));
+    }
+
+    if !parts.status.is_success() {
+        return Ok(Response::from_parts(parts, body));
+    }
+    let bytes = body
+        .collect()
+        .await
+        .map_err(|e| ProxyError::Internal(format!("failed to read response: {e}")))?
+        .to_bytes();
+    let translated = legacy_completions::translate_non_stream(&bytes)?;
+    parts.headers.remove("content-length");
+    Ok(Response::from_parts(parts, Body::from(translated)))
 }
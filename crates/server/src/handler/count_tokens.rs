@@ -46,6 +46,7 @@ pub async fn count_tokens(
             .unwrap_or_default(),
         requested_credential,
     )?;
+    let sticky = crate::dispatch::sticky_value(&state.config.load(), &ctx, &headers);
 
     let auth = state
         .router
@@ -59,6 +60,7 @@ pub async fn count_tokens(
                 &[],
                 ctx.client_region.as_deref(),
                 &allowed_credentials,
+                sticky.as_deref(),
             )
         })
         .ok_or_else(|| ProxyError::NoCredentials {
@@ -73,13 +75,9 @@ pub async fn count_tokens(
         .unwrap_or("https://api.anthropic.com");
     let url = format!("{base_url}/v1/messages/count_tokens");
 
-    let global_proxy = state.config.load().proxy_url.clone();
     let client = state
         .http_client_pool
-        .get_or_create_default(
-            auth.effective_proxy(global_proxy.as_deref()),
-            global_proxy.as_deref(),
-        )
+        .get_or_create_current(auth.proxy_url.as_deref())
         .map_err(|e| ProxyError::Internal(format!("failed to build HTTP client: {e}")))?;
 
     let mut req = client
@@ -100,9 +98,13 @@ pub async fn count_tokens(
         }
     }
 
-    // Forward anthropic-beta if present in incoming headers
-    if let Some(beta) = headers.get("anthropic-beta") {
-        req = req.header("anthropic-beta", beta);
+    // Forward the anthropic-beta flags this credential allows
+    let betas: Vec<String> = crate::dispatch::anthropic_betas(&headers)
+        .into_iter()
+        .filter(|beta| auth.allows_beta(beta))
+        .collect();
+    if !betas.is_empty() {
+        req = req.header("anthropic-beta", betas.join(","));
     }
 
     let resp = req
@@ -6,6 +6,7 @@ use axum::Json;
 use axum::extract::State;
 use axum::http::{HeaderMap, StatusCode, header::SET_COOKIE};
 use axum::response::{IntoResponse, Response};
+use prism_core::dashboard_api::LoginResponse;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
@@ -225,11 +226,11 @@ pub async fn login(
             (
                 StatusCode::OK,
                 [(SET_COOKIE, cookie)],
-                Json(json!({
-                    "authenticated": true,
-                    "username": body.username,
-                    "expires_in": dashboard.jwt_ttl_secs,
-                })),
+                Json(LoginResponse {
+                    authenticated: true,
+                    username: body.username,
+                    expires_in: dashboard.jwt_ttl_secs,
+                }),
             )
                 .into_response()
         }
@@ -271,11 +272,11 @@ pub async fn refresh(
             (
                 StatusCode::OK,
                 [(SET_COOKIE, cookie)],
-                Json(json!({
-                    "authenticated": true,
-                    "username": claims.sub.clone(),
-                    "expires_in": dashboard.jwt_ttl_secs,
-                })),
+                Json(LoginResponse {
+                    authenticated: true,
+                    username: claims.sub.clone(),
+                    expires_in: dashboard.jwt_ttl_secs,
+                }),
             )
                 .into_response()
         }
@@ -1,9 +1,14 @@
 use crate::AppState;
-use axum::Json;
+use crate::handler::dashboard::config_tx::{TryUpdateError, try_update_config_versioned};
+use crate::middleware::dashboard_auth::Claims;
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use axum::{Extension, Json};
 use prism_core::auth_key::{AuthKeyEntry, AuthKeyStore};
+use prism_core::dashboard_api::{
+    AuthKeyCreated, AuthKeyList, AuthKeySummary, AuthKeyToggled, Message,
+};
 use serde::Deserialize;
 use serde_json::json;
 
@@ -25,6 +30,12 @@ pub struct CreateAuthKeyRequest {
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(default)]
     pub metadata: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub moderation: Option<prism_core::config::ModerationAction>,
+    #[serde(default)]
+    pub routing_hints: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,45 +56,56 @@ pub struct UpdateAuthKeyRequest {
     pub expires_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
     #[serde(default)]
     pub metadata: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub moderation: Option<Option<prism_core::config::ModerationAction>>,
+    #[serde(default)]
+    pub routing_hints: Option<bool>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
 }
 
 /// GET /api/dashboard/auth-keys
 pub async fn list_auth_keys(State(state): State<AppState>) -> impl IntoResponse {
     let config = state.config.load();
-    let keys: Vec<serde_json::Value> = config
+    let auth_keys = config
         .auth_keys
         .iter()
-        .enumerate()
-        .map(|(i, entry)| {
-            json!({
-                "id": i,
-                "key_masked": AuthKeyStore::mask_key(&entry.key),
-                "name": entry.name,
-                "tenant_id": entry.tenant_id,
-                "allowed_models": entry.allowed_models,
-                "allowed_credentials": entry.allowed_credentials,
-                "rate_limit": entry.rate_limit,
-                "budget": entry.budget,
-                "expires_at": entry.expires_at,
-                "metadata": entry.metadata,
-            })
+        .map(|entry| AuthKeySummary {
+            id: entry.id.clone().unwrap_or_default(),
+            key_masked: AuthKeyStore::mask_key(&entry.key),
+            name: entry.name.clone(),
+            tenant_id: entry.tenant_id.clone(),
+            allowed_models: entry.allowed_models.clone(),
+            allowed_credentials: entry.allowed_credentials.clone(),
+            rate_limit: entry.rate_limit.clone(),
+            budget: entry.budget.clone(),
+            expires_at: entry.expires_at,
+            metadata: entry.metadata.clone(),
+            moderation: entry.moderation,
+            routing_hints: entry.routing_hints,
+            disabled: entry.disabled,
+            tags: entry.tags.clone(),
         })
         .collect();
-    (StatusCode::OK, Json(json!({ "auth_keys": keys })))
+    (StatusCode::OK, Json(AuthKeyList { auth_keys }))
 }
 
 /// POST /api/dashboard/auth-keys
 pub async fn create_auth_key(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<CreateAuthKeyRequest>,
 ) -> impl IntoResponse {
+    let expected = super::config_tx::expected_version(&headers);
     let key = format!(
         "sk-proxy-{}",
         uuid::Uuid::new_v4().to_string().replace('-', "")
     );
 
     let full_key = key.clone();
+    let id = uuid::Uuid::new_v4().to_string();
     let entry = AuthKeyEntry {
+        id: Some(id.clone()),
         key,
         name: body.name,
         tenant_id: body.tenant_id,
@@ -93,10 +115,14 @@ pub async fn create_auth_key(
         budget: body.budget,
         expires_at: body.expires_at,
         metadata: body.metadata,
+        moderation: body.moderation,
+        routing_hints: body.routing_hints,
+        disabled: false,
+        tags: body.tags,
     };
 
     let key_name = entry.name.clone();
-    match super::config_tx::update_config_file_public(&state, move |config| {
+    match super::config_tx::update_config_file_public(&state, expected.as_deref(), move |config| {
         config.auth_keys.push(entry);
         config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
     })
@@ -106,18 +132,17 @@ pub async fn create_auth_key(
             tracing::info!(name = ?key_name, "Auth key created via dashboard");
             (
                 StatusCode::CREATED,
-                Json(json!({
-                    "key": full_key,
-                    "message": "API key created. Save this key - it will not be shown again.",
+                Json(json!(AuthKeyCreated {
+                    id,
+                    key: full_key,
+                    message: "API key created. Save this key - it will not be shown again."
+                        .to_string(),
                 })),
             )
         }
         Err(e) => {
-            tracing::error!(error = %e, "Failed to create auth key");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "write_failed", "message": e})),
-            )
+            tracing::error!(error = ?e, "Failed to create auth key");
+            super::config_ops::config_tx_error_response(e)
         }
     }
 }
@@ -125,54 +150,64 @@ pub async fn create_auth_key(
 /// PATCH /api/dashboard/auth-keys/:id
 pub async fn update_auth_key(
     State(state): State<AppState>,
-    Path(id): Path<usize>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
     Json(body): Json<UpdateAuthKeyRequest>,
 ) -> impl IntoResponse {
-    match super::config_tx::update_config_file_public(&state, move |config| {
-        if id < config.auth_keys.len() {
-            let entry = &mut config.auth_keys[id];
-            if let Some(name) = body.name {
-                entry.name = Some(name);
-            }
-            if let Some(tenant_id) = body.tenant_id {
-                entry.tenant_id = tenant_id;
-            }
-            if let Some(allowed_models) = body.allowed_models {
-                entry.allowed_models = allowed_models;
-            }
-            if let Some(allowed_credentials) = body.allowed_credentials {
-                entry.allowed_credentials = allowed_credentials;
-            }
-            if let Some(rate_limit) = body.rate_limit {
-                entry.rate_limit = rate_limit;
-            }
-            if let Some(budget) = body.budget {
-                entry.budget = budget;
-            }
-            if let Some(expires_at) = body.expires_at {
-                entry.expires_at = expires_at;
-            }
-            if let Some(metadata) = body.metadata {
-                entry.metadata = metadata;
-            }
-            config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
+    let expected = super::config_tx::expected_version(&headers);
+    let key_id = id.clone();
+    match try_update_config_versioned(&state, expected.as_deref(), move |config| {
+        let position = config.auth_key_position(&key_id).ok_or_else(not_found)?;
+        let entry = &mut config.auth_keys[position];
+        if let Some(name) = body.name {
+            entry.name = Some(name);
+        }
+        if let Some(tenant_id) = body.tenant_id {
+            entry.tenant_id = tenant_id;
+        }
+        if let Some(allowed_models) = body.allowed_models {
+            entry.allowed_models = allowed_models;
+        }
+        if let Some(allowed_credentials) = body.allowed_credentials {
+            entry.allowed_credentials = allowed_credentials;
+        }
+        if let Some(rate_limit) = body.rate_limit {
+            entry.rate_limit = rate_limit;
+        }
+        if let Some(budget) = body.budget {
+            entry.budget = budget;
         }
+        if let Some(expires_at) = body.expires_at {
+            entry.expires_at = expires_at;
+        }
+        if let Some(metadata) = body.metadata {
+            entry.metadata = metadata;
+        }
+        if let Some(moderation) = body.moderation {
+            entry.moderation = moderation;
+        }
+        if let Some(routing_hints) = body.routing_hints {
+            entry.routing_hints = routing_hints;
+        }
+        if let Some(tags) = body.tags {
+            entry.tags = tags;
+        }
+        config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
+        Ok(())
     })
     .await
     {
         Ok(_) => {
-            tracing::info!(key_id = id, "Auth key updated via dashboard");
+            tracing::info!(key_id = %id, "Auth key updated via dashboard");
             (
                 StatusCode::OK,
                 Json(json!({"message": "Auth key updated successfully"})),
             )
         }
-        Err(e) => {
-            tracing::error!(key_id = id, error = %e, "Failed to update auth key");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "write_failed", "message": e})),
-            )
+        Err(TryUpdateError::Aborted(response)) => response,
+        Err(TryUpdateError::Tx(e)) => {
+            tracing::error!(key_id = %id, error = ?e, "Failed to update auth key");
+            super::config_ops::config_tx_error_response(e)
         }
     }
 }
@@ -180,46 +215,131 @@ pub async fn update_auth_key(
 /// POST /api/dashboard/auth-keys/:id/reveal
 pub async fn reveal_auth_key(
     State(state): State<AppState>,
-    Path(id): Path<usize>,
+    Path(id): Path<String>,
 ) -> impl IntoResponse {
     let config = state.config.load();
-    if let Some(entry) = config.auth_keys.get(id) {
-        tracing::info!(key_id = id, name = ?entry.name, "Auth key revealed via dashboard");
-        (StatusCode::OK, Json(json!({ "key": entry.key })))
-    } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "not_found", "message": "Auth key not found"})),
-        )
+    match config.auth_key_position(&id) {
+        Some(position) => {
+            let entry = &config.auth_keys[position];
+            tracing::info!(key_id = %id, name = ?entry.name, "Auth key revealed via dashboard");
+            (StatusCode::OK, Json(json!({ "key": entry.key })))
+        }
+        None => not_found(),
     }
 }
 
 /// DELETE /api/dashboard/auth-keys/:id
 pub async fn delete_auth_key(
     State(state): State<AppState>,
-    Path(id): Path<usize>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
 ) -> impl IntoResponse {
-    match super::config_tx::update_config_file_public(&state, move |config| {
-        if id < config.auth_keys.len() {
-            config.auth_keys.remove(id);
-            config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
-        }
+    let expected = super::config_tx::expected_version(&headers);
+    let key_id = id.clone();
+    match try_update_config_versioned(&state, expected.as_deref(), move |config| {
+        let position = config.auth_key_position(&key_id).ok_or_else(not_found)?;
+        config.auth_keys.remove(position);
+        config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
+        Ok(())
     })
     .await
     {
         Ok(_) => {
-            tracing::info!(key_id = id, "Auth key deleted via dashboard");
+            tracing::info!(key_id = %id, "Auth key deleted via dashboard");
             (
                 StatusCode::OK,
-                Json(json!({"message": "API key deleted successfully"})),
+                Json(json!(Message::new("API key deleted successfully"))),
             )
         }
-        Err(e) => {
-            tracing::error!(key_id = id, error = %e, "Failed to delete auth key");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "write_failed", "message": e})),
-            )
+        Err(TryUpdateError::Aborted(response)) => response,
+        Err(TryUpdateError::Tx(e)) => {
+            tracing::error!(key_id = %id, error = ?e, "Failed to delete auth key");
+            super::config_ops::config_tx_error_response(e)
         }
     }
 }
+
+/// POST /api/dashboard/auth-keys/:id/disable
+pub async fn disable_auth_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    claims: Option<Extension<Claims>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    set_auth_key_disabled(&state, &headers, claims, &id, true).await
+}
+
+/// POST /api/dashboard/auth-keys/:id/enable
+pub async fn enable_auth_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    claims: Option<Extension<Claims>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    set_auth_key_disabled(&state, &headers, claims, &id, false).await
+}
+
+/// Why [`set_auth_key_disabled`] wrote nothing.
+enum ToggleAbort {
+    NotFound,
+    /// Already in the requested state.
+    Unchanged,
+}
+
+/// Flip `disabled` on one auth key. The key is looked up, and its state checked,
+/// in the file under the write lock; already being in the requested state is a
+/// no-op that skips the config write.
+async fn set_auth_key_disabled(
+    state: &AppState,
+    headers: &HeaderMap,
+    claims: Option<Extension<Claims>>,
+    id: &str,
+    disabled: bool,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let expected = super::config_tx::expected_version(headers);
+    let mut key_id = String::new();
+    let outcome = try_update_config_versioned(state, expected.as_deref(), |config| {
+        let position = config.auth_key_position(id).ok_or(ToggleAbort::NotFound)?;
+        let entry = &mut config.auth_keys[position];
+        key_id = entry.id.clone().unwrap_or_else(|| id.to_string());
+        if entry.disabled == disabled {
+            return Err(ToggleAbort::Unchanged);
+        }
+        entry.disabled = disabled;
+        config.auth_key_store = AuthKeyStore::new(config.auth_keys.clone());
+        Ok(())
+    })
+    .await;
+    let changed = match outcome {
+        Ok(_) => {
+            let action = if disabled {
+                "auth_key.disable"
+            } else {
+                "auth_key.enable"
+            };
+            super::audit(claims.as_deref(), action, &key_id);
+            true
+        }
+        Err(TryUpdateError::Aborted(ToggleAbort::Unchanged)) => false,
+        Err(TryUpdateError::Aborted(ToggleAbort::NotFound)) => return not_found(),
+        Err(TryUpdateError::Tx(e)) => {
+            tracing::error!(key_id = %id, error = ?e, "Failed to toggle auth key");
+            return super::config_ops::config_tx_error_response(e);
+        }
+    };
+    (
+        StatusCode::OK,
+        Json(json!(AuthKeyToggled {
+            id: key_id,
+            disabled,
+            changed,
+        })),
+    )
+}
+
+fn not_found() -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "not_found", "message": "Auth key not found"})),
+    )
+}
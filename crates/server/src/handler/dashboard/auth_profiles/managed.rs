@@ -29,6 +29,7 @@ pub async fn import_local_auth_profile(
     Path((provider, profile_id)): Path<(String, String)>,
     Json(body): Json<ImportLocalAuthProfileRequest>,
 ) -> impl IntoResponse {
+    let provider = crate::handler::dashboard::providers::resolve_provider_name(&state, &provider);
     if let Err(response) =
         ensure_managed_profile_shape(&state, &provider, &profile_id, AuthMode::CodexOAuth).await
     {
@@ -70,6 +71,7 @@ pub async fn connect_auth_profile(
     Path((provider, profile_id)): Path<(String, String)>,
     Json(body): Json<ConnectAuthProfileRequest>,
 ) -> impl IntoResponse {
+    let provider = crate::handler::dashboard::providers::resolve_provider_name(&state, &provider);
     if body.secret.trim().is_empty() {
         return validation_error("secret is required");
     }
@@ -126,6 +128,7 @@ pub async fn refresh_auth_profile(
     State(state): State<AppState>,
     Path((provider, profile_id)): Path<(String, String)>,
 ) -> impl IntoResponse {
+    let provider = crate::handler::dashboard::providers::resolve_provider_name(&state, &provider);
     let config = state.config.load();
     let Some((_, profile)) = explicit_profile(&config, &provider, &profile_id) else {
         return not_found("Auth profile not found");
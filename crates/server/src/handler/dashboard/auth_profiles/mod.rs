@@ -8,11 +8,11 @@ pub use device_flow::{poll_codex_device, start_codex_device};
 pub use managed::{connect_auth_profile, import_local_auth_profile, refresh_auth_profile};
 pub use oauth::{complete_codex_oauth, start_codex_oauth};
 
-use super::config_tx::{ConfigTxError, update_config_versioned};
+use super::config_tx::{ANY_VERSION, ConfigTxError, update_config_versioned};
 use crate::AppState;
 use axum::Json;
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use prism_core::auth_profile::{AuthHeaderKind, AuthMode, AuthProfileEntry};
 use prism_core::presentation::UpstreamPresentationConfig;
@@ -247,7 +247,7 @@ pub(super) async fn ensure_managed_profile_shape(
     .map_err(|message| validation_error(&message))?;
     drop(config);
 
-    match update_config_versioned(state, None, move |config| {
+    match update_config_versioned(state, Some(ANY_VERSION), move |config| {
         if let Some(entry) = config
             .providers
             .iter_mut()
@@ -289,6 +289,10 @@ pub(super) async fn ensure_managed_profile_shape(
             StatusCode::CONFLICT,
             Json(json!({"error": "config_conflict", "current_version": current_version})),
         )),
+        Err(ConfigTxError::PreconditionRequired { current_version }) => Err((
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({"error": "precondition_required", "current_version": current_version})),
+        )),
         Err(ConfigTxError::Validation(message)) => Err(validation_error(&message)),
         Err(ConfigTxError::Internal(message)) => Err(internal_error(message)),
     }
@@ -338,8 +342,10 @@ pub async fn auth_profiles_runtime(State(state): State<AppState>) -> Response {
 /// POST /api/dashboard/auth-profiles
 pub async fn create_auth_profile(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<CreateAuthProfileRequest>,
 ) -> Response {
+    let expected = super::config_tx::expected_version(&headers);
     if body.provider.trim().is_empty() || body.id.trim().is_empty() {
         return validation_error("provider and id are required").into_response();
     }
@@ -383,7 +389,7 @@ pub async fn create_auth_profile(
 
     let provider = body.provider.clone();
     let profile_id = profile.id.clone();
-    match update_config_versioned(&state, None, move |config| {
+    match update_config_versioned(&state, expected.as_deref(), move |config| {
         if let Some(entry) = config
             .providers
             .iter_mut()
@@ -411,6 +417,11 @@ pub async fn create_auth_profile(
             Json(json!({"error": "config_conflict", "current_version": current_version})),
         )
             .into_response(),
+        Err(ConfigTxError::PreconditionRequired { current_version }) => (
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({"error": "precondition_required", "current_version": current_version})),
+        )
+            .into_response(),
         Err(ConfigTxError::Validation(message)) => validation_error(&message).into_response(),
         Err(ConfigTxError::Internal(message)) => internal_error(message).into_response(),
     }
@@ -419,9 +430,12 @@ pub async fn create_auth_profile(
 /// PUT /api/dashboard/auth-profiles/{provider}/{profile}
 pub async fn replace_auth_profile(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path((provider, profile_id)): Path<(String, String)>,
     Json(body): Json<ReplaceAuthProfileRequest>,
 ) -> Response {
+    let expected = super::config_tx::expected_version(&headers);
+    let provider = crate::handler::dashboard::providers::resolve_provider_name(&state, &provider);
     let config = state.config.load();
     let Some(entry) = config.providers.iter().find(|entry| entry.name == provider) else {
         return not_found("Auth profile not found").into_response();
@@ -472,7 +486,7 @@ pub async fn replace_auth_profile(
 
     let provider_for_update = provider.clone();
     let profile_id_for_update = profile_id.clone();
-    match update_config_versioned(&state, None, move |config| {
+    match update_config_versioned(&state, expected.as_deref(), move |config| {
         if let Some(entry) = config
             .providers
             .iter_mut()
@@ -511,6 +525,11 @@ pub async fn replace_auth_profile(
             Json(json!({"error": "config_conflict", "current_version": current_version})),
         )
             .into_response(),
+        Err(ConfigTxError::PreconditionRequired { current_version }) => (
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({"error": "precondition_required", "current_version": current_version})),
+        )
+            .into_response(),
         Err(ConfigTxError::Validation(message)) => validation_error(&message).into_response(),
         Err(ConfigTxError::Internal(message)) => internal_error(message).into_response(),
     }
@@ -519,8 +538,11 @@ pub async fn replace_auth_profile(
 /// DELETE /api/dashboard/auth-profiles/{provider}/{profile}
 pub async fn delete_auth_profile(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path((provider, profile_id)): Path<(String, String)>,
 ) -> Response {
+    let expected = super::config_tx::expected_version(&headers);
+    let provider = crate::handler::dashboard::providers::resolve_provider_name(&state, &provider);
     let existed = explicit_profile(&state.config.load(), &provider, &profile_id).is_some();
     if !existed {
         return not_found("Auth profile not found").into_response();
@@ -528,7 +550,7 @@ pub async fn delete_auth_profile(
 
     let provider_for_delete = provider.clone();
     let profile_id_for_delete = profile_id.clone();
-    match update_config_versioned(&state, None, move |config| {
+    match update_config_versioned(&state, expected.as_deref(), move |config| {
         if let Some(entry) = config
             .providers
             .iter_mut()
@@ -560,6 +582,11 @@ pub async fn delete_auth_profile(
             Json(json!({"error": "config_conflict", "current_version": current_version})),
         )
             .into_response(),
+        Err(ConfigTxError::PreconditionRequired { current_version }) => (
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({"error": "precondition_required", "current_version": current_version})),
+        )
+            .into_response(),
         Err(ConfigTxError::Validation(message)) => validation_error(&message).into_response(),
         Err(ConfigTxError::Internal(message)) => internal_error(message).into_response(),
     }
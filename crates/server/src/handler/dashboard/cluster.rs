@@ -0,0 +1,181 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::{Query, RawQuery, State};
+use axum::response::IntoResponse;
+use prism_core::config::ClusterPeer;
+use prism_core::request_log::StatsQuery;
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Lifetime of the dashboard token sent to peers.
+const PEER_TOKEN_TTL_SECS: u64 = 60;
+
+#[derive(Debug, Serialize)]
+struct ClusterNode {
+    name: String,
+    /// `None` for the instance serving the request.
+    url: Option<String>,
+    reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    metrics: Option<Value>,
+    log_stats: Option<Value>,
+}
+
+/// Totals across the reachable nodes.
+#[derive(Debug, Default, Serialize)]
+struct ClusterSummary {
+    nodes: usize,
+    reachable: usize,
+    total_requests: u64,
+    total_errors: u64,
+    error_rate: f64,
+    total_tokens: u64,
+    total_cost_usd: f64,
+    requests_per_minute: f64,
+    log_entries: u64,
+    log_errors: u64,
+    /// Average latency of logged requests, weighted by each node's entry count.
+    avg_latency_ms: f64,
+}
+
+/// GET /api/dashboard/cluster — this instance and every `cluster.peers` entry,
+/// with metrics and log statistics summed across the cluster. Query parameters
+/// are the `logs/stats` filters and are passed on to peers.
+pub async fn cluster_overview(
+    State(state): State<AppState>,
+    Query(query): Query<StatsQuery>,
+    RawQuery(raw_query): RawQuery,
+) -> impl IntoResponse {
+    let config = state.config.load();
+    let local = ClusterNode {
+        name: config.cluster.node_name.clone(),
+        url: None,
+        reachable: true,
+        error: None,
+        metrics: Some(state.metrics.snapshot()),
+        log_stats: serde_json::to_value(state.log_store.stats(&query).await).ok(),
+    };
+
+    let timeout = Duration::from_millis(config.cluster.timeout_ms);
+    let own_secret = config.dashboard.resolve_jwt_secret();
+    // Peers are reached directly, never through the upstream proxy.
+    let client = state.http_client_pool.get_or_create_current(Some(""));
+    let peers = config.cluster.peers.iter().map(|peer| {
+        let client = client.as_ref().map_err(|e| e.to_string());
+        let secret = peer.jwt_secret.clone().or_else(|| own_secret.clone());
+        let node_name = config.cluster.node_name.as_str();
+        let raw_query = raw_query.as_deref();
+        async move {
+            match client {
+                Ok(client) => fetch_peer(client, peer, node_name, secret, raw_query, timeout).await,
+                Err(e) => unreachable_node(peer, e),
+            }
+        }
+    });
+    let mut nodes = vec![local];
+    nodes.extend(futures::future::join_all(peers).await);
+
+    let summary = summarize(&nodes);
+    Json(serde_json::json!({ "nodes": nodes, "summary": summary }))
+}
+
+async fn fetch_peer(
+    client: &reqwest::Client,
+    peer: &ClusterPeer,
+    node_name: &str,
+    secret: Option<String>,
+    raw_query: Option<&str>,
+    timeout: Duration,
+) -> ClusterNode {
+    let base = peer.url.trim_end_matches('/');
+    let metrics = fetch_json(client.get(format!("{base}/admin/metrics")), timeout);
+    let mut stats_url = format!("{base}/api/dashboard/logs/stats");
+    if let Some(q) = raw_query.filter(|q| !q.is_empty()) {
+        stats_url.push('?');
+        stats_url.push_str(q);
+    }
+    let mut stats_request = client.get(stats_url);
+    if let Some(secret) = secret {
+        let subject = format!("cluster:{node_name}");
+        match crate::middleware::dashboard_auth::generate_token(
+            &subject,
+            &secret,
+            PEER_TOKEN_TTL_SECS,
+        ) {
+            Ok(token) => stats_request = stats_request.bearer_auth(token),
+            Err(e) => return unreachable_node(peer, format!("cannot sign peer token: {e}")),
+        }
+    }
+    let log_stats = fetch_json(stats_request, timeout);
+
+    match tokio::join!(metrics, log_stats) {
+        (Ok(metrics), log_stats) => ClusterNode {
+            name: peer.name.clone(),
+            url: Some(peer.url.clone()),
+            reachable: true,
+            error: log_stats.as_ref().err().map(|e| format!("logs/stats: {e}")),
+            metrics: Some(metrics),
+            log_stats: log_stats.ok(),
+        },
+        (Err(e), _) => unreachable_node(peer, e),
+    }
+}
+
+async fn fetch_json(request: reqwest::RequestBuilder, timeout: Duration) -> Result<Value, String> {
+    let response = request
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status.as_u16()));
+    }
+    response.json().await.map_err(|e| e.to_string())
+}
+
+fn unreachable_node(peer: &ClusterPeer, error: String) -> ClusterNode {
+    ClusterNode {
+        name: peer.name.clone(),
+        url: Some(peer.url.clone()),
+        reachable: false,
+        error: Some(error),
+        metrics: None,
+        log_stats: None,
+    }
+}
+
+fn summarize(nodes: &[ClusterNode]) -> ClusterSummary {
+    let u64_at = |v: &Value, key: &str| v.get(key).and_then(Value::as_u64).unwrap_or(0);
+    let f64_at = |v: &Value, key: &str| v.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+    let mut summary = ClusterSummary {
+        nodes: nodes.len(),
+        ..Default::default()
+    };
+    let mut latency_weighted = 0.0;
+    for node in nodes.iter().filter(|n| n.reachable) {
+        summary.reachable += 1;
+        if let Some(m) = &node.metrics {
+            summary.total_requests += u64_at(m, "total_requests");
+            summary.total_errors += u64_at(m, "total_errors");
+            summary.total_tokens += u64_at(m, "total_tokens");
+            summary.total_cost_usd += f64_at(m, "total_cost_usd");
+            summary.requests_per_minute += f64_at(m, "requests_per_minute");
+        }
+        if let Some(s) = &node.log_stats {
+            let entries = u64_at(s, "total_entries");
+            summary.log_entries += entries;
+            summary.log_errors += u64_at(s, "error_count");
+            latency_weighted += f64_at(s, "avg_latency_ms") * entries as f64;
+        }
+    }
+    if summary.total_requests > 0 {
+        summary.error_rate = summary.total_errors as f64 / summary.total_requests as f64;
+    }
+    if summary.log_entries > 0 {
+        summary.avg_latency_ms = latency_weighted / summary.log_entries as f64;
+    }
+    summary
+}
@@ -0,0 +1,361 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use prism_core::config::{Config, ProviderKeyEntry};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Current bundle schema version written by export and accepted by import.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    /// True when literal secrets were stripped on export.
+    #[serde(default)]
+    pub sanitized: bool,
+    #[serde(default)]
+    pub providers: Vec<ProviderKeyEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// `sanitized` (default) or `full`.
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Must be `true` for a full export that includes literal secrets.
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Upsert bundle providers by name, keeping providers not present in the bundle.
+    #[default]
+    Merge,
+    /// Replace the full provider list with the bundle contents.
+    Replace,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    pub bundle: ConfigBundle,
+    #[serde(default)]
+    pub mode: ImportMode,
+    #[serde(default)]
+    pub config_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportItemResult {
+    pub name: String,
+    /// `created`, `updated`, or `removed`.
+    pub action: &'static str,
+    /// True when blank secrets in the bundle were backfilled from the existing entry.
+    pub secrets_preserved: bool,
+}
+
+/// GET /api/dashboard/config/export — export provider entries as a portable bundle.
+/// `env://` and `file://` references are always kept. Literal secrets, including the
+/// values of credential-like headers, are blanked unless `?mode=full&confirm=true` is given.
+pub async fn export_config(
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
+    let full = match query.mode.as_deref() {
+        None | Some("sanitized") => false,
+        Some("full") => true,
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(
+                    json!({"error": "invalid_mode", "message": format!("Unknown export mode '{other}'. Use 'sanitized' or 'full'")}),
+                ),
+            );
+        }
+    };
+    if full && !query.confirm {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(
+                json!({"error": "confirmation_required", "message": "Full export includes plaintext secrets. Repeat with confirm=true"}),
+            ),
+        );
+    }
+
+    let (contents, version) = match super::config_tx::read_config_versioned(&state) {
+        Ok(result) => result,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "read_failed", "message": e})),
+            );
+        }
+    };
+    let raw = match Config::from_yaml_raw(&contents) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "read_failed", "message": e.to_string()})),
+            );
+        }
+    };
+
+    let mut providers = raw.providers;
+    if !full {
+        providers.iter_mut().for_each(strip_literal_secrets);
+    } else {
+        tracing::warn!(
+            providers = providers.len(),
+            "Full config bundle exported via dashboard"
+        );
+    }
+
+    let bundle = ConfigBundle {
+        version: BUNDLE_VERSION,
+        exported_at: Some(chrono::Utc::now().to_rfc3339()),
+        sanitized: !full,
+        providers,
+    };
+    (
+        StatusCode::OK,
+        Json(json!({"bundle": bundle, "config_version": version})),
+    )
+}
+
+/// POST /api/dashboard/config/import — merge or replace provider entries from a bundle.
+pub async fn import_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<ImportRequest>,
+) -> impl IntoResponse {
+    if body.bundle.version != BUNDLE_VERSION {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "validation_failed",
+                "message": format!("Unsupported bundle version {}", body.bundle.version),
+            })),
+        );
+    }
+    let mut seen = std::collections::HashSet::new();
+    for entry in &body.bundle.providers {
+        if entry.name.is_empty() || !seen.insert(entry.name.as_str()) {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": "validation_failed",
+                    "message": format!("Bundle contains an empty or duplicate provider name '{}'", entry.name),
+                })),
+            );
+        }
+    }
+
+    let expected = body
+        .config_version
+        .or_else(|| super::config_tx::expected_version(&headers));
+    let mode = body.mode;
+    let incoming = body.bundle.providers;
+    let mut results = Vec::new();
+    let outcome =
+        super::config_tx::update_config_versioned(&state, expected.as_deref(), |config| {
+            results = merge_providers(&mut config.providers, incoming, mode);
+        })
+        .await;
+
+    match outcome {
+        Ok(new_version) => {
+            tracing::info!(
+                mode = ?mode,
+                items = results.len(),
+                "Config bundle imported via dashboard"
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "message": "Configuration bundle imported successfully",
+                    "results": results,
+                    "config_version": new_version,
+                })),
+            )
+        }
+        Err(error) => super::config_ops::config_tx_error_response(error),
+    }
+}
+
+/// Apply bundle providers onto `providers` and report what happened to each name.
+fn merge_providers(
+    providers: &mut Vec<ProviderKeyEntry>,
+    incoming: Vec<ProviderKeyEntry>,
+    mode: ImportMode,
+) -> Vec<ImportItemResult> {
+    let previous = match mode {
+        ImportMode::Merge => providers.clone(),
+        ImportMode::Replace => std::mem::take(providers),
+    };
+    let mut results = Vec::new();
+
+    for mut entry in incoming {
+        let existing = previous.iter().find(|e| e.name == entry.name);
+        let secrets_preserved = existing.is_some_and(|old| backfill_secrets(&mut entry, old));
+        if entry.id.is_none() {
+            entry.id = existing.and_then(|old| old.id.clone());
+        }
+        results.push(ImportItemResult {
+            name: entry.name.clone(),
+            action: if existing.is_some() {
+                "updated"
+            } else {
+                "created"
+            },
+            secrets_preserved,
+        });
+        match providers.iter_mut().find(|e| e.name == entry.name) {
+            Some(slot) => *slot = entry,
+            None => providers.push(entry),
+        }
+    }
+
+    for entry in &previous {
+        if !providers.iter().any(|e| e.name == entry.name) {
+            results.push(ImportItemResult {
+                name: entry.name.clone(),
+                action: "removed",
+                secrets_preserved: false,
+            });
+        }
+    }
+    results
+}
+
+/// Fill blank secrets on `entry` from a previous version of the same provider.
+/// Covers every field [`strip_literal_secrets`] clears, so a sanitized export
+/// imports back without losing credentials.
+fn backfill_secrets(entry: &mut ProviderKeyEntry, old: &ProviderKeyEntry) -> bool {
+    let mut preserved = false;
+    if entry.api_key.is_empty() && !old.api_key.is_empty() {
+        entry.api_key = old.api_key.clone();
+        preserved = true;
+    }
+    if let (Some(signing), Some(old_signing)) = (&mut entry.signing, &old.signing)
+        && signing.secret.is_empty()
+        && !old_signing.secret.is_empty()
+    {
+        signing.secret = old_signing.secret.clone();
+        preserved = true;
+    }
+    // Loading moves legacy `headers` into `custom-headers`, so look in both.
+    let old_headers = [&old.headers, &old.upstream_presentation.custom_headers];
+    preserved |= backfill_headers(&mut entry.headers, &old_headers);
+    preserved |= backfill_headers(
+        &mut entry.upstream_presentation.custom_headers,
+        &old_headers,
+    );
+    for profile in &mut entry.auth_profiles {
+        let Some(old_profile) = old.auth_profiles.iter().find(|p| p.id == profile.id) else {
+            continue;
+        };
+        for (value, old_value) in [
+            (&mut profile.secret, &old_profile.secret),
+            (&mut profile.access_token, &old_profile.access_token),
+            (&mut profile.refresh_token, &old_profile.refresh_token),
+            (&mut profile.id_token, &old_profile.id_token),
+        ] {
+            if value.is_none() && old_value.is_some() {
+                *value = old_value.clone();
+                preserved = true;
+            }
+        }
+        let old_headers = [
+            &old_profile.headers,
+            &old_profile.upstream_presentation.custom_headers,
+        ];
+        preserved |= backfill_headers(&mut profile.headers, &old_headers);
+        preserved |= backfill_headers(
+            &mut profile.upstream_presentation.custom_headers,
+            &old_headers,
+        );
+    }
+    preserved
+}
+
+/// Fill blank credential header values from `old`, matching names case-insensitively.
+fn backfill_headers(
+    headers: &mut HashMap<String, String>,
+    old: &[&HashMap<String, String>],
+) -> bool {
+    let mut preserved = false;
+    for (name, value) in headers {
+        if !value.is_empty() || !is_credential_header(name) {
+            continue;
+        }
+        if let Some(old_value) = old
+            .iter()
+            .flat_map(|map| map.iter())
+            .find(|(old_name, _)| old_name.eq_ignore_ascii_case(name))
+            .map(|(_, old_value)| old_value)
+            .filter(|old_value| !old_value.is_empty())
+        {
+            *value = old_value.clone();
+            preserved = true;
+        }
+    }
+    preserved
+}
+
+fn strip_literal_secrets(entry: &mut ProviderKeyEntry) {
+    if !is_secret_reference(&entry.api_key) {
+        entry.api_key.clear();
+    }
+    if let Some(signing) = &mut entry.signing
+        && !is_secret_reference(&signing.secret)
+    {
+        signing.secret.clear();
+    }
+    let mut header_maps = vec![
+        &mut entry.headers,
+        &mut entry.upstream_presentation.custom_headers,
+    ];
+    for profile in &mut entry.auth_profiles {
+        header_maps.push(&mut profile.headers);
+        header_maps.push(&mut profile.upstream_presentation.custom_headers);
+    }
+    for (name, value) in header_maps.into_iter().flatten() {
+        if is_credential_header(name) && !is_secret_reference(value) {
+            value.clear();
+        }
+    }
+    for profile in &mut entry.auth_profiles {
+        for value in [
+            &mut profile.secret,
+            &mut profile.access_token,
+            &mut profile.refresh_token,
+            &mut profile.id_token,
+        ] {
+            if value.as_deref().is_some_and(|v| !is_secret_reference(v)) {
+                *value = None;
+            }
+        }
+    }
+}
+
+/// Header names whose values are treated as credentials in a bundle: the names
+/// debug traces redact, plus custom names mentioning a key, token, or secret.
+fn is_credential_header(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    crate::debug_trace::is_secret_name(&lower)
+        || ["auth", "key", "token", "secret", "password"]
+            .iter()
+            .any(|part| lower.contains(part))
+}
+
+fn is_secret_reference(value: &str) -> bool {
+    value.starts_with("env://") || value.starts_with("file://")
+}
@@ -0,0 +1,282 @@
+use prism_core::auth_key::AuthKeyStore;
+use prism_core::config::Config;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Config keys whose string values are secrets and must never appear in a diff.
+const SECRET_FIELDS: &[&str] = &[
+    "api-key",
+    "key",
+    "secret",
+    "password-hash",
+    "jwt-secret",
+    "access-token",
+    "refresh-token",
+    "id-token",
+    "redis-url",
+];
+
+/// Top-level settings that are only read at startup and need a restart to take effect.
+const RESTART_REQUIRED_FIELDS: &[&str] = &[
+    "host",
+    "port",
+    "tls",
+    "daemon",
+    "logging-to-file",
+    "log-dir",
+    "log-rotation",
+    "router-state",
+    "shared-state",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigImpact {
+    pub providers_added: Vec<String>,
+    pub providers_removed: Vec<String>,
+    pub providers_modified: Vec<String>,
+    /// Providers whose API key or auth profile secrets change.
+    pub credentials_rotated: Vec<String>,
+    /// Masked auth keys introduced by the proposal.
+    pub auth_keys_added: Vec<String>,
+    /// Masked auth keys that stop working once the proposal is applied.
+    pub auth_keys_invalidated: Vec<String>,
+    /// Changed settings that are not hot-reloaded.
+    pub restart_required: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiff {
+    pub changes: Vec<ConfigChange>,
+    pub impact: ConfigImpact,
+}
+
+/// Compute a secret-safe structural diff between two raw (unresolved) configs.
+pub fn diff_configs(current: &Config, proposed: &Config) -> ConfigDiff {
+    let before = serde_json::to_value(current).unwrap_or(Value::Null);
+    let after = serde_json::to_value(proposed).unwrap_or(Value::Null);
+
+    let mut changes = Vec::new();
+    diff_value("", None, &before, &after, &mut changes);
+
+    ConfigDiff {
+        impact: compute_impact(current, proposed, &changes),
+        changes,
+    }
+}
+
+/// Apply an RFC 7386 JSON merge patch to `target` in place.
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target_map) = target {
+        for (key, value) in patch_map {
+            if value.is_null() {
+                target_map.remove(key);
+            } else {
+                apply_merge_patch(target_map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn compute_impact(current: &Config, proposed: &Config, changes: &[ConfigChange]) -> ConfigImpact {
+    let mut impact = ConfigImpact::default();
+
+    for entry in &proposed.providers {
+        match current.providers.iter().find(|p| p.name == entry.name) {
+            None => impact.providers_added.push(entry.name.clone()),
+            Some(existing) => {
+                let before = serde_json::to_value(existing).unwrap_or(Value::Null);
+                let after = serde_json::to_value(entry).unwrap_or(Value::Null);
+                if before != after {
+                    impact.providers_modified.push(entry.name.clone());
+                }
+                if provider_secrets(existing) != provider_secrets(entry) {
+                    impact.credentials_rotated.push(entry.name.clone());
+                }
+            }
+        }
+    }
+    for entry in &current.providers {
+        if !proposed.providers.iter().any(|p| p.name == entry.name) {
+            impact.providers_removed.push(entry.name.clone());
+        }
+    }
+
+    let current_keys: BTreeSet<&str> = current.auth_keys.iter().map(|k| k.key.as_str()).collect();
+    let proposed_keys: BTreeSet<&str> = proposed.auth_keys.iter().map(|k| k.key.as_str()).collect();
+    impact.auth_keys_added = proposed_keys
+        .difference(&current_keys)
+        .map(|key| AuthKeyStore::mask_key(key))
+        .collect();
+    impact.auth_keys_invalidated = current_keys
+        .difference(&proposed_keys)
+        .map(|key| AuthKeyStore::mask_key(key))
+        .collect();
+
+    let mut restart = BTreeSet::new();
+    for change in changes {
+        let top = change
+            .path
+            .split(['.', '['])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if RESTART_REQUIRED_FIELDS.contains(&top.as_str()) {
+            restart.insert(top);
+        }
+    }
+    impact.restart_required = restart.into_iter().collect();
+
+    impact
+}
+
+fn provider_secrets(entry: &prism_core::config::ProviderKeyEntry) -> Vec<Option<String>> {
+    let mut secrets = vec![Some(entry.api_key.clone())];
+    secrets.extend(entry.auth_profiles.iter().map(|p| p.secret.clone()));
+    secrets
+}
+
+fn diff_value(
+    path: &str,
+    field: Option<&str>,
+    before: &Value,
+    after: &Value,
+    out: &mut Vec<ConfigChange>,
+) {
+    if before == after {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            let keys: BTreeSet<&String> = b.keys().chain(a.keys()).collect();
+            for key in keys {
+                let child = join_path(path, key);
+                match (b.get(key), a.get(key)) {
+                    (Some(bv), Some(av)) => diff_value(&child, Some(key), bv, av, out),
+                    (Some(bv), None) => out.push(removed(child, Some(key), bv)),
+                    (None, Some(av)) => out.push(added(child, Some(key), av)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(b), Value::Array(a)) => match (element_labels(b), element_labels(a)) {
+            (Some(before_labels), Some(after_labels)) => {
+                for (label, bv) in before_labels.iter().zip(b) {
+                    let child = format!("{path}[{label}]");
+                    match after_labels.iter().position(|l| l == label) {
+                        Some(idx) => diff_value(&child, None, bv, &a[idx], out),
+                        None => out.push(removed(child, None, bv)),
+                    }
+                }
+                for (label, av) in after_labels.iter().zip(a) {
+                    if !before_labels.contains(label) {
+                        out.push(added(format!("{path}[{label}]"), None, av));
+                    }
+                }
+            }
+            _ => out.push(modified(path.to_string(), field, before, after)),
+        },
+        _ => out.push(modified(path.to_string(), field, before, after)),
+    }
+}
+
+/// Stable per-element labels for arrays of named entries (providers, auth keys, profiles).
+/// Returns `None` when elements cannot be matched, in which case the array is diffed whole.
+fn element_labels(items: &[Value]) -> Option<Vec<String>> {
+    let labels: Vec<String> = items
+        .iter()
+        .map(|item| {
+            if let Some(name) = item.get("name").and_then(Value::as_str)
+                && item.get("key").is_none()
+            {
+                Some(name.to_string())
+            } else if let Some(key) = item.get("key").and_then(Value::as_str) {
+                Some(AuthKeyStore::mask_key(key))
+            } else {
+                item.get("id").and_then(Value::as_str).map(str::to_string)
+            }
+        })
+        .collect::<Option<_>>()?;
+    let unique: BTreeSet<&String> = labels.iter().collect();
+    (unique.len() == labels.len()).then_some(labels)
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn added(path: String, field: Option<&str>, value: &Value) -> ConfigChange {
+    ConfigChange {
+        path,
+        kind: ChangeKind::Added,
+        before: None,
+        after: Some(redact(field, value)),
+    }
+}
+
+fn removed(path: String, field: Option<&str>, value: &Value) -> ConfigChange {
+    ConfigChange {
+        path,
+        kind: ChangeKind::Removed,
+        before: Some(redact(field, value)),
+        after: None,
+    }
+}
+
+fn modified(path: String, field: Option<&str>, before: &Value, after: &Value) -> ConfigChange {
+    ConfigChange {
+        path,
+        kind: ChangeKind::Modified,
+        before: Some(redact(field, before)),
+        after: Some(redact(field, after)),
+    }
+}
+
+/// Mask secret-bearing fields anywhere inside `value`.
+fn redact(field: Option<&str>, value: &Value) -> Value {
+    match value {
+        Value::String(s) if field.is_some_and(|f| SECRET_FIELDS.contains(&f)) => {
+            if s.is_empty() || s.starts_with("env://") || s.starts_with("file://") {
+                Value::String(s.clone())
+            } else {
+                Value::String(AuthKeyStore::mask_key(s))
+            }
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact(Some(k), v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(|v| redact(field, v)).collect()),
+        other => other.clone(),
+    }
+}
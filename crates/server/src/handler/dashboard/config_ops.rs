@@ -1,11 +1,11 @@
 use crate::AppState;
 use axum::Json;
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use serde_json::json;
 
-fn config_tx_error_response(
+pub(super) fn config_tx_error_response(
     error: super::config_tx::ConfigTxError,
 ) -> (StatusCode, Json<serde_json::Value>) {
     match error {
@@ -17,6 +17,14 @@ fn config_tx_error_response(
                 "current_version": current_version,
             })),
         ),
+        super::config_tx::ConfigTxError::PreconditionRequired { current_version } => (
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({
+                "error": "precondition_required",
+                "message": "If-Match with the current config version is required.",
+                "current_version": current_version,
+            })),
+        ),
         super::config_tx::ConfigTxError::Validation(message) => (
             StatusCode::UNPROCESSABLE_ENTITY,
             Json(json!({"error": "validation_failed", "message": message})),
@@ -45,8 +53,11 @@ pub async fn validate_config(
         prism_core::config::Config::from_yaml_raw(&yaml_str)
     } else {
         match serde_json::from_value::<prism_core::config::Config>(body) {
-            Ok(_cfg) => {
-                return (StatusCode::OK, Json(json!({"valid": true, "errors": []})));
+            Ok(cfg) => {
+                return (
+                    StatusCode::OK,
+                    Json(json!({"valid": true, "errors": [], "warnings": alias_warnings(&cfg)})),
+                );
             }
             Err(e) => {
                 return (
@@ -94,7 +105,10 @@ pub async fn validate_config(
             // Full validation with resolution
             match prism_core::config::Config::load_from_str(&raw_cfg.to_yaml().unwrap_or_default())
             {
-                Ok(_) => (StatusCode::OK, Json(json!({"valid": true, "errors": []}))),
+                Ok(cfg) => (
+                    StatusCode::OK,
+                    Json(json!({"valid": true, "errors": [], "warnings": alias_warnings(&cfg)})),
+                ),
                 Err(e) => (
                     StatusCode::UNPROCESSABLE_ENTITY,
                     Json(json!({"valid": false, "errors": [e.to_string()]})),
@@ -108,6 +122,11 @@ pub async fn validate_config(
     }
 }
 
+/// Non-fatal findings reported next to a successful validate or reload.
+fn alias_warnings(config: &prism_core::config::Config) -> Vec<String> {
+    prism_core::model_alias::AliasMap::from_config(config).warnings
+}
+
 /// POST /api/dashboard/config/reload — trigger hot-reload.
 pub async fn reload_config(State(state): State<AppState>) -> impl IntoResponse {
     let config_path = state
@@ -117,11 +136,19 @@ pub async fn reload_config(State(state): State<AppState>) -> impl IntoResponse {
         .unwrap_or_default();
 
     match super::config_tx::reload_config_from_disk(&state).await {
-        Ok(()) => {
-            tracing::info!(path = %config_path, "Configuration reloaded via dashboard API");
+        Ok(delta) => {
+            tracing::info!(
+                path = %config_path,
+                touched = ?delta.touched(),
+                "Configuration reloaded via dashboard API"
+            );
             (
                 StatusCode::OK,
-                Json(json!({"message": "Configuration reloaded successfully"})),
+                Json(json!({
+                    "message": "Configuration reloaded successfully",
+                    "touched": delta.touched(),
+                    "warnings": alias_warnings(&state.config.load()),
+                })),
             )
         }
         Err(super::config_tx::ConfigTxError::Validation(message)) => {
@@ -138,7 +165,10 @@ pub async fn reload_config(State(state): State<AppState>) -> impl IntoResponse {
                 Json(json!({"error": "reload_failed", "message": message})),
             )
         }
-        Err(super::config_tx::ConfigTxError::Conflict { .. }) => {
+        Err(
+            super::config_tx::ConfigTxError::Conflict { .. }
+            | super::config_tx::ConfigTxError::PreconditionRequired { .. },
+        ) => {
             tracing::error!(
                 path = %config_path,
                 "Configuration reload hit an unexpected version conflict"
@@ -155,9 +185,11 @@ pub async fn reload_config(State(state): State<AppState>) -> impl IntoResponse {
 
 /// PUT /api/dashboard/config/apply — validate, persist, and reload config.
 /// Accepts `{"yaml": "...", "config_version": "..."}`.
-/// If `config_version` is provided and doesn't match the current file, returns 409 Conflict.
+/// `config_version` (or an `If-Match` header) is required: 428 when missing, 409 Conflict
+/// when it doesn't match the current file.
 pub async fn apply_config(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<serde_json::Value>,
 ) -> impl IntoResponse {
     let yaml_str = match body.get("yaml").and_then(|v| v.as_str()) {
@@ -173,7 +205,8 @@ pub async fn apply_config(
     let expected_version = body
         .get("config_version")
         .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
+        .map(|s| s.to_string())
+        .or_else(|| super::config_tx::expected_version(&headers));
 
     let config_path = state
         .config_path
@@ -198,6 +231,87 @@ pub async fn apply_config(
     }
 }
 
+/// POST /api/dashboard/config/preview — validate a proposed config and diff it against disk.
+/// Accepts either `{"yaml": "..."}` (full document) or `{"patch": {...}}` (JSON merge patch
+/// over the current config, kebab-case keys). Nothing is written.
+pub async fn preview_config(
+    State(state): State<AppState>,
+    Json(body): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let (current_yaml, version) = match super::config_tx::read_config_versioned(&state) {
+        Ok(result) => result,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "read_failed", "message": e})),
+            );
+        }
+    };
+    let current = match prism_core::config::Config::from_yaml_raw(&current_yaml) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "read_failed", "message": e.to_string()})),
+            );
+        }
+    };
+
+    let (proposed_yaml, from_patch) = if let Some(s) = body.get("yaml").and_then(|v| v.as_str()) {
+        (s.to_string(), false)
+    } else if let Some(patch) = body.get("patch") {
+        let mut merged = serde_json::to_value(&current).unwrap_or_default();
+        super::config_diff::apply_merge_patch(&mut merged, patch);
+        let yaml = serde_json::from_value::<prism_core::config::Config>(merged)
+            .map_err(|e| e.to_string())
+            .and_then(|cfg| cfg.to_yaml().map_err(|e| e.to_string()));
+        match yaml {
+            Ok(yaml) => (yaml, true),
+            Err(e) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({"valid": false, "errors": [e]})),
+                );
+            }
+        }
+    } else {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(
+                json!({"error": "validation_failed", "message": "Expected a 'yaml' or 'patch' field"}),
+            ),
+        );
+    };
+
+    let proposed = match prism_core::config::Config::from_yaml_raw(&proposed_yaml) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({"valid": false, "errors": [e.to_string()]})),
+            );
+        }
+    };
+
+    let errors: Vec<String> = match prism_core::config::Config::load_from_str(&proposed_yaml) {
+        Ok(_) => Vec::new(),
+        Err(e) => vec![e.to_string()],
+    };
+    let diff = super::config_diff::diff_configs(&current, &proposed);
+
+    let mut response = json!({
+        "valid": errors.is_empty(),
+        "errors": errors,
+        "config_version": version,
+        "changes": diff.changes,
+        "impact": diff.impact,
+    });
+    if from_patch {
+        response["proposed_yaml"] = json!(proposed_yaml);
+    }
+    (StatusCode::OK, Json(response))
+}
+
 /// GET /api/dashboard/config/raw — get raw YAML config file contents with version.
 pub async fn get_raw_config(State(state): State<AppState>) -> impl IntoResponse {
     match super::config_tx::read_config_versioned(&state) {
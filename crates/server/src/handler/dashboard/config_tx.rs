@@ -1,8 +1,27 @@
 use crate::AppState;
+use axum::http::HeaderMap;
+use std::sync::Arc;
+
+/// Serializes read-check-write cycles so a version check cannot race another writer.
+static CONFIG_WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// `If-Match` value that matches any config version. Server-driven writes that
+/// no dashboard client initiated (e.g. finishing an OAuth flow) use it.
+pub const ANY_VERSION: &str = "*";
+
+/// Runtime config snapshot and the config version last computed for it.
+static VERSION_CACHE: std::sync::Mutex<Option<(Arc<prism_core::config::Config>, String)>> =
+    std::sync::Mutex::new(None);
 
 #[derive(Debug)]
 pub enum ConfigTxError {
-    Conflict { current_version: String },
+    Conflict {
+        current_version: String,
+    },
+    /// The write carried no `If-Match` version.
+    PreconditionRequired {
+        current_version: String,
+    },
     Validation(String),
     Internal(String),
 }
@@ -16,6 +35,18 @@ pub fn sha256_hex(content: &str) -> String {
     format!("{:016x}-{}", hasher.finish(), content.len())
 }
 
+/// Extract the expected config version from an `If-Match` header.
+/// Quotes and a weak `W/` prefix are ignored; `*` ([`ANY_VERSION`]) matches any version.
+pub fn expected_version(headers: &HeaderMap) -> Option<String> {
+    let value = headers
+        .get(axum::http::header::IF_MATCH)?
+        .to_str()
+        .ok()?
+        .trim();
+    let value = value.strip_prefix("W/").unwrap_or(value).trim_matches('"');
+    (!value.is_empty()).then(|| value.to_string())
+}
+
 /// Read the current config file and return `(contents, version_hash)`.
 pub fn read_config_versioned(state: &AppState) -> Result<(String, String), String> {
     let config_path = state
@@ -23,12 +54,27 @@ pub fn read_config_versioned(state: &AppState) -> Result<(String, String), Strin
         .lock()
         .map_err(|e| format!("Failed to lock config path: {e}"))?
         .clone();
-    let contents =
-        std::fs::read_to_string(&config_path).map_err(|e| format!("Failed to read config: {e}"))?;
+    let contents = prism_core::managed_store::read_effective_yaml(&config_path)
+        .map_err(|e| format!("Failed to read config: {e}"))?;
     let version = sha256_hex(&contents);
     Ok((contents, version))
 }
 
+/// Version of the current config, hashed once per runtime config generation:
+/// every reload swaps in a new snapshot, so it is re-read only after one.
+pub fn current_version(state: &AppState) -> Result<String, String> {
+    let snapshot = state.config.load_full();
+    let mut cache = VERSION_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached, version)) = cache.as_ref()
+        && Arc::ptr_eq(cached, &snapshot)
+    {
+        return Ok(version.clone());
+    }
+    let (_, version) = read_config_versioned(state)?;
+    *cache = Some((snapshot, version.clone()));
+    Ok(version)
+}
+
 fn config_path(state: &AppState) -> Result<String, ConfigTxError> {
     state
         .config_path
@@ -60,73 +106,167 @@ fn write_yaml_atomically(config_path: &str, yaml: &str) -> Result<(), ConfigTxEr
     Ok(())
 }
 
+fn read_effective(path: &str) -> Result<String, ConfigTxError> {
+    prism_core::managed_store::read_effective_yaml(path)
+        .map_err(|e| ConfigTxError::Internal(format!("Failed to read config: {e}")))
+}
+
+/// Rewrite `original` to match `updated` while keeping the operator's comments and layout.
+/// Falls back to the plain serialization when the edited text would not load identically.
+fn preserve_layout(original: &str, previous: &str, updated: &str) -> String {
+    let as_json = |yaml: &str| {
+        prism_core::config::Config::from_yaml_raw(yaml)
+            .ok()
+            .and_then(|config| serde_json::to_value(config).ok())
+    };
+    let expected = as_json(updated);
+    prism_core::yaml_edit::rewrite_preserving(original, previous, updated)
+        .filter(|text| expected.is_some() && as_json(text) == expected)
+        .unwrap_or_else(|| updated.to_string())
+}
+
+/// Persist a validated config and return its new version.
+///
+/// With the SQLite managed store, providers and auth keys go to the store and the YAML file
+/// keeps its original seed lists; every other section is still written to the file.
+fn persist_config(
+    path: &str,
+    raw_config: &prism_core::config::Config,
+    yaml: &str,
+) -> Result<String, ConfigTxError> {
+    let store = prism_core::managed_store::ManagedStore::open_configured(path, raw_config)
+        .map_err(|e| ConfigTxError::Internal(format!("Failed to open managed store: {e}")))?;
+    let Some(mut store) = store else {
+        write_yaml_atomically(path, yaml)?;
+        return Ok(sha256_hex(yaml));
+    };
+
+    store
+        .replace(&raw_config.providers, &raw_config.auth_keys)
+        .map_err(|e| ConfigTxError::Internal(format!("Failed to write managed store: {e}")))?;
+    let original = std::fs::read_to_string(path).unwrap_or_default();
+    let seed = prism_core::config::Config::from_yaml_raw(&original).ok();
+    let mut file_config = raw_config.clone();
+    if let Some(seed) = &seed {
+        file_config.providers = seed.providers.clone();
+        file_config.auth_keys = seed.auth_keys.clone();
+    }
+    let file_yaml = file_config
+        .to_yaml()
+        .map_err(|e| ConfigTxError::Internal(format!("Failed to serialize config: {e}")))?;
+    let file_yaml = match seed.and_then(|seed| seed.to_yaml().ok()) {
+        Some(previous) => preserve_layout(&original, &previous, &file_yaml),
+        None => file_yaml,
+    };
+    write_yaml_atomically(path, &file_yaml)?;
+    Ok(sha256_hex(&read_effective(path)?))
+}
+
 pub fn apply_runtime_config(
     state: &AppState,
     runtime_config: prism_core::config::Config,
-) -> Result<(), ConfigTxError> {
-    state
-        .auth_runtime
-        .sync_with_config(&runtime_config)
-        .map_err(|e| ConfigTxError::Internal(format!("Failed to sync auth runtime: {e}")))?;
-    state
-        .router
-        .set_oauth_states(state.auth_runtime.oauth_snapshot());
-    state.router.update_from_config(&runtime_config);
-    state
-        .catalog
-        .update_from_credentials(&state.router.credential_map());
-    state.rate_limiter.update_config(&runtime_config.rate_limit);
-    state
-        .cost_calculator
-        .update_prices(&runtime_config.model_prices);
-    state.http_client_pool.clear();
-    state.config.store(std::sync::Arc::new(runtime_config));
-    Ok(())
+) -> Result<prism_core::config::ReloadDelta, ConfigTxError> {
+    crate::reload::apply_config(state, runtime_config).map_err(ConfigTxError::Internal)
 }
 
+/// Check the write's `If-Match` version against the file. A missing version is
+/// rejected so clients cannot overwrite edits they have not seen.
 fn ensure_expected_version(
     contents: &str,
     expected_version: Option<&str>,
 ) -> Result<(), ConfigTxError> {
-    if let Some(expected) = expected_version {
-        let current = sha256_hex(contents);
-        if current != expected {
-            return Err(ConfigTxError::Conflict {
-                current_version: current,
-            });
-        }
+    let current = sha256_hex(contents);
+    match expected_version {
+        None => Err(ConfigTxError::PreconditionRequired {
+            current_version: current,
+        }),
+        Some(ANY_VERSION) => Ok(()),
+        Some(expected) if expected == current => Ok(()),
+        Some(_) => Err(ConfigTxError::Conflict {
+            current_version: current,
+        }),
     }
+}
+
+/// Apply the config persisted as `version` and remember the version for the
+/// new runtime snapshot.
+fn apply_persisted(
+    state: &AppState,
+    runtime_config: prism_core::config::Config,
+    version: &str,
+) -> Result<(), ConfigTxError> {
+    apply_runtime_config(state, runtime_config)?;
+    *VERSION_CACHE.lock().unwrap_or_else(|e| e.into_inner()) =
+        Some((state.config.load_full(), version.to_string()));
     Ok(())
 }
 
 pub async fn update_config_file_public(
     state: &AppState,
+    expected_version: Option<&str>,
     mutate: impl FnOnce(&mut prism_core::config::Config),
-) -> Result<String, String> {
-    update_config_versioned(state, None, mutate)
-        .await
-        .map_err(|e| match e {
-            ConfigTxError::Conflict { .. } => "conflict".to_string(),
-            ConfigTxError::Validation(msg) | ConfigTxError::Internal(msg) => msg,
-        })
+) -> Result<String, ConfigTxError> {
+    update_config_versioned(state, expected_version, mutate).await
 }
 
 /// Read current config from disk, mutate the raw YAML-backed model, persist atomically,
-/// then rebuild runtime state from the written config.
+/// then rebuild runtime state from the written config. Comments and layout of untouched
+/// sections are kept.
 pub async fn update_config_versioned(
     state: &AppState,
     expected_version: Option<&str>,
     mutate: impl FnOnce(&mut prism_core::config::Config),
 ) -> Result<String, ConfigTxError> {
+    try_update_config_versioned(state, expected_version, |config| {
+        mutate(config);
+        Ok::<(), std::convert::Infallible>(())
+    })
+    .await
+    .map_err(|error| match error {
+        TryUpdateError::Tx(error) => error,
+        TryUpdateError::Aborted(never) => match never {},
+    })
+}
+
+/// Failure of [`try_update_config_versioned`].
+#[derive(Debug)]
+pub enum TryUpdateError<E> {
+    /// The mutation aborted; nothing was written or reloaded.
+    Aborted(E),
+    Tx(ConfigTxError),
+}
+
+impl<E> From<ConfigTxError> for TryUpdateError<E> {
+    fn from(error: ConfigTxError) -> Self {
+        Self::Tx(error)
+    }
+}
+
+/// [`update_config_versioned`] with a mutation that can abort the write, e.g. when
+/// the entry it edits is gone. The mutation sees the file under the write lock, so
+/// its checks cannot race another writer.
+pub async fn try_update_config_versioned<E>(
+    state: &AppState,
+    expected_version: Option<&str>,
+    mutate: impl FnOnce(&mut prism_core::config::Config) -> Result<(), E>,
+) -> Result<String, TryUpdateError<E>> {
     let path = config_path(state)?;
-    let contents = std::fs::read_to_string(&path)
-        .map_err(|e| ConfigTxError::Internal(format!("Failed to read config: {e}")))?;
+    let _guard = CONFIG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let contents = read_effective(&path)?;
 
     ensure_expected_version(&contents, expected_version)?;
 
     let mut raw_config = prism_core::config::Config::from_yaml_raw(&contents)
         .map_err(|e| ConfigTxError::Internal(format!("Failed to parse config: {e}")))?;
-    mutate(&mut raw_config);
+    // Derived ids let `mutate` address entries the file stores without one.
+    raw_config.assign_provider_ids();
+    raw_config.assign_auth_key_ids();
+    let previous_yaml = raw_config
+        .to_yaml()
+        .map_err(|e| ConfigTxError::Internal(format!("Failed to serialize config: {e}")))?;
+    mutate(&mut raw_config).map_err(TryUpdateError::Aborted)?;
+    raw_config.assign_provider_ids();
+    raw_config.assign_auth_key_ids();
 
     let yaml = raw_config
         .to_yaml()
@@ -134,10 +274,11 @@ pub async fn update_config_versioned(
     let runtime_config = prism_core::config::Config::load_from_str(&yaml)
         .map_err(|e| ConfigTxError::Validation(format!("Failed to load runtime config: {e}")))?;
 
-    write_yaml_atomically(&path, &yaml)?;
-    apply_runtime_config(state, runtime_config)?;
+    let file_yaml = preserve_layout(&contents, &previous_yaml, &yaml);
+    let version = persist_config(&path, &raw_config, &file_yaml)?;
+    apply_persisted(state, runtime_config, &version)?;
 
-    Ok(sha256_hex(&yaml))
+    Ok(version)
 }
 
 /// Validate a full YAML document, persist it atomically, then rebuild runtime state.
@@ -148,21 +289,22 @@ pub async fn apply_yaml_versioned(
 ) -> Result<String, ConfigTxError> {
     let runtime_config = prism_core::config::Config::load_from_str(yaml)
         .map_err(|e| ConfigTxError::Validation(e.to_string()))?;
+    let raw_config = prism_core::config::Config::from_yaml_raw(yaml)
+        .map_err(|e| ConfigTxError::Validation(e.to_string()))?;
     let path = config_path(state)?;
+    let _guard = CONFIG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
 
-    if expected_version.is_some() {
-        let contents = std::fs::read_to_string(&path)
-            .map_err(|e| ConfigTxError::Internal(format!("Failed to read config: {e}")))?;
-        ensure_expected_version(&contents, expected_version)?;
-    }
+    ensure_expected_version(&read_effective(&path)?, expected_version)?;
 
-    write_yaml_atomically(&path, yaml)?;
-    apply_runtime_config(state, runtime_config)?;
+    let version = persist_config(&path, &raw_config, yaml)?;
+    apply_persisted(state, runtime_config, &version)?;
 
-    Ok(sha256_hex(yaml))
+    Ok(version)
 }
 
-pub async fn reload_config_from_disk(state: &AppState) -> Result<(), ConfigTxError> {
+pub async fn reload_config_from_disk(
+    state: &AppState,
+) -> Result<prism_core::config::ReloadDelta, ConfigTxError> {
     let path = config_path(state)?;
     let runtime_config = prism_core::config::Config::load(&path)
         .map_err(|e| ConfigTxError::Validation(e.to_string()))?;
@@ -125,6 +125,19 @@ pub(crate) const ENDPOINT_SPECS: &[EndpointSpec] = &[
         surface_id: Some("openai_chat"),
         note: None,
     },
+    EndpointSpec {
+        id: "openai_chat_ws",
+        family: IngressProtocol::OpenAi,
+        method: "GET",
+        path: "/v1/ws/chat",
+        description: "WebSocket bridge over streaming Chat Completions.",
+        scope: EndpointScope::Public,
+        transport: EndpointTransport::WebSocket,
+        operation: Operation::Generate,
+        stream_transport: StreamTransport::WebSocketEvents,
+        surface_id: Some("openai_chat"),
+        note: Some("One text frame per chat.completion.chunk, ending with [DONE]."),
+    },
     EndpointSpec {
         id: "openai_completions",
         family: IngressProtocol::OpenAi,
@@ -203,6 +216,19 @@ pub(crate) const ENDPOINT_SPECS: &[EndpointSpec] = &[
         surface_id: Some("claude_count_tokens"),
         note: None,
     },
+    EndpointSpec {
+        id: "openai_moderations",
+        family: IngressProtocol::OpenAi,
+        method: "POST",
+        path: "/v1/moderations",
+        description: "Direct proxy to OpenAI moderations for OpenAI-format providers.",
+        scope: EndpointScope::Public,
+        transport: EndpointTransport::Http,
+        operation: Operation::Moderate,
+        stream_transport: StreamTransport::None,
+        surface_id: None,
+        note: Some("Responses are passed through unchanged; no protocol translation."),
+    },
     EndpointSpec {
         id: "gemini_models",
         family: IngressProtocol::Gemini,
@@ -104,6 +104,7 @@ fn provider_row(state: &AppState, provider: &ProviderKeyEntry) -> ProviderAtlasR
     let (status, tone, _) = provider_runtime_status(state, provider);
 
     ProviderAtlasRow {
+        id: provider.id.clone(),
         provider: provider.name.clone(),
         format: provider.format.as_str().to_string(),
         auth,
@@ -164,6 +164,7 @@ pub struct TrafficLabResponse {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProviderAtlasRow {
+    pub id: Option<String>,
     pub provider: String,
     pub format: String,
     pub auth: UiText,
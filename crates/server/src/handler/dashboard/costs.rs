@@ -0,0 +1,15 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use prism_core::request_log::TagCostQuery;
+
+/// GET /api/dashboard/costs/by-tag — spend and tokens per tag from the request log.
+pub async fn by_tag(
+    State(state): State<AppState>,
+    Query(query): Query<TagCostQuery>,
+) -> impl IntoResponse {
+    let report = state.log_store.cost_by_tag(&query).await;
+    (StatusCode::OK, Json(report))
+}
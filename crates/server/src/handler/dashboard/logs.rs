@@ -3,7 +3,7 @@ use axum::Json;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use prism_core::request_log::{LogQuery, StatsQuery};
+use prism_core::request_log::{ErrorGroupQuery, LogQuery, StatsQuery};
 
 /// GET /api/dashboard/logs — query request logs with filters.
 pub async fn query_logs(
@@ -31,6 +31,15 @@ pub async fn log_stats(
     (StatusCode::OK, Json(stats))
 }
 
+/// GET /api/dashboard/logs/errors — recent failures grouped by cause.
+pub async fn error_groups(
+    State(state): State<AppState>,
+    Query(query): Query<ErrorGroupQuery>,
+) -> impl IntoResponse {
+    let groups = state.log_store.error_groups(&query).await;
+    (StatusCode::OK, Json(groups))
+}
+
 /// GET /api/dashboard/logs/filters — available filter options.
 pub async fn filter_options(State(state): State<AppState>) -> impl IntoResponse {
     let options = state.log_store.filter_options().await;
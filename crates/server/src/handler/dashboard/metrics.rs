@@ -0,0 +1,76 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct LatencyQuery {
+    pub model: Option<String>,
+}
+
+/// GET /api/dashboard/metrics/latency — per-model and per-provider latency histograms.
+///
+/// With `?model=` only that model's histogram is returned. Models beyond the tracked
+/// limit are folded into the `other` series.
+pub async fn latency(
+    State(state): State<AppState>,
+    Query(query): Query<LatencyQuery>,
+) -> impl IntoResponse {
+    let Some(model) = query.model else {
+        return (StatusCode::OK, Json(state.metrics.latency_breakdown()));
+    };
+    match state.metrics.model_latency_snapshot(&model) {
+        Some(histogram) => (
+            StatusCode::OK,
+            Json(json!({ "model": model, "latency_ms": histogram })),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "not_found",
+                "message": format!("No latency data for model '{model}'"),
+            })),
+        ),
+    }
+}
+
+/// POST /api/dashboard/metrics/reset — zero all in-memory metrics.
+///
+/// The response carries the snapshot taken just before the reset.
+pub async fn reset(State(state): State<AppState>) -> impl IntoResponse {
+    let previous = state.metrics.reset();
+    tracing::info!("Metrics reset from dashboard");
+    (
+        StatusCode::OK,
+        Json(json!({ "reset": true, "previous": previous })),
+    )
+}
+
+/// POST /api/dashboard/metrics/reset/credentials/{credential} — clear circuit breaker,
+/// latency, and cooldown state for credentials matching a name or id.
+pub async fn reset_credential(
+    State(state): State<AppState>,
+    Path(credential): Path<String>,
+) -> impl IntoResponse {
+    let previous = state.router.reset_credential_stats(&credential);
+    if previous.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "not_found",
+                "message": format!("No credential named '{credential}'"),
+            })),
+        );
+    }
+    tracing::info!(
+        credential = credential.as_str(),
+        "Credential stats reset from dashboard"
+    );
+    (
+        StatusCode::OK,
+        Json(json!({ "credential": credential, "reset": true, "previous": previous })),
+    )
+}
@@ -1,13 +1,34 @@
 pub mod auth;
 pub mod auth_keys;
 pub mod auth_profiles;
+pub mod cluster;
+pub mod config_bundle;
+pub mod config_diff;
 pub mod config_ops;
 pub mod config_tx;
 pub mod control_plane;
 pub mod control_plane_workspace;
+pub mod costs;
 pub mod logs;
+pub mod metrics;
 pub mod providers;
+pub mod reports;
+pub mod requests;
 pub mod routing;
 pub mod system;
 pub mod tenant;
+pub mod translate;
 pub mod websocket;
+
+use crate::middleware::dashboard_auth::Claims;
+
+/// Record a dashboard state change on the `prism::audit` log target.
+pub(crate) fn audit(claims: Option<&Claims>, action: &str, resource: &str) {
+    tracing::info!(
+        target: "prism::audit",
+        actor = claims.map_or("unknown", |c| c.sub.as_str()),
+        action,
+        resource,
+        "Dashboard action"
+    );
+}
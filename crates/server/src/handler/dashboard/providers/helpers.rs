@@ -23,6 +23,16 @@ pub(super) fn config_tx_error_response(
                 "current_version": current_version
             })),
         ),
+        crate::handler::dashboard::config_tx::ConfigTxError::PreconditionRequired {
+            current_version,
+        } => (
+            StatusCode::PRECONDITION_REQUIRED,
+            Json(json!({
+                "error": "precondition_required",
+                "message": "If-Match config version required",
+                "current_version": current_version
+            })),
+        ),
         crate::handler::dashboard::config_tx::ConfigTxError::Validation(message) => {
             validation_error(message)
         }
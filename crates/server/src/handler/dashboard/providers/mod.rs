@@ -6,12 +6,26 @@ mod read;
 
 use serde::{Deserialize, Serialize};
 
-pub use mutation::{create_provider, delete_provider, update_provider};
+pub use mutation::{
+    bulk_create_providers, create_provider, delete_provider, disable_provider, duplicate_provider,
+    enable_provider, update_provider,
+};
 pub use probe::{
     cached_probe_result, fetch_models, health_check, presentation_preview, test_request,
 };
 pub use read::{get_provider, list_providers};
 
+/// Map a provider path segment to the provider name. The segment is either the
+/// persistent provider `id` or, as a deprecated fallback, the provider name itself.
+pub(crate) fn resolve_provider_name(state: &crate::AppState, id_or_name: &str) -> String {
+    state
+        .config
+        .load()
+        .find_provider(id_or_name)
+        .map(|entry| entry.name.clone())
+        .unwrap_or_else(|| id_or_name.to_string())
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ProbeStatus {
@@ -0,0 +1,209 @@
+use super::request::{BulkCreateProvidersRequest, DuplicateProviderRequest};
+use super::{PreparedProviderCreate, prepare_provider_create};
+use crate::AppState;
+use crate::handler::dashboard::config_tx::{TryUpdateError, try_update_config_versioned};
+use crate::handler::dashboard::providers::auth_profile_state::seed_runtime_oauth_states;
+use crate::handler::dashboard::providers::helpers::{config_tx_error_response, validation_error};
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Debug, Serialize)]
+struct BulkItemResult {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    /// `created` or `rejected`.
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<serde_json::Value>,
+}
+
+/// POST /api/dashboard/providers/bulk — create many providers in one config write.
+/// Each item is validated independently; valid items are written together and invalid
+/// items are reported back without blocking the rest.
+pub async fn bulk_create_providers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<BulkCreateProvidersRequest>,
+) -> impl IntoResponse {
+    let expected = crate::handler::dashboard::config_tx::expected_version(&headers);
+    if body.providers.is_empty() {
+        return validation_error("providers must not be empty");
+    }
+
+    // Names are checked under the write lock, against the file that is about to be
+    // written, so a provider created concurrently is reported as a duplicate here.
+    let mut results = Vec::with_capacity(body.providers.len());
+    let mut accepted: Vec<PreparedProviderCreate> = Vec::new();
+    let outcome = try_update_config_versioned(&state, expected.as_deref(), |config| {
+        let mut taken: Vec<String> = config.providers.iter().map(|e| e.name.clone()).collect();
+        for (index, item) in body.providers.iter().enumerate() {
+            let taken_refs: Vec<&str> = taken.iter().map(String::as_str).collect();
+            match prepare_provider_create(item, &taken_refs) {
+                Ok(prepared) => {
+                    taken.push(item.name.clone());
+                    results.push(BulkItemResult {
+                        index,
+                        id: prepared.entry.id.clone(),
+                        name: item.name.clone(),
+                        status: "created",
+                        error: None,
+                    });
+                    accepted.push(prepared);
+                }
+                Err((_, Json(error))) => results.push(BulkItemResult {
+                    index,
+                    id: None,
+                    name: item.name.clone(),
+                    status: "rejected",
+                    error: Some(error),
+                }),
+            }
+        }
+        if accepted.is_empty() {
+            return Err(());
+        }
+        config
+            .providers
+            .extend(accepted.iter().map(|p| p.entry.clone()));
+        Ok(())
+    })
+    .await;
+
+    let created = accepted.len();
+    let rejected = results.len() - created;
+    match outcome {
+        Ok(_) => {}
+        Err(TryUpdateError::Aborted(())) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": "validation_failed",
+                    "message": "No provider entries passed validation",
+                    "created": 0,
+                    "rejected": rejected,
+                    "results": results,
+                })),
+            );
+        }
+        Err(TryUpdateError::Tx(error)) => {
+            tracing::error!(error = ?error, "Failed to bulk create providers");
+            return config_tx_error_response(error);
+        }
+    }
+
+    for prepared in &accepted {
+        if let Err(err) =
+            seed_runtime_oauth_states(&state, &prepared.entry.name, &prepared.runtime_oauth_states)
+        {
+            tracing::error!(
+                name = %prepared.entry.name,
+                error = %err,
+                "Provider created but runtime oauth seeding failed"
+            );
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "runtime_auth_seed_failed", "message": err})),
+            );
+        }
+    }
+
+    tracing::info!(created, rejected, "Providers bulk created via dashboard");
+    (
+        StatusCode::CREATED,
+        Json(json!({
+            "message": format!("Created {created} provider(s)"),
+            "created": created,
+            "rejected": rejected,
+            "results": results,
+        })),
+    )
+}
+
+/// POST /api/dashboard/providers/:id/duplicate — clone an entry under a new name.
+/// When `api_key` is given the clone uses it instead of the source credentials.
+pub async fn duplicate_provider(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(source): Path<String>,
+    Json(body): Json<DuplicateProviderRequest>,
+) -> impl IntoResponse {
+    let expected = crate::handler::dashboard::config_tx::expected_version(&headers);
+    let source = crate::handler::dashboard::providers::resolve_provider_name(&state, &source);
+    if body.name.is_empty() {
+        return validation_error("name is required");
+    }
+
+    let new_name = body.name.clone();
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let clone_id = new_id.clone();
+    let source_for_log = source.clone();
+    // Clone from the raw on-disk entry so env:// and file:// references are preserved.
+    // Both checks run under the write lock, against the file that is about to be written.
+    match try_update_config_versioned(&state, expected.as_deref(), move |config| {
+        let Some(original) = config.providers.iter().find(|entry| entry.name == source) else {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "not_found", "message": "Provider not found"})),
+            ));
+        };
+        if config
+            .providers
+            .iter()
+            .any(|entry| entry.name == body.name)
+        {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(
+                    json!({"error": "duplicate_name", "message": format!("Provider name '{}' already exists", body.name)}),
+                ),
+            ));
+        }
+        let mut clone = original.clone();
+        clone.id = Some(clone_id);
+        clone.name = body.name;
+        if let Some(api_key) = body.api_key {
+            clone.api_key = api_key;
+            clone.auth_profiles.clear();
+            clone.credential_source = None;
+            clone.token_source = None;
+        }
+        if let Some(disabled) = body.disabled {
+            clone.disabled = disabled;
+        }
+        config.providers.push(clone);
+        Ok(())
+    })
+    .await
+    {
+        Ok(_) => {
+            tracing::info!(
+                source = %source_for_log,
+                name = %new_name,
+                "Provider duplicated via dashboard"
+            );
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "message": "Provider duplicated successfully",
+                    "id": new_id,
+                    "name": new_name,
+                })),
+            )
+        }
+        Err(TryUpdateError::Aborted(response)) => response,
+        Err(TryUpdateError::Tx(error)) => {
+            tracing::error!(
+                source = %source_for_log,
+                error = ?error,
+                "Failed to duplicate provider"
+            );
+            config_tx_error_response(error)
+        }
+    }
+}
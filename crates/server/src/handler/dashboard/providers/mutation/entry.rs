@@ -43,9 +43,11 @@ pub(super) fn create_provider_entry(
     auth_profiles: Vec<AuthProfileEntry>,
 ) -> ProviderKeyEntry {
     ProviderKeyEntry {
+        id: Some(uuid::Uuid::new_v4().to_string()),
         name: body.name.clone(),
         format,
         upstream: Some(upstream),
+        executor: body.executor.clone(),
         api_key: body.api_key.clone().unwrap_or_default(),
         base_url: body.base_url.clone(),
         proxy_url: body.proxy_url.clone(),
@@ -59,11 +61,19 @@ pub(super) fn create_provider_entry(
         wire_api: resolve_wire_api(upstream, body.wire_api.as_deref(), None),
         weight: body.weight,
         region: body.region.clone(),
+        group: body.group.clone(),
+        tags: body.tags.clone(),
+        quota: body.quota.clone(),
         credential_source: None,
+        token_source: None,
         auth_profiles,
         vertex: body.vertex,
         vertex_project: body.vertex_project.clone(),
         vertex_location: body.vertex_location.clone(),
+        allowed_server_tools: body.allowed_server_tools.clone(),
+        allowed_betas: body.allowed_betas.clone(),
+        signing: None,
+        json_repair: false,
     }
 }
 
@@ -79,6 +89,9 @@ pub(super) fn prepare_provider_update(
 ) -> PreparedProviderUpdate {
     let mut candidate_entry = existing_entry.clone();
     candidate_entry.upstream = Some(upstream);
+    if let Some(ref executor) = request.executor {
+        candidate_entry.executor = executor.clone();
+    }
 
     if let Some(ref key) = request.api_key {
         candidate_entry.api_key = key.clone();
@@ -121,6 +134,15 @@ pub(super) fn prepare_provider_update(
     if let Some(ref region) = request.region {
         candidate_entry.region = region.clone();
     }
+    if let Some(ref group) = request.group {
+        candidate_entry.group = group.clone();
+    }
+    if let Some(ref tags) = request.tags {
+        candidate_entry.tags = tags.clone();
+    }
+    if let Some(ref quota) = request.quota {
+        candidate_entry.quota = quota.clone();
+    }
     if let Some(ref presentation_opt) = request.upstream_presentation {
         candidate_entry.upstream_presentation = presentation_opt
             .clone()
@@ -135,6 +157,12 @@ pub(super) fn prepare_provider_update(
     if let Some(ref location) = request.vertex_location {
         candidate_entry.vertex_location = location.clone();
     }
+    if let Some(ref tools) = request.allowed_server_tools {
+        candidate_entry.allowed_server_tools = tools.clone();
+    }
+    if let Some(ref betas) = request.allowed_betas {
+        candidate_entry.allowed_betas = betas.clone();
+    }
 
     let runtime_oauth_states = auth_profiles.map(strip_runtime_oauth_data);
 
@@ -201,6 +229,9 @@ pub(super) fn apply_provider_update(
     if let Some(ref region) = request.region {
         entry.region = region.clone();
     }
+    if let Some(ref tags) = request.tags {
+        entry.tags = tags.clone();
+    }
     if let Some(ref presentation_opt) = request.upstream_presentation {
         entry.upstream_presentation = presentation_opt
             .clone()
@@ -215,4 +246,10 @@ pub(super) fn apply_provider_update(
     if let Some(ref location) = request.vertex_location {
         entry.vertex_location = location.clone();
     }
+    if let Some(ref tools) = request.allowed_server_tools {
+        entry.allowed_server_tools = tools.clone();
+    }
+    if let Some(ref betas) = request.allowed_betas {
+        entry.allowed_betas = betas.clone();
+    }
 }
@@ -1,5 +1,7 @@
+mod bulk;
 mod entry;
 mod request;
+mod toggle;
 
 use super::auth_profile_state::{
     normalize_auth_profiles, seed_runtime_oauth_states, strip_runtime_oauth_data,
@@ -11,75 +13,86 @@ use super::helpers::{
 use crate::AppState;
 use axum::Json;
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use prism_core::dashboard_api::{Message, ProviderCreated};
 use serde_json::json;
 
+pub use self::bulk::{bulk_create_providers, duplicate_provider};
 use self::entry::{apply_provider_update, create_provider_entry, prepare_provider_update};
 pub use self::request::{CreateProviderRequest, UpdateProviderRequest};
+pub use self::toggle::{disable_provider, enable_provider};
 
-/// POST /api/dashboard/providers
-pub async fn create_provider(
-    State(state): State<AppState>,
-    Json(body): Json<CreateProviderRequest>,
-) -> impl IntoResponse {
+/// Fully validated provider entry ready to be written, plus runtime-only OAuth material.
+struct PreparedProviderCreate {
+    entry: prism_core::config::ProviderKeyEntry,
+    runtime_oauth_states: Vec<(String, prism_core::auth_profile::OAuthTokenState)>,
+}
+
+/// Validate a create request against the names already taken and build the new entry.
+fn prepare_provider_create(
+    body: &CreateProviderRequest,
+    taken_names: &[&str],
+) -> Result<PreparedProviderCreate, (StatusCode, Json<serde_json::Value>)> {
     if body.name.is_empty() {
-        return (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(json!({"error": "validation_failed", "message": "name is required"})),
-        );
+        return Err(validation_error("name is required"));
     }
     if !is_valid_format(&body.format) {
-        return (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(
-                json!({"error": "validation_failed", "message": "Invalid format. Must be one of: openai, claude, gemini"}),
-            ),
-        );
+        return Err(validation_error(
+            "Invalid format. Must be one of: openai, claude, gemini",
+        ));
     }
     let format: prism_core::provider::Format = body
         .format
         .parse()
         .unwrap_or(prism_core::provider::Format::OpenAI);
-    let upstream = match parse_upstream_kind(format, body.upstream.as_deref()) {
-        Ok(value) => value,
-        Err(response) => return response,
-    };
+    let upstream = parse_upstream_kind(format, body.upstream.as_deref())?;
 
-    let auth_profiles = match normalize_auth_profiles(&body.auth_profiles) {
-        Ok(profiles) => profiles,
-        Err(response) => return response,
-    };
-    if let Err(response) = validate_auth_shape(body.api_key.as_deref(), &auth_profiles) {
-        return response;
-    }
-    if let Err(response) =
-        validate_provider_auth_profiles(format, upstream, body.base_url.as_deref(), &auth_profiles)
-    {
-        return response;
-    }
+    let auth_profiles = normalize_auth_profiles(&body.auth_profiles)?;
+    validate_auth_shape(body.api_key.as_deref(), &auth_profiles)?;
+    validate_provider_auth_profiles(format, upstream, body.base_url.as_deref(), &auth_profiles)?;
 
-    {
-        let config = state.config.load();
-        if config.providers.iter().any(|entry| entry.name == body.name) {
-            return (
-                StatusCode::CONFLICT,
-                Json(
-                    json!({"error": "duplicate_name", "message": format!("Provider name '{}' already exists", body.name)}),
-                ),
-            );
-        }
+    if taken_names.contains(&body.name.as_str()) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(
+                json!({"error": "duplicate_name", "message": format!("Provider name '{}' already exists", body.name)}),
+            ),
+        ));
     }
 
-    let provider_name = body.name.clone();
     let (auth_profiles, runtime_oauth_states) = strip_runtime_oauth_data(auth_profiles);
-    let new_entry = create_provider_entry(&body, format, upstream, auth_profiles);
+    let entry = create_provider_entry(body, format, upstream, auth_profiles);
+    entry.validate_shape().map_err(validation_error)?;
 
-    if let Err(message) = new_entry.validate_shape() {
-        return validation_error(message);
-    }
+    Ok(PreparedProviderCreate {
+        entry,
+        runtime_oauth_states,
+    })
+}
+
+/// POST /api/dashboard/providers
+pub async fn create_provider(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<CreateProviderRequest>,
+) -> impl IntoResponse {
+    let expected = crate::handler::dashboard::config_tx::expected_version(&headers);
+    let prepared = {
+        let config = state.config.load();
+        let taken: Vec<&str> = config.providers.iter().map(|e| e.name.as_str()).collect();
+        prepare_provider_create(&body, &taken)
+    };
+    let PreparedProviderCreate {
+        entry: new_entry,
+        runtime_oauth_states,
+    } = match prepared {
+        Ok(prepared) => prepared,
+        Err(response) => return response,
+    };
+    let provider_name = body.name.clone();
 
-    match update_config_file(&state, |config| {
+    match update_config_file(&state, expected.as_deref(), |config| {
         config.providers.push(new_entry.clone());
     })
     .await
@@ -105,7 +118,10 @@ pub async fn create_provider(
             );
             (
                 StatusCode::CREATED,
-                Json(json!({"message": "Provider created successfully"})),
+                Json(json!(ProviderCreated {
+                    message: "Provider created successfully".to_string(),
+                    id: new_entry.id,
+                })),
             )
         }
         Err(error) => {
@@ -122,9 +138,12 @@ pub async fn create_provider(
 /// PATCH /api/dashboard/providers/:name
 pub async fn update_provider(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(name): Path<String>,
     Json(body): Json<UpdateProviderRequest>,
 ) -> impl IntoResponse {
+    let expected = crate::handler::dashboard::config_tx::expected_version(&headers);
+    let name = super::resolve_provider_name(&state, &name);
     let existing_entry = {
         let config = state.config.load();
         match config.providers.iter().find(|entry| entry.name == name) {
@@ -189,7 +208,7 @@ pub async fn update_provider(
     let body_for_write = body.clone();
     let auth_profiles_for_write = prepared.auth_profiles_for_write.clone();
 
-    match update_config_file(&state, move |config| {
+    match update_config_file(&state, expected.as_deref(), move |config| {
         if let Some(entry) = config.providers.iter_mut().find(|entry| entry.name == name) {
             apply_provider_update(entry, &body_for_write, auth_profiles_for_write.as_ref());
         }
@@ -230,8 +249,11 @@ pub async fn update_provider(
 /// DELETE /api/dashboard/providers/:name
 pub async fn delete_provider(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(name): Path<String>,
 ) -> impl IntoResponse {
+    let expected = crate::handler::dashboard::config_tx::expected_version(&headers);
+    let name = super::resolve_provider_name(&state, &name);
     {
         let config = state.config.load();
         if !config.providers.iter().any(|entry| entry.name == name) {
@@ -243,7 +265,7 @@ pub async fn delete_provider(
     }
 
     let name_for_log = name.clone();
-    match update_config_file(&state, move |config| {
+    match update_config_file(&state, expected.as_deref(), move |config| {
         config.providers.retain(|entry| entry.name != name);
     })
     .await
@@ -252,7 +274,7 @@ pub async fn delete_provider(
             tracing::info!(provider = %name_for_log, "Provider deleted via dashboard");
             (
                 StatusCode::OK,
-                Json(json!({"message": "Provider deleted successfully"})),
+                Json(json!(Message::new("Provider deleted successfully"))),
             )
         }
         Err(error) => {
@@ -268,9 +290,10 @@ pub async fn delete_provider(
 
 async fn update_config_file(
     state: &AppState,
+    expected_version: Option<&str>,
     mutate: impl FnOnce(&mut prism_core::config::Config),
 ) -> Result<(), super::super::config_tx::ConfigTxError> {
-    super::super::config_tx::update_config_versioned(state, None, mutate)
+    super::super::config_tx::update_config_versioned(state, expected_version, mutate)
         .await
         .map(|_| ())
 }
@@ -8,6 +8,8 @@ pub struct CreateProviderRequest {
     #[serde(default)]
     pub upstream: Option<String>,
     #[serde(default)]
+    pub executor: Option<String>,
+    #[serde(default)]
     pub api_key: Option<String>,
     #[serde(default)]
     pub auth_profiles: Vec<AuthProfileEntry>,
@@ -32,6 +34,12 @@ pub struct CreateProviderRequest {
     #[serde(default)]
     pub region: Option<String>,
     #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub quota: Option<prism_core::config::CredentialQuotaConfig>,
+    #[serde(default)]
     pub upstream_presentation: Option<prism_core::presentation::UpstreamPresentationConfig>,
     #[serde(default)]
     pub vertex: bool,
@@ -39,6 +47,10 @@ pub struct CreateProviderRequest {
     pub vertex_project: Option<String>,
     #[serde(default)]
     pub vertex_location: Option<String>,
+    #[serde(default)]
+    pub allowed_server_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_betas: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -46,6 +58,8 @@ pub struct UpdateProviderRequest {
     #[serde(default)]
     pub upstream: Option<Option<String>>,
     #[serde(default)]
+    pub executor: Option<Option<String>>,
+    #[serde(default)]
     pub api_key: Option<String>,
     #[serde(default)]
     pub auth_profiles: Option<Vec<AuthProfileEntry>>,
@@ -70,6 +84,12 @@ pub struct UpdateProviderRequest {
     #[serde(default)]
     pub region: Option<Option<String>>,
     #[serde(default)]
+    pub group: Option<Option<String>>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub quota: Option<Option<prism_core::config::CredentialQuotaConfig>>,
+    #[serde(default)]
     pub upstream_presentation: Option<Option<prism_core::presentation::UpstreamPresentationConfig>>,
     #[serde(default)]
     pub vertex: Option<bool>,
@@ -77,6 +97,24 @@ pub struct UpdateProviderRequest {
     pub vertex_project: Option<Option<String>>,
     #[serde(default)]
     pub vertex_location: Option<Option<String>>,
+    #[serde(default)]
+    pub allowed_server_tools: Option<Option<Vec<String>>>,
+    #[serde(default)]
+    pub allowed_betas: Option<Option<Vec<String>>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BulkCreateProvidersRequest {
+    pub providers: Vec<CreateProviderRequest>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DuplicateProviderRequest {
+    pub name: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub disabled: Option<bool>,
 }
 
 fn default_weight() -> u32 {
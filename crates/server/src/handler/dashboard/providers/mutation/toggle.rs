@@ -0,0 +1,83 @@
+use super::update_config_file;
+use crate::AppState;
+use crate::handler::dashboard::providers::helpers::config_tx_error_response;
+use crate::middleware::dashboard_auth::Claims;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::{Extension, Json};
+use prism_core::dashboard_api::ProviderToggled;
+use serde_json::json;
+
+/// POST /api/dashboard/providers/:id/disable
+pub async fn disable_provider(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    claims: Option<Extension<Claims>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    set_provider_disabled(&state, &headers, claims, &id, true).await
+}
+
+/// POST /api/dashboard/providers/:id/enable
+pub async fn enable_provider(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    claims: Option<Extension<Claims>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    set_provider_disabled(&state, &headers, claims, &id, false).await
+}
+
+/// Flip `disabled` on one provider. Already being in the requested state is a
+/// no-op that skips the config write.
+async fn set_provider_disabled(
+    state: &AppState,
+    headers: &HeaderMap,
+    claims: Option<Extension<Claims>>,
+    id: &str,
+    disabled: bool,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let expected = crate::handler::dashboard::config_tx::expected_version(headers);
+    let name = crate::handler::dashboard::providers::resolve_provider_name(state, id);
+    let current = state
+        .config
+        .load()
+        .providers
+        .iter()
+        .find(|entry| entry.name == name)
+        .map(|entry| entry.disabled);
+    let Some(current) = current else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "not_found", "message": "Provider not found"})),
+        );
+    };
+    if current != disabled {
+        let target = name.clone();
+        if let Err(error) = update_config_file(state, expected.as_deref(), move |config| {
+            if let Some(entry) = config.providers.iter_mut().find(|e| e.name == target) {
+                entry.disabled = disabled;
+            }
+        })
+        .await
+        {
+            tracing::error!(provider = %name, error = ?error, "Failed to toggle provider");
+            return config_tx_error_response(error);
+        }
+        let action = if disabled {
+            "provider.disable"
+        } else {
+            "provider.enable"
+        };
+        crate::handler::dashboard::audit(claims.as_deref(), action, &name);
+    }
+    (
+        StatusCode::OK,
+        Json(json!(ProviderToggled {
+            name,
+            disabled,
+            changed: current != disabled,
+        })),
+    )
+}
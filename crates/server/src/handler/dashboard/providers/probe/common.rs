@@ -96,9 +96,7 @@ pub(super) fn provider_name_from_config(
 ) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
     let config = state.config.load();
     config
-        .providers
-        .iter()
-        .find(|entry| entry.name == name)
+        .find_provider(name)
         .map(|entry| entry.name.clone())
         .ok_or_else(provider_not_found_response)
 }
@@ -41,6 +41,7 @@ pub async fn presentation_preview(
     Path(name): Path<String>,
     Json(body): Json<PresentationPreviewRequest>,
 ) -> impl IntoResponse {
+    let name = super::resolve_provider_name(&state, &name);
     let config = state.config.load();
 
     let entry = match config.providers.iter().find(|entry| entry.name == name) {
@@ -30,6 +30,7 @@ pub async fn test_request(
     Path(name): Path<String>,
     Json(body): Json<ProviderTestRequest>,
 ) -> impl IntoResponse {
+    let name = crate::handler::dashboard::providers::resolve_provider_name(&state, &name);
     if body.model.trim().is_empty() || body.input.trim().is_empty() {
         return (
             StatusCode::UNPROCESSABLE_ENTITY,
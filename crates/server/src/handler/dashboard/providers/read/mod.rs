@@ -1,26 +1,41 @@
-mod response;
 mod view;
 
 use crate::AppState;
 use axum::Json;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use prism_core::dashboard_api::ProviderList;
+use serde::Deserialize;
 use serde_json::json;
 
-use self::response::ProviderListResponse;
 use self::view::{provider_detail_response, summarize_provider};
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ProviderListQuery {
+    /// Comma-separated; only providers carrying every listed tag are returned.
+    pub tags: Option<String>,
+}
+
 /// GET /api/dashboard/providers
-pub async fn list_providers(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn list_providers(
+    State(state): State<AppState>,
+    Query(query): Query<ProviderListQuery>,
+) -> impl IntoResponse {
     let config = state.config.load();
     let providers = config
         .providers
         .iter()
+        .filter(|entry| {
+            query
+                .tags
+                .as_deref()
+                .is_none_or(|tags| prism_core::config::matches_tags(&entry.tags, tags))
+        })
         .map(|entry| summarize_provider(&state, entry))
         .collect::<Vec<_>>();
 
-    (StatusCode::OK, Json(ProviderListResponse { providers }))
+    (StatusCode::OK, Json(ProviderList { providers }))
 }
 
 /// GET /api/dashboard/providers/:name
@@ -28,6 +43,7 @@ pub async fn get_provider(
     State(state): State<AppState>,
     Path(name): Path<String>,
 ) -> impl IntoResponse {
+    let name = super::resolve_provider_name(&state, &name);
     let config = state.config.load();
 
     match config.providers.iter().find(|entry| entry.name == name) {
@@ -1,7 +1,6 @@
 use crate::AppState;
 use prism_core::auth_profile::AuthProfileEntry;
-
-use super::response::{AuthProfileSummary, ProviderDetailResponse, ProviderSummary};
+use prism_core::dashboard_api::{AuthProfileSummary, ProviderDetail, ProviderSummary};
 
 fn mask_key(key: &str) -> String {
     if key.len() <= 8 {
@@ -45,10 +44,19 @@ fn provider_api_key_masked(
         .unwrap_or_default()
 }
 
-fn summarize_auth_profile(provider_name: &str, profile: &AuthProfileEntry) -> AuthProfileSummary {
+fn summarize_auth_profile(
+    state: &AppState,
+    entry: &prism_core::config::ProviderKeyEntry,
+    profile: &AuthProfileEntry,
+) -> AuthProfileSummary {
+    let qualified_name = format!("{}/{}", entry.name, profile.id);
+    let quota = state.router.quota_report(
+        &qualified_name,
+        profile.quota.as_ref().or(entry.quota.as_ref()),
+    );
     AuthProfileSummary {
         id: profile.id.clone(),
-        qualified_name: format!("{provider_name}/{}", profile.id),
+        qualified_name,
         mode: profile.mode,
         header: profile.header,
         secret_masked: mask_optional_key(profile.secret.as_deref()),
@@ -71,6 +79,7 @@ fn summarize_auth_profile(provider_name: &str, profile: &AuthProfileEntry) -> Au
         region: profile.region.clone(),
         prefix: profile.prefix.clone(),
         upstream_presentation: profile.upstream_presentation.clone(),
+        quota,
     }
 }
 
@@ -86,7 +95,7 @@ fn summarize_auth_profiles(
                 .auth_runtime
                 .apply_runtime_state(&entry.name, &profile)
                 .unwrap_or(profile);
-            summarize_auth_profile(&entry.name, &hydrated)
+            summarize_auth_profile(state, entry, &hydrated)
         })
         .collect()
 }
@@ -96,27 +105,33 @@ pub(super) fn summarize_provider(
     entry: &prism_core::config::ProviderKeyEntry,
 ) -> ProviderSummary {
     ProviderSummary {
+        id: entry.id.clone(),
         name: entry.name.clone(),
-        format: entry.format.as_str().to_string(),
-        upstream: entry.upstream_kind().as_str().to_string(),
+        format: entry.format,
+        upstream: entry.upstream_kind(),
         api_key_masked: provider_api_key_masked(state, entry),
         base_url: entry.base_url.clone(),
         models: entry.models.clone(),
         disabled: entry.disabled,
+        tags: entry.tags.clone(),
         wire_api: entry.wire_api,
         upstream_presentation: entry.upstream_presentation.clone(),
         auth_profiles: summarize_auth_profiles(state, entry),
+        throughput: state.metrics.provider_throughput(&entry.name),
+        attempts: state.metrics.provider_attempts(&entry.name),
     }
 }
 
 pub(super) fn provider_detail_response(
     state: &AppState,
     entry: &prism_core::config::ProviderKeyEntry,
-) -> ProviderDetailResponse {
-    ProviderDetailResponse {
+) -> ProviderDetail {
+    ProviderDetail {
+        id: entry.id.clone(),
         name: entry.name.clone(),
-        format: entry.format.as_str().to_string(),
-        upstream: entry.upstream_kind().as_str().to_string(),
+        format: entry.format,
+        upstream: entry.upstream_kind(),
+        executor: entry.executor.clone(),
         api_key_masked: provider_api_key_masked(state, entry),
         base_url: entry.base_url.clone(),
         proxy_url: entry.proxy_url.clone(),
@@ -128,10 +143,17 @@ pub(super) fn provider_detail_response(
         wire_api: entry.wire_api,
         weight: entry.weight,
         region: entry.region.clone(),
+        group: entry.group.clone(),
+        tags: entry.tags.clone(),
+        quota: entry.quota.clone(),
         upstream_presentation: entry.upstream_presentation.clone(),
         vertex: entry.vertex,
         vertex_project: entry.vertex_project.clone(),
         vertex_location: entry.vertex_location.clone(),
+        allowed_server_tools: entry.allowed_server_tools.clone(),
+        allowed_betas: entry.allowed_betas.clone(),
         auth_profiles: summarize_auth_profiles(state, entry),
+        throughput: state.metrics.provider_throughput(&entry.name),
+        attempts: state.metrics.provider_attempts(&entry.name),
     }
 }
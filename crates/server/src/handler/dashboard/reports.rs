@@ -0,0 +1,47 @@
+use crate::AppState;
+use crate::middleware::dashboard_auth::Claims;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{Extension, Json};
+use prism_core::dashboard_api::ReportDelivery;
+use serde_json::json;
+
+/// POST /api/dashboard/reports/:name/send — deliver a usage report now, covering
+/// the period that ends at the time of the call.
+pub async fn send_report(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let report = state
+        .config
+        .load()
+        .reports
+        .iter()
+        .find(|r| r.name == name)
+        .cloned();
+    let Some(report) = report else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "not_found", "message": "Report not found"})),
+        );
+    };
+    match crate::reports::deliver(&state, &report, chrono::Utc::now()).await {
+        Ok(usage) => {
+            crate::handler::dashboard::audit(claims.as_deref(), "report.send", &name);
+            (
+                StatusCode::OK,
+                Json(json!(ReportDelivery {
+                    name,
+                    delivered: true,
+                    usage,
+                })),
+            )
+        }
+        Err(message) => (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({"error": "delivery_failed", "message": message})),
+        ),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde_json::json;
+
+/// GET /api/dashboard/requests/active — in-flight API requests, oldest first.
+pub async fn list_inflight(State(state): State<AppState>) -> impl IntoResponse {
+    let requests = state.inflight.list();
+    Json(json!({ "total": requests.len(), "requests": requests }))
+}
+
+/// POST /api/dashboard/requests/:request_id/cancel — abort an in-flight request.
+pub async fn cancel_request(
+    State(state): State<AppState>,
+    Path(request_id): Path<String>,
+) -> impl IntoResponse {
+    if state.inflight.cancel(&request_id) {
+        tracing::info!(request_id = %request_id, "Request cancellation requested from dashboard");
+        (
+            StatusCode::OK,
+            Json(json!({ "request_id": request_id, "cancelled": true })),
+        )
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "not_found", "message": "request not in flight" })),
+        )
+    }
+}
+
+/// GET /api/dashboard/requests/:request_id/debug — trace recorded for `x-debug: trace`.
+pub async fn debug_trace(
+    State(state): State<AppState>,
+    Path(request_id): Path<String>,
+) -> impl IntoResponse {
+    match state.debug_traces.get(&request_id) {
+        Some(trace) => (StatusCode::OK, Json(json!(trace))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "not_found", "message": "no debug trace for this request" })),
+        ),
+    }
+}
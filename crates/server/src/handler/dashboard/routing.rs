@@ -1,13 +1,16 @@
 use crate::AppState;
 use axum::Json;
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use prism_core::routing::config::{ModelResolution, RouteProfile, RouteRule, RoutingConfig};
+use prism_core::provider::Format;
+use prism_core::routing::config::{
+    CredentialGroup, ModelResolution, RouteProfile, RouteRule, RoutingConfig,
+};
 use prism_core::routing::explain::explain;
-use prism_core::routing::planner::RoutePlanner;
-use prism_core::routing::types::RouteRequestFeatures;
-use serde::Deserialize;
+use prism_core::routing::planner::{InventorySnapshot, RoutePlanner};
+use prism_core::routing::types::{RejectReason, RouteRejection, RouteRequestFeatures};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 
@@ -18,6 +21,7 @@ pub struct UpdateRoutingRequest {
     pub profiles: Option<HashMap<String, RouteProfile>>,
     pub rules: Option<Vec<RouteRule>>,
     pub model_resolution: Option<ModelResolution>,
+    pub credential_groups: Option<Vec<CredentialGroup>>,
 }
 
 /// GET /api/dashboard/routing
@@ -29,8 +33,10 @@ pub async fn get_routing(State(state): State<AppState>) -> impl IntoResponse {
 /// PATCH /api/dashboard/routing
 pub async fn update_routing(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<UpdateRoutingRequest>,
 ) -> impl IntoResponse {
+    let expected = super::config_tx::expected_version(&headers);
     let current_routing = state.config.load().routing.clone();
     let effective_routing = materialize_routing_update(&body, &current_routing);
     if let Err(errors) = validate_effective_routing(&effective_routing) {
@@ -40,7 +46,7 @@ pub async fn update_routing(
         );
     }
 
-    match super::config_tx::update_config_file_public(&state, move |config| {
+    match super::config_tx::update_config_file_public(&state, expected.as_deref(), move |config| {
         if let Some(dp) = body.default_profile {
             config.routing.default_profile = dp;
         }
@@ -53,6 +59,9 @@ pub async fn update_routing(
         if let Some(mr) = body.model_resolution {
             config.routing.model_resolution = mr;
         }
+        if let Some(groups) = body.credential_groups {
+            config.routing.credential_groups = groups;
+        }
     })
     .await
     {
@@ -66,11 +75,8 @@ pub async fn update_routing(
             )
         }
         Err(e) => {
-            tracing::error!(error = %e, "Failed to update routing configuration");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "write_failed", "message": e})),
-            )
+            tracing::error!(error = ?e, "Failed to update routing configuration");
+            super::config_ops::config_tx_error_response(e)
         }
     }
 }
@@ -102,6 +108,14 @@ pub async fn preview_route(
     (StatusCode::OK, Json(json!(explanation)))
 }
 
+/// GET /api/dashboard/routing/aliases — effective alias → (credential, model) map
+/// with collision warnings.
+pub async fn model_aliases(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config.load();
+    let aliases = prism_core::model_alias::AliasMap::from_config(&config);
+    (StatusCode::OK, Json(json!(aliases)))
+}
+
 /// POST /api/dashboard/routing/explain — full introspection with scoring detail
 pub async fn explain_route(
     State(state): State<AppState>,
@@ -127,6 +141,143 @@ pub async fn explain_route(
     (StatusCode::OK, Json(json!(explanation)))
 }
 
+/// POST /api/dashboard/routing/resolve — dry-run dispatch: the attempts a request
+/// would make, in order, and why every other credential was left out. Nothing is sent
+/// upstream.
+pub async fn resolve_route(
+    State(state): State<AppState>,
+    Json(req): Json<RouteResolveRequest>,
+) -> impl IntoResponse {
+    let features = req.route.to_features();
+    let config = state.config.load();
+    let inventory = state.catalog.snapshot();
+    let health = state.health_manager.snapshot();
+
+    let mut plan = RoutePlanner::plan(&features, &config.routing, &inventory, &health);
+    crate::dispatch::drop_quota_exhausted(&state, &mut plan);
+    if !req.formats.is_empty() {
+        let mut format_rejections = Vec::new();
+        plan.attempts.retain(|attempt| {
+            let allowed = req.formats.contains(&attempt.provider);
+            if !allowed {
+                format_rejections.push(RouteRejection {
+                    candidate: attempt.credential_name.clone(),
+                    reason: RejectReason::FormatNotAllowed,
+                });
+            }
+            allowed
+        });
+        plan.trace.rejections.extend(format_rejections);
+    }
+
+    let attempts: Vec<ResolvedAttempt> = plan
+        .attempts
+        .iter()
+        .enumerate()
+        .map(|(i, attempt)| ResolvedAttempt {
+            order: i as u32 + 1,
+            provider: state
+                .router
+                .provider_name(&attempt.credential_id)
+                .unwrap_or_else(|| attempt.provider.as_str().to_string()),
+            format: attempt.provider,
+            credential_name: attempt.credential_name.clone(),
+            model: attempt.model.clone(),
+        })
+        .collect();
+    let excluded: Vec<ResolvedExclusion> = plan
+        .trace
+        .rejections
+        .iter()
+        .map(|rejection| ResolvedExclusion {
+            detail: exclusion_detail(&state, &inventory, &plan.model_chain, rejection),
+            candidate: rejection.candidate.clone(),
+            reason: rejection.reason.clone(),
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "model": features.requested_model,
+            "profile": plan.profile,
+            "matched_rule": plan.trace.matched_rule,
+            "model_chain": plan.model_chain,
+            "attempts": attempts,
+            "excluded": excluded,
+        })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RouteResolveRequest {
+    #[serde(flatten)]
+    pub route: RouteIntrospectionRequest,
+    /// Only keep attempts on providers of these formats. Empty keeps all.
+    #[serde(default)]
+    pub formats: Vec<Format>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolvedAttempt {
+    /// 1-based position in the failover order.
+    order: u32,
+    provider: String,
+    format: Format,
+    credential_name: String,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolvedExclusion {
+    candidate: String,
+    reason: RejectReason,
+    detail: Option<String>,
+}
+
+/// Human-readable cause for reasons the planner reports without detail.
+fn exclusion_detail(
+    state: &AppState,
+    inventory: &InventorySnapshot,
+    model_chain: &[String],
+    rejection: &RouteRejection,
+) -> Option<String> {
+    // Planner candidates are labelled `provider/credential`; quota and format
+    // rejections use the bare credential name.
+    let credential_id = inventory.providers.iter().find_map(|provider| {
+        provider.credentials.iter().find_map(|cred| {
+            let matches = rejection.candidate == cred.name
+                || rejection.candidate == format!("{}/{}", provider.name, cred.name);
+            matches.then(|| cred.id.clone())
+        })
+    })?;
+    match rejection.reason {
+        RejectReason::ModelNotSupported => {
+            let auth = state.router.find_credential(&credential_id)?;
+            let model = model_chain.iter().find(|m| !auth.supports_model(m))?;
+            let effective = auth.strip_prefix(model);
+            Some(if auth.is_model_excluded(effective) {
+                format!("'{effective}' matches excluded-models")
+            } else if let Some(prefix) = auth.prefix.as_deref()
+                && !model.starts_with(prefix)
+            {
+                format!("credential serves models under prefix '{prefix}'")
+            } else {
+                format!("'{effective}' is not in the credential's models")
+            })
+        }
+        RejectReason::CooldownActive => state
+            .router
+            .cooldown_remaining(&credential_id)
+            .map(|remaining| format!("cooldown ends in {}s", remaining.as_secs().max(1))),
+        RejectReason::QuotaExhausted => state
+            .router
+            .quota_exhausted(&credential_id)
+            .map(|exhausted| format!("quota resets at {}", exhausted.resets_at.to_rfc3339())),
+        _ => None,
+    }
+}
+
 /// Canonical route introspection request shared by preview and explain endpoints.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -157,7 +308,6 @@ fn default_source_format() -> String {
 
 impl RouteIntrospectionRequest {
     pub fn to_features(&self) -> RouteRequestFeatures {
-        use prism_core::provider::Format;
         use prism_core::routing::types::RouteEndpoint;
 
         let endpoint = match self.endpoint.as_str() {
@@ -220,6 +370,9 @@ fn materialize_routing_update(
     if let Some(model_resolution) = &body.model_resolution {
         next.model_resolution = model_resolution.clone();
     }
+    if let Some(credential_groups) = &body.credential_groups {
+        next.credential_groups = credential_groups.clone();
+    }
     next
 }
 
@@ -250,6 +403,10 @@ fn validate_effective_routing(routing: &RoutingConfig) -> Result<(), Vec<String>
         }
     }
 
+    if let Err(error) = routing.validate_credential_groups() {
+        errors.push(error);
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -1,8 +1,9 @@
 use crate::AppState;
 use axum::Json;
-use axum::extract::{Query, State};
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
 use serde::Deserialize;
 use serde_json::json;
 use std::io::{Read, Seek, SeekFrom};
@@ -111,6 +112,23 @@ pub async fn system_health(State(state): State<AppState>) -> impl IntoResponse {
         "cache_misses": metrics["cache"]["misses"],
     });
 
+    let runtime = tokio::runtime::Handle::current().metrics();
+    let connections = state.connections.counts();
+    let resources = json!({
+        "process": crate::resource_stats::ProcessUsage::current(),
+        "tokio": {
+            "workers": runtime.num_workers(),
+            "alive_tasks": runtime.num_alive_tasks(),
+        },
+        "log_buffer": state.log_store.buffer_usage(),
+        "connections": {
+            "open": connections.open,
+            "accepted_total": connections.accepted_total,
+            "inflight_requests": state.inflight.len(),
+            "active_streams": state.load_shedder.streams(),
+        },
+    });
+
     (
         StatusCode::OK,
         Json(json!({
@@ -122,6 +140,41 @@ pub async fn system_health(State(state): State<AppState>) -> impl IntoResponse {
             "tls_enabled": config.tls.enable,
             "providers": providers,
             "metrics": metrics_summary,
+            "resources": resources,
+            "last_config_reload": state.crash_reporter.last_config_reload(),
+            "crashes": {
+                "count": state.crash_reporter.count(),
+                "last": state.crash_reporter.last().map(|report| json!({
+                    "timestamp": report.timestamp,
+                    "pid": report.pid,
+                    "version": report.version,
+                    "message": report.message,
+                    "location": report.location,
+                    "active_requests": report.active_requests,
+                })),
+            },
+        })),
+    )
+}
+
+/// GET /api/dashboard/system/uptime-history — recorded process runs, newest first.
+pub async fn uptime_history(State(state): State<AppState>) -> impl IntoResponse {
+    let mut runs = state.process_history.runs();
+    runs.reverse();
+    let unclean = runs
+        .iter()
+        .filter(|r| r.shutdown_reason.as_deref() == Some(crate::process_history::UNCLEAN_SHUTDOWN))
+        .count();
+    let panicked = runs.iter().filter(|r| r.panic.is_some()).count();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "uptime_seconds": state.start_time.elapsed().as_secs(),
+            "restarts": runs.len().saturating_sub(1),
+            "unclean_shutdowns": unclean,
+            "panicked_runs": panicked,
+            "runs": runs,
         })),
     )
 }
@@ -280,3 +333,122 @@ pub async fn system_logs(
         })),
     )
 }
+
+/// GET /api/dashboard/system/logs/files
+pub async fn log_files(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config.load();
+    let log_dir = config.log_dir.as_deref().unwrap_or("./logs");
+    let files =
+        prism_lifecycle::logging::list_log_files(std::path::Path::new(log_dir)).unwrap_or_default();
+    let total_bytes: u64 = files.iter().map(|f| f.size).sum();
+    let files: Vec<serde_json::Value> = files
+        .iter()
+        .map(|f| {
+            json!({
+                "name": f.name,
+                "size_bytes": f.size,
+                "modified": f.modified.map(chrono::DateTime::<chrono::Utc>::from),
+            })
+        })
+        .collect();
+
+    Json(json!({
+        "dir": log_dir,
+        "files": files,
+        "total_bytes": total_bytes,
+    }))
+}
+
+/// GET /api/dashboard/system/logs/files/{name}
+pub async fn download_log_file(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let config = state.config.load();
+    let log_dir = config.log_dir.as_deref().unwrap_or("./logs");
+    // Only names from the listing are served, which rules out paths outside the log dir.
+    let file = prism_lifecycle::logging::list_log_files(std::path::Path::new(log_dir))
+        .unwrap_or_default()
+        .into_iter()
+        .find(|f| f.name == name);
+    let Some(file) = file else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "not_found", "message": "Log file not found"})),
+        )
+            .into_response();
+    };
+    // Streamed in chunks, so large rotated files never sit in memory whole.
+    match tokio::fs::File::open(&file.path).await {
+        Ok(handle) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/plain; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file.name),
+                ),
+            ],
+            Body::from_stream(tokio_util::io::ReaderStream::new(handle)),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "read_failed", "message": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+fn log_level_body(state: &AppState) -> serde_json::Value {
+    let filter = &state.log_filter;
+    json!({
+        "level": filter.directives(),
+        "default": filter.initial_directives(),
+        "attached": filter.is_attached(),
+    })
+}
+
+/// GET /api/dashboard/system/log-level
+pub async fn get_log_level(State(state): State<AppState>) -> impl IntoResponse {
+    Json(log_level_body(&state))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateLogLevelRequest {
+    /// `EnvFilter` directives, e.g. `info,prism_provider=debug`.
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Restore the level the process started with.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+/// PATCH /api/dashboard/system/log-level
+pub async fn update_log_level(
+    State(state): State<AppState>,
+    Json(body): Json<UpdateLogLevelRequest>,
+) -> impl IntoResponse {
+    let result = match (body.reset, body.level.as_deref().map(str::trim)) {
+        (true, _) => state.log_filter.reset(),
+        (false, Some(level)) if !level.is_empty() => state.log_filter.set(level),
+        _ => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(
+                    json!({"error": "validation_failed", "message": "Expected a 'level' or 'reset' field"}),
+                ),
+            );
+        }
+    };
+    if let Err(e) = result {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({"error": "validation_failed", "message": e.to_string()})),
+        );
+    }
+    tracing::info!(level = %state.log_filter.directives(), "Log level changed");
+    (StatusCode::OK, Json(log_level_body(&state)))
+}
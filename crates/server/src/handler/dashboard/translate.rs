@@ -0,0 +1,140 @@
+use crate::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use prism_core::provider::Format;
+use prism_translator::TranslateState;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+#[derive(Debug, Deserialize)]
+pub struct TranslateRequest {
+    /// Format the client speaks.
+    pub source_format: Format,
+    /// Format the upstream speaks.
+    pub target_format: Format,
+    /// Client request body in `source_format`.
+    pub body: Value,
+    /// Model passed to the translators; defaults to `body.model`.
+    pub model: Option<String>,
+    /// Defaults to `body.stream`.
+    pub stream: Option<bool>,
+    /// Upstream non-stream response in `target_format`, translated back to `source_format`.
+    pub response: Option<Value>,
+    /// Upstream stream events in `target_format`, translated back in order.
+    #[serde(default)]
+    pub stream_events: Vec<StreamEventInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamEventInput {
+    pub event: Option<String>,
+    /// Raw SSE `data:` payload, e.g. a JSON object or `[DONE]`.
+    pub data: String,
+}
+
+/// POST /api/dashboard/translate — run a body through the translators without traffic.
+pub async fn translate(
+    State(state): State<AppState>,
+    Json(req): Json<TranslateRequest>,
+) -> impl IntoResponse {
+    if !req.body.is_object() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "validation_failed",
+                "message": "body must be a JSON object",
+            })),
+        );
+    }
+    let model = req
+        .model
+        .clone()
+        .or_else(|| {
+            req.body
+                .get("model")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    let stream = req
+        .stream
+        .or_else(|| req.body.get("stream").and_then(Value::as_bool))
+        .unwrap_or(false);
+    let raw_body = req.body.to_string();
+    let translators = &state.translators;
+
+    let translated = match translators.translate_request(
+        req.source_format,
+        req.target_format,
+        &model,
+        raw_body.as_bytes(),
+        stream,
+    ) {
+        Ok(bytes) => bytes,
+        Err(e) => return translation_failed("request", &e.to_string()),
+    };
+
+    let mut result = json!({
+        "source_format": req.source_format,
+        "target_format": req.target_format,
+        "model": model,
+        "stream": stream,
+        "request": parse_payload(&translated),
+    });
+
+    if let Some(ref response) = req.response {
+        match translators.translate_non_stream(
+            req.source_format,
+            req.target_format,
+            &model,
+            raw_body.as_bytes(),
+            response.to_string().as_bytes(),
+        ) {
+            Ok(body) => result["response"] = parse_payload(body.as_bytes()),
+            Err(e) => return translation_failed("response", &e.to_string()),
+        }
+    }
+
+    if !req.stream_events.is_empty() {
+        let mut translate_state = TranslateState::default();
+        let mut lines = Vec::new();
+        for (index, event) in req.stream_events.iter().enumerate() {
+            match translators.translate_stream(
+                req.source_format,
+                req.target_format,
+                &model,
+                raw_body.as_bytes(),
+                event.event.as_deref(),
+                event.data.as_bytes(),
+                &mut translate_state,
+            ) {
+                Ok(out) => lines.extend(out),
+                Err(e) => {
+                    return translation_failed(&format!("stream_events[{index}]"), &e.to_string());
+                }
+            }
+        }
+        result["stream_events"] = json!(lines);
+    }
+
+    (StatusCode::OK, Json(result))
+}
+
+/// The payload as JSON when it parses, otherwise as a string.
+fn parse_payload(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+fn translation_failed(stage: &str, message: &str) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Json(json!({
+            "error": "translation_failed",
+            "stage": stage,
+            "message": message,
+        })),
+    )
+}
@@ -2,6 +2,7 @@ use crate::AppState;
 use axum::extract::ws::{Message, WebSocket};
 use axum::extract::{State, WebSocketUpgrade};
 use axum::response::IntoResponse;
+use prism_core::request_record::RequestRecord;
 use serde_json::json;
 use std::time::Duration;
 use tokio::sync::broadcast;
@@ -17,9 +18,9 @@ pub async fn ws_handler(State(state): State<AppState>, ws: WebSocketUpgrade) ->
 async fn handle_ws(mut socket: WebSocket, state: AppState) {
     let mut subscribed_metrics = true;
     let mut subscribed_logs = true;
+    let mut subscribed_usage = true;
 
-    let mut log_rx: broadcast::Receiver<prism_core::request_record::RequestRecord> =
-        state.log_store.subscribe();
+    let mut log_rx: broadcast::Receiver<RequestRecord> = state.log_store.subscribe();
 
     let mut metrics_interval = tokio::time::interval(Duration::from_secs(1));
 
@@ -37,14 +38,25 @@ async fn handle_ws(mut socket: WebSocket, state: AppState) {
                 }
             }
 
-            // Forward new log entries
-            Ok(entry) = log_rx.recv(), if subscribed_logs => {
-                let msg = json!({
-                    "type": "request_log",
-                    "data": entry,
-                });
-                if socket.send(Message::Text(msg.to_string().into())).await.is_err() {
-                    break;
+            // Forward finished requests as log entries and/or usage events
+            Ok(entry) = log_rx.recv(), if subscribed_logs || subscribed_usage => {
+                if subscribed_usage {
+                    let msg = json!({
+                        "type": "usage",
+                        "data": usage_event(&entry),
+                    });
+                    if socket.send(Message::Text(msg.to_string().into())).await.is_err() {
+                        break;
+                    }
+                }
+                if subscribed_logs {
+                    let msg = json!({
+                        "type": "request_log",
+                        "data": entry,
+                    });
+                    if socket.send(Message::Text(msg.to_string().into())).await.is_err() {
+                        break;
+                    }
                 }
             }
 
@@ -59,6 +71,7 @@ async fn handle_ws(mut socket: WebSocket, state: AppState) {
                             let names: Vec<&str> = channels.iter().filter_map(|c| c.as_str()).collect();
                             subscribed_metrics = names.contains(&"metrics");
                             subscribed_logs = names.contains(&"request_log");
+                            subscribed_usage = names.contains(&"usage");
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => break,
@@ -68,3 +81,54 @@ async fn handle_ws(mut socket: WebSocket, state: AppState) {
         }
     }
 }
+
+/// Completion event for spend tickers: tokens and cost without bodies or attempts.
+fn usage_event(record: &RequestRecord) -> serde_json::Value {
+    let usage = record.usage.clone().unwrap_or_default();
+    json!({
+        "request_id": record.request_id,
+        "timestamp": record.timestamp,
+        "model": record.model.as_ref().or(record.requested_model.as_ref()),
+        "provider": record.provider,
+        "credential_name": record.credential_name,
+        "tenant_id": record.tenant_id,
+        "api_key_id": record.api_key_id,
+        "status": record.status,
+        "latency_ms": record.latency_ms,
+        "input_tokens": usage.input_tokens,
+        "output_tokens": usage.output_tokens,
+        "cache_read_tokens": usage.cache_read_tokens,
+        "cache_creation_tokens": usage.cache_creation_tokens,
+        "cost": record.cost,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::span_data::RequestSpanData;
+
+    #[test]
+    fn test_usage_event_carries_tokens_and_cost() {
+        let record = RequestSpanData {
+            request_id: "req-1".into(),
+            requested_model: Some("fast".into()),
+            model: Some("gpt-4o-mini".into()),
+            status: 200,
+            usage_input: Some(120),
+            usage_output: Some(30),
+            cost: Some(0.0042),
+            request_body: Some("{\"messages\":[]}".into()),
+            ..Default::default()
+        }
+        .into_request_record();
+
+        let event = usage_event(&record);
+        assert_eq!(event["request_id"], "req-1");
+        assert_eq!(event["model"], "gpt-4o-mini");
+        assert_eq!(event["input_tokens"], 120);
+        assert_eq!(event["output_tokens"], 30);
+        assert_eq!(event["cost"], 0.0042);
+        assert!(event.get("request_body").is_none());
+    }
+}
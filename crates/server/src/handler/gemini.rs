@@ -55,15 +55,14 @@ async fn dispatch_gemini(
     model: &str,
     stream: bool,
 ) -> Result<Response, ProxyError> {
+    super::validation::validate_request(super::validation::RequestKind::GenerateContent, &body)?;
+
     let user_agent = headers
         .get("user-agent")
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    let debug = headers
-        .get("x-debug")
-        .and_then(|v| v.to_str().ok())
-        .is_some_and(|v| v == "true" || v == "1");
+    let (debug, debug_trace) = super::debug_flags(headers);
 
     let requested_credential = headers
         .get("x-prism-auth-profile")
@@ -71,13 +70,20 @@ async fn dispatch_gemini(
         .map(str::trim)
         .filter(|v| !v.is_empty());
 
-    let allowed_credentials = super::merge_requested_credential(
+    let mut allowed_credentials = super::merge_requested_credential(
         ctx.auth_key
             .as_ref()
             .map(|e| e.allowed_credentials.clone())
             .unwrap_or_default(),
         requested_credential,
     )?;
+    // A referenced cache only exists for the key that created it.
+    if requested_credential.is_none()
+        && let Some(credential) =
+            super::gemini_cache::pinned_credential(state, &body, &allowed_credentials)
+    {
+        allowed_credentials = vec![credential];
+    }
 
     dispatch(
         state,
@@ -98,13 +104,19 @@ async fn dispatch_gemini(
             allowed_formats: None,
             user_agent,
             debug,
+            debug_trace,
             api_key: ctx.auth_key.as_ref().map(|e| e.key.clone()),
             client_region: ctx.client_region.clone(),
             request_id: Some(ctx.request_id.clone()),
+            trace: Some(ctx.trace.clone()),
+            forwarded_headers: crate::dispatch::forwarded_headers(headers, &state.config.load()),
+            routing_headers: crate::dispatch::routing_headers(headers, &state.config.load()),
+            anthropic_betas: crate::dispatch::anthropic_betas(headers),
             api_key_id: ctx.api_key_id.clone(),
             tenant_id: ctx.tenant_id.clone(),
             allowed_credentials,
             responses_passthrough: false,
+            experiment: None,
         },
     )
     .await
@@ -0,0 +1,426 @@
+//! Gemini context caching (`/v1beta/cachedContents`).
+//!
+//! A cached content belongs to the API key that created it, so each cache created
+//! through the proxy is bound to its upstream credential in
+//! [`AppState::gemini_caches`]. Later reads, updates, deletes, and
+//! `generateContent` requests that reference the cache go to that credential.
+//! Caches the proxy has not seen (created elsewhere, or before a restart) are
+//! looked up on each Gemini credential in turn and bound once found. A binding
+//! lasts until the cache's upstream `expireTime` or until its credential is
+//! removed from the config.
+
+use crate::AppState;
+use axum::Extension;
+use axum::extract::{Path, RawQuery, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use prism_core::context::{RequestContext, TraceContext};
+use prism_core::error::ProxyError;
+use prism_core::provider::{AuthRecord, Format, UpstreamKind};
+use prism_provider::routing::check_credential_access;
+use std::collections::HashMap;
+
+const CACHE_PREFIX: &str = "cachedContents/";
+
+/// Upstream credential a cached content belongs to.
+#[derive(Debug, Clone)]
+pub struct CacheBinding {
+    pub credential_id: String,
+    /// `expireTime` from the last upstream response about the cache.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// POST /v1beta/cachedContents — create a cache on a Gemini credential that
+/// serves the requested model.
+pub async fn create_cached_content(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let mut req_value: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|e| ProxyError::BadRequest(e.to_string()))?;
+    let model = req_value
+        .get("model")
+        .and_then(|m| m.as_str())
+        .map(|m| m.strip_prefix("models/").unwrap_or(m).to_string())
+        .ok_or_else(|| ProxyError::BadRequest("missing model field".into()))?;
+
+    // Enforce model ACL (same as main dispatch path)
+    if let Some(ref auth_key) = ctx.auth_key
+        && !prism_core::auth_key::AuthKeyStore::check_model_access(auth_key, &model)
+    {
+        return Err(ProxyError::ModelNotAllowed(format!(
+            "model '{model}' not allowed for this API key",
+        )));
+    }
+
+    let allowed_credentials = allowed_credentials(&ctx, &headers)?;
+    let config = state.config.load();
+    let forwarded = crate::dispatch::forwarded_headers(&headers, &config);
+    let sticky = crate::dispatch::sticky_value(&config, &ctx, &headers);
+    let mut tried = Vec::new();
+    let mut last_error = None;
+    for (provider_name, format) in state.router.resolve_providers(&model) {
+        if format != Format::Gemini {
+            continue;
+        }
+        let Some(auth) = state.router.pick(
+            &provider_name,
+            &model,
+            &tried,
+            ctx.client_region.as_deref(),
+            &allowed_credentials,
+            sticky.as_deref(),
+        ) else {
+            continue;
+        };
+        if !serves_cached_contents(&auth) {
+            continue;
+        }
+        tried.push(auth.id.clone());
+
+        req_value["model"] =
+            serde_json::Value::String(format!("models/{}", auth.resolve_model_id(&model)));
+        let payload = Bytes::from(
+            serde_json::to_vec(&req_value).map_err(|e| ProxyError::Internal(e.to_string()))?,
+        );
+        match send(
+            &state,
+            &auth,
+            &ctx.trace,
+            &forwarded,
+            Method::POST,
+            "cachedContents",
+            None,
+            Some(payload),
+        )
+        .await
+        {
+            Ok((status, resp_body)) if status.is_server_error() || status.as_u16() == 429 => {
+                state.router.record_failure(&auth.id);
+                last_error = Some(ProxyError::Upstream {
+                    status: status.as_u16(),
+                    body: String::from_utf8_lossy(&resp_body).to_string(),
+                    retry_after_secs: None,
+                    headers: HashMap::new(),
+                });
+            }
+            Ok((status, resp_body)) => {
+                if status.is_success() {
+                    state.router.record_success(&auth.id);
+                    if let Some(name) = cache_name(&resp_body) {
+                        bind(&state, name, &auth.id, &resp_body);
+                    }
+                }
+                return Ok(json_response(status, resp_body));
+            }
+            Err(err) => {
+                state.router.record_failure(&auth.id);
+                last_error = Some(err);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| ProxyError::NoCredentials {
+        provider: "gemini".into(),
+        model,
+    }))
+}
+
+/// GET /v1beta/cachedContents/{id}
+pub async fn get_cached_content(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(id): Path<String>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, ProxyError> {
+    forward_to_owner(&state, &ctx, &headers, Method::GET, &id, query, None).await
+}
+
+/// PATCH /v1beta/cachedContents/{id} — update the TTL or expiry.
+pub async fn update_cached_content(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(id): Path<String>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    forward_to_owner(
+        &state,
+        &ctx,
+        &headers,
+        Method::PATCH,
+        &id,
+        query,
+        Some(body),
+    )
+    .await
+}
+
+/// DELETE /v1beta/cachedContents/{id}
+pub async fn delete_cached_content(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ProxyError> {
+    let response =
+        forward_to_owner(&state, &ctx, &headers, Method::DELETE, &id, None, None).await?;
+    if response.status().is_success() {
+        state.gemini_caches.remove(&format!("{CACHE_PREFIX}{id}"));
+    }
+    Ok(response)
+}
+
+/// Credential name a `generateContent` body must be pinned to because it
+/// references a cache bound to that credential. `None` routes as usual.
+pub(crate) fn pinned_credential(
+    state: &AppState,
+    body: &[u8],
+    allowed_credentials: &[String],
+) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let name = value.get("cachedContent")?.as_str()?;
+    let auth = bound_credential(state, name)?;
+    let credential_name = auth.credential_name?;
+    check_credential_access(allowed_credentials, Some(&credential_name)).then_some(credential_name)
+}
+
+/// Send a request about one cache to the credential that owns it, or find the
+/// owner among the Gemini credentials when the cache is not bound yet.
+async fn forward_to_owner(
+    state: &AppState,
+    ctx: &RequestContext,
+    headers: &HeaderMap,
+    method: Method,
+    id: &str,
+    query: Option<String>,
+    body: Option<Bytes>,
+) -> Result<Response, ProxyError> {
+    let name = format!("{CACHE_PREFIX}{id}");
+    let allowed_credentials = allowed_credentials(ctx, headers)?;
+    let forwarded = crate::dispatch::forwarded_headers(headers, &state.config.load());
+
+    let bound = bound_credential(state, &name).filter(|auth| {
+        check_credential_access(&allowed_credentials, auth.credential_name.as_deref())
+    });
+    let candidates = match bound {
+        Some(auth) => vec![auth],
+        None => gemini_credentials(state, &allowed_credentials),
+    };
+
+    let mut last = None;
+    for auth in candidates {
+        let (status, resp_body) = send(
+            state,
+            &auth,
+            &ctx.trace,
+            &forwarded,
+            method.clone(),
+            &name,
+            query.as_deref(),
+            body.clone(),
+        )
+        .await?;
+        // Another key's cache is reported as missing or forbidden.
+        if matches!(status.as_u16(), 403 | 404) {
+            last = Some((status, resp_body));
+            continue;
+        }
+        if status.is_success() && method != Method::DELETE {
+            bind(state, name, &auth.id, &resp_body);
+        }
+        return Ok(json_response(status, resp_body));
+    }
+
+    match last {
+        Some((status, resp_body)) => Ok(json_response(status, resp_body)),
+        None => Err(ProxyError::NoCredentials {
+            provider: "gemini".into(),
+            model: name,
+        }),
+    }
+}
+
+/// Bind a cache to `credential_id` until the `expireTime` in `body`, dropping
+/// bindings that have expired or whose credential is gone.
+fn bind(state: &AppState, name: String, credential_id: &str, body: &[u8]) {
+    let now = Utc::now();
+    state
+        .gemini_caches
+        .retain(|_, binding| is_live(state, binding, now));
+    state.gemini_caches.insert(
+        name,
+        CacheBinding {
+            credential_id: credential_id.to_string(),
+            expires_at: expire_time(body),
+        },
+    );
+}
+
+/// Credential a cache is bound to. Expired bindings and bindings to removed
+/// credentials are dropped.
+fn bound_credential(state: &AppState, name: &str) -> Option<AuthRecord> {
+    let binding = state.gemini_caches.get(name)?.value().clone();
+    let auth = is_live(state, &binding, Utc::now())
+        .then(|| state.router.find_credential(&binding.credential_id))
+        .flatten();
+    if auth.is_none() {
+        state.gemini_caches.remove(name);
+    }
+    auth
+}
+
+fn is_live(state: &AppState, binding: &CacheBinding, now: DateTime<Utc>) -> bool {
+    binding.expires_at.is_none_or(|at| at > now)
+        && state
+            .router
+            .find_credential(&binding.credential_id)
+            .is_some()
+}
+
+fn allowed_credentials(
+    ctx: &RequestContext,
+    headers: &HeaderMap,
+) -> Result<Vec<String>, ProxyError> {
+    let requested_credential = headers
+        .get("x-prism-auth-profile")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    super::merge_requested_credential(
+        ctx.auth_key
+            .as_ref()
+            .map(|e| e.allowed_credentials.clone())
+            .unwrap_or_default(),
+        requested_credential,
+    )
+}
+
+/// Vertex AI manages caches per project under a different path.
+fn serves_cached_contents(auth: &AuthRecord) -> bool {
+    auth.upstream == UpstreamKind::Gemini && !auth.vertex && auth.is_available()
+}
+
+/// Gemini API credentials the caller may use, in config order.
+fn gemini_credentials(state: &AppState, allowed_credentials: &[String]) -> Vec<AuthRecord> {
+    let credentials = state.router.credential_map();
+    state
+        .config
+        .load()
+        .providers
+        .iter()
+        .filter_map(|entry| credentials.get(&entry.name))
+        .flatten()
+        .filter(|auth| {
+            serves_cached_contents(auth)
+                && check_credential_access(allowed_credentials, auth.credential_name.as_deref())
+        })
+        .cloned()
+        .collect()
+}
+
+fn cache_name(body: &[u8]) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    value
+        .get("name")?
+        .as_str()
+        .filter(|name| name.starts_with(CACHE_PREFIX))
+        .map(str::to_string)
+}
+
+fn expire_time(body: &[u8]) -> Option<DateTime<Utc>> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    DateTime::parse_from_rfc3339(value.get("expireTime")?.as_str()?)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Drop the client's own `key` parameter; the upstream key goes in a header.
+fn upstream_query(query: Option<&str>) -> Option<String> {
+    let kept: Vec<&str> = query?
+        .split('&')
+        .filter(|part| !part.is_empty() && part.split('=').next() != Some("key"))
+        .collect();
+    (!kept.is_empty()).then(|| kept.join("&"))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send(
+    state: &AppState,
+    auth: &AuthRecord,
+    trace: &TraceContext,
+    forwarded: &HashMap<String, String>,
+    method: Method,
+    path: &str,
+    query: Option<&str>,
+    body: Option<Bytes>,
+) -> Result<(StatusCode, Bytes), ProxyError> {
+    let mut url = format!("{}/v1beta/{path}", auth.resolved_base_url());
+    if let Some(query) = upstream_query(query) {
+        url.push('?');
+        url.push_str(&query);
+    }
+    let client = prism_provider::common::build_client(auth, &state.http_client_pool)?;
+    let mut req = client.request(method, &url);
+    if let Some(body) = body {
+        req = req.header("content-type", "application/json").body(body);
+    }
+    let req = prism_provider::common::apply_auth(req, auth);
+    let config = state.config.load();
+    let mut headers = HashMap::new();
+    crate::dispatch::insert_trace_header(&mut headers, &config, Some(trace));
+    crate::dispatch::apply_outbound_headers(&mut headers, &config, forwarded);
+    let req = prism_provider::common::apply_headers(req, &headers, auth);
+
+    let resp = req.send().await?;
+    let status =
+        StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    Ok((status, resp.bytes().await?))
+}
+
+fn json_response(status: StatusCode, body: Bytes) -> Response {
+    (status, [("content-type", "application/json")], body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_query_drops_client_key() {
+        assert_eq!(
+            upstream_query(Some("key=prism-client-key&updateMask=ttl")).as_deref(),
+            Some("updateMask=ttl")
+        );
+        assert_eq!(upstream_query(Some("key=prism-client-key")), None);
+        assert_eq!(upstream_query(None), None);
+    }
+
+    #[test]
+    fn test_cache_name_requires_cached_contents_prefix() {
+        assert_eq!(
+            cache_name(br#"{"name":"cachedContents/abc123","model":"models/gemini-2.0-flash"}"#)
+                .as_deref(),
+            Some("cachedContents/abc123")
+        );
+        assert_eq!(cache_name(br#"{"name":"models/gemini-2.0-flash"}"#), None);
+    }
+
+    #[test]
+    fn test_expire_time_parses_upstream_timestamp() {
+        assert_eq!(
+            expire_time(
+                br#"{"name":"cachedContents/abc","expireTime":"2026-10-14T09:30:00.123456Z"}"#
+            )
+            .map(|at| at.to_rfc3339()),
+            Some("2026-10-14T09:30:00.123456+00:00".to_string())
+        );
+        assert_eq!(expire_time(br#"{"name":"cachedContents/abc"}"#), None);
+    }
+}
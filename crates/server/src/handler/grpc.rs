@@ -0,0 +1,267 @@
+//! gRPC ingress for chat completions (`prism.chat.v1.ChatCompletions`), built
+//! with the `grpc` cargo feature.
+//!
+//! The service is defined in `crates/server/proto/chat.proto`. Messages wrap the
+//! OpenAI Chat Completions JSON, so both RPCs go through the same auth, validation,
+//! routing, and request logging as `POST /v1/chat/completions`. The message types
+//! below are written by hand to avoid a `protoc` build step.
+
+use crate::AppState;
+use axum::Extension;
+use axum::extract::{Request, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use futures::Stream;
+use http_body_util::BodyExt;
+use prism_core::context::RequestContext;
+use prism_core::error::ProxyError;
+use prism_core::provider::Format;
+use prism_provider::sse::parse_sse_stream;
+use serde_json::Value;
+use std::pin::Pin;
+use tokio_stream::StreamExt;
+use tonic::codec::ProstCodec;
+use tonic::server::Grpc;
+use tonic::{Code, Status};
+
+pub const CREATE_PATH: &str = "/prism.chat.v1.ChatCompletions/Create";
+pub const CREATE_STREAM_PATH: &str = "/prism.chat.v1.ChatCompletions/CreateStream";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ChatCompletionRequest {
+    /// Chat Completions request body.
+    #[prost(string, tag = "1")]
+    pub json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ChatCompletionResponse {
+    /// `chat.completion` response body.
+    #[prost(string, tag = "1")]
+    pub json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ChatCompletionChunk {
+    /// One `chat.completion.chunk` event.
+    #[prost(string, tag = "1")]
+    pub json: String,
+}
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, Status>> + Send>>;
+
+/// Register both RPCs on the API router, behind its auth and rate limit layers.
+pub(crate) fn routes(router: axum::Router<AppState>) -> axum::Router<AppState> {
+    router
+        .route(CREATE_PATH, axum::routing::post(create))
+        .route(CREATE_STREAM_PATH, axum::routing::post(create_stream))
+}
+
+/// rpc Create(ChatCompletionRequest) returns (ChatCompletionResponse)
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    request: Request,
+) -> Response {
+    let headers = request.headers().clone();
+    let service = tower::service_fn(move |req: tonic::Request<ChatCompletionRequest>| {
+        let (state, ctx, headers) = (state.clone(), ctx.clone(), headers.clone());
+        async move {
+            let body = request_body(req.into_inner(), false).map_err(|e| to_status(&e))?;
+            let response = dispatch(&state, &ctx, &headers, body, CREATE_PATH).await?;
+            let bytes = response
+                .into_body()
+                .collect()
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .to_bytes();
+            Ok(tonic::Response::new(ChatCompletionResponse {
+                json: String::from_utf8_lossy(&bytes).into_owned(),
+            }))
+        }
+    });
+    Grpc::new(ProstCodec::default())
+        .unary(service, request)
+        .await
+        .into_response()
+}
+
+/// rpc CreateStream(ChatCompletionRequest) returns (stream ChatCompletionChunk)
+pub async fn create_stream(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    request: Request,
+) -> Response {
+    let headers = request.headers().clone();
+    let service = tower::service_fn(move |req: tonic::Request<ChatCompletionRequest>| {
+        let (state, ctx, headers) = (state.clone(), ctx.clone(), headers.clone());
+        async move {
+            let body = request_body(req.into_inner(), true).map_err(|e| to_status(&e))?;
+            let response = dispatch(&state, &ctx, &headers, body, CREATE_STREAM_PATH).await?;
+            let chunks: ChunkStream = Box::pin(
+                parse_sse_stream(response.into_body().into_data_stream()).filter_map(|event| {
+                    match event {
+                        // The end of the gRPC stream marks completion.
+                        Ok(event) if event.data == "[DONE]" => None,
+                        Ok(event) => Some(Ok(ChatCompletionChunk { json: event.data })),
+                        Err(err) => Some(Err(to_status(&err))),
+                    }
+                }),
+            );
+            Ok(tonic::Response::new(chunks))
+        }
+    });
+    Grpc::new(ProstCodec::default())
+        .server_streaming(service, request)
+        .await
+        .into_response()
+}
+
+async fn dispatch(
+    state: &AppState,
+    ctx: &RequestContext,
+    headers: &HeaderMap,
+    body: Bytes,
+    request_path: &str,
+) -> Result<Response, Status> {
+    super::dispatch_api_request(
+        state,
+        ctx,
+        headers,
+        body,
+        request_path,
+        Format::OpenAI,
+        None,
+    )
+    .await
+    .map_err(|err| to_status(&err))
+}
+
+/// Parse the wrapped JSON and set `stream` to match the RPC.
+fn request_body(request: ChatCompletionRequest, stream: bool) -> Result<Bytes, ProxyError> {
+    let mut value: Value = serde_json::from_str(&request.json)
+        .map_err(|e| ProxyError::BadRequest(format!("invalid request JSON: {e}")))?;
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| ProxyError::BadRequest("expected request object".into()))?;
+    obj.insert("stream".into(), Value::Bool(stream));
+    serde_json::to_vec(&value)
+        .map(Bytes::from)
+        .map_err(|e| ProxyError::Internal(e.to_string()))
+}
+
+/// gRPC status for a proxy error. Upstream errors follow the upstream's HTTP
+/// status; the gateway's own errors map by kind.
+fn to_status(err: &ProxyError) -> Status {
+    let code = match err {
+        ProxyError::Auth(_) | ProxyError::KeyExpired => Code::Unauthenticated,
+        ProxyError::ModelNotAllowed(_) => Code::PermissionDenied,
+        ProxyError::ModelNotFound(_) => Code::NotFound,
+        ProxyError::BadRequest(_) | ProxyError::ContentBlocked(_) => Code::InvalidArgument,
+        ProxyError::RateLimited { .. }
+        | ProxyError::ModelCooldown { .. }
+        | ProxyError::PayloadTooLarge(_) => Code::ResourceExhausted,
+        ProxyError::NoCredentials { .. }
+        | ProxyError::Overloaded { .. }
+        | ProxyError::Network(_) => Code::Unavailable,
+        ProxyError::Upstream { status, .. } => code_for_http_status(*status),
+        ProxyError::Config(_) | ProxyError::Translation(_) | ProxyError::Internal(_) => {
+            Code::Internal
+        }
+    };
+    Status::new(code, err.to_string())
+}
+
+/// gRPC code for an upstream HTTP status.
+fn code_for_http_status(status: u16) -> Code {
+    match status {
+        400 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        408 | 504 => Code::DeadlineExceeded,
+        409 => Code::Aborted,
+        413 | 429 => Code::ResourceExhausted,
+        501 => Code::Unimplemented,
+        502 | 503 => Code::Unavailable,
+        _ => Code::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body_sets_stream_for_rpc() {
+        let request = ChatCompletionRequest {
+            json: r#"{"model":"gpt-4o","stream":true,"messages":[]}"#.into(),
+        };
+        let body = request_body(request, false).unwrap();
+        let value: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(value["stream"], Value::Bool(false));
+        assert_eq!(value["model"], "gpt-4o");
+
+        let invalid = ChatCompletionRequest { json: "[]".into() };
+        let err = request_body(invalid, true).unwrap_err();
+        assert_eq!(to_status(&err).code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_to_status_maps_error_kinds() {
+        let cases = [
+            (ProxyError::Auth("bad key".into()), Code::Unauthenticated),
+            (ProxyError::KeyExpired, Code::Unauthenticated),
+            (
+                ProxyError::ModelNotAllowed("gpt-4o".into()),
+                Code::PermissionDenied,
+            ),
+            (ProxyError::ModelNotFound("gpt-9".into()), Code::NotFound),
+            (
+                ProxyError::RateLimited {
+                    message: "rpm".into(),
+                    retry_after_secs: 1,
+                },
+                Code::ResourceExhausted,
+            ),
+            (
+                ProxyError::ModelCooldown {
+                    model: "gpt-4o".into(),
+                    seconds: 5,
+                },
+                Code::ResourceExhausted,
+            ),
+            (
+                ProxyError::PayloadTooLarge("body".into()),
+                Code::ResourceExhausted,
+            ),
+            (
+                ProxyError::NoCredentials {
+                    provider: "openai".into(),
+                    model: "gpt-4o".into(),
+                },
+                Code::Unavailable,
+            ),
+            (ProxyError::Network("reset".into()), Code::Unavailable),
+            (ProxyError::Translation("bad".into()), Code::Internal),
+        ];
+        for (err, code) in cases {
+            assert_eq!(to_status(&err).code(), code, "{err}");
+        }
+    }
+
+    #[test]
+    fn test_to_status_follows_upstream_http_status() {
+        let upstream = |status| ProxyError::Upstream {
+            status,
+            body: String::new(),
+            retry_after_secs: None,
+            headers: Default::default(),
+        };
+        assert_eq!(to_status(&upstream(429)).code(), Code::ResourceExhausted);
+        assert_eq!(to_status(&upstream(401)).code(), Code::Unauthenticated);
+        assert_eq!(to_status(&upstream(504)).code(), Code::DeadlineExceeded);
+        assert_eq!(to_status(&upstream(500)).code(), Code::Internal);
+    }
+}
@@ -1,13 +1,203 @@
 use crate::AppState;
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use serde::Deserialize;
+use std::time::Duration;
 
-pub async fn health() -> impl IntoResponse {
-    Json(serde_json::json!({
-        "status": "ok",
-        "version": env!("CARGO_PKG_VERSION"),
-    }))
+/// Timeout for resolving each upstream host in the verbose health check.
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize)]
+pub struct HealthQuery {
+    pub verbose: Option<String>,
+}
+
+/// GET /health — liveness. With `?verbose=1` and a dashboard token, also checks
+/// dependencies and returns an overall `healthy` / `degraded` / `unhealthy` verdict
+/// (503 when unhealthy). The checks touch the filesystem and DNS and name upstream
+/// hosts, so they are not served anonymously.
+pub async fn health(
+    State(state): State<AppState>,
+    Query(query): Query<HealthQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let verbose = query
+        .verbose
+        .as_deref()
+        .is_some_and(|v| v == "1" || v == "true");
+    if !verbose {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "ok",
+                "version": env!("CARGO_PKG_VERSION"),
+            })),
+        );
+    }
+
+    if !has_dashboard_session(&state, &headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": "missing_token",
+                "message": "Verbose health checks require a dashboard token",
+            })),
+        );
+    }
+
+    let config_file = check_config_file(&state);
+    let log_dir = check_log_dir(&state);
+    let upstream_dns = check_upstream_dns(&state).await;
+
+    let unhealthy = config_file["status"] == "failed" || upstream_dns["status"] == "failed";
+    let degraded = log_dir["status"] == "failed" || upstream_dns["status"] == "degraded";
+    let (status, verdict) = if unhealthy {
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy")
+    } else if degraded {
+        (StatusCode::OK, "degraded")
+    } else {
+        (StatusCode::OK, "healthy")
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": verdict,
+            "version": env!("CARGO_PKG_VERSION"),
+            "checks": {
+                "config_file": config_file,
+                "log_dir": log_dir,
+                "upstream_dns": upstream_dns,
+            },
+        })),
+    )
+}
+
+fn has_dashboard_session(state: &AppState, headers: &HeaderMap) -> bool {
+    use crate::middleware::dashboard_auth::{decode_claims, extract_token_from_headers};
+    let Some(secret) = state.config.load().dashboard.resolve_jwt_secret() else {
+        return false;
+    };
+    extract_token_from_headers(headers).is_some_and(|token| decode_claims(&token, &secret).is_ok())
+}
+
+fn check_config_file(state: &AppState) -> serde_json::Value {
+    let path = state
+        .config_path
+        .lock()
+        .map(|p| p.clone())
+        .unwrap_or_default();
+    match std::fs::File::open(&path) {
+        Ok(_) => serde_json::json!({ "status": "ok" }),
+        Err(e) => serde_json::json!({ "status": "failed", "error": e.to_string() }),
+    }
+}
+
+/// Only checked when logging to file; writes and removes a probe file.
+fn check_log_dir(state: &AppState) -> serde_json::Value {
+    let config = state.config.load();
+    if !config.logging_to_file {
+        return serde_json::json!({ "status": "skipped" });
+    }
+    let dir = config
+        .log_dir
+        .clone()
+        .unwrap_or_else(|| "./logs".to_string());
+    let probe = std::path::Path::new(&dir).join(format!(".health-{}", uuid::Uuid::new_v4()));
+    let result = std::fs::write(&probe, b"").and_then(|()| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => serde_json::json!({ "status": "ok" }),
+        Err(e) => serde_json::json!({ "status": "failed", "error": e.to_string() }),
+    }
+}
+
+/// Resolve every distinct upstream host of enabled credentials. `failed` when none
+/// resolve, `degraded` when only some do.
+async fn check_upstream_dns(state: &AppState) -> serde_json::Value {
+    let mut targets: Vec<(String, u16)> = Vec::new();
+    for auth in state.router.credential_map().into_values().flatten() {
+        if auth.disabled {
+            continue;
+        }
+        let Ok(url) = reqwest::Url::parse(&auth.resolved_base_url()) else {
+            continue;
+        };
+        if let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) {
+            let target = (host.to_string(), port);
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    if targets.is_empty() {
+        return serde_json::json!({ "status": "skipped", "hosts": {} });
+    }
+
+    let lookups = targets.iter().map(|(host, port)| async move {
+        let outcome =
+            tokio::time::timeout(DNS_TIMEOUT, tokio::net::lookup_host((host.as_str(), *port)))
+                .await;
+        let result = match outcome {
+            Ok(Ok(mut addrs)) => match addrs.next() {
+                Some(_) => Ok(()),
+                None => Err("no addresses".to_string()),
+            },
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("timed out".to_string()),
+        };
+        (host.clone(), result)
+    });
+    let results = futures::future::join_all(lookups).await;
+
+    let resolved = results.iter().filter(|(_, r)| r.is_ok()).count();
+    let status = if resolved == results.len() {
+        "ok"
+    } else if resolved == 0 {
+        "failed"
+    } else {
+        "degraded"
+    };
+    let hosts: serde_json::Map<String, serde_json::Value> = results
+        .into_iter()
+        .map(|(host, result)| {
+            (
+                host,
+                result.err().unwrap_or_else(|| "ok".to_string()).into(),
+            )
+        })
+        .collect();
+    serde_json::json!({ "status": status, "hosts": hosts })
+}
+
+/// GET /ready — 503 until the listener is bound, executors exist, the optional
+/// startup warm-up has finished, and the optional credential probe has passed.
+pub async fn ready(State(state): State<AppState>) -> impl IntoResponse {
+    let listening = state.readiness.is_listening();
+    let executors = state.executors.all().next().is_some();
+    let probe = state.readiness.probe();
+    let warm_up = state.readiness.warm_up();
+    let ready =
+        listening && executors && state.readiness.warm_up_done() && state.readiness.probe_passed();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "checks": {
+                "config": "ok",
+                "executors": if executors { "ok" } else { "missing" },
+                "listener": if listening { "ok" } else { "pending" },
+                "credential_probe": probe,
+                "warm_up": warm_up,
+                "token_sources": state.auth_runtime.tokens().status(),
+            },
+        })),
+    )
 }
 
 pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
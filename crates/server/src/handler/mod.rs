@@ -1,15 +1,23 @@
 pub mod admin;
 pub mod chat_completions;
+pub mod chat_ws;
 pub mod completions;
 pub mod count_tokens;
 pub mod dashboard;
 pub mod gemini;
+pub mod gemini_cache;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod health;
 pub mod messages;
 pub mod models;
+pub mod moderations;
+pub mod openai_passthrough;
+pub mod openapi;
 pub mod provider_scoped;
 pub mod responses;
 pub mod responses_ws;
+pub(crate) mod validation;
 
 use crate::AppState;
 use crate::dispatch::{DispatchRequest, dispatch};
@@ -29,8 +37,57 @@ pub(crate) struct ParsedRequest {
     pub user_agent: Option<String>,
     /// Debug mode: return routing details in response headers.
     pub debug: bool,
+    /// `x-debug: trace`: also record a retrievable debug trace.
+    pub debug_trace: bool,
     /// Optional request-scoped auth profile pin.
     pub auth_profile: Option<String>,
+    /// `x-proxy-*` overrides; only honored once [`apply_routing_hints`] allows them.
+    pub hints: RoutingHints,
+}
+
+/// Request header overrides for debugging and targeted evaluations.
+#[derive(Debug, Default)]
+pub(crate) struct RoutingHints {
+    /// `x-proxy-model`: replaces the body model.
+    pub model: Option<String>,
+    /// `x-proxy-provider`: restricts routing to one provider's credentials.
+    pub provider: Option<String>,
+    /// `x-proxy-credential`: pins one credential.
+    pub credential: Option<String>,
+}
+
+impl RoutingHints {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            model: header_value(headers, "x-proxy-model"),
+            provider: header_value(headers, "x-proxy-provider"),
+            credential: header_value(headers, "x-proxy-credential"),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.model.is_none() && self.provider.is_none() && self.credential.is_none()
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(ToString::to_string)
+}
+
+/// `x-debug: true` (or `1`) adds routing details to the response headers;
+/// `x-debug: trace` also asks for a debug trace, recorded when `request-debug`
+/// allows it.
+pub(crate) fn debug_flags(headers: &HeaderMap) -> (bool, bool) {
+    match headers.get("x-debug").and_then(|v| v.to_str().ok()) {
+        Some("true" | "1") => (true, false),
+        Some("trace") => (true, true),
+        _ => (false, false),
+    }
 }
 
 pub(crate) fn parse_request(
@@ -65,18 +122,9 @@ pub(crate) fn parse_request(
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    // Check x-debug header
-    let debug = headers
-        .get("x-debug")
-        .and_then(|v| v.to_str().ok())
-        .is_some_and(|v| v == "true" || v == "1");
+    let (debug, debug_trace) = debug_flags(headers);
 
-    let auth_profile = headers
-        .get("x-prism-auth-profile")
-        .and_then(|v| v.to_str().ok())
-        .map(str::trim)
-        .filter(|v| !v.is_empty())
-        .map(ToString::to_string);
+    let auth_profile = header_value(headers, "x-prism-auth-profile");
 
     Ok(ParsedRequest {
         model,
@@ -84,10 +132,72 @@ pub(crate) fn parse_request(
         stream,
         user_agent,
         debug,
+        debug_trace,
         auth_profile,
+        hints: RoutingHints::from_headers(headers),
     })
 }
 
+/// Apply `x-proxy-*` hints to the parsed request and return the credential scope.
+/// Hints need `routing-hint-headers` or an auth key with `routing-hints: true`.
+pub(crate) fn apply_routing_hints(
+    state: &AppState,
+    ctx: &RequestContext,
+    parsed: &mut ParsedRequest,
+    body: &mut Bytes,
+) -> Result<Vec<String>, ProxyError> {
+    let key_credentials = ctx
+        .auth_key
+        .as_ref()
+        .map(|e| e.allowed_credentials.clone())
+        .unwrap_or_default();
+    let hints = std::mem::take(&mut parsed.hints);
+    if hints.is_empty() {
+        return merge_requested_credential(key_credentials, parsed.auth_profile.as_deref());
+    }
+
+    let allowed = state.config.load().routing_hint_headers
+        || ctx.auth_key.as_ref().is_some_and(|e| e.routing_hints);
+    if !allowed {
+        return Err(ProxyError::Auth(
+            "x-proxy-* routing headers are not enabled for this API key".to_string(),
+        ));
+    }
+
+    if let Some(model) = hints.model {
+        *body = crate::dispatch::rewrite_model_in_body(body, &model);
+        parsed.model = model;
+    }
+
+    let requested_credential = hints.credential.or(parsed.auth_profile.take());
+    let Some(provider) = hints.provider else {
+        return merge_requested_credential(key_credentials, requested_credential.as_deref());
+    };
+    let mut credentials = provider_scoped::resolve_provider(state, &provider)?;
+    if !key_credentials.is_empty() {
+        credentials.retain(|name| {
+            key_credentials
+                .iter()
+                .any(|pattern| prism_core::glob::glob_match(pattern, name))
+        });
+        if credentials.is_empty() {
+            return Err(ProxyError::BadRequest(format!(
+                "no accessible credentials for provider '{provider}' with current API key"
+            )));
+        }
+    }
+    if let Some(requested) = requested_credential {
+        credentials
+            .retain(|candidate| provider_scoped::matches_scoped_credential(candidate, &requested));
+        if credentials.is_empty() {
+            return Err(ProxyError::BadRequest(format!(
+                "unknown credential '{requested}' for provider '{provider}'"
+            )));
+        }
+    }
+    Ok(credentials)
+}
+
 pub(crate) fn merge_requested_credential(
     mut allowed_credentials: Vec<String>,
     requested_credential: Option<&str>,
@@ -123,20 +233,17 @@ pub(crate) async fn dispatch_api_request(
     state: &AppState,
     ctx: &RequestContext,
     headers: &HeaderMap,
-    body: Bytes,
+    mut body: Bytes,
     request_path: &str,
     source_format: Format,
     allowed_formats: Option<Vec<Format>>,
 ) -> Result<Response, ProxyError> {
-    let parsed = parse_request(headers, &body)?;
+    let mut parsed = parse_request(headers, &body)?;
+    if let Some(kind) = validation::RequestKind::from_path(request_path) {
+        validation::validate_request(kind, &body)?;
+    }
 
-    let allowed_credentials = merge_requested_credential(
-        ctx.auth_key
-            .as_ref()
-            .map(|e| e.allowed_credentials.clone())
-            .unwrap_or_default(),
-        parsed.auth_profile.as_deref(),
-    )?;
+    let allowed_credentials = apply_routing_hints(state, ctx, &mut parsed, &mut body)?;
 
     dispatch(
         state,
@@ -150,13 +257,19 @@ pub(crate) async fn dispatch_api_request(
             allowed_formats,
             user_agent: parsed.user_agent,
             debug: parsed.debug,
+            debug_trace: parsed.debug_trace,
             api_key: ctx.auth_key.as_ref().map(|e| e.key.clone()),
             client_region: ctx.client_region.clone(),
             request_id: Some(ctx.request_id.clone()),
+            trace: Some(ctx.trace.clone()),
+            forwarded_headers: crate::dispatch::forwarded_headers(headers, &state.config.load()),
+            routing_headers: crate::dispatch::routing_headers(headers, &state.config.load()),
+            anthropic_betas: crate::dispatch::anthropic_betas(headers),
             api_key_id: ctx.api_key_id.clone(),
             tenant_id: ctx.tenant_id.clone(),
             allowed_credentials,
             responses_passthrough: false,
+            experiment: None,
         },
     )
     .await
@@ -216,6 +329,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_request_routing_hints() {
+        let body = make_body(serde_json::json!({"model": "gpt-4"}));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-proxy-model", "gpt-4o-mini".parse().unwrap());
+        headers.insert("x-proxy-provider", " openai ".parse().unwrap());
+        headers.insert("x-proxy-credential", "".parse().unwrap());
+        let parsed = parse_request(&headers, &body).unwrap();
+        assert_eq!(parsed.model, "gpt-4");
+        assert_eq!(parsed.hints.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(parsed.hints.provider.as_deref(), Some("openai"));
+        assert!(parsed.hints.credential.is_none());
+        assert!(!parsed.hints.is_empty());
+    }
+
     #[test]
     fn test_parse_request_user_agent() {
         let body = make_body(serde_json::json!({"model": "gpt-4"}));
@@ -243,6 +371,16 @@ mod tests {
         assert!(parsed.debug);
     }
 
+    #[test]
+    fn test_parse_request_debug_trace() {
+        let body = make_body(serde_json::json!({"model": "gpt-4"}));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-debug", "trace".parse().unwrap());
+        let parsed = parse_request(&headers, &body).unwrap();
+        assert!(parsed.debug);
+        assert!(parsed.debug_trace);
+    }
+
     #[test]
     fn test_parse_request_debug_false_value() {
         let body = make_body(serde_json::json!({"model": "gpt-4"}));
@@ -6,6 +6,8 @@ pub async fn list_models(State(state): State<AppState>) -> Result<impl IntoRespo
     let models = state.router.all_models();
     let created = chrono::Utc::now().timestamp();
 
+    let config = state.config.load();
+
     let data: Vec<serde_json::Value> = models
         .into_iter()
         .map(|m| {
@@ -16,6 +18,14 @@ pub async fn list_models(State(state): State<AppState>) -> Result<impl IntoRespo
                 "owned_by": m.owned_by,
             })
         })
+        .chain(config.preset_models.iter().map(|preset| {
+            serde_json::json!({
+                "id": preset.name,
+                "object": "model",
+                "created": created,
+                "owned_by": "preset",
+            })
+        }))
         .collect();
 
     let response = serde_json::json!({
@@ -0,0 +1,220 @@
+use crate::AppState;
+use axum::Extension;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use prism_core::context::{RequestContext, TraceContext};
+use prism_core::error::ProxyError;
+use prism_core::provider::{AuthRecord, Format, UpstreamKind};
+use prism_core::request_record::{LogDetailLevel, classify_error, truncate_body};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Model used for routing and ACL checks when the request omits `model`.
+const DEFAULT_MODERATION_MODEL: &str = "omni-moderation-latest";
+
+/// POST /v1/moderations — Proxy to an OpenAI-compatible moderation endpoint.
+///
+/// Tries each OpenAI-format provider that serves the model, failing over on
+/// 429, 5xx, and network errors. Responses are passed through unchanged.
+pub async fn moderations(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let start = Instant::now();
+    let req_value: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|e| ProxyError::BadRequest(e.to_string()))?;
+    if req_value
+        .get("input")
+        .is_none_or(serde_json::Value::is_null)
+    {
+        return Err(ProxyError::BadRequest("missing input field".into()));
+    }
+    let model = req_value
+        .get("model")
+        .and_then(|m| m.as_str())
+        .unwrap_or(DEFAULT_MODERATION_MODEL)
+        .to_string();
+
+    // Enforce model ACL (same as main dispatch path)
+    if let Some(ref auth_key) = ctx.auth_key
+        && !prism_core::auth_key::AuthKeyStore::check_model_access(auth_key, &model)
+    {
+        return Err(ProxyError::ModelNotAllowed(format!(
+            "model '{model}' not allowed for this API key",
+        )));
+    }
+
+    let requested_credential = headers
+        .get("x-prism-auth-profile")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let allowed_credentials = super::merge_requested_credential(
+        ctx.auth_key
+            .as_ref()
+            .map(|e| e.allowed_credentials.clone())
+            .unwrap_or_default(),
+        requested_credential,
+    )?;
+
+    let config = state.config.load();
+    let detail_level = config.log_store.detail_level;
+    let max_body_bytes = config.log_store.max_body_bytes;
+    let request_span = tracing::info_span!(
+        "gateway.request",
+        request_id = ctx.request_id.as_str(),
+        trace_id = ctx.trace.trace_id.as_str(),
+        method = "POST",
+        path = "/v1/moderations",
+        stream = false,
+        requested_model = model.as_str(),
+        request_body = tracing::field::Empty,
+        provider = tracing::field::Empty,
+        model = tracing::field::Empty,
+        credential_name = tracing::field::Empty,
+        total_attempts = tracing::field::Empty,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        response_body = tracing::field::Empty,
+        error = tracing::field::Empty,
+        error_type = tracing::field::Empty,
+        api_key_id = ctx.api_key_id.as_deref().unwrap_or(""),
+        tenant_id = ctx.tenant_id.as_deref().unwrap_or(""),
+        client_region = ctx.client_region.as_deref().unwrap_or(""),
+    );
+    state.metrics.record_request_bytes(body.len() as u64);
+    if detail_level >= LogDetailLevel::Standard
+        && let Ok(body_str) = std::str::from_utf8(&body)
+    {
+        request_span.record(
+            "request_body",
+            truncate_body(body_str, max_body_bytes).as_ref(),
+        );
+    }
+
+    let forwarded = crate::dispatch::forwarded_headers(&headers, &config);
+    let sticky = crate::dispatch::sticky_value(&config, &ctx, &headers);
+    let mut tried = Vec::new();
+    let mut last_error = None;
+    for (provider_name, format) in state.router.resolve_providers(&model) {
+        if format != Format::OpenAI {
+            continue;
+        }
+        let Some(auth) = state.router.pick(
+            &provider_name,
+            &model,
+            &tried,
+            ctx.client_region.as_deref(),
+            &allowed_credentials,
+            sticky.as_deref(),
+        ) else {
+            continue;
+        };
+        if auth.upstream != UpstreamKind::OpenAI {
+            continue;
+        }
+        tried.push(auth.id.clone());
+
+        match forward(
+            &state, &auth, &ctx.trace, &forwarded, &model, &req_value, &body,
+        )
+        .await
+        {
+            Ok(payload) => {
+                state.router.record_success(&auth.id);
+                state.metrics.record_request(&model, format.as_str());
+                state.metrics.record_latency_ms(start.elapsed().as_millis());
+                request_span.record("provider", provider_name.as_str());
+                request_span.record("model", auth.resolve_model_id(&model).as_str());
+                request_span.record(
+                    "credential_name",
+                    auth.credential_name.as_deref().unwrap_or(""),
+                );
+                request_span.record("total_attempts", tried.len() as u64);
+                request_span.record("status", 200u64);
+                request_span.record("latency_ms", start.elapsed().as_millis() as u64);
+                if detail_level >= LogDetailLevel::Full
+                    && let Ok(body_str) = std::str::from_utf8(&payload)
+                {
+                    request_span.record(
+                        "response_body",
+                        truncate_body(body_str, max_body_bytes).as_ref(),
+                    );
+                }
+                return Ok((
+                    StatusCode::OK,
+                    [("content-type", "application/json")],
+                    payload,
+                )
+                    .into_response());
+            }
+            Err(err) => {
+                let retryable = matches!(
+                    err,
+                    ProxyError::Upstream {
+                        status: 429 | 500..=599,
+                        ..
+                    } | ProxyError::Network(_)
+                );
+                if !retryable {
+                    last_error = Some(err);
+                    break;
+                }
+                state.router.record_failure(&auth.id);
+                last_error = Some(err);
+            }
+        }
+    }
+
+    let err = last_error.unwrap_or_else(|| ProxyError::NoCredentials {
+        provider: "openai".into(),
+        model: model.clone(),
+    });
+    state.metrics.record_error();
+    state.metrics.record_latency_ms(start.elapsed().as_millis());
+    request_span.record("total_attempts", tried.len() as u64);
+    request_span.record("status", err.status_code_u16() as u64);
+    request_span.record("latency_ms", start.elapsed().as_millis() as u64);
+    request_span.record("error", err.to_string());
+    request_span.record("error_type", classify_error(&err));
+    Err(err)
+}
+
+/// Send the request to one credential, resolving model aliases in the body.
+async fn forward(
+    state: &AppState,
+    auth: &AuthRecord,
+    trace: &TraceContext,
+    forwarded: &HashMap<String, String>,
+    model: &str,
+    req_value: &serde_json::Value,
+    body: &Bytes,
+) -> Result<Bytes, ProxyError> {
+    let actual_model = auth.resolve_model_id(model);
+    let payload = if req_value.get("model").is_some() && actual_model != model {
+        let mut value = req_value.clone();
+        value["model"] = serde_json::Value::String(actual_model);
+        Bytes::from(serde_json::to_vec(&value).map_err(|e| ProxyError::Internal(e.to_string()))?)
+    } else {
+        body.clone()
+    };
+
+    let url = format!("{}/v1/moderations", auth.resolved_base_url());
+    let client = prism_provider::common::build_client(auth, &state.http_client_pool)?;
+    let req = client
+        .post(&url)
+        .header("content-type", "application/json")
+        .body(payload);
+    let req = prism_provider::common::apply_auth(req, auth);
+    let config = state.config.load();
+    let mut headers = HashMap::new();
+    crate::dispatch::insert_trace_header(&mut headers, &config, Some(trace));
+    crate::dispatch::apply_outbound_headers(&mut headers, &config, forwarded);
+    let req = prism_provider::common::apply_headers(req, &headers, auth);
+    let (payload, _) = prism_provider::common::handle_response(req.send().await?).await?;
+    Ok(payload)
+}
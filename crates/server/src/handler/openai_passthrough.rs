@@ -0,0 +1,225 @@
+//! Raw passthrough of the OpenAI Files, Assistants, and Threads APIs, and with
+//! `catch-all` of any other `/v1` path the proxy has no route for.
+//!
+//! Enabled by `openai-passthrough`. Requests go to one designated credential
+//! without translation; only the client auth is swapped for the credential's key.
+//! Files, assistants, and threads live in that upstream account, so there is no
+//! failover to other credentials.
+
+use crate::AppState;
+use axum::Extension;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use prism_core::config::OpenAIPassthroughConfig;
+use prism_core::context::RequestContext;
+use prism_core::error::ProxyError;
+use prism_core::provider::{AuthRecord, UpstreamKind};
+use prism_core::request_record::classify_error;
+use prism_provider::routing::check_credential_access;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Client headers the upstream API needs, e.g. `OpenAI-Beta: assistants=v2`.
+const FORWARDED_REQUEST_HEADERS: &[&str] = &["accept", "openai-beta"];
+
+/// Upstream headers returned to the client along with the body.
+const RETURNED_RESPONSE_HEADERS: &[&str] = &[
+    "content-type",
+    "content-disposition",
+    "cache-control",
+    "openai-processing-ms",
+    "x-request-id",
+];
+
+/// ANY /v1/files, /v1/assistants, /v1/threads, and everything below them.
+pub async fn openai_passthrough(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    if !state.config.load().openai_passthrough.enabled {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+    passthrough(&state, &ctx, method, uri, headers, body).await
+}
+
+/// ANY /v1/{*path} — every `/v1` path without a route of its own.
+pub async fn unknown_path_passthrough(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let config = state.config.load();
+    if !(config.openai_passthrough.enabled && config.openai_passthrough.catch_all) {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+    passthrough(&state, &ctx, method, uri, headers, body).await
+}
+
+async fn passthrough(
+    state: &AppState,
+    ctx: &RequestContext,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let config = state.config.load();
+    let start = Instant::now();
+    let path = uri.path();
+    let request_span = tracing::info_span!(
+        "gateway.request",
+        request_id = ctx.request_id.as_str(),
+        trace_id = ctx.trace.trace_id.as_str(),
+        method = method.as_str(),
+        path = path,
+        stream = false,
+        requested_model = "",
+        provider = tracing::field::Empty,
+        credential_name = tracing::field::Empty,
+        total_attempts = tracing::field::Empty,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        error = tracing::field::Empty,
+        error_type = tracing::field::Empty,
+        api_key_id = ctx.api_key_id.as_deref().unwrap_or(""),
+        tenant_id = ctx.tenant_id.as_deref().unwrap_or(""),
+        client_region = ctx.client_region.as_deref().unwrap_or(""),
+    );
+    state.metrics.record_request_bytes(body.len() as u64);
+
+    let result = match passthrough_credential(state, &config.openai_passthrough, ctx, &headers) {
+        Ok(auth) => {
+            request_span.record("provider", config.openai_passthrough.provider.as_str());
+            request_span.record(
+                "credential_name",
+                auth.credential_name.as_deref().unwrap_or(""),
+            );
+            request_span.record("total_attempts", 1u64);
+            forward(state, &auth, ctx, &method, &uri, &headers, body).await
+        }
+        Err(err) => Err(err),
+    };
+
+    state.metrics.record_latency_ms(start.elapsed().as_millis());
+    request_span.record("latency_ms", start.elapsed().as_millis() as u64);
+    match result {
+        Ok(response) => {
+            request_span.record("status", response.status().as_u16() as u64);
+            Ok(response)
+        }
+        Err(err) => {
+            state.metrics.record_error();
+            request_span.record("status", err.status_code_u16() as u64);
+            request_span.record("error", err.to_string());
+            request_span.record("error_type", classify_error(&err));
+            Err(err)
+        }
+    }
+}
+
+/// First available credential of the passthrough provider that matches the
+/// configured name and the caller's allowed credentials. Codex credentials do
+/// not serve these APIs and are skipped.
+fn passthrough_credential(
+    state: &AppState,
+    passthrough: &OpenAIPassthroughConfig,
+    ctx: &RequestContext,
+    headers: &HeaderMap,
+) -> Result<AuthRecord, ProxyError> {
+    let requested_credential = headers
+        .get("x-prism-auth-profile")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let allowed_credentials = super::merge_requested_credential(
+        ctx.auth_key
+            .as_ref()
+            .map(|e| e.allowed_credentials.clone())
+            .unwrap_or_default(),
+        requested_credential,
+    )?;
+    let designated: Vec<String> = passthrough.credential.iter().cloned().collect();
+
+    state
+        .router
+        .credential_map()
+        .remove(&passthrough.provider)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|auth| {
+            auth.is_available()
+                && auth.upstream == UpstreamKind::OpenAI
+                && check_credential_access(&designated, auth.credential_name.as_deref())
+                && check_credential_access(&allowed_credentials, auth.credential_name.as_deref())
+        })
+        .ok_or_else(|| ProxyError::NoCredentials {
+            provider: passthrough.provider.clone(),
+            model: "openai-passthrough".into(),
+        })
+}
+
+/// Send the request unchanged apart from auth, streaming the response back.
+async fn forward(
+    state: &AppState,
+    auth: &AuthRecord,
+    ctx: &RequestContext,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let path_and_query = uri.path_and_query().map_or(uri.path(), |p| p.as_str());
+    let url = format!("{}{path_and_query}", auth.resolved_base_url());
+    let client = prism_provider::common::build_client(auth, &state.http_client_pool)?;
+    let mut req = client.request(method.clone(), &url);
+    // Multipart uploads carry their boundary in the content type.
+    if let Some(content_type) = headers.get("content-type") {
+        req = req.header("content-type", content_type);
+    }
+    let req = prism_provider::common::apply_auth(req.body(body), auth);
+
+    let config = state.config.load();
+    let mut upstream_headers: HashMap<String, String> = FORWARDED_REQUEST_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = headers.get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
+    crate::dispatch::insert_trace_header(&mut upstream_headers, &config, Some(&ctx.trace));
+    crate::dispatch::apply_outbound_headers(
+        &mut upstream_headers,
+        &config,
+        &crate::dispatch::forwarded_headers(headers, &config),
+    );
+    let req = prism_provider::common::apply_headers(req, &upstream_headers, auth);
+
+    let resp = req.send().await?;
+    let status =
+        StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        state.router.record_failure(&auth.id);
+    } else {
+        state.router.record_success(&auth.id);
+    }
+
+    let mut response = Response::builder().status(status);
+    for name in RETURNED_RESPONSE_HEADERS {
+        if let Some(value) = resp.headers().get(*name) {
+            response = response.header(*name, value);
+        }
+    }
+    response
+        .body(Body::from_stream(resp.bytes_stream()))
+        .map_err(|e| ProxyError::Internal(format!("failed to build response: {e}")))
+}
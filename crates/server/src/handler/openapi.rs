@@ -0,0 +1,867 @@
+//! OpenAPI 3.1 description of the gateway, served at `GET /api/openapi.json`.
+//!
+//! Operations are listed in [`OPERATIONS`]; tests check that every `/v1`,
+//! `/v1beta`, `/api/provider`, `/admin`, and `/api/dashboard` route in
+//! `build_router` is listed, and that the component schemas match the
+//! serialized shape of the server types they describe. Dashboard and admin
+//! operations are published under their current versioned path
+//! (`/api/v1/dashboard/*`, `/api/v1/admin/*`); the unversioned aliases are
+//! deprecated and left out.
+
+use crate::api_version;
+use axum::Json;
+use axum::response::IntoResponse;
+use serde_json::{Map, Value, json};
+use std::sync::LazyLock;
+
+/// One documented operation. Path parameters are taken from `{name}` segments.
+#[derive(Clone, Copy)]
+struct Operation {
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    /// `(name, JSON schema type)` query parameters.
+    query: &'static [(&'static str, &'static str)],
+    /// Component schema of the 200 response, if documented.
+    response: Option<&'static str>,
+}
+
+const fn op(method: &'static str, path: &'static str, summary: &'static str) -> Operation {
+    Operation {
+        method,
+        path,
+        summary,
+        query: &[],
+        response: None,
+    }
+}
+
+impl Operation {
+    const fn query(mut self, query: &'static [(&'static str, &'static str)]) -> Self {
+        self.query = query;
+        self
+    }
+
+    const fn returns(mut self, schema: &'static str) -> Self {
+        self.response = Some(schema);
+        self
+    }
+}
+
+const LOG_QUERY: &[(&str, &str)] = &[
+    ("page", "integer"),
+    ("page_size", "integer"),
+    ("request_id", "string"),
+    ("tenant_id", "string"),
+    ("api_key_id", "string"),
+    ("provider", "string"),
+    ("model", "string"),
+    ("status", "string"),
+    ("error_type", "string"),
+    ("stream", "boolean"),
+    ("tags", "string"),
+    ("from", "integer"),
+    ("to", "integer"),
+    ("latency_min", "integer"),
+    ("latency_max", "integer"),
+    ("keyword", "string"),
+    ("sort_by", "string"),
+    ("sort_order", "string"),
+];
+
+const STATS_QUERY: &[(&str, &str)] = &[
+    ("from", "integer"),
+    ("to", "integer"),
+    ("provider", "string"),
+    ("model", "string"),
+    ("tags", "string"),
+];
+
+const ERROR_GROUP_QUERY: &[(&str, &str)] = &[
+    ("from", "integer"),
+    ("to", "integer"),
+    ("provider", "string"),
+    ("model", "string"),
+    ("tags", "string"),
+    ("limit", "integer"),
+];
+
+const TAG_COST_QUERY: &[(&str, &str)] =
+    &[("from", "integer"), ("to", "integer"), ("prefix", "string")];
+
+/// Methods the passthrough routes are documented with; they accept any method.
+const PASSTHROUGH: [&str; 3] = ["get", "post", "delete"];
+
+const OPERATIONS: &[Operation] = &[
+    // Admin
+    op("get", "/admin/config", "Sanitized configuration summary"),
+    op("get", "/admin/metrics", "Full metrics snapshot"),
+    op("get", "/admin/models", "All models known to the router"),
+    // OpenAI-compatible API
+    op("get", "/v1/models", "List models available to the caller"),
+    op("post", "/v1/chat/completions", "OpenAI Chat Completions"),
+    op("get", "/v1/ws/chat", "Chat Completions over WebSocket"),
+    op("post", "/v1/messages", "Anthropic Messages"),
+    op("post", "/v1/completions", "OpenAI legacy Completions"),
+    op("post", "/v1/responses", "OpenAI Responses"),
+    op("get", "/v1/responses/ws", "Responses over WebSocket"),
+    op(
+        "post",
+        "/v1/messages/count_tokens",
+        "Anthropic token counting",
+    ),
+    op("post", "/v1/moderations", "OpenAI Moderations"),
+    // Gemini-compatible API
+    op("get", "/v1beta/models", "List models in Gemini format"),
+    op(
+        "post",
+        "/v1beta/models/{model_action}",
+        "Gemini generateContent, streamGenerateContent, or countTokens (`model:action`)",
+    ),
+    op(
+        "post",
+        "/v1beta/cachedContents",
+        "Create a Gemini cached content",
+    ),
+    op(
+        "get",
+        "/v1beta/cachedContents/{id}",
+        "Get a Gemini cached content",
+    ),
+    op(
+        "patch",
+        "/v1beta/cachedContents/{id}",
+        "Update a Gemini cached content",
+    ),
+    op(
+        "delete",
+        "/v1beta/cachedContents/{id}",
+        "Delete a Gemini cached content",
+    ),
+    // Provider-scoped API
+    op(
+        "post",
+        "/api/provider/{provider}/v1/chat/completions",
+        "Chat Completions restricted to one provider",
+    ),
+    op(
+        "post",
+        "/api/provider/{provider}/v1/messages",
+        "Messages restricted to one provider",
+    ),
+    op(
+        "post",
+        "/api/provider/{provider}/v1/responses",
+        "Responses restricted to one provider",
+    ),
+    op(
+        "get",
+        "/api/provider/{provider}/v1/responses/ws",
+        "Responses over WebSocket restricted to one provider",
+    ),
+    // Dashboard: session
+    op(
+        "post",
+        "/api/dashboard/auth/login",
+        "Log in and receive a dashboard token",
+    ),
+    op(
+        "get",
+        "/api/dashboard/auth/session",
+        "Current dashboard session",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth/refresh",
+        "Refresh the dashboard token",
+    ),
+    op("post", "/api/dashboard/auth/logout", "Log out"),
+    // Dashboard: auth profiles
+    op("get", "/api/dashboard/auth-profiles", "List auth profiles"),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles",
+        "Create an auth profile",
+    ),
+    op(
+        "get",
+        "/api/dashboard/auth-profiles/runtime",
+        "Runtime state of managed auth profiles",
+    ),
+    op(
+        "put",
+        "/api/dashboard/auth-profiles/{provider}/{profile}",
+        "Replace an auth profile",
+    ),
+    op(
+        "delete",
+        "/api/dashboard/auth-profiles/{provider}/{profile}",
+        "Delete an auth profile",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/codex/oauth/start",
+        "Start a Codex OAuth login",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/codex/oauth/complete",
+        "Complete a Codex OAuth login",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/codex/device/start",
+        "Start a Codex device login",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/codex/device/poll",
+        "Poll a Codex device login",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/{provider}/{profile}/connect",
+        "Connect a managed auth profile",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/{provider}/{profile}/import-local",
+        "Import local CLI credentials into an auth profile",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-profiles/{provider}/{profile}/refresh",
+        "Refresh an auth profile's token",
+    ),
+    // Dashboard: providers
+    op(
+        "post",
+        "/api/dashboard/providers/fetch-models",
+        "Fetch the model list from an upstream",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/bulk",
+        "Create several providers",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/duplicate",
+        "Duplicate a provider",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/disable",
+        "Disable a provider",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/enable",
+        "Enable a provider",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/health",
+        "Health-check a provider",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/test-request",
+        "Send a test request through a provider",
+    ),
+    op(
+        "post",
+        "/api/dashboard/providers/{id}/presentation-preview",
+        "Preview upstream presentation headers and body",
+    ),
+    op("get", "/api/dashboard/providers", "List providers").query(&[("tags", "string")]),
+    op("post", "/api/dashboard/providers", "Create a provider"),
+    op("get", "/api/dashboard/providers/{id}", "Get a provider"),
+    op(
+        "patch",
+        "/api/dashboard/providers/{id}",
+        "Update a provider",
+    ),
+    op(
+        "delete",
+        "/api/dashboard/providers/{id}",
+        "Delete a provider",
+    ),
+    op(
+        "get",
+        "/api/dashboard/providers/capabilities",
+        "Provider capability matrix",
+    ),
+    // Dashboard: auth keys
+    op("get", "/api/dashboard/auth-keys", "List API keys"),
+    op("post", "/api/dashboard/auth-keys", "Create an API key"),
+    op(
+        "patch",
+        "/api/dashboard/auth-keys/{id}",
+        "Update an API key",
+    ),
+    op(
+        "delete",
+        "/api/dashboard/auth-keys/{id}",
+        "Delete an API key",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-keys/{id}/reveal",
+        "Reveal an API key",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-keys/{id}/disable",
+        "Disable an API key",
+    ),
+    op(
+        "post",
+        "/api/dashboard/auth-keys/{id}/enable",
+        "Enable an API key",
+    ),
+    // Dashboard: routing
+    op("get", "/api/dashboard/routing", "Routing configuration"),
+    op(
+        "patch",
+        "/api/dashboard/routing",
+        "Update the routing configuration",
+    ),
+    op("post", "/api/dashboard/routing/preview", "Preview a route"),
+    op("get", "/api/dashboard/routing/aliases", "Model aliases"),
+    op(
+        "post",
+        "/api/dashboard/routing/explain",
+        "Explain a routing decision",
+    ),
+    op("post", "/api/dashboard/routing/resolve", "Resolve a route"),
+    // Dashboard: configuration
+    op(
+        "post",
+        "/api/dashboard/config/validate",
+        "Validate configuration YAML",
+    ),
+    op(
+        "post",
+        "/api/dashboard/config/reload",
+        "Reload the configuration file",
+    ),
+    op(
+        "put",
+        "/api/dashboard/config/apply",
+        "Apply configuration YAML",
+    ),
+    op(
+        "post",
+        "/api/dashboard/config/preview",
+        "Preview a configuration change",
+    ),
+    op(
+        "get",
+        "/api/dashboard/config/export",
+        "Export a configuration bundle",
+    ),
+    op(
+        "post",
+        "/api/dashboard/config/import",
+        "Import a configuration bundle",
+    ),
+    op(
+        "get",
+        "/api/dashboard/config/current",
+        "Current configuration",
+    ),
+    op("get", "/api/dashboard/config/raw", "Raw configuration file"),
+    op(
+        "get",
+        "/api/dashboard/cluster",
+        "Metrics and log stats across the cluster",
+    ),
+    // Dashboard: logs and usage
+    op("get", "/api/dashboard/logs", "Query the request log").query(LOG_QUERY),
+    op("get", "/api/dashboard/logs/stats", "Request log statistics").query(STATS_QUERY),
+    op(
+        "get",
+        "/api/dashboard/logs/errors",
+        "Failed requests grouped by cause",
+    )
+    .query(ERROR_GROUP_QUERY),
+    op(
+        "get",
+        "/api/dashboard/logs/filters",
+        "Values available as log filters",
+    )
+    .returns("FilterOptions"),
+    op(
+        "get",
+        "/api/dashboard/logs/{id}",
+        "Get one request log entry",
+    ),
+    op(
+        "get",
+        "/api/dashboard/costs/by-tag",
+        "Spend and tokens per tag",
+    )
+    .query(TAG_COST_QUERY)
+    .returns("TagCostReport"),
+    op(
+        "post",
+        "/api/dashboard/reports/{name}/send",
+        "Send a usage report now",
+    )
+    .returns("ReportDelivery"),
+    // Dashboard: in-flight requests
+    op(
+        "get",
+        "/api/dashboard/requests/active",
+        "List in-flight requests",
+    ),
+    op(
+        "post",
+        "/api/dashboard/requests/{request_id}/cancel",
+        "Cancel an in-flight request",
+    ),
+    op(
+        "get",
+        "/api/dashboard/requests/{request_id}/debug",
+        "Debug trace of a request",
+    ),
+    // Dashboard: system
+    op("get", "/api/dashboard/system/health", "System health"),
+    op("get", "/api/dashboard/system/logs", "Recent gateway logs"),
+    op("get", "/api/dashboard/system/logs/files", "List log files"),
+    op(
+        "get",
+        "/api/dashboard/system/logs/files/{name}",
+        "Download a log file",
+    ),
+    op(
+        "get",
+        "/api/dashboard/system/log-level",
+        "Current log level",
+    ),
+    op(
+        "patch",
+        "/api/dashboard/system/log-level",
+        "Change the log level",
+    ),
+    op(
+        "get",
+        "/api/dashboard/system/uptime-history",
+        "Process uptime history",
+    ),
+    // Dashboard: metrics and tenants
+    op(
+        "get",
+        "/api/dashboard/metrics/latency",
+        "Latency percentiles",
+    ),
+    op("post", "/api/dashboard/metrics/reset", "Reset metrics"),
+    op(
+        "post",
+        "/api/dashboard/metrics/reset/credentials/{credential}",
+        "Reset one credential's metrics",
+    ),
+    op("get", "/api/dashboard/tenants", "List tenants"),
+    op(
+        "get",
+        "/api/dashboard/tenants/{id}/metrics",
+        "Metrics of one tenant",
+    ),
+    // Dashboard: control plane
+    op(
+        "get",
+        "/api/dashboard/protocols/matrix",
+        "Protocol translation matrix",
+    ),
+    op(
+        "get",
+        "/api/dashboard/control-plane/command-center",
+        "Command center workspace",
+    ),
+    op(
+        "get",
+        "/api/dashboard/control-plane/traffic-lab",
+        "Traffic lab workspace",
+    ),
+    op(
+        "get",
+        "/api/dashboard/control-plane/provider-atlas",
+        "Provider atlas workspace",
+    ),
+    op(
+        "get",
+        "/api/dashboard/control-plane/route-studio",
+        "Route studio workspace",
+    ),
+    op(
+        "get",
+        "/api/dashboard/control-plane/change-studio",
+        "Change studio workspace",
+    ),
+    op(
+        "post",
+        "/api/dashboard/translate",
+        "Translate a request between formats",
+    ),
+];
+
+/// Routes forwarded untranslated to `openai-passthrough`.
+const PASSTHROUGH_PATHS: &[&str] = &[
+    "/v1/files",
+    "/v1/files/{rest}",
+    "/v1/assistants",
+    "/v1/assistants/{rest}",
+    "/v1/threads",
+    "/v1/threads/{rest}",
+];
+
+static DOCUMENT: LazyLock<Value> = LazyLock::new(build_document);
+
+/// GET /api/openapi.json — OpenAPI 3.1 document for the gateway.
+pub async fn openapi_json() -> impl IntoResponse {
+    Json(DOCUMENT.clone())
+}
+
+fn build_document() -> Value {
+    let mut paths = Map::new();
+    for operation in OPERATIONS {
+        let item = paths
+            .entry(published_path(operation.path))
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("path items are objects");
+        item.insert(operation.method.to_string(), operation_object(operation));
+    }
+    for path in PASSTHROUGH_PATHS {
+        let item = paths
+            .entry(*path)
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("path items are objects");
+        for method in PASSTHROUGH {
+            let operation = op(
+                method,
+                path,
+                "Forwarded untranslated to the openai-passthrough credential",
+            );
+            item.insert(method.to_string(), operation_object(&operation));
+        }
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Prism AI gateway",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "apiKey": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Gateway API key. `x-api-key`, `x-goog-api-key`, and `?key=` are also accepted.",
+                },
+                "dashboardToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "bearerFormat": "JWT",
+                    "description": "Token from `POST /api/v1/dashboard/auth/login`, or the session cookie.",
+                },
+            },
+            "schemas": schemas(),
+        },
+    })
+}
+
+fn operation_object(operation: &Operation) -> Value {
+    let path = operation.path;
+    let mut parameters = path_parameters(path);
+    parameters.extend(operation.query.iter().map(|(name, kind)| {
+        json!({"name": name, "in": "query", "required": false, "schema": {"type": kind}})
+    }));
+
+    let success = match operation.response {
+        Some(schema) => json!({
+            "description": "OK",
+            "content": {"application/json": {"schema": {"$ref": format!("#/components/schemas/{schema}")}}},
+        }),
+        None => json!({"description": "OK"}),
+    };
+    let error_schema = if path.starts_with("/api/dashboard") {
+        "DashboardError"
+    } else {
+        "ApiError"
+    };
+    let mut object = json!({
+        "operationId": operation_id(operation.method, &published_path(path)),
+        "summary": operation.summary,
+        "tags": [tag(path)],
+        "parameters": parameters,
+        "responses": {
+            "200": success,
+            "default": {
+                "description": "Error",
+                "content": {"application/json": {"schema": {"$ref": format!("#/components/schemas/{error_schema}")}}},
+            },
+        },
+    });
+    if operation.method != "get" && operation.method != "delete" {
+        object["requestBody"] = json!({
+            "required": false,
+            "content": {"application/json": {"schema": {"type": "object"}}},
+        });
+    }
+    if let Some(security) = security(path) {
+        object["security"] = json!([{ security: [] }]);
+    }
+    object
+}
+
+/// The path an operation is published under: the versioned path for dashboard
+/// and admin routes, the route itself otherwise.
+fn published_path(path: &str) -> String {
+    api_version::versioned_path(path).unwrap_or_else(|| path.to_string())
+}
+
+fn path_parameters(path: &str) -> Vec<Value> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}}))
+        .collect()
+}
+
+/// `get /api/v1/dashboard/providers/{id}` → `get_api_v1_dashboard_providers_id`.
+fn operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_string();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        id.push('_');
+        id.extend(
+            segment
+                .chars()
+                .filter(|c| !matches!(c, '{' | '}'))
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }),
+        );
+    }
+    id
+}
+
+fn tag(path: &str) -> &'static str {
+    if path.starts_with("/admin") {
+        "admin"
+    } else if path.starts_with("/api/dashboard") {
+        "dashboard"
+    } else if path.starts_with("/api/provider") {
+        "provider-scoped"
+    } else if path.starts_with("/v1beta") {
+        "gemini"
+    } else {
+        "inference"
+    }
+}
+
+fn security(path: &str) -> Option<&'static str> {
+    if path.starts_with("/admin") || path == "/api/dashboard/auth/login" {
+        None
+    } else if path.starts_with("/api/dashboard") {
+        Some("dashboardToken")
+    } else {
+        Some("apiKey")
+    }
+}
+
+fn object_schema(properties: Value) -> Value {
+    let required: Vec<&String> = properties
+        .as_object()
+        .expect("properties are an object")
+        .keys()
+        .collect();
+    json!({"type": "object", "required": required, "properties": properties})
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({"$ref": format!("#/components/schemas/{name}")})
+}
+
+fn schemas() -> Value {
+    let integer = json!({"type": "integer", "minimum": 0});
+    let number = json!({"type": "number"});
+    let string = json!({"type": "string"});
+    let strings = json!({"type": "array", "items": {"type": "string"}});
+    json!({
+        "ApiError": object_schema(json!({
+            "error": object_schema(json!({
+                "message": string,
+                "type": string,
+                "code": string,
+            })),
+        })),
+        "DashboardError": object_schema(json!({
+            "error": string,
+            "message": string,
+        })),
+        "FilterOptions": object_schema(json!({
+            "providers": strings,
+            "models": strings,
+            "error_types": strings,
+            "tenant_ids": strings,
+            "tags": strings,
+        })),
+        "TagCost": object_schema(json!({
+            "tag": string,
+            "requests": integer,
+            "input_tokens": integer,
+            "output_tokens": integer,
+            "cost": number,
+        })),
+        "TagCostReport": object_schema(json!({
+            "total_requests": integer,
+            "total_tokens": integer,
+            "total_cost": number,
+            "tags": {"type": "array", "items": schema_ref("TagCost")},
+            "untagged": schema_ref("TagCost"),
+            "since": {"type": ["integer", "null"]},
+        })),
+        "UsageBreakdown": object_schema(json!({
+            "name": string,
+            "requests": integer,
+            "tokens": integer,
+            "cost": number,
+        })),
+        "UsageSummary": object_schema(json!({
+            "requests": integer,
+            "errors": integer,
+            "input_tokens": integer,
+            "output_tokens": integer,
+            "cost": number,
+            "by_model": {"type": "array", "items": schema_ref("UsageBreakdown")},
+            "by_api_key": {"type": "array", "items": schema_ref("UsageBreakdown")},
+        })),
+        "ReportDelivery": object_schema(json!({
+            "name": string,
+            "delivered": {"type": "boolean"},
+            "usage": schema_ref("UsageSummary"),
+        })),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prism_core::error::ProxyError;
+    use prism_core::request_log::{FilterOptions, TagCost, TagCostReport, UsageSummary};
+    use std::collections::BTreeSet;
+
+    /// Documented prefixes; other routes (health, dashboard WebSocket) are out of scope.
+    const PREFIXES: &[&str] = &[
+        "/v1/",
+        "/v1beta/",
+        "/api/provider/",
+        "/admin/",
+        "/api/dashboard/",
+    ];
+
+    fn registered_routes() -> BTreeSet<String> {
+        let source = include_str!("../lib.rs");
+        let mut routes = BTreeSet::new();
+        let mut rest = source;
+        while let Some(start) = rest.find(".route(") {
+            rest = &rest[start + ".route(".len()..];
+            let Some(open) = rest.find('"') else { break };
+            let Some(len) = rest[open + 1..].find('"') else {
+                break;
+            };
+            routes.insert(rest[open + 1..open + 1 + len].replace("{*", "{"));
+        }
+        routes
+    }
+
+    #[test]
+    fn test_document_covers_every_api_route() {
+        let document = build_document();
+        let paths = document["paths"].as_object().unwrap();
+        let missing: Vec<String> = registered_routes()
+            .into_iter()
+            .filter(|route| PREFIXES.iter().any(|p| route.starts_with(p)))
+            // Catch-all for unknown `/v1` paths, not an endpoint of its own.
+            .filter(|route| route != "/v1/{path}")
+            .filter(|route| !paths.contains_key(&published_path(route)))
+            .collect();
+        assert!(missing.is_empty(), "undocumented routes: {missing:?}");
+    }
+
+    #[test]
+    fn test_operations_are_unique_and_well_formed() {
+        let mut seen = BTreeSet::new();
+        for operation in OPERATIONS {
+            assert!(
+                seen.insert((operation.method, operation.path)),
+                "duplicate operation {} {}",
+                operation.method,
+                operation.path
+            );
+        }
+        let document = build_document();
+        assert_eq!(document["openapi"], "3.1.0");
+        let login = &document["paths"]["/api/v1/dashboard/auth/login"]["post"];
+        assert!(login.get("security").is_none());
+        let provider = &document["paths"]["/api/v1/dashboard/providers/{id}"]["patch"];
+        assert_eq!(
+            provider["operationId"],
+            "patch_api_v1_dashboard_providers_id"
+        );
+        assert_eq!(provider["parameters"][0]["name"], "id");
+        assert_eq!(provider["security"][0]["dashboardToken"], json!([]));
+        assert!(document["paths"].get("/admin/metrics").is_none());
+        assert!(document["paths"]["/api/v1/admin/metrics"].is_object());
+        let files = &document["paths"]["/v1/files/{rest}"]["get"];
+        assert_eq!(files["parameters"][0]["name"], "rest");
+    }
+
+    fn assert_schema_matches(name: &str, value: impl serde::Serialize) {
+        let document = build_document();
+        let schema = &document["components"]["schemas"][name];
+        let documented: BTreeSet<&String> =
+            schema["properties"].as_object().unwrap().keys().collect();
+        let value = serde_json::to_value(value).unwrap();
+        let actual: BTreeSet<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(documented, actual, "schema {name} drifted from its type");
+    }
+
+    #[test]
+    fn test_schemas_match_server_types() {
+        assert_schema_matches("FilterOptions", FilterOptions::default());
+        assert_schema_matches("TagCost", TagCost::default());
+        assert_schema_matches(
+            "TagCostReport",
+            TagCostReport {
+                total_requests: 0,
+                total_tokens: 0,
+                total_cost: 0.0,
+                tags: Vec::new(),
+                untagged: TagCost::default(),
+                since: None,
+            },
+        );
+        assert_schema_matches("UsageSummary", UsageSummary::default());
+        let error: Value =
+            serde_json::from_str(&ProxyError::BadRequest("bad".into()).to_json_body()).unwrap();
+        assert_schema_matches("ApiError", &error);
+        let document = build_document();
+        let inner: BTreeSet<&String> = document["components"]["schemas"]["ApiError"]["properties"]
+            ["error"]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(
+            inner,
+            error["error"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<BTreeSet<_>>()
+        );
+    }
+}
@@ -163,13 +163,19 @@ async fn provider_dispatch(
             allowed_formats,
             user_agent: parsed.user_agent,
             debug: parsed.debug,
+            debug_trace: parsed.debug_trace,
             api_key: ctx.auth_key.as_ref().map(|e| e.key.clone()),
             client_region: ctx.client_region.clone(),
             request_id: Some(ctx.request_id.clone()),
+            trace: Some(ctx.trace.clone()),
+            forwarded_headers: crate::dispatch::forwarded_headers(headers, &state.config.load()),
+            routing_headers: crate::dispatch::routing_headers(headers, &state.config.load()),
+            anthropic_betas: crate::dispatch::anthropic_betas(headers),
             api_key_id: ctx.api_key_id.clone(),
             tenant_id: ctx.tenant_id.clone(),
             allowed_credentials,
             responses_passthrough,
+            experiment: None,
         },
     )
     .await
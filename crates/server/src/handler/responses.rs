@@ -16,17 +16,12 @@ pub async fn responses(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     headers: HeaderMap,
-    body: Bytes,
+    mut body: Bytes,
 ) -> Result<Response, ProxyError> {
-    let parsed = super::parse_request(&headers, &body)?;
+    let mut parsed = super::parse_request(&headers, &body)?;
+    super::validation::validate_request(super::validation::RequestKind::Responses, &body)?;
 
-    let allowed_credentials = super::merge_requested_credential(
-        ctx.auth_key
-            .as_ref()
-            .map(|e| e.allowed_credentials.clone())
-            .unwrap_or_default(),
-        parsed.auth_profile.as_deref(),
-    )?;
+    let allowed_credentials = super::apply_routing_hints(&state, &ctx, &mut parsed, &mut body)?;
 
     dispatch(
         &state,
@@ -40,13 +35,19 @@ pub async fn responses(
             allowed_formats: Some(vec![Format::OpenAI]),
             user_agent: parsed.user_agent,
             debug: parsed.debug,
+            debug_trace: parsed.debug_trace,
             api_key: ctx.auth_key.as_ref().map(|e| e.key.clone()),
             client_region: ctx.client_region.clone(),
             request_id: Some(ctx.request_id.clone()),
+            trace: Some(ctx.trace.clone()),
+            forwarded_headers: crate::dispatch::forwarded_headers(&headers, &state.config.load()),
+            routing_headers: crate::dispatch::routing_headers(&headers, &state.config.load()),
+            anthropic_betas: crate::dispatch::anthropic_betas(&headers),
             api_key_id: ctx.api_key_id.clone(),
             tenant_id: ctx.tenant_id.clone(),
             allowed_credentials,
             responses_passthrough: true,
+            experiment: None,
         },
     )
     .await
@@ -185,13 +185,22 @@ async fn handle_ws(
                 allowed_formats: Some(vec![Format::OpenAI]),
                 user_agent: user_agent.clone(),
                 debug: true,
+                debug_trace: false,
                 api_key: ctx.auth_key.as_ref().map(|entry| entry.key.clone()),
                 client_region: ctx.client_region.clone(),
                 request_id: Some(request_id),
+                trace: Some(ctx.trace.clone()),
+                forwarded_headers: crate::dispatch::forwarded_headers(
+                    &headers,
+                    &state.config.load(),
+                ),
+                routing_headers: crate::dispatch::routing_headers(&headers, &state.config.load()),
+                anthropic_betas: crate::dispatch::anthropic_betas(&headers),
                 api_key_id: ctx.api_key_id.clone(),
                 tenant_id: ctx.tenant_id.clone(),
                 allowed_credentials,
                 responses_passthrough: true,
+                experiment: None,
             },
         )
         .await;
@@ -377,7 +386,10 @@ fn inherit_request_fields(obj: &mut serde_json::Map<String, Value>, last_request
     }
 }
 
-async fn send_ws_error(socket: &mut WebSocket, message: &str) -> Result<(), axum::Error> {
+pub(crate) async fn send_ws_error(
+    socket: &mut WebSocket,
+    message: &str,
+) -> Result<(), axum::Error> {
     socket
         .send(Message::Text(
             json!({
@@ -0,0 +1,615 @@
+//! Shape checks for incoming API requests, run before dispatch.
+//!
+//! Only rejects requests every upstream would reject anyway, so clients get an actionable
+//! 400 with a JSON pointer instead of a translated upstream error after a full round trip.
+//! Unknown fields and provider-specific extensions are left alone.
+
+use prism_core::error::ProxyError;
+use serde_json::{Map, Value};
+
+/// Maximum number of problems listed in one error message.
+const MAX_REPORTED: usize = 5;
+
+/// Request schema to validate against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestKind {
+    ChatCompletions,
+    Completions,
+    Responses,
+    Messages,
+    GenerateContent,
+}
+
+impl RequestKind {
+    /// Schema for an OpenAI/Claude endpoint path, if it has one.
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        match path {
+            "/v1/chat/completions"
+            | "/v1/ws/chat"
+            | "/prism.chat.v1.ChatCompletions/Create"
+            | "/prism.chat.v1.ChatCompletions/CreateStream" => Some(Self::ChatCompletions),
+            "/v1/responses" => Some(Self::Responses),
+            "/v1/messages" => Some(Self::Messages),
+            // `/v1/completions` is validated by its handler, before it becomes a chat request.
+            _ => None,
+        }
+    }
+}
+
+/// Validate `body` against `kind`, listing every problem found (up to a cap).
+pub(crate) fn validate_request(kind: RequestKind, body: &[u8]) -> Result<(), ProxyError> {
+    let value: Value =
+        serde_json::from_slice(body).map_err(|e| ProxyError::BadRequest(e.to_string()))?;
+    let Some(root) = value.as_object() else {
+        return Err(ProxyError::BadRequest(
+            "request body must be a JSON object".into(),
+        ));
+    };
+
+    let mut v = Validator::default();
+    match kind {
+        RequestKind::ChatCompletions => v.chat_completions(root),
+        RequestKind::Completions => v.completions(root),
+        RequestKind::Responses => v.responses(root),
+        RequestKind::Messages => v.messages(root),
+        RequestKind::GenerateContent => v.generate_content(root),
+    }
+    v.finish()
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn finish(self) -> Result<(), ProxyError> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let total = self.errors.len();
+        let mut message = self
+            .errors
+            .into_iter()
+            .take(MAX_REPORTED)
+            .collect::<Vec<_>>()
+            .join("; ");
+        if total > MAX_REPORTED {
+            message.push_str(&format!(" (and {} more)", total - MAX_REPORTED));
+        }
+        Err(ProxyError::BadRequest(message))
+    }
+
+    fn error(&mut self, pointer: &str, problem: impl std::fmt::Display) {
+        self.errors.push(format!("{pointer}: {problem}"));
+    }
+
+    fn required<'a>(
+        &mut self,
+        obj: &'a Map<String, Value>,
+        parent: &str,
+        key: &str,
+    ) -> Option<&'a Value> {
+        let value = obj.get(key);
+        if value.is_none() {
+            self.error(&format!("{parent}/{key}"), "is required");
+        }
+        value
+    }
+
+    /// Check a field is an array; `non_empty` also rejects `[]`.
+    fn array<'a>(&mut self, value: &'a Value, pointer: &str, non_empty: bool) -> &'a [Value] {
+        match value.as_array() {
+            Some(items) if non_empty && items.is_empty() => {
+                self.error(pointer, "must not be empty");
+                &[]
+            }
+            Some(items) => items,
+            None => {
+                self.error(
+                    pointer,
+                    format!("expected an array, got {}", kind_of(value)),
+                );
+                &[]
+            }
+        }
+    }
+
+    fn object<'a>(&mut self, value: &'a Value, pointer: &str) -> Option<&'a Map<String, Value>> {
+        let obj = value.as_object();
+        if obj.is_none() {
+            self.error(
+                pointer,
+                format!("expected an object, got {}", kind_of(value)),
+            );
+        }
+        obj
+    }
+
+    fn string(&mut self, obj: &Map<String, Value>, parent: &str, key: &str) {
+        if let Some(value) = obj.get(key)
+            && !value.is_string()
+        {
+            self.error(
+                &format!("{parent}/{key}"),
+                format!("expected a string, got {}", kind_of(value)),
+            );
+        }
+    }
+
+    fn boolean(&mut self, obj: &Map<String, Value>, parent: &str, key: &str) {
+        if let Some(value) = obj.get(key)
+            && !value.is_boolean()
+        {
+            self.error(
+                &format!("{parent}/{key}"),
+                format!("expected a boolean, got {}", kind_of(value)),
+            );
+        }
+    }
+
+    fn one_of(&mut self, obj: &Map<String, Value>, parent: &str, key: &str, allowed: &[&str]) {
+        let Some(value) = obj.get(key) else {
+            return;
+        };
+        if !value.as_str().is_some_and(|s| allowed.contains(&s)) {
+            self.error(
+                &format!("{parent}/{key}"),
+                format!("expected one of {}, got {value}", allowed.join(", ")),
+            );
+        }
+    }
+
+    fn number_in(&mut self, obj: &Map<String, Value>, parent: &str, key: &str, min: f64, max: f64) {
+        let Some(value) = obj.get(key) else {
+            return;
+        };
+        match value.as_f64() {
+            Some(n) if (min..=max).contains(&n) => {}
+            Some(n) => self.error(
+                &format!("{parent}/{key}"),
+                format!("must be between {min} and {max}, got {n}"),
+            ),
+            None if value.is_null() => {}
+            None => self.error(
+                &format!("{parent}/{key}"),
+                format!("expected a number, got {}", kind_of(value)),
+            ),
+        }
+    }
+
+    fn integer_at_least(&mut self, obj: &Map<String, Value>, parent: &str, key: &str, min: i64) {
+        let Some(value) = obj.get(key) else {
+            return;
+        };
+        match value.as_i64() {
+            Some(n) if n >= min => {}
+            Some(n) => self.error(
+                &format!("{parent}/{key}"),
+                format!("must be at least {min}, got {n}"),
+            ),
+            None if value.is_null() => {}
+            None => self.error(
+                &format!("{parent}/{key}"),
+                format!("expected an integer, got {}", kind_of(value)),
+            ),
+        }
+    }
+
+    fn exclusive(&mut self, obj: &Map<String, Value>, parent: &str, a: &str, b: &str) {
+        if obj.get(a).is_some_and(|v| !v.is_null()) && obj.get(b).is_some_and(|v| !v.is_null()) {
+            self.error(
+                &format!("{parent}/{b}"),
+                format!("cannot be combined with {parent}/{a}"),
+            );
+        }
+    }
+
+    /// Content that is a string or an array of typed parts; `null` only when `nullable`.
+    fn content(&mut self, value: &Value, pointer: &str, nullable: bool) {
+        match value {
+            Value::String(_) => {}
+            Value::Null if nullable => {}
+            Value::Array(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    let part_pointer = format!("{pointer}/{i}");
+                    if let Some(part) = self.object(part, &part_pointer)
+                        && self.required(part, &part_pointer, "type").is_some()
+                    {
+                        self.string(part, &part_pointer, "type");
+                    }
+                }
+            }
+            other => self.error(
+                pointer,
+                format!(
+                    "expected a string or an array of content parts, got {}",
+                    kind_of(other)
+                ),
+            ),
+        }
+    }
+
+    /// OpenAI `tools`: function tools need a name; other tool types pass through.
+    fn openai_tools(&mut self, root: &Map<String, Value>, function_nested: bool) {
+        let Some(tools) = root.get("tools") else {
+            return;
+        };
+        for (i, tool) in self.array(tools, "/tools", false).iter().enumerate() {
+            let pointer = format!("/tools/{i}");
+            let Some(tool) = self.object(tool, &pointer) else {
+                continue;
+            };
+            if tool.get("type").and_then(|t| t.as_str()) != Some("function") {
+                continue;
+            }
+            let (owner, owner_pointer) = if function_nested {
+                let Some(function) = self.required(tool, &pointer, "function") else {
+                    continue;
+                };
+                let function_pointer = format!("{pointer}/function");
+                match self.object(function, &function_pointer) {
+                    Some(function) => (function, function_pointer),
+                    None => continue,
+                }
+            } else {
+                (tool, pointer)
+            };
+            if self.required(owner, &owner_pointer, "name").is_some() {
+                self.string(owner, &owner_pointer, "name");
+            }
+        }
+    }
+
+    fn chat_completions(&mut self, root: &Map<String, Value>) {
+        if let Some(messages) = self.required(root, "", "messages") {
+            for (i, message) in self.array(messages, "/messages", true).iter().enumerate() {
+                let pointer = format!("/messages/{i}");
+                let Some(message) = self.object(message, &pointer) else {
+                    continue;
+                };
+                if self.required(message, &pointer, "role").is_some() {
+                    self.one_of(
+                        message,
+                        &pointer,
+                        "role",
+                        &[
+                            "system",
+                            "developer",
+                            "user",
+                            "assistant",
+                            "tool",
+                            "function",
+                        ],
+                    );
+                }
+                let role = message.get("role").and_then(|r| r.as_str());
+                let nullable = matches!(role, Some("assistant" | "function"));
+                match message.get("content") {
+                    Some(content) => self.content(content, &format!("{pointer}/content"), nullable),
+                    None if nullable => {}
+                    None => self.error(&format!("{pointer}/content"), "is required"),
+                }
+                if role == Some("tool")
+                    && self.required(message, &pointer, "tool_call_id").is_some()
+                {
+                    self.string(message, &pointer, "tool_call_id");
+                }
+            }
+        }
+        self.boolean(root, "", "stream");
+        self.number_in(root, "", "temperature", 0.0, 2.0);
+        self.number_in(root, "", "top_p", 0.0, 1.0);
+        self.number_in(root, "", "presence_penalty", -2.0, 2.0);
+        self.number_in(root, "", "frequency_penalty", -2.0, 2.0);
+        self.integer_at_least(root, "", "n", 1);
+        self.integer_at_least(root, "", "max_tokens", 1);
+        self.integer_at_least(root, "", "max_completion_tokens", 1);
+        self.exclusive(root, "", "tools", "functions");
+        if root.get("stream_options").is_some_and(|v| !v.is_null())
+            && root.get("stream").and_then(|s| s.as_bool()) != Some(true)
+        {
+            self.error("/stream_options", "is only allowed when stream is true");
+        }
+        self.openai_tools(root, true);
+    }
+
+    fn completions(&mut self, root: &Map<String, Value>) {
+        if let Some(prompt) = self.required(root, "", "prompt")
+            && !(prompt.is_string() || prompt.is_array())
+        {
+            self.error(
+                "/prompt",
+                format!("expected a string or an array, got {}", kind_of(prompt)),
+            );
+        }
+        self.boolean(root, "", "stream");
+        self.number_in(root, "", "temperature", 0.0, 2.0);
+        self.number_in(root, "", "top_p", 0.0, 1.0);
+        self.integer_at_least(root, "", "max_tokens", 1);
+        self.integer_at_least(root, "", "n", 1);
+    }
+
+    fn responses(&mut self, root: &Map<String, Value>) {
+        if let Some(input) = root.get("input")
+            && !(input.is_string() || input.is_array())
+        {
+            self.error(
+                "/input",
+                format!(
+                    "expected a string or an array of items, got {}",
+                    kind_of(input)
+                ),
+            );
+        }
+        if root.get("input").is_none() && root.get("previous_response_id").is_none() {
+            self.error("/input", "is required unless previous_response_id is set");
+        }
+        self.boolean(root, "", "stream");
+        self.number_in(root, "", "temperature", 0.0, 2.0);
+        self.number_in(root, "", "top_p", 0.0, 1.0);
+        self.integer_at_least(root, "", "max_output_tokens", 1);
+        self.openai_tools(root, false);
+    }
+
+    fn messages(&mut self, root: &Map<String, Value>) {
+        if self.required(root, "", "max_tokens").is_some() {
+            self.integer_at_least(root, "", "max_tokens", 1);
+        }
+        if let Some(messages) = self.required(root, "", "messages") {
+            for (i, message) in self.array(messages, "/messages", true).iter().enumerate() {
+                let pointer = format!("/messages/{i}");
+                let Some(message) = self.object(message, &pointer) else {
+                    continue;
+                };
+                if self.required(message, &pointer, "role").is_some() {
+                    self.one_of(message, &pointer, "role", &["user", "assistant"]);
+                }
+                if let Some(content) = self.required(message, &pointer, "content") {
+                    self.content(content, &format!("{pointer}/content"), false);
+                }
+            }
+        }
+        if let Some(system) = root.get("system")
+            && !(system.is_string() || system.is_array())
+        {
+            self.error(
+                "/system",
+                format!(
+                    "expected a string or an array of text blocks, got {}",
+                    kind_of(system)
+                ),
+            );
+        }
+        self.boolean(root, "", "stream");
+        self.number_in(root, "", "temperature", 0.0, 1.0);
+        self.number_in(root, "", "top_p", 0.0, 1.0);
+        self.integer_at_least(root, "", "top_k", 0);
+        if let Some(thinking) = root.get("thinking").and_then(|t| t.as_object())
+            && thinking.get("type").and_then(|t| t.as_str()) == Some("enabled")
+            && self
+                .required(thinking, "/thinking", "budget_tokens")
+                .is_some()
+        {
+            // No budget-vs-max_tokens check: interleaved thinking allows a larger budget, and
+            // the payload phase may clamp it or the request may go to a non-Claude upstream.
+            self.integer_at_least(thinking, "/thinking", "budget_tokens", 1024);
+        }
+        if let Some(tools) = root.get("tools") {
+            for (i, tool) in self.array(tools, "/tools", false).iter().enumerate() {
+                let pointer = format!("/tools/{i}");
+                if let Some(tool) = self.object(tool, &pointer)
+                    && self.required(tool, &pointer, "name").is_some()
+                {
+                    self.string(tool, &pointer, "name");
+                }
+            }
+        }
+    }
+
+    fn generate_content(&mut self, root: &Map<String, Value>) {
+        if let Some(contents) = self.required(root, "", "contents") {
+            for (i, content) in self.array(contents, "/contents", true).iter().enumerate() {
+                let pointer = format!("/contents/{i}");
+                let Some(content) = self.object(content, &pointer) else {
+                    continue;
+                };
+                self.one_of(content, &pointer, "role", &["user", "model", "function"]);
+                if let Some(parts) = self.required(content, &pointer, "parts") {
+                    let parts_pointer = format!("{pointer}/parts");
+                    for (j, part) in self.array(parts, &parts_pointer, true).iter().enumerate() {
+                        self.object(part, &format!("{parts_pointer}/{j}"));
+                    }
+                }
+            }
+        }
+        if let Some(config) = root.get("generationConfig") {
+            let pointer = "/generationConfig";
+            if let Some(config) = self.object(config, pointer) {
+                self.number_in(config, pointer, "temperature", 0.0, 2.0);
+                self.number_in(config, pointer, "topP", 0.0, 1.0);
+                self.integer_at_least(config, pointer, "topK", 1);
+                self.integer_at_least(config, pointer, "candidateCount", 1);
+                self.integer_at_least(config, pointer, "maxOutputTokens", 1);
+                self.exclusive(config, pointer, "responseSchema", "responseJsonSchema");
+            }
+        }
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(kind: RequestKind, body: Value) -> Result<(), String> {
+        validate_request(kind, &serde_json::to_vec(&body).unwrap()).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_valid_requests_pass() {
+        check(
+            RequestKind::ChatCompletions,
+            json!({
+                "model": "gpt-4o",
+                "messages": [
+                    {"role": "system", "content": "Be brief"},
+                    {"role": "user", "content": [{"type": "text", "text": "hi"}]},
+                    {"role": "assistant", "content": null, "tool_calls": []},
+                    {"role": "tool", "tool_call_id": "call_1", "content": "42"}
+                ],
+                "tools": [{"type": "web_search"}],
+                "temperature": 1.5,
+                "stream": true,
+                "stream_options": {"include_usage": true}
+            }),
+        )
+        .unwrap();
+        check(
+            RequestKind::Messages,
+            json!({
+                "model": "claude-sonnet",
+                "max_tokens": 4096,
+                "system": [{"type": "text", "text": "Be brief"}],
+                "messages": [{"role": "user", "content": "hi"}],
+                "thinking": {"type": "enabled", "budget_tokens": 2048}
+            }),
+        )
+        .unwrap();
+        check(
+            RequestKind::GenerateContent,
+            json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}),
+        )
+        .unwrap();
+        check(
+            RequestKind::Responses,
+            json!({"model": "gpt-5", "previous_response_id": "resp_1"}),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_chat_errors_carry_json_pointers() {
+        let err = check(
+            RequestKind::ChatCompletions,
+            json!({
+                "model": "gpt-4o",
+                "messages": [
+                    {"role": "bot", "content": "hi"},
+                    {"role": "user"},
+                    {"role": "tool", "content": "42"}
+                ],
+                "temperature": 3,
+                "stream_options": {"include_usage": true}
+            }),
+        )
+        .unwrap_err();
+        assert!(err.contains("/messages/0/role: expected one of"), "{err}");
+        assert!(err.contains("/messages/1/content: is required"), "{err}");
+        assert!(
+            err.contains("/messages/2/tool_call_id: is required"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/temperature: must be between 0 and 2, got 3"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/stream_options: is only allowed when stream is true"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_messages_requires_max_tokens_and_checks_thinking_budget() {
+        let err = check(
+            RequestKind::Messages,
+            json!({"model": "claude", "messages": [{"role": "system", "content": "x"}]}),
+        )
+        .unwrap_err();
+        assert!(err.contains("/max_tokens: is required"), "{err}");
+        assert!(
+            err.contains("/messages/0/role: expected one of user, assistant"),
+            "{err}"
+        );
+
+        let err = check(
+            RequestKind::Messages,
+            json!({
+                "model": "claude",
+                "max_tokens": 2000,
+                "messages": [{"role": "user", "content": "x"}],
+                "thinking": {"type": "enabled", "budget_tokens": 512}
+            }),
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("/thinking/budget_tokens: must be at least 1024, got 512"),
+            "{err}"
+        );
+
+        // A budget at or above max_tokens is valid under interleaved thinking.
+        check(
+            RequestKind::Messages,
+            json!({
+                "model": "claude",
+                "max_tokens": 2000,
+                "messages": [{"role": "user", "content": "x"}],
+                "thinking": {"type": "enabled", "budget_tokens": 4000}
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_gemini_and_mutually_exclusive_fields() {
+        let err = check(
+            RequestKind::GenerateContent,
+            json!({
+                "contents": [{"role": "assistant", "parts": []}],
+                "generationConfig": {
+                    "responseSchema": {"type": "object"},
+                    "responseJsonSchema": {"type": "object"}
+                }
+            }),
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("/contents/0/role: expected one of user, model"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/contents/0/parts: must not be empty"),
+            "{err}"
+        );
+        assert!(
+            err.contains(
+                "/generationConfig/responseJsonSchema: cannot be combined with /generationConfig/responseSchema"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_error_list_is_capped() {
+        let messages: Vec<Value> = (0..8).map(|_| json!({"role": "nobody"})).collect();
+        let err = check(
+            RequestKind::Messages,
+            json!({"max_tokens": 1, "messages": messages}),
+        )
+        .unwrap_err();
+        assert!(err.contains("more)"), "{err}");
+        assert_eq!(err.matches("; ").count(), MAX_REPORTED - 1);
+    }
+}
@@ -0,0 +1,268 @@
+//! Registry of in-flight API requests behind `GET /api/dashboard/requests/active` and
+//! `POST /api/dashboard/requests/{request_id}/cancel`.
+//!
+//! Each request is registered by [`cancellation_middleware`] and stays listed until
+//! its response body has been fully sent or dropped, so long streams and keepalive
+//! responses can be cancelled after their headers went out.
+
+use crate::AppState;
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dashmap::DashMap;
+use prism_core::context::RequestContext;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// Status returned when a request is cancelled before its response started.
+const CANCELLED_STATUS: u16 = 499;
+
+struct Entry {
+    method: String,
+    path: String,
+    started_at: Instant,
+    started_at_utc: chrono::DateTime<chrono::Utc>,
+    tenant_id: Option<String>,
+    provider: Option<String>,
+    model: Option<String>,
+    streaming: AtomicBool,
+    cancel: watch::Sender<bool>,
+}
+
+/// An in-flight request as listed on the dashboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct InflightRequest {
+    pub request_id: String,
+    pub method: String,
+    pub path: String,
+    pub started_at: String,
+    pub elapsed_ms: u64,
+    pub tenant_id: Option<String>,
+    /// Provider of the current attempt; `None` until routing picked one.
+    pub provider: Option<String>,
+    /// Requested model, then the model of the current attempt.
+    pub model: Option<String>,
+    /// True once the response headers were sent and the body is still streaming.
+    pub streaming: bool,
+}
+
+#[derive(Default)]
+pub struct InflightRegistry {
+    entries: DashMap<String, Entry>,
+}
+
+impl InflightRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a request until the returned guard is dropped.
+    fn register(self: &Arc<Self>, ctx: &RequestContext, request: &Request) -> InflightGuard {
+        let (cancel, cancelled) = watch::channel(false);
+        self.entries.insert(
+            ctx.request_id.clone(),
+            Entry {
+                method: request.method().to_string(),
+                path: request.uri().path().to_string(),
+                started_at: ctx.start_time,
+                started_at_utc: chrono::Utc::now(),
+                tenant_id: ctx.tenant_id.clone(),
+                provider: None,
+                model: None,
+                streaming: AtomicBool::new(false),
+                cancel,
+            },
+        );
+        InflightGuard {
+            registry: self.clone(),
+            request_id: ctx.request_id.clone(),
+            cancelled,
+        }
+    }
+
+    /// Record where a tracked request is being sent. Unknown IDs are ignored.
+    pub fn set_target(&self, request_id: &str, provider: Option<&str>, model: &str) {
+        if let Some(mut entry) = self.entries.get_mut(request_id) {
+            if provider.is_some() {
+                entry.provider = provider.map(str::to_string);
+            }
+            entry.model = Some(model.to_string());
+        }
+    }
+
+    /// Signal cancellation to a tracked request. Returns false when it is unknown
+    /// or already finished.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.entries.get(request_id) {
+            Some(entry) => {
+                entry.cancel.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tracked requests, oldest first.
+    pub fn list(&self) -> Vec<InflightRequest> {
+        let mut requests: Vec<InflightRequest> = self
+            .entries
+            .iter()
+            .map(|entry| InflightRequest {
+                request_id: entry.key().clone(),
+                method: entry.method.clone(),
+                path: entry.path.clone(),
+                started_at: entry.started_at_utc.to_rfc3339(),
+                elapsed_ms: entry.started_at.elapsed().as_millis() as u64,
+                tenant_id: entry.tenant_id.clone(),
+                provider: entry.provider.clone(),
+                model: entry.model.clone(),
+                streaming: entry.streaming.load(Ordering::Relaxed),
+            })
+            .collect();
+        requests.sort_by_key(|r| std::cmp::Reverse(r.elapsed_ms));
+        requests
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+struct InflightGuard {
+    registry: Arc<InflightRegistry>,
+    request_id: String,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl InflightGuard {
+    /// Resolves once the request has been cancelled.
+    async fn cancelled(&mut self) {
+        // The sender lives in the registry entry, which outlives the guard.
+        let _ = self.cancelled.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.registry.entries.remove(&self.request_id);
+    }
+}
+
+/// Middleware that registers each API request in [`InflightRegistry`] and aborts it
+/// on cancellation: before the response starts the handler future is dropped and a
+/// 499 returned; afterwards the body stream is ended, which drops the upstream stream.
+pub async fn cancellation_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(ctx) = request.extensions().get::<RequestContext>() else {
+        return next.run(request).await;
+    };
+    let mut guard = state.inflight.register(ctx, &request);
+
+    let response = tokio::select! {
+        response = next.run(request) => response,
+        () = guard.cancelled() => {
+            tracing::info!(request_id = %guard.request_id, "Request cancelled before response");
+            return cancelled_response();
+        }
+    };
+
+    // Sized bodies are already complete; only streamed bodies stay cancellable.
+    let (parts, body) = response.into_parts();
+    if body.size_hint().exact().is_some() {
+        return Response::from_parts(parts, body);
+    }
+    if let Some(entry) = state.inflight.entries.get(&guard.request_id) {
+        entry.streaming.store(true, Ordering::Relaxed);
+    }
+    let stream = futures::stream::unfold(
+        (body.into_data_stream(), guard),
+        |(mut body, mut guard)| async move {
+            use futures::StreamExt;
+            tokio::select! {
+                frame = body.next() => frame.map(|frame| (frame, (body, guard))),
+                () = guard.cancelled() => {
+                    tracing::info!(request_id = %guard.request_id, "Stream cancelled");
+                    None
+                }
+            }
+        },
+    );
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+fn cancelled_response() -> Response {
+    let body = serde_json::json!({
+        "error": {
+            "message": "request cancelled by operator",
+            "type": "server_error",
+            "code": "request_cancelled",
+        }
+    });
+    let mut response = (
+        StatusCode::from_u16(CANCELLED_STATUS).unwrap_or(StatusCode::SERVICE_UNAVAILABLE),
+        body.to_string(),
+    )
+        .into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_guard_drop_unregisters() {
+        let registry = Arc::new(InflightRegistry::new());
+        let ctx = RequestContext::new(None);
+        let guard = registry.register(&ctx, &request("/v1/messages"));
+
+        let listed = registry.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].request_id, ctx.request_id);
+        assert_eq!(listed[0].path, "/v1/messages");
+        assert!(!listed[0].streaming);
+        assert_eq!(listed[0].model, None);
+
+        registry.set_target(&ctx.request_id, None, "gpt-4o");
+        registry.set_target(&ctx.request_id, Some("openai"), "gpt-4o-2024-08-06");
+        let listed = registry.list();
+        assert_eq!(listed[0].provider.as_deref(), Some("openai"));
+        assert_eq!(listed[0].model.as_deref(), Some("gpt-4o-2024-08-06"));
+
+        drop(guard);
+        assert!(registry.is_empty());
+        assert!(!registry.cancel(&ctx.request_id));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_wakes_guard() {
+        let registry = Arc::new(InflightRegistry::new());
+        let ctx = RequestContext::new(None);
+        let mut guard = registry.register(&ctx, &request("/v1/chat/completions"));
+
+        assert!(registry.cancel(&ctx.request_id));
+        tokio::time::timeout(std::time::Duration::from_secs(1), guard.cancelled())
+            .await
+            .expect("cancellation observed");
+    }
+}
@@ -1,13 +1,26 @@
+pub mod api_version;
 pub mod app;
 pub mod auth;
 pub mod auth_runtime;
+pub mod builder;
+pub mod crash_report;
+pub mod debug_trace;
 pub mod dispatch;
 pub mod handler;
+pub mod inflight;
+pub mod load_shed;
 pub mod middleware;
+pub mod process_history;
+pub mod readiness;
+pub mod reload;
+pub mod reports;
+pub mod resource_stats;
+pub mod shared_state;
 pub mod streaming;
 pub mod telemetry;
 
 use arc_swap::ArcSwap;
+use axum::extract::DefaultBodyLimit;
 use axum::{Router, middleware as axum_mw};
 use prism_core::cache::ResponseCacheBackend;
 use prism_core::config::Config;
@@ -23,6 +36,7 @@ use prism_provider::routing::CredentialRouter;
 use prism_translator::TranslatorRegistry;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
+use tower::Layer;
 use tower_http::cors::CorsLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
@@ -50,18 +64,34 @@ pub struct AppState {
     pub device_sessions: Arc<dashmap::DashMap<String, auth_runtime::PendingCodexDeviceSession>>,
     pub provider_probe_cache:
         Arc<dashmap::DashMap<String, handler::dashboard::providers::ProviderProbeResult>>,
+    pub readiness: Arc<readiness::Readiness>,
+    pub inflight: Arc<inflight::InflightRegistry>,
+    pub load_shedder: Arc<load_shed::LoadShedder>,
+    pub process_history: Arc<process_history::ProcessHistory>,
+    pub crash_reporter: Arc<crash_report::CrashReporter>,
+    pub connections: Arc<resource_stats::ConnectionCounter>,
+    /// Traces of requests sent with `x-debug: trace`.
+    pub debug_traces: Arc<debug_trace::DebugTraceStore>,
+    /// Upstream credential owning each Gemini cached content, by name
+    /// (`cachedContents/...`).
+    pub gemini_caches: Arc<dashmap::DashMap<String, handler::gemini_cache::CacheBinding>>,
+    /// Runtime control of the tracing level filter.
+    pub log_filter: Arc<prism_lifecycle::logging::LogFilterHandle>,
 }
 
 pub fn build_router(state: AppState) -> Router {
-    let body_limit_bytes = state.config.load().body_limit_mb * 1024 * 1024;
-
     // Public routes — no auth required
     let public_routes = Router::new()
         .route("/health", axum::routing::get(handler::health::health))
+        .route("/ready", axum::routing::get(handler::health::ready))
         .route("/metrics", axum::routing::get(handler::health::metrics))
         .route(
             "/metrics/prometheus",
             axum::routing::get(handler::health::prometheus_metrics),
+        )
+        .route(
+            "/api/openapi.json",
+            axum::routing::get(handler::openapi::openapi_json),
         );
 
     // Admin routes — no auth required (read-only)
@@ -89,6 +119,7 @@ pub fn build_router(state: AppState) -> Router {
             "/v1/chat/completions",
             axum::routing::post(handler::chat_completions::chat_completions),
         )
+        .route("/v1/ws/chat", axum::routing::get(handler::chat_ws::chat_ws))
         .route(
             "/v1/messages",
             axum::routing::post(handler::messages::messages),
@@ -109,6 +140,40 @@ pub fn build_router(state: AppState) -> Router {
             "/v1/messages/count_tokens",
             axum::routing::post(handler::count_tokens::count_tokens),
         )
+        .route(
+            "/v1/moderations",
+            axum::routing::post(handler::moderations::moderations),
+        )
+        // Untranslated OpenAI Files / Assistants / Threads, when `openai-passthrough` is enabled
+        .route(
+            "/v1/files",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        .route(
+            "/v1/files/{*rest}",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        .route(
+            "/v1/assistants",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        .route(
+            "/v1/assistants/{*rest}",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        .route(
+            "/v1/threads",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        .route(
+            "/v1/threads/{*rest}",
+            axum::routing::any(handler::openai_passthrough::openai_passthrough),
+        )
+        // Any other /v1 path, with `openai-passthrough.catch-all`; specific routes win
+        .route(
+            "/v1/{*path}",
+            axum::routing::any(handler::openai_passthrough::unknown_path_passthrough),
+        )
         // Gemini native routes
         .route(
             "/v1beta/models",
@@ -118,6 +183,16 @@ pub fn build_router(state: AppState) -> Router {
             "/v1beta/models/{model_action}",
             axum::routing::post(handler::gemini::gemini_model_action),
         )
+        .route(
+            "/v1beta/cachedContents",
+            axum::routing::post(handler::gemini_cache::create_cached_content),
+        )
+        .route(
+            "/v1beta/cachedContents/{id}",
+            axum::routing::get(handler::gemini_cache::get_cached_content)
+                .patch(handler::gemini_cache::update_cached_content)
+                .delete(handler::gemini_cache::delete_cached_content),
+        )
         // Provider-scoped routes
         .route(
             "/api/provider/{provider}/v1/chat/completions",
@@ -134,8 +209,21 @@ pub fn build_router(state: AppState) -> Router {
         .route(
             "/api/provider/{provider}/v1/responses/ws",
             axum::routing::get(handler::responses_ws::provider_responses_ws),
-        )
-        .layer(RequestBodyLimitLayer::new(body_limit_bytes))
+        );
+    // gRPC chat completions, with the `grpc` feature
+    #[cfg(feature = "grpc")]
+    let api_routes = handler::grpc::routes(api_routes);
+    let api_routes = api_routes
+        .layer(axum_mw::from_fn_with_state(
+            state.clone(),
+            inflight::cancellation_middleware,
+        ))
+        // Body limit follows `body-limit-mb` from the live config, replacing axum's 2 MB default
+        .layer(axum_mw::from_fn_with_state(
+            state.clone(),
+            middleware::body_limit::body_limit_middleware,
+        ))
+        .layer(DefaultBodyLimit::disable())
         .layer(axum_mw::from_fn_with_state(
             state.clone(),
             middleware::rate_limit::rate_limit_middleware,
@@ -143,6 +231,11 @@ pub fn build_router(state: AppState) -> Router {
         .layer(axum_mw::from_fn_with_state(
             state.clone(),
             auth::auth_middleware,
+        ))
+        // Outermost, so shed requests cost no auth or body work
+        .layer(axum_mw::from_fn_with_state(
+            state.clone(),
+            load_shed::load_shed_middleware,
         ));
 
     // Dashboard auth routes — no auth required (login endpoint)
@@ -156,6 +249,12 @@ pub fn build_router(state: AppState) -> Router {
             axum::routing::get(handler::dashboard::auth::session),
         );
 
+    // Config reads carry the config version as an `ETag` for edits made from them
+    let config_etag = axum_mw::from_fn_with_state(
+        state.clone(),
+        middleware::config_version::config_etag_middleware,
+    );
+
     // Dashboard protected routes — JWT auth required
     let dashboard_protected_routes = Router::new()
         .route(
@@ -169,6 +268,7 @@ pub fn build_router(state: AppState) -> Router {
         .route(
             "/api/dashboard/auth-profiles",
             axum::routing::get(handler::dashboard::auth_profiles::list_auth_profiles)
+                .layer(config_etag.clone())
                 .post(handler::dashboard::auth_profiles::create_auth_profile),
         )
         .route(
@@ -213,6 +313,22 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/providers/fetch-models",
             axum::routing::post(handler::dashboard::providers::fetch_models),
         )
+        .route(
+            "/api/dashboard/providers/bulk",
+            axum::routing::post(handler::dashboard::providers::bulk_create_providers),
+        )
+        .route(
+            "/api/dashboard/providers/{id}/duplicate",
+            axum::routing::post(handler::dashboard::providers::duplicate_provider),
+        )
+        .route(
+            "/api/dashboard/providers/{id}/disable",
+            axum::routing::post(handler::dashboard::providers::disable_provider),
+        )
+        .route(
+            "/api/dashboard/providers/{id}/enable",
+            axum::routing::post(handler::dashboard::providers::enable_provider),
+        )
         .route(
             "/api/dashboard/providers/{id}/health",
             axum::routing::post(handler::dashboard::providers::health_check),
@@ -228,11 +344,13 @@ pub fn build_router(state: AppState) -> Router {
         .route(
             "/api/dashboard/providers",
             axum::routing::get(handler::dashboard::providers::list_providers)
+                .layer(config_etag.clone())
                 .post(handler::dashboard::providers::create_provider),
         )
         .route(
             "/api/dashboard/providers/{id}",
             axum::routing::get(handler::dashboard::providers::get_provider)
+                .layer(config_etag.clone())
                 .patch(handler::dashboard::providers::update_provider)
                 .delete(handler::dashboard::providers::delete_provider),
         )
@@ -240,6 +358,7 @@ pub fn build_router(state: AppState) -> Router {
         .route(
             "/api/dashboard/auth-keys",
             axum::routing::get(handler::dashboard::auth_keys::list_auth_keys)
+                .layer(config_etag.clone())
                 .post(handler::dashboard::auth_keys::create_auth_key),
         )
         .route(
@@ -251,16 +370,29 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/auth-keys/{id}/reveal",
             axum::routing::post(handler::dashboard::auth_keys::reveal_auth_key),
         )
+        .route(
+            "/api/dashboard/auth-keys/{id}/disable",
+            axum::routing::post(handler::dashboard::auth_keys::disable_auth_key),
+        )
+        .route(
+            "/api/dashboard/auth-keys/{id}/enable",
+            axum::routing::post(handler::dashboard::auth_keys::enable_auth_key),
+        )
         // Routing
         .route(
             "/api/dashboard/routing",
             axum::routing::get(handler::dashboard::routing::get_routing)
+                .layer(config_etag.clone())
                 .patch(handler::dashboard::routing::update_routing),
         )
         .route(
             "/api/dashboard/routing/preview",
             axum::routing::post(handler::dashboard::routing::preview_route),
         )
+        .route(
+            "/api/dashboard/routing/aliases",
+            axum::routing::get(handler::dashboard::routing::model_aliases),
+        )
         // Config operations
         .route(
             "/api/dashboard/config/validate",
@@ -274,19 +406,40 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/config/apply",
             axum::routing::put(handler::dashboard::config_ops::apply_config),
         )
+        .route(
+            "/api/dashboard/config/preview",
+            axum::routing::post(handler::dashboard::config_ops::preview_config),
+        )
+        .route(
+            "/api/dashboard/config/export",
+            axum::routing::get(handler::dashboard::config_bundle::export_config),
+        )
+        .route(
+            "/api/dashboard/config/import",
+            axum::routing::post(handler::dashboard::config_bundle::import_config),
+        )
         .route(
             "/api/dashboard/config/current",
-            axum::routing::get(handler::dashboard::config_ops::get_config),
+            axum::routing::get(handler::dashboard::config_ops::get_config)
+                .layer(config_etag.clone()),
         )
         .route(
             "/api/dashboard/config/raw",
-            axum::routing::get(handler::dashboard::config_ops::get_raw_config),
+            axum::routing::get(handler::dashboard::config_ops::get_raw_config).layer(config_etag),
+        )
+        .route(
+            "/api/dashboard/cluster",
+            axum::routing::get(handler::dashboard::cluster::cluster_overview),
         )
         // Request logs — filters before {id} to avoid capture
         .route(
             "/api/dashboard/logs/stats",
             axum::routing::get(handler::dashboard::logs::log_stats),
         )
+        .route(
+            "/api/dashboard/logs/errors",
+            axum::routing::get(handler::dashboard::logs::error_groups),
+        )
         .route(
             "/api/dashboard/logs/filters",
             axum::routing::get(handler::dashboard::logs::filter_options),
@@ -299,6 +452,27 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/logs",
             axum::routing::get(handler::dashboard::logs::query_logs),
         )
+        .route(
+            "/api/dashboard/costs/by-tag",
+            axum::routing::get(handler::dashboard::costs::by_tag),
+        )
+        .route(
+            "/api/dashboard/reports/{name}/send",
+            axum::routing::post(handler::dashboard::reports::send_report),
+        )
+        // In-flight requests
+        .route(
+            "/api/dashboard/requests/active",
+            axum::routing::get(handler::dashboard::requests::list_inflight),
+        )
+        .route(
+            "/api/dashboard/requests/{request_id}/cancel",
+            axum::routing::post(handler::dashboard::requests::cancel_request),
+        )
+        .route(
+            "/api/dashboard/requests/{request_id}/debug",
+            axum::routing::get(handler::dashboard::requests::debug_trace),
+        )
         // System
         .route(
             "/api/dashboard/system/health",
@@ -308,6 +482,36 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/system/logs",
             axum::routing::get(handler::dashboard::system::system_logs),
         )
+        .route(
+            "/api/dashboard/system/logs/files",
+            axum::routing::get(handler::dashboard::system::log_files),
+        )
+        .route(
+            "/api/dashboard/system/logs/files/{name}",
+            axum::routing::get(handler::dashboard::system::download_log_file),
+        )
+        .route(
+            "/api/dashboard/system/log-level",
+            axum::routing::get(handler::dashboard::system::get_log_level)
+                .patch(handler::dashboard::system::update_log_level),
+        )
+        .route(
+            "/api/dashboard/system/uptime-history",
+            axum::routing::get(handler::dashboard::system::uptime_history),
+        )
+        // Metrics
+        .route(
+            "/api/dashboard/metrics/latency",
+            axum::routing::get(handler::dashboard::metrics::latency),
+        )
+        .route(
+            "/api/dashboard/metrics/reset",
+            axum::routing::post(handler::dashboard::metrics::reset),
+        )
+        .route(
+            "/api/dashboard/metrics/reset/credentials/{credential}",
+            axum::routing::post(handler::dashboard::metrics::reset_credential),
+        )
         // Tenants
         .route(
             "/api/dashboard/tenants",
@@ -350,6 +554,14 @@ pub fn build_router(state: AppState) -> Router {
             "/api/dashboard/routing/explain",
             axum::routing::post(handler::dashboard::routing::explain_route),
         )
+        .route(
+            "/api/dashboard/routing/resolve",
+            axum::routing::post(handler::dashboard::routing::resolve_route),
+        )
+        .route(
+            "/api/dashboard/translate",
+            axum::routing::post(handler::dashboard::translate::translate),
+        )
         // WebSocket route (auth via bearer header or session cookie)
         .route(
             "/ws/dashboard",
@@ -362,20 +574,20 @@ pub fn build_router(state: AppState) -> Router {
         // Dashboard body size limit (1 MB) to reject oversized payloads
         .layer(RequestBodyLimitLayer::new(1024 * 1024));
 
+    // Dashboard routes answer 404 while `dashboard.enabled` is false in the live config
+    let dashboard_routes = dashboard_auth_routes
+        .merge(dashboard_protected_routes)
+        .layer(axum_mw::from_fn_with_state(
+            state.clone(),
+            middleware::dashboard_auth::dashboard_enabled_middleware,
+        ));
+
     // Compose: public + admin + api + dashboard, then global middleware layers (outer → inner)
-    let mut router = Router::new()
+    let app = Router::new()
         .merge(public_routes)
         .merge(admin_routes)
-        .merge(api_routes);
-
-    // Only register dashboard routes when dashboard is enabled
-    if state.config.load().dashboard.enabled {
-        router = router
-            .merge(dashboard_auth_routes)
-            .merge(dashboard_protected_routes);
-    }
-
-    router
+        .merge(api_routes)
+        .merge(dashboard_routes)
         .layer(axum_mw::from_fn(
             middleware::request_logging::request_logging_middleware,
         ))
@@ -384,5 +596,9 @@ pub fn build_router(state: AppState) -> Router {
         ))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .with_state(state)
+        .with_state(state);
+
+    // Version negotiation wraps the whole router: `/api/v1/dashboard/*` and
+    // `/api/v1/admin/*` are rewritten before route matching
+    Router::new().fallback_service(axum_mw::from_fn(api_version::negotiate).layer(app))
 }
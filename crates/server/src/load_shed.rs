@@ -0,0 +1,228 @@
+//! Load shedding per the `concurrency` config: process-wide caps on API requests
+//! and streamed responses in flight.
+//!
+//! A request holds its slot until its response body has been fully sent or
+//! dropped, so long streams keep counting against the caps. Past a cap the
+//! request is rejected with 503 and `Retry-After` instead of queueing. Caps are
+//! read from the live config, so dashboard edits apply to the next request.
+
+use crate::AppState;
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use prism_core::config::ConcurrencyConfig;
+use prism_core::error::ProxyError;
+use prism_core::metrics::Metrics;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+pub struct LoadShedder {
+    requests: AtomicUsize,
+    streams: AtomicUsize,
+    /// Woken whenever a slot is given back.
+    released: Notify,
+}
+
+impl LoadShedder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// API requests currently holding a slot.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Streamed responses currently holding a slot.
+    pub fn streams(&self) -> usize {
+        self.streams.load(Ordering::Relaxed)
+    }
+
+    /// Resolves the next time any request or stream slot is given back.
+    pub async fn slot_released(&self) {
+        self.released.notified().await;
+    }
+
+    /// Take a request slot, or shed the request when `max-requests` are in flight.
+    pub fn acquire_request(
+        self: &Arc<Self>,
+        config: &ConcurrencyConfig,
+        metrics: &Metrics,
+    ) -> Result<Slot, ProxyError> {
+        self.acquire(false, config.max_requests, config, metrics)
+    }
+
+    /// Take a stream slot, or shed the request when `max-streams` are in flight.
+    pub fn acquire_stream(
+        self: &Arc<Self>,
+        config: &ConcurrencyConfig,
+        metrics: &Metrics,
+    ) -> Result<Slot, ProxyError> {
+        self.acquire(true, config.max_streams, config, metrics)
+    }
+
+    fn acquire(
+        self: &Arc<Self>,
+        stream: bool,
+        max: usize,
+        config: &ConcurrencyConfig,
+        metrics: &Metrics,
+    ) -> Result<Slot, ProxyError> {
+        let slot = Slot {
+            shedder: self.clone(),
+            stream,
+        };
+        let in_flight = slot.counter().fetch_add(1, Ordering::AcqRel);
+        if max > 0 && in_flight >= max {
+            // Dropping the slot gives the count back.
+            drop(slot);
+            metrics.record_load_shed(stream);
+            let kind = if stream { "streams" } else { "requests" };
+            tracing::warn!(kind, max, "Load shed: concurrency cap reached");
+            return Err(ProxyError::Overloaded {
+                message: format!("too many concurrent {kind} (limit {max})"),
+                retry_after_secs: config.retry_after_secs,
+            });
+        }
+        Ok(slot)
+    }
+}
+
+/// A held request or stream slot, given back on drop.
+pub struct Slot {
+    shedder: Arc<LoadShedder>,
+    stream: bool,
+}
+
+impl Slot {
+    fn counter(&self) -> &AtomicUsize {
+        if self.stream {
+            &self.shedder.streams
+        } else {
+            &self.shedder.requests
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.counter().fetch_sub(1, Ordering::AcqRel);
+        self.shedder.released.notify_waiters();
+    }
+}
+
+/// Keep `slot` until the response body ends. Sized bodies are already complete
+/// and release it right away.
+pub fn hold_until_body_end(response: Response, slot: Slot) -> Response {
+    let (parts, body) = response.into_parts();
+    if body.size_hint().exact().is_some() {
+        return Response::from_parts(parts, body);
+    }
+    let stream = futures::stream::unfold(
+        (body.into_data_stream(), slot),
+        |(mut body, slot)| async move {
+            use futures::StreamExt;
+            body.next().await.map(|frame| (frame, (body, slot)))
+        },
+    );
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// Middleware enforcing `concurrency.max-requests` on API routes.
+pub async fn load_shed_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ProxyError> {
+    let config = state.config.load();
+    let slot = state
+        .load_shedder
+        .acquire_request(&config.concurrency, &state.metrics)?;
+    drop(config);
+    Ok(hold_until_body_end(next.run(request).await, slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(max_requests: usize, max_streams: usize) -> ConcurrencyConfig {
+        ConcurrencyConfig {
+            max_requests,
+            max_streams,
+            ..ConcurrencyConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_sheds_past_cap_and_releases_on_drop() {
+        let shedder = Arc::new(LoadShedder::new());
+        let metrics = Metrics::new();
+        let config = caps(2, 1);
+
+        let first = shedder.acquire_request(&config, &metrics).unwrap();
+        let _second = shedder.acquire_request(&config, &metrics).unwrap();
+        let err = shedder.acquire_request(&config, &metrics).err().unwrap();
+        assert_eq!(err.status_code_u16(), 503);
+        assert_eq!(err.retry_after_secs(), Some(1));
+        assert_eq!(shedder.requests(), 2);
+
+        drop(first);
+        assert!(shedder.acquire_request(&config, &metrics).is_ok());
+
+        let _stream = shedder.acquire_stream(&config, &metrics).unwrap();
+        assert!(shedder.acquire_stream(&config, &metrics).is_err());
+        assert_eq!(metrics.shed_requests.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.shed_streams.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_zero_cap_is_unlimited() {
+        let shedder = Arc::new(LoadShedder::new());
+        let metrics = Metrics::new();
+        let slots: Vec<_> = (0..100)
+            .map(|_| shedder.acquire_stream(&caps(0, 0), &metrics).unwrap())
+            .collect();
+        assert_eq!(shedder.streams(), 100);
+        drop(slots);
+        assert_eq!(shedder.streams(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slot_release_wakes_waiters() {
+        let shedder = Arc::new(LoadShedder::new());
+        let slot = shedder
+            .acquire_request(&caps(0, 0), &Metrics::new())
+            .unwrap();
+        let waiter = tokio::spawn({
+            let shedder = shedder.clone();
+            async move { shedder.slot_released().await }
+        });
+        tokio::task::yield_now().await;
+        drop(slot);
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter woken on release")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_holds_slot_until_end() {
+        use futures::StreamExt;
+        let shedder = Arc::new(LoadShedder::new());
+        let slot = shedder
+            .acquire_stream(&caps(0, 0), &Metrics::new())
+            .unwrap();
+        let chunks = futures::stream::iter(["a", "b"].map(Ok::<_, std::convert::Infallible>));
+        let response = hold_until_body_end(Response::new(Body::from_stream(chunks)), slot);
+        assert_eq!(shedder.streams(), 1);
+
+        let mut body = response.into_body().into_data_stream();
+        while body.next().await.is_some() {}
+        drop(body);
+        assert_eq!(shedder.streams(), 0);
+    }
+}
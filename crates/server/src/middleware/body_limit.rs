@@ -0,0 +1,33 @@
+use crate::AppState;
+use axum::body::Body;
+use axum::{extract::State, http::Request, middleware::Next, response::Response};
+use prism_core::error::ProxyError;
+
+/// Enforce `body-limit-mb` (or a matching `routes` override) from the current config snapshot.
+///
+/// Requests with a declared `Content-Length` above the limit are rejected up front;
+/// streamed bodies are wrapped so extractors fail with 413 once the limit is crossed.
+/// Reading the limit per request lets dashboard edits take effect without a restart.
+pub async fn body_limit_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, ProxyError> {
+    let limit = state.config.load().body_limit_bytes(request.uri().path());
+
+    let declared = request
+        .headers()
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if let Some(len) = declared
+        && len > limit
+    {
+        return Err(ProxyError::PayloadTooLarge(format!(
+            "request body of {len} bytes exceeds the {limit} byte limit for this route"
+        )));
+    }
+
+    let request = request.map(|body| Body::new(http_body_util::Limited::new(body, limit)));
+    Ok(next.run(request).await)
+}
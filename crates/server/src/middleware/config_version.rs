@@ -0,0 +1,24 @@
+use crate::AppState;
+use crate::handler::dashboard::config_tx::current_version;
+use axum::http::{HeaderValue, header};
+use axum::{extract::State, http::Request, middleware::Next, response::Response};
+
+/// Stamp successful reads of config-backed resources (config, providers, auth keys,
+/// auth profiles, routing) with the current config version as an `ETag`, so an
+/// edit made from that read can send it back through `If-Match`.
+pub async fn config_etag_middleware(
+    State(state): State<AppState>,
+    request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    if !response.status().is_success() || response.headers().contains_key(header::ETAG) {
+        return response;
+    }
+    if let Ok(version) = current_version(&state)
+        && let Ok(value) = HeaderValue::from_str(&format!("\"{version}\""))
+    {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
@@ -15,6 +15,18 @@ pub struct Claims {
     pub iat: usize,
 }
 
+/// Hide dashboard endpoints while `dashboard.enabled` is false in the current config.
+pub async fn dashboard_enabled_middleware(
+    State(state): State<AppState>,
+    request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    if !state.config.load().dashboard.enabled {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    next.run(request).await
+}
+
 /// JWT authentication middleware for dashboard endpoints.
 pub async fn dashboard_auth_middleware(
     State(state): State<AppState>,
@@ -1,3 +1,5 @@
+pub mod body_limit;
+pub mod config_version;
 pub mod dashboard_auth;
 pub mod rate_limit;
 pub mod request_context;
@@ -9,7 +9,18 @@ pub async fn rate_limit_middleware(
     next: Next,
 ) -> Result<Response, ProxyError> {
     let config = state.config.load();
+
+    // Per-route RPM override from the `routes` config section. It is checked
+    // after the global limits and counted only for admitted requests.
+    let route = config
+        .route_limits(request.uri().path())
+        .and_then(|route| Some((route.path.as_str(), route.rpm?)));
+
     if !config.rate_limit.enabled {
+        if let Some((path, rpm)) = route {
+            check_route(&state, path, rpm)?;
+            state.rate_limiter.record_route(path);
+        }
         return Ok(next.run(request).await);
     }
 
@@ -85,8 +96,15 @@ pub async fn rate_limit_middleware(
         }
     }
 
-    // Record the request (RPM dimension)
+    if let Some((path, rpm)) = route {
+        check_route(&state, path, rpm)?;
+    }
+
+    // Record the admitted request (RPM dimensions)
     state.rate_limiter.record_request(api_key.as_deref());
+    if let Some((path, _)) = route {
+        state.rate_limiter.record_route(path);
+    }
 
     let mut response = next.run(request).await;
 
@@ -108,3 +126,22 @@ pub async fn rate_limit_middleware(
 
     Ok(response)
 }
+
+fn check_route(state: &AppState, path: &str, rpm: u32) -> Result<(), ProxyError> {
+    let info = state.rate_limiter.check_route(path, rpm);
+    if info.allowed {
+        return Ok(());
+    }
+    tracing::warn!(
+        route = %path,
+        reset_secs = info.reset_secs,
+        "Per-route rate limit exceeded"
+    );
+    Err(ProxyError::RateLimited {
+        message: format!(
+            "Route rate limit exceeded. Retry after {}s",
+            info.reset_secs
+        ),
+        retry_after_secs: info.reset_secs,
+    })
+}
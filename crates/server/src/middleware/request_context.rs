@@ -1,6 +1,7 @@
 use axum::extract::ConnectInfo;
+use axum::http::HeaderValue;
 use axum::{extract::Request, middleware::Next, response::Response};
-use prism_core::context::RequestContext;
+use prism_core::context::{RequestContext, TraceContext};
 use std::net::SocketAddr;
 
 /// Middleware that injects a `RequestContext` as an axum Extension.
@@ -9,6 +10,9 @@ use std::net::SocketAddr;
 /// Forwarded headers (`X-Forwarded-For`, `X-Real-IP`) are NOT trusted
 /// unless a reverse proxy is explicitly configured, preventing IP spoofing
 /// that could bypass `localhost_only` or login rate limiting.
+///
+/// An inbound W3C `traceparent` is continued (new span, same trace id); otherwise a
+/// new trace is started. The gateway's `traceparent` is echoed on the response.
 pub async fn request_context_middleware(mut request: Request, next: Next) -> Response {
     // Use the actual socket peer address as the client IP (safe default)
     let client_ip = request
@@ -25,8 +29,21 @@ pub async fn request_context_middleware(mut request: Request, next: Next) -> Res
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
+    let trace = TraceContext::from_traceparent(
+        request
+            .headers()
+            .get("traceparent")
+            .and_then(|v| v.to_str().ok()),
+    );
+    let traceparent = HeaderValue::from_str(&trace.to_traceparent()).ok();
+
     let mut ctx = RequestContext::new(client_ip);
     ctx.client_region = client_region;
+    ctx.trace = trace;
     request.extensions_mut().insert(ctx);
-    next.run(request).await
+    let mut response = next.run(request).await;
+    if let Some(traceparent) = traceparent {
+        response.headers_mut().insert("traceparent", traceparent);
+    }
+    response
 }
@@ -0,0 +1,182 @@
+//! Start/stop history of the proxy process behind
+//! `GET /api/dashboard/system/uptime-history`.
+//!
+//! Each start appends a run and writes the file right away, so a run that never
+//! records a stop was ended by a crash, `SIGKILL`, or a host restart.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Shutdown reason given to a previous run that never recorded a stop.
+pub const UNCLEAN_SHUTDOWN: &str = "unclean";
+
+/// One lifetime of the proxy process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessRun {
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub stopped_at: Option<DateTime<Utc>>,
+    pub pid: u32,
+    pub version: String,
+    /// `sigterm`, `sigint`, `shutdown`, or `unclean` once a later start found no stop.
+    #[serde(default)]
+    pub shutdown_reason: Option<String>,
+    /// Message of the last panic seen during the run, caught or not.
+    #[serde(default)]
+    pub panic: Option<String>,
+}
+
+impl ProcessRun {
+    fn current() -> Self {
+        Self {
+            started_at: Utc::now(),
+            stopped_at: None,
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            shutdown_reason: None,
+            panic: None,
+        }
+    }
+}
+
+/// Process runs, oldest first; the last one is the current process.
+pub struct ProcessHistory {
+    path: Option<PathBuf>,
+    max_runs: usize,
+    runs: Mutex<Vec<ProcessRun>>,
+}
+
+impl Default for ProcessHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessHistory {
+    /// In-memory history holding only the current run.
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            max_runs: 1,
+            runs: Mutex::new(vec![ProcessRun::current()]),
+        }
+    }
+
+    /// Load the history file, close out an unfinished previous run, and record this
+    /// start. Keeps the newest `max_runs` runs.
+    pub fn open(path: PathBuf, max_runs: usize) -> Result<Self, anyhow::Error> {
+        let mut runs = load(&path)?;
+        if let Some(last) = runs.last_mut()
+            && last.stopped_at.is_none()
+            && last.shutdown_reason.is_none()
+        {
+            last.shutdown_reason = Some(UNCLEAN_SHUTDOWN.to_string());
+        }
+        runs.push(ProcessRun::current());
+        let history = Self {
+            path: Some(path),
+            max_runs: max_runs.max(1),
+            runs: Mutex::new(runs),
+        };
+        history.save()?;
+        Ok(history)
+    }
+
+    pub fn runs(&self) -> Vec<ProcessRun> {
+        self.runs
+            .lock()
+            .map(|runs| runs.clone())
+            .unwrap_or_default()
+    }
+
+    /// Mark the current run as having panicked. Safe to call from a panic hook.
+    pub fn record_panic(&self, message: &str) {
+        self.update(|run| run.panic = Some(message.to_string()));
+    }
+
+    /// Record a graceful stop of the current run.
+    pub fn record_stop(&self, reason: &str) {
+        self.update(|run| {
+            run.stopped_at = Some(Utc::now());
+            run.shutdown_reason = Some(reason.to_string());
+        });
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut ProcessRun)) {
+        // A panic while the lock is held must not abort the process from the hook.
+        let Ok(mut runs) = self.runs.try_lock() else {
+            return;
+        };
+        if let Some(run) = runs.last_mut() {
+            apply(run);
+        }
+        drop(runs);
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to write process history: {e}");
+        }
+    }
+
+    /// Trim to `max_runs` and write the file atomically (temp file, then rename).
+    fn save(&self) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let bytes = {
+            let mut runs = self
+                .runs
+                .lock()
+                .map_err(|e| anyhow::anyhow!("process history lock poisoned: {e}"))?;
+            let excess = runs.len().saturating_sub(self.max_runs);
+            runs.drain(..excess);
+            serde_json::to_vec_pretty(&*runs)?
+        };
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Read the history file. A missing file yields no runs.
+fn load(path: &Path) -> Result<Vec<ProcessRun>, anyhow::Error> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_marks_unfinished_run_unclean() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+
+        let first = ProcessHistory::open(path.clone(), 2).unwrap();
+        first.record_stop("sigterm");
+        drop(first);
+
+        // Second run crashes: a panic is recorded but no stop.
+        let second = ProcessHistory::open(path.clone(), 2).unwrap();
+        second.record_panic("boom");
+        drop(second);
+
+        let third = ProcessHistory::open(path, 2).unwrap();
+        let runs = third.runs();
+        assert_eq!(runs.len(), 2, "trimmed to max_runs");
+        assert_eq!(runs[0].shutdown_reason.as_deref(), Some(UNCLEAN_SHUTDOWN));
+        assert_eq!(runs[0].panic.as_deref(), Some("boom"));
+        assert!(runs[0].stopped_at.is_none());
+        assert!(runs[1].shutdown_reason.is_none());
+    }
+}
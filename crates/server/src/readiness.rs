@@ -0,0 +1,225 @@
+//! Readiness tracking behind `GET /ready` and the lifecycle ready notification.
+//!
+//! Unlike `/health`, readiness stays false until the listener is bound and, when
+//! `readiness.probe-credentials` is set, one credential's upstream has answered.
+//! With `readiness.warm-up` it also waits for a warm-up round that opens pooled
+//! connections to every enabled credential's upstream.
+
+use crate::AppState;
+use serde::Serialize;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Outcome of the startup credential connectivity probe.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProbeState {
+    /// Probing is disabled in config.
+    Skipped,
+    /// No probe round has finished yet.
+    Pending,
+    Passed {
+        credential: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Outcome of the startup connection warm-up.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WarmUpState {
+    /// Warm-up is disabled in config.
+    Skipped,
+    /// The warm-up round has not finished yet.
+    Pending,
+    /// Every credential was tried. Failures do not block readiness.
+    Finished {
+        warmed: usize,
+        failed: Vec<String>,
+        elapsed_ms: u64,
+    },
+}
+
+pub struct Readiness {
+    listening: AtomicBool,
+    probe: RwLock<ProbeState>,
+    warm_up: RwLock<WarmUpState>,
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self {
+            listening: AtomicBool::new(false),
+            probe: RwLock::new(ProbeState::Pending),
+            warm_up: RwLock::new(WarmUpState::Pending),
+        }
+    }
+
+    /// Record that the server socket is bound and accepting connections.
+    pub fn mark_listening(&self) {
+        self.listening.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.listening.load(Ordering::Relaxed)
+    }
+
+    pub fn probe(&self) -> ProbeState {
+        self.probe
+            .read()
+            .map(|p| p.clone())
+            .unwrap_or(ProbeState::Pending)
+    }
+
+    fn set_probe(&self, probe: ProbeState) {
+        if let Ok(mut current) = self.probe.write() {
+            *current = probe;
+        }
+    }
+
+    pub fn probe_passed(&self) -> bool {
+        matches!(
+            self.probe(),
+            ProbeState::Skipped | ProbeState::Passed { .. }
+        )
+    }
+
+    pub fn warm_up(&self) -> WarmUpState {
+        self.warm_up
+            .read()
+            .map(|w| w.clone())
+            .unwrap_or(WarmUpState::Pending)
+    }
+
+    fn set_warm_up(&self, warm_up: WarmUpState) {
+        if let Ok(mut current) = self.warm_up.write() {
+            *current = warm_up;
+        }
+    }
+
+    pub fn warm_up_done(&self) -> bool {
+        !matches!(self.warm_up(), WarmUpState::Pending)
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run the startup self-test: the optional warm-up round, then the credential
+/// probe, retried until one passes.
+pub async fn run_self_test(state: &AppState) {
+    if state.config.load().readiness.warm_up {
+        state.readiness.set_warm_up(warm_up(state).await);
+    } else {
+        state.readiness.set_warm_up(WarmUpState::Skipped);
+    }
+    if !state.config.load().readiness.probe_credentials {
+        state.readiness.set_probe(ProbeState::Skipped);
+        return;
+    }
+    loop {
+        match probe_credentials(state).await {
+            Ok(credential) => {
+                tracing::info!(credential = credential.as_str(), "Readiness probe passed");
+                state.readiness.set_probe(ProbeState::Passed { credential });
+                return;
+            }
+            Err(error) => {
+                tracing::warn!(error = error.as_str(), "Readiness probe failed, retrying");
+                state.readiness.set_probe(ProbeState::Failed { error });
+            }
+        }
+        let interval = state.config.load().readiness.probe_interval_secs;
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+/// Probe credentials in turn; returns the first whose upstream answers below 500.
+async fn probe_credentials(state: &AppState) -> Result<String, String> {
+    let timeout = Duration::from_secs(state.config.load().readiness.probe_timeout_secs);
+    let mut last_error = "no credentials configured".to_string();
+    for auth in state.router.credential_map().into_values().flatten() {
+        if auth.disabled {
+            continue;
+        }
+        let name = auth
+            .credential_name
+            .clone()
+            .unwrap_or_else(|| auth.provider_name.clone());
+        let client = match prism_provider::common::build_client(&auth, &state.http_client_pool) {
+            Ok(client) => client,
+            Err(e) => {
+                last_error = format!("{name}: {e}");
+                continue;
+            }
+        };
+        let request = client.get(auth.resolved_base_url()).timeout(timeout);
+        let request = prism_provider::common::apply_auth(request, &auth);
+        match request.send().await {
+            Ok(resp) if resp.status().as_u16() < 500 => return Ok(name),
+            Ok(resp) => last_error = format!("{name}: status {}", resp.status()),
+            Err(e) => last_error = format!("{name}: {e}"),
+        }
+    }
+    Err(last_error)
+}
+
+/// Send one small unauthenticated request to every enabled credential's upstream
+/// through its pooled client, leaving an idle connection ready for real traffic.
+/// Any HTTP response counts as warmed; requests run concurrently.
+async fn warm_up(state: &AppState) -> WarmUpState {
+    let timeout = Duration::from_secs(state.config.load().readiness.probe_timeout_secs);
+    let started = Instant::now();
+    let attempts = state
+        .router
+        .credential_map()
+        .into_values()
+        .flatten()
+        .filter(|auth| !auth.disabled)
+        .map(|auth| async move {
+            let name = auth
+                .credential_name
+                .clone()
+                .unwrap_or_else(|| auth.provider_name.clone());
+            let client = prism_provider::common::build_client(&auth, &state.http_client_pool)
+                .map_err(|e| format!("{name}: {e}"))?;
+            let request_started = Instant::now();
+            client
+                .head(auth.resolved_base_url())
+                .timeout(timeout)
+                .send()
+                .await
+                .map_err(|e| format!("{name}: {e}"))?;
+            tracing::debug!(
+                credential = name.as_str(),
+                elapsed_ms = request_started.elapsed().as_millis() as u64,
+                "Warmed upstream connection"
+            );
+            Ok::<_, String>(())
+        });
+    let results = futures::future::join_all(attempts).await;
+
+    let warmed = results.iter().filter(|r| r.is_ok()).count();
+    let failed: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    for error in &failed {
+        tracing::warn!(error = error.as_str(), "Warm-up request failed");
+    }
+    tracing::info!(
+        warmed,
+        failed = failed.len(),
+        elapsed_ms,
+        "Upstream connection warm-up finished"
+    );
+    WarmUpState::Finished {
+        warmed,
+        failed,
+        elapsed_ms,
+    }
+}
@@ -0,0 +1,80 @@
+//! Diff-based runtime config reload shared by the file watcher, SIGHUP, and dashboard writes.
+
+use crate::AppState;
+use prism_core::config::{Config, ReloadDelta};
+use std::sync::Arc;
+
+/// Swap in `next` and rebuild only the subsystems whose config sections changed.
+/// Returns what was touched so callers can log or report it.
+pub fn apply_config(state: &AppState, next: Config) -> Result<ReloadDelta, String> {
+    let delta = state.config.load().reload_delta(&next);
+
+    if delta.credentials {
+        state.executors.check_config(&next)?;
+        state
+            .auth_runtime
+            .sync_with_config(&next)
+            .map_err(|e| format!("Failed to sync auth runtime: {e}"))?;
+        state
+            .router
+            .set_oauth_states(state.auth_runtime.oauth_snapshot());
+        state
+            .router
+            .set_token_states(state.auth_runtime.tokens().states());
+    }
+    // The router keeps its own copy of the credential groups and the default
+    // profile's credential strategy, so routing-only edits rebuild it too.
+    if delta.credentials || delta.routing {
+        state.router.update_from_config(&next);
+        state
+            .catalog
+            .update_from_credentials(&state.router.credential_map());
+    }
+    if delta.rate_limits {
+        state.rate_limiter.update_config(&next.rate_limit);
+    }
+    if delta.prices {
+        state.cost_calculator.update_prices(&next.model_prices);
+    }
+    if delta.translation {
+        state.translators.set_rules(next.translation_rules.clone());
+    }
+    // Executors read the global proxy, timeouts, and fault injection settings from the
+    // pool on every request; updating the network settings drops cached clients.
+    // Per-provider proxies changing also needs a clear.
+    if delta.network {
+        state.http_client_pool.set_network(next.network_settings());
+        state
+            .http_client_pool
+            .set_fault_injection(next.fault_injection.clone());
+    }
+    if delta.credentials {
+        state.http_client_pool.clear();
+    }
+    // Secrets to mask follow the credentials and auth keys of the new config.
+    prism_core::redact::install_for(&next);
+    // Auth keys, payload rules, and route profiles and rules are read from the
+    // config snapshot per request.
+    state.config.store(Arc::new(next));
+    state.crash_reporter.note_config_reload();
+    Ok(delta)
+}
+
+/// Load the config file from disk and apply it, logging the touched subsystems.
+pub fn reload_from_disk(state: &AppState, source: &str) -> Result<ReloadDelta, String> {
+    let path = state
+        .config_path
+        .lock()
+        .map_err(|e| format!("Failed to lock config path: {e}"))?
+        .clone();
+    let next = Config::load(&path).map_err(|e| e.to_string())?;
+    let providers = next.providers.len();
+    let delta = apply_config(state, next)?;
+    tracing::info!(
+        source,
+        providers,
+        touched = ?delta.touched(),
+        "Config reloaded"
+    );
+    Ok(delta)
+}
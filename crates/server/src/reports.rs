@@ -0,0 +1,253 @@
+//! Scheduled usage reports (`reports` in config).
+//!
+//! A background task checks the schedule every minute and, for each report
+//! whose send time passed since the last check, summarizes the hourly usage
+//! totals of the log store over the period that just ended and posts it to the
+//! report's webhook.
+//! Reports are read from the live config, so reloads take effect without a
+//! restart. A send time that passes while the process is down is skipped.
+
+use crate::AppState;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Utc};
+use prism_core::config::{ReportFormat, ReportPeriod, UsageReportConfig};
+use prism_core::request_log::{UsageBreakdown, UsageSummary};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Start the report scheduler.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut checked = Utc::now();
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+            let reports = state.config.load().reports.clone();
+            for report in reports.iter().filter(|r| last_send_time(r, now) > checked) {
+                let end = last_send_time(report, now);
+                match deliver(&state, report, end).await {
+                    Ok(_) => tracing::info!(report = %report.name, "Usage report delivered"),
+                    Err(e) => {
+                        tracing::warn!(report = %report.name, error = %e, "Usage report delivery failed")
+                    }
+                }
+            }
+            checked = now;
+        }
+    });
+}
+
+/// Summarize the period ending at `end` and post it to the report's webhook.
+/// Returns the summary that was sent.
+pub async fn deliver(
+    state: &AppState,
+    report: &UsageReportConfig,
+    end: DateTime<Utc>,
+) -> Result<UsageSummary, String> {
+    let start = end - period_length(report.period);
+    let summary = state
+        .log_store
+        .usage_summary(start.timestamp_millis(), end.timestamp_millis(), report.top)
+        .await;
+    let body = payload(report, start, end, &summary);
+
+    let client = state
+        .http_client_pool
+        .get_or_create_current(None)
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(&report.webhook_url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("webhook request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("webhook returned {}", response.status()));
+    }
+    Ok(summary)
+}
+
+/// The most recent scheduled send time at or before `now`.
+fn last_send_time(report: &UsageReportConfig, now: DateTime<Utc>) -> DateTime<Utc> {
+    let today = now
+        .date_naive()
+        .and_hms_opt(report.hour_utc, 0, 0)
+        .expect("hour-utc is validated to be below 24")
+        .and_utc();
+    let mut time = match report.period {
+        ReportPeriod::Daily => today,
+        ReportPeriod::Weekly => {
+            today - ChronoDuration::days(i64::from(now.weekday().num_days_from_monday()))
+        }
+    };
+    if time > now {
+        time -= period_length(report.period);
+    }
+    time
+}
+
+fn period_length(period: ReportPeriod) -> ChronoDuration {
+    match period {
+        ReportPeriod::Daily => ChronoDuration::days(1),
+        ReportPeriod::Weekly => ChronoDuration::weeks(1),
+    }
+}
+
+fn payload(
+    report: &UsageReportConfig,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    summary: &UsageSummary,
+) -> Value {
+    let text = render_text(report, start, end, summary);
+    match report.format {
+        ReportFormat::Json => json!({
+            "report": report.name,
+            "period": report.period.as_str(),
+            "from": start.to_rfc3339(),
+            "to": end.to_rfc3339(),
+            "text": text,
+            "usage": summary,
+        }),
+        ReportFormat::Slack => json!({ "text": text }),
+        ReportFormat::Discord => json!({ "content": text }),
+    }
+}
+
+fn render_text(
+    report: &UsageReportConfig,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    summary: &UsageSummary,
+) -> String {
+    let mut text = format!(
+        "*{} usage report: {}* ({} to {} UTC)\nRequests: {} ({} errors) | Tokens: {} in, {} out | Cost: ${:.2}",
+        report.period.as_str(),
+        report.name,
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M"),
+        summary.requests,
+        summary.errors,
+        summary.input_tokens,
+        summary.output_tokens,
+        summary.cost,
+    );
+    for (title, rows) in [
+        ("Top models", &summary.by_model),
+        ("Top API keys", &summary.by_api_key),
+    ] {
+        if rows.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\n{title}:"));
+        for row in rows {
+            text.push_str(&format_row(row));
+        }
+    }
+    text
+}
+
+fn format_row(row: &UsageBreakdown) -> String {
+    format!(
+        "\n- {}: {} requests, {} tokens, ${:.2}",
+        row.name, row.requests, row.tokens, row.cost
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(period: ReportPeriod, hour_utc: u32, format: ReportFormat) -> UsageReportConfig {
+        UsageReportConfig {
+            name: "usage".to_string(),
+            period,
+            hour_utc,
+            webhook_url: "http://127.0.0.1/hook".to_string(),
+            format,
+            top: 10,
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_last_send_time_daily_and_weekly() {
+        let daily = report(ReportPeriod::Daily, 9, ReportFormat::Json);
+        // 2026-10-14 is a Wednesday.
+        assert_eq!(
+            last_send_time(&daily, at("2026-10-14T10:00:00Z")),
+            at("2026-10-14T09:00:00Z")
+        );
+        assert_eq!(
+            last_send_time(&daily, at("2026-10-14T08:59:00Z")),
+            at("2026-10-13T09:00:00Z")
+        );
+
+        let weekly = report(ReportPeriod::Weekly, 9, ReportFormat::Json);
+        assert_eq!(
+            last_send_time(&weekly, at("2026-10-14T10:00:00Z")),
+            at("2026-10-12T09:00:00Z")
+        );
+        assert_eq!(
+            last_send_time(&weekly, at("2026-10-12T08:00:00Z")),
+            at("2026-10-05T09:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_payload_formats() {
+        let summary = UsageSummary {
+            requests: 3,
+            cost: 1.5,
+            by_model: vec![UsageBreakdown {
+                name: "gpt-4o".to_string(),
+                requests: 3,
+                tokens: 300,
+                cost: 1.5,
+            }],
+            ..Default::default()
+        };
+        let (start, end) = (at("2026-10-13T09:00:00Z"), at("2026-10-14T09:00:00Z"));
+
+        let slack = payload(
+            &report(ReportPeriod::Daily, 9, ReportFormat::Slack),
+            start,
+            end,
+            &summary,
+        );
+        let text = slack["text"].as_str().unwrap();
+        assert!(
+            text.contains("2026-10-13 09:00 to 2026-10-14 09:00 UTC"),
+            "{text}"
+        );
+        assert!(
+            text.contains("- gpt-4o: 3 requests, 300 tokens, $1.50"),
+            "{text}"
+        );
+        assert!(!text.contains("Top API keys"));
+
+        let discord = payload(
+            &report(ReportPeriod::Daily, 9, ReportFormat::Discord),
+            start,
+            end,
+            &summary,
+        );
+        assert_eq!(discord["content"], slack["text"]);
+
+        let json = payload(
+            &report(ReportPeriod::Daily, 9, ReportFormat::Json),
+            start,
+            end,
+            &summary,
+        );
+        assert_eq!(json["period"], "daily");
+        assert_eq!(json["usage"]["by_model"][0]["name"], "gpt-4o");
+    }
+}
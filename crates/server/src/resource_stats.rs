@@ -0,0 +1,219 @@
+//! Process resource usage for `GET /api/dashboard/system/health`: memory and file
+//! descriptors from the OS, plus inbound connection counts kept by the listener.
+
+use serde::Serialize;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Figures read from the OS. `None` where the platform does not expose them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProcessUsage {
+    pub rss_bytes: Option<u64>,
+    pub open_fds: Option<u64>,
+    pub threads: Option<u64>,
+}
+
+impl ProcessUsage {
+    pub fn current() -> Self {
+        platform::usage()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::ProcessUsage;
+
+    /// Reads `/proc/self/status` and counts `/proc/self/fd`.
+    pub fn usage() -> ProcessUsage {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .and_then(|rest| rest.split_whitespace().next())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        ProcessUsage {
+            rss_bytes: field("VmRSS:").map(|kib| kib * 1024),
+            open_fds: super::count_entries("/proc/self/fd"),
+            threads: field("Threads:"),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ProcessUsage;
+
+    /// macOS has no procfs; only the descriptor table is cheap to read.
+    pub fn usage() -> ProcessUsage {
+        ProcessUsage {
+            open_fds: super::count_entries("/dev/fd"),
+            ..ProcessUsage::default()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::ProcessUsage;
+
+    pub fn usage() -> ProcessUsage {
+        ProcessUsage::default()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn count_entries(dir: &str) -> Option<u64> {
+    // The directory handle used for listing shows up as one extra descriptor.
+    std::fs::read_dir(dir)
+        .ok()
+        .map(|entries| entries.count().saturating_sub(1) as u64)
+}
+
+/// Inbound client connections, counted by [`CountedListener`] and the TLS accept loop.
+#[derive(Debug, Default)]
+pub struct ConnectionCounter {
+    open: AtomicUsize,
+    accepted: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ConnectionCounts {
+    pub open: usize,
+    pub accepted_total: u64,
+}
+
+impl ConnectionCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a new connection until the returned guard is dropped.
+    pub fn open(self: &Arc<Self>) -> ConnectionGuard {
+        self.open.fetch_add(1, Ordering::Relaxed);
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(self.clone())
+    }
+
+    pub fn counts(&self) -> ConnectionCounts {
+        ConnectionCounts {
+            open: self.open.load(Ordering::Relaxed),
+            accepted_total: self.accepted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub struct ConnectionGuard(Arc<ConnectionCounter>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.open.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// TCP listener for `axum::serve` whose connections stay counted until closed.
+pub struct CountedListener {
+    inner: tokio::net::TcpListener,
+    counter: Arc<ConnectionCounter>,
+}
+
+impl CountedListener {
+    pub fn new(inner: tokio::net::TcpListener, counter: Arc<ConnectionCounter>) -> Self {
+        Self { inner, counter }
+    }
+}
+
+impl axum::serve::Listener for CountedListener {
+    type Io = CountedStream;
+    type Addr = std::net::SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, addr) = axum::serve::Listener::accept(&mut self.inner).await;
+        let stream = CountedStream {
+            inner: stream,
+            _guard: self.counter.open(),
+        };
+        (stream, addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+pub struct CountedStream {
+    inner: tokio::net::TcpStream,
+    _guard: ConnectionGuard,
+}
+
+impl AsyncRead for CountedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for CountedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_guard_tracks_open_connections() {
+        let counter = Arc::new(ConnectionCounter::new());
+        let first = counter.open();
+        let second = counter.open();
+        assert_eq!(counter.counts().open, 2);
+        drop(first);
+        drop(second);
+        let counts = counter.counts();
+        assert_eq!(counts.open, 0);
+        assert_eq!(counts.accepted_total, 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linux_usage_reads_procfs() {
+        let usage = ProcessUsage::current();
+        assert!(usage.rss_bytes.is_some_and(|rss| rss > 0));
+        assert!(usage.open_fds.is_some_and(|fds| fds >= 3));
+        assert!(usage.threads.is_some_and(|threads| threads >= 1));
+    }
+}
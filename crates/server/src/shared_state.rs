@@ -0,0 +1,319 @@
+//! Sharing of rate-limit usage, credential cooldowns, and quota counters
+//! between replicas through a Redis stream (`shared-state` in config).
+//!
+//! Every instance keeps enforcing limits from its own in-memory state. A
+//! background task appends local changes to `<key-prefix>events` and replays
+//! entries written by other instances, so each replica sees the fleet's usage
+//! within one sync interval. On its first sync a starting instance replays
+//! the last `retention-secs` of entries.
+//!
+//! Monthly budgets outlast any reasonable stream retention, so spend is also
+//! summed per UTC day in `<key-prefix>spend:<date>` hashes. Budgets are
+//! checked against those fleet totals as well as the replayed usage.
+//!
+//! API keys are shared as SHA-256 digests; each instance maps them back to
+//! the keys in its own `auth-keys`, so raw keys never reach Redis.
+
+use crate::AppState;
+use prism_core::config::{Config, SharedStateConfig};
+use prism_core::rate_limit::{SharedSpend, UsageDimension, UsageRecord};
+use prism_provider::router_state::RouterEvent;
+use redis::aio::ConnectionManager;
+use redis::streams::{StreamId, StreamReadReply};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Entries read per `XREAD` call.
+const READ_BATCH: usize = 500;
+
+/// Days of spend a monthly budget covers, today included.
+const BUDGET_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+enum SharedEvent {
+    Usage(UsageRecord),
+    Router(RouterEvent),
+}
+
+impl SharedEvent {
+    /// Stream field name and JSON payload.
+    fn encode(&self) -> Option<(&'static str, String)> {
+        match self {
+            Self::Usage(record) => Some(("usage", serde_json::to_string(record).ok()?)),
+            Self::Router(event) => Some(("router", serde_json::to_string(event).ok()?)),
+        }
+    }
+
+    /// The event in a stream entry, unless `instance` wrote it.
+    fn decode(entry: &StreamId, instance: &str) -> Option<Self> {
+        if entry.get::<String>("src").as_deref() == Some(instance) {
+            return None;
+        }
+        if let Some(json) = entry.get::<String>("usage") {
+            return serde_json::from_str(&json).ok().map(Self::Usage);
+        }
+        let json = entry.get::<String>("router")?;
+        serde_json::from_str(&json).ok().map(Self::Router)
+    }
+}
+
+/// Start sharing state when `shared-state.redis-url` is set. Settings are read
+/// once; changing them requires a restart.
+pub fn spawn(config: &SharedStateConfig, state: AppState) {
+    if !config.enabled() {
+        return;
+    }
+    let client = match redis::Client::open(config.redis_url.as_str()) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("shared-state disabled, invalid redis-url: {e}");
+            return;
+        }
+    };
+    let sync = StateSync {
+        stream_key: format!("{}events", config.key_prefix),
+        spend_prefix: format!("{}spend:", config.key_prefix),
+        instance: uuid::Uuid::new_v4().to_string(),
+        retention_ms: config.retention_secs.saturating_mul(1000),
+        last_id: String::new(),
+        keys_from: None,
+    };
+    let interval = Duration::from_millis(config.sync_interval_ms);
+    tokio::spawn(sync.run(client, state, interval));
+}
+
+struct StateSync {
+    stream_key: String,
+    spend_prefix: String,
+    instance: String,
+    retention_ms: u64,
+    /// Id of the last stream entry applied.
+    last_id: String,
+    /// Config the rate limiter's known keys were taken from.
+    keys_from: Option<Arc<Config>>,
+}
+
+impl StateSync {
+    async fn run(mut self, client: redis::Client, state: AppState, interval: Duration) {
+        let mut conn = loop {
+            match ConnectionManager::new(client.clone()).await {
+                Ok(conn) => break conn,
+                Err(e) => {
+                    tracing::warn!("shared-state: cannot connect to Redis, retrying: {e}");
+                    tokio::time::sleep(interval.max(Duration::from_secs(1))).await;
+                }
+            }
+        };
+        state.rate_limiter.share_usage();
+        state.router.share_events();
+        self.last_id = format!("{}-0", self.min_id_ms());
+        tracing::info!(stream = %self.stream_key, "Sharing rate-limit and credential state");
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.sync_once(&mut conn, &state).await {
+                tracing::warn!("shared-state sync failed: {e}");
+            }
+        }
+    }
+
+    /// Oldest entry id worth keeping, in Unix milliseconds.
+    fn min_id_ms(&self) -> i64 {
+        chrono::Utc::now().timestamp_millis() - self.retention_ms as i64
+    }
+
+    /// Publish local changes, then apply everything peers wrote since the last sync.
+    /// Local changes that fail to publish are dropped rather than retried.
+    async fn sync_once(
+        &mut self,
+        conn: &mut ConnectionManager,
+        state: &AppState,
+    ) -> redis::RedisResult<()> {
+        let outgoing: Vec<SharedEvent> = state
+            .rate_limiter
+            .take_shared_usage()
+            .into_iter()
+            .map(SharedEvent::Usage)
+            .chain(
+                state
+                    .router
+                    .take_shared_events()
+                    .into_iter()
+                    .map(SharedEvent::Router),
+            )
+            .collect();
+        if !outgoing.is_empty() {
+            let min_id = self.min_id_ms();
+            let mut pipe = redis::pipe();
+            for (field, json) in outgoing.iter().filter_map(SharedEvent::encode) {
+                pipe.cmd("XADD")
+                    .arg(&self.stream_key)
+                    .arg("MINID")
+                    .arg("~")
+                    .arg(min_id)
+                    .arg("*")
+                    .arg("src")
+                    .arg(&self.instance)
+                    .arg(field)
+                    .arg(json)
+                    .ignore();
+            }
+            for event in &outgoing {
+                let SharedEvent::Usage(UsageRecord {
+                    dimension: UsageDimension::Cost,
+                    key: Some(key),
+                    amount,
+                    at_ms,
+                }) = event
+                else {
+                    continue;
+                };
+                let Some(at) = chrono::DateTime::from_timestamp_millis(*at_ms) else {
+                    continue;
+                };
+                let bucket = spend_bucket(&self.spend_prefix, at.date_naive());
+                pipe.cmd("HINCRBYFLOAT")
+                    .arg(&bucket)
+                    .arg(key)
+                    .arg(*amount)
+                    .ignore();
+                pipe.cmd("EXPIRE")
+                    .arg(&bucket)
+                    .arg((BUDGET_DAYS + 1) * 86400)
+                    .ignore();
+            }
+            pipe.query_async::<()>(conn).await?;
+        }
+
+        let config = state.config.load_full();
+        if !self
+            .keys_from
+            .as_ref()
+            .is_some_and(|seen| Arc::ptr_eq(seen, &config))
+        {
+            state
+                .rate_limiter
+                .set_known_keys(config.auth_keys.iter().map(|entry| entry.key.as_str()));
+            self.keys_from = Some(config);
+        }
+
+        loop {
+            let reply: Option<StreamReadReply> = redis::cmd("XREAD")
+                .arg("COUNT")
+                .arg(READ_BATCH)
+                .arg("STREAMS")
+                .arg(&self.stream_key)
+                .arg(&self.last_id)
+                .query_async(conn)
+                .await?;
+            let entries: Vec<StreamId> = reply
+                .into_iter()
+                .flat_map(|r| r.keys)
+                .flat_map(|k| k.ids)
+                .collect();
+            let read = entries.len();
+            for entry in entries {
+                match SharedEvent::decode(&entry, &self.instance) {
+                    Some(SharedEvent::Usage(record)) => {
+                        state.rate_limiter.apply_shared_usage(&record)
+                    }
+                    Some(SharedEvent::Router(event)) => state.router.apply_shared_event(event),
+                    None => {}
+                }
+                self.last_id = entry.id;
+            }
+            if read < READ_BATCH {
+                break;
+            }
+        }
+
+        let today = chrono::Utc::now().date_naive();
+        let mut pipe = redis::pipe();
+        for days_ago in 0..BUDGET_DAYS {
+            let day = today - chrono::Days::new(days_ago);
+            pipe.cmd("HGETALL")
+                .arg(spend_bucket(&self.spend_prefix, day));
+        }
+        let days: Vec<HashMap<String, f64>> = pipe.query_async(conn).await?;
+        state.rate_limiter.set_shared_spend(spend_totals(days));
+        Ok(())
+    }
+}
+
+/// Hash holding the fleet's spend per API key digest on `day`.
+fn spend_bucket(prefix: &str, day: chrono::NaiveDate) -> String {
+    format!("{prefix}{}", day.format("%Y-%m-%d"))
+}
+
+/// Budget totals from the daily spend hashes, newest day first.
+fn spend_totals(days: Vec<HashMap<String, f64>>) -> SharedSpend {
+    let mut spend = SharedSpend::default();
+    for (i, day) in days.into_iter().enumerate() {
+        for (key, amount) in day {
+            if i == 0 {
+                spend.daily.insert(key.clone(), amount);
+            }
+            *spend.monthly.entry(key).or_default() += amount;
+        }
+    }
+    spend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::Value;
+
+    fn entry(fields: &[(&str, String)]) -> StreamId {
+        StreamId {
+            id: "1-0".to_string(),
+            map: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), Value::BulkString(v.clone().into_bytes())))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_spend_totals_by_budget_period() {
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        assert_eq!(spend_bucket("prism:spend:", day), "prism:spend:2026-03-09");
+
+        let spend = spend_totals(vec![
+            HashMap::from([("sk-1".to_string(), 1.5)]),
+            HashMap::new(),
+            HashMap::from([("sk-1".to_string(), 2.0), ("sk-2".to_string(), 4.0)]),
+        ]);
+        assert_eq!(spend.daily, HashMap::from([("sk-1".to_string(), 1.5)]));
+        assert_eq!(
+            spend.monthly,
+            HashMap::from([("sk-1".to_string(), 3.5), ("sk-2".to_string(), 4.0)])
+        );
+    }
+
+    #[test]
+    fn test_event_roundtrip_skips_own_entries() {
+        let event = SharedEvent::Usage(UsageRecord {
+            dimension: UsageDimension::Tokens,
+            key: Some("sk-1".to_string()),
+            amount: 42.0,
+            at_ms: 1_700_000_000_000,
+        });
+        let (field, json) = event.encode().unwrap();
+        let from_peer = entry(&[("src", "peer".to_string()), (field, json.clone())]);
+        assert_eq!(SharedEvent::decode(&from_peer, "me"), Some(event));
+        let own = entry(&[("src", "me".to_string()), (field, json)]);
+        assert_eq!(SharedEvent::decode(&own, "me"), None);
+
+        let event = SharedEvent::Router(RouterEvent::Cooldown {
+            credential: "openai/main".to_string(),
+            until: chrono::Utc::now(),
+        });
+        let (field, json) = event.encode().unwrap();
+        let from_peer = entry(&[("src", "peer".to_string()), (field, json)]);
+        assert_eq!(SharedEvent::decode(&from_peer, "me"), Some(event));
+    }
+}
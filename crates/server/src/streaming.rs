@@ -1,66 +1,551 @@
-use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use bytes::{BufMut, Bytes, BytesMut};
 use futures::Stream;
 use futures::stream::StreamExt;
+use prism_core::config::{SlowClientAction, StreamBufferConfig, StreamCoalesce};
 use prism_core::error::ProxyError;
+use prism_core::metrics::Metrics;
+use prism_core::provider::StreamChunk;
 use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use tokio::sync::Semaphore;
 
-/// Build an SSE response from a stream of data strings.
+/// Build an SSE response from a stream of chunks.
 ///
-/// Each string in the stream can be:
+/// A chunk with an `event_type` is sent as that event. Its data (or the whole
+/// data of an untyped chunk) is read line by line, and each line can be:
 /// - Plain JSON data (will be wrapped in `data: ...\n\n`)
 /// - `"[DONE]"` sentinel (emitted as `data: [DONE]\n\n`)
-/// - Multi-line with `event:` prefix for Claude SSE (e.g. `"event: message_start\ndata: {...}"`)
-/// - Empty string (skipped)
+/// - `event:` / `data:` pairs for Claude SSE (e.g. `"event: message_start\ndata: {...}"`)
+/// - Empty (skipped)
+///
+/// Frames are written straight into a reused buffer, so the chunk bytes are
+/// copied once on their way to the client. A `:` comment is sent after
+/// `keepalive_seconds` without output.
 pub fn build_sse_response(
-    data_stream: impl Stream<Item = Result<String, ProxyError>> + Send + 'static,
+    chunk_stream: impl Stream<Item = Result<StreamChunk, ProxyError>> + Send + 'static,
     keepalive_seconds: u64,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let stream = data_stream
-        .filter_map(|result| async move {
-            match result {
-                Ok(data) if data.is_empty() => None,
-                Ok(data) => Some(Ok(data)),
-                Err(e) => Some(Err(e)),
+) -> Response {
+    struct State<S> {
+        inner: std::pin::Pin<Box<S>>,
+        framer: SseFramer,
+        keepalive: Duration,
+    }
+
+    let state = State {
+        inner: Box::pin(chunk_stream),
+        framer: SseFramer::default(),
+        keepalive: Duration::from_secs(keepalive_seconds),
+    };
+    let stream = futures::stream::unfold(state, |mut state| async move {
+        loop {
+            let next = match state.keepalive {
+                Duration::ZERO => state.inner.next().await,
+                keepalive => match tokio::time::timeout(keepalive, state.inner.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        let comment = Bytes::from_static(b":\n\n");
+                        return Some((Ok::<_, Infallible>(comment), state));
+                    }
+                },
+            };
+            let frame = match next? {
+                Ok(chunk) => state.framer.chunk(&chunk),
+                Err(e) => state.framer.error(&e),
+            };
+            if let Some(frame) = frame {
+                return Some((Ok(frame), state));
             }
-        })
-        .flat_map(|result| {
-            let items: Vec<Result<Event, Infallible>> = match result {
-                Ok(data) => {
-                    // Split multi-line output into individual SSE events
-                    // Each line might be a JSON chunk or "[DONE]" or "event: ...\ndata: ..."
-                    let mut events = Vec::new();
-                    for line in data.split('\n') {
-                        let line = line.trim();
-                        if line.is_empty() {
-                            continue;
-                        }
-                        if line == "[DONE]" {
-                            events.push(Ok(Event::default().data("[DONE]")));
-                        } else if let Some(rest) = line.strip_prefix("event: ") {
-                            // This is an SSE event type line - create event with the type
-                            // The next line should be the data
-                            events.push(Ok(Event::default().event(rest)));
-                        } else if let Some(rest) = line.strip_prefix("data: ") {
-                            events.push(Ok(Event::default().data(rest)));
-                        } else {
-                            // Raw JSON data
-                            events.push(Ok(Event::default().data(line)));
+        }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/event-stream"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// Writes SSE frames into one buffer whose allocation is reclaimed once the
+/// client body has sent the previous frame.
+#[derive(Default)]
+pub struct SseFramer {
+    buf: BytesMut,
+}
+
+impl SseFramer {
+    /// Initial and reclaimed buffer capacity; most frames fit.
+    const CAPACITY: usize = 4 * 1024;
+
+    /// Frame one chunk; `None` when it carries nothing to send.
+    pub fn chunk(&mut self, chunk: &StreamChunk) -> Option<Bytes> {
+        let needed = chunk.data.len() + 64;
+        if self.buf.capacity() < needed {
+            self.buf.reserve(needed.max(Self::CAPACITY));
+        }
+        let mut open_event = false;
+        if let Some(ref event_type) = chunk.event_type {
+            self.event_line(event_type.as_bytes());
+            open_event = true;
+        }
+        for line in chunk.data.split(|&b| b == b'\n') {
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(event_type) = line.strip_prefix(b"event: ") {
+                if open_event {
+                    self.buf.extend_from_slice(b"\n");
+                }
+                self.event_line(event_type);
+                open_event = true;
+            } else {
+                let data = line.strip_prefix(b"data: ").unwrap_or(line);
+                self.buf.extend_from_slice(b"data: ");
+                self.buf.extend_from_slice(data);
+                self.buf.extend_from_slice(b"\n\n");
+                open_event = false;
+            }
+        }
+        if open_event {
+            self.buf.extend_from_slice(b"\n");
+        }
+        (!self.buf.is_empty()).then(|| self.buf.split().freeze())
+    }
+
+    /// Frame a mid-stream error as a data event.
+    pub fn error(&mut self, error: &ProxyError) -> Option<Bytes> {
+        let error_json = serde_json::json!({"error": {"message": error.to_string()}});
+        self.buf.extend_from_slice(b"data: ");
+        let _ = serde_json::to_writer((&mut self.buf).writer(), &error_json);
+        self.buf.extend_from_slice(b"\n\n");
+        Some(self.buf.split().freeze())
+    }
+
+    fn event_line(&mut self, event_type: &[u8]) {
+        self.buf.extend_from_slice(b"event: ");
+        self.buf.extend_from_slice(event_type.trim_ascii());
+        self.buf.extend_from_slice(b"\n");
+    }
+}
+
+/// Send an SSE response body in batches per a `streaming.coalesce` entry:
+/// output is held until `max_bytes` have built up or the oldest held byte has
+/// waited `max_delay_ms`. Without an entry the response is returned as is.
+pub fn coalesce_response(response: Response, coalesce: Option<&StreamCoalesce>) -> Response {
+    let Some(coalesce) = coalesce else {
+        return response;
+    };
+    struct State {
+        inner: axum::body::BodyDataStream,
+        buf: BytesMut,
+        deadline: Option<tokio::time::Instant>,
+        error: Option<axum::Error>,
+        done: bool,
+    }
+
+    let max_delay = Duration::from_millis(coalesce.max_delay_ms);
+    let max_bytes = coalesce.max_bytes;
+    let (parts, body) = response.into_parts();
+    let state = State {
+        inner: body.into_data_stream(),
+        buf: BytesMut::new(),
+        deadline: None,
+        error: None,
+        done: false,
+    };
+    let stream = futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if state.done {
+                if !state.buf.is_empty() {
+                    let batch = state.buf.split().freeze();
+                    return Some((Ok(batch), state));
+                }
+                let error = state.error.take()?;
+                return Some((Err(error), state));
+            }
+            let next = match state.deadline {
+                Some(deadline) => {
+                    match tokio::time::timeout_at(deadline, state.inner.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            state.deadline = None;
+                            let batch = state.buf.split().freeze();
+                            return Some((Ok(batch), state));
                         }
                     }
-                    events
                 }
-                Err(e) => {
-                    let error_json = serde_json::json!({"error": {"message": e.to_string()}});
-                    vec![Ok(Event::default().data(error_json.to_string()))]
+                None => state.inner.next().await,
+            };
+            match next {
+                Some(Ok(bytes)) => {
+                    if state.buf.is_empty() {
+                        state.deadline = Some(tokio::time::Instant::now() + max_delay);
+                    }
+                    state.buf.extend_from_slice(&bytes);
+                    if max_bytes > 0 && state.buf.len() >= max_bytes {
+                        state.deadline = None;
+                        let batch = state.buf.split().freeze();
+                        return Some((Ok(batch), state));
+                    }
+                }
+                Some(Err(e)) => {
+                    state.error = Some(e);
+                    state.done = true;
                 }
+                None => state.done = true,
+            }
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// Read a response body ahead of the client through a queue bounded to
+/// `high_water_mark_bytes`, per `streaming.buffer`. When a chunk does not fit,
+/// the upstream is paused (`backpressure`) or the response is aborted and the
+/// upstream dropped (`disconnect`); either is counted once per stream in `metrics`.
+pub fn buffer_response(
+    response: Response,
+    buffer: &StreamBufferConfig,
+    metrics: Arc<Metrics>,
+) -> Response {
+    let high_water_mark = buffer.high_water_mark_bytes.min(u32::MAX as usize);
+    if high_water_mark == 0 {
+        return response;
+    }
+    let on_full = buffer.on_full;
+    let (parts, body) = response.into_parts();
+    let mut inner = body.into_data_stream();
+    // Every queued chunk holds one permit per byte until the client takes it. A
+    // chunk larger than the whole queue needs all permits, so it waits for an
+    // empty queue instead of never fitting.
+    let queued = Arc::new(Semaphore::new(high_water_mark));
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let overflowed = Arc::new(AtomicBool::new(false));
+
+    let producer_overflowed = overflowed.clone();
+    tokio::spawn(async move {
+        let mut stalled = false;
+        loop {
+            let frame = tokio::select! {
+                frame = inner.next() => frame,
+                // The client went away; stop reading the upstream.
+                _ = tx.closed() => return,
+            };
+            let Some(frame) = frame else { return };
+            let bytes = frame.as_ref().map_or(0, Bytes::len).min(high_water_mark) as u32;
+            let permit = match queued.clone().try_acquire_many_owned(bytes) {
+                Ok(permit) => permit,
+                Err(_) => match on_full {
+                    SlowClientAction::Backpressure => {
+                        if !stalled {
+                            stalled = true;
+                            metrics.record_slow_client(false);
+                        }
+                        let permit = tokio::select! {
+                            permit = queued.clone().acquire_many_owned(bytes) => permit,
+                            _ = tx.closed() => return,
+                        };
+                        let Ok(permit) = permit else { return };
+                        permit
+                    }
+                    SlowClientAction::Disconnect => {
+                        metrics.record_slow_client(true);
+                        tracing::warn!(
+                            high_water_mark,
+                            "Slow client disconnected: stream buffer full"
+                        );
+                        producer_overflowed.store(true, Ordering::Release);
+                        return;
+                    }
+                },
             };
-            futures::stream::iter(items)
-        });
+            if tx.send((frame, permit)).is_err() {
+                return;
+            }
+        }
+    });
 
-    Sse::new(stream).keep_alive(
-        KeepAlive::new()
-            .interval(Duration::from_secs(keepalive_seconds))
-            .text(""),
-    )
+    let stream = futures::stream::unfold(Some(rx), move |rx| {
+        let overflowed = overflowed.clone();
+        async move {
+            let mut rx = rx?;
+            let next = rx.recv().await;
+            if overflowed.load(Ordering::Acquire) {
+                let error = axum::Error::new("client too slow: stream buffer full");
+                return Some((Err(error), None));
+            }
+            // Dropping the permit here frees the chunk's bytes in the queue.
+            next.map(|(frame, _permit)| (frame, Some(rx)))
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coalesce(max_delay_ms: u64, max_bytes: usize) -> StreamCoalesce {
+        StreamCoalesce {
+            max_delay_ms,
+            max_bytes,
+            ..StreamCoalesce::default()
+        }
+    }
+
+    async fn frames(response: Response) -> Vec<Bytes> {
+        response
+            .into_body()
+            .into_data_stream()
+            .map(|frame| frame.unwrap())
+            .collect()
+            .await
+    }
+
+    /// A body sending each part after its delay.
+    fn chatty_body(parts: &'static [(u64, &'static str)]) -> Response {
+        let stream =
+            futures::stream::iter(parts.iter().copied()).then(|(delay_ms, part)| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                Ok::<_, Infallible>(Bytes::from_static(part.as_bytes()))
+            });
+        Response::new(Body::from_stream(stream))
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_flushes_on_size() {
+        let parts = &[
+            (0, "data: a\n\n"),
+            (0, "data: b\n\n"),
+            (0, "data: c\n\n"),
+            (0, "data: d\n\n"),
+        ];
+        let response = chatty_body(parts);
+        let frames = frames(coalesce_response(response, Some(&coalesce(60_000, 18)))).await;
+        assert_eq!(
+            frames,
+            vec![
+                Bytes::from("data: a\n\ndata: b\n\n"),
+                Bytes::from("data: c\n\ndata: d\n\n"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_flushes_on_delay() {
+        // The pause after `b` outlasts the window, so `a` and `b` go out together.
+        let parts = &[(0, "data: a\n\n"), (0, "data: b\n\n"), (300, "data: c\n\n")];
+        let response = chatty_body(parts);
+        let frames = frames(coalesce_response(response, Some(&coalesce(50, 0)))).await;
+        assert_eq!(
+            frames,
+            vec![
+                Bytes::from("data: a\n\ndata: b\n\n"),
+                Bytes::from("data: c\n\n"),
+            ]
+        );
+    }
+
+    fn buffer(high_water_mark_bytes: usize, on_full: SlowClientAction) -> StreamBufferConfig {
+        StreamBufferConfig {
+            high_water_mark_bytes,
+            on_full,
+        }
+    }
+
+    /// A body of `count` chunks available immediately.
+    fn fast_body(count: usize) -> Response {
+        let stream = futures::stream::iter(
+            (0..count).map(|i| Ok::<_, Infallible>(Bytes::from(format!("data: {i}\n\n")))),
+        );
+        Response::new(Body::from_stream(stream))
+    }
+
+    #[tokio::test]
+    async fn test_buffer_backpressure_delivers_everything() {
+        let metrics = Arc::new(Metrics::new());
+        let response = buffer_response(
+            fast_body(20),
+            &buffer(20, SlowClientAction::Backpressure),
+            metrics.clone(),
+        );
+        let mut body = response.into_body().into_data_stream();
+        // Let the reader fill the queue before the client starts reading.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut received = 0;
+        while let Some(frame) = body.next().await {
+            frame.unwrap();
+            received += 1;
+        }
+        assert_eq!(received, 20);
+        assert_eq!(
+            metrics.stream_backpressure_stalls.load(Ordering::Relaxed),
+            1
+        );
+        assert_eq!(
+            metrics
+                .stream_slow_client_disconnects
+                .load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_disconnects_slow_client() {
+        let metrics = Arc::new(Metrics::new());
+        let response = buffer_response(
+            fast_body(20),
+            &buffer(20, SlowClientAction::Disconnect),
+            metrics.clone(),
+        );
+        let mut body = response.into_body().into_data_stream();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut received = 0;
+        let mut failed = false;
+        while let Some(frame) = body.next().await {
+            match frame {
+                Ok(_) => received += 1,
+                Err(_) => failed = true,
+            }
+        }
+        assert!(failed);
+        assert!(received < 20, "received {received}");
+        assert_eq!(
+            metrics
+                .stream_slow_client_disconnects
+                .load(Ordering::Relaxed),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_limit_counts_bytes_not_chunks() {
+        let large = |fill: u8| Ok::<_, Infallible>(Bytes::from(vec![fill; 100]));
+        let body = || Body::from_stream(futures::stream::iter([large(b'a'), large(b'b')]));
+
+        // Two chunks, but 200 bytes do not fit in 150.
+        let metrics = Arc::new(Metrics::new());
+        let response = buffer_response(
+            Response::new(body()),
+            &buffer(150, SlowClientAction::Disconnect),
+            metrics.clone(),
+        );
+        let mut stream = response.into_body().into_data_stream();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut failed = false;
+        while let Some(frame) = stream.next().await {
+            failed |= frame.is_err();
+        }
+        assert!(failed);
+        assert_eq!(
+            metrics
+                .stream_slow_client_disconnects
+                .load(Ordering::Relaxed),
+            1
+        );
+
+        let metrics = Arc::new(Metrics::new());
+        let response = buffer_response(
+            Response::new(body()),
+            &buffer(200, SlowClientAction::Disconnect),
+            metrics.clone(),
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(frames(response).await.len(), 2);
+        assert_eq!(
+            metrics
+                .stream_slow_client_disconnects
+                .load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_off_leaves_body_unchanged() {
+        let metrics = Arc::new(Metrics::new());
+        let response = buffer_response(
+            fast_body(3),
+            &StreamBufferConfig::default(),
+            metrics.clone(),
+        );
+        assert_eq!(frames(response).await.len(), 3);
+        assert_eq!(
+            metrics.stream_backpressure_stalls.load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    fn chunk(event_type: Option<&str>, data: &'static str) -> StreamChunk {
+        StreamChunk {
+            event_type: event_type.map(String::from),
+            data: Bytes::from_static(data.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_framer_writes_events_and_data_lines() {
+        let mut framer = SseFramer::default();
+        assert_eq!(
+            framer.chunk(&chunk(
+                Some("message_start"),
+                "{\"type\":\"message_start\"}"
+            )),
+            Some(Bytes::from(
+                "event: message_start\ndata: {\"type\":\"message_start\"}\n\n"
+            ))
+        );
+        assert_eq!(
+            framer.chunk(&chunk(
+                None,
+                "event: content_block_stop\ndata: {\"index\":0}\n{\"a\":1}\n[DONE]"
+            )),
+            Some(Bytes::from(
+                "event: content_block_stop\ndata: {\"index\":0}\n\ndata: {\"a\":1}\n\ndata: [DONE]\n\n"
+            ))
+        );
+        assert_eq!(framer.chunk(&chunk(None, "")), None);
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_frames_chunks_and_errors() {
+        let upstream = futures::stream::iter(vec![
+            Ok(chunk(None, "{\"a\":1}")),
+            Err(ProxyError::Internal("boom".to_string())),
+        ]);
+        let response = build_sse_response(upstream, 15);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let body = frames(response).await.concat();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "data: {\"a\":1}\n\ndata: {\"error\":{\"message\":\"internal error: boom\"}}\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_sends_keepalive_comment() {
+        let upstream = futures::stream::pending::<Result<StreamChunk, ProxyError>>();
+        let mut body = build_sse_response(upstream, 1)
+            .into_body()
+            .into_data_stream();
+        assert_eq!(body.next().await.unwrap().unwrap(), Bytes::from(":\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_none_leaves_body_unchanged() {
+        let parts = &[(0, "data: a\n\n"), (0, "data: b\n\n")];
+        let response = chatty_body(parts);
+        let frames = frames(coalesce_response(response, None)).await;
+        assert_eq!(frames.len(), 2);
+    }
 }
@@ -131,6 +131,7 @@ mod tests {
             let span = tracing::info_span!(
                 "gateway.request",
                 request_id = "test-req-1",
+                trace_id = "4bf92f3577b34da6a3ce929d0e0e4736",
                 method = "POST",
                 path = "/v1/chat/completions",
                 stream = false,
@@ -177,6 +178,10 @@ mod tests {
         assert_eq!(page.total, 1);
         let record = &page.data[0];
         assert_eq!(record.request_id, "test-req-1");
+        assert_eq!(
+            record.trace_id.as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
         assert_eq!(record.provider.as_deref(), Some("openai"));
         assert_eq!(record.model.as_deref(), Some("gpt-4"));
         assert_eq!(record.status, 200);
@@ -283,6 +288,61 @@ mod tests {
         assert!(record.attempts[1].error.is_none());
     }
 
+    #[tokio::test]
+    async fn test_slow_request_keeps_phase_timings() {
+        let logs: Arc<dyn LogStore> = Arc::new(InMemoryLogStore::new(100, None));
+        let layer = GatewayLogLayer::new(logs.clone());
+
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // Streams count latency to first response plus stream duration: 80 + 40 >= 100.
+        for (request_id, stream_ms) in [("fast-req", 10u64), ("slow-req", 40u64)] {
+            let request_span = tracing::info_span!(
+                "gateway.request",
+                request_id,
+                stream = true,
+                status = 200u64,
+                latency_ms = 80u64,
+                slow_threshold_ms = 100u64,
+                stream_ms = tracing::field::Empty,
+            );
+            for (attempt_index, ttfb) in [(0u64, 30u64), (1, 45)] {
+                let attempt_span = tracing::info_span!(
+                    parent: &request_span,
+                    "gateway.attempt",
+                    attempt_index,
+                    auth_ms = 5u64,
+                    translate_ms = tracing::field::Empty,
+                    upstream_ttfb_ms = tracing::field::Empty,
+                );
+                attempt_span.record("translate_ms", 2u64);
+                attempt_span.record("upstream_ttfb_ms", ttfb);
+            }
+            request_span.record("stream_ms", stream_ms);
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let fast = logs.get("fast-req").await.unwrap();
+        assert!(!fast.slow);
+        assert!(fast.timings.is_none());
+        assert_eq!(fast.attempts.len(), 2);
+        assert!(fast.attempts.iter().all(|a| a.timings.is_none()));
+
+        let slow = logs.get("slow-req").await.unwrap();
+        assert!(slow.slow);
+        let timings = slow.timings.unwrap();
+        assert_eq!(timings.auth_ms, Some(10));
+        assert_eq!(timings.translate_ms, Some(4));
+        assert_eq!(timings.upstream_ttfb_ms, Some(45));
+        assert_eq!(timings.stream_ms, Some(40));
+        assert_eq!(
+            slow.attempts[0].timings.as_ref().unwrap().upstream_ttfb_ms,
+            Some(30)
+        );
+    }
+
     #[tokio::test]
     async fn test_display_formatted_request_fields_are_recorded() {
         let logs: Arc<dyn LogStore> = Arc::new(InMemoryLogStore::new(100, None));
@@ -390,4 +450,35 @@ mod tests {
         assert_eq!(record.tenant_id, None);
         assert_eq!(record.client_region, None);
     }
+
+    #[tokio::test]
+    async fn test_key_and_provider_tags_merge_into_record() {
+        let logs: Arc<dyn LogStore> = Arc::new(InMemoryLogStore::new(100, None));
+        let layer = GatewayLogLayer::new(logs.clone());
+
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        {
+            let span = tracing::info_span!(
+                "gateway.request",
+                request_id = "tagged-req",
+                method = "POST",
+                path = "/v1/chat/completions",
+                stream = false,
+                status = 200u64,
+                key_tags = "team:ml,env:prod",
+                provider_tags = tracing::field::Empty,
+            );
+            let _enter = span.enter();
+            span.record("provider_tags", "env:prod,region:eu");
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let page = logs
+            .query(&prism_core::request_log::LogQuery::default())
+            .await;
+        assert_eq!(page.data[0].tags, ["team:ml", "env:prod", "region:eu"]);
+    }
 }
@@ -1,9 +1,10 @@
-use prism_core::request_record::{AttemptSummary, RequestRecord, TokenUsage};
+use prism_core::request_record::{AttemptSummary, PhaseTimings, RequestRecord, TokenUsage};
 
 /// Data collected from a `gateway.request` span during its lifetime.
 #[derive(Debug, Default)]
 pub struct RequestSpanData {
     pub request_id: String,
+    pub trace_id: Option<String>,
     pub method: String,
     pub path: String,
     pub stream: bool,
@@ -35,11 +36,23 @@ pub struct RequestSpanData {
     pub client_ip: Option<String>,
     pub client_region: Option<String>,
 
+    pub experiment: Option<String>,
+    pub experiment_arm: Option<String>,
+
+    /// Comma-separated tags of the API key and of the serving provider.
+    pub key_tags: Option<String>,
+    pub provider_tags: Option<String>,
+
     pub attempts: Vec<AttemptSummary>,
+
+    /// `log-store.slow-request-threshold-ms` at dispatch time (0 = off).
+    pub slow_threshold_ms: u64,
+    pub stream_ms: Option<u64>,
+    pub stream_truncated: Option<String>,
 }
 
 impl RequestSpanData {
-    pub fn into_request_record(self) -> RequestRecord {
+    pub fn into_request_record(mut self) -> RequestRecord {
         let usage = if self.usage_input.is_some() || self.usage_output.is_some() {
             Some(TokenUsage {
                 input_tokens: self.usage_input.unwrap_or(0),
@@ -51,8 +64,32 @@ impl RequestSpanData {
             None
         };
 
+        // Streams record latency at first response; the total includes the stream itself.
+        let total_ms = self.latency_ms + self.stream_ms.unwrap_or(0);
+        let slow = self.slow_threshold_ms > 0 && total_ms >= self.slow_threshold_ms;
+        let timings = if slow {
+            Some(self.phase_timings())
+        } else {
+            for attempt in &mut self.attempts {
+                attempt.timings = None;
+            }
+            None
+        };
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in [&self.key_tags, &self.provider_tags]
+            .into_iter()
+            .flatten()
+            .flat_map(|tags| tags.split(','))
+        {
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
         RequestRecord {
             request_id: self.request_id,
+            trace_id: self.trace_id,
             timestamp: chrono::Utc::now(),
             method: self.method,
             path: self.path,
@@ -76,7 +113,32 @@ impl RequestSpanData {
             tenant_id: self.tenant_id,
             client_ip: self.client_ip,
             client_region: self.client_region,
+            experiment: self.experiment,
+            experiment_arm: self.experiment_arm,
+            tags,
             attempts: self.attempts,
+            slow,
+            timings,
+            stream_truncated: self.stream_truncated,
+        }
+    }
+
+    /// Request-level breakdown: auth and translate summed across attempts, TTFB of
+    /// the last attempt that reached the upstream.
+    fn phase_timings(&self) -> PhaseTimings {
+        let attempt_timings = || self.attempts.iter().filter_map(|a| a.timings.as_ref());
+        let sum = |field: fn(&PhaseTimings) -> Option<u64>| {
+            attempt_timings()
+                .filter_map(field)
+                .reduce(|total, ms| total + ms)
+        };
+        PhaseTimings {
+            auth_ms: sum(|t| t.auth_ms),
+            translate_ms: sum(|t| t.translate_ms),
+            upstream_ttfb_ms: attempt_timings()
+                .filter_map(|t| t.upstream_ttfb_ms)
+                .next_back(),
+            stream_ms: self.stream_ms,
         }
     }
 }
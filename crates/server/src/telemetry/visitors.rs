@@ -1,5 +1,5 @@
 use super::span_data::RequestSpanData;
-use prism_core::request_record::AttemptSummary;
+use prism_core::request_record::{AttemptSummary, PhaseTimings};
 use tracing::field::{Field, Visit};
 
 /// Visitor for recording fields from a `gateway.request` span into `RequestSpanData`.
@@ -12,11 +12,13 @@ impl<'a> RequestSpanVisitor<'a> {
         Self { data }
     }
 
+    /// Store a non-empty value with credentials masked; bodies and errors end up
+    /// in the request log and file audit.
     fn set_optional_string(slot: &mut Option<String>, value: &str) {
         if value.is_empty() {
             *slot = None;
         } else {
-            *slot = Some(value.to_string());
+            *slot = Some(prism_core::redact::redact(value).into_owned());
         }
     }
 }
@@ -25,6 +27,7 @@ impl Visit for RequestSpanVisitor<'_> {
     fn record_str(&mut self, field: &Field, value: &str) {
         match field.name() {
             "request_id" => self.data.request_id = value.to_string(),
+            "trace_id" => Self::set_optional_string(&mut self.data.trace_id, value),
             "method" => self.data.method = value.to_string(),
             "path" => self.data.path = value.to_string(),
             "requested_model" => Self::set_optional_string(&mut self.data.requested_model, value),
@@ -45,6 +48,11 @@ impl Visit for RequestSpanVisitor<'_> {
             "tenant_id" => Self::set_optional_string(&mut self.data.tenant_id, value),
             "client_ip" => Self::set_optional_string(&mut self.data.client_ip, value),
             "client_region" => Self::set_optional_string(&mut self.data.client_region, value),
+            "experiment" => Self::set_optional_string(&mut self.data.experiment, value),
+            "experiment_arm" => Self::set_optional_string(&mut self.data.experiment_arm, value),
+            "key_tags" => Self::set_optional_string(&mut self.data.key_tags, value),
+            "provider_tags" => Self::set_optional_string(&mut self.data.provider_tags, value),
+            "stream_truncated" => Self::set_optional_string(&mut self.data.stream_truncated, value),
             _ => {}
         }
     }
@@ -58,6 +66,8 @@ impl Visit for RequestSpanVisitor<'_> {
             "usage_output" => self.data.usage_output = Some(value),
             "usage_cache_read" => self.data.usage_cache_read = Some(value),
             "usage_cache_creation" => self.data.usage_cache_creation = Some(value),
+            "slow_threshold_ms" => self.data.slow_threshold_ms = value,
+            "stream_ms" => self.data.stream_ms = Some(value),
             _ => {}
         }
     }
@@ -92,6 +102,7 @@ impl Visit for RequestSpanVisitor<'_> {
         let rendered = rendered.trim_matches('"');
         match field.name() {
             "request_id" => self.data.request_id = rendered.to_string(),
+            "trace_id" => Self::set_optional_string(&mut self.data.trace_id, rendered),
             "method" => self.data.method = rendered.to_string(),
             "path" => self.data.path = rendered.to_string(),
             "requested_model" => {
@@ -112,6 +123,13 @@ impl Visit for RequestSpanVisitor<'_> {
             "tenant_id" => Self::set_optional_string(&mut self.data.tenant_id, rendered),
             "client_ip" => Self::set_optional_string(&mut self.data.client_ip, rendered),
             "client_region" => Self::set_optional_string(&mut self.data.client_region, rendered),
+            "experiment" => Self::set_optional_string(&mut self.data.experiment, rendered),
+            "experiment_arm" => Self::set_optional_string(&mut self.data.experiment_arm, rendered),
+            "key_tags" => Self::set_optional_string(&mut self.data.key_tags, rendered),
+            "provider_tags" => Self::set_optional_string(&mut self.data.provider_tags, rendered),
+            "stream_truncated" => {
+                Self::set_optional_string(&mut self.data.stream_truncated, rendered)
+            }
             _ => {}
         }
     }
@@ -126,6 +144,10 @@ impl<'a> AttemptSpanVisitor<'a> {
     pub fn new(data: &'a mut AttemptSummary) -> Self {
         Self { data }
     }
+
+    fn timings(&mut self) -> &mut PhaseTimings {
+        self.data.timings.get_or_insert_default()
+    }
 }
 
 impl Visit for AttemptSpanVisitor<'_> {
@@ -134,7 +156,7 @@ impl Visit for AttemptSpanVisitor<'_> {
             "provider" => self.data.provider = value.to_string(),
             "model" => self.data.model = value.to_string(),
             "credential_name" => self.data.credential_name = Some(value.to_string()),
-            "error" => self.data.error = Some(value.to_string()),
+            "error" => self.data.error = Some(prism_core::redact::redact(value).into_owned()),
             "error_type" => self.data.error_type = Some(value.to_string()),
             _ => {}
         }
@@ -145,6 +167,9 @@ impl Visit for AttemptSpanVisitor<'_> {
             "attempt_index" => self.data.attempt_index = value as u32,
             "status" => self.data.status = Some(value as u16),
             "latency_ms" => self.data.latency_ms = value,
+            "auth_ms" => self.timings().auth_ms = Some(value),
+            "translate_ms" => self.timings().translate_ms = Some(value),
+            "upstream_ttfb_ms" => self.timings().upstream_ttfb_ms = Some(value),
             _ => {}
         }
     }
@@ -10,7 +10,7 @@ use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use chrono::{Duration as ChronoDuration, Utc};
 use prism_core::auth_key::AuthKeyEntry;
 use prism_core::auth_profile::{AuthMode, AuthProfileEntry};
-use prism_core::config::{Config, DashboardConfig};
+use prism_core::config::{Config, DashboardConfig, ReportFormat, UsageReportConfig};
 use prism_core::cost::CostCalculator;
 use prism_core::memory_log_store::InMemoryLogStore;
 use prism_core::metrics::Metrics;
@@ -18,12 +18,13 @@ use prism_core::provider::{Format, UpstreamKind, WireApi};
 use prism_core::rate_limit::CompositeRateLimiter;
 use prism_core::request_log::LogStore;
 use prism_core::request_record::{AttemptSummary, RequestRecord, TokenUsage};
-use prism_core::routing::config::{RouteMatch, RouteRule, RoutingConfig};
+use prism_core::routing::config::{ProviderStrategy, RouteMatch, RouteRule, RoutingConfig};
 use prism_provider::build_registry;
 use prism_provider::catalog::ProviderCatalog;
 use prism_provider::health::HealthManager;
 use prism_provider::routing::CredentialRouter;
 use prism_server::{AppState, build_router};
+use prism_test_support::{MockReply, MockUpstream, SseEvent};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -78,10 +79,11 @@ fn create_test_harness_with_auth_runtime(
     let config_arc = Arc::new(ArcSwap::new(Arc::new(config.clone())));
     let credential_router = Arc::new(CredentialRouter::new(Default::default()));
     credential_router.set_oauth_states(auth_runtime.oauth_snapshot());
+    credential_router.set_token_states(auth_runtime.tokens().states());
     credential_router.update_from_config(&config);
 
     let http_client_pool = Arc::new(prism_core::proxy::HttpClientPool::new());
-    let executors = Arc::new(build_registry(None, http_client_pool.clone()));
+    let executors = Arc::new(build_registry(http_client_pool.clone()));
     let translators = Arc::new(prism_translator::build_registry());
     let metrics = Arc::new(Metrics::new());
     let log_store: Arc<dyn LogStore> = Arc::new(InMemoryLogStore::new(1000, None));
@@ -109,6 +111,15 @@ fn create_test_harness_with_auth_runtime(
         oauth_sessions: Arc::new(dashmap::DashMap::new()),
         device_sessions: Arc::new(dashmap::DashMap::new()),
         provider_probe_cache: Arc::new(dashmap::DashMap::new()),
+        readiness: Arc::new(prism_server::readiness::Readiness::new()),
+        inflight: Arc::new(prism_server::inflight::InflightRegistry::new()),
+        load_shedder: Arc::new(prism_server::load_shed::LoadShedder::new()),
+        process_history: Arc::new(prism_server::process_history::ProcessHistory::new()),
+        crash_reporter: Arc::new(prism_server::crash_report::CrashReporter::default()),
+        connections: Arc::new(prism_server::resource_stats::ConnectionCounter::new()),
+        debug_traces: Arc::new(prism_server::debug_trace::DebugTraceStore::new()),
+        gemini_caches: Arc::new(dashmap::DashMap::new()),
+        log_filter: Arc::new(prism_lifecycle::logging::LogFilterHandle::detached("info")),
     };
 
     TestHarness {
@@ -129,6 +140,10 @@ fn reload_runtime_config(harness: &TestHarness) {
         .state
         .router
         .set_oauth_states(harness.state.auth_runtime.oauth_snapshot());
+    harness
+        .state
+        .router
+        .set_token_states(harness.state.auth_runtime.tokens().states());
     harness.state.router.update_from_config(&new_config);
     harness
         .state
@@ -557,11 +572,13 @@ fn authed_get(uri: &str, token: &str) -> Request<Body> {
         .unwrap()
 }
 
-/// Helper: build a POST request with JWT auth and JSON body.
+/// Helper: build a POST request with JWT auth and JSON body. Writes send
+/// `If-Match: *`; tests of version checks override it.
 fn authed_post(uri: &str, token: &str, body: Value) -> Request<Body> {
     Request::builder()
         .method("POST")
         .uri(uri)
+        .header("if-match", "*")
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {token}"))
         .body(Body::from(body.to_string()))
@@ -573,6 +590,7 @@ fn authed_patch(uri: &str, token: &str, body: Value) -> Request<Body> {
     Request::builder()
         .method("PATCH")
         .uri(uri)
+        .header("if-match", "*")
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {token}"))
         .body(Body::from(body.to_string()))
@@ -584,6 +602,7 @@ fn authed_delete(uri: &str, token: &str) -> Request<Body> {
     Request::builder()
         .method("DELETE")
         .uri(uri)
+        .header("if-match", "*")
         .header("authorization", format!("Bearer {token}"))
         .body(Body::empty())
         .unwrap()
@@ -594,6 +613,7 @@ fn authed_put(uri: &str, token: &str, body: Value) -> Request<Body> {
     Request::builder()
         .method("PUT")
         .uri(uri)
+        .header("if-match", "*")
         .header("content-type", "application/json")
         .header("authorization", format!("Bearer {token}"))
         .body(Body::from(body.to_string()))
@@ -744,283 +764,4588 @@ async fn test_login_with_dashboard_disabled() {
         .unwrap();
 
     let (status, _body) = send_request(&harness, req).await;
-    // Dashboard routes are not registered when disabled, so we get a plain 404
+    // Dashboard routes answer a plain 404 while disabled
     assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_token_refresh() {
+async fn test_body_limit_follows_live_config() {
     let harness = create_test_harness();
-    let token = login_and_get_token(&harness).await;
+    let router = build_router(harness.state.clone());
+    let oversized = vec![b' '; 2 * 1024 * 1024];
+    let chat_request = |body: Vec<u8>| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
 
-    let req = authed_post("/api/dashboard/auth/refresh", &token, json!({}));
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["authenticated"], true);
-    assert_eq!(body["username"], "admin");
-    assert_eq!(body["expires_in"], 3600);
+    // The default 10 MB limit accepts a 2 MB body (it fails later as invalid JSON).
+    let response = router
+        .clone()
+        .oneshot(chat_request(oversized.clone()))
+        .await
+        .unwrap();
+    assert_ne!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Lowering the limit takes effect on the already-built router.
+    let mut config = (*harness.state.config.load_full()).clone();
+    config.body_limit_mb = 1;
+    harness.state.config.store(Arc::new(config));
+
+    let response = router.oneshot(chat_request(oversized)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
 }
 
 #[tokio::test]
-async fn test_session_probe_without_dashboard_session_returns_unauthenticated() {
+async fn test_route_overrides_body_limit_and_rpm() {
     let harness = create_test_harness();
+    let mut config = (*harness.state.config.load_full()).clone();
+    config.body_limit_mb = 1;
+    config.routes = vec![
+        prism_core::config::RouteLimitConfig {
+            path: "/v1/chat/completions".to_string(),
+            body_limit_mb: Some(3),
+            rpm: Some(1),
+        },
+        prism_core::config::RouteLimitConfig {
+            path: "/v1/messages*".to_string(),
+            body_limit_mb: None,
+            rpm: None,
+        },
+    ];
+    harness.state.config.store(Arc::new(config));
+    let router = build_router(harness.state.clone());
+    let post = |uri: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(vec![b' '; 2 * 1024 * 1024]))
+            .unwrap()
+    };
 
-    let req = Request::builder()
-        .method("GET")
-        .uri("/api/dashboard/auth/session")
-        .body(Body::empty())
+    // The chat override raises the body limit above the global 1 MB...
+    let response = router
+        .clone()
+        .oneshot(post("/v1/chat/completions"))
+        .await
+        .unwrap();
+    assert_ne!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    // ...and caps the route at one request per minute.
+    let response = router
+        .clone()
+        .oneshot(post("/v1/chat/completions"))
+        .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
 
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["authenticated"], false);
-    assert!(body["username"].is_null());
+    // A matching entry without a body override keeps the global limit.
+    let response = router.oneshot(post("/v1/messages")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
 }
 
 #[tokio::test]
-async fn test_session_probe_with_valid_token_returns_authenticated() {
+async fn test_route_rpm_counts_only_requests_admitted_by_global_limits() {
     let harness = create_test_harness();
-    let token = login_and_get_token(&harness).await;
+    let mut config = (*harness.state.config.load_full()).clone();
+    config.rate_limit.enabled = true;
+    config.rate_limit.global_rpm = 1;
+    config.routes = vec![prism_core::config::RouteLimitConfig {
+        path: "/v1/chat/completions".to_string(),
+        body_limit_mb: None,
+        rpm: Some(2),
+    }];
+    harness.state.rate_limiter.update_config(&config.rate_limit);
+    harness.state.config.store(Arc::new(config));
+    let router = build_router(harness.state.clone());
+    let post = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap()
+    };
 
-    let req = authed_get("/api/dashboard/auth/session", &token);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["authenticated"], true);
-    assert_eq!(body["username"], "admin");
+    let response = router.clone().oneshot(post()).await.unwrap();
+    assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let response = router.oneshot(post()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&body).contains("Route rate limit"));
+
+    // Only the admitted request used the route's window.
+    let route = harness
+        .state
+        .rate_limiter
+        .check_route("/v1/chat/completions", 2);
+    assert!(route.allowed);
+    assert_eq!(route.remaining, 1);
 }
 
-// ===========================================================================
-// Provider CRUD tests
-// ===========================================================================
+#[tokio::test]
+async fn test_dashboard_enabled_follows_live_config() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let router = build_router(harness.state.clone());
+
+    let mut config = (*harness.state.config.load_full()).clone();
+    config.dashboard.enabled = false;
+    harness.state.config.store(Arc::new(config));
+
+    let response = router
+        .oneshot(authed_get("/api/dashboard/providers", &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
 
 #[tokio::test]
-async fn test_list_providers_empty() {
+async fn test_latency_metrics_by_model() {
     let harness = create_test_harness();
     let token = login_and_get_token(&harness).await;
+    harness
+        .state
+        .metrics
+        .record_target_latency_ms("gpt-4o", "openai", 300);
 
-    let req = authed_get("/api/dashboard/providers", &token);
+    let req = authed_get("/api/dashboard/metrics/latency", &token);
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK);
-    let providers = body["providers"]
-        .as_array()
-        .expect("providers should be array");
+    assert_eq!(body["by_model"]["gpt-4o"]["count"], 1);
+    assert_eq!(body["by_provider"]["openai"]["buckets"]["<=500"], 1);
+
+    let req = authed_get("/api/dashboard/metrics/latency?model=gpt-4o", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["model"], "gpt-4o");
+    assert_eq!(body["latency_ms"]["sum"], 300);
+
+    let req = authed_get("/api/dashboard/metrics/latency?model=unknown", &token);
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_ready_waits_for_listener_and_credential_probe() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/", get(|| async { StatusCode::NOT_FOUND }));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let ready = || {
+        Request::builder()
+            .uri("/ready")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let (status, body) = send_request(&harness, ready()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["listener"], "pending");
+
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.readiness.probe_credentials = true;
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-probe",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    harness.state.readiness.mark_listening();
+    prism_server::readiness::run_self_test(&harness.state).await;
+    let (status, body) = send_request(&harness, ready()).await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["checks"]["credential_probe"]["status"], "passed");
+}
+
+#[tokio::test]
+async fn test_ready_reports_warm_up_results() {
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_url = format!("http://{}", closed.local_addr().unwrap());
+    drop(closed);
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.readiness.warm_up = true;
+    config.readiness.probe_timeout_secs = 2;
+    config.providers = [
+        ("openai-warm", upstream.base_url()),
+        ("openai-down", &closed_url),
+    ]
+    .into_iter()
+    .map(|(name, base_url)| {
+        provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some(base_url),
+            region: None,
+        })
+    })
+    .collect();
+    write_test_config(&harness, &config);
+
+    let ready = || {
+        Request::builder()
+            .uri("/ready")
+            .body(Body::empty())
+            .unwrap()
+    };
+    harness.state.readiness.mark_listening();
+    let (status, body) = send_request(&harness, ready()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["warm_up"]["status"], "pending");
+
+    prism_server::readiness::run_self_test(&harness.state).await;
+    let (status, body) = send_request(&harness, ready()).await;
+    // A credential that cannot be warmed is reported but does not block readiness.
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    let warm_up = &body["checks"]["warm_up"];
+    assert_eq!(warm_up["status"], "finished");
+    assert_eq!(warm_up["warmed"], 1);
+    assert_eq!(warm_up["failed"].as_array().unwrap().len(), 1);
     assert!(
-        providers.is_empty(),
-        "initially there should be no providers"
+        warm_up["failed"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("openai-down"),
+        "{warm_up}"
+    );
+    assert_eq!(upstream.request_count(), 1);
+    // Warm-up requests carry no credentials.
+    assert!(
+        !upstream
+            .last_request()
+            .headers
+            .contains_key("authorization")
     );
 }
 
 #[tokio::test]
-async fn test_create_provider_and_list() {
+async fn test_verbose_health_reports_dependency_checks() {
     let harness = create_test_harness();
-    let token = login_and_get_token(&harness).await;
+    let health = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
 
-    // Create a provider
-    let create_body = json!({
-        "format": "openai",
-        "api_key": "sk-test-key-1234567890abcdef",
-        "name": "Test OpenAI"
-    });
-    let req = authed_post("/api/dashboard/providers", &token, create_body);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::CREATED, "create failed: {body:?}");
+    let (status, body) = send_request(&harness, health("/health")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    assert!(body.get("checks").is_none());
 
-    // Reload config into state so list sees the new provider
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = ["http://127.0.0.1:9", "http://prism-health.invalid"]
+        .iter()
+        .enumerate()
+        .map(|(i, base_url)| {
+            provider_entry(ProviderFixture {
+                name: if i == 0 { "local" } else { "unresolvable" },
+                format: Format::OpenAI,
+                upstream: None,
+                wire_api: WireApi::Chat,
+                models: &["gpt-4o"],
+                auth_profiles: Vec::new(),
+                api_key: "sk-test",
+                base_url: Some(base_url),
+                region: None,
+            })
+        })
+        .collect();
+    write_test_config(&harness, &config);
 
-    // List providers
-    let req = authed_get("/api/dashboard/providers", &token);
+    let (status, body) = send_request(&harness, health("/health?verbose=1")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{body:?}");
+    assert!(body.get("checks").is_none());
+
+    let token = login_and_get_token(&harness).await;
+    let (status, body) = send_request(&harness, authed_get("/health?verbose=1", &token)).await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["checks"]["config_file"]["status"], "ok");
+    assert!(body["checks"]["config_file"].get("path").is_none());
+    assert_eq!(body["checks"]["log_dir"]["status"], "skipped");
+    assert_eq!(body["checks"]["upstream_dns"]["status"], "degraded");
+    assert_eq!(body["checks"]["upstream_dns"]["hosts"]["127.0.0.1"], "ok");
+}
+
+#[tokio::test]
+async fn test_metrics_reset_returns_previous_snapshot() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    harness.state.metrics.record_request("gpt-4o", "openai");
+
+    let req = authed_post("/api/dashboard/metrics/reset", &token, json!({}));
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK);
-    let providers = body["providers"].as_array().unwrap();
-    assert_eq!(providers.len(), 1);
-    assert_eq!(providers[0]["format"], "openai");
-    assert_eq!(providers[0]["name"], "Test OpenAI");
-    // API key should be masked
-    let masked = providers[0]["api_key_masked"].as_str().unwrap();
-    assert!(masked.contains("****"), "API key should be masked");
-    assert!(
-        !masked.contains("sk-test-key-1234567890abcdef"),
-        "full key should not appear"
+    assert_eq!(body["previous"]["total_requests"], 1);
+    assert_eq!(harness.state.metrics.snapshot()["total_requests"], 0);
+
+    let req = authed_post(
+        "/api/dashboard/metrics/reset/credentials/missing",
+        &token,
+        json!({}),
     );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_get_provider_by_name() {
+async fn test_provider_detail_reports_stream_throughput() {
     let harness = create_test_harness();
     let token = login_and_get_token(&harness).await;
+    let req = authed_post(
+        "/api/dashboard/providers",
+        &token,
+        json!({"name": "fast", "format": "openai", "api_key": "sk-test"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {body:?}");
 
-    // Create a provider
-    let create_body = json!({
-        "format": "claude",
-        "api_key": "sk-ant-REDACTED",
-        "name": "Test Claude Provider",
-        "base_url": "https://api.anthropic.com"
-    });
-    let req = authed_post("/api/dashboard/providers", &token, create_body);
-    let (status, _) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::CREATED);
-
-    // Reload config
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let req = authed_get("/api/dashboard/providers/fast", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert!(body["throughput"].is_null());
 
-    // Get the provider by name
-    let req = authed_get("/api/dashboard/providers/Test%20Claude%20Provider", &token);
+    harness
+        .state
+        .metrics
+        .record_stream_throughput("fast", 300, std::time::Duration::from_secs(3));
+    let req = authed_get("/api/dashboard/providers/fast", &token);
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["format"], "claude");
-    assert_eq!(body["name"], "Test Claude Provider");
-    assert_eq!(body["base_url"], "https://api.anthropic.com");
-    // API key should be masked
-    let masked = body["api_key_masked"].as_str().unwrap();
-    assert!(masked.contains("****"));
+    assert_eq!(body["throughput"]["streams"], 1);
+    assert_eq!(body["throughput"]["tokens_per_sec"], 100.0);
 }
 
 #[tokio::test]
-async fn test_get_provider_not_found() {
+async fn test_claude_server_tools_rejected_when_target_cannot_run_them() {
     let harness = create_test_harness();
-    let token = login_and_get_token(&harness).await;
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut claude = provider_entry(ProviderFixture {
+        name: "claude-restricted",
+        format: Format::Claude,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["claude-opus"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-ant-test",
+        base_url: Some("http://127.0.0.1:9"),
+        region: None,
+    });
+    claude.allowed_server_tools = Some(vec!["computer_*".to_string()]);
+    config.providers = vec![
+        provider_entry(ProviderFixture {
+            name: "openai-only",
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        }),
+        claude,
+    ];
+    write_test_config(&harness, &config);
+
+    let messages = |model: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/messages")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "model": model,
+                    "max_tokens": 64,
+                    "messages": [{"role": "user", "content": "news today?"}],
+                    "tools": [{"type": "web_search_20250305", "name": "web_search"}]
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
 
-    let req = authed_get("/api/dashboard/providers/nonexistent-provider", &token);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::NOT_FOUND);
-    assert_eq!(body["error"], "not_found");
+    let (status, body) = send_request(&harness, messages("gpt-4o")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+    assert!(
+        body.to_string()
+            .contains("not supported by openai providers")
+    );
+
+    let (status, body) = send_request(&harness, messages("claude-opus")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+    assert!(
+        body.to_string()
+            .contains("not allowed for provider 'claude-restricted'")
+    );
 }
 
 #[tokio::test]
-async fn test_update_provider() {
+async fn test_moderation_block_is_counted_as_error() {
+    async fn moderations() -> Json<Value> {
+        Json(json!({"results": [{"flagged": true, "categories": {"violence": true}}]}))
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1/moderations", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/v1/moderations", post(moderations));
+        axum::serve(listener, app).await.unwrap();
+    });
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+
     let harness = create_test_harness();
-    let token = login_and_get_token(&harness).await;
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.moderation.enabled = true;
+    config.moderation.url = url;
+    config.moderation.fail_open = false;
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
 
-    // Create a provider
-    let create_body = json!({
-        "format": "openai",
-        "api_key": "sk-test-key-1234567890abcdef",
-        "name": "Original Name"
+    let (status, body) = send_request(
+        &harness,
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert!(status.is_client_error(), "{status} {body}");
+    assert_eq!(upstream.request_count(), 0);
+
+    let snap = harness.state.metrics.snapshot();
+    assert_eq!(snap["moderation"]["blocked"], 1);
+    assert_eq!(snap["total_errors"], 1);
+}
+
+#[tokio::test]
+async fn test_moderations_proxied_to_openai_provider() {
+    async fn moderations(body: String) -> (StatusCode, Json<Value>) {
+        let request: Value = serde_json::from_str(&body).unwrap();
+        (
+            StatusCode::OK,
+            Json(json!({
+                "id": "modr-1",
+                "model": request["model"].as_str().unwrap_or("omni-moderation-latest"),
+                "results": [{"flagged": false, "categories": {}}]
+            })),
+        )
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/v1/moderations", post(moderations));
+        axum::serve(listener, app).await.unwrap();
     });
-    let req = authed_post("/api/dashboard/providers", &token, create_body);
-    let (status, _) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::CREATED);
 
-    // Reload config
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![
+        provider_entry(ProviderFixture {
+            name: "claude-only",
+            format: Format::Claude,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &[],
+            auth_profiles: Vec::new(),
+            api_key: "sk-ant-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        }),
+        provider_entry(ProviderFixture {
+            name: "openai-mod",
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["omni-moderation-*"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some(&base_url),
+            region: None,
+        }),
+    ];
+    write_test_config(&harness, &config);
+
+    let moderation = |body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/moderations")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let (status, body) = send_request(&harness, moderation(json!({"input": "hello"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["id"], "modr-1");
+    assert_eq!(body["results"][0]["flagged"], false);
+
+    let (status, body) = send_request(
+        &harness,
+        moderation(json!({"model": "omni-moderation-2024-09-26", "input": ["a", "b"]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["model"], "omni-moderation-2024-09-26");
+
+    let (status, _) = send_request(&harness, moderation(json!({"model": "x"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_openai_stream_usage_chunk_only_when_client_requests_it() {
+    async fn chat_completions(body: String) -> axum::response::Response {
+        use axum::response::IntoResponse;
+        let request: Value = serde_json::from_str(&body).unwrap();
+        // The proxy always asks for usage so it can account for the request.
+        assert_eq!(request["stream_options"]["include_usage"], true);
+        let sse = concat!(
+            "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"hi\"},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+            "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-4o\",\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":1,\"total_tokens\":4}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        ([("content-type", "text/event-stream")], sse).into_response()
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-usage",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let stream_events = |body: Value| {
+        let state = harness.state.clone();
+        async move {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let response = build_router(state).oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            String::from_utf8(body.to_vec())
+                .unwrap()
+                .lines()
+                .filter_map(|line| line.strip_prefix("data: ").map(str::to_string))
+                .collect::<Vec<_>>()
+        }
+    };
+    let messages = json!([{"role": "user", "content": "hi"}]);
+
+    let events =
+        stream_events(json!({"model": "gpt-4o", "stream": true, "messages": messages})).await;
+    assert_eq!(events.len(), 2, "{events:?}");
+    assert!(!events[0].contains("total_tokens"));
+    assert_eq!(events[1], "[DONE]");
+
+    let events = stream_events(json!({
+        "model": "gpt-4o",
+        "stream": true,
+        "stream_options": {"include_usage": true},
+        "messages": messages,
+    }))
+    .await;
+    assert_eq!(events.len(), 3, "{events:?}");
+    let usage: Value = serde_json::from_str(&events[1]).unwrap();
+    assert_eq!(usage["choices"], json!([]));
+    assert_eq!(usage["usage"]["total_tokens"], 4);
+}
+
+#[tokio::test]
+async fn test_concurrency_caps_shed_with_retry_after() {
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+    // One chunk, then the stream stays open until the client goes away.
+    upstream.set_fallback(
+        MockReply::sse(vec![SseEvent::data(
+            json!({"id": "c1", "object": "chat.completion.chunk", "model": "gpt-4o", "choices": [{"index": 0, "delta": {"content": "hi"}, "finish_reason": null}]})
+                .to_string(),
+        )])
+        .stall(),
+    );
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-slow",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    config.concurrency.max_streams = 1;
+    write_test_config(&harness, &config);
+
+    let stream_request = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "stream": true, "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+    let held = build_router(harness.state.clone())
+        .oneshot(stream_request())
+        .await
+        .unwrap();
+    assert_eq!(held.status(), StatusCode::OK);
+
+    let shed = build_router(harness.state.clone())
+        .oneshot(stream_request())
+        .await
+        .unwrap();
+    assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(shed.headers()["retry-after"], "1");
+    let body = axum::body::to_bytes(shed.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "overloaded");
+
+    // The open stream also holds a request slot.
+    config.concurrency.max_requests = 1;
+    write_test_config(&harness, &config);
+    let (status, _) = send_request(
+        &harness,
+        Request::builder()
+            .uri("/v1/models")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let snapshot = harness.state.metrics.snapshot();
+    assert_eq!(snapshot["load_shed"]["streams"], 1);
+    assert_eq!(snapshot["load_shed"]["requests"], 1);
+
+    drop(held);
+    assert_eq!(harness.state.load_shedder.streams(), 0);
+    assert_eq!(harness.state.load_shedder.requests(), 0);
+    let resumed = build_router(harness.state.clone())
+        .oneshot(stream_request())
+        .await
+        .unwrap();
+    assert_eq!(resumed.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_upstream_429_passes_rate_limit_headers_through() {
+    let upstream = MockUpstream::start(Format::Claude).await;
+    upstream.set_fallback(
+        MockReply::error(Format::Claude, 429, "slow down")
+            .with_header("anthropic-ratelimit-requests-remaining", "0")
+            .with_header("anthropic-ratelimit-requests-limit", "50")
+            .with_header("x-upstream-internal", "hidden"),
+    );
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.retry.max_retries = 0;
+    config.retry.max_backoff_secs = 0;
+    config.passthrough_headers = vec!["rate-limits".to_string()];
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "claude-limited",
+        format: Format::Claude,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["claude-sonnet-4-5"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let response = build_router(harness.state.clone())
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "model": "claude-sonnet-4-5",
+                        "messages": [{"role": "user", "content": "hi"}],
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let headers = response.headers();
+    assert_eq!(headers["x-ratelimit-remaining-requests"], "0");
+    assert_eq!(headers["x-ratelimit-limit-requests"], "50");
+    assert!(headers.contains_key("retry-after"));
+    assert!(!headers.contains_key("anthropic-ratelimit-requests-remaining"));
+    assert!(!headers.contains_key("x-upstream-internal"));
+}
+
+#[tokio::test]
+async fn test_mock_upstream_fails_over_after_server_error() {
+    let failing = MockUpstream::start(Format::OpenAI).await;
+    failing.set_fallback(MockReply::error(Format::OpenAI, 500, "upstream exploded"));
+    let healthy = MockUpstream::start(Format::OpenAI).await;
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.retry.max_backoff_secs = 0;
+    config.providers = [("openai-failing", &failing), ("openai-healthy", &healthy)]
+        .into_iter()
+        .map(|(name, upstream)| {
+            provider_entry(ProviderFixture {
+                name,
+                format: Format::OpenAI,
+                upstream: None,
+                wire_api: WireApi::Chat,
+                models: &["gpt-4o"],
+                auth_profiles: Vec::new(),
+                api_key: "sk-test",
+                base_url: Some(upstream.base_url()),
+                region: None,
+            })
+        })
+        .collect();
+    write_test_config(&harness, &config);
+
+    let (status, body) = send_request(
+        &harness,
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["choices"][0]["message"]["content"], "ok");
+    assert_eq!(healthy.request_count(), 1);
+    assert!(failing.request_count() <= 1);
+    let sent = healthy.last_request();
+    assert_eq!(sent.path, "/v1/chat/completions");
+    assert_eq!(sent.headers["authorization"], "Bearer sk-test");
+}
+
+#[tokio::test]
+async fn test_failed_request_counts_under_provider_tags() {
+    let failing = MockUpstream::start(Format::OpenAI).await;
+    failing.set_fallback(MockReply::error(Format::OpenAI, 500, "upstream exploded"));
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.retry.max_retries = 0;
+    config.retry.max_backoff_secs = 0;
+    let mut provider = provider_entry(ProviderFixture {
+        name: "openai-tagged",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(failing.base_url()),
+        region: None,
+    });
+    provider.tags = vec!["team:ml".to_string()];
+    config.providers = vec![provider];
+    write_test_config(&harness, &config);
+
+    let (status, _) = send_request(
+        &harness,
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let snap = harness.state.metrics.snapshot();
+    assert_eq!(snap["by_tag"]["team:ml"]["requests"], 1);
+    assert_eq!(snap["by_tag"]["team:ml"]["errors"], 1);
+}
+
+#[tokio::test]
+async fn test_fault_injection_errors_fail_over_to_other_provider() {
+    let faulty = MockUpstream::start(Format::OpenAI).await;
+    let healthy = MockUpstream::start(Format::OpenAI).await;
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.retry.max_backoff_secs = 0;
+    config.fault_injection.enabled = true;
+    config.fault_injection.providers = vec!["openai-faulty".to_string()];
+    config.fault_injection.error_rate = 1.0;
+    config.fault_injection.error_statuses = vec![500];
+    config.providers = [("openai-faulty", &faulty), ("openai-healthy", &healthy)]
+        .into_iter()
+        .map(|(name, upstream)| {
+            provider_entry(ProviderFixture {
+                name,
+                format: Format::OpenAI,
+                upstream: None,
+                wire_api: WireApi::Chat,
+                models: &["gpt-4o"],
+                auth_profiles: Vec::new(),
+                api_key: "sk-test",
+                base_url: Some(upstream.base_url()),
+                region: None,
+            })
+        })
+        .collect();
+    write_test_config(&harness, &config);
+    harness
+        .state
+        .http_client_pool
+        .set_fault_injection(config.fault_injection.clone());
+
+    for _ in 0..3 {
+        let (status, body) = send_request(
+            &harness,
+            Request::builder()
+                .method("POST")
+                .uri("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+    // Injected errors fail before anything is sent.
+    assert_eq!(faulty.request_count(), 0);
+    assert_eq!(healthy.request_count(), 3);
+}
+
+#[tokio::test]
+async fn test_signing_adds_hmac_over_sent_body() {
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "internal-gateway",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    });
+    let signing: prism_core::signing::RequestSigningConfig = serde_json::from_value(json!({
+        "secret": "gateway-key",
+        "header": "x-gateway-signature",
+        "timestamp-header": "x-gateway-timestamp",
+        "string-to-sign": "{method}\n{path}\n{timestamp}\n{body}"
+    }))
+    .unwrap();
+    entry.signing = Some(signing.clone());
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+
+    let (status, body) = send_request(
+        &harness,
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let sent = upstream.last_request();
+    let timestamp: i64 = sent.headers["x-gateway-timestamp"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let expected = signing.sign(&prism_core::signing::SigningInput {
+        method: "POST",
+        path: "/v1/chat/completions",
+        body: &sent.body,
+        timestamp,
+    });
+    assert_eq!(sent.headers["x-gateway-signature"], expected[0].1.as_str());
+    assert_eq!(sent.headers["authorization"], "Bearer sk-test");
+}
+
+#[tokio::test]
+async fn test_token_source_supplies_refreshed_vertex_bearer_token() {
+    let metadata = MockUpstream::start(Format::OpenAI).await;
+    metadata.push(MockReply::json(
+        json!({"access_token": "ya29.first", "expires_in": 3599, "token_type": "Bearer"}),
+    ));
+    let upstream = MockUpstream::start(Format::Gemini).await;
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "vertex",
+        format: Format::Gemini,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gemini-2.5-pro"],
+        auth_profiles: Vec::new(),
+        api_key: "",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    });
+    entry.vertex = true;
+    entry.vertex_project = Some("my-project".to_string());
+    entry.vertex_location = Some("us-central1".to_string());
+    entry.token_source = Some(
+        serde_json::from_value(json!({
+            "type": "gcp-metadata",
+            "metadata-url": metadata.base_url(),
+            "scopes": ["https://www.googleapis.com/auth/cloud-platform"]
+        }))
+        .unwrap(),
+    );
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+    harness.state.auth_runtime.tokens().refresh_due().await;
+
+    let fetch = metadata.last_request();
+    assert_eq!(fetch.headers["metadata-flavor"], "Google");
+    assert!(
+        fetch.path.starts_with(
+            "/computeMetadata/v1/instance/service-accounts/default/token?scopes=https"
+        ),
+        "{}",
+        fetch.path
+    );
+
+    let (status, body) = send_request(
+        &harness,
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gemini-2.5-pro", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let sent = upstream.last_request();
+    assert_eq!(sent.headers["authorization"], "Bearer ya29.first");
+    assert!(
+        sent.path
+            .contains("/projects/my-project/locations/us-central1/")
+    );
+
+    let status = harness.state.auth_runtime.tokens().status();
+    assert_eq!(status.len(), 1);
+    assert_eq!(status[0].credential, "vertex/vertex");
+    assert_eq!(status[0].consecutive_failures, 0);
+    assert!(status[0].expires_at.is_some());
+}
+
+#[tokio::test]
+async fn test_mock_upstream_claude_stream_translated_to_openai() {
+    let upstream = MockUpstream::start(Format::Claude).await;
+    upstream.push(MockReply::text_stream(Format::Claude, &["Hel", "lo"]));
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "claude-mock",
+        format: Format::Claude,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["claude-sonnet-4"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-ant-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "model": "claude-sonnet-4",
+                "stream": true,
+                "messages": [{"role": "user", "content": "hi"}]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = build_router(harness.state.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events: Vec<String> = String::from_utf8(body.to_vec())
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: ").map(str::to_string))
+        .collect();
+    assert_eq!(events.last().map(String::as_str), Some("[DONE]"));
+    let chunks: Vec<Value> = events[..events.len() - 1]
+        .iter()
+        .map(|e| serde_json::from_str(e).unwrap())
+        .collect();
+    let text: String = chunks
+        .iter()
+        .filter_map(|c| c["choices"][0]["delta"]["content"].as_str())
+        .collect();
+    assert_eq!(text, "Hello");
+    assert!(
+        chunks
+            .iter()
+            .any(|c| c["choices"][0]["finish_reason"] == "stop")
+    );
+
+    let sent = upstream.last_request();
+    assert_eq!(sent.path, "/v1/messages");
+    assert!(sent.is_stream());
+    assert_eq!(sent.json()["messages"][0]["content"], "hi");
+}
+
+#[tokio::test]
+async fn test_ws_chat_streams_chunks_in_order_and_closes() {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+    upstream.push(MockReply::text_stream(Format::OpenAI, &["Hel", "lo"]));
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-mock",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(harness.state.clone());
+    let _server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/v1/ws/chat"))
+        .await
+        .unwrap();
+    socket
+        .send(Message::text(
+            json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]}).to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let mut frames = Vec::new();
+    while let Some(message) = socket.next().await {
+        let text = message.unwrap().into_text().unwrap().to_string();
+        let done = text == "[DONE]";
+        frames.push(text);
+        if done {
+            break;
+        }
+    }
+    let chunks: Vec<Value> = frames[..frames.len() - 1]
+        .iter()
+        .map(|f| serde_json::from_str(f).unwrap())
+        .collect();
+    assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+    let deltas: Vec<&str> = chunks
+        .iter()
+        .filter_map(|c| c["choices"][0]["delta"]["content"].as_str())
+        .filter(|t| !t.is_empty())
+        .collect();
+    assert_eq!(deltas, ["Hel", "lo"]);
+    let finish = chunks
+        .iter()
+        .position(|c| c["choices"][0]["finish_reason"] == "stop")
+        .expect("finish chunk");
+    assert!(
+        finish > 2,
+        "finish_reason before the text deltas: {frames:?}"
+    );
+    assert!(upstream.last_request().is_stream());
+
+    socket.close(None).await.unwrap();
+    match socket.next().await {
+        Some(Ok(Message::Close(_))) | None => {}
+        other => panic!("expected the server to close, got {other:?}"),
+    }
+    assert!(socket.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_legacy_completions_translated_to_chat() {
+    async fn chat_completions(body: String) -> axum::response::Response {
+        use axum::response::IntoResponse;
+        let request: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            request["messages"],
+            json!([{"role": "user", "content": "Say this is a test"}]),
+        );
+        assert!(request.get("prompt").is_none());
+        if request["stream"] == true {
+            let chunk = |delta: Value, finish: Value| {
+                json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion.chunk",
+                    "model": "gpt-4o-mini",
+                    "choices": [{"index": 0, "delta": delta, "finish_reason": finish}]
+                })
+            };
+            let sse = format!(
+                "data: {}\n\ndata: {}\n\ndata: {}\n\ndata: [DONE]\n\n",
+                chunk(json!({"role": "assistant"}), Value::Null),
+                chunk(json!({"content": "This is"}), Value::Null),
+                chunk(json!({}), json!("stop")),
+            );
+            return ([("content-type", "text/event-stream")], sse).into_response();
+        }
+        Json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1700000000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "This is a test."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 4, "total_tokens": 9}
+        }))
+        .into_response()
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-chat",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o-mini"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let completion = |body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let (status, body) = send_request(
+        &harness,
+        completion(
+            json!({"model": "gpt-4o-mini", "prompt": "Say this is a test", "max_tokens": 7}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["object"], "text_completion");
+    assert_eq!(body["choices"][0]["text"], "This is a test.");
+    assert_eq!(body["usage"]["total_tokens"], 9);
+
+    let response = build_router(harness.state.clone())
+        .oneshot(completion(
+            json!({"model": "gpt-4o-mini", "prompt": "Say this is a test", "stream": true}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events: Vec<&str> = std::str::from_utf8(&body)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .collect();
+    assert_eq!(events.len(), 3, "{events:?}");
+    let first: Value = serde_json::from_str(events[0]).unwrap();
+    assert_eq!(first["object"], "text_completion");
+    assert_eq!(first["choices"][0]["text"], "This is");
+    let last: Value = serde_json::from_str(events[1]).unwrap();
+    assert_eq!(last["choices"][0]["finish_reason"], "stop");
+    assert_eq!(events[2], "[DONE]");
+
+    let (status, _) = send_request(
+        &harness,
+        completion(json!({"model": "gpt-4o-mini", "prompt": ["a", "b"]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_gemini_cached_content_bound_to_creating_credential() {
+    fn key(headers: &axum::http::HeaderMap) -> String {
+        headers
+            .get("x-goog-api-key")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    }
+    async fn create(headers: axum::http::HeaderMap, body: String) -> Json<Value> {
+        let request: Value = serde_json::from_str(&body).unwrap();
+        Json(json!({
+            "name": format!("cachedContents/cache-{}", key(&headers)),
+            "model": request["model"],
+        }))
+    }
+    async fn cached(
+        headers: axum::http::HeaderMap,
+        axum::extract::Path(id): axum::extract::Path<String>,
+    ) -> (StatusCode, Json<Value>) {
+        if id == format!("cache-{}", key(&headers)) {
+            (
+                StatusCode::OK,
+                Json(json!({"name": format!("cachedContents/{id}")})),
+            )
+        } else {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": {"code": 403, "status": "PERMISSION_DENIED"}})),
+            )
+        }
+    }
+    async fn generate(headers: axum::http::HeaderMap, body: String) -> (StatusCode, Json<Value>) {
+        let request: Value = serde_json::from_str(&body).unwrap();
+        if request["cachedContent"] != format!("cachedContents/cache-{}", key(&headers)) {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": {"code": 403, "status": "PERMISSION_DENIED"}})),
+            );
+        }
+        (
+            StatusCode::OK,
+            Json(json!({
+                "candidates": [{
+                    "content": {"parts": [{"text": "cached"}], "role": "model"},
+                    "finishReason": "STOP"
+                }],
+                "usageMetadata": {
+                    "promptTokenCount": 1010,
+                    "candidatesTokenCount": 2,
+                    "cachedContentTokenCount": 1000,
+                    "totalTokenCount": 1012
+                }
+            })),
+        )
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new()
+            .route("/v1beta/cachedContents", post(create))
+            .route("/v1beta/cachedContents/{id}", get(cached).delete(cached))
+            .route("/v1beta/models/{action}", post(generate));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = ["gem-a", "gem-b"]
+        .into_iter()
+        .map(|name| {
+            provider_entry(ProviderFixture {
+                name,
+                format: Format::Gemini,
+                upstream: None,
+                wire_api: WireApi::Chat,
+                models: &["gemini-2.0-flash"],
+                auth_profiles: Vec::new(),
+                api_key: &name[4..],
+                base_url: Some(&base_url),
+                region: None,
+            })
+        })
+        .collect();
+    write_test_config(&harness, &config);
+
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        let builder = if uri == "/v1beta/cachedContents" {
+            builder.header("x-prism-auth-profile", "gem-b")
+        } else {
+            builder
+        };
+        builder
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+
+    let (status, body) = send_request(
+        &harness,
+        request(
+            "POST",
+            "/v1beta/cachedContents",
+            Some(json!({"model": "models/gemini-2.0-flash", "contents": [], "ttl": "300s"})),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(body["name"], "cachedContents/cache-b");
+
+    // Without a pin these would also go to gem-a, which does not own the cache.
+    for _ in 0..3 {
+        let (status, body) = send_request(
+            &harness,
+            request(
+                "POST",
+                "/v1beta/models/gemini-2.0-flash:generateContent",
+                Some(json!({
+                    "cachedContent": "cachedContents/cache-b",
+                    "contents": [{"role": "user", "parts": [{"text": "hi"}]}]
+                })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body:?}");
+        assert_eq!(body["usageMetadata"]["cachedContentTokenCount"], 1000);
+    }
+
+    // An unbound cache is found by asking each Gemini credential.
+    harness.state.gemini_caches.clear();
+    let (status, body) = send_request(
+        &harness,
+        request("GET", "/v1beta/cachedContents/cache-b", None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert!(
+        harness
+            .state
+            .gemini_caches
+            .contains_key("cachedContents/cache-b")
+    );
+
+    // A binding to a credential that no longer exists is dropped and the owner found again.
+    let owner = harness
+        .state
+        .gemini_caches
+        .get("cachedContents/cache-b")
+        .unwrap()
+        .credential_id
+        .clone();
+    harness.state.gemini_caches.insert(
+        "cachedContents/cache-b".to_string(),
+        prism_server::handler::gemini_cache::CacheBinding {
+            credential_id: "removed-credential".to_string(),
+            expires_at: None,
+        },
+    );
+    let (status, body) = send_request(
+        &harness,
+        request("GET", "/v1beta/cachedContents/cache-b", None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body:?}");
+    assert_eq!(
+        harness
+            .state
+            .gemini_caches
+            .get("cachedContents/cache-b")
+            .unwrap()
+            .credential_id,
+        owner
+    );
+
+    let (status, _) = send_request(
+        &harness,
+        request("DELETE", "/v1beta/cachedContents/cache-b", None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(harness.state.gemini_caches.is_empty());
+
+    let (status, _) = send_request(
+        &harness,
+        request("GET", "/v1beta/cachedContents/missing", None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_openai_passthrough_swaps_auth_without_translation() {
+    async fn echo(
+        method: axum::http::Method,
+        uri: axum::http::Uri,
+        headers: axum::http::HeaderMap,
+        body: String,
+    ) -> (StatusCode, [(&'static str, &'static str); 1], Json<Value>) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        (
+            StatusCode::CREATED,
+            [("x-request-id", "req-upstream-1")],
+            Json(json!({
+                "method": method.as_str(),
+                "uri": uri.to_string(),
+                "authorization": header("authorization"),
+                "openai_beta": header("openai-beta"),
+                "content_type": header("content-type"),
+                "body": body,
+            })),
+        )
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().fallback(echo);
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = ["openai-a", "openai-b"]
+        .into_iter()
+        .map(|name| {
+            provider_entry(ProviderFixture {
+                name,
+                format: Format::OpenAI,
+                upstream: None,
+                wire_api: WireApi::Chat,
+                models: &["gpt-4o"],
+                auth_profiles: Vec::new(),
+                api_key: if name == "openai-a" { "sk-a" } else { "sk-b" },
+                base_url: Some(&base_url),
+                region: None,
+            })
+        })
+        .collect();
+    write_test_config(&harness, &config);
+
+    let request = |method: &str, uri: &str, body: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", "Bearer client-key")
+            .header("openai-beta", "assistants=v2")
+            .header("content-type", "multipart/form-data; boundary=xyz")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let (status, _) = send_request(&harness, request("GET", "/v1/files", "")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "disabled by default");
+
+    config.openai_passthrough.enabled = true;
+    config.openai_passthrough.provider = "openai-b".into();
+    write_test_config(&harness, &config);
+
+    let (status, body) = send_request(
+        &harness,
+        request(
+            "POST",
+            "/v1/files",
+            "--xyz\r\npurpose=assistants\r\n--xyz--",
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    assert_eq!(body["authorization"], "Bearer sk-b");
+    assert_eq!(body["content_type"], "multipart/form-data; boundary=xyz");
+    assert_eq!(body["body"], "--xyz\r\npurpose=assistants\r\n--xyz--");
+
+    let response = build_router(harness.state.clone())
+        .oneshot(request("GET", "/v1/threads/thread_1/runs?limit=2", ""))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-request-id"], "req-upstream-1");
+    let body: Value = serde_json::from_slice(
+        &axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(body["method"], "GET");
+    assert_eq!(body["uri"], "/v1/threads/thread_1/runs?limit=2");
+    assert_eq!(body["openai_beta"], "assistants=v2");
+
+    let (status, _) = send_request(&harness, request("POST", "/v1/batches", "{}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "catch-all is opt-in");
+
+    config.openai_passthrough.catch_all = true;
+    write_test_config(&harness, &config);
+    let (status, body) = send_request(&harness, request("POST", "/v1/batches", "{}")).await;
+    assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    assert_eq!(body["uri"], "/v1/batches");
+    assert_eq!(body["authorization"], "Bearer sk-b");
+    // Routed endpoints are still handled by the proxy itself.
+    let (status, body) = send_request(&harness, request("GET", "/v1/models", "")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["object"], "list");
+    let (status, _) = send_request(&harness, request("GET", "/v1/chat/completions", "")).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+
+    config.openai_passthrough.provider = "missing".into();
+    assert!(Config::load_from_str(&config.to_yaml().unwrap()).is_err());
+}
+
+/// A gRPC message frame holding a message whose only field is string field 1.
+#[cfg(feature = "grpc")]
+fn grpc_frame(json: &str) -> Vec<u8> {
+    let mut message = vec![0x0a];
+    let mut len = json.len();
+    while len >= 0x80 {
+        message.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    message.push(len as u8);
+    message.extend_from_slice(json.as_bytes());
+    let mut frame = vec![0];
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend(message);
+    frame
+}
+
+/// The string field 1 of each message in a gRPC response body.
+#[cfg(feature = "grpc")]
+fn grpc_messages(mut body: &[u8]) -> Vec<Value> {
+    let mut messages = Vec::new();
+    while body.len() >= 5 {
+        let len = u32::from_be_bytes(body[1..5].try_into().unwrap()) as usize;
+        let mut message = &body[5..5 + len];
+        assert_eq!(message[0], 0x0a);
+        message = &message[1..];
+        let (mut field_len, mut shift) = (0usize, 0);
+        while message[0] & 0x80 != 0 {
+            field_len |= ((message[0] & 0x7f) as usize) << shift;
+            shift += 7;
+            message = &message[1..];
+        }
+        field_len |= (message[0] as usize) << shift;
+        messages.push(serde_json::from_slice(&message[1..1 + field_len]).unwrap());
+        body = &body[5 + len..];
+    }
+    messages
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_grpc_chat_completions_unary_and_streaming() {
+    async fn chat_completions(body: String) -> axum::response::Response {
+        use axum::response::IntoResponse;
+        let request: Value = serde_json::from_str(&body).unwrap();
+        if request["stream"] == true {
+            let chunk = |content: &str| {
+                json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion.chunk",
+                    "model": "gpt-4o",
+                    "choices": [{"index": 0, "delta": {"content": content}, "finish_reason": null}]
+                })
+            };
+            let sse = format!(
+                "data: {}\n\ndata: {}\n\ndata: [DONE]\n\n",
+                chunk("Hel"),
+                chunk("lo")
+            );
+            return ([("content-type", "text/event-stream")], sse).into_response();
+        }
+        Json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hello"},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}
+        }))
+        .into_response()
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let _task = tokio::spawn(async move {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions));
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-grpc",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let rpc = |method: &str, json: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/prism.chat.v1.ChatCompletions/{method}"))
+            .header("content-type", "application/grpc")
+            .header("te", "trailers")
+            .body(Body::from(grpc_frame(json)))
+            .unwrap()
+    };
+    let request = r#"{"model":"gpt-4o","messages":[{"role":"user","content":"hi"}]}"#;
+
+    let response = build_router(harness.state.clone())
+        .oneshot(rpc("Create", request))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let messages = grpc_messages(&body);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["choices"][0]["message"]["content"], "Hello");
+
+    let response = build_router(harness.state.clone())
+        .oneshot(rpc("CreateStream", request))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let content: Vec<_> = grpc_messages(&body)
+        .iter()
+        .map(|chunk| chunk["choices"][0]["delta"]["content"].clone())
+        .collect();
+    assert_eq!(content, vec![json!("Hel"), json!("lo")]);
+
+    // Validation failures come back as a gRPC status, not an HTTP error.
+    let response = build_router(harness.state.clone())
+        .oneshot(rpc("Create", r#"{"model":"gpt-4o"}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["grpc-status"], "3");
+}
+
+#[tokio::test]
+async fn test_traceparent_propagated_upstream_and_to_response() {
+    async fn chat_completions(
+        State(seen): State<Arc<Mutex<Vec<String>>>>,
+        headers: axum::http::HeaderMap,
+    ) -> Json<Value> {
+        let traceparent = headers
+            .get("x-upstream-trace")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        seen.lock().unwrap().push(traceparent);
+        Json(json!({
+            "id": "chatcmpl-trace",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "ok"},
+                "finish_reason": "stop"
+            }]
+        }))
+    }
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(seen.clone());
+    let _task = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.trace_propagation.enabled = true;
+    config.trace_propagation.upstream_header = "x-upstream-trace".to_string();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-trace",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let inbound = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let chat = |traceparent: Option<&str>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json");
+        if let Some(traceparent) = traceparent {
+            builder = builder.header("traceparent", traceparent);
+        }
+        builder
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = build_router(harness.state.clone())
+        .oneshot(chat(Some(inbound)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let echoed = response.headers()["traceparent"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(echoed.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+    assert_ne!(echoed, inbound);
+    assert_eq!(seen.lock().unwrap().last().unwrap(), &echoed);
+
+    // Without an inbound header a fresh trace is started.
+    let response = build_router(harness.state.clone())
+        .oneshot(chat(None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let fresh = response.headers()["traceparent"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(!fresh.contains("4bf92f3577b34da6a3ce929d0e0e4736"));
+    assert_eq!(seen.lock().unwrap().last().unwrap(), &fresh);
+
+    // Disabled propagation keeps the response header but sends nothing upstream.
+    config.trace_propagation.enabled = false;
+    write_test_config(&harness, &config);
+    let response = build_router(harness.state.clone())
+        .oneshot(chat(Some(inbound)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("traceparent"));
+    assert_eq!(seen.lock().unwrap().last().unwrap(), "");
+}
+
+#[tokio::test]
+async fn test_outbound_header_policy() {
+    async fn chat_completions(
+        State(seen): State<Arc<Mutex<Option<axum::http::HeaderMap>>>>,
+        headers: axum::http::HeaderMap,
+    ) -> Json<Value> {
+        *seen.lock().unwrap() = Some(headers);
+        Json(json!({
+            "id": "chatcmpl-headers",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "ok"},
+                "finish_reason": "stop"
+            }]
+        }))
+    }
+    let seen = Arc::new(Mutex::new(None));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(seen.clone());
+    let _task = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.outbound_headers.forward = vec!["x-request-id".into(), "x-team".into()];
+    config.outbound_headers.strip = vec!["traceparent".into()];
+    config.trace_propagation.enabled = true;
+    let mut entry = provider_entry(ProviderFixture {
+        name: "openai-headers",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    });
+    entry
+        .upstream_presentation
+        .custom_headers
+        .insert("x-team".into(), "configured".into());
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .header("x-request-id", "client-req-1")
+        .header("x-team", "from-client")
+        .header("x-unlisted", "secret")
+        .header(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .body(Body::from(
+            json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]}).to_string(),
+        ))
+        .unwrap();
+    let response = build_router(harness.state.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upstream = seen.lock().unwrap().take().unwrap();
+    assert_eq!(upstream["x-request-id"], "client-req-1");
+    // Configured provider headers win over forwarded client values.
+    assert_eq!(upstream["x-team"], "configured");
+    assert!(!upstream.contains_key("x-unlisted"));
+    // Stripped even though trace propagation would add it.
+    assert!(!upstream.contains_key("traceparent"));
+}
+
+#[tokio::test]
+async fn test_anthropic_beta_allowlist_on_passthrough() {
+    async fn messages(
+        State(seen): State<Arc<Mutex<Option<axum::http::HeaderMap>>>>,
+        headers: axum::http::HeaderMap,
+    ) -> Json<Value> {
+        *seen.lock().unwrap() = Some(headers);
+        Json(json!({
+            "id": "msg_beta",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-sonnet-4",
+            "content": [{"type": "text", "text": "ok"}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 3, "output_tokens": 1}
+        }))
+    }
+    let seen = Arc::new(Mutex::new(None));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/v1/messages", post(messages))
+        .with_state(seen.clone());
+    let _task = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "anthropic-beta",
+        format: Format::Claude,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["claude-sonnet-4"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-ant-test",
+        base_url: Some(&base_url),
+        region: None,
+    });
+    entry.allowed_betas = Some(vec!["prompt-caching-*".to_string()]);
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/messages?beta=true")
+        .header("content-type", "application/json")
+        .header(
+            "anthropic-beta",
+            "prompt-caching-2024-07-31,context-1m-2025-08-07",
+        )
+        .body(Body::from(
+            json!({
+                "model": "claude-sonnet-4",
+                "max_tokens": 16,
+                "messages": [{"role": "user", "content": "hi"}]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let (status, body) = send_request(&harness, request).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let upstream = seen.lock().unwrap().take().unwrap();
+    let betas: Vec<&str> = upstream
+        .get_all("anthropic-beta")
+        .iter()
+        .flat_map(|v| v.to_str().unwrap().split(','))
+        .collect();
+    assert!(betas.contains(&"prompt-caching-2024-07-31"), "{betas:?}");
+    // Not on the credential's allowlist.
+    assert!(!betas.contains(&"context-1m-2025-08-07"), "{betas:?}");
+}
+
+#[tokio::test]
+async fn test_exhausted_rate_limits_return_429_with_retry_after() {
+    async fn spawn_upstream(status: StatusCode, retry_after: Option<&'static str>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post(move || async move {
+                let mut response = axum::response::IntoResponse::into_response((
+                    status,
+                    Json(json!({"error": {"message": "slow down", "type": "rate_limit"}})),
+                ));
+                if let Some(value) = retry_after {
+                    response
+                        .headers_mut()
+                        .insert("retry-after", value.parse().unwrap());
+                }
+                response
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        base_url
+    }
+    let limited_url = spawn_upstream(StatusCode::TOO_MANY_REQUESTS, Some("20")).await;
+    let failing_url = spawn_upstream(StatusCode::SERVICE_UNAVAILABLE, None).await;
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = [
+        ("openai-limited", &limited_url),
+        ("openai-down", &failing_url),
+    ]
+    .into_iter()
+    .map(|(name, base_url)| {
+        provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some(base_url),
+            region: None,
+        })
+    })
+    .collect();
+    write_test_config(&harness, &config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]}).to_string(),
+        ))
+        .unwrap();
+    let response = build_router(harness.state.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    // One credential is rate limited and the other is down: the client is told
+    // to back off until the rate-limited credential's cooldown ends.
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(
+        (19..=20).contains(&retry_after),
+        "retry-after={retry_after}"
+    );
+}
+
+#[tokio::test]
+async fn test_queue_on_exhaustion_waits_for_cooldown() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route(
+            "/v1/chat/completions",
+            post(|State(hits): State<Arc<AtomicUsize>>| async move {
+                if hits.fetch_add(1, Ordering::SeqCst) == 0 {
+                    let mut response = axum::response::IntoResponse::into_response((
+                        StatusCode::TOO_MANY_REQUESTS,
+                        Json(json!({"error": {"message": "slow down", "type": "rate_limit"}})),
+                    ));
+                    response
+                        .headers_mut()
+                        .insert("retry-after", "1".parse().unwrap());
+                    return response;
+                }
+                axum::response::IntoResponse::into_response(Json(json!({
+                    "id": "chatcmpl-queued",
+                    "object": "chat.completion",
+                    "model": "gpt-4o",
+                    "choices": [{
+                        "index": 0,
+                        "message": {"role": "assistant", "content": "ok"},
+                        "finish_reason": "stop"
+                    }]
+                })))
+            }),
+        )
+        .with_state(hits.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.queue_on_exhaustion_ms = 3000;
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-queued",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+
+    let request = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+
+    // The only credential is rate limited for 1s, which fits the 3s budget:
+    // the request waits out the cooldown and is served.
+    let response = build_router(harness.state.clone())
+        .oneshot(request())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    // A cooldown past the budget fails fast without reaching the upstream.
+    for records in harness.state.router.credential_map().values() {
+        for record in records {
+            harness
+                .state
+                .router
+                .set_quota_cooldown(&record.id, std::time::Duration::from_secs(60));
+        }
+    }
+    let response = build_router(harness.state.clone())
+        .oneshot(request())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(
+        (59..=60).contains(&retry_after),
+        "retry-after={retry_after}"
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_credential_quota_exhaustion() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new().route(
+        "/v1/chat/completions",
+        post(|| async {
+            Json(json!({
+                "id": "chatcmpl-quota",
+                "object": "chat.completion",
+                "model": "gpt-4o",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "ok"},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 7, "completion_tokens": 3, "total_tokens": 10}
+            }))
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "openai-capped",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    });
+    entry.quota = Some(prism_core::config::CredentialQuotaConfig {
+        daily: prism_core::config::QuotaLimits {
+            requests: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+
+    let request = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+    let response = build_router(harness.state.clone())
+        .oneshot(request())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The daily request cap is used up: the credential sits out until midnight UTC.
+    let response = build_router(harness.state.clone())
+        .oneshot(request())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=86_400).contains(&retry_after), "{retry_after}");
+
+    let token = login_and_get_token(&harness).await;
+    let (status, body) =
+        send_request(&harness, authed_get("/api/dashboard/providers", &token)).await;
+    assert_eq!(status, StatusCode::OK);
+    let quota = &body["providers"][0]["auth_profiles"][0]["quota"];
+    assert_eq!(quota["daily"]["used"]["requests"], 1);
+    assert_eq!(quota["daily"]["used"]["tokens"], 10);
+    assert_eq!(quota["daily"]["limits"]["requests"], 1);
+    assert_eq!(quota["daily"]["exhausted"], true);
+    assert_eq!(quota["monthly"]["exhausted"], false);
+}
+
+#[tokio::test]
+async fn test_cluster_view_aggregates_peers() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route(
+            "/admin/metrics",
+            get(|| async {
+                Json(json!({
+                    "total_requests": 30,
+                    "total_errors": 3,
+                    "total_tokens": 500,
+                    "total_cost_usd": 1.5,
+                    "requests_per_minute": 2.0
+                }))
+            }),
+        )
+        .route(
+            "/api/dashboard/logs/stats",
+            get(
+                |headers: axum::http::HeaderMap, query: axum::extract::RawQuery| async move {
+                    let token = headers["authorization"]
+                        .to_str()
+                        .unwrap()
+                        .strip_prefix("Bearer ")
+                        .unwrap()
+                        .to_string();
+                    let claims = prism_server::middleware::dashboard_auth::decode_claims(
+                        &token,
+                        "peer-secret",
+                    );
+                    if claims.is_err() {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({})));
+                    }
+                    assert_eq!(query.0.as_deref(), Some("provider=openai"));
+                    (
+                        StatusCode::OK,
+                        Json(json!({
+                            "total_entries": 10,
+                            "error_count": 1,
+                            "avg_latency_ms": 200.0
+                        })),
+                    )
+                },
+            ),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.cluster.node_name = "node-a".to_string();
+    config.cluster.peers = vec![
+        prism_core::config::ClusterPeer {
+            name: "node-b".to_string(),
+            url: peer_url,
+            jwt_secret: Some("peer-secret".to_string()),
+        },
+        prism_core::config::ClusterPeer {
+            name: "node-c".to_string(),
+            // Nothing listens on the discard port.
+            url: "http://127.0.0.1:9".to_string(),
+            jwt_secret: None,
+        },
+    ];
+    write_test_config(&harness, &config);
+
+    let token = login_and_get_token(&harness).await;
+    let (status, body) = send_request(
+        &harness,
+        authed_get("/api/dashboard/cluster?provider=openai", &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let nodes = body["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(nodes[0]["name"], "node-a");
+    assert_eq!(nodes[0]["reachable"], true);
+    assert_eq!(nodes[1]["name"], "node-b");
+    assert_eq!(nodes[1]["reachable"], true);
+    assert_eq!(nodes[1]["log_stats"]["total_entries"], 10);
+    assert_eq!(nodes[2]["name"], "node-c");
+    assert_eq!(nodes[2]["reachable"], false);
+    assert!(nodes[2]["error"].is_string());
+
+    let summary = &body["summary"];
+    assert_eq!(summary["nodes"], 3);
+    assert_eq!(summary["reachable"], 2);
+    assert_eq!(summary["total_requests"], 30);
+    assert_eq!(summary["total_errors"], 3);
+    assert_eq!(summary["log_entries"], 10);
+    assert_eq!(summary["avg_latency_ms"], 200.0);
+}
+
+#[tokio::test]
+async fn test_translation_rules_apply_and_hot_reload() {
+    async fn chat_completions(
+        State(seen): State<Arc<Mutex<Vec<Value>>>>,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        seen.lock().unwrap().push(body);
+        Json(json!({
+            "id": "chatcmpl-rules",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "ok"},
+                "finish_reason": "length"
+            }],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}
+        }))
+    }
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(seen.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-rules",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+    // As loaded, with provider IDs assigned.
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.translation_rules = vec![prism_core::config::TranslationRule {
+        models: vec!["gpt-4o*".to_string()],
+        from: Some(Format::Claude),
+        drop_fields: vec!["temperature".to_string()],
+        finish_reasons: HashMap::from([("max_tokens".to_string(), "end_turn".to_string())]),
+        ..Default::default()
+    }];
+    let delta = prism_server::reload::apply_config(&harness.state, config.clone()).unwrap();
+    assert_eq!(delta.touched(), vec!["translation"]);
+
+    let messages = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/messages")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "model": "gpt-4o",
+                    "max_tokens": 16,
+                    "temperature": 0.3,
+                    "messages": [{"role": "user", "content": "hi"}]
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+    let (status, body) = send_request(&harness, messages()).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["stop_reason"], "end_turn");
+    assert!(seen.lock().unwrap()[0].get("temperature").is_none());
+
+    config.translation_rules.clear();
+    prism_server::reload::apply_config(&harness.state, config).unwrap();
+    let (status, body) = send_request(&harness, messages()).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["stop_reason"], "max_tokens");
+    assert_eq!(seen.lock().unwrap()[1]["temperature"], 0.3);
+}
+
+#[tokio::test]
+async fn test_routing_only_reload_updates_router_strategy() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let profile = |id: &str| AuthProfileEntry {
+        id: id.to_string(),
+        secret: Some(format!("sk-{id}")),
+        ..Default::default()
+    };
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "pool",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: vec![profile("a"), profile("b")],
+        api_key: "",
+        base_url: None,
+        region: None,
+    })];
+    prism_server::reload::apply_config(&harness.state, config.clone()).unwrap();
+    let pick = || {
+        harness
+            .state
+            .router
+            .pick("pool", "gpt-4o", &[], None, &[], None)
+            .unwrap()
+            .id
+    };
+    assert_ne!(pick(), pick());
+
+    let default_profile = config.routing.default_profile.clone();
+    config
+        .routing
+        .profiles
+        .get_mut(&default_profile)
+        .unwrap()
+        .credential_policy
+        .strategy = prism_core::routing::config::CredentialStrategy::FillFirst;
+    let delta = prism_server::reload::apply_config(&harness.state, config).unwrap();
+    assert_eq!(delta.touched(), vec!["routing"]);
+    assert_eq!(pick(), pick());
+}
+
+#[tokio::test]
+async fn test_preset_model_expands_to_base_model() {
+    async fn chat_completions(
+        State(seen): State<Arc<Mutex<Vec<Value>>>>,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        seen.lock().unwrap().push(body);
+        Json(json!({
+            "id": "chatcmpl-preset",
+            "object": "chat.completion",
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "ok"},
+                "finish_reason": "stop"
+            }]
+        }))
+    }
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(seen.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "openai-preset",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(&base_url),
+        region: None,
+    })];
+    config.preset_models = vec![prism_core::config::PresetModel {
+        name: "team-reviewer".to_string(),
+        model: "gpt-4o".to_string(),
+        system_prompt: Some("Review code tersely.".to_string()),
+        params: serde_json::Map::from_iter([("temperature".to_string(), json!(0.1))]),
+    }];
+    write_test_config(&harness, &config);
+    prism_server::reload::apply_config(&harness.state, config).unwrap();
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "model": "team-reviewer",
+                "messages": [{"role": "user", "content": "fn main() {}"}]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let upstream = seen.lock().unwrap()[0].clone();
+    assert_eq!(upstream["model"], "gpt-4o");
+    assert_eq!(upstream["temperature"], 0.1);
+    assert_eq!(upstream["messages"][0]["role"], "system");
+    assert_eq!(upstream["messages"][0]["content"], "Review code tersely.");
+    assert_eq!(upstream["messages"][1]["content"], "fn main() {}");
+
+    let req = Request::builder()
+        .uri("/v1/models")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|m| m["id"] == "team-reviewer" && m["owned_by"] == "preset")
+    );
+}
+
+/// Executor that answers every chat request with a fixed completion.
+struct CannedExecutor(&'static str);
+
+#[async_trait::async_trait]
+impl prism_core::provider::ProviderExecutor for CannedExecutor {
+    fn identifier(&self) -> &str {
+        self.0
+    }
+
+    fn native_format(&self) -> Format {
+        Format::OpenAI
+    }
+
+    async fn execute(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::ProviderResponse, prism_core::error::ProxyError> {
+        let payload = json!({
+            "id": "chatcmpl-embedded",
+            "object": "chat.completion",
+            "model": request.model,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": format!("from {}", self.0)},
+                "finish_reason": "stop"
+            }]
+        });
+        Ok(prism_core::provider::ProviderResponse {
+            payload: payload.to_string().into(),
+            headers: HashMap::new(),
+        })
+    }
+
+    async fn execute_stream(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        _request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::StreamResult, prism_core::error::ProxyError> {
+        Err(prism_core::error::ProxyError::Internal(
+            "streaming not supported".to_string(),
+        ))
+    }
+
+    fn supported_models(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+    ) -> Vec<prism_core::provider::ModelInfo> {
+        Vec::new()
+    }
+}
+
+#[tokio::test]
+async fn test_proxy_builder_embeds_router_with_custom_executor() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        providers: vec![provider_entry(ProviderFixture {
+            name: "embedded",
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        })],
+        ..Config::default()
+    };
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(CannedExecutor("openai")))
+        .build()
+        .unwrap();
+    let app = Router::new().nest("/llm", proxy.router());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/llm/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]}).to_string(),
+        ))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["choices"][0]["message"]["content"], "from openai");
+
+    let mut next = proxy.state().config.load().as_ref().clone();
+    next.request_retry = 1;
+    assert_eq!(proxy.apply_config(next).unwrap().touched(), vec!["other"]);
+}
+
+#[tokio::test]
+async fn test_debug_trace_header_records_redacted_attempts() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = create_test_harness().state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "alpha",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-upstream-secret",
+        base_url: Some("http://127.0.0.1:9"),
+        region: None,
+    })];
+    config.request_debug.enabled = true;
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(CannedExecutor("openai")))
+        .build()
+        .unwrap();
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let token = login_and_get_token(&harness).await;
+    let request = |debug: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .header("x-debug", debug)
+            .body(Body::from(
+                json!({
+                    "model": "gpt-4o",
+                    "messages": [{"role": "user", "content": "hi"}],
+                    "metadata": {"api_key": "sk-client-secret"}
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = build_router(harness.state.clone())
+        .oneshot(request("trace"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let request_id = response.headers()["x-prism-debug-trace"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let req = authed_get(
+        &format!("/api/dashboard/requests/{request_id}/debug"),
+        &token,
+    );
+    let (status, trace) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let kinds: Vec<&str> = trace["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "client_request",
+            "route_plan",
+            "upstream_request",
+            "upstream_result",
+            "completed"
+        ]
+    );
+    let events = &trace["events"];
+    assert_eq!(
+        events[0]["data"]["body"]["metadata"]["api_key"],
+        "[redacted]"
+    );
+    assert_eq!(events[2]["data"]["provider"], "alpha");
+    assert_eq!(events[3]["data"]["provider"], "alpha");
+    assert_eq!(
+        events[3]["data"]["credential"],
+        events[2]["data"]["credential"]
+    );
+    assert_eq!(events[2]["data"]["body"]["model"], "gpt-4o");
+    assert_eq!(events[3]["data"]["status"], 200);
+    assert_eq!(events[4]["data"]["status"], 200);
+    assert!(!trace.to_string().contains("secret"), "{trace}");
+
+    // Plain debug mode and a disabled section record nothing.
+    let response = build_router(harness.state.clone())
+        .oneshot(request("true"))
+        .await
+        .unwrap();
+    assert!(response.headers().get("x-prism-debug-trace").is_none());
+    let mut next = harness.state.config.load().as_ref().clone();
+    next.request_debug.enabled = false;
+    harness.state.config.store(Arc::new(next));
+    let response = build_router(harness.state.clone())
+        .oneshot(request("trace"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("x-prism-debug-trace").is_none());
+}
+
+#[tokio::test]
+async fn test_model_prefix_namespaces_credentials() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let entry = |name: &'static str, prefix: &str| {
+        let mut entry = provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        });
+        entry.prefix = Some(prefix.to_string());
+        entry
+    };
+    let config = Config {
+        providers: vec![entry("alpha", "team-a/"), entry("beta", "team-b/")],
+        ..Config::default()
+    };
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(CannedExecutor("openai")))
+        .build()
+        .unwrap();
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let request = |model: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .header("x-debug", "true")
+            .body(Body::from(
+                json!({"model": model, "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+
+    for _ in 0..3 {
+        let response = build_router(harness.state.clone())
+            .oneshot(request("team-b/gpt-4o"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let credential = response.headers()["x-prism-route-credential"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(credential.starts_with("beta"), "{credential}");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["model"], "gpt-4o");
+    }
+
+    let models = Request::builder()
+        .uri("/v1/models")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send_request(&harness, models).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut ids: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|m| m["id"].as_str())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["team-a/gpt-4o", "team-b/gpt-4o"]);
+
+    let (status, _) = send_request(&harness, request("gpt-4o")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mut next = proxy.state().config.load().as_ref().clone();
+    next.force_model_prefix = true;
+    proxy.apply_config(next).unwrap();
+
+    let (status, body) = send_request(&harness, request("gpt-4o")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let (status, _) = send_request(&harness, request("team-a/gpt-4o")).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_routing_hint_headers_override_model_and_provider() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let entry = |name: &'static str| {
+        provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o", "gpt-4o-mini"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        })
+    };
+    let config = Config {
+        providers: vec![entry("alpha"), entry("beta")],
+        ..Config::default()
+    };
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(CannedExecutor("openai")))
+        .build()
+        .unwrap();
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let request = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .header("x-debug", "true")
+            .header("x-proxy-model", "gpt-4o-mini")
+            .header("x-proxy-provider", "beta")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+
+    // Without the flag the headers are rejected rather than silently ignored.
+    let (status, body) = send_request(&harness, request()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{body}");
+
+    let mut next = proxy.state().config.load().as_ref().clone();
+    next.routing_hint_headers = true;
+    proxy.apply_config(next).unwrap();
+
+    for _ in 0..3 {
+        let response = build_router(harness.state.clone())
+            .oneshot(request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let credential = response.headers()["x-prism-route-credential"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(credential.starts_with("beta"), "{credential}");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["model"], "gpt-4o-mini");
+    }
+}
+
+#[tokio::test]
+async fn test_provider_dispatches_to_named_custom_executor() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "internal",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["house-model"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-internal",
+        base_url: Some("http://127.0.0.1:9"),
+        region: None,
+    });
+    entry.executor = Some("house-llm".to_string());
+    let config = Config {
+        providers: vec![entry],
+        ..Config::default()
+    };
+    let builder = || {
+        prism_server::builder::ProxyBuilder::new(config.clone())
+            .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+    };
+    let err = builder().build().err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("unregistered executor 'house-llm'")
+    );
+
+    let proxy = builder()
+        .executor_as("house-llm", Arc::new(CannedExecutor("internal-backend")))
+        .build()
+        .unwrap();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"model": "house-model", "messages": [{"role": "user", "content": "hi"}]})
+                .to_string(),
+        ))
+        .unwrap();
+    let response = proxy.router().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body["choices"][0]["message"]["content"],
+        "from internal-backend"
+    );
+
+    // A reload may not point providers at executors that do not exist.
+    let mut next = config.clone();
+    next.providers[0].executor = Some("missing".to_string());
+    let err = proxy.apply_config(next).unwrap_err();
+    assert!(err.contains("unregistered executor 'missing'"), "{err}");
+}
+
+/// Executor whose upstream always answers 500.
+struct FailingExecutor;
+
+#[async_trait::async_trait]
+impl prism_core::provider::ProviderExecutor for FailingExecutor {
+    fn identifier(&self) -> &str {
+        "failing"
+    }
+
+    fn native_format(&self) -> Format {
+        Format::OpenAI
+    }
+
+    async fn execute(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        _request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::ProviderResponse, prism_core::error::ProxyError> {
+        Err(prism_core::error::ProxyError::Upstream {
+            status: 500,
+            body: "boom".to_string(),
+            retry_after_secs: None,
+            headers: Default::default(),
+        })
+    }
+
+    async fn execute_stream(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        _request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::StreamResult, prism_core::error::ProxyError> {
+        Err(prism_core::error::ProxyError::Internal(
+            "streaming not supported".to_string(),
+        ))
+    }
+
+    fn supported_models(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+    ) -> Vec<prism_core::provider::ModelInfo> {
+        Vec::new()
+    }
+}
+
+#[tokio::test]
+async fn test_failed_attempts_counted_per_provider() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let entry = |name: &'static str, executor: &str| {
+        let mut entry = provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["shared-model"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        });
+        entry.executor = Some(executor.to_string());
+        entry
+    };
+    let mut config = Config {
+        dashboard: DashboardConfig {
+            enabled: true,
+            username: "admin".to_string(),
+            password_hash: bcrypt::hash("test123", 4).unwrap(),
+            jwt_secret: Some("test-secret".to_string()),
+            localhost_only: false,
+            ..DashboardConfig::default()
+        },
+        providers: vec![entry("flaky", "failing"), entry("backup", "canned")],
+        ..Config::default()
+    };
+    // Try the flaky provider first on every request.
+    let balanced = config.routing.profiles.get_mut("balanced").unwrap();
+    balanced.provider_policy.strategy = ProviderStrategy::OrderedFallback;
+    balanced.provider_policy.order = vec!["flaky".to_string(), "backup".to_string()];
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(FailingExecutor))
+        .executor(Arc::new(CannedExecutor("canned")))
+        .build()
+        .unwrap();
+    let metrics = proxy.state().metrics.clone();
+
+    for _ in 0..2 {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "shared-model", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap();
+        let response = proxy.router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let flaky = metrics
+        .provider_attempts("flaky")
+        .expect("flaky provider tried");
+    assert_eq!(flaky["attempts"], 2);
+    assert_eq!(flaky["failures"], 2);
+    let backup = metrics.provider_attempts("backup").unwrap();
+    assert_eq!(backup["failures"], 0);
+    assert_eq!(backup["retries"], 2);
+    assert_eq!(metrics.snapshot()["total_retries"], 2);
+
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let token = login_and_get_token(&harness).await;
+    let (status, body) = send_request(
+        &harness,
+        authed_get("/api/dashboard/providers/flaky", &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["attempts"]["failure_rate"], 1.0);
+}
+
+/// Executor whose upstream never answers.
+struct HangingExecutor;
+
+#[async_trait::async_trait]
+impl prism_core::provider::ProviderExecutor for HangingExecutor {
+    fn identifier(&self) -> &str {
+        "hanging"
+    }
+
+    fn native_format(&self) -> Format {
+        Format::OpenAI
+    }
+
+    async fn execute(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        _request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::ProviderResponse, prism_core::error::ProxyError> {
+        std::future::pending().await
+    }
+
+    async fn execute_stream(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+        _request: prism_core::provider::ProviderRequest,
+    ) -> Result<prism_core::provider::StreamResult, prism_core::error::ProxyError> {
+        std::future::pending().await
+    }
+
+    fn supported_models(
+        &self,
+        _auth: &prism_core::provider::AuthRecord,
+    ) -> Vec<prism_core::provider::ModelInfo> {
+        Vec::new()
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_inflight_request() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "slow",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["slow-model"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some("http://127.0.0.1:9"),
+        region: None,
+    });
+    entry.executor = Some("hanging".to_string());
+    let config = Config {
+        dashboard: DashboardConfig {
+            enabled: true,
+            username: "admin".to_string(),
+            password_hash: bcrypt::hash("test123", 4).unwrap(),
+            jwt_secret: Some("test-secret".to_string()),
+            localhost_only: false,
+            ..DashboardConfig::default()
+        },
+        providers: vec![entry],
+        ..Config::default()
+    };
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .executor(Arc::new(HangingExecutor))
+        .build()
+        .unwrap();
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/chat/completions")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"model": "slow-model", "messages": [{"role": "user", "content": "hi"}]})
+                .to_string(),
+        ))
+        .unwrap();
+    let pending = tokio::spawn(proxy.router().oneshot(request));
+
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let token = login_and_get_token(&harness).await;
+    let mut listed = Value::Null;
+    for _ in 0..50 {
+        let (status, body) = send_request(
+            &harness,
+            authed_get("/api/dashboard/requests/active", &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        if body["total"] == 1 {
+            listed = body;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let request_id = listed["requests"][0]["request_id"]
+        .as_str()
+        .expect("request listed as in flight")
+        .to_string();
+    assert_eq!(listed["requests"][0]["path"], "/v1/chat/completions");
+    assert_eq!(listed["requests"][0]["provider"], "slow");
+    assert_eq!(listed["requests"][0]["model"], "slow-model");
+    assert_eq!(listed["requests"][0]["streaming"], false);
+
+    let cancel_uri = format!("/api/dashboard/requests/{request_id}/cancel");
+    let (status, body) = send_request(&harness, authed_post(&cancel_uri, &token, json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["cancelled"], true);
+
+    let response = tokio::time::timeout(std::time::Duration::from_secs(5), pending)
+        .await
+        .expect("cancelled request finished")
+        .unwrap()
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 499);
+    assert!(harness.state.inflight.is_empty());
+
+    let (status, _) = send_request(&harness, authed_post(&cancel_uri, &token, json!({}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_model_alias_collisions_resolve_to_first_provider() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let entry = |name: &'static str, models: &'static [&'static str], alias: &str| {
+        let mut entry = provider_entry(ProviderFixture {
+            name,
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models,
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        });
+        entry.models[0].alias = Some(alias.to_string());
+        entry
+    };
+    let mut next = harness.state.config.load().as_ref().clone();
+    next.providers = vec![
+        entry("alpha", &["gpt-4o-mini"], "fast"),
+        entry("beta", &["claude-haiku"], "fast"),
+    ];
+    prism_server::reload::apply_config(&harness.state, next).unwrap();
+
+    let (status, body) = send_request(
+        &harness,
+        authed_get("/api/dashboard/routing/aliases", &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = body["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["provider"], "alpha");
+    assert_eq!(entries[0]["shadowed"], false);
+    assert_eq!(entries[1]["provider"], "beta");
+    assert_eq!(entries[1]["model"], "claude-haiku");
+    assert_eq!(entries[1]["shadowed"], true);
+    assert!(
+        body["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("'alpha' wins")
+    );
+
+    // The shadowed alias no longer routes; the model stays reachable under its id.
+    let providers: Vec<String> = harness
+        .state
+        .router
+        .resolve_providers("fast")
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(providers, vec!["alpha"]);
+    let mut ids: Vec<String> = harness
+        .state
+        .router
+        .all_models()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["claude-haiku", "fast"]);
+
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/validate",
+            &token,
+            json!(harness.state.config.load().as_ref()),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["warnings"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_uptime_history_reports_unclean_restart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    // A previous run that never recorded a stop, as left behind by a crash.
+    std::fs::write(
+        temp_dir.path().join("history.json"),
+        json!([{"started_at": "2026-10-01T00:00:00Z", "pid": 42, "version": "0.1.0"}]).to_string(),
+    )
+    .unwrap();
+    let mut config = Config {
+        dashboard: DashboardConfig {
+            enabled: true,
+            jwt_secret: Some("test-secret".to_string()),
+            localhost_only: false,
+            ..DashboardConfig::default()
+        },
+        ..Config::default()
+    };
+    config.process_history.enabled = true;
+    config.process_history.path = "history.json".to_string();
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .build()
+        .unwrap();
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let token = login_and_get_token(&harness).await;
+
+    let (status, body) = send_request(
+        &harness,
+        authed_get("/api/dashboard/system/uptime-history", &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["restarts"], 1);
+    assert_eq!(body["unclean_shutdowns"], 1);
+    let runs = body["runs"].as_array().unwrap();
+    assert_eq!(runs[0]["pid"], std::process::id());
+    assert_eq!(runs[0]["shutdown_reason"], Value::Null);
+    assert_eq!(runs[1]["pid"], 42);
+    assert_eq!(runs[1]["shutdown_reason"], "unclean");
+
+    // The file already holds the current run, so a crash now is detected next start.
+    let saved: Value = serde_json::from_slice(
+        &std::fs::read(harness._temp_dir.path().join("history.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_translate_playground_round_trip() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/translate",
+        &token,
+        json!({
+            "source_format": "openai",
+            "target_format": "claude",
+            "body": {
+                "model": "claude-sonnet-4",
+                "messages": [
+                    {"role": "system", "content": "Be brief."},
+                    {"role": "user", "content": "Hi"}
+                ]
+            },
+            "response": {
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-sonnet-4",
+                "content": [{"type": "text", "text": "Hello!"}],
+                "stop_reason": "end_turn",
+                "usage": {"input_tokens": 5, "output_tokens": 2}
+            }
+        }),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "translate failed: {body:?}");
+    assert_eq!(body["model"], "claude-sonnet-4");
+    assert_eq!(body["stream"], false);
+    // The system message moves to Claude's top-level `system` field.
+    assert!(body["request"]["system"].to_string().contains("Be brief."));
+    assert_eq!(body["request"]["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        body["response"]["choices"][0]["message"]["content"],
+        "Hello!"
+    );
+    assert!(body.get("stream_events").is_none());
+
+    let req = authed_post(
+        "/api/dashboard/translate",
+        &token,
+        json!({
+            "source_format": "claude",
+            "target_format": "openai",
+            "body": "not a request"
+        }),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "validation_failed");
+}
+
+#[tokio::test]
+async fn test_token_refresh() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post("/api/dashboard/auth/refresh", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["authenticated"], true);
+    assert_eq!(body["username"], "admin");
+    assert_eq!(body["expires_in"], 3600);
+}
+
+#[tokio::test]
+async fn test_session_probe_without_dashboard_session_returns_unauthenticated() {
+    let harness = create_test_harness();
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/dashboard/auth/session")
+        .body(Body::empty())
+        .unwrap();
+
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["authenticated"], false);
+    assert!(body["username"].is_null());
+}
+
+#[tokio::test]
+async fn test_session_probe_with_valid_token_returns_authenticated() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_get("/api/dashboard/auth/session", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["authenticated"], true);
+    assert_eq!(body["username"], "admin");
+}
+
+// ===========================================================================
+// Provider CRUD tests
+// ===========================================================================
+
+#[tokio::test]
+async fn test_list_providers_empty() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_get("/api/dashboard/providers", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let providers = body["providers"]
+        .as_array()
+        .expect("providers should be array");
+    assert!(
+        providers.is_empty(),
+        "initially there should be no providers"
+    );
+}
+
+#[tokio::test]
+async fn test_create_provider_and_list() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    // Create a provider
+    let create_body = json!({
+        "format": "openai",
+        "api_key": "sk-test-key-1234567890abcdef",
+        "name": "Test OpenAI"
+    });
+    let req = authed_post("/api/dashboard/providers", &token, create_body);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {body:?}");
+
+    // Reload config into state so list sees the new provider
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    // List providers
+    let req = authed_get("/api/dashboard/providers", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let providers = body["providers"].as_array().unwrap();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0]["format"], "openai");
+    assert_eq!(providers[0]["name"], "Test OpenAI");
+    // API key should be masked
+    let masked = providers[0]["api_key_masked"].as_str().unwrap();
+    assert!(masked.contains("****"), "API key should be masked");
+    assert!(
+        !masked.contains("sk-test-key-1234567890abcdef"),
+        "full key should not appear"
+    );
+}
+
+#[tokio::test]
+async fn test_get_provider_by_name() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    // Create a provider
+    let create_body = json!({
+        "format": "claude",
+        "api_key": "sk-ant-REDACTED",
+        "name": "Test Claude Provider",
+        "base_url": "https://api.anthropic.com"
+    });
+    let req = authed_post("/api/dashboard/providers", &token, create_body);
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Reload config
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    // Get the provider by name
+    let req = authed_get("/api/dashboard/providers/Test%20Claude%20Provider", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["format"], "claude");
+    assert_eq!(body["name"], "Test Claude Provider");
+    assert_eq!(body["base_url"], "https://api.anthropic.com");
+    // API key should be masked
+    let masked = body["api_key_masked"].as_str().unwrap();
+    assert!(masked.contains("****"));
+}
+
+#[tokio::test]
+async fn test_get_provider_not_found() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_get("/api/dashboard/providers/nonexistent-provider", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "not_found");
+}
+
+#[tokio::test]
+async fn test_update_provider() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    // Create a provider
+    let create_body = json!({
+        "format": "openai",
+        "api_key": "sk-test-key-1234567890abcdef",
+        "name": "Original Name"
+    });
+    let req = authed_post("/api/dashboard/providers", &token, create_body);
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Reload config
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    // Update the provider (name is immutable, not included in update body)
+    let update_body = json!({
+        "disabled": true
+    });
+    let req = authed_patch(
+        "/api/dashboard/providers/Original%20Name",
+        &token,
+        update_body,
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "update failed: {body:?}");
+
+    // Reload and verify
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    let req = authed_get("/api/dashboard/providers/Original%20Name", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "Original Name");
+    assert_eq!(body["disabled"], true);
+}
+
+#[tokio::test]
+async fn test_update_provider_preserves_existing_codex_upstream() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let create_body = json!({
+        "format": "openai",
+        "upstream": "codex",
+        "name": "Codex Gateway",
+        "base_url": "https://chatgpt.com/backend-api/codex",
+        "wire_api": "responses",
+        "auth_profiles": [
+            { "id": "codex-user", "mode": "codex-oauth" }
+        ]
+    });
+    let req = authed_post("/api/dashboard/providers", &token, create_body);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {body:?}");
+
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    let update_body = json!({
+        "disabled": true
+    });
+    let req = authed_patch(
+        "/api/dashboard/providers/Codex%20Gateway",
+        &token,
+        update_body,
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "update failed: {body:?}");
+
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    let req = authed_get("/api/dashboard/providers/Codex%20Gateway", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["upstream"], "codex");
+    assert_eq!(body["wire_api"], "responses");
+    assert_eq!(body["disabled"], true);
+}
+
+#[tokio::test]
+async fn test_delete_provider() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    // Create a provider
+    let create_body = json!({
+        "format": "gemini",
+        "api_key": "gemini-test-key-1234567890abcdef",
+        "name": "Gemini Test"
+    });
+    let req = authed_post("/api/dashboard/providers", &token, create_body);
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Reload config
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    // Verify it exists
+    let req = authed_get("/api/dashboard/providers", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["providers"].as_array().unwrap().len(), 1);
+
+    // Delete the provider
+    let req = authed_delete("/api/dashboard/providers/Gemini%20Test", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "delete failed: {body:?}");
+
+    // Reload config and verify deletion
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let new_config = Config::load(&config_path).expect("failed to reload config");
+    harness.state.config.store(Arc::new(new_config));
+
+    let req = authed_get("/api/dashboard/providers", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["providers"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_disable_and_enable_provider_toggle_routing() {
+    let upstream = MockUpstream::start(Format::OpenAI).await;
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "toggled",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: Some(upstream.base_url()),
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+    let chat = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"model": "gpt-4o", "messages": [{"role": "user", "content": "hi"}]})
+                    .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let req = authed_post(
+        "/api/dashboard/providers/toggled/disable",
+        &token,
+        json!({}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["disabled"], true);
+    assert_eq!(body["changed"], true);
+    assert!(harness.state.config.load().providers[0].disabled);
+    let (status, _) = send_request(&harness, chat()).await;
+    assert_ne!(status, StatusCode::OK);
+    assert_eq!(upstream.request_count(), 0);
+
+    let req = authed_post("/api/dashboard/providers/toggled/enable", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["disabled"], false);
+    let (status, body) = send_request(&harness, chat()).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    // Already enabled: nothing to write.
+    let req = authed_post("/api/dashboard/providers/toggled/enable", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["changed"], false);
+
+    let req = authed_post(
+        "/api/dashboard/providers/missing/disable",
+        &token,
+        json!({}),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_tag_filters_on_providers_and_logs() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let fixture = |name| ProviderFixture {
+        name,
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-test",
+        base_url: None,
+        region: None,
+    };
+    let mut ml = provider_entry(fixture("ml-prod"));
+    ml.tags = vec!["team:ml".to_string(), "env:prod".to_string()];
+    config.providers = vec![ml, provider_entry(fixture("search"))];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+
+    let names = |body: &Value| -> Vec<String> {
+        body["providers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let req = authed_get("/api/dashboard/providers?tags=team:ml", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["ml-prod"]);
+    assert_eq!(body["providers"][0]["tags"], json!(["team:ml", "env:prod"]));
+
+    let req = authed_patch(
+        "/api/dashboard/providers/search",
+        &token,
+        json!({"tags": ["team:search", "env:prod"]}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let req = authed_get("/api/dashboard/providers?tags=env:prod", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(names(&body), ["ml-prod", "search"]);
+    let req = authed_get("/api/dashboard/providers?tags=env:prod,team:search", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(names(&body), ["search"]);
+
+    for (id, tags) in [("req-ml", json!(["team:ml"])), ("req-untagged", json!([]))] {
+        let record: RequestRecord = serde_json::from_value(json!({
+            "request_id": id,
+            "timestamp": Utc::now(),
+            "method": "POST",
+            "path": "/v1/chat/completions",
+            "stream": false,
+            "status": 200,
+            "latency_ms": 10,
+            "tags": tags,
+        }))
+        .unwrap();
+        harness.state.log_store.push(record).await;
+    }
+    let req = authed_get("/api/dashboard/logs?tags=team:ml", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["request_id"], "req-ml");
+    let req = authed_get("/api/dashboard/logs/stats?tags=team:ml", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(body["total_entries"], 1);
+    let req = authed_get("/api/dashboard/logs/filters", &token);
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(body["tags"], json!(["team:ml"]));
+}
+
+#[tokio::test]
+async fn test_costs_by_tag_report() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    for (id, tags, cost) in [
+        ("req-1", json!(["team:ml", "env:prod"]), 0.5),
+        ("req-2", json!(["team:ml"]), 0.25),
+        ("req-3", json!(["team:search"]), 1.0),
+    ] {
+        let record: RequestRecord = serde_json::from_value(json!({
+            "request_id": id,
+            "timestamp": Utc::now(),
+            "method": "POST",
+            "path": "/v1/chat/completions",
+            "stream": false,
+            "status": 200,
+            "latency_ms": 10,
+            "usage": {"input_tokens": 100, "output_tokens": 10},
+            "cost": cost,
+            "tags": tags,
+        }))
+        .unwrap();
+        harness.state.log_store.push(record).await;
+    }
+
+    let req = authed_get("/api/dashboard/costs/by-tag?prefix=team:", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["total_requests"], 3);
+    assert_eq!(body["total_cost"], 1.75);
+    assert_eq!(body["tags"][0]["tag"], "team:search");
+    assert_eq!(body["tags"][1]["tag"], "team:ml");
+    assert_eq!(body["tags"][1]["requests"], 2);
+    assert_eq!(body["tags"][1]["input_tokens"], 200);
+    assert_eq!(body["tags"][1]["cost"], 0.75);
+    assert_eq!(body["untagged"]["requests"], 0);
+
+    let future = (Utc::now() + ChronoDuration::hours(1)).timestamp_millis();
+    let req = authed_get(
+        &format!("/api/dashboard/costs/by-tag?from={future}"),
+        &token,
+    );
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(body["total_requests"], 0);
+    assert_eq!(body["tags"], json!([]));
+}
+
+#[tokio::test]
+async fn test_send_usage_report_posts_to_webhook() {
+    let webhook = MockUpstream::start(Format::OpenAI).await;
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let mut config = (*harness.state.config.load_full()).clone();
+    config.reports = vec![UsageReportConfig {
+        name: "daily-slack".to_string(),
+        period: Default::default(),
+        hour_utc: 9,
+        webhook_url: format!("{}/hooks/usage", webhook.base_url()),
+        format: ReportFormat::Slack,
+        top: 5,
+    }];
+    harness.state.config.store(Arc::new(config));
+    let record: RequestRecord = serde_json::from_value(json!({
+        "request_id": "req-1",
+        "timestamp": Utc::now(),
+        "method": "POST",
+        "path": "/v1/chat/completions",
+        "stream": false,
+        "status": 200,
+        "latency_ms": 10,
+        "model": "gpt-4o",
+        "api_key_id": "team-a",
+        "usage": {"input_tokens": 100, "output_tokens": 20},
+        "cost": 0.5,
+    }))
+    .unwrap();
+    harness.state.log_store.push(record).await;
+
+    let req = authed_post("/api/dashboard/reports/daily-slack/send", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["usage"]["requests"], 1);
+    assert_eq!(body["usage"]["by_model"][0]["name"], "gpt-4o");
+
+    let delivered = webhook.last_request();
+    assert_eq!(delivered.path, "/hooks/usage");
+    let text = delivered.json()["text"].as_str().unwrap().to_string();
+    assert!(text.contains("daily usage report: daily-slack"), "{text}");
+    assert!(
+        text.contains("- gpt-4o: 1 requests, 120 tokens, $0.50"),
+        "{text}"
+    );
+    assert!(text.contains("- team-a:"), "{text}");
+
+    webhook.push(MockReply::error(Format::OpenAI, 500, "down"));
+    let req = authed_post("/api/dashboard/reports/daily-slack/send", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["error"], "delivery_failed");
+
+    let req = authed_post("/api/dashboard/reports/missing/send", &token, json!({}));
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_openapi_document_is_public() {
+    let harness = create_test_harness();
+    let req = Request::builder()
+        .uri("/api/openapi.json")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["openapi"], "3.1.0");
+    assert!(body["paths"]["/v1/chat/completions"]["post"].is_object());
+    assert!(body["paths"]["/api/v1/dashboard/logs"]["get"]["parameters"].is_array());
+    assert!(body["components"]["schemas"]["TagCostReport"].is_object());
+}
+
+#[tokio::test]
+async fn test_versioned_paths_and_deprecated_aliases() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let router = build_router(harness.state.clone());
+
+    let response = router
+        .clone()
+        .oneshot(authed_get("/api/v1/dashboard/logs?page=1", &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-prism-api-version"], "1");
+    assert!(response.headers().get("deprecation").is_none());
+
+    let response = router
+        .clone()
+        .oneshot(authed_get("/api/dashboard/logs", &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["deprecation"], "@1791936000");
+    assert_eq!(
+        response.headers()["sunset"],
+        "Wed, 14 Apr 2027 00:00:00 GMT"
+    );
+    assert_eq!(
+        response.headers()["link"],
+        "</api/v1/dashboard/logs>; rel=\"successor-version\""
+    );
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/admin/models")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, body) = send_request(&harness, authed_get("/api/v2/dashboard/logs", &token)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unsupported_api_version");
+
+    let mut request = authed_get("/api/dashboard/logs", &token);
+    request
+        .headers_mut()
+        .insert("x-prism-api-version", "2".parse().unwrap());
+    let (status, _) = send_request(&harness, request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_client_round_trips_against_live_server() {
+    use prism_client::types::{CreateAuthKey, CreateProvider};
+    use prism_core::request_log::{LogQuery, StatsQuery, TagCostQuery};
+
+    let harness = create_test_harness();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(harness.state.clone());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut client = prism_client::Client::new(format!("http://{addr}"));
+    let login = client.login("admin", "test123").await.unwrap();
+    assert!(login.authenticated);
+    assert!(client.token().is_some());
+    assert!(
+        client
+            .config_version("/api/v1/dashboard/providers")
+            .is_none()
+    );
+
+    let mut provider = CreateProvider::new("team-openai", Format::OpenAI);
+    provider.upstream = Some(UpstreamKind::OpenAI);
+    provider.api_key = Some("sk-client-test-0001".to_string());
+    provider.models = vec!["gpt-4o".to_string()];
+    provider.tags = vec!["team:ml".to_string()];
+    // A write to a resource never read sends the current version, not `If-Match: *`.
+    client.create_provider(&provider).await.unwrap();
+    let providers = client.list_providers(Some("team:ml")).await.unwrap();
+    assert!(
+        client
+            .config_version("/api/v1/dashboard/providers/team-openai")
+            .is_some()
+    );
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].format, Format::OpenAI);
+    assert_eq!(providers[0].upstream, UpstreamKind::OpenAI);
+    let detail = client.get_provider("team-openai").await.unwrap();
+    assert_eq!(detail.models[0].id, "gpt-4o");
+    let stale =
+        prism_client::Client::new(format!("http://{addr}")).with_token(client.token().unwrap());
+    stale.get_provider("team-openai").await.unwrap();
+    let toggled = client.disable_provider("team-openai").await.unwrap();
+    assert!(toggled.disabled && toggled.changed);
+    assert!(matches!(
+        stale.enable_provider("team-openai").await.unwrap_err(),
+        prism_client::Error::Api { status: 409, .. }
+    ));
+
+    let created = client
+        .create_auth_key(&CreateAuthKey {
+            name: Some("ci".to_string()),
+            tags: vec!["team:ml".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(created.key.starts_with("sk-proxy-"));
+    let keys = client.list_auth_keys().await.unwrap();
+    assert_eq!(keys[0].name.as_deref(), Some("ci"));
+    assert!(client.disable_auth_key(&keys[0].id).await.unwrap().disabled);
+
+    let record: RequestRecord = serde_json::from_value(json!({
+        "request_id": "req-client",
+        "timestamp": Utc::now(),
+        "method": "POST",
+        "path": "/v1/chat/completions",
+        "stream": false,
+        "status": 200,
+        "latency_ms": 10,
+        "model": "gpt-4o",
+        "usage": {"input_tokens": 10, "output_tokens": 5},
+        "cost": 0.25,
+        "tags": ["team:ml"],
+    }))
+    .unwrap();
+    harness.state.log_store.push(record).await;
+    let page = client
+        .query_logs(&LogQuery {
+            tags: Some("team:ml".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(
+        client.get_log("req-client").await.unwrap().model.as_deref(),
+        Some("gpt-4o")
+    );
+    assert_eq!(
+        client
+            .log_stats(&StatsQuery::default())
+            .await
+            .unwrap()
+            .total_entries,
+        1
+    );
+    assert_eq!(client.log_filters().await.unwrap().tags, ["team:ml"]);
+    let costs = client.costs_by_tag(&TagCostQuery::default()).await.unwrap();
+    assert_eq!(costs.tags[0].tag, "team:ml");
+
+    let err = client.get_provider("missing").await.unwrap_err();
+    assert!(
+        matches!(err, prism_client::Error::Api { status: 404, ref code, .. } if code == "not_found"),
+        "{err}"
+    );
+    let anonymous = prism_client::Client::new(format!("http://{addr}"));
+    assert!(matches!(
+        anonymous.list_auth_keys().await.unwrap_err(),
+        prism_client::Error::Api { status: 401, .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_bulk_create_providers_reports_per_item_results() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/providers/bulk",
+        &token,
+        json!({"providers": [
+            {"name": "pool-1", "format": "openai", "api_key": "sk-pool-key-0001"},
+            {"name": "pool-2", "format": "openai", "api_key": "sk-pool-key-0002"},
+            {"name": "pool-1", "format": "openai", "api_key": "sk-pool-key-dupe"},
+            {"name": "pool-3", "format": "invalid"},
+        ]}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED, "bulk create failed: {body:?}");
+    assert_eq!(body["created"], 2);
+    assert_eq!(body["rejected"], 2);
+    assert_eq!(body["results"][2]["status"], "rejected");
+    assert_eq!(body["results"][2]["error"]["error"], "duplicate_name");
+    assert_eq!(body["results"][3]["error"]["error"], "validation_failed");
+
+    let names: Vec<String> = harness
+        .state
+        .config
+        .load()
+        .providers
+        .iter()
+        .map(|p| p.name.clone())
+        .collect();
+    assert_eq!(names, vec!["pool-1", "pool-2"]);
+
+    let req = authed_post(
+        "/api/dashboard/providers/bulk",
+        &token,
+        json!({"providers": [{"name": "", "format": "openai"}]}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["created"], 0);
+
+    // A name taken only in the file is caught under the write lock, and a batch
+    // with nothing left to create writes nothing.
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let mut on_disk =
+        Config::from_yaml_raw(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    let mut file_only = on_disk.providers[0].clone();
+    file_only.id = None;
+    file_only.name = "file-only".to_string();
+    on_disk.providers.push(file_only);
+    std::fs::write(&config_path, on_disk.to_yaml().unwrap()).unwrap();
+    let req = authed_post(
+        "/api/dashboard/providers/bulk",
+        &token,
+        json!({"providers": [
+            {"name": "file-only", "format": "openai", "api_key": "sk-pool-key-0003"},
+        ]}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body:?}");
+    assert_eq!(body["results"][0]["error"]["error"], "duplicate_name");
+    assert_eq!(
+        std::fs::read_to_string(&config_path).unwrap(),
+        on_disk.to_yaml().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_duplicate_provider_with_new_key() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/providers",
+        &token,
+        json!({
+            "name": "source",
+            "format": "openai",
+            "api_key": "sk-source-key-123456",
+            "base_url": "https://api.example.com",
+            "models": ["gpt-4o"],
+        }),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let req = authed_post(
+        "/api/dashboard/providers/source/duplicate",
+        &token,
+        json!({"name": "source-copy", "api_key": "sk-copy-key-654321"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED, "duplicate failed: {body:?}");
+
+    let config = harness.state.config.load();
+    let copy = config
+        .providers
+        .iter()
+        .find(|p| p.name == "source-copy")
+        .expect("clone should exist");
+    assert_eq!(copy.api_key, "sk-copy-key-654321");
+    assert_eq!(copy.id.as_deref(), body["id"].as_str());
+    assert_eq!(copy.base_url.as_deref(), Some("https://api.example.com"));
+    assert_eq!(copy.models[0].id, "gpt-4o");
+
+    let req = authed_post(
+        "/api/dashboard/providers/source/duplicate",
+        &token,
+        json!({"name": "source-copy"}),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let req = authed_post(
+        "/api/dashboard/providers/missing/duplicate",
+        &token,
+        json!({"name": "other"}),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Deleted from the file after the runtime config was loaded: the write
+    // finds no source and nothing is created.
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    let mut on_disk =
+        Config::from_yaml_raw(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    on_disk.providers.retain(|p| p.name != "source");
+    std::fs::write(&config_path, on_disk.to_yaml().unwrap()).unwrap();
+    let req = authed_post(
+        "/api/dashboard/providers/source/duplicate",
+        &token,
+        json!({"name": "late-copy"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body:?}");
+    let runtime = harness.state.config.load();
+    assert!(!runtime.providers.iter().any(|p| p.name == "late-copy"));
+    // The aborted write neither persisted nor reloaded the config.
+    assert!(runtime.providers.iter().any(|p| p.name == "source"));
+    assert_eq!(
+        std::fs::read_to_string(&config_path).unwrap(),
+        on_disk.to_yaml().unwrap()
+    );
+
+    // A name taken only in the file is caught under the write lock.
+    let mut file_only = on_disk
+        .providers
+        .iter()
+        .find(|p| p.name == "source-copy")
+        .unwrap()
+        .clone();
+    file_only.id = None;
+    file_only.name = "file-only".to_string();
+    on_disk.providers.push(file_only);
+    std::fs::write(&config_path, on_disk.to_yaml().unwrap()).unwrap();
+    let req = authed_post(
+        "/api/dashboard/providers/source-copy/duplicate",
+        &token,
+        json!({"name": "file-only"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body:?}");
+}
+
+#[tokio::test]
+async fn test_provider_routes_accept_persistent_id() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/providers",
+        &token,
+        json!({
+            "name": "by-id",
+            "format": "openai",
+            "api_key": "sk-by-id-key-123456",
+            "models": ["gpt-4o"],
+        }),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = body["id"]
+        .as_str()
+        .expect("create should return id")
+        .to_string();
+
+    let (status, body) =
+        send_request(&harness, authed_get("/api/dashboard/providers", &token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["providers"][0]["id"], id);
+
+    let uri = format!("/api/dashboard/providers/{id}");
+    let (status, body) = send_request(&harness, authed_get(&uri, &token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "by-id");
+
+    let req = authed_patch(&uri, &token, json!({"disabled": true}));
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let config = harness.state.config.load();
+    let entry = config.find_provider(&id).expect("provider should exist");
+    assert!(entry.disabled);
+    assert_eq!(entry.id.as_deref(), Some(id.as_str()));
+    drop(config);
+
+    // Name lookups keep working as a fallback.
+    let req = authed_get("/api/dashboard/providers/by-id", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], id);
+
+    let (status, _) = send_request(&harness, authed_delete(&uri, &token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(harness.state.config.load().providers.is_empty());
+}
+
+#[tokio::test]
+async fn test_stale_if_match_rejects_dashboard_write() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let response = build_router(harness.state.clone())
+        .oneshot(authed_get("/api/dashboard/routing", &token))
+        .await
+        .expect("request failed");
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response
+        .headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .expect("reads should carry an etag")
+        .to_string();
+
+    // Only config-backed reads are versioned; other dashboard reads carry no ETag.
+    let response = build_router(harness.state.clone())
+        .oneshot(authed_get("/api/dashboard/logs", &token))
+        .await
+        .expect("request failed");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("etag").is_none());
+
+    let create = |if_match: &str, name: &str| {
+        let mut req = authed_post("/api/dashboard/auth-keys", &token, json!({"name": name}));
+        req.headers_mut()
+            .insert("if-match", if_match.parse().unwrap());
+        req
+    };
+
+    let (status, _) = send_request(&harness, create(&etag, "first")).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // The same (now stale) version must not overwrite the first write.
+    let (status, body) = send_request(&harness, create(&etag, "second")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "config_conflict");
+    let current = body["current_version"].as_str().expect("current version");
+    assert_ne!(format!("\"{current}\""), etag);
+    assert_eq!(harness.state.config.load().auth_keys.len(), 1);
+
+    let (status, _) = send_request(&harness, create(current, "second")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(harness.state.config.load().auth_keys.len(), 2);
+}
+
+#[test]
+fn test_proxy_builder_requires_config_file_for_sqlite_store() {
+    let mut config = Config::default();
+    config.dashboard.managed_store.backend = prism_core::managed_store::ManagedStoreBackend::Sqlite;
+    let err = prism_server::builder::ProxyBuilder::new(config)
+        .build()
+        .err()
+        .expect("sqlite store without a config file should be rejected");
+    assert!(err.to_string().contains("needs a config file"), "{err}");
+}
+
+#[tokio::test]
+async fn test_sqlite_managed_store_keeps_providers_out_of_yaml() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let mut config = (**harness.state.config.load()).clone();
+    config.dashboard.managed_store.backend = prism_core::managed_store::ManagedStoreBackend::Sqlite;
+    write_test_config(&harness, &config);
+
+    // Reads leave the store alone; only the write below creates it.
     let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let db_path = prism_core::managed_store::resolve_path(&config_path, "prism-state.db");
+    let (status, _) = send_request(&harness, authed_get("/api/dashboard/config/raw", &token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!db_path.exists());
 
-    // Update the provider (name is immutable, not included in update body)
-    let update_body = json!({
-        "disabled": true
-    });
-    let req = authed_patch(
-        "/api/dashboard/providers/Original%20Name",
+    let req = authed_post(
+        "/api/dashboard/providers",
         &token,
-        update_body,
+        json!({
+            "name": "stored",
+            "format": "openai",
+            "api_key": "sk-stored-key-123456",
+            "models": ["gpt-4o"],
+        }),
     );
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK, "update failed: {body:?}");
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(db_path.exists());
 
-    // Reload and verify
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let on_disk = Config::from_yaml_raw(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert!(
+        on_disk.providers.is_empty(),
+        "providers must not be written to YAML"
+    );
 
-    let req = authed_get("/api/dashboard/providers/Original%20Name", &token);
-    let (status, body) = send_request(&harness, req).await;
+    // A reload from disk still sees the store-managed provider.
+    reload_runtime_config(&harness);
+    assert_eq!(harness.state.config.load().providers[0].name, "stored");
+
+    let (status, body) =
+        send_request(&harness, authed_get("/api/dashboard/config/raw", &token)).await;
     assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["name"], "Original Name");
-    assert_eq!(body["disabled"], true);
+    assert!(body["content"].as_str().unwrap().contains("stored"));
 }
 
 #[tokio::test]
-async fn test_update_provider_preserves_existing_codex_upstream() {
+async fn test_provider_update_preserves_yaml_comments() {
     let harness = create_test_harness();
     let token = login_and_get_token(&harness).await;
 
-    let create_body = json!({
-        "format": "openai",
-        "upstream": "codex",
-        "name": "Codex Gateway",
-        "base_url": "https://chatgpt.com/backend-api/codex",
-        "wire_api": "responses",
-        "auth_profiles": [
-            { "id": "codex-user", "mode": "codex-oauth" }
-        ]
-    });
-    let req = authed_post("/api/dashboard/providers", &token, create_body);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::CREATED, "create failed: {body:?}");
+    let req = authed_post(
+        "/api/dashboard/providers",
+        &token,
+        json!({
+            "name": "commented",
+            "format": "openai",
+            "api_key": "sk-commented-key-123456",
+            "models": ["gpt-4o"],
+        }),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
 
     let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let written = std::fs::read_to_string(&config_path).unwrap();
+    let annotated = format!(
+        "# operator note\n{}",
+        written.replacen(
+            "format: openai",
+            "format: openai  # upstream wire format",
+            1
+        )
+    );
+    std::fs::write(&config_path, &annotated).unwrap();
 
-    let update_body = json!({
-        "disabled": true
-    });
     let req = authed_patch(
-        "/api/dashboard/providers/Codex%20Gateway",
+        "/api/dashboard/providers/commented",
         &token,
-        update_body,
+        json!({"disabled": true}),
     );
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK, "update failed: {body:?}");
 
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
-
-    let req = authed_get("/api/dashboard/providers/Codex%20Gateway", &token);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["upstream"], "codex");
-    assert_eq!(body["wire_api"], "responses");
-    assert_eq!(body["disabled"], true);
+    let rewritten = std::fs::read_to_string(&config_path).unwrap();
+    assert!(rewritten.starts_with("# operator note\n"));
+    assert!(rewritten.contains("format: openai  # upstream wire format"));
+    assert!(harness.state.config.load().providers[0].disabled);
 }
 
 #[tokio::test]
-async fn test_delete_provider() {
+async fn test_dashboard_write_without_if_match_is_rejected() {
     let harness = create_test_harness();
     let token = login_and_get_token(&harness).await;
 
-    // Create a provider
-    let create_body = json!({
-        "format": "gemini",
-        "api_key": "gemini-test-key-1234567890abcdef",
-        "name": "Gemini Test"
-    });
-    let req = authed_post("/api/dashboard/providers", &token, create_body);
-    let (status, _) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::CREATED);
-
-    // Reload config
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
-
-    // Verify it exists
-    let req = authed_get("/api/dashboard/providers", &token);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["providers"].as_array().unwrap().len(), 1);
+    let etag = |response: &axum::response::Response| {
+        response.headers()["etag"].to_str().unwrap().to_string()
+    };
+    let read = || {
+        build_router(harness.state.clone()).oneshot(authed_get("/api/dashboard/routing", &token))
+    };
+    let first = etag(&read().await.unwrap());
+    assert_eq!(etag(&read().await.unwrap()), first);
 
-    // Delete the provider
-    let req = authed_delete("/api/dashboard/providers/Gemini%20Test", &token);
+    let mut req = authed_post("/api/dashboard/auth-keys", &token, json!({"name": "ci"}));
+    req.headers_mut().remove("if-match");
     let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK, "delete failed: {body:?}");
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    assert_eq!(body["error"], "precondition_required");
+    assert_eq!(
+        format!("\"{}\"", body["current_version"].as_str().unwrap()),
+        first
+    );
+    assert!(harness.state.config.load().auth_keys.is_empty());
 
-    // Reload config and verify deletion
-    let config_path = harness.state.config_path.lock().unwrap().clone();
-    let new_config = Config::load(&config_path).expect("failed to reload config");
-    harness.state.config.store(Arc::new(new_config));
+    let mut req = authed_put("/api/dashboard/config/apply", &token, json!({"yaml": ""}));
+    req.headers_mut().remove("if-match");
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
 
-    let req = authed_get("/api/dashboard/providers", &token);
-    let (status, body) = send_request(&harness, req).await;
-    assert_eq!(status, StatusCode::OK);
-    assert!(body["providers"].as_array().unwrap().is_empty());
+    let mut req = authed_post("/api/dashboard/auth-keys", &token, json!({"name": "ci"}));
+    req.headers_mut().insert("if-match", first.parse().unwrap());
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_ne!(etag(&read().await.unwrap()), first);
 }
 
 #[tokio::test]
@@ -1847,16 +6172,17 @@ async fn test_delete_auth_key() {
 
     // Create a key
     let req = authed_post("/api/dashboard/auth-keys", &token, json!({}));
-    let (status, _) = send_request(&harness, req).await;
+    let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::CREATED);
+    let id = body["id"].as_str().unwrap().to_string();
 
     // Reload config
     let config_path = harness.state.config_path.lock().unwrap().clone();
     let new_config = Config::load(&config_path).expect("failed to reload config");
     harness.state.config.store(Arc::new(new_config));
 
-    // Delete the key (id = 0)
-    let req = authed_delete("/api/dashboard/auth-keys/0", &token);
+    // Delete the key
+    let req = authed_delete(&format!("/api/dashboard/auth-keys/{id}"), &token);
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK, "delete auth key failed: {body:?}");
 
@@ -1871,6 +6197,111 @@ async fn test_delete_auth_key() {
     assert!(body["auth_keys"].as_array().unwrap().is_empty());
 }
 
+#[tokio::test]
+async fn test_disabled_auth_key_is_rejected_until_enabled() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.auth_keys = vec![AuthKeyEntry {
+        id: None,
+        key: "sk-proxy-toggle".to_string(),
+        name: Some("toggle".to_string()),
+        tenant_id: None,
+        allowed_models: Vec::new(),
+        allowed_credentials: Vec::new(),
+        rate_limit: None,
+        budget: None,
+        expires_at: None,
+        metadata: HashMap::new(),
+        moderation: None,
+        routing_hints: false,
+        disabled: false,
+        tags: Vec::new(),
+    }];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+    let models = || {
+        Request::builder()
+            .uri("/v1/models")
+            .header("authorization", "Bearer sk-proxy-toggle")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let (status, _) = send_request(&harness, models()).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = send_request(&harness, authed_get("/api/dashboard/auth-keys", &token)).await;
+    let id = body["auth_keys"][0]["id"].as_str().unwrap().to_string();
+
+    let req = authed_post(
+        &format!("/api/dashboard/auth-keys/{id}/disable"),
+        &token,
+        json!({}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["changed"], true);
+    assert_eq!(body["id"], id.as_str());
+    let (status, _) = send_request(&harness, models()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (_, body) = send_request(&harness, authed_get("/api/dashboard/auth-keys", &token)).await;
+    assert_eq!(body["auth_keys"][0]["disabled"], true);
+
+    let req = authed_post(
+        &format!("/api/dashboard/auth-keys/{id}/enable"),
+        &token,
+        json!({}),
+    );
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_request(&harness, models()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let req = authed_post("/api/dashboard/auth-keys/0/disable", &token, json!({}));
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_auth_key_id_survives_removal_of_earlier_key() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let req = authed_post("/api/dashboard/auth-keys", &token, json!({}));
+        let (status, body) = send_request(&harness, req).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        ids.push(body["id"].as_str().unwrap().to_string());
+    }
+
+    let req = authed_delete(&format!("/api/dashboard/auth-keys/{}", ids[0]), &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    // The second key is now first in the file but keeps its id.
+    let req = authed_post(
+        &format!("/api/dashboard/auth-keys/{}/disable", ids[1]),
+        &token,
+        json!({}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["changed"], true);
+    let (_, body) = send_request(&harness, authed_get("/api/dashboard/auth-keys", &token)).await;
+    assert_eq!(body["auth_keys"][0]["id"], ids[1].as_str());
+    assert_eq!(body["auth_keys"][0]["disabled"], true);
+
+    // The deleted key's id no longer resolves, and a repeat toggle writes nothing.
+    let req = authed_delete(&format!("/api/dashboard/auth-keys/{}", ids[0]), &token);
+    let (status, _) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let req = authed_post(
+        &format!("/api/dashboard/auth-keys/{}/disable", ids[1]),
+        &token,
+        json!({}),
+    );
+    let (_, body) = send_request(&harness, req).await;
+    assert_eq!(body["changed"], false);
+}
+
 // ===========================================================================
 // Routing tests
 // ===========================================================================
@@ -2007,6 +6438,7 @@ async fn test_log_stats_with_entries() {
         .log_store
         .push(prism_core::request_record::RequestRecord {
             request_id: "req-1".to_string(),
+            trace_id: None,
             timestamp: chrono::Utc::now(),
             method: "POST".to_string(),
             path: "/v1/chat/completions".to_string(),
@@ -2034,7 +6466,13 @@ async fn test_log_stats_with_entries() {
             tenant_id: None,
             client_ip: None,
             client_region: None,
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         })
         .await;
     harness
@@ -2042,6 +6480,7 @@ async fn test_log_stats_with_entries() {
         .log_store
         .push(prism_core::request_record::RequestRecord {
             request_id: "req-2".to_string(),
+            trace_id: None,
             timestamp: chrono::Utc::now(),
             method: "POST".to_string(),
             path: "/v1/chat/completions".to_string(),
@@ -2065,7 +6504,13 @@ async fn test_log_stats_with_entries() {
             tenant_id: None,
             client_ip: None,
             client_region: None,
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         })
         .await;
 
@@ -2088,6 +6533,7 @@ async fn test_query_logs_with_entries() {
             .log_store
             .push(prism_core::request_record::RequestRecord {
                 request_id: format!("req-{i}"),
+                trace_id: None,
                 timestamp: chrono::Utc::now(),
                 method: "POST".to_string(),
                 path: "/v1/chat/completions".to_string(),
@@ -2119,7 +6565,13 @@ async fn test_query_logs_with_entries() {
                 tenant_id: None,
                 client_ip: None,
                 client_region: None,
+                experiment: None,
+                experiment_arm: None,
+                tags: Vec::new(),
                 attempts: vec![],
+                slow: false,
+                stream_truncated: None,
+                timings: None,
             })
             .await;
     }
@@ -2132,6 +6584,66 @@ async fn test_query_logs_with_entries() {
     assert_eq!(items.len(), 5);
 }
 
+#[tokio::test]
+async fn test_log_error_groups() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    for i in 0..4 {
+        let status = if i == 0 { 200 } else { 429 };
+        harness
+            .state
+            .log_store
+            .push(prism_core::request_record::RequestRecord {
+                request_id: format!("req-{i}"),
+                trace_id: None,
+                timestamp: chrono::Utc::now(),
+                method: "POST".to_string(),
+                path: "/v1/chat/completions".to_string(),
+                stream: false,
+                requested_model: Some("gpt-4".to_string()),
+                request_body: None,
+                upstream_request_body: None,
+                provider: Some("openai".to_string()),
+                model: Some("gpt-4".to_string()),
+                credential_name: None,
+                total_attempts: 1,
+                status,
+                latency_ms: 100,
+                response_body: None,
+                stream_content_preview: None,
+                usage: None,
+                cost: None,
+                error: (status != 200).then(|| "rate limited".to_string()),
+                error_type: (status != 200).then(|| "rate_limited".to_string()),
+                api_key_id: None,
+                tenant_id: None,
+                client_ip: None,
+                client_region: None,
+                experiment: None,
+                experiment_arm: None,
+                tags: Vec::new(),
+                attempts: vec![],
+                slow: false,
+                stream_truncated: None,
+                timings: None,
+            })
+            .await;
+    }
+
+    let req = authed_get("/api/dashboard/logs/errors", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_errors"], 3);
+    let groups = body["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["error_type"], "rate_limited");
+    assert_eq!(groups[0]["provider"], "openai");
+    assert_eq!(groups[0]["status"], 429);
+    assert_eq!(groups[0]["count"], 3);
+    assert_eq!(groups[0]["example_request_ids"][0], "req-3");
+}
+
 // ===========================================================================
 // System tests
 // ===========================================================================
@@ -2152,6 +6664,56 @@ async fn test_system_health() {
     assert!(body["host"].is_string());
     assert!(body["port"].is_number());
     assert!(body["providers"].is_array());
+    let resources = &body["resources"];
+    assert!(resources["tokio"]["workers"].as_u64().unwrap() >= 1);
+    assert!(resources["tokio"]["alive_tasks"].is_number());
+    assert!(resources["log_buffer"]["capacity"].is_number());
+    assert_eq!(resources["connections"]["open"], 0);
+    assert_eq!(resources["connections"]["inflight_requests"], 0);
+    if cfg!(target_os = "linux") {
+        assert!(resources["process"]["rss_bytes"].as_u64().unwrap() > 0);
+        assert!(resources["process"]["open_fds"].as_u64().unwrap() > 0);
+    }
+}
+
+#[tokio::test]
+async fn test_system_health_reports_last_crash() {
+    let mut harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let crash_dir = harness._temp_dir.path().join("logs");
+    std::fs::create_dir_all(&crash_dir).unwrap();
+    std::fs::write(
+        crash_dir.join("crash-20261001T000000.000Z-42.json"),
+        json!({
+            "timestamp": "2026-10-01T00:00:00Z",
+            "pid": 42,
+            "version": "0.1.0",
+            "thread": "tokio-runtime-worker",
+            "message": "index out of bounds",
+            "location": "crates/server/src/dispatch.rs:1:1",
+            "backtrace": "",
+            "active_requests": 5,
+            "last_config_reload": null
+        })
+        .to_string(),
+    )
+    .unwrap();
+    std::fs::write(crash_dir.join("crash-count"), "3").unwrap();
+    harness.state.crash_reporter = Arc::new(prism_server::crash_report::CrashReporter::new(Some(
+        crash_dir,
+    )));
+
+    let next = harness.state.config.load().as_ref().clone();
+    prism_server::reload::apply_config(&harness.state, next).unwrap();
+
+    let req = authed_get("/api/dashboard/system/health", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["crashes"]["count"], 3);
+    assert_eq!(body["crashes"]["last"]["message"], "index out of bounds");
+    assert_eq!(body["crashes"]["last"]["active_requests"], 5);
+    assert!(body["crashes"]["last"].get("backtrace").is_none());
+    assert!(body["last_config_reload"].is_string());
 }
 
 #[tokio::test]
@@ -2400,17 +6962,113 @@ async fn test_provider_capabilities_return_identity_and_extended_probe_truth() {
 }
 
 #[tokio::test]
-async fn test_system_logs() {
+async fn test_system_logs() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    // System logs endpoint reads from log directory -- should handle missing dir gracefully
+    let req = authed_get("/api/dashboard/system/logs", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    // Even with no log directory, should return a valid response
+    assert!(body["logs"].is_array());
+    assert_eq!(body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_log_files_listing_and_download() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let log_dir = harness._temp_dir.path().join("logs");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    std::fs::write(log_dir.join("prism.2026-10-13.log"), "older\n").unwrap();
+    std::fs::write(log_dir.join("prism.2026-10-14.log"), "newer line\n").unwrap();
+    std::fs::write(log_dir.join("crash-count"), "1").unwrap();
+    let mut next = harness.state.config.load().as_ref().clone();
+    next.log_dir = Some(log_dir.display().to_string());
+    harness.state.config.store(Arc::new(next));
+
+    let req = authed_get("/api/dashboard/system/logs/files", &token);
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = body["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.len(), 2, "{body}");
+    assert!(names.contains(&"prism.2026-10-14.log"));
+    assert_eq!(body["total_bytes"], 17);
+
+    let req = authed_get(
+        "/api/dashboard/system/logs/files/prism.2026-10-14.log",
+        &token,
+    );
+    let resp = build_router(harness.state.clone())
+        .oneshot(req)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(
+        resp.headers()[axum::http::header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .contains("prism.2026-10-14.log")
+    );
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&bytes[..], b"newer line\n");
+
+    for name in ["crash-count", "..%2Fconfig.yaml"] {
+        let req = authed_get(&format!("/api/dashboard/system/logs/files/{name}"), &token);
+        let (status, _) = send_request(&harness, req).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_log_level_update_and_reset() {
     let harness = create_test_harness();
     let token = login_and_get_token(&harness).await;
 
-    // System logs endpoint reads from log directory -- should handle missing dir gracefully
-    let req = authed_get("/api/dashboard/system/logs", &token);
+    let req = authed_get("/api/dashboard/system/log-level", &token);
     let (status, body) = send_request(&harness, req).await;
     assert_eq!(status, StatusCode::OK);
-    // Even with no log directory, should return a valid response
-    assert!(body["logs"].is_array());
-    assert_eq!(body["total"], 0);
+    assert_eq!(body["level"], "info");
+
+    let req = authed_patch(
+        "/api/dashboard/system/log-level",
+        &token,
+        json!({"level": "info,prism_provider=debug"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["level"], "info,prism_provider=debug");
+    assert_eq!(body["default"], "info");
+
+    let req = authed_patch(
+        "/api/dashboard/system/log-level",
+        &token,
+        json!({"level": "prism_provider=verbose"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "validation_failed");
+    assert_eq!(
+        harness.state.log_filter.directives(),
+        "info,prism_provider=debug"
+    );
+
+    let req = authed_patch(
+        "/api/dashboard/system/log-level",
+        &token,
+        json!({"reset": true}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["level"], "info");
 }
 
 // ===========================================================================
@@ -2462,6 +7120,55 @@ async fn test_reload_config() {
     assert_eq!(body["message"], "Configuration reloaded successfully");
 }
 
+#[tokio::test]
+async fn test_reload_config_reports_touched_subsystems() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post("/api/dashboard/config/reload", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["touched"], json!([]));
+
+    let mut config = (**harness.state.config.load()).clone();
+    config.rate_limit.enabled = true;
+    config.rate_limit.global_rpm = 100;
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    std::fs::write(&config_path, config.to_yaml().unwrap()).unwrap();
+
+    let req = authed_post("/api/dashboard/config/reload", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "reload failed: {body:?}");
+    assert_eq!(body["touched"], json!(["rate_limits"]));
+    assert_eq!(harness.state.config.load().rate_limit.global_rpm, 100);
+}
+
+#[tokio::test]
+async fn test_reload_applies_global_network_settings() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let mut config = (**harness.state.config.load()).clone();
+    config.proxy_url = Some("http://proxy.internal:3128".to_string());
+    config.connect_timeout = 7;
+    config.request_timeout = 90;
+    let config_path = harness.state.config_path.lock().unwrap().clone();
+    std::fs::write(&config_path, config.to_yaml().unwrap()).unwrap();
+
+    let req = authed_post("/api/dashboard/config/reload", &token, json!({}));
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "reload failed: {body:?}");
+    assert_eq!(body["touched"], json!(["network"]));
+
+    let network = harness.state.http_client_pool.network();
+    assert_eq!(
+        network.proxy_url.as_deref(),
+        Some("http://proxy.internal:3128")
+    );
+    assert_eq!(network.connect_timeout_secs, 7);
+    assert_eq!(network.request_timeout_secs, 90);
+}
+
 #[tokio::test]
 async fn test_validate_config_valid() {
     let harness = create_test_harness();
@@ -2880,6 +7587,87 @@ async fn test_explain_with_provider() {
     assert!(body["scoring"].is_array());
 }
 
+#[tokio::test]
+async fn test_resolve_route_lists_attempts_and_exclusions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let entry = |name: &'static str, format: Format, models: &'static [&'static str]| {
+        provider_entry(ProviderFixture {
+            name,
+            format,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models,
+            auth_profiles: Vec::new(),
+            api_key: "sk-test",
+            base_url: Some("http://127.0.0.1:9"),
+            region: None,
+        })
+    };
+    let mut excluder = entry("excluder", Format::OpenAI, &[]);
+    excluder.excluded_models = vec!["gpt-4*".to_string()];
+    let config = Config {
+        dashboard: DashboardConfig {
+            enabled: true,
+            username: "admin".to_string(),
+            password_hash: bcrypt::hash("test123", 4).unwrap(),
+            jwt_secret: Some("test-secret".to_string()),
+            localhost_only: false,
+            ..DashboardConfig::default()
+        },
+        providers: vec![
+            entry("primary", Format::OpenAI, &["gpt-4o"]),
+            excluder,
+            entry("anthropic", Format::Claude, &["gpt-4o"]),
+        ],
+        ..Config::default()
+    };
+    let proxy = prism_server::builder::ProxyBuilder::new(config)
+        .config_path(temp_dir.path().join("config.yaml").to_string_lossy())
+        .build()
+        .unwrap();
+    let harness = TestHarness {
+        state: proxy.state().clone(),
+        _temp_dir: temp_dir,
+    };
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/routing/resolve",
+        &token,
+        json!({"model": "gpt-4o", "formats": ["openai"]}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "resolve failed: {body:?}");
+    assert_eq!(body["model"], "gpt-4o");
+    let attempts = body["attempts"].as_array().unwrap();
+    assert_eq!(attempts.len(), 1, "{body:?}");
+    assert_eq!(attempts[0]["order"], 1);
+    assert_eq!(attempts[0]["provider"], "primary");
+    assert_eq!(attempts[0]["format"], "openai");
+    assert_eq!(attempts[0]["model"], "gpt-4o");
+
+    let excluded = body["excluded"].as_array().unwrap();
+    let excluded_model = excluded
+        .iter()
+        .find(|e| e["reason"] == "model_not_supported")
+        .expect("excluder rejected");
+    assert!(
+        excluded_model["candidate"]
+            .as_str()
+            .unwrap()
+            .starts_with("excluder/")
+    );
+    assert!(
+        excluded_model["detail"]
+            .as_str()
+            .unwrap()
+            .contains("excluded-models")
+    );
+    assert!(excluded.iter().any(|e| e["reason"] == "format_not_allowed"));
+    // Resolving never touches the upstream.
+    assert!(harness.state.metrics.provider_attempts("primary").is_none());
+}
+
 #[tokio::test]
 async fn test_update_routing_validation_empty_profiles() {
     let harness = create_test_harness();
@@ -3163,6 +7951,361 @@ async fn test_routing_update_returns_version() {
     assert!(body["config_version"].is_string());
 }
 
+#[tokio::test]
+async fn test_preview_config_patch_reports_diff_without_writing() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+    let path = harness.state.config_path.lock().unwrap().clone();
+    let before = std::fs::read_to_string(&path).unwrap();
+
+    let req = authed_post(
+        "/api/dashboard/config/preview",
+        &token,
+        json!({"patch": {
+            "port": 9100,
+            "body-limit-mb": 4,
+            "dashboard": {"enabled": false},
+            "providers": [{
+                "name": "preview-openai",
+                "format": "openai",
+                "api-key": "sk-preview-secret-value",
+            }],
+        }}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "preview failed: {body:?}");
+    assert_eq!(body["valid"], true);
+    assert_eq!(body["impact"]["providers_added"], json!(["preview-openai"]));
+    assert_eq!(body["impact"]["restart_required"], json!(["port"]));
+    assert!(
+        body["proposed_yaml"]
+            .as_str()
+            .unwrap()
+            .contains("preview-openai")
+    );
+
+    let added = body["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["path"] == "providers[preview-openai]")
+        .expect("provider change should be listed");
+    assert_eq!(added["kind"], "added");
+    assert_eq!(added["after"]["api-key"], "sk-p****alue");
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+}
+
+#[tokio::test]
+async fn test_preview_config_reports_invalidated_auth_keys() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.auth_keys = vec![AuthKeyEntry {
+        id: None,
+        key: "sk-proxy-preview-removed".to_string(),
+        name: Some("old".to_string()),
+        tenant_id: None,
+        allowed_models: Vec::new(),
+        allowed_credentials: Vec::new(),
+        rate_limit: None,
+        budget: None,
+        expires_at: None,
+        metadata: HashMap::new(),
+        moderation: None,
+        routing_hints: false,
+        disabled: false,
+        tags: Vec::new(),
+    }];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/config/preview",
+        &token,
+        json!({"patch": {"auth-keys": []}}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::OK, "preview failed: {body:?}");
+    assert_eq!(
+        body["impact"]["auth_keys_invalidated"],
+        json!(["sk-p****oved"])
+    );
+}
+
+#[tokio::test]
+async fn test_preview_config_invalid_yaml() {
+    let harness = create_test_harness();
+    let token = login_and_get_token(&harness).await;
+
+    let req = authed_post(
+        "/api/dashboard/config/preview",
+        &token,
+        json!({"yaml": "port: not-a-number\n"}),
+    );
+    let (status, body) = send_request(&harness, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["valid"], false);
+}
+
+#[tokio::test]
+async fn test_export_config_sanitizes_literal_secrets() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![
+        provider_entry(ProviderFixture {
+            name: "literal",
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "sk-literal-secret",
+            base_url: None,
+            region: None,
+        }),
+        provider_entry(ProviderFixture {
+            name: "referenced",
+            format: Format::OpenAI,
+            upstream: None,
+            wire_api: WireApi::Chat,
+            models: &["gpt-4o"],
+            auth_profiles: Vec::new(),
+            api_key: "env://PRISM_EXPORT_TEST_KEY",
+            base_url: None,
+            region: None,
+        }),
+    ];
+    config.providers[0].headers = HashMap::from([
+        ("X-Custom-Auth".to_string(), "hdr-secret".to_string()),
+        ("anthropic-beta".to_string(), "tools-2024".to_string()),
+    ]);
+    let path = harness.state.config_path.lock().unwrap().clone();
+    std::fs::write(&path, config.to_yaml().unwrap()).unwrap();
+    let token = login_and_get_token(&harness).await;
+
+    let (status, body) =
+        send_request(&harness, authed_get("/api/dashboard/config/export", &token)).await;
+    assert_eq!(status, StatusCode::OK, "export failed: {body:?}");
+    assert_eq!(body["bundle"]["sanitized"], true);
+    assert_eq!(body["bundle"]["providers"][0]["api-key"], "");
+    let headers = &body["bundle"]["providers"][0]["upstream-presentation"]["custom-headers"];
+    assert_eq!(headers["x-custom-auth"], "");
+    assert_eq!(headers["anthropic-beta"], "tools-2024");
+    assert_eq!(
+        body["bundle"]["providers"][1]["api-key"],
+        "env://PRISM_EXPORT_TEST_KEY"
+    );
+
+    let (status, body) = send_request(
+        &harness,
+        authed_get("/api/dashboard/config/export?mode=full", &token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "confirmation_required");
+
+    let (status, body) = send_request(
+        &harness,
+        authed_get(
+            "/api/dashboard/config/export?mode=full&confirm=true",
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["bundle"]["providers"][0]["api-key"],
+        "sk-literal-secret"
+    );
+    assert_eq!(
+        body["bundle"]["providers"][0]["upstream-presentation"]["custom-headers"]["x-custom-auth"],
+        "hdr-secret"
+    );
+}
+
+#[tokio::test]
+async fn test_import_config_merge_and_replace() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "existing",
+        format: Format::OpenAI,
+        upstream: None,
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-existing-secret",
+        base_url: None,
+        region: None,
+    })];
+    config.providers[0].headers =
+        HashMap::from([("x-api-key".to_string(), "hdr-existing".to_string())]);
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+
+    let bundle = json!({
+        "version": 1,
+        "sanitized": true,
+        "providers": [
+            {"name": "existing", "format": "openai", "api-key": "", "base-url": "https://mirror.example.com", "headers": {"X-Api-Key": ""}},
+            {"name": "imported", "format": "claude", "api-key": "sk-ant-imported"},
+        ],
+    });
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/import",
+            &token,
+            json!({"bundle": bundle, "mode": "merge"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "import failed: {body:?}");
+    assert_eq!(body["results"][0]["action"], "updated");
+    assert_eq!(body["results"][0]["secrets_preserved"], true);
+    assert_eq!(body["results"][1]["action"], "created");
+
+    let config = harness.state.config.load();
+    assert_eq!(config.providers.len(), 2);
+    assert_eq!(config.providers[0].api_key, "sk-existing-secret");
+    assert_eq!(
+        config.providers[0].upstream_presentation.custom_headers["x-api-key"],
+        "hdr-existing"
+    );
+    assert_eq!(
+        config.providers[0].base_url.as_deref(),
+        Some("https://mirror.example.com")
+    );
+
+    let bundle = json!({
+        "version": 1,
+        "providers": [{"name": "imported", "format": "claude", "api-key": "sk-ant-imported"}],
+    });
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/import",
+            &token,
+            json!({"bundle": bundle, "mode": "replace", "config_version": "stale"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "expected conflict: {body:?}");
+
+    let (_, raw) = send_request(&harness, authed_get("/api/dashboard/config/raw", &token)).await;
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/import",
+            &token,
+            json!({"bundle": bundle, "mode": "replace", "config_version": raw["config_version"]}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "replace failed: {body:?}");
+    assert!(
+        body["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["name"] == "existing" && r["action"] == "removed")
+    );
+    assert_eq!(harness.state.config.load().providers.len(), 1);
+}
+
+#[tokio::test]
+async fn test_sanitized_export_round_trips_oauth_credentials() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    config.providers = vec![provider_entry(ProviderFixture {
+        name: "codex",
+        format: Format::OpenAI,
+        upstream: Some(UpstreamKind::Codex),
+        wire_api: WireApi::Responses,
+        models: &["gpt-5"],
+        auth_profiles: vec![AuthProfileEntry {
+            id: "personal".to_string(),
+            mode: AuthMode::CodexOAuth,
+            access_token: Some("oauth-access".to_string()),
+            refresh_token: Some("oauth-refresh".to_string()),
+            id_token: Some("oauth-id".to_string()),
+            ..Default::default()
+        }],
+        api_key: "",
+        base_url: None,
+        region: None,
+    })];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+
+    let (status, exported) =
+        send_request(&harness, authed_get("/api/dashboard/config/export", &token)).await;
+    assert_eq!(status, StatusCode::OK, "export failed: {exported:?}");
+    let profile = &exported["bundle"]["providers"][0]["auth-profiles"][0];
+    assert!(profile.get("access-token").is_none_or(Value::is_null));
+    assert!(profile.get("refresh-token").is_none_or(Value::is_null));
+
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/import",
+            &token,
+            json!({"bundle": exported["bundle"], "mode": "merge"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "import failed: {body:?}");
+    assert_eq!(body["results"][0]["secrets_preserved"], true);
+
+    let config = harness.state.config.load();
+    let profile = &config.providers[0].auth_profiles[0];
+    assert_eq!(profile.access_token.as_deref(), Some("oauth-access"));
+    assert_eq!(profile.refresh_token.as_deref(), Some("oauth-refresh"));
+    assert_eq!(profile.id_token.as_deref(), Some("oauth-id"));
+}
+
+#[tokio::test]
+async fn test_sanitized_export_strips_literal_signing_secret() {
+    let harness = create_test_harness();
+    let mut config = harness.state.config.load().as_ref().clone();
+    let mut entry = provider_entry(ProviderFixture {
+        name: "signed",
+        format: Format::OpenAI,
+        upstream: Some(UpstreamKind::OpenAI),
+        wire_api: WireApi::Chat,
+        models: &["gpt-4o"],
+        auth_profiles: Vec::new(),
+        api_key: "sk-signed-literal",
+        base_url: None,
+        region: None,
+    });
+    entry.signing = Some(serde_json::from_value(json!({"secret": "hmac-literal-secret"})).unwrap());
+    config.providers = vec![entry];
+    write_test_config(&harness, &config);
+    let token = login_and_get_token(&harness).await;
+
+    let (status, exported) =
+        send_request(&harness, authed_get("/api/dashboard/config/export", &token)).await;
+    assert_eq!(status, StatusCode::OK, "export failed: {exported:?}");
+    assert!(!exported.to_string().contains("hmac-literal-secret"));
+    assert_eq!(exported["bundle"]["providers"][0]["signing"]["secret"], "");
+
+    let (status, body) = send_request(
+        &harness,
+        authed_post(
+            "/api/dashboard/config/import",
+            &token,
+            json!({"bundle": exported["bundle"], "mode": "merge"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "import failed: {body:?}");
+    assert_eq!(body["results"][0]["secrets_preserved"], true);
+    let config = harness.state.config.load();
+    let signing = config.providers[0].signing.as_ref().unwrap();
+    assert_eq!(signing.secret, "hmac-literal-secret");
+}
+
 fn write_test_config(harness: &TestHarness, config: &Config) {
     let path = harness.state.config_path.lock().unwrap().clone();
     std::fs::write(&path, config.to_yaml().expect("serialize config")).expect("write test config");
@@ -3183,9 +8326,11 @@ struct ProviderFixture<'a> {
 
 fn provider_entry(fixture: ProviderFixture<'_>) -> prism_core::config::ProviderKeyEntry {
     prism_core::config::ProviderKeyEntry {
+        id: None,
         name: fixture.name.to_string(),
         format: fixture.format,
         upstream: fixture.upstream,
+        executor: None,
         api_key: fixture.api_key.to_string(),
         base_url: fixture.base_url.map(str::to_string),
         proxy_url: None,
@@ -3205,12 +8350,20 @@ fn provider_entry(fixture: ProviderFixture<'_>) -> prism_core::config::ProviderK
         wire_api: fixture.wire_api,
         weight: 1,
         region: fixture.region.map(str::to_string),
+        group: None,
+        quota: None,
         credential_source: None,
+        tags: Vec::new(),
+        token_source: None,
         auth_profiles: fixture.auth_profiles,
         upstream_presentation: Default::default(),
         vertex: false,
         vertex_project: None,
         vertex_location: None,
+        allowed_server_tools: None,
+        allowed_betas: None,
+        signing: None,
+        json_repair: false,
     }
 }
 
@@ -3248,6 +8401,7 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
     ];
     config.auth_keys = vec![
         AuthKeyEntry {
+            id: None,
             key: "sk-proxy-tenant-red".to_string(),
             name: Some("tenant-red".to_string()),
             tenant_id: Some("tenant-red".to_string()),
@@ -3257,8 +8411,13 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
             budget: None,
             expires_at: None,
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         },
         AuthKeyEntry {
+            id: None,
             key: "sk-proxy-tenant-blue".to_string(),
             name: Some("tenant-blue".to_string()),
             tenant_id: Some("tenant-blue".to_string()),
@@ -3268,6 +8427,10 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
             budget: None,
             expires_at: None,
             metadata: HashMap::new(),
+            moderation: None,
+            routing_hints: false,
+            disabled: false,
+            tags: Vec::new(),
         },
     ];
     config.routing.rules = vec![RouteRule {
@@ -3333,6 +8496,7 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
         .log_store
         .push(RequestRecord {
             request_id: "req_traffic_latest".to_string(),
+            trace_id: None,
             timestamp: Utc::now(),
             method: "POST".to_string(),
             path: "/v1/chat/completions".to_string(),
@@ -3361,6 +8525,9 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
             tenant_id: Some("tenant-red".to_string()),
             client_ip: Some("127.0.0.1".to_string()),
             client_region: Some("eu-central".to_string()),
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![
                 AttemptSummary {
                     attempt_index: 0,
@@ -3371,6 +8538,7 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
                     latency_ms: 410,
                     error: Some("managed auth disconnected".to_string()),
                     error_type: Some("auth_runtime".to_string()),
+                    timings: None,
                 },
                 AttemptSummary {
                     attempt_index: 1,
@@ -3381,8 +8549,12 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
                     latency_ms: 1430,
                     error: None,
                     error_type: None,
+                    timings: None,
                 },
             ],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         })
         .await;
     harness
@@ -3390,6 +8562,7 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
         .log_store
         .push(RequestRecord {
             request_id: "req_provider_fail".to_string(),
+            trace_id: None,
             timestamp: Utc::now() - ChronoDuration::minutes(10),
             method: "POST".to_string(),
             path: "/v1/messages".to_string(),
@@ -3413,6 +8586,9 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
             tenant_id: Some("tenant-red".to_string()),
             client_ip: Some("127.0.0.1".to_string()),
             client_region: Some("eu-central".to_string()),
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![AttemptSummary {
                 attempt_index: 0,
                 provider: "claude-sub-eu".to_string(),
@@ -3422,7 +8598,11 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
                 latency_ms: 920,
                 error: Some("upstream unavailable".to_string()),
                 error_type: Some("upstream_5xx".to_string()),
+                timings: None,
             }],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         })
         .await;
     harness
@@ -3430,6 +8610,7 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
         .log_store
         .push(RequestRecord {
             request_id: "req_openai_ok".to_string(),
+            trace_id: None,
             timestamp: Utc::now() - ChronoDuration::minutes(20),
             method: "POST".to_string(),
             path: "/v1/responses".to_string(),
@@ -3458,6 +8639,9 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
             tenant_id: Some("tenant-blue".to_string()),
             client_ip: Some("127.0.0.1".to_string()),
             client_region: Some("us-east".to_string()),
+            experiment: None,
+            experiment_arm: None,
+            tags: Vec::new(),
             attempts: vec![AttemptSummary {
                 attempt_index: 0,
                 provider: "openai-prod".to_string(),
@@ -3467,7 +8651,11 @@ async fn seed_control_plane_fixture(harness: &TestHarness) {
                 latency_ms: 610,
                 error: None,
                 error_type: None,
+                timings: None,
             }],
+            slow: false,
+            stream_truncated: None,
+            timings: None,
         })
         .await;
 }
@@ -0,0 +1,257 @@
+//! Shared utilities for integration tests.
+//!
+//! [`MockUpstream`] is a local HTTP server standing in for an OpenAI, Claude, or
+//! Gemini API. Point a provider's `base-url` at it, queue the replies a test
+//! needs (status codes, SSE scripts, delays), and inspect what the proxy sent.
+//! Nothing leaves the machine, so dispatch, retry, and streaming tests run in CI
+//! without network access.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use prism_test_support::{MockReply, MockUpstream};
+//! use prism_types::format::Format;
+//!
+//! let upstream = MockUpstream::start(Format::Claude).await;
+//! upstream.push(MockReply::error(Format::Claude, 529, "overloaded"));
+//! upstream.push(MockReply::text_stream(Format::Claude, &["Hel", "lo"]));
+//! // ... configure a Claude provider with `upstream.base_url()` and send requests ...
+//! assert_eq!(upstream.request_count(), 2);
+//! # }
+//! ```
+
+mod reply;
+
+pub use reply::{MockReply, SseEvent};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{OriginalUri, State};
+use axum::http::{HeaderMap, Method};
+use axum::response::Response;
+use prism_types::format::Format;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// A request received by a [`MockUpstream`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// Path and query, e.g. `/v1beta/models/m:streamGenerateContent?alt=sse`.
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl RecordedRequest {
+    /// The body parsed as JSON, `Value::Null` if it is not JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+
+    /// Whether the request asked for a streamed response.
+    pub fn is_stream(&self) -> bool {
+        self.path.contains(":streamGenerateContent") || self.json()["stream"] == true
+    }
+}
+
+struct Shared {
+    format: Format,
+    script: Mutex<VecDeque<MockReply>>,
+    fallback: Mutex<Option<MockReply>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+/// A mock provider API on `127.0.0.1`, stopped when dropped.
+///
+/// Every request, whatever its path, gets the next queued reply. Once the queue
+/// is empty it gets the fallback, which defaults to an "ok" answer in the
+/// upstream's format, streamed if the request asked for a stream.
+pub struct MockUpstream {
+    base_url: String,
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl MockUpstream {
+    pub async fn start(format: Format) -> Self {
+        let shared = Arc::new(Shared {
+            format,
+            script: Mutex::new(VecDeque::new()),
+            fallback: Mutex::new(None),
+            requests: Mutex::new(Vec::new()),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock upstream");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().fallback(handle).with_state(shared.clone());
+        let task = tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("serve mock upstream");
+        });
+        Self {
+            base_url,
+            shared,
+            task,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn format(&self) -> Format {
+        self.shared.format
+    }
+
+    /// Queue a reply for one request. Replies are served in push order.
+    pub fn push(&self, reply: MockReply) -> &Self {
+        self.shared.script.lock().unwrap().push_back(reply);
+        self
+    }
+
+    /// Serve `reply` to every request once the queue is empty.
+    pub fn set_fallback(&self, reply: MockReply) {
+        *self.shared.fallback.lock().unwrap() = Some(reply);
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.shared.requests.lock().unwrap().clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.shared.requests.lock().unwrap().len()
+    }
+
+    /// The most recent request. Panics if none arrived.
+    pub fn last_request(&self) -> RecordedRequest {
+        self.shared
+            .requests
+            .lock()
+            .unwrap()
+            .last()
+            .cloned()
+            .expect("mock upstream received no request")
+    }
+}
+
+impl Drop for MockUpstream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle(
+    State(shared): State<Arc<Shared>>,
+    method: Method,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let request = RecordedRequest {
+        method,
+        path: uri
+            .path_and_query()
+            .map_or_else(|| uri.path().to_string(), |pq| pq.to_string()),
+        headers,
+        body,
+    };
+    let stream = request.is_stream();
+    shared.requests.lock().unwrap().push(request);
+
+    let scripted = shared.script.lock().unwrap().pop_front();
+    let reply = scripted
+        .or_else(|| shared.fallback.lock().unwrap().clone())
+        .unwrap_or_else(|| {
+            if stream {
+                MockReply::text_stream(shared.format, &["ok"])
+            } else {
+                MockReply::text(shared.format, "ok")
+            }
+        });
+    reply.into_response().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    async fn post(upstream: &MockUpstream, path: &str, body: Value) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}{path}", upstream.base_url()))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serves_script_then_fallback_and_records_requests() {
+        let upstream = MockUpstream::start(Format::OpenAI).await;
+        upstream
+            .push(MockReply::error(Format::OpenAI, 500, "boom"))
+            .push(MockReply::text(Format::OpenAI, "first").with_header("x-mock", "1"));
+
+        let body = serde_json::json!({"model": "m", "messages": []});
+        let failed = post(&upstream, "/v1/chat/completions", body.clone()).await;
+        assert_eq!(failed.status(), 500);
+        let failed: Value = failed.json().await.unwrap();
+        assert_eq!(failed["error"]["message"], "boom");
+
+        let scripted = post(&upstream, "/v1/chat/completions", body.clone()).await;
+        assert_eq!(scripted.headers()["x-mock"], "1");
+        let scripted: Value = scripted.json().await.unwrap();
+        assert_eq!(scripted["choices"][0]["message"]["content"], "first");
+
+        let fallback: Value = post(&upstream, "/v1/chat/completions", body)
+            .await
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(fallback["choices"][0]["message"]["content"], "ok");
+
+        assert_eq!(upstream.request_count(), 3);
+        let last = upstream.last_request();
+        assert_eq!(last.path, "/v1/chat/completions");
+        assert_eq!(last.json()["model"], "m");
+    }
+
+    #[tokio::test]
+    async fn test_streams_script_with_delays() {
+        let upstream = MockUpstream::start(Format::Gemini).await;
+        upstream.push(MockReply::sse(vec![
+            SseEvent::data("{\"a\":1}"),
+            SseEvent::data("{\"b\":2}").after(Duration::from_millis(50)),
+        ]));
+
+        let started = Instant::now();
+        let response = post(
+            &upstream,
+            "/v1beta/models/m:streamGenerateContent?alt=sse",
+            serde_json::json!({"contents": []}),
+        )
+        .await;
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let body = response.text().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(body, "data: {\"a\":1}\n\ndata: {\"b\":2}\n\n");
+        assert!(upstream.last_request().is_stream());
+
+        // The default fallback streams in the upstream's own framing.
+        let body = post(
+            &upstream,
+            "/v1beta/models/m:streamGenerateContent?alt=sse",
+            serde_json::json!({"contents": []}),
+        )
+        .await
+        .text()
+        .await
+        .unwrap();
+        assert!(body.contains("\"finishReason\":\"STOP\""), "{body}");
+    }
+}
@@ -0,0 +1,363 @@
+use axum::body::Body;
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::response::Response;
+use bytes::Bytes;
+use futures::StreamExt;
+use prism_types::format::Format;
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// One scripted reply from a [`MockUpstream`](crate::MockUpstream).
+#[derive(Debug, Clone)]
+pub struct MockReply {
+    status: StatusCode,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    delay: Duration,
+    body: ReplyBody,
+}
+
+#[derive(Debug, Clone)]
+enum ReplyBody {
+    Json(Value),
+    Sse { events: Vec<SseEvent>, stall: bool },
+}
+
+/// One server-sent event. `delay` is waited before the event is written.
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    event: Option<String>,
+    data: String,
+    delay: Duration,
+}
+
+impl SseEvent {
+    /// A data-only event.
+    pub fn data(data: impl Into<String>) -> Self {
+        Self {
+            event: None,
+            data: data.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// An event with an `event:` line, as Claude sends them.
+    pub fn named(event: impl Into<String>, data: &Value) -> Self {
+        Self {
+            event: Some(event.into()),
+            ..Self::data(data.to_string())
+        }
+    }
+
+    /// Wait `delay` before writing this event.
+    pub fn after(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn encode(&self) -> Bytes {
+        let mut out = String::new();
+        if let Some(event) = &self.event {
+            out.push_str(&format!("event: {event}\n"));
+        }
+        for line in self.data.split('\n') {
+            out.push_str(&format!("data: {line}\n"));
+        }
+        out.push('\n');
+        Bytes::from(out)
+    }
+}
+
+impl MockReply {
+    /// A 200 JSON reply.
+    pub fn json(body: Value) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+            body: ReplyBody::Json(body),
+        }
+    }
+
+    /// A 200 `text/event-stream` reply writing `events` in order.
+    pub fn sse(events: Vec<SseEvent>) -> Self {
+        Self {
+            body: ReplyBody::Sse {
+                events,
+                stall: false,
+            },
+            ..Self::json(Value::Null)
+        }
+    }
+
+    /// An error reply in the provider's own error shape.
+    pub fn error(format: Format, status: u16, message: &str) -> Self {
+        let body = match format {
+            Format::OpenAI => json!({
+                "error": {"message": message, "type": error_type(status), "code": null}
+            }),
+            Format::Claude => json!({
+                "type": "error",
+                "error": {"type": claude_error_type(status), "message": message}
+            }),
+            Format::Gemini => json!({
+                "error": {"code": status, "message": message, "status": gemini_status(status)}
+            }),
+        };
+        Self::json(body).with_status(status)
+    }
+
+    /// A complete non-streaming response answering with `text`.
+    pub fn text(format: Format, text: &str) -> Self {
+        let body = match format {
+            Format::OpenAI => json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion",
+                "created": 1700000000,
+                "model": "mock-model",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": text},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}
+            }),
+            Format::Claude => json!({
+                "id": "msg_mock",
+                "type": "message",
+                "role": "assistant",
+                "model": "mock-model",
+                "content": [{"type": "text", "text": text}],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 1}
+            }),
+            Format::Gemini => json!({
+                "candidates": [{
+                    "content": {"role": "model", "parts": [{"text": text}]},
+                    "finishReason": "STOP",
+                    "index": 0
+                }],
+                "usageMetadata": {
+                    "promptTokenCount": 3,
+                    "candidatesTokenCount": 1,
+                    "totalTokenCount": 4
+                },
+                "modelVersion": "mock-model"
+            }),
+        };
+        Self::json(body)
+    }
+
+    /// A complete stream answering with `chunks`, one text delta per chunk,
+    /// framed the way the provider frames it.
+    pub fn text_stream(format: Format, chunks: &[&str]) -> Self {
+        let events = match format {
+            Format::OpenAI => {
+                let chunk = |delta: Value, finish: Value| {
+                    SseEvent::data(
+                        json!({
+                            "id": "chatcmpl-mock",
+                            "object": "chat.completion.chunk",
+                            "created": 1700000000,
+                            "model": "mock-model",
+                            "choices": [{"index": 0, "delta": delta, "finish_reason": finish}]
+                        })
+                        .to_string(),
+                    )
+                };
+                let mut events = vec![chunk(
+                    json!({"role": "assistant", "content": ""}),
+                    Value::Null,
+                )];
+                events.extend(
+                    chunks
+                        .iter()
+                        .map(|text| chunk(json!({"content": text}), Value::Null)),
+                );
+                events.push(chunk(json!({}), json!("stop")));
+                events.push(SseEvent::data(
+                    json!({
+                        "id": "chatcmpl-mock",
+                        "object": "chat.completion.chunk",
+                        "created": 1700000000,
+                        "model": "mock-model",
+                        "choices": [],
+                        "usage": {"prompt_tokens": 3, "completion_tokens": chunks.len(), "total_tokens": 3 + chunks.len()}
+                    })
+                    .to_string(),
+                ));
+                events.push(SseEvent::data("[DONE]"));
+                events
+            }
+            Format::Claude => {
+                let mut events = vec![
+                    SseEvent::named(
+                        "message_start",
+                        &json!({
+                            "type": "message_start",
+                            "message": {
+                                "id": "msg_mock",
+                                "type": "message",
+                                "role": "assistant",
+                                "model": "mock-model",
+                                "content": [],
+                                "stop_reason": null,
+                                "stop_sequence": null,
+                                "usage": {"input_tokens": 3, "output_tokens": 1}
+                            }
+                        }),
+                    ),
+                    SseEvent::named(
+                        "content_block_start",
+                        &json!({
+                            "type": "content_block_start",
+                            "index": 0,
+                            "content_block": {"type": "text", "text": ""}
+                        }),
+                    ),
+                ];
+                events.extend(chunks.iter().map(|text| {
+                    SseEvent::named(
+                        "content_block_delta",
+                        &json!({
+                            "type": "content_block_delta",
+                            "index": 0,
+                            "delta": {"type": "text_delta", "text": text}
+                        }),
+                    )
+                }));
+                events.extend([
+                    SseEvent::named(
+                        "content_block_stop",
+                        &json!({"type": "content_block_stop", "index": 0}),
+                    ),
+                    SseEvent::named(
+                        "message_delta",
+                        &json!({
+                            "type": "message_delta",
+                            "delta": {"stop_reason": "end_turn", "stop_sequence": null},
+                            "usage": {"output_tokens": chunks.len()}
+                        }),
+                    ),
+                    SseEvent::named("message_stop", &json!({"type": "message_stop"})),
+                ]);
+                events
+            }
+            Format::Gemini => {
+                let last = chunks.len().saturating_sub(1);
+                chunks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, text)| {
+                        let mut chunk = json!({
+                            "candidates": [{
+                                "content": {"role": "model", "parts": [{"text": text}]},
+                                "index": 0
+                            }],
+                            "modelVersion": "mock-model"
+                        });
+                        if i == last {
+                            chunk["candidates"][0]["finishReason"] = json!("STOP");
+                            chunk["usageMetadata"] = json!({
+                                "promptTokenCount": 3,
+                                "candidatesTokenCount": chunks.len(),
+                                "totalTokenCount": 3 + chunks.len()
+                            });
+                        }
+                        SseEvent::data(chunk.to_string())
+                    })
+                    .collect()
+            }
+        };
+        Self::sse(events)
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = StatusCode::from_u16(status).expect("valid status code");
+        self
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((
+            HeaderName::from_static(name),
+            HeaderValue::from_str(value).expect("valid header value"),
+        ));
+        self
+    }
+
+    /// Wait `delay` before sending the response headers.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Keep a stream open after its last event instead of ending it, like an
+    /// upstream that stops sending mid-response.
+    pub fn stall(mut self) -> Self {
+        if let ReplyBody::Sse { stall, .. } = &mut self.body {
+            *stall = true;
+        }
+        self
+    }
+
+    pub(crate) async fn into_response(self) -> Response {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        let (content_type, body) = match self.body {
+            ReplyBody::Json(value) => ("application/json", Body::from(value.to_string())),
+            ReplyBody::Sse { events, stall } => {
+                let events = futures::stream::iter(events).then(|event| async move {
+                    if !event.delay.is_zero() {
+                        tokio::time::sleep(event.delay).await;
+                    }
+                    Ok::<_, Infallible>(event.encode())
+                });
+                let body = if stall {
+                    Body::from_stream(events.chain(futures::stream::pending()))
+                } else {
+                    Body::from_stream(events)
+                };
+                ("text/event-stream", body)
+            }
+        };
+        let mut response = Response::new(body);
+        *response.status_mut() = self.status;
+        let headers = response.headers_mut();
+        headers.insert("content-type", HeaderValue::from_static(content_type));
+        for (name, value) in self.headers {
+            headers.insert(name, value);
+        }
+        response
+    }
+}
+
+fn error_type(status: u16) -> &'static str {
+    match status {
+        400 => "invalid_request_error",
+        401 => "authentication_error",
+        429 => "rate_limit_error",
+        _ => "server_error",
+    }
+}
+
+fn claude_error_type(status: u16) -> &'static str {
+    match status {
+        400 => "invalid_request_error",
+        401 => "authentication_error",
+        429 => "rate_limit_error",
+        529 => "overloaded_error",
+        _ => "api_error",
+    }
+}
+
+fn gemini_status(status: u16) -> &'static str {
+    match status {
+        400 => "INVALID_ARGUMENT",
+        401 => "UNAUTHENTICATED",
+        429 => "RESOURCE_EXHAUSTED",
+        503 => "UNAVAILABLE",
+        _ => "INTERNAL",
+    }
+}
@@ -0,0 +1,488 @@
+//! Criterion benchmarks for the translator hot path: request translation,
+//! stream chunk translation, and whole-response translation for every
+//! registered format pair, plus the legacy Completions adapter.
+//!
+//! Payloads are built to look like agent traffic: a long system prompt, a
+//! multi-turn history with tool calls and tool results, and a tool catalog.
+//! Baselines and how to compare against them: `docs/reference/benchmarks.md`.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use prism_translator::{TranslateState, TranslatorRegistry, build_registry, legacy_completions};
+use prism_types::format::Format;
+use serde_json::{Value, json};
+use std::hint::black_box;
+
+const MODEL: &str = "bench-model";
+
+/// About 16 KB of prose, the size of a typical agent system prompt.
+fn long_text() -> String {
+    "The proxy translates requests between provider formats, keeping tool calls, \
+     images, and usage intact across every hop. "
+        .repeat(140)
+}
+
+fn tool_schemas() -> Vec<(String, Value)> {
+    (0..8)
+        .map(|i| {
+            let schema = json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path relative to the workspace"},
+                    "pattern": {"type": "string", "description": "Search pattern"},
+                    "limit": {"type": "integer", "minimum": 1, "maximum": 500},
+                    "flags": {"type": "array", "items": {"type": "string", "enum": ["i", "m", "s"]}},
+                },
+                "required": ["path"],
+            });
+            (format!("tool_{i}"), schema)
+        })
+        .collect()
+}
+
+fn tool_arguments(i: usize) -> String {
+    json!({"path": format!("src/module_{i}.rs"), "pattern": "fn translate", "limit": 50})
+        .to_string()
+}
+
+fn openai_request() -> Value {
+    let mut messages = vec![json!({"role": "system", "content": long_text()})];
+    for i in 0..6 {
+        messages.push(
+            json!({"role": "user", "content": format!("Step {i}: {}", &long_text()[..2000])}),
+        );
+        messages.push(json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": format!("call_{i}"),
+                "type": "function",
+                "function": {"name": format!("tool_{}", i % 8), "arguments": tool_arguments(i)},
+            }],
+        }));
+        messages.push(json!({
+            "role": "tool",
+            "tool_call_id": format!("call_{i}"),
+            "content": long_text()[..4000].to_string(),
+        }));
+    }
+    messages.push(json!({"role": "user", "content": "Summarize what you found."}));
+    let tools: Vec<Value> = tool_schemas()
+        .into_iter()
+        .map(|(name, schema)| {
+            json!({
+                "type": "function",
+                "function": {"name": name, "description": "Workspace tool", "parameters": schema},
+            })
+        })
+        .collect();
+    json!({
+        "model": MODEL,
+        "messages": messages,
+        "tools": tools,
+        "tool_choice": "auto",
+        "max_tokens": 4096,
+        "temperature": 0.2,
+        "stream": true,
+    })
+}
+
+fn claude_request() -> Value {
+    let mut messages = Vec::new();
+    for i in 0..6 {
+        messages.push(json!({"role": "user", "content": [
+            {"type": "text", "text": format!("Step {i}: {}", &long_text()[..2000])},
+        ]}));
+        messages.push(json!({"role": "assistant", "content": [
+            {"type": "text", "text": "Let me look."},
+            {
+                "type": "tool_use",
+                "id": format!("toolu_{i}"),
+                "name": format!("tool_{}", i % 8),
+                "input": serde_json::from_str::<Value>(&tool_arguments(i)).unwrap(),
+            },
+        ]}));
+        messages.push(json!({"role": "user", "content": [{
+            "type": "tool_result",
+            "tool_use_id": format!("toolu_{i}"),
+            "content": &long_text()[..4000],
+        }]}));
+    }
+    messages.push(json!({"role": "user", "content": "Summarize what you found."}));
+    let tools: Vec<Value> = tool_schemas()
+        .into_iter()
+        .map(|(name, schema)| json!({"name": name, "description": "Workspace tool", "input_schema": schema}))
+        .collect();
+    json!({
+        "model": MODEL,
+        "system": [{"type": "text", "text": long_text()}],
+        "messages": messages,
+        "tools": tools,
+        "max_tokens": 4096,
+        "stream": true,
+    })
+}
+
+fn gemini_request() -> Value {
+    let mut contents = Vec::new();
+    for i in 0..6 {
+        contents.push(json!({"role": "user", "parts": [{"text": format!("Step {i}: {}", &long_text()[..2000])}]}));
+        contents.push(json!({"role": "model", "parts": [{"functionCall": {
+            "name": format!("tool_{}", i % 8),
+            "args": serde_json::from_str::<Value>(&tool_arguments(i)).unwrap(),
+        }}]}));
+        contents.push(json!({"role": "user", "parts": [{"functionResponse": {
+            "name": format!("tool_{}", i % 8),
+            "response": {"content": &long_text()[..4000]},
+        }}]}));
+    }
+    contents.push(json!({"role": "user", "parts": [{"text": "Summarize what you found."}]}));
+    let declarations: Vec<Value> = tool_schemas()
+        .into_iter()
+        .map(|(name, schema)| json!({"name": name, "description": "Workspace tool", "parameters": schema}))
+        .collect();
+    json!({
+        "systemInstruction": {"parts": [{"text": long_text()}]},
+        "contents": contents,
+        "tools": [{"functionDeclarations": declarations}],
+        "generationConfig": {"temperature": 0.2, "maxOutputTokens": 4096},
+    })
+}
+
+/// Text deltas of a few tokens each, as providers stream them.
+fn deltas() -> Vec<String> {
+    long_text()
+        .split_inclusive(' ')
+        .collect::<Vec<_>>()
+        .chunks(3)
+        .take(300)
+        .map(|words| words.concat())
+        .collect()
+}
+
+/// A Claude Messages stream: text, then a tool call with streamed input.
+fn claude_stream() -> Vec<(Option<&'static str>, Vec<u8>)> {
+    let mut events = vec![
+        (
+            "message_start",
+            json!({"type": "message_start", "message": {
+                "id": "msg_bench", "type": "message", "role": "assistant", "model": MODEL,
+                "content": [], "usage": {"input_tokens": 12000, "output_tokens": 1},
+            }}),
+        ),
+        (
+            "content_block_start",
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        ),
+    ];
+    for text in deltas() {
+        events.push((
+            "content_block_delta",
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": text}}),
+        ));
+    }
+    events.push((
+        "content_block_stop",
+        json!({"type": "content_block_stop", "index": 0}),
+    ));
+    events.push((
+        "content_block_start",
+        json!({"type": "content_block_start", "index": 1, "content_block": {
+            "type": "tool_use", "id": "toolu_bench", "name": "tool_0", "input": {},
+        }}),
+    ));
+    let arguments = tool_arguments(0);
+    for part in arguments.as_bytes().chunks(8) {
+        events.push((
+            "content_block_delta",
+            json!({"type": "content_block_delta", "index": 1, "delta": {
+                "type": "input_json_delta", "partial_json": String::from_utf8_lossy(part),
+            }}),
+        ));
+    }
+    events.push((
+        "content_block_stop",
+        json!({"type": "content_block_stop", "index": 1}),
+    ));
+    events.push((
+        "message_delta",
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 900}}),
+    ));
+    events.push(("message_stop", json!({"type": "message_stop"})));
+    events
+        .into_iter()
+        .map(|(event, data)| (Some(event), data.to_string().into_bytes()))
+        .collect()
+}
+
+/// A Gemini `streamGenerateContent` stream: text chunks, then a function call.
+fn gemini_stream() -> Vec<(Option<&'static str>, Vec<u8>)> {
+    let mut chunks: Vec<Value> = deltas()
+        .into_iter()
+        .map(|text| {
+            json!({"candidates": [{"content": {"role": "model", "parts": [{"text": text}]}, "index": 0}]})
+        })
+        .collect();
+    chunks.push(json!({
+        "candidates": [{
+            "content": {"role": "model", "parts": [{"functionCall": {
+                "name": "tool_0",
+                "args": serde_json::from_str::<Value>(&tool_arguments(0)).unwrap(),
+            }}]},
+            "finishReason": "STOP",
+            "index": 0,
+        }],
+        "usageMetadata": {"promptTokenCount": 12000, "candidatesTokenCount": 900, "totalTokenCount": 12900},
+        "modelVersion": MODEL,
+    }));
+    chunks
+        .into_iter()
+        .map(|chunk| (None, chunk.to_string().into_bytes()))
+        .collect()
+}
+
+/// An OpenAI Chat Completions stream: role, text, a tool call, finish, usage.
+fn openai_stream() -> Vec<(Option<&'static str>, Vec<u8>)> {
+    let chunk = |delta: Value, finish: Value| {
+        json!({
+            "id": "chatcmpl-bench", "object": "chat.completion.chunk", "created": 1_700_000_000,
+            "model": MODEL, "choices": [{"index": 0, "delta": delta, "finish_reason": finish}],
+        })
+    };
+    let mut chunks = vec![chunk(
+        json!({"role": "assistant", "content": ""}),
+        Value::Null,
+    )];
+    for text in deltas() {
+        chunks.push(chunk(json!({"content": text}), Value::Null));
+    }
+    chunks.push(chunk(
+        json!({"tool_calls": [{"index": 0, "id": "call_bench", "type": "function",
+            "function": {"name": "tool_0", "arguments": ""}}]}),
+        Value::Null,
+    ));
+    for part in tool_arguments(0).as_bytes().chunks(8) {
+        chunks.push(chunk(
+            json!({"tool_calls": [{"index": 0, "function": {"arguments": String::from_utf8_lossy(part)}}]}),
+            Value::Null,
+        ));
+    }
+    chunks.push(chunk(json!({}), json!("tool_calls")));
+    chunks.push(json!({
+        "id": "chatcmpl-bench", "object": "chat.completion.chunk", "created": 1_700_000_000,
+        "model": MODEL, "choices": [],
+        "usage": {"prompt_tokens": 12000, "completion_tokens": 900, "total_tokens": 12900},
+    }));
+    let mut events: Vec<_> = chunks
+        .into_iter()
+        .map(|chunk| (None, chunk.to_string().into_bytes()))
+        .collect();
+    events.push((None, b"[DONE]".to_vec()));
+    events
+}
+
+fn claude_response() -> Value {
+    json!({
+        "id": "msg_bench", "type": "message", "role": "assistant", "model": MODEL,
+        "content": [
+            {"type": "text", "text": long_text()},
+            {"type": "tool_use", "id": "toolu_bench", "name": "tool_0",
+             "input": serde_json::from_str::<Value>(&tool_arguments(0)).unwrap()},
+        ],
+        "stop_reason": "tool_use",
+        "usage": {"input_tokens": 12000, "output_tokens": 900},
+    })
+}
+
+fn gemini_response() -> Value {
+    json!({
+        "candidates": [{
+            "content": {"role": "model", "parts": [
+                {"text": long_text()},
+                {"functionCall": {"name": "tool_0", "args": serde_json::from_str::<Value>(&tool_arguments(0)).unwrap()}},
+            ]},
+            "finishReason": "STOP",
+            "index": 0,
+        }],
+        "usageMetadata": {"promptTokenCount": 12000, "candidatesTokenCount": 900, "totalTokenCount": 12900},
+        "modelVersion": MODEL,
+    })
+}
+
+fn openai_response() -> Value {
+    json!({
+        "id": "chatcmpl-bench", "object": "chat.completion", "created": 1_700_000_000, "model": MODEL,
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": long_text(),
+                "tool_calls": [{"id": "call_bench", "type": "function",
+                    "function": {"name": "tool_0", "arguments": tool_arguments(0)}}],
+            },
+            "finish_reason": "tool_calls",
+        }],
+        "usage": {"prompt_tokens": 12000, "completion_tokens": 900, "total_tokens": 12900},
+    })
+}
+
+/// `(client, upstream)` pairs: the client's request format and the provider format.
+const PAIRS: &[(Format, Format)] = &[
+    (Format::OpenAI, Format::Claude),
+    (Format::OpenAI, Format::Gemini),
+    (Format::Claude, Format::OpenAI),
+    (Format::Gemini, Format::OpenAI),
+    (Format::Claude, Format::Gemini),
+    (Format::Gemini, Format::Claude),
+];
+
+fn pair_id(from: Format, to: Format) -> String {
+    format!("{}->{}", from.as_str(), to.as_str())
+}
+
+fn request_for(format: Format) -> Vec<u8> {
+    match format {
+        Format::OpenAI => openai_request(),
+        Format::Claude => claude_request(),
+        Format::Gemini => gemini_request(),
+    }
+    .to_string()
+    .into_bytes()
+}
+
+fn requests(c: &mut Criterion) {
+    let registry = build_registry();
+    let mut group = c.benchmark_group("request");
+    for &(from, to) in PAIRS {
+        let body = request_for(from);
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pair_id(from, to)),
+            &body,
+            |b, body| {
+                b.iter(|| {
+                    registry
+                        .translate_request(from, to, MODEL, black_box(body), true)
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Upstream stream events and whole responses, by upstream format.
+fn stream_for(format: Format) -> Vec<(Option<&'static str>, Vec<u8>)> {
+    match format {
+        Format::OpenAI => openai_stream(),
+        Format::Claude => claude_stream(),
+        Format::Gemini => gemini_stream(),
+    }
+}
+
+fn response_for(format: Format) -> Vec<u8> {
+    match format {
+        Format::OpenAI => openai_response(),
+        Format::Claude => claude_response(),
+        Format::Gemini => gemini_response(),
+    }
+    .to_string()
+    .into_bytes()
+}
+
+fn translate_whole_stream(
+    registry: &TranslatorRegistry,
+    from: Format,
+    to: Format,
+    original_req: &[u8],
+    events: &[(Option<&'static str>, Vec<u8>)],
+) -> usize {
+    let mut state = TranslateState::default();
+    events
+        .iter()
+        .map(|(event, data)| {
+            registry
+                .translate_stream(from, to, MODEL, original_req, *event, data, &mut state)
+                .unwrap()
+                .len()
+        })
+        .sum()
+}
+
+fn streams(c: &mut Criterion) {
+    let registry = build_registry();
+    let mut group = c.benchmark_group("stream");
+    for &(from, to) in PAIRS {
+        if !registry.has_response_translator(from, to) {
+            continue;
+        }
+        let original_req = request_for(from);
+        let events = stream_for(to);
+        let bytes: usize = events.iter().map(|(_, data)| data.len()).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pair_id(to, from)),
+            &events,
+            |b, events| {
+                b.iter(|| {
+                    translate_whole_stream(&registry, from, to, &original_req, black_box(events))
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn non_streams(c: &mut Criterion) {
+    let registry = build_registry();
+    let mut group = c.benchmark_group("non_stream");
+    for &(from, to) in PAIRS {
+        if !registry.has_response_translator(from, to) {
+            continue;
+        }
+        let original_req = request_for(from);
+        let body = response_for(to);
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pair_id(to, from)),
+            &body,
+            |b, body| {
+                b.iter(|| {
+                    registry
+                        .translate_non_stream(from, to, MODEL, &original_req, black_box(body))
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn legacy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("legacy_completions");
+    let body = json!({"model": MODEL, "prompt": long_text(), "max_tokens": 256, "stream": true})
+        .to_string();
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function("request", |b| {
+        b.iter(|| legacy_completions::translate_request(black_box(body.as_bytes())).unwrap())
+    });
+
+    let chunks: Vec<String> = openai_stream()
+        .into_iter()
+        .filter(|(_, data)| data != b"[DONE]")
+        .map(|(_, data)| String::from_utf8(data).unwrap())
+        .collect();
+    let bytes: usize = chunks.iter().map(String::len).sum();
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_function("stream", |b| {
+        b.iter(|| {
+            black_box(&chunks)
+                .iter()
+                .filter_map(|chunk| legacy_completions::translate_chunk(chunk).unwrap())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, requests, streams, non_streams, legacy);
+criterion_main!(benches);
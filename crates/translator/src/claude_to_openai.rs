@@ -1,7 +1,7 @@
 use crate::TranslateState;
 use crate::common::{
-    build_assistant_message, build_openai_chunk, build_openai_response, build_tool_call,
-    build_tool_call_delta, map_claude_finish_reason,
+    build_assistant_message, build_openai_chunk, build_openai_response, build_openai_usage_chunk,
+    build_tool_call, build_tool_call_delta, map_claude_finish_reason, stream_usage_requested,
 };
 use prism_types::error::ProxyError;
 use serde_json::{Value, json};
@@ -126,7 +126,7 @@ pub fn translate_non_stream(
 
 pub fn translate_stream(
     _model: &str,
-    _original_req: &[u8],
+    original_req: &[u8],
     event_type: Option<&str>,
     data: &[u8],
     state: &mut TranslateState,
@@ -258,29 +258,36 @@ pub fn translate_stream(
                 let finish_reason =
                     map_claude_finish_reason(delta.get("stop_reason").and_then(|v| v.as_str()));
 
-                let mut chunk = build_openai_chunk(
+                let chunk = build_openai_chunk(
                     &state.response_id,
                     state.created,
                     &state.model,
                     json!({}),
                     Some(finish_reason),
                 );
+                chunks.push(serde_json::to_string(&chunk)?);
 
-                // Include usage if available
-                if let Some(usage) = event.get("usage") {
+                // Usage goes in a chunk of its own, only when the client asked for it
+                if let Some(usage) = event.get("usage")
+                    && stream_usage_requested(original_req)
+                {
                     let output_tokens = usage
                         .get("output_tokens")
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0);
                     let input_tokens = state.input_tokens;
-                    chunk["usage"] = json!({
-                        "prompt_tokens": input_tokens,
-                        "completion_tokens": output_tokens,
-                        "total_tokens": input_tokens + output_tokens,
-                    });
+                    let chunk = build_openai_usage_chunk(
+                        &state.response_id,
+                        state.created,
+                        &state.model,
+                        json!({
+                            "prompt_tokens": input_tokens,
+                            "completion_tokens": output_tokens,
+                            "total_tokens": input_tokens + output_tokens,
+                        }),
+                    );
+                    chunks.push(serde_json::to_string(&chunk)?);
                 }
-
-                chunks.push(serde_json::to_string(&chunk)?);
             }
         }
 
@@ -626,12 +633,30 @@ mod tests {
         let chunks =
             translate_stream("model", b"{}", Some("message_delta"), &data, &mut state).unwrap();
 
+        // Without include_usage the client gets no usage at all
         assert_eq!(chunks.len(), 1);
         let chunk = parse_chunk(&chunks[0]);
         assert_eq!(chunk["choices"][0]["finish_reason"], "stop");
-        assert_eq!(chunk["usage"]["prompt_tokens"], 10);
-        assert_eq!(chunk["usage"]["completion_tokens"], 20);
-        assert_eq!(chunk["usage"]["total_tokens"], 30);
+        assert!(chunk.get("usage").is_none());
+
+        let chunks = translate_stream(
+            "model",
+            br#"{"stream":true,"stream_options":{"include_usage":true}}"#,
+            Some("message_delta"),
+            &data,
+            &mut state,
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 2);
+        let finish = parse_chunk(&chunks[0]);
+        assert_eq!(finish["choices"][0]["finish_reason"], "stop");
+        assert!(finish.get("usage").is_none());
+        let usage = parse_chunk(&chunks[1]);
+        assert_eq!(usage["id"], "chatcmpl-test");
+        assert_eq!(usage["choices"], json!([]));
+        assert_eq!(usage["usage"]["prompt_tokens"], 10);
+        assert_eq!(usage["usage"]["completion_tokens"], 20);
+        assert_eq!(usage["usage"]["total_tokens"], 30);
     }
 
     #[test]
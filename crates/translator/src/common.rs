@@ -54,6 +54,32 @@ pub fn build_openai_chunk(
     })
 }
 
+/// Build the terminal chunk OpenAI sends when `stream_options.include_usage`
+/// is set: no choices, only usage.
+pub fn build_openai_usage_chunk(
+    response_id: &str,
+    created: i64,
+    model: &str,
+    usage: Value,
+) -> Value {
+    json!({
+        "id": response_id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [],
+        "usage": usage,
+    })
+}
+
+/// Whether an OpenAI Chat Completions request asks for a terminal usage chunk.
+pub fn stream_usage_requested(openai_req: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(openai_req)
+        .ok()
+        .and_then(|req| req.pointer("/stream_options/include_usage")?.as_bool())
+        .unwrap_or(false)
+}
+
 /// Build a complete OpenAI non-stream response.
 pub fn build_openai_response(
     id: &str,
@@ -128,10 +154,167 @@ pub fn build_assistant_message(content: Option<&str>, tool_calls: Option<Vec<Val
     message
 }
 
+/// Gemini tool keys that enable Google Search grounding, in camelCase and snake_case.
+pub const GEMINI_SEARCH_TOOL_KEYS: &[&str] = &[
+    "googleSearch",
+    "google_search",
+    "googleSearchRetrieval",
+    "google_search_retrieval",
+];
+
+/// Gemini built-in tool keys (search grounding and friends), in camelCase and snake_case.
+pub const GEMINI_BUILTIN_TOOL_KEYS: &[&str] = &[
+    "googleSearch",
+    "google_search",
+    "googleSearchRetrieval",
+    "google_search_retrieval",
+    "codeExecution",
+    "code_execution",
+    "urlContext",
+    "url_context",
+];
+
+/// Whether a Gemini tool entry enables Google Search grounding.
+pub fn is_gemini_search_tool(tool: &Value) -> bool {
+    GEMINI_SEARCH_TOOL_KEYS
+        .iter()
+        .any(|key| tool.get(key).is_some())
+}
+
+/// Convert Gemini `groundingMetadata` into OpenAI `url_citation` annotations.
+///
+/// Each grounding support yields one annotation per referenced web chunk, spanning the
+/// supported segment. Web chunks no support refers to are still listed with an empty span.
+pub fn grounding_to_annotations(metadata: &Value) -> Vec<Value> {
+    let chunks: Vec<&Value> = metadata
+        .get("groundingChunks")
+        .and_then(|c| c.as_array())
+        .map(|arr| arr.iter().collect())
+        .unwrap_or_default();
+    let citation = |index: usize, start: u64, end: u64| {
+        let web = chunks.get(index)?.get("web")?;
+        let url = web.get("uri")?.as_str()?;
+        Some(json!({
+            "type": "url_citation",
+            "url_citation": {
+                "url": url,
+                "title": web.get("title").and_then(|t| t.as_str()).unwrap_or(""),
+                "start_index": start,
+                "end_index": end,
+            }
+        }))
+    };
+
+    let mut annotations = Vec::new();
+    let mut cited = vec![false; chunks.len()];
+    if let Some(supports) = metadata.get("groundingSupports").and_then(|s| s.as_array()) {
+        for support in supports {
+            let segment = support.get("segment");
+            let bound = |key: &str| {
+                segment
+                    .and_then(|s| s.get(key))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0)
+            };
+            let indices = support
+                .get("groundingChunkIndices")
+                .and_then(|i| i.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|i| i.as_u64());
+            for index in indices {
+                let index = index as usize;
+                if let Some(annotation) = citation(index, bound("startIndex"), bound("endIndex")) {
+                    cited[index] = true;
+                    annotations.push(annotation);
+                }
+            }
+        }
+    }
+    for (index, _) in cited.iter().enumerate().filter(|(_, cited)| !**cited) {
+        annotations.extend(citation(index, 0, 0));
+    }
+    annotations
+}
+
+/// Convert OpenAI `url_citation` annotations back into Gemini `groundingMetadata`.
+pub fn annotations_to_grounding(annotations: &[Value]) -> Option<Value> {
+    let mut chunks: Vec<Value> = Vec::new();
+    let mut supports = Vec::new();
+    for annotation in annotations {
+        let Some(citation) = annotation.get("url_citation") else {
+            continue;
+        };
+        let Some(url) = citation.get("url").and_then(|u| u.as_str()) else {
+            continue;
+        };
+        let index = chunks
+            .iter()
+            .position(|chunk| chunk["web"]["uri"] == url)
+            .unwrap_or_else(|| {
+                chunks.push(json!({
+                    "web": {
+                        "uri": url,
+                        "title": citation.get("title").cloned().unwrap_or(json!("")),
+                    }
+                }));
+                chunks.len() - 1
+            });
+        let start = citation
+            .get("start_index")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let end = citation
+            .get("end_index")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        if end > start {
+            supports.push(json!({
+                "segment": {"startIndex": start, "endIndex": end},
+                "groundingChunkIndices": [index],
+            }));
+        }
+    }
+    if chunks.is_empty() {
+        return None;
+    }
+    let mut metadata = json!({"groundingChunks": chunks});
+    if !supports.is_empty() {
+        metadata["groundingSupports"] = Value::Array(supports);
+    }
+    Some(metadata)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_stream_usage_requested() {
+        assert!(stream_usage_requested(
+            br#"{"stream":true,"stream_options":{"include_usage":true}}"#
+        ));
+        assert!(!stream_usage_requested(
+            br#"{"stream":true,"stream_options":{"include_usage":false}}"#
+        ));
+        assert!(!stream_usage_requested(br#"{"stream":true}"#));
+        assert!(!stream_usage_requested(b"not json"));
+    }
+
+    #[test]
+    fn test_is_gemini_search_tool() {
+        assert!(is_gemini_search_tool(&json!({"googleSearch": {}})));
+        assert!(is_gemini_search_tool(
+            &json!({"google_search_retrieval": {}})
+        ));
+        assert!(!is_gemini_search_tool(&json!({"codeExecution": {}})));
+        assert!(
+            GEMINI_SEARCH_TOOL_KEYS
+                .iter()
+                .all(|key| GEMINI_BUILTIN_TOOL_KEYS.contains(key))
+        );
+    }
+
     #[test]
     fn test_map_claude_finish_reason() {
         assert_eq!(map_claude_finish_reason(Some("end_turn")), "stop");
@@ -0,0 +1,510 @@
+//! Shared processing of multimodal content parts.
+//!
+//! Works on translated payloads of any wire format: OpenAI `image_url` data URLs
+//! (Chat and Responses), Claude base64 `source` blocks, and Gemini `inlineData` parts.
+//! Remote image references in Claude and Gemini payloads can be listed and replaced with
+//! inline data fetched by the caller.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// Bytes per pixel reserved when decoding: 16-bit RGBA, the widest supported color type.
+const MAX_BYTES_PER_PIXEL: u64 = 8;
+/// JPEG quality used when re-encoding opaque images.
+const JPEG_QUALITY: u8 = 85;
+/// Resize passes before giving up on reaching `max_bytes`; each pass shrinks the edge by 25%.
+const MAX_RESIZE_PASSES: usize = 5;
+
+/// Size limits applied to inline images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Longest edge in pixels.
+    pub max_dimension: u32,
+    /// Decoded size in bytes.
+    pub max_bytes: usize,
+    /// Largest image, in pixels, that is decoded at all; bigger ones are left untouched.
+    pub max_pixels: u64,
+}
+
+/// Downscaled replacement (`(mime, base64 data)`) per original base64 image data, or `None`
+/// when the image was left as is. Lets repeated passes over the same images skip the decoding.
+pub type DownscaleMemo = HashMap<String, Option<(&'static str, String)>>;
+
+/// Resize and re-encode oversized inline images in `payload`. Returns how many were rewritten.
+///
+/// Images that cannot be decoded, or that declare more than `max_pixels`, are left untouched
+/// for the upstream to judge.
+pub fn downscale_images(payload: &mut Value, limits: &ImageLimits) -> usize {
+    downscale_images_memoized(payload, limits, &mut DownscaleMemo::new())
+}
+
+/// [`downscale_images`], reusing the outcome for images already in `memo` and recording
+/// the rest.
+pub fn downscale_images_memoized(
+    payload: &mut Value,
+    limits: &ImageLimits,
+    memo: &mut DownscaleMemo,
+) -> usize {
+    let mut pass = Pass {
+        limits,
+        memo,
+        rewritten: 0,
+    };
+    visit(payload, &mut pass);
+    pass.rewritten
+}
+
+struct Pass<'a> {
+    limits: &'a ImageLimits,
+    memo: &'a mut DownscaleMemo,
+    rewritten: usize,
+}
+
+impl Pass<'_> {
+    fn shrink(&mut self, mime: &str, data: &str) -> Option<(&'static str, String)> {
+        if !mime.starts_with("image/") {
+            return None;
+        }
+        let shrunk = match self.memo.get(data) {
+            Some(shrunk) => shrunk.clone(),
+            None => {
+                let shrunk = shrink_base64(data, self.limits);
+                self.memo.insert(data.to_string(), shrunk.clone());
+                shrunk
+            }
+        };
+        if shrunk.is_some() {
+            self.rewritten += 1;
+        }
+        shrunk
+    }
+}
+
+fn visit(value: &mut Value, pass: &mut Pass<'_>) {
+    match value {
+        Value::Object(map) => {
+            // OpenAI Chat `image_url: {url}` and Responses `image_url: "data:..."`.
+            match map.get_mut("image_url") {
+                Some(Value::String(url)) => rewrite_data_url(url, pass),
+                Some(Value::Object(image_url)) => {
+                    if let Some(Value::String(url)) = image_url.get_mut("url") {
+                        rewrite_data_url(url, pass);
+                    }
+                }
+                _ => {}
+            }
+            // Claude `{type: image, source: {type: base64, media_type, data}}`.
+            if map.get("type").and_then(|t| t.as_str()) == Some("image")
+                && let Some(Value::Object(source)) = map.get_mut("source")
+                && source.get("type").and_then(|t| t.as_str()) == Some("base64")
+            {
+                rewrite_inline(source, "media_type", pass);
+            }
+            // Gemini `inlineData: {mimeType, data}` (or snake_case).
+            for (key, mime_key) in [("inlineData", "mimeType"), ("inline_data", "mime_type")] {
+                if let Some(Value::Object(inline)) = map.get_mut(key) {
+                    rewrite_inline(inline, mime_key, pass);
+                }
+            }
+            for child in map.values_mut() {
+                visit(child, pass);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit(item, pass);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rewrite_data_url(url: &mut String, pass: &mut Pass<'_>) {
+    let Some((mime, data)) = parse_data_url(url) else {
+        return;
+    };
+    if let Some((mime, data)) = pass.shrink(mime, data) {
+        *url = format!("data:{mime};base64,{data}");
+    }
+}
+
+fn rewrite_inline(
+    inline: &mut serde_json::Map<String, Value>,
+    mime_key: &str,
+    pass: &mut Pass<'_>,
+) {
+    let Some(mime) = inline.get(mime_key).and_then(|m| m.as_str()) else {
+        return;
+    };
+    let Some(data) = inline.get("data").and_then(|d| d.as_str()) else {
+        return;
+    };
+    if let Some((mime, data)) = pass.shrink(mime, data) {
+        inline.insert(mime_key.to_string(), Value::String(mime.to_string()));
+        inline.insert("data".to_string(), Value::String(data));
+    }
+}
+
+/// Image bytes fetched for a remote URL, ready to be inlined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineImage {
+    pub mime_type: String,
+    /// Base64-encoded image bytes.
+    pub data: String,
+}
+
+impl InlineImage {
+    pub fn new(mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            data: STANDARD.encode(bytes),
+        }
+    }
+}
+
+/// Remote http(s) image URLs a Claude or Gemini payload references, deduplicated in order.
+///
+/// Covers Claude `{type: image, source: {type: url}}` blocks and Gemini `fileData` parts
+/// with an `image/*` mime type.
+pub fn remote_image_urls(payload: &Value) -> Vec<String> {
+    let mut urls = Vec::new();
+    collect_remote_urls(payload, &mut urls);
+    urls
+}
+
+fn collect_remote_urls(value: &Value, urls: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(url) = remote_image_url(map)
+                && !urls.iter().any(|existing| existing == url)
+            {
+                urls.push(url.to_string());
+            }
+            for child in map.values() {
+                collect_remote_urls(child, urls);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_remote_urls(item, urls);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn remote_image_url(map: &serde_json::Map<String, Value>) -> Option<&str> {
+    let is_http = |url: &&str| url.starts_with("http://") || url.starts_with("https://");
+    if map.get("type").and_then(|t| t.as_str()) == Some("image")
+        && let Some(source) = map.get("source")
+        && source.get("type").and_then(|t| t.as_str()) == Some("url")
+    {
+        return source.get("url").and_then(|u| u.as_str()).filter(is_http);
+    }
+    for (key, mime_key, uri_key) in [
+        ("fileData", "mimeType", "fileUri"),
+        ("file_data", "mime_type", "file_uri"),
+    ] {
+        if let Some(file) = map.get(key)
+            && file
+                .get(mime_key)
+                .and_then(|m| m.as_str())
+                .is_some_and(|mime| mime.starts_with("image/"))
+        {
+            return file.get(uri_key).and_then(|u| u.as_str()).filter(is_http);
+        }
+    }
+    None
+}
+
+/// Replace remote image references with the fetched inline data. Returns how many were replaced.
+///
+/// URLs missing from `fetched` keep their remote reference.
+pub fn inline_remote_images(payload: &mut Value, fetched: &HashMap<String, InlineImage>) -> usize {
+    let mut replaced = 0;
+    replace_remote(payload, fetched, &mut replaced);
+    replaced
+}
+
+fn replace_remote(value: &mut Value, fetched: &HashMap<String, InlineImage>, replaced: &mut usize) {
+    match value {
+        Value::Object(map) => {
+            if let Some(image) = remote_image_url(map).and_then(|url| fetched.get(url)) {
+                if map.contains_key("source") {
+                    map.insert(
+                        "source".to_string(),
+                        json!({"type": "base64", "media_type": image.mime_type, "data": image.data}),
+                    );
+                } else {
+                    map.remove("fileData");
+                    map.remove("file_data");
+                    map.insert(
+                        "inlineData".to_string(),
+                        json!({"mimeType": image.mime_type, "data": image.data}),
+                    );
+                }
+                *replaced += 1;
+                return;
+            }
+            for child in map.values_mut() {
+                replace_remote(child, fetched, replaced);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                replace_remote(item, fetched, replaced);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Split `data:<mime>;base64,<data>` into its media type and payload.
+pub fn parse_data_url(url: &str) -> Option<(&str, &str)> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let mime = header.strip_suffix(";base64")?;
+    Some((mime, data))
+}
+
+fn shrink_base64(data: &str, limits: &ImageLimits) -> Option<(&'static str, String)> {
+    let bytes = STANDARD.decode(data).ok()?;
+    let (encoded, mime) = shrink_image(&bytes, limits)?;
+    tracing::debug!(
+        before = bytes.len(),
+        after = encoded.len(),
+        mime,
+        "Downscaled inline image"
+    );
+    Some((mime, STANDARD.encode(encoded)))
+}
+
+/// Resize and re-encode `bytes` when it exceeds `limits`; `None` when it already fits.
+fn shrink_image(bytes: &[u8], limits: &ImageLimits) -> Option<(Vec<u8>, &'static str)> {
+    let (width, height) = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    let longest = width.max(height);
+    if bytes.len() <= limits.max_bytes && longest <= limits.max_dimension {
+        return None;
+    }
+    let pixels = u64::from(width) * u64::from(height);
+    if pixels > limits.max_pixels {
+        tracing::debug!(width, height, "Skipping image above the decode pixel limit");
+        return None;
+    }
+
+    // Hold the decoder to the checked header size, so a lying header cannot allocate more.
+    let mut decode_limits = image::Limits::default();
+    decode_limits.max_image_width = Some(width);
+    decode_limits.max_image_height = Some(height);
+    decode_limits.max_alloc = Some(pixels * MAX_BYTES_PER_PIXEL);
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?;
+    reader.limits(decode_limits);
+    let image = reader.decode().ok()?;
+    let mut edge = longest.min(limits.max_dimension).max(1);
+    let mut best = None;
+    for _ in 0..MAX_RESIZE_PASSES {
+        let resized = if longest > edge {
+            image.resize(edge, edge, FilterType::Triangle)
+        } else {
+            image.clone()
+        };
+        let encoded = encode(&resized)?;
+        let fits = encoded.0.len() <= limits.max_bytes;
+        best = Some(encoded);
+        if fits || edge == 1 {
+            break;
+        }
+        edge = (edge * 3 / 4).max(1);
+    }
+    best
+}
+
+/// Encode as PNG when the image has transparency, JPEG otherwise.
+fn encode(image: &DynamicImage) -> Option<(Vec<u8>, &'static str)> {
+    let mut buf = Vec::new();
+    if image.color().has_alpha() {
+        image.write_with_encoder(PngEncoder::new(&mut buf)).ok()?;
+        Some((buf, "image/png"))
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY))
+            .ok()?;
+        Some((buf, "image/jpeg"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn png(width: u32, height: u32) -> String {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(width, height, |x, y| {
+            // Noisy pixels so PNG compression cannot hide the size.
+            let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729) ^ (x * y)) as u8;
+            image::Rgb([noise, noise.rotate_left(3), 128])
+        }));
+        let mut buf = Vec::new();
+        image.write_with_encoder(PngEncoder::new(&mut buf)).unwrap();
+        STANDARD.encode(buf)
+    }
+
+    fn dimensions(data: &str) -> (u32, u32) {
+        let bytes = STANDARD.decode(data).unwrap();
+        let image = image::load_from_memory(&bytes).unwrap();
+        (image.width(), image.height())
+    }
+
+    const LIMITS: ImageLimits = ImageLimits {
+        max_dimension: 64,
+        max_bytes: 1024 * 1024,
+        max_pixels: 1 << 20,
+    };
+
+    #[test]
+    fn test_downscales_images_in_every_format() {
+        let data = png(256, 128);
+        let mut payload = json!({
+            "messages": [{"content": [
+                {"type": "image_url", "image_url": {"url": format!("data:image/png;base64,{data}")}},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": data}},
+                {"type": "input_image", "image_url": format!("data:image/png;base64,{data}")}
+            ]}],
+            "contents": [{"parts": [{"inlineData": {"mimeType": "image/png", "data": data}}]}]
+        });
+
+        assert_eq!(downscale_images(&mut payload, &LIMITS), 4);
+
+        let parts = &payload["messages"][0]["content"];
+        let (mime, chat) = parse_data_url(parts[0]["image_url"]["url"].as_str().unwrap()).unwrap();
+        assert_eq!(mime, "image/jpeg");
+        assert_eq!(dimensions(chat), (64, 32));
+        assert_eq!(parts[1]["source"]["media_type"], "image/jpeg");
+        assert_eq!(
+            dimensions(parts[1]["source"]["data"].as_str().unwrap()),
+            (64, 32)
+        );
+        assert!(
+            parts[2]["image_url"]
+                .as_str()
+                .unwrap()
+                .starts_with("data:image/jpeg;base64,")
+        );
+        let inline = &payload["contents"][0]["parts"][0]["inlineData"];
+        assert_eq!(inline["mimeType"], "image/jpeg");
+        assert_eq!(dimensions(inline["data"].as_str().unwrap()), (64, 32));
+    }
+
+    #[test]
+    fn test_leaves_small_remote_and_invalid_images_untouched() {
+        let small = png(32, 32);
+        let mut payload = json!({"content": [
+            {"type": "image_url", "image_url": {"url": format!("data:image/png;base64,{small}")}},
+            {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "bm90IGFuIGltYWdl"}}
+        ]});
+        let before = payload.clone();
+
+        assert_eq!(downscale_images(&mut payload, &LIMITS), 0);
+        assert_eq!(payload, before);
+    }
+
+    #[test]
+    fn test_shrinks_until_byte_limit_fits() {
+        let data = png(256, 256);
+        let limits = ImageLimits {
+            max_dimension: 4096,
+            max_bytes: 16_000,
+            max_pixels: 1 << 20,
+        };
+        let mut payload = json!({"inlineData": {"mimeType": "image/png", "data": data}});
+
+        assert_eq!(downscale_images(&mut payload, &limits), 1);
+        let bytes = STANDARD
+            .decode(payload["inlineData"]["data"].as_str().unwrap())
+            .unwrap();
+        assert!(bytes.len() <= 16_000, "still {} bytes", bytes.len());
+    }
+
+    #[test]
+    fn test_memoized_downscale_reuses_results_across_formats() {
+        let data = png(256, 128);
+        let mut memo = DownscaleMemo::new();
+        let mut gemini = json!({"inlineData": {"mimeType": "image/png", "data": data}});
+        let mut claude = json!({"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": data}});
+
+        assert_eq!(
+            downscale_images_memoized(&mut gemini, &LIMITS, &mut memo),
+            1
+        );
+        assert_eq!(
+            downscale_images_memoized(&mut claude, &LIMITS, &mut memo),
+            1
+        );
+        assert_eq!(memo.len(), 1);
+        assert_eq!(claude["source"]["data"], gemini["inlineData"]["data"]);
+        assert_eq!(claude["source"]["media_type"], "image/jpeg");
+    }
+
+    #[test]
+    fn test_skips_images_above_pixel_limit() {
+        let limits = ImageLimits {
+            max_pixels: 128 * 128,
+            ..LIMITS
+        };
+        let mut payload = json!({"inlineData": {"mimeType": "image/png", "data": png(256, 256)}});
+        let before = payload.clone();
+
+        assert_eq!(downscale_images(&mut payload, &limits), 0);
+        assert_eq!(payload, before);
+    }
+
+    #[test]
+    fn test_inlines_fetched_remote_images_for_claude_and_gemini() {
+        let mut payload = json!({
+            "messages": [{"content": [
+                {"type": "image", "source": {"type": "url", "url": "https://img.example/a.png"}},
+                {"type": "image", "source": {"type": "url", "url": "https://img.example/missing.png"}}
+            ]}],
+            "contents": [{"parts": [
+                {"fileData": {"mimeType": "image/png", "fileUri": "https://img.example/a.png"}},
+                {"fileData": {"mimeType": "application/pdf", "fileUri": "https://img.example/doc.pdf"}}
+            ]}]
+        });
+        assert_eq!(
+            remote_image_urls(&payload),
+            vec![
+                "https://img.example/a.png",
+                "https://img.example/missing.png"
+            ]
+        );
+
+        let fetched = HashMap::from([(
+            "https://img.example/a.png".to_string(),
+            InlineImage::new("image/png", b"png-bytes"),
+        )]);
+        assert_eq!(inline_remote_images(&mut payload, &fetched), 2);
+
+        let claude = &payload["messages"][0]["content"];
+        assert_eq!(claude[0]["source"]["type"], "base64");
+        assert_eq!(claude[0]["source"]["media_type"], "image/png");
+        assert_eq!(claude[0]["source"]["data"], STANDARD.encode(b"png-bytes"));
+        assert_eq!(claude[1]["source"]["type"], "url");
+        let gemini = &payload["contents"][0]["parts"];
+        assert_eq!(gemini[0]["inlineData"]["mimeType"], "image/png");
+        assert!(gemini[0].get("fileData").is_none());
+        assert_eq!(gemini[1]["fileData"]["mimeType"], "application/pdf");
+    }
+}
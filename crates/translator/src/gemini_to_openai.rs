@@ -1,7 +1,8 @@
 use crate::TranslateState;
 use crate::common::{
-    build_assistant_message, build_openai_chunk, build_openai_response, build_tool_call,
-    build_tool_call_delta, map_gemini_finish_reason,
+    build_assistant_message, build_openai_chunk, build_openai_response, build_openai_usage_chunk,
+    build_tool_call, build_tool_call_delta, grounding_to_annotations, map_gemini_finish_reason,
+    stream_usage_requested,
 };
 use prism_types::error::ProxyError;
 use serde_json::{Value, json};
@@ -27,6 +28,11 @@ pub fn translate_non_stream(
         .and_then(|c| c.as_array())
         .and_then(|arr| arr.first());
 
+    let annotations = candidate
+        .and_then(|c| c.get("groundingMetadata"))
+        .map(grounding_to_annotations)
+        .unwrap_or_default();
+
     let (content_str, tool_calls, finish_reason) = if let Some(candidate) = candidate {
         let parts = candidate
             .get("content")
@@ -71,7 +77,10 @@ pub fn translate_non_stream(
     } else {
         Some(tool_calls)
     };
-    let message = build_assistant_message(content, tc);
+    let mut message = build_assistant_message(content, tc);
+    if !annotations.is_empty() {
+        message["annotations"] = Value::Array(annotations);
+    }
 
     // Map usage
     let usage = if let Some(u) = resp.get("usageMetadata") {
@@ -87,11 +96,13 @@ pub fn translate_non_stream(
             .get("totalTokenCount")
             .and_then(|v| v.as_u64())
             .unwrap_or(prompt + completion);
-        Some(json!({
+        let mut usage = json!({
             "prompt_tokens": prompt,
             "completion_tokens": completion,
             "total_tokens": total,
-        }))
+        });
+        add_cached_tokens(&mut usage, u);
+        Some(usage)
     } else {
         None
     };
@@ -102,7 +113,7 @@ pub fn translate_non_stream(
 
 pub fn translate_stream(
     _model: &str,
-    _original_req: &[u8],
+    original_req: &[u8],
     _event_type: Option<&str>,
     data: &[u8],
     state: &mut TranslateState,
@@ -182,20 +193,39 @@ pub fn translate_stream(
             }
         }
 
+        // Grounding citations usually arrive with the final chunk.
+        let annotations = candidate
+            .get("groundingMetadata")
+            .map(grounding_to_annotations)
+            .unwrap_or_default();
+        if !annotations.is_empty() {
+            let chunk = build_openai_chunk(
+                &state.response_id,
+                state.created,
+                &state.model,
+                json!({"annotations": annotations}),
+                None,
+            );
+            chunks.push(serde_json::to_string(&chunk)?);
+        }
+
         // Check for finish_reason
         if let Some(finish) = candidate.get("finishReason").and_then(|v| v.as_str()) {
             let finish_reason = map_gemini_finish_reason(Some(finish));
 
-            let mut chunk = build_openai_chunk(
+            let chunk = build_openai_chunk(
                 &state.response_id,
                 state.created,
                 &state.model,
                 json!({}),
                 Some(finish_reason),
             );
+            chunks.push(serde_json::to_string(&chunk)?);
 
-            // Include usage if available
-            if let Some(u) = resp.get("usageMetadata") {
+            // Usage goes in a chunk of its own, only when the client asked for it
+            if let Some(u) = resp.get("usageMetadata")
+                && stream_usage_requested(original_req)
+            {
                 let prompt = u
                     .get("promptTokenCount")
                     .and_then(|v| v.as_u64())
@@ -204,14 +234,21 @@ pub fn translate_stream(
                     .get("candidatesTokenCount")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0);
-                chunk["usage"] = json!({
+                let mut usage = json!({
                     "prompt_tokens": prompt,
                     "completion_tokens": completion,
                     "total_tokens": prompt + completion,
                 });
+                add_cached_tokens(&mut usage, u);
+                let chunk = build_openai_usage_chunk(
+                    &state.response_id,
+                    state.created,
+                    &state.model,
+                    usage,
+                );
+                chunks.push(serde_json::to_string(&chunk)?);
             }
 
-            chunks.push(serde_json::to_string(&chunk)?);
             chunks.push("[DONE]".to_string());
         }
     }
@@ -219,6 +256,17 @@ pub fn translate_stream(
     Ok(chunks)
 }
 
+/// Report Gemini context-cache hits the way OpenAI reports prompt cache hits.
+fn add_cached_tokens(usage: &mut Value, usage_metadata: &Value) {
+    if let Some(cached) = usage_metadata
+        .get("cachedContentTokenCount")
+        .and_then(|v| v.as_u64())
+        .filter(|cached| *cached > 0)
+    {
+        usage["prompt_tokens_details"] = json!({ "cached_tokens": cached });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,6 +304,31 @@ mod tests {
         assert_eq!(result["usage"]["prompt_tokens"], 10);
         assert_eq!(result["usage"]["completion_tokens"], 5);
         assert_eq!(result["usage"]["total_tokens"], 15);
+        assert!(result["usage"].get("prompt_tokens_details").is_none());
+    }
+
+    #[test]
+    fn test_non_stream_cached_content_usage() {
+        let gemini_resp = json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "ok"}], "role": "model"},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {
+                "promptTokenCount": 1200,
+                "candidatesTokenCount": 5,
+                "cachedContentTokenCount": 1000,
+                "totalTokenCount": 1205
+            }
+        });
+        let data = serde_json::to_vec(&gemini_resp).unwrap();
+        let result: Value =
+            serde_json::from_str(&translate_non_stream("model", b"{}", &data).unwrap()).unwrap();
+        assert_eq!(result["usage"]["prompt_tokens"], 1200);
+        assert_eq!(
+            result["usage"]["prompt_tokens_details"]["cached_tokens"],
+            1000
+        );
     }
 
     #[test]
@@ -452,17 +525,53 @@ mod tests {
         let data = serde_json::to_vec(&resp).unwrap();
         let chunks = translate_stream("model", b"{}", None, &data, &mut state).unwrap();
 
-        // Should have content chunk, finish chunk, and [DONE]
-        assert!(chunks.len() >= 3);
-        let last_json = chunks.iter().rev().nth(1).unwrap();
-        let finish_chunk = parse_chunk(last_json);
+        // Content chunk, finish chunk, and [DONE]; no usage unless requested
+        assert_eq!(chunks.len(), 3);
+        let finish_chunk = parse_chunk(&chunks[1]);
         assert_eq!(finish_chunk["choices"][0]["finish_reason"], "stop");
-        assert_eq!(finish_chunk["usage"]["prompt_tokens"], 10);
-        assert_eq!(finish_chunk["usage"]["completion_tokens"], 5);
-
+        assert!(finish_chunk.get("usage").is_none());
         assert_eq!(chunks.last().unwrap(), "[DONE]");
     }
 
+    #[test]
+    fn test_stream_include_usage_emits_terminal_usage_chunk() {
+        let mut state = new_state();
+        state.response_id = "chatcmpl-test".to_string();
+        state.created = 1000;
+        state.model = "gemini".to_string();
+
+        let resp = json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "Done"}], "role": "model"},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {
+                "promptTokenCount": 10,
+                "candidatesTokenCount": 5
+            }
+        });
+        let data = serde_json::to_vec(&resp).unwrap();
+        let chunks = translate_stream(
+            "model",
+            br#"{"stream":true,"stream_options":{"include_usage":true}}"#,
+            None,
+            &data,
+            &mut state,
+        )
+        .unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        let finish_chunk = parse_chunk(&chunks[1]);
+        assert_eq!(finish_chunk["choices"][0]["finish_reason"], "stop");
+        assert!(finish_chunk.get("usage").is_none());
+        let usage_chunk = parse_chunk(&chunks[2]);
+        assert_eq!(usage_chunk["choices"], json!([]));
+        assert_eq!(usage_chunk["usage"]["prompt_tokens"], 10);
+        assert_eq!(usage_chunk["usage"]["completion_tokens"], 5);
+        assert_eq!(usage_chunk["usage"]["total_tokens"], 15);
+        assert_eq!(chunks[3], "[DONE]");
+    }
+
     #[test]
     fn test_stream_model_version_update() {
         let mut state = new_state();
@@ -494,4 +603,60 @@ mod tests {
         let chunks = translate_stream("model", b"{}", None, &data, &mut state).unwrap();
         assert!(chunks.is_empty());
     }
+
+    fn grounded_response() -> Value {
+        json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "Rust 1.95 shipped."}], "role": "model"},
+                "finishReason": "STOP",
+                "groundingMetadata": {
+                    "webSearchQueries": ["rust release"],
+                    "groundingChunks": [
+                        {"web": {"uri": "https://blog.rust-lang.org", "title": "Rust Blog"}},
+                        {"web": {"uri": "https://example.com", "title": "Example"}}
+                    ],
+                    "groundingSupports": [{
+                        "segment": {"startIndex": 0, "endIndex": 18, "text": "Rust 1.95 shipped."},
+                        "groundingChunkIndices": [0]
+                    }]
+                }
+            }]
+        })
+    }
+
+    #[test]
+    fn test_non_stream_grounding_to_annotations() {
+        let data = serde_json::to_vec(&grounded_response()).unwrap();
+        let result: Value =
+            serde_json::from_str(&translate_non_stream("m", b"{}", &data).unwrap()).unwrap();
+        let annotations = result["choices"][0]["message"]["annotations"]
+            .as_array()
+            .unwrap();
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0]["type"], "url_citation");
+        assert_eq!(
+            annotations[0]["url_citation"]["url"],
+            "https://blog.rust-lang.org"
+        );
+        assert_eq!(annotations[0]["url_citation"]["end_index"], 18);
+        assert_eq!(annotations[1]["url_citation"]["title"], "Example");
+        assert_eq!(annotations[1]["url_citation"]["end_index"], 0);
+    }
+
+    #[test]
+    fn test_stream_grounding_emits_annotations_chunk() {
+        let data = serde_json::to_vec(&grounded_response()).unwrap();
+        let mut state = new_state();
+        let chunks = translate_stream("m", b"{}", None, &data, &mut state).unwrap();
+        let annotated = chunks
+            .iter()
+            .filter_map(|c| serde_json::from_str::<Value>(c).ok())
+            .find(|c| c["choices"][0]["delta"].get("annotations").is_some())
+            .expect("annotations chunk");
+        assert_eq!(
+            annotated["choices"][0]["delta"]["annotations"][0]["url_citation"]["url"],
+            "https://blog.rust-lang.org"
+        );
+        assert_eq!(chunks.last().unwrap(), "[DONE]");
+    }
 }
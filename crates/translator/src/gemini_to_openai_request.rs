@@ -1,3 +1,4 @@
+use crate::common::is_gemini_search_tool;
 use prism_types::error::ProxyError;
 use serde_json::{Value, json};
 
@@ -46,6 +47,14 @@ pub fn translate_request(
     if let Some(tools) = convert_tools(&req) {
         openai_req["tools"] = tools;
     }
+    // Google Search grounding maps to Chat Completions web search.
+    if req
+        .get("tools")
+        .and_then(|t| t.as_array())
+        .is_some_and(|tools| tools.iter().any(is_gemini_search_tool))
+    {
+        openai_req["web_search_options"] = json!({});
+    }
 
     serde_json::to_vec(&openai_req).map_err(|e| ProxyError::Translation(e.to_string()))
 }
@@ -432,4 +441,15 @@ mod tests {
         assert_eq!(result["messages"].as_array().unwrap().len(), 1);
         assert_eq!(result["messages"][0]["role"], "user");
     }
+
+    #[test]
+    fn test_google_search_tool_to_web_search_options() {
+        let req = json!({
+            "contents": [{"role": "user", "parts": [{"text": "News?"}]}],
+            "tools": [{"googleSearch": {}}]
+        });
+        let result = translate(req);
+        assert_eq!(result["web_search_options"], json!({}));
+        assert!(result.get("tools").is_none());
+    }
 }
@@ -0,0 +1,258 @@
+//! Legacy OpenAI Completions (`/v1/completions`) on top of Chat Completions.
+//!
+//! Providers only serve chat, so a prompt-style request is rewritten as a single
+//! user message and the chat response, streamed or not, is rewritten as
+//! `text_completion` objects.
+
+use prism_types::error::ProxyError;
+use serde_json::{Map, Value, json};
+
+/// Completions-only fields with no chat equivalent. Requests that use them are
+/// rejected rather than silently answered differently.
+const UNSUPPORTED_FIELDS: &[&str] = &["suffix", "echo", "logprobs", "best_of"];
+
+/// Translate a Completions request into a Chat Completions request. Fields the
+/// two APIs share (`model`, `max_tokens`, `stop`, `n`, `stream`, ...) are kept.
+pub fn translate_request(body: &[u8]) -> Result<Vec<u8>, ProxyError> {
+    let mut req: Map<String, Value> = serde_json::from_slice(body)?;
+    for field in UNSUPPORTED_FIELDS {
+        if let Some(value) = req.remove(*field)
+            && !is_default(field, &value)
+        {
+            return Err(ProxyError::BadRequest(format!(
+                "'{field}' is not supported by /v1/completions on chat providers"
+            )));
+        }
+    }
+    let prompt = req
+        .remove("prompt")
+        .ok_or_else(|| ProxyError::BadRequest("missing prompt field".to_string()))?;
+    let content = prompt_text(prompt)?;
+    req.insert(
+        "messages".into(),
+        json!([{"role": "user", "content": content}]),
+    );
+    serde_json::to_vec(&req).map_err(|e| ProxyError::Translation(e.to_string()))
+}
+
+/// Translate a `chat.completion` response into a `text_completion` response.
+pub fn translate_non_stream(data: &[u8]) -> Result<String, ProxyError> {
+    let resp: Value = serde_json::from_slice(data)?;
+    let choices: Vec<Value> = resp
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .map(|choices| {
+            choices
+                .iter()
+                .map(|choice| {
+                    text_choice(
+                        choice,
+                        choice.pointer("/message/content").and_then(|c| c.as_str()),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let completion = text_completion(&resp, choices);
+    serde_json::to_string(&completion).map_err(|e| ProxyError::Translation(e.to_string()))
+}
+
+/// Translate one `chat.completion.chunk` into a `text_completion` chunk.
+/// Chunks carrying neither text, a finish reason, nor usage (the initial role
+/// chunk) have no Completions counterpart and return `None`.
+pub fn translate_chunk(data: &str) -> Result<Option<String>, ProxyError> {
+    let chunk: Value = serde_json::from_str(data)?;
+    let choices: Vec<Value> = chunk
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .map(|choices| {
+            choices
+                .iter()
+                .map(|choice| {
+                    text_choice(
+                        choice,
+                        choice.pointer("/delta/content").and_then(|c| c.as_str()),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let has_content = choices.iter().any(|choice| {
+        choice["text"].as_str().is_some_and(|text| !text.is_empty())
+            || !choice["finish_reason"].is_null()
+    });
+    if !has_content && chunk.get("usage").is_none_or(Value::is_null) {
+        return Ok(None);
+    }
+    let completion = text_completion(&chunk, choices);
+    serde_json::to_string(&completion)
+        .map(Some)
+        .map_err(|e| ProxyError::Translation(e.to_string()))
+}
+
+fn is_default(field: &str, value: &Value) -> bool {
+    match field {
+        "echo" => value.as_bool() != Some(true),
+        "best_of" => value.is_null() || value.as_u64() == Some(1),
+        "suffix" => value.is_null() || value.as_str() == Some(""),
+        _ => value.is_null(),
+    }
+}
+
+/// The prompt as message text: a string, or an array holding one string.
+/// Several prompts (a batch) and token-id prompts cannot be expressed as chat.
+fn prompt_text(prompt: Value) -> Result<String, ProxyError> {
+    match prompt {
+        Value::String(text) => Ok(text),
+        Value::Array(mut items) if items.len() == 1 && items[0].is_string() => {
+            Ok(items.remove(0).as_str().unwrap_or_default().to_string())
+        }
+        Value::Array(items) if items.iter().all(Value::is_string) => Err(ProxyError::BadRequest(
+            "only a single prompt is supported by /v1/completions on chat providers".to_string(),
+        )),
+        _ => Err(ProxyError::BadRequest(
+            "token-id prompts are not supported by /v1/completions on chat providers".to_string(),
+        )),
+    }
+}
+
+fn text_choice(choice: &Value, text: Option<&str>) -> Value {
+    json!({
+        "text": text.unwrap_or_default(),
+        "index": choice.get("index").cloned().unwrap_or(json!(0)),
+        "logprobs": null,
+        "finish_reason": choice.get("finish_reason").cloned().unwrap_or(Value::Null),
+    })
+}
+
+fn text_completion(chat: &Value, choices: Vec<Value>) -> Value {
+    let mut completion = json!({
+        "id": chat.get("id").cloned().unwrap_or(json!("")),
+        "object": "text_completion",
+        "created": chat.get("created").cloned().unwrap_or(json!(0)),
+        "model": chat.get("model").cloned().unwrap_or(json!("")),
+        "choices": choices,
+    });
+    for field in ["system_fingerprint", "usage"] {
+        if let Some(value) = chat.get(field).filter(|v| !v.is_null()) {
+            completion[field] = value.clone();
+        }
+    }
+    completion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_request_prompt_to_user_message() {
+        let body = json!({
+            "model": "gpt-4o-mini",
+            "prompt": "Say this is a test",
+            "max_tokens": 7,
+            "stop": ["\n"],
+            "echo": false,
+            "stream": true
+        });
+        let out = translate_request(body.to_string().as_bytes()).unwrap();
+        let req: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            req["messages"],
+            json!([{"role": "user", "content": "Say this is a test"}])
+        );
+        assert_eq!(req["max_tokens"], 7);
+        assert_eq!(req["stop"], json!(["\n"]));
+        assert_eq!(req["stream"], true);
+        assert!(req.get("prompt").is_none());
+        assert!(req.get("echo").is_none());
+    }
+
+    #[test]
+    fn test_translate_request_single_prompt_array() {
+        let body = json!({"model": "m", "prompt": ["hello"]});
+        let out = translate_request(body.to_string().as_bytes()).unwrap();
+        let req: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(req["messages"][0]["content"], "hello");
+    }
+
+    #[test]
+    fn test_translate_request_rejects_what_chat_cannot_express() {
+        for body in [
+            json!({"model": "m", "prompt": ["a", "b"]}),
+            json!({"model": "m", "prompt": [1, 2, 3]}),
+            json!({"model": "m", "prompt": "a", "echo": true}),
+            json!({"model": "m", "prompt": "a", "suffix": "end"}),
+            json!({"model": "m", "prompt": "a", "logprobs": 2}),
+            json!({"model": "m", "prompt": "a", "best_of": 3}),
+        ] {
+            let err = translate_request(body.to_string().as_bytes()).unwrap_err();
+            assert!(matches!(err, ProxyError::BadRequest(_)), "{body}");
+        }
+    }
+
+    #[test]
+    fn test_translate_non_stream() {
+        let resp = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1700000000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "This is a test."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 4, "total_tokens": 9}
+        });
+        let out = translate_non_stream(resp.to_string().as_bytes()).unwrap();
+        let completion: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(completion["object"], "text_completion");
+        assert_eq!(completion["id"], "chatcmpl-1");
+        assert_eq!(completion["choices"][0]["text"], "This is a test.");
+        assert_eq!(completion["choices"][0]["finish_reason"], "stop");
+        assert!(completion["choices"][0]["logprobs"].is_null());
+        assert_eq!(completion["usage"]["total_tokens"], 9);
+    }
+
+    #[test]
+    fn test_translate_chunk() {
+        let role = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "choices": [{"index": 0, "delta": {"role": "assistant"}, "finish_reason": null}]
+        });
+        assert_eq!(translate_chunk(&role.to_string()).unwrap(), None);
+
+        let text = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1700000000,
+            "model": "gpt-4o-mini",
+            "choices": [{"index": 0, "delta": {"content": "This"}, "finish_reason": null}]
+        });
+        let out = translate_chunk(&text.to_string()).unwrap().unwrap();
+        let chunk: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(chunk["object"], "text_completion");
+        assert_eq!(chunk["choices"][0]["text"], "This");
+        assert!(chunk["choices"][0]["finish_reason"].is_null());
+
+        let done = json!({
+            "id": "chatcmpl-1",
+            "choices": [{"index": 0, "delta": {}, "finish_reason": "length"}]
+        });
+        let out = translate_chunk(&done.to_string()).unwrap().unwrap();
+        let chunk: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(chunk["choices"][0]["text"], "");
+        assert_eq!(chunk["choices"][0]["finish_reason"], "length");
+
+        let usage = json!({
+            "id": "chatcmpl-1",
+            "choices": [],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 4, "total_tokens": 9}
+        });
+        let out = translate_chunk(&usage.to_string()).unwrap().unwrap();
+        let chunk: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(chunk["usage"]["completion_tokens"], 4);
+    }
+}
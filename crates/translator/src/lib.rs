@@ -1,16 +1,21 @@
 pub mod claude_to_openai;
 pub mod claude_to_openai_request;
 pub mod common;
+pub mod content;
 pub mod gemini_to_openai;
 pub mod gemini_to_openai_request;
+pub mod legacy_completions;
 pub mod openai_to_claude;
 pub mod openai_to_claude_response;
 pub mod openai_to_gemini;
 pub mod openai_to_gemini_response;
+pub mod rules;
 
 use prism_types::error::ProxyError;
 use prism_types::format::Format;
+use rules::{TranslationRule, TranslationRules};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 /// State accumulated during stream translation.
 #[derive(Debug, Default)]
@@ -59,14 +64,76 @@ pub type StreamTransformFn = fn(
 pub type NonStreamTransformFn =
     fn(model: &str, original_req: &[u8], data: &[u8]) -> Result<String, ProxyError>;
 
+/// Translates a client request body into the upstream format.
+///
+/// Implemented for plain functions and closures with the
+/// [`RequestTransformFn`] signature; implement it directly for translators
+/// that carry configuration.
+pub trait RequestTranslator: Send + Sync {
+    fn translate(&self, model: &str, raw_json: &[u8], stream: bool) -> Result<Vec<u8>, ProxyError>;
+}
+
+impl<F> RequestTranslator for F
+where
+    F: Fn(&str, &[u8], bool) -> Result<Vec<u8>, ProxyError> + Send + Sync,
+{
+    fn translate(&self, model: &str, raw_json: &[u8], stream: bool) -> Result<Vec<u8>, ProxyError> {
+        self(model, raw_json, stream)
+    }
+}
+
+/// Translates upstream responses, streamed and whole, back into the client format.
+pub trait ResponseTranslator: Send + Sync {
+    fn translate_stream(
+        &self,
+        model: &str,
+        original_req: &[u8],
+        event_type: Option<&str>,
+        data: &[u8],
+        state: &mut TranslateState,
+    ) -> Result<Vec<String>, ProxyError>;
+
+    fn translate_non_stream(
+        &self,
+        model: &str,
+        original_req: &[u8],
+        data: &[u8],
+    ) -> Result<String, ProxyError>;
+}
+
+/// A [`ResponseTranslator`] made of two plain functions.
 pub struct ResponseTransform {
     pub stream: StreamTransformFn,
     pub non_stream: NonStreamTransformFn,
 }
 
+impl ResponseTranslator for ResponseTransform {
+    fn translate_stream(
+        &self,
+        model: &str,
+        original_req: &[u8],
+        event_type: Option<&str>,
+        data: &[u8],
+        state: &mut TranslateState,
+    ) -> Result<Vec<String>, ProxyError> {
+        (self.stream)(model, original_req, event_type, data, state)
+    }
+
+    fn translate_non_stream(
+        &self,
+        model: &str,
+        original_req: &[u8],
+        data: &[u8],
+    ) -> Result<String, ProxyError> {
+        (self.non_stream)(model, original_req, data)
+    }
+}
+
 pub struct TranslatorRegistry {
-    requests: HashMap<(Format, Format), RequestTransformFn>,
-    responses: HashMap<(Format, Format), ResponseTransform>,
+    requests: HashMap<(Format, Format), Box<dyn RequestTranslator>>,
+    responses: HashMap<(Format, Format), Box<dyn ResponseTranslator>>,
+    /// Config-defined overrides applied after the built-in transforms.
+    rules: RwLock<Arc<TranslationRules>>,
 }
 
 impl Default for TranslatorRegistry {
@@ -80,22 +147,39 @@ impl TranslatorRegistry {
         Self {
             requests: HashMap::new(),
             responses: HashMap::new(),
+            rules: RwLock::default(),
         }
     }
 
+    /// Replace the override rules, e.g. after a config reload.
+    pub fn set_rules(&self, rules: Vec<TranslationRule>) {
+        let rules = Arc::new(TranslationRules::new(rules));
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = rules;
+    }
+
+    fn rules(&self) -> Arc<TranslationRules> {
+        self.rules.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Register both directions for `from -> to`, replacing any existing pair.
     pub fn register(
         &mut self,
         from: Format,
         to: Format,
-        request: RequestTransformFn,
-        response: ResponseTransform,
+        request: impl RequestTranslator + 'static,
+        response: impl ResponseTranslator + 'static,
     ) {
-        self.requests.insert((from, to), request);
-        self.responses.insert((from, to), response);
+        self.requests.insert((from, to), Box::new(request));
+        self.responses.insert((from, to), Box::new(response));
     }
 
-    pub fn register_request(&mut self, from: Format, to: Format, request: RequestTransformFn) {
-        self.requests.insert((from, to), request);
+    pub fn register_request(
+        &mut self,
+        from: Format,
+        to: Format,
+        request: impl RequestTranslator + 'static,
+    ) {
+        self.requests.insert((from, to), Box::new(request));
     }
 
     pub fn translate_request(
@@ -106,14 +190,16 @@ impl TranslatorRegistry {
         raw_json: &[u8],
         stream: bool,
     ) -> Result<Vec<u8>, ProxyError> {
-        if from == to {
+        let translated = if from == to {
             // Even for passthrough, replace the model name (alias → actual ID)
-            return replace_model_in_payload(raw_json, model);
-        }
-        match self.requests.get(&(from, to)) {
-            Some(f) => f(model, raw_json, stream),
-            None => Ok(raw_json.to_vec()),
-        }
+            replace_model_in_payload(raw_json, model)?
+        } else {
+            match self.requests.get(&(from, to)) {
+                Some(translator) => translator.translate(model, raw_json, stream)?,
+                None => raw_json.to_vec(),
+            }
+        };
+        self.rules().apply_request(from, to, model, translated)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -127,22 +213,19 @@ impl TranslatorRegistry {
         data: &[u8],
         state: &mut TranslateState,
     ) -> Result<Vec<String>, ProxyError> {
-        if from == to {
-            let line = String::from_utf8_lossy(data).to_string();
+        let lines = if from == to {
             // Pass through [DONE] sentinel and raw data as-is
-            return Ok(vec![line]);
-        }
-        // Skip [DONE] sentinel for translation paths (translators produce their own)
-        if data == b"[DONE]" {
+            vec![String::from_utf8_lossy(data).to_string()]
+        } else if data == b"[DONE]" {
+            // Skip [DONE] sentinel for translation paths (translators produce their own)
             return Ok(vec!["[DONE]".to_string()]);
-        }
-        match self.responses.get(&(from, to)) {
-            Some(rt) => (rt.stream)(model, orig_req, event_type, data, state),
-            None => {
-                let line = String::from_utf8_lossy(data).to_string();
-                Ok(vec![line])
+        } else {
+            match self.responses.get(&(from, to)) {
+                Some(rt) => rt.translate_stream(model, orig_req, event_type, data, state)?,
+                None => vec![String::from_utf8_lossy(data).to_string()],
             }
-        }
+        };
+        Ok(self.rules().apply_stream(from, to, model, lines))
     }
 
     pub fn translate_non_stream(
@@ -153,13 +236,11 @@ impl TranslatorRegistry {
         orig_req: &[u8],
         data: &[u8],
     ) -> Result<String, ProxyError> {
-        if from == to {
-            return Ok(String::from_utf8_lossy(data).to_string());
-        }
-        match self.responses.get(&(from, to)) {
-            Some(rt) => (rt.non_stream)(model, orig_req, data),
-            None => Ok(String::from_utf8_lossy(data).to_string()),
-        }
+        let body = match self.responses.get(&(from, to)) {
+            Some(rt) if from != to => rt.translate_non_stream(model, orig_req, data)?,
+            _ => String::from_utf8_lossy(data).to_string(),
+        };
+        Ok(self.rules().apply_response(from, to, model, body))
     }
 
     pub fn has_response_translator(&self, from: Format, to: Format) -> bool {
@@ -218,10 +299,14 @@ pub fn build_registry() -> TranslatorRegistry {
     );
 
     // Gemini -> Claude (chain: Gemini -> OpenAI -> Claude request only)
-    reg.register_request(Format::Gemini, Format::Claude, |model, raw, stream| {
-        let openai_payload = gemini_to_openai_request::translate_request(model, raw, stream)?;
-        openai_to_claude::translate_request(model, &openai_payload, stream)
-    });
+    reg.register_request(
+        Format::Gemini,
+        Format::Claude,
+        |model: &str, raw: &[u8], stream: bool| {
+            let openai_payload = gemini_to_openai_request::translate_request(model, raw, stream)?;
+            openai_to_claude::translate_request(model, &openai_payload, stream)
+        },
+    );
 
     // Claude -> OpenAI request translation, OpenAI -> Claude response translation
     reg.register(
@@ -235,10 +320,14 @@ pub fn build_registry() -> TranslatorRegistry {
     );
 
     // Claude -> Gemini (chain: Claude -> OpenAI -> Gemini request only)
-    reg.register_request(Format::Claude, Format::Gemini, |model, raw, stream| {
-        let openai_payload = claude_to_openai_request::translate_request(model, raw, stream)?;
-        openai_to_gemini::translate_request(model, &openai_payload, stream)
-    });
+    reg.register_request(
+        Format::Claude,
+        Format::Gemini,
+        |model: &str, raw: &[u8], stream: bool| {
+            let openai_payload = claude_to_openai_request::translate_request(model, raw, stream)?;
+            openai_to_gemini::translate_request(model, &openai_payload, stream)
+        },
+    );
 
     reg
 }
@@ -332,6 +421,83 @@ mod tests {
         assert_eq!(val["contents"][0]["role"], "user");
     }
 
+    /// Translators can carry configuration captured at registration.
+    struct TaggingTranslator {
+        tag: String,
+    }
+
+    impl RequestTranslator for TaggingTranslator {
+        fn translate(
+            &self,
+            model: &str,
+            _raw_json: &[u8],
+            _stream: bool,
+        ) -> Result<Vec<u8>, ProxyError> {
+            Ok(serde_json::to_vec(&json!({"model": model, "tag": self.tag})).unwrap())
+        }
+    }
+
+    impl ResponseTranslator for TaggingTranslator {
+        fn translate_stream(
+            &self,
+            _model: &str,
+            _original_req: &[u8],
+            _event_type: Option<&str>,
+            data: &[u8],
+            _state: &mut TranslateState,
+        ) -> Result<Vec<String>, ProxyError> {
+            Ok(vec![format!(
+                "{}:{}",
+                self.tag,
+                String::from_utf8_lossy(data)
+            )])
+        }
+
+        fn translate_non_stream(
+            &self,
+            _model: &str,
+            _original_req: &[u8],
+            data: &[u8],
+        ) -> Result<String, ProxyError> {
+            Ok(format!("{}:{}", self.tag, String::from_utf8_lossy(data)))
+        }
+    }
+
+    #[test]
+    fn test_registry_stateful_translator_replaces_builtin() {
+        let mut reg = build_registry();
+        reg.register(
+            Format::OpenAI,
+            Format::Claude,
+            TaggingTranslator { tag: "a".into() },
+            TaggingTranslator { tag: "b".into() },
+        );
+
+        let out = reg
+            .translate_request(Format::OpenAI, Format::Claude, "m", b"{}", false)
+            .unwrap();
+        let val: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(val, json!({"model": "m", "tag": "a"}));
+
+        let mut state = TranslateState::default();
+        let lines = reg
+            .translate_stream(
+                Format::OpenAI,
+                Format::Claude,
+                "m",
+                b"{}",
+                None,
+                b"x",
+                &mut state,
+            )
+            .unwrap();
+        assert_eq!(lines, vec!["b:x".to_string()]);
+        let body = reg
+            .translate_non_stream(Format::OpenAI, Format::Claude, "m", b"{}", b"y")
+            .unwrap();
+        assert_eq!(body, "b:y");
+    }
+
     // === translate_stream ===
 
     #[test]
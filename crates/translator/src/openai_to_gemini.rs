@@ -1,3 +1,4 @@
+use crate::common::{GEMINI_BUILTIN_TOOL_KEYS, is_gemini_search_tool};
 use prism_types::error::ProxyError;
 use serde_json::{Value, json};
 
@@ -298,11 +299,25 @@ fn infer_mime_type_from_url(url: &str) -> &str {
 }
 
 fn convert_tools(req: &Value) -> Option<Value> {
-    let tools = req.get("tools")?.as_array()?;
+    let empty = Vec::new();
+    let tools = req
+        .get("tools")
+        .and_then(|t| t.as_array())
+        .unwrap_or(&empty);
     let mut function_declarations = Vec::new();
+    let mut builtin_tools = Vec::new();
 
     for tool in tools {
-        if let Some(func) = tool.get("function") {
+        let tool_type = tool.get("type").and_then(|t| t.as_str());
+        if matches!(tool_type, Some("web_search" | "web_search_preview")) {
+            builtin_tools.push(json!({"googleSearch": {}}));
+        } else if let Some(key) = GEMINI_BUILTIN_TOOL_KEYS
+            .iter()
+            .find(|key| tool.get(**key).is_some())
+        {
+            // Gemini-native tool passed through an OpenAI-format request.
+            builtin_tools.push(json!({ *key: tool[*key].clone() }));
+        } else if let Some(func) = tool.get("function") {
             let name = func.get("name").and_then(|n| n.as_str()).unwrap_or("");
             let description = func
                 .get("description")
@@ -322,12 +337,23 @@ fn convert_tools(req: &Value) -> Option<Value> {
         }
     }
 
-    if function_declarations.is_empty() {
+    // Chat Completions web search options enable Google Search grounding.
+    if req.get("web_search_options").is_some() && !builtin_tools.iter().any(is_gemini_search_tool) {
+        builtin_tools.push(json!({"googleSearch": {}}));
+    }
+
+    let mut gemini_tools = Vec::new();
+    if !function_declarations.is_empty() {
+        gemini_tools.push(json!({
+            "functionDeclarations": function_declarations,
+        }));
+    }
+    gemini_tools.extend(builtin_tools);
+
+    if gemini_tools.is_empty() {
         None
     } else {
-        Some(json!([{
-            "functionDeclarations": function_declarations,
-        }]))
+        Some(Value::Array(gemini_tools))
     }
 }
 
@@ -854,4 +880,29 @@ mod tests {
         let result = translate(req);
         assert_eq!(result["contents"][0]["parts"][0]["text"], "");
     }
+
+    #[test]
+    fn test_web_search_tools_to_google_search() {
+        let result = translate(json!({
+            "messages": [{"role": "user", "content": "News?"}],
+            "web_search_options": {},
+            "tools": [
+                {"type": "function", "function": {"name": "lookup", "parameters": {"type": "object"}}},
+                {"googleSearchRetrieval": {"dynamicRetrievalConfig": {"mode": "MODE_DYNAMIC"}}}
+            ]
+        }));
+        let tools = result["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0]["functionDeclarations"][0]["name"], "lookup");
+        assert_eq!(
+            tools[1]["googleSearchRetrieval"]["dynamicRetrievalConfig"]["mode"],
+            "MODE_DYNAMIC"
+        );
+
+        let result = translate(json!({
+            "messages": [{"role": "user", "content": "News?"}],
+            "tools": [{"type": "web_search_preview"}]
+        }));
+        assert_eq!(result["tools"], json!([{"googleSearch": {}}]));
+    }
 }
@@ -1,4 +1,5 @@
 use crate::TranslateState;
+use crate::common::annotations_to_grounding;
 use prism_types::error::ProxyError;
 use serde_json::{Value, json};
 
@@ -26,6 +27,12 @@ pub fn translate_non_stream(
         .and_then(|c| c.as_array())
         .and_then(|arr| arr.first());
 
+    let grounding = choice
+        .and_then(|c| c.get("message"))
+        .and_then(|m| m.get("annotations"))
+        .and_then(|a| a.as_array())
+        .and_then(|a| annotations_to_grounding(a));
+
     let (parts, finish_reason) = if let Some(choice) = choice {
         let message = choice.get("message");
         let mut parts = Vec::new();
@@ -85,6 +92,9 @@ pub fn translate_non_stream(
             "finishReason": finish_reason,
         }],
     });
+    if let Some(grounding) = grounding {
+        gemini_resp["candidates"][0]["groundingMetadata"] = grounding;
+    }
 
     // Map usage
     if let Some(usage) = resp.get("usage") {
@@ -168,9 +178,13 @@ pub fn translate_stream(
         }
 
         let finish_reason = choice.get("finish_reason").and_then(|f| f.as_str());
+        let grounding = delta
+            .and_then(|d| d.get("annotations"))
+            .and_then(|a| a.as_array())
+            .and_then(|a| annotations_to_grounding(a));
 
-        // Skip role-only or empty chunks (no content, no finish)
-        if parts.is_empty() && finish_reason.is_none() {
+        // Skip role-only or empty chunks (no content, no finish, no citations)
+        if parts.is_empty() && finish_reason.is_none() && grounding.is_none() {
             return Ok(results);
         }
 
@@ -184,6 +198,9 @@ pub fn translate_stream(
         if let Some(reason) = finish_reason {
             candidate["finishReason"] = json!(map_openai_finish_reason(Some(reason)));
         }
+        if let Some(grounding) = grounding {
+            candidate["groundingMetadata"] = grounding;
+        }
 
         let mut gemini_chunk = json!({"candidates": [candidate]});
 
@@ -486,4 +503,43 @@ mod tests {
         let results = translate_stream("model", b"{}", None, &data, &mut state).unwrap();
         assert!(results.is_empty());
     }
+
+    #[test]
+    fn test_non_stream_annotations_to_grounding_metadata() {
+        let openai_resp = json!({
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": "Rust 1.95 shipped.",
+                    "annotations": [
+                        {"type": "url_citation", "url_citation": {
+                            "url": "https://blog.rust-lang.org", "title": "Rust Blog",
+                            "start_index": 0, "end_index": 18
+                        }},
+                        {"type": "url_citation", "url_citation": {
+                            "url": "https://blog.rust-lang.org", "title": "Rust Blog",
+                            "start_index": 0, "end_index": 4
+                        }}
+                    ]
+                },
+                "finish_reason": "stop"
+            }]
+        });
+        let data = serde_json::to_vec(&openai_resp).unwrap();
+        let result: Value =
+            serde_json::from_str(&translate_non_stream("model", b"{}", &data).unwrap()).unwrap();
+
+        let grounding = &result["candidates"][0]["groundingMetadata"];
+        assert_eq!(grounding["groundingChunks"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            grounding["groundingChunks"][0]["web"]["uri"],
+            "https://blog.rust-lang.org"
+        );
+        assert_eq!(grounding["groundingSupports"][1]["segment"]["endIndex"], 4);
+        assert_eq!(
+            grounding["groundingSupports"][1]["groundingChunkIndices"],
+            json!([0])
+        );
+    }
 }
@@ -0,0 +1,288 @@
+//! Config-defined per-model tweaks layered on top of the built-in translators
+//! (`translation-rules` in config). Rules are swapped in on config reload.
+
+use prism_types::error::ProxyError;
+use prism_types::format::Format;
+use prism_types::glob::glob_match;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct TranslationRule {
+    /// Upstream model ids the rule applies to; `*` and `?` wildcards allowed.
+    pub models: Vec<String>,
+    /// Only apply when the client sends this format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Format>,
+    /// Only apply when the upstream receives this format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Format>,
+    /// Dot-separated request fields removed after translation, e.g. `temperature`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub drop_fields: Vec<String>,
+    /// Finish reasons in translated responses, mapped to the value sent to the client.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub finish_reasons: HashMap<String, String>,
+}
+
+impl TranslationRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.models.iter().all(|m| m.trim().is_empty()) {
+            return Err("models must not be empty".to_string());
+        }
+        if self
+            .drop_fields
+            .iter()
+            .any(|f| f.split('.').any(str::is_empty))
+        {
+            return Err("drop-fields entries must be non-empty dot-separated paths".to_string());
+        }
+        if self.drop_fields.is_empty() && self.finish_reasons.is_empty() {
+            return Err("rule sets neither drop-fields nor finish-reasons".to_string());
+        }
+        Ok(())
+    }
+
+    fn matches(&self, from: Format, to: Format, model: &str) -> bool {
+        self.from.is_none_or(|f| f == from)
+            && self.to.is_none_or(|t| t == to)
+            && self.models.iter().any(|m| glob_match(m, model))
+    }
+}
+
+/// The active rule set. Matching rules apply in config order.
+#[derive(Debug, Default)]
+pub struct TranslationRules {
+    rules: Vec<TranslationRule>,
+}
+
+impl TranslationRules {
+    pub fn new(rules: Vec<TranslationRule>) -> Self {
+        Self { rules }
+    }
+
+    fn matching(
+        &self,
+        from: Format,
+        to: Format,
+        model: &str,
+    ) -> impl Iterator<Item = &TranslationRule> {
+        self.rules
+            .iter()
+            .filter(move |r| r.matches(from, to, model))
+    }
+
+    /// Finish reason mapping of the matching rules; earlier rules win.
+    fn finish_reasons(&self, from: Format, to: Format, model: &str) -> HashMap<&str, &str> {
+        let mut map = HashMap::new();
+        for rule in self.matching(from, to, model) {
+            for (k, v) in &rule.finish_reasons {
+                map.entry(k.as_str()).or_insert(v.as_str());
+            }
+        }
+        map
+    }
+
+    /// Remove the matching rules' `drop-fields` from a translated request.
+    pub fn apply_request(
+        &self,
+        from: Format,
+        to: Format,
+        model: &str,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, ProxyError> {
+        let fields: Vec<&str> = self
+            .matching(from, to, model)
+            .flat_map(|r| r.drop_fields.iter().map(String::as_str))
+            .collect();
+        if fields.is_empty() {
+            return Ok(payload);
+        }
+        let mut value: Value = serde_json::from_slice(&payload)?;
+        for field in fields {
+            remove_path(&mut value, field);
+        }
+        serde_json::to_vec(&value).map_err(|e| ProxyError::Translation(e.to_string()))
+    }
+
+    /// Rewrite finish reasons in a translated non-stream response body.
+    pub fn apply_response(&self, from: Format, to: Format, model: &str, body: String) -> String {
+        let map = self.finish_reasons(from, to, model);
+        if map.is_empty() {
+            return body;
+        }
+        rewrite_json(&body, &map).unwrap_or(body)
+    }
+
+    /// Rewrite finish reasons in translated stream lines, which are either a
+    /// JSON payload or `event: <name>\ndata: <json>`.
+    pub fn apply_stream(
+        &self,
+        from: Format,
+        to: Format,
+        model: &str,
+        lines: Vec<String>,
+    ) -> Vec<String> {
+        let map = self.finish_reasons(from, to, model);
+        if map.is_empty() {
+            return lines;
+        }
+        lines
+            .into_iter()
+            .map(|line| {
+                let (prefix, json) = match line.rfind("data: ") {
+                    Some(i) => line.split_at(i + "data: ".len()),
+                    None => ("", line.as_str()),
+                };
+                match rewrite_json(json, &map) {
+                    Some(json) => format!("{prefix}{json}"),
+                    None => line,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Remove a dot-separated path from nested objects.
+fn remove_path(value: &mut Value, path: &str) {
+    let mut parts: Vec<&str> = path.split('.').collect();
+    let Some(last) = parts.pop() else {
+        return;
+    };
+    let mut current = value;
+    for part in parts {
+        match current.get_mut(part) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+    if let Some(obj) = current.as_object_mut() {
+        obj.remove(last);
+    }
+}
+
+/// `json` with its finish reasons rewritten, or `None` when nothing matched.
+fn rewrite_json(json: &str, map: &HashMap<&str, &str>) -> Option<String> {
+    let mut value: Value = serde_json::from_str(json).ok()?;
+    rewrite_finish_reasons(&mut value, map).then(|| value.to_string())
+}
+
+/// Rewrite the finish reason fields of OpenAI (`choices[].finish_reason`),
+/// Claude (`stop_reason`, `delta.stop_reason`) and Gemini
+/// (`candidates[].finishReason`) payloads. Returns whether anything changed.
+fn rewrite_finish_reasons(value: &mut Value, map: &HashMap<&str, &str>) -> bool {
+    let mut fields: Vec<&mut Value> = Vec::new();
+    let Some(obj) = value.as_object_mut() else {
+        return false;
+    };
+    for (key, v) in obj.iter_mut() {
+        match key.as_str() {
+            "stop_reason" => fields.push(v),
+            "delta" => fields.extend(v.get_mut("stop_reason")),
+            "choices" | "candidates" => {
+                let field = if key == "choices" {
+                    "finish_reason"
+                } else {
+                    "finishReason"
+                };
+                if let Some(items) = v.as_array_mut() {
+                    fields.extend(items.iter_mut().filter_map(|item| item.get_mut(field)));
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut changed = false;
+    for field in fields {
+        if let Some(mapped) = field.as_str().and_then(|reason| map.get(reason)) {
+            *field = Value::String((*mapped).to_string());
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules() -> TranslationRules {
+        TranslationRules::new(vec![TranslationRule {
+            models: vec!["o3*".to_string()],
+            to: Some(Format::OpenAI),
+            drop_fields: vec!["temperature".to_string(), "metadata.user".to_string()],
+            finish_reasons: HashMap::from([("length".to_string(), "max_tokens".to_string())]),
+            ..Default::default()
+        }])
+    }
+
+    #[test]
+    fn test_drop_fields_only_for_matching_model_and_format() {
+        let payload =
+            serde_json::to_vec(&json!({"temperature": 0.2, "metadata": {"user": "u", "k": 1}}))
+                .unwrap();
+        let out = rules()
+            .apply_request(Format::Claude, Format::OpenAI, "o3-mini", payload.clone())
+            .unwrap();
+        let out: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(out, json!({"metadata": {"k": 1}}));
+
+        let untouched = rules()
+            .apply_request(Format::Claude, Format::OpenAI, "gpt-4o", payload.clone())
+            .unwrap();
+        assert_eq!(untouched, payload);
+        let untouched = rules()
+            .apply_request(Format::OpenAI, Format::Claude, "o3-mini", payload.clone())
+            .unwrap();
+        assert_eq!(untouched, payload);
+    }
+
+    #[test]
+    fn test_finish_reasons_rewritten_in_responses_and_stream() {
+        let body = json!({"choices": [{"finish_reason": "length"}]}).to_string();
+        let out = rules().apply_response(Format::OpenAI, Format::OpenAI, "o3", body);
+        let out: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(out["choices"][0]["finish_reason"], "max_tokens");
+
+        let lines = vec![
+            format!(
+                "event: message_delta\ndata: {}",
+                json!({"type": "message_delta", "delta": {"stop_reason": "length"}})
+            ),
+            "[DONE]".to_string(),
+        ];
+        let out = rules().apply_stream(Format::Claude, Format::OpenAI, "o3", lines);
+        assert_eq!(
+            out[0],
+            format!(
+                "event: message_delta\ndata: {}",
+                json!({"type": "message_delta", "delta": {"stop_reason": "max_tokens"}})
+            )
+        );
+        assert_eq!(out[1], "[DONE]");
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(
+            TranslationRule {
+                models: vec!["*".to_string()],
+                drop_fields: vec!["a..b".to_string()],
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            TranslationRule {
+                drop_fields: vec!["temperature".to_string()],
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
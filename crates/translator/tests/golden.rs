@@ -0,0 +1,245 @@
+//! Golden-file conformance tests: sanitized provider traffic under
+//! `tests/fixtures/<client>-from-<upstream>/<case>/` is run through the
+//! translators and compared with the expected client-visible output.
+//!
+//! A case directory holds:
+//! - `request.json`: the client request, in the client's format.
+//! - `upstream-request.json` (optional): the expected translated request.
+//! - `response.json` or `response.sse`: the recorded upstream body.
+//! - `expected.json`: the expected client output. For streams, one entry per
+//!   emitted event: `{"event": ..., "data": ...}`, with `event` omitted for
+//!   data-only events and `"[DONE]"` as a bare string.
+//!
+//! Generated ids and timestamps are redacted to `<uuid>` and `<created>`.
+//! Set `PRISM_BLESS=1` to rewrite the expected files from the current output.
+//! See `tests/fixtures/README.md` for adding a case from a bug report.
+use assert_json_diff::{CompareMode, Config, assert_json_matches_no_panic};
+use prism_translator::{TranslateState, build_registry};
+use prism_types::format::Format;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static UUID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}").unwrap()
+});
+
+const MODEL: &str = "golden-model";
+
+struct Case {
+    dir: PathBuf,
+    client: Format,
+    upstream: Format,
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn cases() -> Vec<Case> {
+    let mut cases = Vec::new();
+    for pair in sorted_dirs(&fixtures_dir()) {
+        let name = pair.file_name().unwrap().to_string_lossy().into_owned();
+        let (client, upstream) = name
+            .split_once("-from-")
+            .unwrap_or_else(|| panic!("fixture dir {name} is not <client>-from-<upstream>"));
+        let client: Format = client.parse().unwrap();
+        let upstream: Format = upstream.parse().unwrap();
+        for dir in sorted_dirs(&pair) {
+            cases.push(Case {
+                dir,
+                client,
+                upstream,
+            });
+        }
+    }
+    cases
+}
+
+fn sorted_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn read_json(path: &Path) -> Value {
+    let text = std::fs::read_to_string(path).unwrap();
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+}
+
+fn write_json(path: &Path, value: &Value) {
+    let text = serde_json::to_string_pretty(value).unwrap();
+    std::fs::write(path, text + "\n").unwrap();
+}
+
+/// Split a recorded SSE body into `(event type, data)` pairs. Comments and
+/// blocks without data are dropped, as the provider stream parser does.
+fn parse_sse(body: &str) -> Vec<(Option<String>, String)> {
+    let mut events = Vec::new();
+    for block in body.replace("\r\n", "\n").split("\n\n") {
+        let mut event = None;
+        let mut data: Vec<&str> = Vec::new();
+        for line in block.lines() {
+            if let Some(rest) = line.strip_prefix("event:") {
+                event = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix("data:") {
+                data.push(rest.strip_prefix(' ').unwrap_or(rest));
+            }
+        }
+        if !data.is_empty() {
+            events.push((event, data.join("\n")));
+        }
+    }
+    events
+}
+
+/// Parse one translated stream line into its `expected.json` entry.
+fn stream_entry(line: &str) -> Value {
+    if line == "[DONE]" {
+        return Value::String(line.to_string());
+    }
+    let parse =
+        |data: &str| serde_json::from_str(data).unwrap_or_else(|_| Value::String(data.to_string()));
+    match line
+        .strip_prefix("event: ")
+        .and_then(|rest| rest.split_once("\ndata: "))
+    {
+        Some((event, data)) => serde_json::json!({"event": event, "data": parse(data)}),
+        None => serde_json::json!({"data": parse(line.strip_prefix("data: ").unwrap_or(line))}),
+    }
+}
+
+/// Replace generated ids and timestamps so output is stable across runs.
+fn redact(value: &mut Value) {
+    match value {
+        Value::String(s) if UUID.is_match(s) => *s = UUID.replace_all(s, "<uuid>").into_owned(),
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if key == "created" && v.is_number() {
+                    *v = Value::String("<created>".into());
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Run one case, returning `(file, actual)` for every expected file it checks.
+fn run(case: &Case) -> Vec<(PathBuf, Value)> {
+    let reg = build_registry();
+    let request = read_json(&case.dir.join("request.json"));
+    let raw_request = serde_json::to_vec(&request).unwrap();
+    let stream = request["stream"].as_bool().unwrap_or(false);
+    let mut checks = Vec::new();
+
+    let upstream_request = case.dir.join("upstream-request.json");
+    if upstream_request.exists() {
+        let translated = reg
+            .translate_request(case.client, case.upstream, MODEL, &raw_request, stream)
+            .unwrap_or_else(|e| panic!("{}: {e}", case.dir.display()));
+        let mut actual: Value = serde_json::from_slice(&translated).unwrap();
+        redact(&mut actual);
+        checks.push((upstream_request, actual));
+    }
+
+    let sse = case.dir.join("response.sse");
+    let mut actual = if sse.exists() {
+        let body = std::fs::read_to_string(&sse).unwrap();
+        let mut state = TranslateState::default();
+        let mut entries = Vec::new();
+        for (event, data) in parse_sse(&body) {
+            let lines = reg
+                .translate_stream(
+                    case.client,
+                    case.upstream,
+                    MODEL,
+                    &raw_request,
+                    event.as_deref(),
+                    data.as_bytes(),
+                    &mut state,
+                )
+                .unwrap_or_else(|e| panic!("{}: {e}", case.dir.display()));
+            entries.extend(lines.iter().map(|line| stream_entry(line)));
+        }
+        Value::Array(entries)
+    } else {
+        let body = std::fs::read(case.dir.join("response.json")).unwrap();
+        let translated = reg
+            .translate_non_stream(case.client, case.upstream, MODEL, &raw_request, &body)
+            .unwrap_or_else(|e| panic!("{}: {e}", case.dir.display()));
+        serde_json::from_str(&translated).unwrap()
+    };
+    redact(&mut actual);
+    checks.push((case.dir.join("expected.json"), actual));
+    checks
+}
+
+#[test]
+fn golden_fixtures() {
+    let bless = std::env::var_os("PRISM_BLESS").is_some();
+    let cases = cases();
+    assert!(!cases.is_empty(), "no fixtures under {:?}", fixtures_dir());
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        for (path, actual) in run(case) {
+            if bless {
+                write_json(&path, &actual);
+                continue;
+            }
+            if !path.exists() {
+                failures.push(format!(
+                    "{}: missing, run with PRISM_BLESS=1",
+                    path.display()
+                ));
+                continue;
+            }
+            let expected = read_json(&path);
+            if let Err(diff) =
+                assert_json_matches_no_panic(&actual, &expected, Config::new(CompareMode::Strict))
+            {
+                failures.push(format!("{}:\n{diff}", path.display()));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} golden file(s) differ:\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn golden_fixtures_cover_every_response_translator() {
+    let reg = build_registry();
+    let cases = cases();
+    let formats = [Format::OpenAI, Format::Claude, Format::Gemini];
+    for client in formats {
+        for upstream in formats {
+            if !reg.has_response_translator(client, upstream) {
+                continue;
+            }
+            let pair: Vec<&Case> = cases
+                .iter()
+                .filter(|c| c.client == client && c.upstream == upstream)
+                .collect();
+            assert!(
+                pair.iter().any(|c| c.dir.join("response.sse").exists()),
+                "no streaming fixture for {client}-from-{upstream}"
+            );
+            assert!(
+                pair.iter().any(|c| c.dir.join("response.json").exists()),
+                "no non-streaming fixture for {client}-from-{upstream}"
+            );
+        }
+    }
+}
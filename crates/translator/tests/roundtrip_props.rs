@@ -0,0 +1,380 @@
+//! Property-based roundtrip tests: random valid OpenAI requests and responses are
+//! translated to Claude or Gemini and back, and the parts every translation must
+//! keep (message order and text, tool call names and arguments, tool result
+//! matching, finish reasons) are compared after normalizing known, documented
+//! losses such as regenerated Gemini tool call ids.
+use prism_translator::{TranslatorRegistry, build_registry};
+use prism_types::format::Format;
+use proptest::prelude::*;
+use serde_json::{Map, Value, json};
+
+// === Strategies ===
+
+/// Text with the shapes that break escaping: empty, quotes and backslashes,
+/// control characters, and arbitrary unicode.
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        "[ -~]{1,24}",
+        "[\"\\\\\n\r\t\u{0}\u{1f}/]{1,8}",
+        any::<String>(),
+    ]
+}
+
+fn non_empty_text() -> impl Strategy<Value = String> {
+    text().prop_filter("non-empty", |s| !s.is_empty())
+}
+
+fn tool_name() -> impl Strategy<Value = String> {
+    "[a-zA-Z_][a-zA-Z0-9_]{0,15}"
+}
+
+/// JSON without floats, whose text form does not survive a reparse bit for bit.
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        text().prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::btree_map(text(), inner, 0..4)
+                .prop_map(|m| Value::Object(m.into_iter().collect())),
+        ]
+    })
+}
+
+fn tool_args() -> impl Strategy<Value = Value> {
+    prop::collection::btree_map(text(), json_value(), 0..4)
+        .prop_map(|m| Value::Object(m.into_iter().collect::<Map<_, _>>()))
+}
+
+/// Tool output: plain text or a serialized JSON document.
+fn tool_output() -> impl Strategy<Value = String> {
+    prop_oneof![text(), json_value().prop_map(|v| v.to_string())]
+}
+
+#[derive(Debug, Clone)]
+struct ToolCall {
+    name: String,
+    args: Value,
+    output: String,
+}
+
+#[derive(Debug, Clone)]
+enum Reply {
+    Text(String),
+    /// Assistant text alongside tool calls, their results, then a final answer.
+    Tools(String, Vec<ToolCall>, String),
+}
+
+fn tool_call() -> impl Strategy<Value = ToolCall> {
+    (tool_name(), tool_args(), tool_output()).prop_map(|(name, args, output)| ToolCall {
+        name,
+        args,
+        output,
+    })
+}
+
+fn reply() -> impl Strategy<Value = Reply> {
+    prop_oneof![
+        text().prop_map(Reply::Text),
+        (text(), prop::collection::vec(tool_call(), 1..4), text())
+            .prop_map(|(text, calls, answer)| Reply::Tools(text, calls, answer)),
+    ]
+}
+
+/// A valid conversation: system prompts, then user turns each answered by the
+/// assistant. Tool results directly follow the assistant message calling them,
+/// and the assistant always speaks again before the next user turn.
+fn openai_request() -> impl Strategy<Value = Value> {
+    (
+        prop::collection::vec(non_empty_text(), 0..3),
+        prop::collection::vec((text(), reply()), 1..4),
+    )
+        .prop_map(|(system, turns)| {
+            let mut messages: Vec<Value> = system
+                .into_iter()
+                .map(|s| json!({"role": "system", "content": s}))
+                .collect();
+            let mut next_id = 0;
+            for (user, reply) in turns {
+                messages.push(json!({"role": "user", "content": user}));
+                match reply {
+                    Reply::Text(text) => {
+                        messages.push(json!({"role": "assistant", "content": text}));
+                    }
+                    Reply::Tools(text, calls, answer) => {
+                        let ids: Vec<String> = calls
+                            .iter()
+                            .map(|_| {
+                                next_id += 1;
+                                format!("call_{next_id}")
+                            })
+                            .collect();
+                        let tool_calls: Vec<Value> = calls
+                            .iter()
+                            .zip(&ids)
+                            .map(|(call, id)| {
+                                json!({
+                                    "id": id,
+                                    "type": "function",
+                                    "function": {
+                                        "name": call.name,
+                                        "arguments": call.args.to_string(),
+                                    }
+                                })
+                            })
+                            .collect();
+                        messages.push(json!({
+                            "role": "assistant",
+                            "content": text,
+                            "tool_calls": tool_calls,
+                        }));
+                        for (call, id) in calls.iter().zip(&ids) {
+                            messages.push(json!({
+                                "role": "tool",
+                                "tool_call_id": id,
+                                "name": call.name,
+                                "content": call.output,
+                            }));
+                        }
+                        messages.push(json!({"role": "assistant", "content": answer}));
+                    }
+                }
+            }
+            json!({"model": "gpt-4o", "messages": messages})
+        })
+}
+
+fn finish_reason() -> impl Strategy<Value = &'static str> {
+    prop_oneof![Just("stop"), Just("length"), Just("tool_calls")]
+}
+
+fn openai_response() -> impl Strategy<Value = Value> {
+    (
+        text(),
+        prop::collection::vec((tool_name(), tool_args()), 0..4),
+        finish_reason(),
+    )
+        .prop_map(|(content, calls, finish)| {
+            let mut message = json!({"role": "assistant", "content": content});
+            if !calls.is_empty() {
+                let tool_calls: Vec<Value> = calls
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, args))| {
+                        json!({
+                            "id": format!("call_{i}"),
+                            "type": "function",
+                            "function": {"name": name, "arguments": args.to_string()},
+                        })
+                    })
+                    .collect();
+                message["tool_calls"] = Value::Array(tool_calls);
+            }
+            json!({
+                "id": "chatcmpl-prop",
+                "object": "chat.completion",
+                "created": 1700000000,
+                "model": "gpt-4o",
+                "choices": [{"index": 0, "message": message, "finish_reason": finish}],
+                "usage": {"prompt_tokens": 3, "completion_tokens": 5, "total_tokens": 8},
+            })
+        })
+}
+
+// === Normalization ===
+
+/// A message reduced to what a roundtrip must keep. Absent, null, and empty
+/// content are the same message.
+#[derive(Debug, PartialEq)]
+struct Normalized {
+    role: String,
+    text: String,
+    tool_calls: Vec<(String, String, Value)>,
+    tool_call_id: Option<String>,
+    tool_name: Option<String>,
+}
+
+fn content_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// Split OpenAI messages into the joined system prompt and the rest.
+fn normalize(req: &Value) -> (String, Vec<Normalized>) {
+    let mut system = Vec::new();
+    let mut rest = Vec::new();
+    for msg in req["messages"].as_array().expect("messages") {
+        let role = msg["role"].as_str().unwrap_or_default();
+        if role == "system" {
+            system.push(content_text(msg.get("content")));
+            continue;
+        }
+        let tool_calls = msg
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .map(|calls| {
+                calls
+                    .iter()
+                    .map(|tc| {
+                        let args = tc["function"]["arguments"].as_str().unwrap_or("{}");
+                        (
+                            tc["id"].as_str().unwrap_or_default().to_string(),
+                            tc["function"]["name"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            serde_json::from_str(args).expect("tool arguments are JSON"),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        rest.push(Normalized {
+            role: role.to_string(),
+            text: content_text(msg.get("content")),
+            tool_calls,
+            tool_call_id: msg["tool_call_id"].as_str().map(String::from),
+            tool_name: msg["name"].as_str().map(String::from),
+        });
+    }
+    (system.join("\n\n"), rest)
+}
+
+fn request_roundtrip(reg: &TranslatorRegistry, via: Format, req: &Value) -> Value {
+    let raw = serde_json::to_vec(req).unwrap();
+    let translated = reg
+        .translate_request(Format::OpenAI, via, "target-model", &raw, false)
+        .unwrap();
+    let back = reg
+        .translate_request(via, Format::OpenAI, "gpt-4o", &translated, false)
+        .unwrap();
+    serde_json::from_slice(&back).unwrap()
+}
+
+/// Translate an OpenAI response as a `via` upstream would return it, then back
+/// to OpenAI as an OpenAI client would see it from a `via` upstream.
+fn response_roundtrip(reg: &TranslatorRegistry, via: Format, resp: &Value) -> Value {
+    let orig_req = br#"{"model":"gpt-4o","messages":[]}"#;
+    let translated = reg
+        .translate_non_stream(
+            via,
+            Format::OpenAI,
+            "gpt-4o",
+            orig_req,
+            resp.to_string().as_bytes(),
+        )
+        .unwrap();
+    let back = reg
+        .translate_non_stream(
+            Format::OpenAI,
+            via,
+            "gpt-4o",
+            orig_req,
+            translated.as_bytes(),
+        )
+        .unwrap();
+    serde_json::from_str(&back).unwrap()
+}
+
+fn response_message(resp: &Value) -> Normalized {
+    let wrapped = json!({"messages": [resp["choices"][0]["message"].clone()]});
+    normalize(&wrapped).1.remove(0)
+}
+
+proptest! {
+    #[test]
+    fn prop_request_roundtrip_via_claude(req in openai_request()) {
+        let reg = build_registry();
+        let back = request_roundtrip(&reg, Format::Claude, &req);
+        let (system, mut expected) = normalize(&req);
+        let (back_system, actual) = normalize(&back);
+
+        prop_assert_eq!(back_system, system);
+        // Claude tool results carry only the call id, so the name is dropped.
+        for msg in &mut expected {
+            msg.tool_name = None;
+        }
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn prop_request_roundtrip_via_gemini(req in openai_request()) {
+        let reg = build_registry();
+        let back = request_roundtrip(&reg, Format::Gemini, &req);
+        let (system, expected) = normalize(&req);
+        let (back_system, actual) = normalize(&back);
+
+        prop_assert_eq!(back_system, system);
+        prop_assert_eq!(actual.len(), expected.len());
+
+        // Gemini has no call ids: they are regenerated, and each result must
+        // point at the call it answered.
+        let mut call_ids = std::collections::HashMap::new();
+        for (a, e) in actual.iter().zip(&expected) {
+            prop_assert_eq!(&a.role, &e.role);
+            prop_assert_eq!(a.tool_calls.len(), e.tool_calls.len());
+            for (ac, ec) in a.tool_calls.iter().zip(&e.tool_calls) {
+                prop_assert_eq!(&ac.1, &ec.1);
+                prop_assert_eq!(&ac.2, &ec.2);
+                call_ids.insert(ec.0.clone(), ac.0.clone());
+            }
+            if a.role == "tool" {
+                prop_assert_eq!(&a.tool_name, &e.tool_name);
+                let want_id = call_ids.get(e.tool_call_id.as_deref().unwrap());
+                prop_assert_eq!(a.tool_call_id.as_ref(), want_id);
+                // Results travel as JSON; plain text comes back wrapped.
+                let output: Value = serde_json::from_str(&a.text).unwrap();
+                let original = serde_json::from_str(&e.text)
+                    .unwrap_or_else(|_| json!({"result": e.text}));
+                prop_assert_eq!(output, original);
+            } else {
+                prop_assert_eq!(&a.text, &e.text);
+            }
+        }
+    }
+
+    #[test]
+    fn prop_response_roundtrip_via_claude(resp in openai_response()) {
+        let reg = build_registry();
+        let back = response_roundtrip(&reg, Format::Claude, &resp);
+
+        prop_assert_eq!(response_message(&back), response_message(&resp));
+        prop_assert_eq!(
+            &back["choices"][0]["finish_reason"],
+            &resp["choices"][0]["finish_reason"]
+        );
+        prop_assert_eq!(&back["usage"]["total_tokens"], &json!(8));
+    }
+
+    #[test]
+    fn prop_response_roundtrip_via_gemini(resp in openai_response()) {
+        let reg = build_registry();
+        let back = response_roundtrip(&reg, Format::Gemini, &resp);
+        let expected = response_message(&resp);
+        let actual = response_message(&back);
+
+        prop_assert_eq!(&actual.text, &expected.text);
+        let names_args = |m: &Normalized| -> Vec<(String, Value)> {
+            m.tool_calls.iter().map(|(_, n, a)| (n.clone(), a.clone())).collect()
+        };
+        prop_assert_eq!(names_args(&actual), names_args(&expected));
+        // Gemini reports tool calls with STOP, so they come back as "stop".
+        let finish = match resp["choices"][0]["finish_reason"].as_str() {
+            Some("length") => "length",
+            _ => "stop",
+        };
+        prop_assert_eq!(&back["choices"][0]["finish_reason"], &json!(finish));
+        prop_assert_eq!(&back["usage"]["total_tokens"], &json!(8));
+    }
+}
@@ -1,4 +1,5 @@
 use serde_json::json;
+use std::collections::HashMap;
 
 /// Unified error type for all proxy operations.
 #[derive(Debug, thiserror::Error)]
@@ -21,6 +22,9 @@ pub enum ProxyError {
         body: String,
         /// Parsed from upstream `Retry-After` header (seconds), if present.
         retry_after_secs: Option<u64>,
+        /// Upstream response headers. The dispatcher narrows them to the
+        /// configured `passthrough-headers`; the error response sends what is left.
+        headers: HashMap<String, String>,
     },
 
     #[error("network error: {0}")]
@@ -32,6 +36,12 @@ pub enum ProxyError {
     #[error("bad request: {0}")]
     BadRequest(String),
 
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("request blocked by content moderation: {0}")]
+    ContentBlocked(String),
+
     #[error("model not found: {0}")]
     ModelNotFound(String),
 
@@ -42,6 +52,13 @@ pub enum ProxyError {
         retry_after_secs: u64,
     },
 
+    #[error("server overloaded: {message}")]
+    Overloaded {
+        message: String,
+        /// Seconds the client should wait before retrying.
+        retry_after_secs: u64,
+    },
+
     #[error("model access denied: {0}")]
     ModelNotAllowed(String),
 
@@ -59,12 +76,13 @@ impl ProxyError {
             Self::Config(_) | Self::Internal(_) => 500,
             Self::Auth(_) | Self::KeyExpired => 401,
             Self::ModelNotAllowed(_) => 403,
-            Self::NoCredentials { .. } => 503,
+            Self::NoCredentials { .. } | Self::Overloaded { .. } => 503,
             Self::ModelCooldown { .. } | Self::RateLimited { .. } => 429,
             Self::Upstream { status, .. } => *status,
             Self::Network(_) => 502,
             Self::Translation(_) => 500,
-            Self::BadRequest(_) => 400,
+            Self::BadRequest(_) | Self::ContentBlocked(_) => 400,
+            Self::PayloadTooLarge(_) => 413,
             Self::ModelNotFound(_) => 404,
         }
     }
@@ -75,7 +93,9 @@ impl ProxyError {
             Self::ModelNotAllowed(_) => "permission_error",
             Self::NoCredentials { .. } => "insufficient_quota",
             Self::ModelCooldown { .. } | Self::RateLimited { .. } => "rate_limit_error",
-            Self::BadRequest(_) => "invalid_request_error",
+            Self::BadRequest(_) | Self::PayloadTooLarge(_) | Self::ContentBlocked(_) => {
+                "invalid_request_error"
+            }
             Self::ModelNotFound(_) => "invalid_request_error",
             Self::Upstream { .. } => "upstream_error",
             _ => "server_error",
@@ -89,7 +109,10 @@ impl ProxyError {
             Self::NoCredentials { .. } => "insufficient_quota",
             Self::ModelCooldown { .. } | Self::RateLimited { .. } => "rate_limit_exceeded",
             Self::ModelNotFound(_) => "model_not_found",
+            Self::Overloaded { .. } => "overloaded",
             Self::BadRequest(_) => "invalid_request",
+            Self::PayloadTooLarge(_) => "request_too_large",
+            Self::ContentBlocked(_) => "content_policy_violation",
             _ => "internal_error",
         }
     }
@@ -100,12 +123,12 @@ impl ProxyError {
         if let Self::Upstream { body, .. } = self
             && serde_json::from_str::<serde_json::Value>(body).is_ok()
         {
-            return body.clone();
+            return crate::redact::redact(body).into_owned();
         }
 
         json!({
             "error": {
-                "message": self.to_string(),
+                "message": crate::redact::redact(&self.to_string()),
                 "type": self.error_type(),
                 "code": self.error_code(),
             }
@@ -120,6 +143,14 @@ impl ProxyError {
                 retry_after_secs, ..
             } => Some(*retry_after_secs),
             Self::ModelCooldown { seconds, .. } => Some(*seconds),
+            Self::Overloaded {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            Self::Upstream {
+                status: 429,
+                retry_after_secs,
+                ..
+            } => *retry_after_secs,
             _ => None,
         }
     }
@@ -141,6 +172,17 @@ impl axum::response::IntoResponse for ProxyError {
 
         let mut response = (status, [("content-type", "application/json")], body).into_response();
 
+        if let Self::Upstream { headers, .. } = self {
+            for (name, value) in headers {
+                if let (Ok(name), Ok(value)) = (
+                    axum::http::HeaderName::from_bytes(name.as_bytes()),
+                    axum::http::HeaderValue::from_str(&value),
+                ) {
+                    response.headers_mut().insert(name, value);
+                }
+            }
+        }
+        // Set last: a Retry-After computed from credential cooldowns beats the upstream's.
         if let Some(secs) = retry_secs
             && let Ok(val) = secs.to_string().parse()
         {
@@ -153,13 +195,22 @@ impl axum::response::IntoResponse for ProxyError {
 
 #[cfg(feature = "reqwest")]
 impl From<reqwest::Error> for ProxyError {
-    fn from(e: reqwest::Error) -> Self {
+    fn from(mut e: reqwest::Error) -> Self {
+        // The error text includes the request URL. Credential query parameters
+        // are masked there even with redaction disabled; the rest of the text
+        // goes through the configured redactor.
+        if let Some(url) = e.url_mut()
+            && let Some(query) = url.query().and_then(crate::redact::scrub_query)
+        {
+            url.set_query(Some(&query));
+        }
+        let message = crate::redact::redact(&e.to_string()).into_owned();
         if e.is_timeout() {
-            Self::Network(format!("request timed out: {e}"))
+            Self::Network(format!("request timed out: {message}"))
         } else if e.is_connect() {
-            Self::Network(format!("connection failed: {e}"))
+            Self::Network(format!("connection failed: {message}"))
         } else {
-            Self::Network(e.to_string())
+            Self::Network(message)
         }
     }
 }
@@ -1,3 +1,5 @@
 pub mod error;
 pub mod format;
+pub mod glob;
+pub mod redact;
 pub mod types;
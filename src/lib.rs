@@ -0,0 +1,14 @@
+//! Prism as a library, for embedding the proxy in an existing axum service.
+//!
+//! Build a [`Proxy`] with [`ProxyBuilder`] from a [`Config`], YAML, or a config
+//! file, optionally adding executors and translators, then mount
+//! [`Proxy::router`]. The `prism` binary runs the same assembly behind the CLI.
+
+pub use prism_core::config::Config;
+pub use prism_server::AppState;
+pub use prism_server::builder::{Proxy, ProxyBuilder};
+
+pub use prism_core as core;
+pub use prism_provider as provider;
+pub use prism_server as server;
+pub use prism_translator as translator;
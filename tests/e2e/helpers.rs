@@ -52,11 +52,10 @@ impl TestServer {
             catalog.update_from_credentials(&cred_map);
         }
 
-        let http_client_pool = Arc::new(prism_core::proxy::HttpClientPool::new());
-        let executors = Arc::new(prism_provider::build_registry(
-            config.proxy_url.clone(),
-            http_client_pool.clone(),
+        let http_client_pool = Arc::new(prism_core::proxy::HttpClientPool::with_network(
+            config.network_settings(),
         ));
+        let executors = Arc::new(prism_provider::build_registry(http_client_pool.clone()));
         let translators = Arc::new(prism_translator::build_registry());
         let rate_limiter = Arc::new(CompositeRateLimiter::new(&config.rate_limit));
         let cost_calculator = Arc::new(CostCalculator::new(&config.model_prices));
@@ -89,6 +88,15 @@ impl TestServer {
             oauth_sessions: Arc::new(Default::default()),
             device_sessions: Arc::new(Default::default()),
             provider_probe_cache: Arc::new(Default::default()),
+            readiness: Arc::new(prism_server::readiness::Readiness::new()),
+            inflight: Arc::new(prism_server::inflight::InflightRegistry::new()),
+            load_shedder: Arc::new(prism_server::load_shed::LoadShedder::new()),
+            process_history: Arc::new(prism_server::process_history::ProcessHistory::new()),
+            crash_reporter: Arc::new(prism_server::crash_report::CrashReporter::default()),
+            connections: Arc::new(prism_server::resource_stats::ConnectionCounter::new()),
+            debug_traces: Arc::new(prism_server::debug_trace::DebugTraceStore::new()),
+            gemini_caches: Arc::new(Default::default()),
+            log_filter: Arc::new(prism_lifecycle::logging::LogFilterHandle::detached("info")),
         };
 
         let app_router = prism_server::build_router(state);
@@ -126,9 +134,11 @@ fn make_key_entry(
     base_url: Option<&str>,
 ) -> ProviderKeyEntry {
     ProviderKeyEntry {
+        id: None,
         name: name.to_string(),
         format,
         upstream: None,
+        executor: None,
         api_key: api_key.to_string(),
         base_url: base_url.map(String::from),
         proxy_url: None,
@@ -142,11 +152,19 @@ fn make_key_entry(
         wire_api: Default::default(),
         weight: 1,
         region: None,
+        group: None,
+        quota: None,
         credential_source: None,
+        tags: Vec::new(),
+        token_source: None,
         auth_profiles: vec![],
         vertex: false,
         vertex_project: None,
         vertex_location: None,
+        allowed_server_tools: None,
+        allowed_betas: None,
+        signing: None,
+        json_repair: false,
     }
 }
 
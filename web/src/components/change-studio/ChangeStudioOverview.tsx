@@ -12,7 +12,7 @@ interface ChangeStudioOverviewProps {
   selectedFamily: string | null;
   selectedRegistry: RegistryRow | null;
   authKeys: AuthKeySummary[];
-  selectedAuthKeyId: number | null;
+  selectedAuthKeyId: string | null;
   tenants: TenantSummary[];
   selectedTenantId: string | null;
   tenantMetrics: TenantMetricsResponse | null;
@@ -21,7 +21,7 @@ interface ChangeStudioOverviewProps {
   refreshingAccess: boolean;
   onSelectFamily: (family: string) => void;
   onOpenAccessWorkbench: () => void;
-  onSelectAuthKey: (authKeyId: number) => void;
+  onSelectAuthKey: (authKeyId: string) => void;
   onRefreshAccessPosture: () => void;
   onSelectTenant: (tenantId: string) => void;
 }
@@ -162,14 +162,14 @@ interface AccessControlSheetProps {
   accessForm: AccessPolicyFormState;
   selectedAuthKey: AuthKeySummary | null;
   authKeys: AuthKeySummary[];
-  selectedAuthKeyId: number | null;
+  selectedAuthKeyId: string | null;
   onClose: () => void;
   onStartNewDraft: () => void;
   onRevealSelected: () => void;
   onDeleteSelected: () => void;
   onSaveKey: () => void;
   onAccessFormChange: (patch: Partial<AccessPolicyFormState>) => void;
-  onSelectAuthKey: (authKeyId: number) => void;
+  onSelectAuthKey: (authKeyId: string) => void;
 }
 
 export function AccessControlSheet({
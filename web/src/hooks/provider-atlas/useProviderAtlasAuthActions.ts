@@ -14,6 +14,7 @@ import type { AuthProfileSummary, ProviderDetail } from '../../types/backend';
 
 interface UseProviderAtlasAuthActionsOptions {
   selectedProvider: string | null;
+  selectedProviderId: string | null;
   reload: () => Promise<void>;
   setDetail: Dispatch<SetStateAction<ProviderDetail | null>>;
   loadProfiles: () => Promise<unknown>;
@@ -30,6 +31,7 @@ interface UseProviderAtlasAuthActionsOptions {
 
 export function useProviderAtlasAuthActions({
   selectedProvider,
+  selectedProviderId,
   reload,
   setDetail,
   loadProfiles,
@@ -63,8 +65,8 @@ export function useProviderAtlasAuthActions({
       const response = await authProfilesApi.refresh(provider, profileId);
       setAuthStatus(t('providerAtlas.authStatus.refreshed', { profile: response.profile.qualified_name }));
       await loadProfiles();
-      if (selectedProvider === provider) {
-        const refreshed = await providersApi.get(provider);
+      if (selectedProvider === provider && selectedProviderId) {
+        const refreshed = await providersApi.get(selectedProviderId);
         setDetail(refreshed);
       }
     } catch (refreshError) {
@@ -13,6 +13,7 @@ import { useProviderAtlasDeviceFlow } from './useProviderAtlasDeviceFlow';
 interface UseProviderAtlasAuthWorkbenchOptions {
   providers: ProviderAtlasResponse['providers'];
   selectedProvider: string | null;
+  selectedProviderId: string | null;
   reload: () => Promise<void>;
   setDetail: Dispatch<SetStateAction<ProviderDetail | null>>;
   setRuntimeInfo: Dispatch<SetStateAction<AuthProfilesRuntimeResponse | null>>;
@@ -21,6 +22,7 @@ interface UseProviderAtlasAuthWorkbenchOptions {
 export function useProviderAtlasAuthWorkbench({
   providers,
   selectedProvider,
+  selectedProviderId,
   reload,
   setDetail,
   setRuntimeInfo,
@@ -57,6 +59,7 @@ export function useProviderAtlasAuthWorkbench({
 
   const actions = useProviderAtlasAuthActions({
     selectedProvider,
+    selectedProviderId,
     reload,
     setDetail,
     loadProfiles,
@@ -24,6 +24,7 @@ export const emptyRegistryForm: ProviderRegistryFormState = {
 
 interface UseProviderAtlasRegistryWorkbenchOptions {
   selectedProvider: string | null;
+  selectedProviderId: string | null;
   reload: () => Promise<void>;
   loadRuntimeSurfaces: () => Promise<void>;
   setSelectedProvider: Dispatch<SetStateAction<string | null>>;
@@ -32,6 +33,7 @@ interface UseProviderAtlasRegistryWorkbenchOptions {
 
 export function useProviderAtlasRegistryWorkbench({
   selectedProvider,
+  selectedProviderId,
   reload,
   loadRuntimeSurfaces,
   setSelectedProvider,
@@ -123,7 +125,7 @@ export function useProviderAtlasRegistryWorkbench({
   };
 
   const deleteSelectedProvider = async () => {
-    if (!selectedProvider) {
+    if (!selectedProvider || !selectedProviderId) {
       setRegistryError(t('providerAtlas.registryError.selectProvider'));
       return;
     }
@@ -135,7 +137,7 @@ export function useProviderAtlasRegistryWorkbench({
     setRegistryError(null);
     setRegistryStatus(null);
     try {
-      await providersApi.remove(selectedProvider);
+      await providersApi.remove(selectedProviderId);
       setRegistryStatus(t('providerAtlas.registryStatus.deletedProvider', { provider: selectedProvider }));
       setSelectedProvider(null);
       setDetail(null);
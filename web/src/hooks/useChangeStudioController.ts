@@ -73,7 +73,7 @@ export function useChangeStudioController({
   const [tenantLoading, setTenantLoading] = useState(false);
   const [tenantError, setTenantError] = useState<string | null>(null);
   const [refreshingAccess, setRefreshingAccess] = useState(false);
-  const [selectedAuthKeyId, setSelectedAuthKeyId] = useState<number | null>(null);
+  const [selectedAuthKeyId, setSelectedAuthKeyId] = useState<string | null>(null);
   const [accessOpen, setAccessOpen] = useState(false);
   const [accessEditorMode, setAccessEditorMode] = useState<'create' | 'edit'>('create');
   const [accessForm, setAccessForm] = useState<AccessPolicyFormState>(emptyAccessForm);
@@ -306,13 +306,9 @@ export function useChangeStudioController({
         const response = await authKeysApi.create(body);
         setAccessStatus(t('changeStudio.status.createdAuthKey', { key: body.name || response.key }));
         setRevealedKey(response.key);
+        setSelectedAuthKeyId(response.id);
       }
       await loadAccessData();
-      const latestKeys = await authKeysApi.list();
-      const matchingKey = latestKeys.auth_keys.find((item) => item.name === (body.name ?? null));
-      if (matchingKey) {
-        setSelectedAuthKeyId(matchingKey.id);
-      }
       if (accessForm.tenantId.trim()) {
         await loadTenantMetrics(accessForm.tenantId.trim());
       }
@@ -102,6 +102,8 @@ export function useProviderAtlasController({
     () => data?.providers.find((provider) => provider.provider === activeProvider) ?? null,
     [activeProvider, data],
   );
+  // Provider calls address the persistent id; the name is only for display.
+  const activeProviderId = selectedRow?.id ?? activeProvider;
   const selectedCapabilities = useMemo(
     () => capabilityEntries.find((provider) => provider.name === activeProvider) ?? null,
     [activeProvider, capabilityEntries],
@@ -161,6 +163,7 @@ export function useProviderAtlasController({
 
   const registryWorkbench = useProviderAtlasRegistryWorkbench({
     selectedProvider: activeProvider,
+    selectedProviderId: activeProviderId,
     reload,
     loadRuntimeSurfaces,
     setSelectedProvider,
@@ -170,6 +173,7 @@ export function useProviderAtlasController({
   const authWorkbench = useProviderAtlasAuthWorkbench({
     providers: useMemo(() => data?.providers ?? [], [data]),
     selectedProvider: activeProvider,
+    selectedProviderId: activeProviderId,
     reload,
     setDetail,
     setRuntimeInfo,
@@ -190,7 +194,7 @@ export function useProviderAtlasController({
 
     try {
       const [provider, runtime] = await Promise.all([
-        providersApi.get(activeProvider),
+        providersApi.get(activeProviderId),
         authProfilesApi.runtime(),
       ]);
       setDetail(provider);
@@ -219,7 +223,7 @@ export function useProviderAtlasController({
     setActionError(null);
     setActionStatus(t('providerAtlas.status.runningHealthProbe'));
     try {
-      const result = await providersApi.healthCheck(activeProvider);
+      const result = await providersApi.healthCheck(activeProviderId);
       setHealth(result);
       setActionStatus(t('providerAtlas.status.healthProbeCompleted', { status: presentProbeStatus(result.status, t) }));
       await Promise.all([reload(), loadRuntimeSurfaces()]);
@@ -236,7 +240,7 @@ export function useProviderAtlasController({
     setActionError(null);
     setActionStatus(null);
     try {
-      const result = await providersApi.presentationPreview(activeProvider, {
+      const result = await providersApi.presentationPreview(activeProviderId, {
         model: detail?.models[0]?.id ?? selectedCapabilities?.models[0]?.id ?? '',
         user_agent: 'prism-control-plane',
         sample_body: {
@@ -268,7 +272,7 @@ export function useProviderAtlasController({
     setTestError(null);
     setTestResult(null);
     try {
-      const result = await providersApi.testRequest(activeProvider, {
+      const result = await providersApi.testRequest(activeProviderId, {
         model: testForm.model.trim(),
         input: testForm.input.trim(),
       });
@@ -289,7 +293,7 @@ export function useProviderAtlasController({
     setActionError(null);
     setActionStatus(null);
     try {
-      await providersApi.update(activeProvider, {
+      await providersApi.update(activeProviderId, {
         base_url: registryWorkbench.formState.baseUrl.trim() || null,
         region: registryWorkbench.formState.region.trim() || null,
         weight: Number(registryWorkbench.formState.weight) || 1,
@@ -298,7 +302,7 @@ export function useProviderAtlasController({
       setActionStatus(t('providerAtlas.status.savedProvider', { provider: activeProvider }));
       await reload();
       await loadRuntimeSurfaces();
-      const refreshed = await providersApi.get(activeProvider);
+      const refreshed = await providersApi.get(activeProviderId);
       setDetail(refreshed);
     } catch (saveError) {
       setActionError(getApiErrorMessage(saveError, t('providerAtlas.error.saveProvider')));
@@ -2,7 +2,7 @@ import axios from 'axios';
 import type { LoginResponse, SessionResponse } from '../types/backend';
 
 export const apiClient = axios.create({
-  baseURL: '/api/dashboard',
+  baseURL: '/api/v1/dashboard',
   timeout: 15_000,
   withCredentials: true,
   headers: {
@@ -12,6 +12,65 @@ export const apiClient = axios.create({
 
 let sessionSetter: ((authenticated: boolean) => void) | null = null;
 
+// Reads of config-backed resources (providers, auth keys, routing, config) carry
+// the config version as an `ETag`, kept here per path. A write sends the version
+// its resource, or the closest parent collection, was loaded with as `If-Match`,
+// so an edit made from a stale form fails with 409 instead of overwriting another
+// session's changes. Writes to a resource never loaded read the current version
+// first; a caller that really means to overwrite sets `If-Match: *` itself.
+const resourceVersions = new Map<string, string>();
+let pendingVersion: Promise<string | null> | null = null;
+
+function isAuthUrl(url: string) {
+  return url.includes('/auth/');
+}
+
+function resourcePath(url: string) {
+  return url.split('?')[0];
+}
+
+function loadedVersion(url: string): string | null {
+  let path = resourcePath(url);
+  while (path) {
+    const version = resourceVersions.get(path);
+    if (version) {
+      return version;
+    }
+    path = path.slice(0, Math.max(path.lastIndexOf('/'), 0));
+  }
+  return null;
+}
+
+function fetchConfigVersion(): Promise<string | null> {
+  pendingVersion ??= apiClient
+    .get('/config/current')
+    .then((response) => {
+      const etag = response.headers?.etag;
+      return typeof etag === 'string' && etag ? etag : null;
+    })
+    .finally(() => {
+      pendingVersion = null;
+    });
+  return pendingVersion;
+}
+
+apiClient.interceptors.request.use(async (config) => {
+  const method = (config.method ?? 'get').toLowerCase();
+  if (
+    method !== 'get' &&
+    method !== 'head' &&
+    !config.headers.has('If-Match') &&
+    !isAuthUrl(String(config.url ?? ''))
+  ) {
+    const version =
+      loadedVersion(String(config.url ?? '')) ?? (await fetchConfigVersion());
+    if (version) {
+      config.headers.set('If-Match', version);
+    }
+  }
+  return config;
+});
+
 export function setSessionSetter(setter: (authenticated: boolean) => void) {
   sessionSetter = setter;
 }
@@ -21,7 +80,25 @@ function applySession(authenticated: boolean) {
 }
 
 apiClient.interceptors.response.use(
-  (response) => response,
+  (response) => {
+    const etag = response.headers?.etag;
+    const method = (response.config?.method ?? 'get').toLowerCase();
+    if (typeof etag === 'string' && etag && method === 'get') {
+      resourceVersions.set(resourcePath(String(response.config.url ?? '')), etag);
+    }
+    // A write that reports the new version only moved the config past our own
+    // edit, so resources loaded at the version it was made from stay current.
+    const sent = response.config?.headers?.get?.('If-Match');
+    const next = response.data?.config_version;
+    if (method !== 'get' && typeof sent === 'string' && typeof next === 'string') {
+      for (const [path, version] of resourceVersions) {
+        if (version === sent) {
+          resourceVersions.set(path, `"${next}"`);
+        }
+      }
+    }
+    return response;
+  },
   async (error) => {
     const originalRequest = error.config ?? {};
     const url = String(originalRequest.url ?? '');
@@ -34,7 +111,7 @@ apiClient.interceptors.response.use(
     if (error.response?.status === 401 && !originalRequest._retry && !isAuthRequest) {
       originalRequest._retry = true;
       try {
-        await axios.post<LoginResponse>('/api/dashboard/auth/refresh', null, {
+        await axios.post<LoginResponse>('/api/v1/dashboard/auth/refresh', null, {
           withCredentials: true,
         });
         applySession(true);
@@ -13,11 +13,11 @@ export const authKeysApi = {
   create: async (body: AuthKeyCreateRequest) =>
     (await apiClient.post<AuthKeyCreateResponse>('/auth-keys', body)).data,
 
-  update: async (id: number, body: AuthKeyUpdateRequest) =>
+  update: async (id: string, body: AuthKeyUpdateRequest) =>
     (await apiClient.patch(`/auth-keys/${id}`, body)).data,
 
-  reveal: async (id: number) =>
+  reveal: async (id: string) =>
     (await apiClient.post<AuthKeyRevealResponse>(`/auth-keys/${id}/reveal`)).data,
 
-  remove: async (id: number) => (await apiClient.delete(`/auth-keys/${id}`)).data,
+  remove: async (id: string) => (await apiClient.delete(`/auth-keys/${id}`)).data,
 };
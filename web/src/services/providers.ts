@@ -23,22 +23,23 @@ export const providersApi = {
   create: async (body: ProviderCreateRequest) =>
     (await apiClient.post('/providers', body)).data,
 
-  get: async (name: string) =>
-    (await apiClient.get<ProviderDetail>(`/providers/${encodeURIComponent(name)}`)).data,
+  // Single-provider calls take the provider's persistent `id`, which survives renames.
+  get: async (id: string) =>
+    (await apiClient.get<ProviderDetail>(`/providers/${encodeURIComponent(id)}`)).data,
 
-  update: async (name: string, body: ProviderUpdateRequest) =>
-    (await apiClient.patch(`/providers/${encodeURIComponent(name)}`, body)).data,
+  update: async (id: string, body: ProviderUpdateRequest) =>
+    (await apiClient.patch(`/providers/${encodeURIComponent(id)}`, body)).data,
 
-  remove: async (name: string) =>
-    (await apiClient.delete(`/providers/${encodeURIComponent(name)}`)).data,
+  remove: async (id: string) =>
+    (await apiClient.delete(`/providers/${encodeURIComponent(id)}`)).data,
 
-  healthCheck: async (name: string) =>
-    (await apiClient.post<ProviderHealthResult>(`/providers/${encodeURIComponent(name)}/health`))
+  healthCheck: async (id: string) =>
+    (await apiClient.post<ProviderHealthResult>(`/providers/${encodeURIComponent(id)}/health`))
       .data,
 
-  testRequest: async (name: string, body: { model: string; input: string }) =>
+  testRequest: async (id: string, body: { model: string; input: string }) =>
     (await apiClient.post<ProviderTestResponse>(
-      `/providers/${encodeURIComponent(name)}/test-request`,
+      `/providers/${encodeURIComponent(id)}/test-request`,
       body,
     )).data,
 
@@ -46,11 +47,11 @@ export const providersApi = {
     (await apiClient.post<ProviderFetchModelsResult>('/providers/fetch-models', body)).data,
 
   presentationPreview: async (
-    name: string,
+    id: string,
     body: { model?: string; user_agent?: string; sample_body?: unknown },
   ) =>
     (await apiClient.post<PresentationPreviewResponse>(
-      `/providers/${encodeURIComponent(name)}/presentation-preview`,
+      `/providers/${encodeURIComponent(id)}/presentation-preview`,
       body,
     )).data,
 
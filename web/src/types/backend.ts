@@ -25,10 +25,19 @@ export interface AttemptSummary {
   latency_ms: number;
   error: string | null;
   error_type: string | null;
+  timings?: PhaseTimings;
+}
+
+export interface PhaseTimings {
+  auth_ms?: number;
+  translate_ms?: number;
+  upstream_ttfb_ms?: number;
+  stream_ms?: number;
 }
 
 export interface RequestLog {
   request_id: string;
+  trace_id?: string;
   timestamp: string;
   method: string;
   path: string;
@@ -52,7 +61,39 @@ export interface RequestLog {
   tenant_id: string | null;
   client_ip: string | null;
   client_region?: string | null;
+  experiment?: string | null;
+  experiment_arm?: string | null;
   attempts?: AttemptSummary[];
+  slow?: boolean;
+  timings?: PhaseTimings;
+  stream_truncated?: 'max-bytes' | 'max-duration';
+}
+
+export interface LogErrorGroup {
+  error_type: string | null;
+  provider: string | null;
+  model: string | null;
+  status: number;
+  count: number;
+  last_seen: string;
+  example_request_ids: string[];
+}
+
+export interface LogErrorGroups {
+  total_errors: number;
+  groups: LogErrorGroup[];
+}
+
+export interface InflightRequest {
+  request_id: string;
+  method: string;
+  path: string;
+  started_at: string;
+  elapsed_ms: number;
+  tenant_id: string | null;
+  provider: string | null;
+  model: string | null;
+  streaming: boolean;
 }
 
 export interface ProviderAuthProfile {
@@ -76,6 +117,7 @@ export interface ProviderAuthProfile {
 }
 
 export interface ProviderDetail {
+  id: string | null;
   name: string;
   format: 'openai' | 'claude' | 'gemini';
   upstream: string;
@@ -91,6 +133,16 @@ export interface ProviderDetail {
   weight: number;
   region: string | null;
   auth_profiles: ProviderAuthProfile[];
+  attempts?: ProviderAttemptStats | null;
+}
+
+/** Upstream attempt outcomes; `retries` followed an earlier failed attempt of the same request. */
+export interface ProviderAttemptStats {
+  attempts: number;
+  failures: number;
+  retries: number;
+  failure_rate: number;
+  retry_rate: number;
 }
 
 export interface ProviderProbeCheck {
@@ -151,6 +203,8 @@ export type RejectReason =
   | 'credential_disabled'
   | 'access_denied'
   | 'cooldown_active'
+  | 'quota_exhausted'
+  | 'format_not_allowed'
   | { missing_capability: { capabilities: string[] } };
 
 export interface RouteRejection {
@@ -158,6 +212,110 @@ export interface RouteRejection {
   reason: RejectReason;
 }
 
+export interface ResolvedRouteAttempt {
+  order: number;
+  provider: string;
+  format: string;
+  credential_name: string;
+  model: string;
+}
+
+export interface RouteResolution {
+  model: string;
+  profile: string;
+  matched_rule: string | null;
+  model_chain: string[];
+  attempts: ResolvedRouteAttempt[];
+  excluded: Array<RouteRejection & { detail: string | null }>;
+}
+
+export interface ProcessRun {
+  started_at: string;
+  stopped_at: string | null;
+  pid: number;
+  version: string;
+  shutdown_reason: string | null;
+  panic: string | null;
+}
+
+export interface UptimeHistory {
+  uptime_seconds: number;
+  restarts: number;
+  unclean_shutdowns: number;
+  panicked_runs: number;
+  runs: ProcessRun[];
+}
+
+export interface LogFileEntry {
+  name: string;
+  size_bytes: number;
+  modified: string | null;
+}
+
+export interface LogFilesResponse {
+  dir: string;
+  files: LogFileEntry[];
+  total_bytes: number;
+}
+
+export interface DebugTraceEvent {
+  at: string;
+  kind: 'client_request' | 'route_plan' | 'upstream_request' | 'upstream_result' | 'completed';
+  data: Record<string, unknown>;
+}
+
+export interface DebugTrace {
+  request_id: string;
+  started_at: string;
+  events: DebugTraceEvent[];
+}
+
+export interface LogLevelState {
+  level: string;
+  default: string;
+  attached: boolean;
+}
+
+export interface UsageEvent {
+  request_id: string;
+  timestamp: string;
+  model: string | null;
+  provider: string | null;
+  credential_name: string | null;
+  tenant_id: string | null;
+  api_key_id: string | null;
+  status: number;
+  latency_ms: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_read_tokens: number;
+  cache_creation_tokens: number;
+  cost: number | null;
+}
+
+export interface ModelAliasEntry {
+  alias: string;
+  provider: string;
+  credential: string;
+  model: string;
+  shadowed: boolean;
+}
+
+export interface ModelAliasMap {
+  entries: ModelAliasEntry[];
+  warnings: string[];
+}
+
+export interface TranslatePlaygroundResult {
+  source_format: string;
+  target_format: string;
+  model: string;
+  stream: boolean;
+  request: unknown;
+  response?: unknown;
+  stream_events?: string[];
+}
+
 export interface ModelResolutionStep {
   step: string;
   from?: string;
@@ -195,6 +353,7 @@ export interface RawConfigResponse {
 export interface ConfigValidateResponse {
   valid: boolean;
   errors: string[];
+  warnings?: string[];
 }
 
 export interface ConfigApplyResponse {
@@ -292,7 +451,7 @@ export interface AuthProfileConnectRequest {
 }
 
 export interface AuthKeySummary {
-  id: number;
+  id: string;
   key_masked: string;
   name?: string | null;
   tenant_id?: string | null;
@@ -320,6 +479,7 @@ export interface BudgetConfig {
 }
 
 export interface AuthKeyCreateResponse {
+  id: string;
   key: string;
   message: string;
 }
@@ -469,6 +629,43 @@ export interface SystemHealthResponse {
   tls_enabled: boolean;
   providers: SystemHealthProvider[];
   metrics: Record<string, unknown>;
+  resources: SystemResources;
+  last_config_reload: string | null;
+  crashes: {
+    count: number;
+    last: CrashSummary | null;
+  };
+}
+
+export interface SystemResources {
+  process: {
+    rss_bytes: number | null;
+    open_fds: number | null;
+    threads: number | null;
+  };
+  tokio: {
+    workers: number;
+    alive_tasks: number;
+  };
+  log_buffer: {
+    entries: number;
+    capacity: number;
+    approx_bytes: number;
+  } | null;
+  connections: {
+    open: number;
+    accepted_total: number;
+    inflight_requests: number;
+  };
+}
+
+export interface CrashSummary {
+  timestamp: string;
+  pid: number;
+  version: string;
+  message: string;
+  location: string | null;
+  active_requests: number;
 }
 
 export interface SystemLogEntry {
@@ -557,7 +754,7 @@ export interface ProtocolEndpointEntry {
   description: string;
   scope: 'public' | 'provider_scoped';
   transport: 'http' | 'web_socket';
-  operation: 'generate' | 'count_tokens' | 'list_models';
+  operation: 'generate' | 'count_tokens' | 'list_models' | 'moderate';
   stream_transport: 'none' | 'sse' | 'web_socket_events';
   state: {
     status: 'verified' | 'failed' | 'unknown' | 'unsupported';
@@ -62,6 +62,7 @@ export interface TrafficLabResponse {
 }
 
 export interface ProviderAtlasRow {
+  id: string | null;
   provider: string;
   format: string;
   auth: LocalizedText;
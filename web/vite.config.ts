@@ -9,7 +9,7 @@ export default defineConfig({
   server: {
     port: 3100,
     proxy: {
-      '/api/dashboard': {
+      '/api/v1/dashboard': {
         target: proxyTarget,
         changeOrigin: true,
       },